@@ -0,0 +1,15 @@
+[![Build Status](https://circleci.com/gh/ethercrab-rs/ethercrab/tree/master.svg?style=shield)](https://circleci.com/gh/ethercrab-rs/ethercrab/tree/master)
+[![Crates.io](https://img.shields.io/crates/v/ethercrab-derive.svg)](https://crates.io/crates/ethercrab-derive)
+[![Docs.rs](https://docs.rs/ethercrab-derive/badge.svg)](https://docs.rs/ethercrab-derive)
+
+{{readme}}
+
+## License
+
+Licensed under either of
+
+- Apache License, Version 2.0 ([LICENSE-APACHE](LICENSE-APACHE) or
+  http://www.apache.org/licenses/LICENSE-2.0)
+- MIT license ([LICENSE-MIT](LICENSE-MIT) or http://opensource.org/licenses/MIT)
+
+at your option.
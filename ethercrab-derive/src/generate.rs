@@ -0,0 +1,114 @@
+use crate::parse::{Direction, PdiMeta};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::DeriveInput;
+
+/// Generate a struct holding only the fields mapped in one direction, packed by the
+/// `ethercrab-wire` derives.
+fn generate_packed_struct(
+    parsed: &PdiMeta,
+    direction: Direction,
+    packed_name: &Ident,
+) -> TokenStream {
+    let (width_bits, derive) = match direction {
+        Direction::Input => (
+            parsed.inputs_bits,
+            quote! { ::ethercrab::ethercrab_wire::EtherCrabWireRead },
+        ),
+        Direction::Output => (
+            parsed.outputs_bits,
+            quote! { ::ethercrab::ethercrab_wire::EtherCrabWireWrite },
+        ),
+    };
+
+    let fields = parsed.fields(direction).map(|field| {
+        let name = &field.name;
+        let ty = &field.ty;
+        let wire_attrs = &field.wire_attrs;
+
+        quote! {
+            #(#wire_attrs)*
+            #name: #ty
+        }
+    });
+
+    quote! {
+        #[derive(#derive)]
+        #[wire(bits = #width_bits, crate = "::ethercrab::ethercrab_wire")]
+        struct #packed_name {
+            #(#fields),*
+        }
+    }
+}
+
+pub fn generate_pdi(parsed: &PdiMeta, input: &DeriveInput) -> TokenStream {
+    let name = &input.ident;
+
+    let inputs_name = Ident::new("__EtherCatPdiInputs", name.span());
+    let outputs_name = Ident::new("__EtherCatPdiOutputs", name.span());
+
+    let inputs_len = parsed.inputs_bits.div_ceil(8);
+    let outputs_len = parsed.outputs_bits.div_ceil(8);
+
+    let unpack_inputs = if parsed.fields(Direction::Input).next().is_some() {
+        let packed = generate_packed_struct(parsed, Direction::Input, &inputs_name);
+
+        let names = parsed
+            .fields(Direction::Input)
+            .map(|field| &field.name)
+            .collect::<Vec<_>>();
+
+        quote! {
+            #packed
+
+            let #inputs_name { #(#names),* } =
+                <#inputs_name as ::ethercrab::ethercrab_wire::EtherCrabWireRead>::unpack_from_slice(inputs)?;
+
+            #(self.#names = #names;)*
+        }
+    } else {
+        quote! {
+            let _ = inputs;
+        }
+    };
+
+    let pack_outputs = if parsed.fields(Direction::Output).next().is_some() {
+        let packed = generate_packed_struct(parsed, Direction::Output, &outputs_name);
+
+        let names = parsed.fields(Direction::Output).map(|field| &field.name);
+
+        quote! {
+            #packed
+
+            let packed = #outputs_name {
+                #(#names: ::core::clone::Clone::clone(&self.#names)),*
+            };
+
+            <#outputs_name as ::ethercrab::ethercrab_wire::EtherCrabWireWrite>::pack_to_slice(&packed, outputs)?;
+        }
+    } else {
+        quote! {
+            let _ = outputs;
+        }
+    };
+
+    quote! {
+        impl ::ethercrab::EtherCatPdi for #name {
+            const INPUTS_LEN: usize = #inputs_len;
+
+            const OUTPUTS_LEN: usize = #outputs_len;
+
+            fn unpack_inputs(&mut self, inputs: &[u8]) -> Result<(), ::ethercrab::ethercrab_wire::WireError> {
+                #unpack_inputs
+
+                Ok(())
+            }
+
+            fn pack_outputs(&self, outputs: &mut [u8]) -> Result<(), ::ethercrab::ethercrab_wire::WireError> {
+                #pack_outputs
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Derive macros for [`ethercrab`].
+//!
+//! # Experimental
+//!
+//! This crate is in its early stages and may contain bugs or publish breaking changes at any time.
+//!
+//! # `EtherCatPdi`
+//!
+//! Maps the fields of a struct onto a SubDevice's inputs and outputs in the Process Data Image
+//! (PDI), replacing manual slicing and bit twiddling of `SlaveRef::io_raw`. The struct can be
+//! updated from a SubDevice's inputs with `SlaveRef::read_inputs`, and written into its outputs
+//! with `SlaveRef::write_outputs`.
+//!
+//! Fields are laid out in declaration order with the [`ethercrab-wire`] derives, so every field
+//! type must implement `EtherCrabWireRead` (inputs) or `EtherCrabWireWrite` and `Clone` (outputs).
+//! The generated code uses the `ethercrab_wire` re-exported by [`ethercrab`], so a crate using
+//! this derive only needs to depend on `ethercrab`. The total width of all fields in each direction
+//! is checked against the struct's declared widths at compile time.
+//!
+//! # Supported attributes
+//!
+//! ## Structs
+//!
+//! - `#[pdi(inputs_bits = N)]` OR `#[pdi(inputs_bytes = N)]`
+//!
+//!   The size of the SubDevice's inputs. Required if any field is an input.
+//!
+//! - `#[pdi(outputs_bits = N)]` OR `#[pdi(outputs_bytes = N)]`
+//!
+//!   The size of the SubDevice's outputs. Required if any field is an output.
+//!
+//! ## Struct fields
+//!
+//! - `#[pdi(input)]` OR `#[pdi(output)]`
+//!
+//!   Whether this field is read from the SubDevice's inputs or written to its outputs. Every field
+//!   must have one of these attributes.
+//!
+//! - `#[wire(...)]`
+//!
+//!   The width and position of this field in its half of the PDI. All field attributes supported
+//!   by [`ethercrab-wire-derive`], e.g. `bits`, `bytes`, `pre_skip` and `post_skip`, can be used.
+//!
+//! # Examples
+//!
+//! A SubDevice with one byte of inputs holding a button and a 3 bit mode switch, and one byte of
+//! outputs driving 8 LEDs.
+//!
+//! ```rust
+//! #[derive(Debug, Default, ethercrab::EtherCatPdi)]
+//! #[pdi(inputs_bytes = 1, outputs_bytes = 1)]
+//! struct Io {
+//!     #[pdi(input)]
+//!     #[wire(bits = 1)]
+//!     button: bool,
+//!
+//!     #[pdi(input)]
+//!     #[wire(bits = 3, post_skip = 4)]
+//!     mode: u8,
+//!
+//!     #[pdi(output)]
+//!     #[wire(bytes = 1)]
+//!     leds: u8,
+//! }
+//! ```
+//!
+//! [`ethercrab`]: https://docs.rs/ethercrab
+//! [`ethercrab-wire`]: https://docs.rs/ethercrab-wire
+//! [`ethercrab-wire-derive`]: https://docs.rs/ethercrab-wire-derive
+
+#![deny(missing_docs)]
+#![deny(missing_copy_implementations)]
+#![deny(trivial_casts)]
+#![deny(trivial_numeric_casts)]
+#![deny(unused_import_braces)]
+#![deny(unused_qualifications)]
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(rustdoc::private_intra_doc_links)]
+
+mod generate;
+mod parse;
+
+use generate::generate_pdi;
+use parse::parse_pdi;
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, Data, DeriveInput};
+
+/// A typed view of a SubDevice's inputs and outputs.
+///
+/// Please see the [crate documentation](index.html) for examples and supported attributes.
+#[proc_macro_derive(EtherCatPdi, attributes(pdi, wire))]
+pub fn ethercat_pdi(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let res = match input.clone().data {
+        Data::Struct(s) => parse_pdi(s, &input).map(|parsed| generate_pdi(&parsed, &input)),
+        Data::Enum(_) | Data::Union(_) => Err(syn::Error::new(
+            input.ident.span(),
+            "Only structs are supported",
+        )),
+    };
+
+    let res = match res {
+        Ok(res) => res,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    TokenStream::from(res)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn trybuild_cases() {
+        let t = trybuild::TestCases::new();
+
+        t.compile_fail("ui/*.rs");
+    }
+}
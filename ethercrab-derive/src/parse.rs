@@ -0,0 +1,261 @@
+use syn::{
+    spanned::Spanned, Attribute, DataStruct, DeriveInput, Expr, Fields, FieldsNamed, Ident, LitInt,
+    Token, Type,
+};
+
+pub const MY_ATTRIBUTE: &str = "pdi";
+
+/// Which half of the process data image a field is mapped to.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Input,
+    Output,
+}
+
+#[derive(Clone)]
+pub struct PdiMeta {
+    /// Width of the SubDevice inputs in bits.
+    pub inputs_bits: usize,
+
+    /// Width of the SubDevice outputs in bits.
+    pub outputs_bits: usize,
+
+    pub fields: Vec<FieldMeta>,
+}
+
+impl PdiMeta {
+    pub fn fields(&self, direction: Direction) -> impl Iterator<Item = &FieldMeta> {
+        self.fields
+            .iter()
+            .filter(move |field| field.direction == direction)
+    }
+}
+
+#[derive(Clone)]
+pub struct FieldMeta {
+    pub name: Ident,
+    pub ty: Type,
+    pub direction: Direction,
+
+    /// Width of this field in bits, including any bits skipped before and after it.
+    pub width_bits: usize,
+
+    /// `#[wire()]` attributes to pass through to the packed struct.
+    pub wire_attrs: Vec<Attribute>,
+}
+
+pub fn parse_pdi(s: DataStruct, input: &DeriveInput) -> syn::Result<PdiMeta> {
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "Generic structs are not supported",
+        ));
+    }
+
+    // --- Struct attributes
+
+    let mut inputs_bits = None;
+    let mut outputs_bits = None;
+
+    for attr in my_attributes(&input.attrs) {
+        attr.parse_nested_meta(|meta| {
+            let (width, multiplier) = if meta.path.is_ident("inputs_bits") {
+                (&mut inputs_bits, 1)
+            } else if meta.path.is_ident("inputs_bytes") {
+                (&mut inputs_bits, 8)
+            } else if meta.path.is_ident("outputs_bits") {
+                (&mut outputs_bits, 1)
+            } else if meta.path.is_ident("outputs_bytes") {
+                (&mut outputs_bits, 8)
+            } else {
+                return Err(meta.error("Invalid attribute"));
+            };
+
+            if width.is_some() {
+                return Err(meta.error("Width may only be given once"));
+            }
+
+            let value: LitInt = meta.value()?.parse()?;
+
+            *width = Some(value.base10_parse::<usize>()? * multiplier);
+
+            Ok(())
+        })?;
+    }
+
+    // --- Fields
+
+    let Fields::Named(FieldsNamed { named: fields, .. }) = &s.fields else {
+        return Err(syn::Error::new(
+            ident.span(),
+            "Only structs with named fields can be derived.",
+        ));
+    };
+
+    let mut field_meta = Vec::new();
+
+    for field in fields {
+        // Unwrap: this is a named-field struct so the field will always have a name.
+        let name = field.ident.clone().unwrap();
+
+        let mut direction = None;
+
+        for attr in my_attributes(&field.attrs) {
+            attr.parse_nested_meta(|meta| {
+                let dir = if meta.path.is_ident("input") {
+                    Direction::Input
+                } else if meta.path.is_ident("output") {
+                    Direction::Output
+                } else {
+                    return Err(meta.error("Invalid attribute"));
+                };
+
+                if direction.replace(dir).is_some() {
+                    return Err(meta.error("Field may only be either an input or an output"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        let Some(direction) = direction else {
+            return Err(syn::Error::new(
+                name.span(),
+                "Field must be mapped to the PDI with #[pdi(input)] or #[pdi(output)]",
+            ));
+        };
+
+        let wire_attrs = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("wire"))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let width_bits = wire_width(&wire_attrs, &name)?;
+
+        field_meta.push(FieldMeta {
+            name,
+            ty: field.ty.clone(),
+            direction,
+            width_bits,
+            wire_attrs,
+        });
+    }
+
+    let meta = PdiMeta {
+        inputs_bits: inputs_bits.unwrap_or(0),
+        outputs_bits: outputs_bits.unwrap_or(0),
+        fields: field_meta,
+    };
+
+    for (direction, width, attr) in [
+        (Direction::Input, meta.inputs_bits, "inputs_bytes"),
+        (Direction::Output, meta.outputs_bits, "outputs_bytes"),
+    ] {
+        let has_fields = meta.fields(direction).next().is_some();
+
+        if has_fields && width == 0 {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "Total width is required when fields are mapped, e.g. #[pdi({} = 2)]",
+                    attr
+                ),
+            ));
+        }
+
+        if !has_fields && width > 0 {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("{} is set but no fields are mapped", attr),
+            ));
+        }
+
+        // Checked here instead of by the `ethercrab-wire` derive on the generated struct, so the
+        // user only sees this error and not the unimplemented traits that would follow from it.
+        let total_field_width = meta
+            .fields(direction)
+            .map(|field| field.width_bits)
+            .sum::<usize>();
+
+        if has_fields && total_field_width != width {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "Total field width is {}, expected {} from struct definition",
+                    total_field_width, width
+                ),
+            ));
+        }
+    }
+
+    Ok(meta)
+}
+
+/// The number of bits a field takes up in the PDI, as laid out by the `ethercrab-wire` derives.
+fn wire_width(attrs: &[Attribute], name: &Ident) -> syn::Result<usize> {
+    let mut width = None;
+    let mut skipped = 0;
+    let mut skip = false;
+
+    for attr in attrs {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+
+                return Ok(());
+            }
+
+            let (is_skip, multiplier) = if meta.path.is_ident("bits") {
+                (false, 1)
+            } else if meta.path.is_ident("bytes") {
+                (false, 8)
+            } else if meta.path.is_ident("pre_skip") || meta.path.is_ident("post_skip") {
+                (true, 1)
+            } else if meta.path.is_ident("pre_skip_bytes") || meta.path.is_ident("post_skip_bytes")
+            {
+                (true, 8)
+            } else {
+                // Anything else is validated by the `ethercrab-wire` derive
+                if meta.input.peek(Token![=]) {
+                    meta.value()?.parse::<Expr>()?;
+                }
+
+                return Ok(());
+            };
+
+            let value: LitInt = meta.value()?.parse()?;
+            let bits = value.base10_parse::<usize>()? * multiplier;
+
+            if is_skip {
+                skipped += bits;
+            } else {
+                width = Some(bits);
+            }
+
+            Ok(())
+        })?;
+    }
+
+    if skip {
+        return Ok(0);
+    }
+
+    let Some(width) = width else {
+        return Err(syn::Error::new(
+            name.span(),
+            "Field must have a width attribute, e.g. #[wire(bits = 4)]",
+        ));
+    };
+
+    Ok(width + skipped)
+}
+
+fn my_attributes(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(MY_ATTRIBUTE))
+}
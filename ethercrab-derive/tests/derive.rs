@@ -0,0 +1,101 @@
+use ethercrab::ethercrab_wire::WireError;
+use ethercrab::EtherCatPdi;
+
+#[derive(Debug, Default, PartialEq, EtherCatPdi)]
+#[pdi(inputs_bytes = 3, outputs_bytes = 2)]
+struct Mixed {
+    #[pdi(input)]
+    #[wire(bits = 1)]
+    button: bool,
+
+    #[pdi(output)]
+    #[wire(bits = 4, post_skip = 4)]
+    leds: u8,
+
+    #[pdi(input)]
+    #[wire(bits = 3, post_skip = 4)]
+    mode: u8,
+
+    #[pdi(input)]
+    #[wire(bytes = 2)]
+    position: i16,
+
+    #[pdi(output)]
+    #[wire(bytes = 1)]
+    setpoint: u8,
+}
+
+#[derive(Debug, Default, PartialEq, EtherCatPdi)]
+#[pdi(outputs_bits = 8)]
+struct OutputsOnly {
+    #[pdi(output)]
+    #[wire(bits = 1, post_skip = 7)]
+    enable: bool,
+}
+
+#[test]
+fn round_trip() {
+    assert_eq!(Mixed::INPUTS_LEN, 3);
+    assert_eq!(Mixed::OUTPUTS_LEN, 2);
+
+    let mut io = Mixed {
+        leds: 0b1010,
+        setpoint: 0xaa,
+        ..Mixed::default()
+    };
+
+    io.unpack_inputs(&[0b0000_1011, 0xfe, 0xff]).unwrap();
+
+    assert_eq!(
+        io,
+        Mixed {
+            button: true,
+            leds: 0b1010,
+            mode: 0b101,
+            position: -2,
+            setpoint: 0xaa,
+        }
+    );
+
+    let mut outputs = [0xffu8; 2];
+
+    io.pack_outputs(&mut outputs).unwrap();
+
+    assert_eq!(outputs, [0b0000_1010, 0xaa]);
+}
+
+#[test]
+fn short_buffers() {
+    let mut io = Mixed::default();
+
+    assert_eq!(
+        io.unpack_inputs(&[0u8; 2]),
+        Err(WireError::ReadBufferTooShort {
+            expected: 3,
+            got: 2
+        })
+    );
+
+    assert_eq!(
+        io.pack_outputs(&mut [0u8; 1]),
+        Err(WireError::WriteBufferTooShort {
+            expected: 2,
+            got: 1
+        })
+    );
+}
+
+#[test]
+fn no_inputs() {
+    let mut io = OutputsOnly { enable: true };
+
+    assert_eq!(OutputsOnly::INPUTS_LEN, 0);
+
+    assert_eq!(io.unpack_inputs(&[]), Ok(()));
+
+    let mut outputs = [0u8; 1];
+
+    io.pack_outputs(&mut outputs).unwrap();
+
+    assert_eq!(outputs, [0x01]);
+}
@@ -0,0 +1,8 @@
+#[derive(ethercrab::EtherCatPdi)]
+#[pdi(inputs_bytes = 1)]
+struct NoFieldWidth {
+    #[pdi(input)]
+    position: u8,
+}
+
+fn main() {}
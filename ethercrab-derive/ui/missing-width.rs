@@ -0,0 +1,8 @@
+#[derive(ethercrab::EtherCatPdi)]
+struct NoWidth {
+    #[pdi(output)]
+    #[wire(bytes = 1)]
+    leds: u8,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#[derive(ethercrab::EtherCatPdi)]
+#[pdi(inputs_bytes = 1)]
+struct Unmapped {
+    #[pdi(input)]
+    #[wire(bytes = 1)]
+    position: u8,
+
+    state: u8,
+}
+
+fn main() {}
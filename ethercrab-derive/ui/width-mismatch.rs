@@ -0,0 +1,9 @@
+#[derive(ethercrab::EtherCatPdi)]
+#[pdi(inputs_bytes = 2)]
+struct TooShort {
+    #[pdi(input)]
+    #[wire(bytes = 1)]
+    position: u8,
+}
+
+fn main() {}
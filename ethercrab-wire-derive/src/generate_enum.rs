@@ -9,6 +9,7 @@ pub fn generate_enum_write(
     gen_sized_impl: bool,
 ) -> proc_macro2::TokenStream {
     let name = input.ident.clone();
+    let krate = &parsed.krate;
     let repr_type = parsed.repr_type;
     let size_bytes = match repr_type.to_string().as_str() {
         "u8" | "i8" => 1usize,
@@ -50,7 +51,7 @@ pub fn generate_enum_write(
 
     let sized_impl = if gen_sized_impl {
         quote! {
-            impl ::ethercrab_wire::EtherCrabWireSized for #name {
+            impl #krate::EtherCrabWireSized for #name {
                 const PACKED_LEN: usize = #size_bytes;
 
                 type Buffer = [u8; #size_bytes];
@@ -65,7 +66,7 @@ pub fn generate_enum_write(
     };
 
     quote! {
-        impl ::ethercrab_wire::EtherCrabWireWrite for #name {
+        impl #krate::EtherCrabWireWrite for #name {
             fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
                 let mut buf = &mut buf[0..#size_bytes];
 
@@ -85,12 +86,12 @@ pub fn generate_enum_write(
 
         #sized_impl
 
-        impl ::ethercrab_wire::EtherCrabWireWriteSized for #name {
+        impl #krate::EtherCrabWireWriteSized for #name {
             fn pack(&self) -> Self::Buffer {
                 let mut buf = [0u8; #size_bytes];
 
                 // Delegate to EtherCrabWireWrite impl above
-                <Self as ::ethercrab_wire::EtherCrabWireWrite>::pack_to_slice_unchecked(self, &mut buf);
+                <Self as #krate::EtherCrabWireWrite>::pack_to_slice_unchecked(self, &mut buf);
 
                 buf
             }
@@ -102,6 +103,7 @@ pub fn generate_enum_write(
 
 pub fn generate_enum_read(parsed: EnumMeta, input: &DeriveInput) -> proc_macro2::TokenStream {
     let name = input.ident.clone();
+    let krate = &parsed.krate;
     let repr_type = parsed.repr_type;
     let size_bytes = match repr_type.to_string().as_str() {
         "u8" | "i8" => 1usize,
@@ -140,7 +142,7 @@ pub fn generate_enum_read(parsed: EnumMeta, input: &DeriveInput) -> proc_macro2:
         }
     } else {
         quote! {
-            _other => { Err(::ethercrab_wire::WireError::InvalidValue) }
+            _other => { Err(#krate::WireError::InvalidValue) }
         }
     };
 
@@ -186,12 +188,12 @@ pub fn generate_enum_read(parsed: EnumMeta, input: &DeriveInput) -> proc_macro2:
 
         quote! {
             impl TryFrom<#repr_type> for #name {
-                type Error = ::ethercrab_wire::WireError;
+                type Error = #krate::WireError;
 
                 fn try_from(value: #repr_type) -> Result<Self, Self::Error> {
                     match value {
                         #(#match_arms),*
-                        _other => Err(::ethercrab_wire::WireError::InvalidValue)
+                        _other => Err(#krate::WireError::InvalidValue)
                     }
                 }
             }
@@ -235,11 +237,11 @@ pub fn generate_enum_read(parsed: EnumMeta, input: &DeriveInput) -> proc_macro2:
     };
 
     quote! {
-        impl ::ethercrab_wire::EtherCrabWireRead for #name {
-            fn unpack_from_slice(buf: &[u8]) -> Result<Self, ::ethercrab_wire::WireError> {
+        impl #krate::EtherCrabWireRead for #name {
+            fn unpack_from_slice(buf: &[u8]) -> Result<Self, #krate::WireError> {
                 let raw = buf.get(0..#size_bytes).map(|bytes| {
                     #repr_type::from_le_bytes(bytes.try_into().unwrap())
-                }).ok_or(::ethercrab_wire::WireError::ReadBufferTooShort {
+                }).ok_or(#krate::WireError::ReadBufferTooShort {
                     expected: #size_bytes,
                     got: buf.len(),
                 })?;
@@ -251,7 +253,7 @@ pub fn generate_enum_read(parsed: EnumMeta, input: &DeriveInput) -> proc_macro2:
             }
         }
 
-        impl ::ethercrab_wire::EtherCrabWireSized for #name {
+        impl #krate::EtherCrabWireSized for #name {
             const PACKED_LEN: usize = #size_bytes;
 
             type Buffer = [u8; #size_bytes];
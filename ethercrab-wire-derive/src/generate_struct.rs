@@ -6,6 +6,7 @@ use syn::DeriveInput;
 
 pub fn generate_struct_write(parsed: &StructMeta, input: &DeriveInput) -> proc_macro2::TokenStream {
     let name = input.ident.clone();
+    let krate = &parsed.krate;
     let size_bytes = parsed.width_bits.div_ceil(8);
 
     let fields_pack = parsed.fields.clone().into_iter().map(|field| {
@@ -38,7 +39,7 @@ pub fn generate_struct_write(parsed: &StructMeta, input: &DeriveInput) -> proc_m
 
             quote! {
                 let mut field_buf = [0u8; 1];
-                let res = <#field_ty as ::ethercrab_wire::EtherCrabWireWrite>::pack_to_slice_unchecked(&self.#name, &mut field_buf)[0];
+                let res = <#field_ty as #krate::EtherCrabWireWrite>::pack_to_slice_unchecked(&self.#name, &mut field_buf)[0];
 
                 buf[#byte_start] |= (res << #bit_start) & #mask;
             }
@@ -48,13 +49,13 @@ pub fn generate_struct_write(parsed: &StructMeta, input: &DeriveInput) -> proc_m
             let byte_end = field.bytes.end;
 
             quote! {
-                <#field_ty as ::ethercrab_wire::EtherCrabWireWrite>::pack_to_slice_unchecked(&self.#name, &mut buf[#byte_start..#byte_end]);
+                <#field_ty as #krate::EtherCrabWireWrite>::pack_to_slice_unchecked(&self.#name, &mut buf[#byte_start..#byte_end]);
             }
         }
     });
 
     quote! {
-        impl ::ethercrab_wire::EtherCrabWireWrite for #name {
+        impl #krate::EtherCrabWireWrite for #name {
             fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
                 let buf = match buf.get_mut(0..#size_bytes) {
                     Some(buf) => buf,
@@ -75,11 +76,11 @@ pub fn generate_struct_write(parsed: &StructMeta, input: &DeriveInput) -> proc_m
             }
         }
 
-        impl ::ethercrab_wire::EtherCrabWireWriteSized for #name {
+        impl #krate::EtherCrabWireWriteSized for #name {
             fn pack(&self) -> Self::Buffer {
                 let mut buf = [0u8; #size_bytes];
 
-                <Self as ::ethercrab_wire::EtherCrabWireWrite>::pack_to_slice_unchecked(self, &mut buf);
+                <Self as #krate::EtherCrabWireWrite>::pack_to_slice_unchecked(self, &mut buf);
 
                 buf
             }
@@ -89,6 +90,7 @@ pub fn generate_struct_write(parsed: &StructMeta, input: &DeriveInput) -> proc_m
 
 pub fn generate_struct_read(parsed: &StructMeta, input: &DeriveInput) -> proc_macro2::TokenStream {
     let name = input.ident.clone();
+    let krate = &parsed.krate;
     let size_bytes = parsed.width_bits.div_ceil(8);
 
     let fields_unpack = parsed.fields.clone().into_iter().map(|field| {
@@ -128,7 +130,7 @@ pub fn generate_struct_read(parsed: &StructMeta, input: &DeriveInput) -> proc_ma
                     #name: {
                         let masked = (buf[#byte_start] & #mask) >> #bit_start;
 
-                        <#ty as ::ethercrab_wire::EtherCrabWireRead>::unpack_from_slice(&[masked])?
+                        <#ty as #krate::EtherCrabWireRead>::unpack_from_slice(&[masked])?
                     }
                 }
             }
@@ -139,15 +141,15 @@ pub fn generate_struct_read(parsed: &StructMeta, input: &DeriveInput) -> proc_ma
             let end_byte = field.bytes.end;
 
             quote! {
-                #name: <#ty as ::ethercrab_wire::EtherCrabWireRead>::unpack_from_slice(&buf[#start_byte..#end_byte])?
+                #name: <#ty as #krate::EtherCrabWireRead>::unpack_from_slice(&buf[#start_byte..#end_byte])?
             }
         }
     });
 
     quote! {
-        impl ::ethercrab_wire::EtherCrabWireRead for #name {
-            fn unpack_from_slice(buf: &[u8]) -> Result<Self, ::ethercrab_wire::WireError> {
-                let buf = buf.get(0..#size_bytes).ok_or(::ethercrab_wire::WireError::ReadBufferTooShort {
+        impl #krate::EtherCrabWireRead for #name {
+            fn unpack_from_slice(buf: &[u8]) -> Result<Self, #krate::WireError> {
+                let buf = buf.get(0..#size_bytes).ok_or(#krate::WireError::ReadBufferTooShort {
                     expected: #size_bytes,
                     got: buf.len(),
                 })?;
@@ -162,10 +164,11 @@ pub fn generate_struct_read(parsed: &StructMeta, input: &DeriveInput) -> proc_ma
 
 pub fn generate_sized_impl(parsed: &StructMeta, input: &DeriveInput) -> proc_macro2::TokenStream {
     let name = input.ident.clone();
+    let krate = &parsed.krate;
     let size_bytes = parsed.width_bits.div_ceil(8);
 
     quote! {
-        impl ::ethercrab_wire::EtherCrabWireSized for #name {
+        impl #krate::EtherCrabWireSized for #name {
             const PACKED_LEN: usize = #size_bytes;
 
             type Buffer = [u8; #size_bytes];
@@ -1,8 +1,8 @@
 use proc_macro2::Span;
 use std::collections::HashSet;
 use syn::{
-    punctuated::Punctuated, spanned::Spanned, Expr, ExprArray, ExprLit, Ident, Lit, Meta, Token,
-    Type,
+    punctuated::Punctuated, spanned::Spanned, Expr, ExprArray, ExprLit, Ident, Lit, Meta, Path,
+    Token, Type,
 };
 
 pub const MY_ATTRIBUTE: &str = "wire";
@@ -27,6 +27,38 @@ pub fn bit_width_attr(attrs: &[syn::Attribute]) -> Result<Option<usize>, syn::Er
     Ok(bits.or(bytes))
 }
 
+/// Look for `crate = "..."` attribute on a struct or enum, defaulting to `::ethercrab_wire`.
+pub fn crate_path_attr(attrs: &[syn::Attribute]) -> Result<Path, syn::Error> {
+    for attr in my_attributes(attrs) {
+        let Ok(nested) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+        else {
+            continue;
+        };
+
+        for meta in nested {
+            match meta {
+                Meta::NameValue(nv) if nv.path.is_ident("crate") => {
+                    let Expr::Lit(ExprLit {
+                        lit: Lit::Str(path),
+                        ..
+                    }) = &nv.value
+                    else {
+                        return Err(syn::Error::new(
+                            nv.value.span(),
+                            "Crate path must be a string, e.g. #[wire(crate = \"::ethercrab_wire\")]",
+                        ));
+                    };
+
+                    return path.parse();
+                }
+                _ => (),
+            }
+        }
+    }
+
+    Ok(syn::parse_quote!(::ethercrab_wire))
+}
+
 pub fn usize_attr(attrs: &[syn::Attribute], search: &str) -> Result<Option<usize>, syn::Error> {
     for attr in my_attributes(attrs) {
         let Ok(nested) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
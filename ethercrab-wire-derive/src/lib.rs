@@ -18,6 +18,12 @@
 //!   The size of this struct when packed on the wire. These attributes may not be present at the
 //!   same time.
 //!
+//! - `#[wire(crate = "path")]`
+//!
+//!   The path to the `ethercrab-wire` crate used in generated code. Defaults to
+//!   `::ethercrab_wire`. This is useful when `ethercrab-wire` is re-exported by another crate,
+//!   e.g. `#[wire(crate = "::ethercrab::ethercrab_wire")]`.
+//!
 //! ## Struct fields
 //!
 //! - `#[wire(bits = N)]` OR `#[wire(bytes = N)]`
@@ -39,6 +45,10 @@
 //!
 //! Enums must have a `#[repr()]` attribute, as well as implement the `Copy` trait.
 //!
+//! - `#[wire(crate = "path")]`
+//!
+//!   The path to the `ethercrab-wire` crate used in generated code, as for structs.
+//!
 //! ## Enum discriminants
 //!
 //! Enum discriminants may not contain fields.
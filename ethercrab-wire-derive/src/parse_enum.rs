@@ -1,7 +1,8 @@
 use crate::help::{
-    all_valid_attrs, attr_exists, enum_repr_ty, variant_alternatives, variant_is_default,
+    all_valid_attrs, attr_exists, crate_path_attr, enum_repr_ty, variant_alternatives,
+    variant_is_default,
 };
-use syn::{DataEnum, DeriveInput, Expr, ExprLit, ExprUnary, Ident, Lit, UnOp};
+use syn::{DataEnum, DeriveInput, Expr, ExprLit, ExprUnary, Ident, Lit, Path, UnOp};
 
 #[derive(Clone)]
 pub struct EnumMeta {
@@ -9,6 +10,9 @@ pub struct EnumMeta {
     // pub width: usize,
     pub repr_type: Ident,
 
+    /// Path to the `ethercrab-wire` crate used in generated code.
+    pub krate: Path,
+
     pub variants: Vec<VariantMeta>,
 
     pub catch_all: Option<VariantMeta>,
@@ -30,7 +34,9 @@ pub fn parse_enum(
 ) -> syn::Result<EnumMeta> {
     // let width = bit_width_attr(&attrs)?;
 
-    all_valid_attrs(&attrs, &["bits", "bytes"])?;
+    all_valid_attrs(&attrs, &["bits", "bytes", "crate"])?;
+
+    let krate = crate_path_attr(&attrs)?;
 
     let repr = enum_repr_ty(&attrs, &ident)?;
 
@@ -154,6 +160,7 @@ pub fn parse_enum(
     Ok(EnumMeta {
         // width,
         repr_type: repr,
+        krate,
         variants,
         catch_all,
         default_variant,
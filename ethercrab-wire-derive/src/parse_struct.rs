@@ -1,12 +1,15 @@
-use crate::help::{all_valid_attrs, attr_exists, bit_width_attr, usize_attr};
+use crate::help::{all_valid_attrs, attr_exists, bit_width_attr, crate_path_attr, usize_attr};
 use std::ops::Range;
-use syn::{DataStruct, DeriveInput, Fields, FieldsNamed, Ident, Type, Visibility};
+use syn::{DataStruct, DeriveInput, Fields, FieldsNamed, Ident, Path, Type, Visibility};
 
 #[derive(Clone)]
 pub struct StructMeta {
     /// Width in bits on the wire.
     pub width_bits: usize,
 
+    /// Path to the `ethercrab-wire` crate used in generated code.
+    pub krate: Path,
+
     pub fields: Vec<FieldMeta>,
 }
 
@@ -39,9 +42,10 @@ pub fn parse_struct(
 ) -> syn::Result<StructMeta> {
     // --- Struct attributes
 
-    all_valid_attrs(&attrs, &["bits", "bytes"])?;
+    all_valid_attrs(&attrs, &["bits", "bytes", "crate"])?;
 
     let width = bit_width_attr(&attrs)?;
+    let krate = crate_path_attr(&attrs)?;
 
     let Some(width) = width else {
         return Err(syn::Error::new(
@@ -176,6 +180,7 @@ pub fn parse_struct(
 
     Ok(StructMeta {
         width_bits: width,
+        krate,
         fields: field_meta,
     })
 }
@@ -340,9 +340,12 @@ where
 
 impl<const N: usize> EtherCrabWireRead for heapless::String<N> {
     fn unpack_from_slice(buf: &[u8]) -> Result<Self, WireError> {
+        // Strings are often padded with trailing null bytes to fill a fixed length field.
         core::str::from_utf8(buf)
             .map_err(|_| WireError::InvalidUtf8)
-            .and_then(|s| Self::try_from(s).map_err(|_| WireError::ArrayLength))
+            .and_then(|s| {
+                Self::try_from(s.trim_end_matches('\0')).map_err(|_| WireError::ArrayLength)
+            })
     }
 }
 
@@ -356,6 +359,26 @@ impl<const N: usize> EtherCrabWireSized for heapless::String<N> {
     }
 }
 
+impl<const N: usize> EtherCrabWireWrite for heapless::String<N> {
+    fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
+        self.as_bytes().pack_to_slice_unchecked(buf)
+    }
+
+    fn packed_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl EtherCrabWireWrite for &str {
+    fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
+        self.as_bytes().pack_to_slice_unchecked(buf)
+    }
+
+    fn packed_len(&self) -> usize {
+        self.len()
+    }
+}
+
 // --- std ---
 
 #[cfg(feature = "std")]
@@ -363,7 +386,7 @@ impl EtherCrabWireRead for String {
     fn unpack_from_slice(buf: &[u8]) -> Result<Self, WireError> {
         core::str::from_utf8(buf)
             .map_err(|_| WireError::InvalidUtf8)
-            .map(String::from)
+            .map(|s| String::from(s.trim_end_matches('\0')))
     }
 }
 
@@ -392,6 +415,26 @@ mod tests {
         assert_eq!(bool::unpack_from_slice(&[0x01]), Ok(true));
     }
 
+    #[test]
+    fn string_pack() {
+        let mut buf = [0xffu8; 8];
+
+        assert_eq!("EK1100".pack_to_slice(&mut buf), Ok(&b"EK1100"[..]));
+
+        let s = heapless::String::<8>::try_from("EL2004").unwrap();
+
+        assert_eq!(s.packed_len(), 6);
+        assert_eq!(s.pack_to_slice(&mut buf), Ok(&b"EL2004"[..]));
+    }
+
+    #[test]
+    fn string_unpack_trailing_nulls() {
+        assert_eq!(
+            heapless::String::<8>::unpack_from_slice(b"EL1004\0\0"),
+            Ok(heapless::String::try_from("EL1004").unwrap())
+        );
+    }
+
     #[test]
     fn tuple_decode() {
         let res = <(u32, u8)>::unpack_from_slice(&[0xaa, 0xbb, 0xcc, 0xdd, 0x99]);
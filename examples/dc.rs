@@ -8,7 +8,7 @@ use ethercrab::{
     error::Error,
     slave_group::{CycleInfo, DcConfiguration},
     std::{ethercat_now, tx_rx_task},
-    Client, ClientConfig, DcSync, PduStorage, RegisterAddress, Timeouts,
+    Client, ClientConfig, DcStaticSync, DcSync, PduStorage, RegisterAddress, Timeouts,
 };
 use futures_lite::StreamExt;
 use std::{
@@ -71,12 +71,12 @@ fn main() -> Result<(), Error> {
         pdu_loop,
         Timeouts {
             wait_loop_delay: Duration::from_millis(5),
-            state_transition: Duration::from_secs(10),
+            safe_op_to_op: Duration::from_secs(10),
             pdu: Duration::from_millis(2000),
             ..Timeouts::default()
         },
         ClientConfig {
-            dc_static_sync_iterations: 10_000,
+            dc_static_sync: DcStaticSync::default(),
             ..ClientConfig::default()
         },
     ));
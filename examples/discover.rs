@@ -3,7 +3,7 @@
 use env_logger::Env;
 use ethercrab::{
     std::{ethercat_now, tx_rx_task},
-    Client, ClientConfig, PduStorage, Timeouts,
+    Client, ClientConfig, DcStaticSync, PduStorage, Timeouts,
 };
 use std::sync::Arc;
 
@@ -33,7 +33,7 @@ fn main() {
         pdu_loop,
         Timeouts::default(),
         ClientConfig {
-            dc_static_sync_iterations: 0,
+            dc_static_sync: DcStaticSync::disabled(),
             ..ClientConfig::default()
         },
     ));
@@ -10,7 +10,7 @@ use ethercrab::{
     error::Error,
     internals::{ChunkReader, DeviceEeprom},
     std::{ethercat_now, tx_rx_task},
-    Client, ClientConfig, PduStorage, Timeouts,
+    Client, ClientConfig, DcStaticSync, PduStorage, Timeouts,
 };
 
 /// Maximum number of slaves that can be stored. This must be a power of 2 greater than 1.
@@ -50,7 +50,7 @@ async fn main() -> Result<(), Error> {
         pdu_loop,
         Timeouts::default(),
         ClientConfig {
-            dc_static_sync_iterations: 0,
+            dc_static_sync: DcStaticSync::disabled(),
             ..ClientConfig::default()
         },
     );
@@ -96,13 +96,15 @@ async fn main() -> Result<(), Error> {
         .expect("Could not read EEPROM len");
 
     // Kilobits to bits to bytes, and undoing the offset
-    let len = ((u16::from_le_bytes(len_buf) + 1) * 1024) / 8;
+    let len = ((u32::from(u16::from_le_bytes(len_buf)) + 1) * 1024) / 8;
 
     log::info!("--> Device EEPROM is {} bytes long", len);
 
-    let mut provider = ChunkReader::new(DeviceEeprom::new(&client, base_address + index), 0, len);
+    // EEPROMs larger than 1 Mbit use WORD addresses above 0xffff
+    let mut provider =
+        ChunkReader::new(DeviceEeprom::new(&client, base_address + index), 0, len / 2);
 
-    let mut buf = vec![0u8; usize::from(len)];
+    let mut buf = vec![0u8; len as usize];
 
     provider.read_exact(&mut buf).await.expect("Read");
 
@@ -0,0 +1,106 @@
+//! Write an EEPROM image file, e.g. one saved with the `dump-eeprom` example, to a given sub
+//! device.
+//!
+//! The image is checked before being written, and each WORD is read back after it is written.
+
+use env_logger::Env;
+use ethercrab::{
+    error::Error,
+    sii,
+    std::{ethercat_now, tx_rx_task},
+    Client, ClientConfig, DcStaticSync, PduStorage, Timeouts,
+};
+use std::time::Duration;
+
+/// Maximum number of slaves that can be stored. This must be a power of 2 greater than 1.
+const MAX_SLAVES: usize = 16;
+/// Maximum PDU data payload size - set this to the max PDI size or higher.
+const MAX_PDU_DATA: usize = PduStorage::element_size(1100);
+/// Maximum number of EtherCAT frames that can be in flight at any one time.
+const MAX_FRAMES: usize = 16;
+/// Maximum total PDI length.
+const PDI_LEN: usize = 64;
+
+static PDU_STORAGE: PduStorage<MAX_FRAMES, MAX_PDU_DATA> = PduStorage::new();
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+
+    let interface = std::env::args()
+        .nth(1)
+        .expect("Provide network interface as first argument.");
+
+    let index: usize = std::env::args()
+        .nth(2)
+        .expect("Provide device index (starting from zero) as second argument.")
+        .parse()
+        .expect("Invalid index: must be a number");
+
+    let path = std::env::args()
+        .nth(3)
+        .expect("Provide EEPROM image file path as third argument.");
+
+    let image = std::fs::read(&path).expect("Could not read EEPROM image");
+
+    log::info!(
+        "Starting EEPROM flash tool, interface {}, device index {}, image {} ({} bytes)",
+        interface,
+        index,
+        path,
+        image.len()
+    );
+
+    let (tx, rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+
+    let client = Client::new(
+        pdu_loop,
+        Timeouts {
+            // Some EEPROMs take a while to write each WORD
+            eeprom: Duration::from_millis(100),
+            ..Timeouts::default()
+        },
+        ClientConfig {
+            dc_static_sync: DcStaticSync::disabled(),
+            ..ClientConfig::default()
+        },
+    );
+
+    tokio::spawn(tx_rx_task(&interface, tx, rx).expect("spawn TX/RX task"));
+
+    let group = client
+        .init_single_group::<MAX_SLAVES, PDI_LEN>(ethercat_now)
+        .await
+        .expect("Init");
+
+    log::info!("Discovered {} slaves", group.len());
+
+    let slave = group
+        .slave(&client, index)
+        .expect("Could not find device for given index");
+
+    log::info!(
+        "Flashing EEPROM for device index {}: {:#06x} {} {}...",
+        index,
+        slave.configured_address(),
+        slave.name(),
+        slave.identity()
+    );
+
+    let mut last_percent = 0;
+
+    sii::flash_eeprom(&slave, &image, |written, total| {
+        let percent = written * 100 / total;
+
+        if percent / 10 != last_percent / 10 {
+            log::info!("--> {}% ({} / {} bytes)", percent, written, total);
+        }
+
+        last_percent = percent;
+    })
+    .await?;
+
+    log::info!("Done. Power cycle the device to load the new EEPROM contents.");
+
+    Ok(())
+}
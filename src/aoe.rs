@@ -0,0 +1,199 @@
+//! ADS over EtherCAT (AoE).
+//!
+//! Uses the AMS header defined in Beckhoff's ADS specification.
+
+use crate::mailbox::{MailboxHeaderBase, MailboxType, Priority};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite};
+
+/// The state flags of an ADS request.
+pub const STATE_FLAGS_REQUEST: u16 = 0x0004;
+
+/// Set in the state flags of an ADS response.
+pub const STATE_FLAGS_RESPONSE: u16 = 0x0001;
+
+/// An AMS network ID, e.g. `5.12.34.56.1.1`, identifying a device on an ADS network.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AmsNetId(pub [u8; 6]);
+
+impl core::fmt::Display for AmsNetId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+
+        write!(f, "{}.{}.{}.{}.{}.{}", a, b, c, d, e, g)
+    }
+}
+
+impl EtherCrabWireSized for AmsNetId {
+    const PACKED_LEN: usize = 6;
+
+    type Buffer = [u8; Self::PACKED_LEN];
+
+    fn buffer() -> Self::Buffer {
+        [0u8; Self::PACKED_LEN]
+    }
+}
+
+impl EtherCrabWireRead for AmsNetId {
+    fn unpack_from_slice(buf: &[u8]) -> Result<Self, ethercrab_wire::WireError> {
+        <[u8; 6]>::unpack_from_slice(buf).map(Self)
+    }
+}
+
+impl EtherCrabWireWrite for AmsNetId {
+    fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
+        self.0.pack_to_slice_unchecked(buf)
+    }
+
+    fn packed_len(&self) -> usize {
+        Self::PACKED_LEN
+    }
+}
+
+/// The address of an ADS device: an AMS network ID and port.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AmsAddress {
+    /// AMS network ID.
+    pub net_id: AmsNetId,
+    /// AMS port, e.g. `851` for the first TwinCAT 3 PLC runtime.
+    pub port: u16,
+}
+
+impl AmsAddress {
+    /// Create a new AMS address.
+    pub const fn new(net_id: [u8; 6], port: u16) -> Self {
+        Self {
+            net_id: AmsNetId(net_id),
+            port,
+        }
+    }
+}
+
+/// ADS command ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 2)]
+#[repr(u16)]
+pub enum AdsCommand {
+    ReadDeviceInfo = 0x0001,
+    Read = 0x0002,
+    Write = 0x0003,
+    ReadState = 0x0004,
+    WriteControl = 0x0005,
+    AddNotification = 0x0006,
+    DeleteNotification = 0x0007,
+    Notification = 0x0008,
+    ReadWrite = 0x0009,
+    #[wire(catch_all)]
+    Other(u16),
+}
+
+/// Mailbox and AMS headers common to all AoE requests and responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 38)]
+pub struct AoeHeader {
+    #[wire(bytes = 6)]
+    pub header: MailboxHeaderBase,
+    #[wire(bytes = 6)]
+    pub target_net_id: AmsNetId,
+    #[wire(bytes = 2)]
+    pub target_port: u16,
+    #[wire(bytes = 6)]
+    pub source_net_id: AmsNetId,
+    #[wire(bytes = 2)]
+    pub source_port: u16,
+    #[wire(bytes = 2)]
+    pub command: AdsCommand,
+    #[wire(bytes = 2)]
+    pub state_flags: u16,
+    /// Length of the ADS data following this header.
+    #[wire(bytes = 4)]
+    pub length: u32,
+    /// AMS error code. `0` if there was no error.
+    #[wire(bytes = 4)]
+    pub error_code: u32,
+    /// Chosen by the requester and copied into the response, so responses can be matched to
+    /// requests.
+    #[wire(bytes = 4)]
+    pub invoke_id: u32,
+}
+
+impl AoeHeader {
+    /// Create a new ADS request header, followed by `data_len` bytes of command data.
+    pub fn request(
+        counter: u8,
+        target: AmsAddress,
+        source: AmsAddress,
+        command: AdsCommand,
+        invoke_id: u32,
+        data_len: u16,
+    ) -> Self {
+        Self {
+            header: MailboxHeaderBase {
+                // 32 byte AMS header plus data
+                length: 0x20 + data_len,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Aoe,
+                counter,
+            },
+            target_net_id: target.net_id,
+            target_port: target.port,
+            source_net_id: source.net_id,
+            source_port: source.port,
+            command,
+            state_flags: STATE_FLAGS_REQUEST,
+            length: u32::from(data_len),
+            error_code: 0,
+            invoke_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireWriteSized;
+
+    #[test]
+    fn net_id_display() {
+        assert_eq!(
+            AmsNetId([5, 12, 34, 56, 1, 1]).to_string(),
+            "5.12.34.56.1.1"
+        );
+    }
+
+    #[test]
+    fn encode_read_request() {
+        let header = AoeHeader::request(
+            1,
+            AmsAddress::new([5, 1, 2, 3, 1, 1], 851),
+            AmsAddress::new([10, 0, 0, 1, 1, 1], 0x8000),
+            AdsCommand::Read,
+            0x1234,
+            12,
+        );
+
+        assert_eq!(
+            header.pack(),
+            [
+                // Mailbox header
+                0x2c, 0x00, 0x00, 0x00, 0x00, 0x11, //
+                // Target
+                0x05, 0x01, 0x02, 0x03, 0x01, 0x01, 0x53, 0x03, //
+                // Source
+                0x0a, 0x00, 0x00, 0x01, 0x01, 0x01, 0x00, 0x80, //
+                // Command, state flags
+                0x02, 0x00, 0x04, 0x00, //
+                // Length
+                0x0c, 0x00, 0x00, 0x00, //
+                // Error code
+                0x00, 0x00, 0x00, 0x00, //
+                // Invoke ID
+                0x34, 0x12, 0x00, 0x00,
+            ]
+        );
+    }
+}
@@ -6,6 +6,7 @@
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum PrimitiveDataType {
+    /// Unknown or unsupported data type.
     Unknown = 0x00,
 
     /// Boolean, bit, on or off.
@@ -0,0 +1,450 @@
+//! Verification of the discovered SubDevices against an expected bus configuration.
+
+use crate::SlaveIdentity;
+
+/// The SubDevices expected on the network, checked by [`Client::init`](crate::Client::init) when
+/// set in [`ClientConfig::bus_configuration`](crate::ClientConfig::bus_configuration).
+///
+/// SubDevices are listed in network order. Each one is expected at its position in the list, or
+/// anywhere on the network with the given station alias if one is set. If the discovered SubDevices
+/// don't match, `init` returns [`Error::BusConfiguration`](crate::error::Error::BusConfiguration)
+/// before any SubDevice is configured.
+///
+/// # Examples
+///
+/// ```rust
+/// use ethercrab::{BusConfiguration, ClientConfig, ExpectedSubDevice};
+///
+/// static BUS: &[ExpectedSubDevice] = &[
+///     // EK1100 coupler
+///     ExpectedSubDevice::new(0x0000_0002, 0x044c_2c52),
+///     // EL2004 digital output, revision 0x0011_0000
+///     ExpectedSubDevice::new(0x0000_0002, 0x07d4_3052).with_revision(0x0011_0000),
+///     // A drive that is identified by the station alias in its EEPROM
+///     ExpectedSubDevice::new(0x0000_0539, 0x0220_0001).with_alias(10),
+/// ];
+///
+/// let config = ClientConfig {
+///     bus_configuration: Some(BusConfiguration::new(BUS)),
+///     ..ClientConfig::default()
+/// };
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BusConfiguration {
+    subdevices: &'static [ExpectedSubDevice],
+}
+
+impl BusConfiguration {
+    /// Expect the given SubDevices, in network order.
+    pub const fn new(subdevices: &'static [ExpectedSubDevice]) -> Self {
+        Self { subdevices }
+    }
+
+    /// The expected SubDevices, in network order.
+    pub fn subdevices(&self) -> &'static [ExpectedSubDevice] {
+        self.subdevices
+    }
+
+    /// Whether any expected SubDevice is identified by its station alias.
+    pub(crate) fn uses_aliases(&self) -> bool {
+        self.subdevices.iter().any(|s| s.alias.is_some())
+    }
+
+    /// Compare the discovered SubDevices against this configuration.
+    ///
+    /// `discovered` is the identity and station alias of each SubDevice on the network, in
+    /// network order. The returned iterator yields every mismatch found, ordered by position, so an
+    /// empty iterator means the network matches.
+    pub fn mismatches<'a, I>(&'a self, discovered: I) -> impl Iterator<Item = BusMismatch> + 'a
+    where
+        I: IntoIterator<Item = (SlaveIdentity, u16)>,
+        I::IntoIter: Clone + 'a,
+    {
+        let discovered = discovered.into_iter();
+        let extra = discovered.clone();
+
+        let expected =
+            self.subdevices
+                .iter()
+                .enumerate()
+                .filter_map(move |(position, expected)| {
+                    self.check(position, expected, discovered.clone())
+                });
+
+        let extra =
+            extra
+                .enumerate()
+                .skip(self.subdevices.len())
+                .map(|(position, (identity, _alias))| BusMismatch::Extra {
+                    position: position as u16,
+                    vendor_id: identity.vendor_id,
+                    product_id: identity.product_id,
+                });
+
+        expected.chain(extra)
+    }
+
+    /// Check the SubDevice expected at `position` was discovered there.
+    fn check(
+        &self,
+        position: usize,
+        expected: &ExpectedSubDevice,
+        discovered: impl Iterator<Item = (SlaveIdentity, u16)> + Clone,
+    ) -> Option<BusMismatch> {
+        let at_position = discovered
+            .clone()
+            .nth(position)
+            .map(|(identity, _)| identity);
+
+        let wrong_order = |found_at: usize| {
+            Some(BusMismatch::WrongOrder {
+                position: position as u16,
+                found_at: found_at as u16,
+            })
+        };
+
+        if let Some(alias) = expected.alias {
+            return match (
+                discovered.clone().position(|(_, found)| found == alias),
+                at_position,
+            ) {
+                (Some(found_at), Some(identity)) if found_at == position => {
+                    expected.check_identity(position, &identity)
+                }
+                (Some(found_at), _) => wrong_order(found_at),
+                (None, _) => Some(BusMismatch::Missing {
+                    position: position as u16,
+                }),
+            };
+        }
+
+        match at_position {
+            Some(identity) if expected.is_product(&identity) => {
+                expected.check_identity(position, &identity)
+            }
+            Some(identity) => {
+                // Look for the expected SubDevice in a position where it doesn't belong, so
+                // SubDevices of the same product elsewhere on the network aren't reported.
+                let found_at = discovered.enumerate().position(|(other, (found, _))| {
+                    expected.is_product(&found)
+                        && !self
+                            .subdevices
+                            .get(other)
+                            .is_some_and(|there| there.alias.is_none() && there.is_product(&found))
+                });
+
+                match found_at {
+                    Some(found_at) => wrong_order(found_at),
+                    None => Some(BusMismatch::WrongDevice {
+                        position: position as u16,
+                        vendor_id: identity.vendor_id,
+                        product_id: identity.product_id,
+                    }),
+                }
+            }
+            None => Some(BusMismatch::Missing {
+                position: position as u16,
+            }),
+        }
+    }
+}
+
+/// A SubDevice expected on the network, as part of a [`BusConfiguration`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExpectedSubDevice {
+    /// Vendor ID.
+    pub vendor_id: u32,
+
+    /// Product ID.
+    pub product_id: u32,
+
+    /// The exact product revision, or `None` to accept any revision.
+    pub revision: Option<u32>,
+
+    /// The station alias stored in the SubDevice's EEPROM, or `None` to identify the SubDevice by
+    /// its position.
+    pub alias: Option<u16>,
+}
+
+impl ExpectedSubDevice {
+    /// Expect a SubDevice with the given vendor and product ID and any revision.
+    pub const fn new(vendor_id: u32, product_id: u32) -> Self {
+        Self {
+            vendor_id,
+            product_id,
+            revision: None,
+            alias: None,
+        }
+    }
+
+    /// Expect the given product revision.
+    pub const fn with_revision(self, revision: u32) -> Self {
+        Self {
+            revision: Some(revision),
+            ..self
+        }
+    }
+
+    /// Identify the SubDevice by its station alias instead of by its position.
+    pub const fn with_alias(self, alias: u16) -> Self {
+        Self {
+            alias: Some(alias),
+            ..self
+        }
+    }
+
+    fn is_product(&self, identity: &SlaveIdentity) -> bool {
+        identity.vendor_id == self.vendor_id && identity.product_id == self.product_id
+    }
+
+    /// Check the SubDevice found in this SubDevice's position has the expected product and
+    /// revision.
+    fn check_identity(&self, position: usize, identity: &SlaveIdentity) -> Option<BusMismatch> {
+        let position = position as u16;
+
+        if !self.is_product(identity) {
+            return Some(BusMismatch::WrongDevice {
+                position,
+                vendor_id: identity.vendor_id,
+                product_id: identity.product_id,
+            });
+        }
+
+        match self.revision {
+            Some(expected) if expected != identity.revision => Some(BusMismatch::WrongRevision {
+                position,
+                expected,
+                found: identity.revision,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A difference between a [`BusConfiguration`] and the SubDevices discovered on the network.
+///
+/// Positions count from zero at the first SubDevice on the network.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum BusMismatch {
+    /// The expected SubDevice isn't on the network. There is no SubDevice at its position, or none
+    /// with its station alias.
+    Missing {
+        /// The position in the [`BusConfiguration`] of the missing SubDevice.
+        position: u16,
+    },
+
+    /// A SubDevice was found on the network after all expected SubDevices.
+    Extra {
+        /// The position of the SubDevice on the network.
+        position: u16,
+
+        /// The vendor ID of the SubDevice.
+        vendor_id: u32,
+
+        /// The product ID of the SubDevice.
+        product_id: u32,
+    },
+
+    /// The expected SubDevice was found, but at a different position.
+    ///
+    /// SubDevices that match the SubDevice expected at their own position aren't considered, so
+    /// two swapped SubDevices are each reported as in the wrong order.
+    WrongOrder {
+        /// The position in the [`BusConfiguration`] of the SubDevice.
+        position: u16,
+
+        /// The position the SubDevice was found at on the network.
+        found_at: u16,
+    },
+
+    /// A different SubDevice was found at the expected position, and the expected SubDevice wasn't
+    /// found anywhere else.
+    WrongDevice {
+        /// The position of the SubDevice.
+        position: u16,
+
+        /// The vendor ID of the SubDevice found.
+        vendor_id: u32,
+
+        /// The product ID of the SubDevice found.
+        product_id: u32,
+    },
+
+    /// The expected SubDevice was found, but with a different revision.
+    WrongRevision {
+        /// The position of the SubDevice.
+        position: u16,
+
+        /// The expected revision.
+        expected: u32,
+
+        /// The revision found.
+        found: u32,
+    },
+}
+
+impl core::fmt::Display for BusMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Missing { position } => write!(f, "SubDevice {} is missing", position),
+            Self::Extra {
+                position,
+                vendor_id,
+                product_id,
+            } => write!(
+                f,
+                "unexpected SubDevice {} (vendor {:#010x}, product {:#010x})",
+                position, vendor_id, product_id
+            ),
+            Self::WrongOrder { position, found_at } => write!(
+                f,
+                "SubDevice {} was found at position {}",
+                position, found_at
+            ),
+            Self::WrongDevice {
+                position,
+                vendor_id,
+                product_id,
+            } => write!(
+                f,
+                "SubDevice {} is vendor {:#010x}, product {:#010x}",
+                position, vendor_id, product_id
+            ),
+            Self::WrongRevision {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "SubDevice {} revision is {:#010x}, expected {:#010x}",
+                position, found, expected
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUPLER: ExpectedSubDevice = ExpectedSubDevice::new(2, 0x044c_2c52);
+    const INPUTS: ExpectedSubDevice = ExpectedSubDevice::new(2, 0x03ec_3052);
+    const OUTPUTS: ExpectedSubDevice = ExpectedSubDevice::new(2, 0x07d4_3052);
+    const DRIVE: ExpectedSubDevice = ExpectedSubDevice::new(0x539, 0x0220_0001);
+
+    fn found(expected: ExpectedSubDevice, revision: u32, alias: u16) -> (SlaveIdentity, u16) {
+        (
+            SlaveIdentity {
+                vendor_id: expected.vendor_id,
+                product_id: expected.product_id,
+                revision,
+                serial: 0,
+            },
+            alias,
+        )
+    }
+
+    fn mismatches(
+        expected: &'static [ExpectedSubDevice],
+        discovered: &[(SlaveIdentity, u16)],
+    ) -> Vec<BusMismatch> {
+        BusConfiguration::new(expected)
+            .mismatches(discovered.iter().copied())
+            .collect()
+    }
+
+    #[test]
+    fn matching_bus() {
+        static EXPECTED: &[ExpectedSubDevice] =
+            &[COUPLER, OUTPUTS.with_revision(0x10), DRIVE.with_alias(7)];
+
+        let discovered = [
+            found(COUPLER, 1, 0),
+            found(OUTPUTS, 0x10, 0),
+            found(DRIVE, 1, 7),
+        ];
+
+        assert_eq!(mismatches(EXPECTED, &discovered), []);
+    }
+
+    #[test]
+    fn mismatched_bus() {
+        static EXPECTED: &[ExpectedSubDevice] = &[
+            COUPLER,
+            OUTPUTS.with_revision(0x10),
+            DRIVE,
+            DRIVE.with_alias(7),
+            OUTPUTS,
+        ];
+
+        let discovered = [
+            found(COUPLER, 1, 0),
+            found(OUTPUTS, 0x11, 0),
+            found(INPUTS, 1, 0),
+            found(DRIVE, 1, 0),
+        ];
+
+        assert_eq!(
+            mismatches(EXPECTED, &discovered),
+            [
+                BusMismatch::WrongRevision {
+                    position: 1,
+                    expected: 0x10,
+                    found: 0x11
+                },
+                BusMismatch::WrongOrder {
+                    position: 2,
+                    found_at: 3
+                },
+                BusMismatch::Missing { position: 3 },
+                BusMismatch::Missing { position: 4 },
+            ]
+        );
+
+        static SHORT: &[ExpectedSubDevice] = &[COUPLER, OUTPUTS, OUTPUTS];
+
+        assert_eq!(
+            mismatches(SHORT, &discovered),
+            [
+                // The OUTPUTS at position 1 is where it's expected
+                BusMismatch::WrongDevice {
+                    position: 2,
+                    vendor_id: INPUTS.vendor_id,
+                    product_id: INPUTS.product_id
+                },
+                BusMismatch::Extra {
+                    position: 3,
+                    vendor_id: DRIVE.vendor_id,
+                    product_id: DRIVE.product_id
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn swapped() {
+        static EXPECTED: &[ExpectedSubDevice] = &[COUPLER, OUTPUTS, DRIVE.with_alias(7)];
+
+        let discovered = [
+            found(COUPLER, 1, 0),
+            found(DRIVE, 1, 7),
+            found(OUTPUTS, 1, 0),
+        ];
+
+        assert_eq!(
+            mismatches(EXPECTED, &discovered),
+            [
+                BusMismatch::WrongOrder {
+                    position: 1,
+                    found_at: 2
+                },
+                BusMismatch::WrongOrder {
+                    position: 2,
+                    found_at: 1
+                },
+            ]
+        );
+    }
+}
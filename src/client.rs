@@ -3,20 +3,22 @@ use crate::{
     al_status_code::AlStatusCode,
     command::Command,
     dc,
-    error::{Error, Item, PduError},
+    error::{DistributedClockError, Error, Item, PduError, TransitionFailures},
     fmt,
     pdi::PdiOffset,
     pdu_loop::{CreatedFrame, PduLoop, ReceivedFrame, ReceivedPdu},
     register::RegisterAddress,
-    slave::Slave,
+    slave::{CoeCache, EoeReassembly, LargeMailbox, Slave, SlaveRef},
     slave_group::{self, SlaveGroupHandle},
     slave_state::SlaveState,
     timer_factory::IntoTimeout,
-    ClientConfig, SlaveGroup, Timeouts, BASE_SLAVE_ADDR,
+    BusConfiguration, ClientConfig, SlaveGroup, Timeouts, BASE_SLAVE_ADDR,
 };
+use atomic_refcell::AtomicRefCell;
 use core::{
     ops::Range,
-    sync::atomic::{AtomicU16, Ordering},
+    sync::atomic::{AtomicU16, AtomicU32, Ordering},
+    time::Duration,
 };
 use ethercrab_wire::EtherCrabWireWrite;
 use heapless::FnvIndexMap;
@@ -30,8 +32,8 @@ pub struct Client<'sto> {
     pub(crate) pdu_loop: PduLoop<'sto>,
     /// The total number of discovered slaves.
     ///
-    /// Using an `AtomicU16` here only to satisfy `Sync` requirements, but it's only ever written to
-    /// once so its safety is largely unused.
+    /// Written during init, and again when SubDevices are added with
+    /// [`SlaveGroup::hot_connect`](crate::SlaveGroup::hot_connect).
     num_slaves: AtomicU16,
     /// DC reference clock.
     ///
@@ -39,6 +41,25 @@ pub struct Client<'sto> {
     dc_reference_configured_address: AtomicU16,
     pub(crate) timeouts: Timeouts,
     pub(crate) config: ClientConfig,
+    /// The Ethernet frame currently being received over EoE.
+    ///
+    /// One frame is buffered for all SubDevices instead of one per [`Slave`], as few SubDevices
+    /// support EoE.
+    pub(crate) eoe_reassembly: AtomicRefCell<EoeReassembly>,
+    /// Holds the contents of a SubDevice mailbox that is too long to be read or written with a
+    /// single PDU.
+    pub(crate) large_mailbox: AtomicRefCell<LargeMailbox>,
+    /// Values read from SubDevice object dictionaries over CoE.
+    ///
+    /// One cache is shared by all SubDevices instead of one per [`Slave`], to keep [`Slave`]
+    /// small.
+    pub(crate) coe_cache: AtomicRefCell<CoeCache>,
+    /// The DC system time read from the reference clock by the most recent `FRMW`.
+    dc_time: AtomicRefCell<Option<u64>>,
+    /// The DC system time deviation read after static drift compensation.
+    ///
+    /// `u32::MAX` if it was not read. Valid deviations never have the sign bit set.
+    dc_static_sync_deviation: AtomicU32,
 }
 
 unsafe impl<'sto> Sync for Client<'sto> {}
@@ -52,6 +73,11 @@ impl<'sto> Client<'sto> {
             dc_reference_configured_address: AtomicU16::new(0),
             timeouts,
             config,
+            eoe_reassembly: AtomicRefCell::new(EoeReassembly::new()),
+            large_mailbox: AtomicRefCell::new(LargeMailbox::new()),
+            coe_cache: AtomicRefCell::new(CoeCache::new()),
+            dc_time: AtomicRefCell::new(None),
+            dc_static_sync_deviation: AtomicU32::new(u32::MAX),
         }
     }
 
@@ -265,6 +291,10 @@ impl<'sto> Client<'sto> {
                 .map_err(|_| Error::Capacity(Item::Slave))?;
         }
 
+        if let Some(bus) = self.config.bus_configuration {
+            self.verify_bus(&bus, &slaves).await?;
+        }
+
         fmt::debug!("Configuring topology/distributed clocks");
 
         // Configure distributed clock offsets/propagation delays, perform static drift
@@ -276,7 +306,12 @@ impl<'sto> Client<'sto> {
             self.dc_reference_configured_address
                 .store(dc_master.configured_address(), Ordering::Relaxed);
 
-            dc::run_dc_static_sync(self, dc_master, self.config.dc_static_sync_iterations).await?;
+            if let Some(deviation) =
+                dc::run_dc_static_sync(self, dc_master, self.config.dc_static_sync).await?
+            {
+                self.dc_static_sync_deviation
+                    .store(deviation, Ordering::Relaxed);
+            }
         }
 
         // This block is to reduce the lifetime of the groups map references
@@ -297,19 +332,54 @@ impl<'sto> Client<'sto> {
                     .map_err(|_| Error::Capacity(Item::Group))?;
             }
 
+            // Groups placed at a fixed logical address. Other groups are packed around them.
+            let mut reserved = heapless::Vec::<_, MAX_SLAVES>::new();
+
+            for group in group_map.values() {
+                let Some(range) = group.fixed_logical_range() else {
+                    continue;
+                };
+
+                if reserved
+                    .iter()
+                    .any(|other: &Range<u32>| range.start < other.end && other.start < range.end)
+                {
+                    fmt::error!(
+                        "Group PDI at {:#010x} ({} bytes) overlaps another group",
+                        range.start,
+                        range.len()
+                    );
+
+                    return Err(Error::PdiOverlap {
+                        start_address: range.start,
+                        length: range.len(),
+                    });
+                }
+
+                // Unwrap: there is at most one range per group
+                fmt::unwrap!(reserved.push(range).map_err(|_| ()));
+            }
+
             let mut offset = PdiOffset::default();
 
             for (id, group) in group_map.iter_mut() {
-                offset = group.into_pre_op(offset, self).await?;
+                let start = group.pdi_start(offset, &reserved);
+
+                let end = group.into_pre_op(start, self).await?;
 
-                fmt::debug!("After group ID {} offset: {:?}", id, offset);
+                if group.fixed_logical_range().is_none() {
+                    offset = end;
+                }
+
+                fmt::debug!("After group ID {} offset: {:?}", id, end);
             }
 
             fmt::debug!("Total PDI {} bytes", offset.start_address);
         }
 
         // Check that all slaves reached PRE-OP
-        self.wait_for_state(SlaveState::PreOp).await?;
+        self.wait_for_transition(SlaveState::Init, SlaveState::PreOp)
+            .await?;
 
         Ok(groups)
     }
@@ -403,8 +473,140 @@ impl<'sto> Client<'sto> {
             .await
     }
 
+    /// Tear down the current bus configuration and initialise the network again, e.g. after a
+    /// cable or SubDevice has been replaced.
+    ///
+    /// This forgets everything the client learnt about the network during the previous
+    /// [`init`](Client::init), such as the number of SubDevices, the DC reference clock and cached
+    /// CoE values, then calls `init` with the given arguments, which resets every SubDevice back
+    /// to INIT. The same `PduStorage` and TX/RX task keep being used, so a long running service
+    /// doesn't need to restart to rebuild the bus.
+    ///
+    /// All groups returned by the previous `init` must be dropped before calling this method, as
+    /// the SubDevices they contain are reset and may be given different configured addresses.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Borrow`] if an EoE or large mailbox transfer with a SubDevice from the
+    /// previous configuration is still in progress, or if the DC time is being read at the same
+    /// moment, as well as any error returned by `init`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{error::Error, std::ethercat_now, Client, SlaveGroup};
+    /// # async fn run(client: &Client<'_>) -> Result<(), Error> {
+    /// let mut group = client.init_single_group::<16, 64>(ethercat_now).await?.into_op(client).await?;
+    ///
+    /// loop {
+    ///     if let Err(e) = group.tx_rx(client).await {
+    ///         log::error!("Lost connection to the network: {}, reinitialising", e);
+    ///
+    ///         drop(group);
+    ///
+    ///         group = client
+    ///             .reinit::<16, SlaveGroup<16, 64>>(ethercat_now, |group, _slave| Ok(group))
+    ///             .await?
+    ///             .into_op(client)
+    ///             .await?;
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn reinit<const MAX_SLAVES: usize, G>(
+        &self,
+        now: impl Fn() -> u64 + Copy,
+        group_filter: impl for<'g> FnMut(&'g G, &Slave) -> Result<&'g dyn SlaveGroupHandle, Error>,
+    ) -> Result<G, Error>
+    where
+        G: Default,
+    {
+        self.reset_state()?;
+
+        self.init::<MAX_SLAVES, G>(now, group_filter).await
+    }
+
+    /// Forget the state of the network from a previous [`init`](Client::init).
+    fn reset_state(&self) -> Result<(), Error> {
+        *self
+            .eoe_reassembly
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow)? = EoeReassembly::new();
+
+        self.large_mailbox
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow)?
+            .clear();
+
+        *self.coe_cache.try_borrow_mut().map_err(|_| Error::Borrow)? = CoeCache::new();
+
+        *self.dc_time.try_borrow_mut().map_err(|_| Error::Borrow)? = None;
+
+        self.num_slaves.store(0, Ordering::Relaxed);
+        self.dc_reference_configured_address
+            .store(0, Ordering::Relaxed);
+        self.dc_static_sync_deviation
+            .store(u32::MAX, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Check the discovered SubDevices against the expected bus configuration.
+    async fn verify_bus<const MAX_SLAVES: usize>(
+        &self,
+        bus: &BusConfiguration,
+        slaves: &heapless::Deque<Slave, MAX_SLAVES>,
+    ) -> Result<(), Error> {
+        // Deque iterators aren't `Send`, so can't be held across an await
+        let (front, back) = slaves.as_slices();
+
+        // Station aliases are only read if they're needed, to not add traffic to every init
+        let mut aliases = heapless::Vec::<u16, MAX_SLAVES>::new();
+
+        if bus.uses_aliases() {
+            for slave in front.iter().chain(back) {
+                let alias = match slave.alias() {
+                    Some(alias) => alias,
+                    None => {
+                        SlaveRef::new(self, slave.configured_address(), ())
+                            .station_alias()
+                            .await?
+                    }
+                };
+
+                fmt::unwrap!(aliases.push(alias).map_err(|_| ()));
+            }
+        }
+
+        let discovered = front
+            .iter()
+            .chain(back)
+            .enumerate()
+            .map(|(position, slave)| {
+                (
+                    slave.identity(),
+                    aliases.get(position).copied().unwrap_or(0),
+                )
+            });
+
+        let mut first = None;
+        let mut count = 0u16;
+
+        for mismatch in bus.mismatches(discovered) {
+            fmt::error!("Bus configuration mismatch: {}", mismatch);
+
+            first.get_or_insert(mismatch);
+            count = count.saturating_add(1);
+        }
+
+        match first {
+            Some(mismatch) => Err(Error::BusConfiguration { mismatch, count }),
+            None => Ok(()),
+        }
+    }
+
     /// Count the number of slaves on the network.
-    async fn count_slaves(&self) -> Result<u16, Error> {
+    pub(crate) async fn count_slaves(&self) -> Result<u16, Error> {
         Command::brd(RegisterAddress::Type.into())
             .receive_wkc::<u8>(self)
             .await
@@ -418,8 +620,17 @@ impl<'sto> Client<'sto> {
         usize::from(self.num_slaves.load(Ordering::Relaxed))
     }
 
-    /// Get the configured address of the designated DC reference subdevice.
-    pub(crate) fn dc_ref_address(&self) -> Option<u16> {
+    /// Record SubDevices added to the network after init.
+    pub(crate) fn set_num_slaves(&self, num_slaves: u16) {
+        self.num_slaves.store(num_slaves, Ordering::Relaxed);
+    }
+
+    /// Get the configured address of the SubDevice used as the DC reference clock.
+    ///
+    /// The reference is chosen during [`init`](Client::init) using
+    /// [`ClientConfig::dc_reference`]. This method returns `None` if no SubDevice supports DC, or
+    /// if `init` hasn't been called yet.
+    pub fn dc_ref_address(&self) -> Option<u16> {
         let addr = self.dc_reference_configured_address.load(Ordering::Relaxed);
 
         if addr > 0 {
@@ -429,11 +640,163 @@ impl<'sto> Client<'sto> {
         }
     }
 
+    /// Distribute the DC reference clock's system time to all other SubDevices with a single
+    /// `FRMW`, returning the reference clock's system time in nanoseconds.
+    ///
+    /// SubDevices continuously compensate for drift between their local clock and the reference
+    /// clock as long as the reference time is distributed periodically. This happens every cycle
+    /// when using [`SlaveGroup::tx_rx_sync_system_time`] or
+    /// [`SlaveGroup::tx_rx_dc`](crate::SlaveGroup::tx_rx_dc). This method can be called instead,
+    /// e.g. from a separate task, when process data is exchanged without them. Use
+    /// [`SlaveGroup::max_system_time_difference`] to check how well the SubDevices are
+    /// synchronised.
+    ///
+    /// # Errors
+    ///
+    /// Returns an
+    /// [`Error::DistributedClock(DistributedClockError::NoReference)`](Error::DistributedClock)
+    /// if no DC reference SubDevice is present on the network.
+    pub async fn sync_system_time(&self) -> Result<u64, Error> {
+        let reference = self
+            .dc_ref_address()
+            .ok_or(Error::DistributedClock(DistributedClockError::NoReference))?;
+
+        let time = Command::frmw(reference, RegisterAddress::DcSystemTime.into())
+            .ignore_wkc()
+            .receive::<u64>(self)
+            .await?;
+
+        self.set_dc_time(time);
+
+        Ok(time)
+    }
+
+    /// Steer the DC reference clock towards the host's clock, so that DC system time follows an
+    /// external time source such as `CLOCK_TAI` synchronised with PTP.
+    ///
+    /// `host_time` is the current host time in nanoseconds since the EtherCAT epoch of 2000-01-01,
+    /// e.g. from [`std::ethercat_now_tai`](crate::std::ethercat_now_tai). It is written to the
+    /// reference clock's system time register, which makes the reference clock's control loop
+    /// speed up or slow down its clock to reduce the difference.
+    ///
+    /// The same time source should be passed to [`init`](Client::init) so that the DC system time
+    /// starts close to the host time. Call this method once per cycle, before the system time is
+    /// distributed to the other SubDevices with e.g.
+    /// [`SlaveGroup::tx_rx_dc`](crate::SlaveGroup::tx_rx_dc).
+    ///
+    /// # Errors
+    ///
+    /// Returns an
+    /// [`Error::DistributedClock(DistributedClockError::NoReference)`](Error::DistributedClock)
+    /// if no DC reference SubDevice is present on the network.
+    pub async fn steer_reference_clock(&self, host_time: u64) -> Result<(), Error> {
+        let reference = self
+            .dc_ref_address()
+            .ok_or(Error::DistributedClock(DistributedClockError::NoReference))?;
+
+        // The control loop only uses the lower 32 bits of the written time.
+        Command::fpwr(reference, RegisterAddress::DcSystemTime.into())
+            .send(self, host_time as u32)
+            .await
+    }
+
+    /// Get the DC system time in nanoseconds, as read from the DC reference clock by the most
+    /// recent cyclic exchange.
+    ///
+    /// The time is updated by [`SlaveGroup::tx_rx_sync_system_time`],
+    /// [`SlaveGroup::tx_rx_dc`](crate::SlaveGroup::tx_rx_dc) and
+    /// [`sync_system_time`](Client::sync_system_time), and can be used to timestamp process data in
+    /// EtherCAT bus time. This method returns `None` if none of these have been called yet, or if
+    /// the time is being updated by another task at the same moment.
+    pub fn dc_time(&self) -> Option<u64> {
+        self.dc_time.try_borrow().ok().and_then(|time| *time)
+    }
+
+    /// Store the DC system time read by a cyclic exchange.
+    ///
+    /// If the time is being read by another task at the same moment, this update is skipped
+    /// instead of waiting.
+    pub(crate) fn set_dc_time(&self, time: u64) {
+        if let Ok(mut dc_time) = self.dc_time.try_borrow_mut() {
+            *dc_time = Some(time);
+        }
+    }
+
+    /// The DC system time deviation of all SubDevices after static drift compensation in
+    /// [`init`](Client::init), in nanoseconds.
+    ///
+    /// This is the value returned by [`system_time_deviation`](Client::system_time_deviation) once
+    /// static sync completes. `None` is returned if there are no DC SubDevices, or if
+    /// [`DcStaticSync::read_deviation`](crate::DcStaticSync::read_deviation) is disabled.
+    pub fn dc_static_sync_deviation(&self) -> Option<u32> {
+        let deviation = self.dc_static_sync_deviation.load(Ordering::Relaxed);
+
+        (deviation != u32::MAX).then_some(deviation)
+    }
+
+    /// Read an upper bound of the DC system time difference of all SubDevices on the network with
+    /// a single `BRD` of register `0x092C`, in nanoseconds.
+    ///
+    /// SubDevices combine their values with a bitwise OR, so the returned value is at least the
+    /// largest difference, and less than twice the largest difference. Use
+    /// [`SlaveGroup::max_system_time_difference`] to read the exact difference of each SubDevice.
+    pub async fn system_time_deviation(&self) -> Result<u32, Error> {
+        Command::brd(RegisterAddress::DcSystemTimeDifference.into())
+            .ignore_wkc()
+            .receive::<u32>(self)
+            .await
+            // Discard sign bit
+            .map(|difference| difference & 0x7fff_ffff)
+    }
+
+    /// Check that all SubDevices are synchronised to the DC reference clock to within
+    /// `max_deviation`.
+    ///
+    /// This can be called periodically during OP to detect SubDevices that drift out of a sync
+    /// window. Only a single PDU is sent, so see [`system_time_deviation`](Client::system_time_deviation)
+    /// for the accuracy of the check.
+    ///
+    /// # Errors
+    ///
+    /// Returns an
+    /// [`Error::DistributedClock(DistributedClockError::OutOfSyncWindow)`](Error::DistributedClock)
+    /// error if any SubDevice may be outside the sync window.
+    pub async fn check_sync_window(&self, max_deviation: Duration) -> Result<(), Error> {
+        let deviation = self.system_time_deviation().await?;
+
+        if u128::from(deviation) > max_deviation.as_nanos() {
+            fmt::warn!(
+                "DC system time deviation of up to {} ns is outside sync window of {} ns",
+                deviation,
+                max_deviation.as_nanos()
+            );
+
+            return Err(Error::DistributedClock(
+                DistributedClockError::OutOfSyncWindow { deviation },
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Wait for all slaves on the network to reach a given state.
+    ///
+    /// This waits for up to [`Timeouts::state_transition`](crate::Timeouts::state_transition), as
+    /// the state the SubDevices are coming from isn't known.
     pub async fn wait_for_state(&self, desired_state: SlaveState) -> Result<(), Error> {
+        self.wait_for_transition(SlaveState::None, desired_state)
+            .await
+    }
+
+    /// Wait for all slaves on the network to go from `current_state` to `desired_state`.
+    async fn wait_for_transition(
+        &self,
+        current_state: SlaveState,
+        desired_state: SlaveState,
+    ) -> Result<(), Error> {
         let num_slaves = self.num_slaves.load(Ordering::Relaxed);
 
-        async {
+        let result = async {
             loop {
                 let status = Command::brd(RegisterAddress::AlStatus.into())
                     .with_wkc(num_slaves)
@@ -470,26 +833,82 @@ impl<'sto> Client<'sto> {
                 self.timeouts.loop_tick().await;
             }
         }
-        .timeout(self.timeouts.state_transition)
-        .await
+        .timeout(self.timeouts.transition(current_state, desired_state))
+        .await;
+
+        match result {
+            Err(Error::Timeout) => Err(self
+                .transition_timeout(
+                    BASE_SLAVE_ADDR..(BASE_SLAVE_ADDR + num_slaves),
+                    desired_state,
+                )
+                .await),
+            result => result,
+        }
     }
 
-    pub(crate) fn max_frame_data(&self) -> usize {
-        self.pdu_loop.max_frame_data()
+    /// Read the AL status code of each of the given SubDevices that isn't in `desired_state`, to
+    /// report why a state transition timed out.
+    pub(crate) async fn transition_timeout(
+        &self,
+        configured_addresses: impl Iterator<Item = u16>,
+        desired_state: SlaveState,
+    ) -> Error {
+        let mut subdevices = TransitionFailures::new();
+
+        for configured_address in configured_addresses {
+            let status = Command::fprd(configured_address, RegisterAddress::AlStatus.into())
+                .receive::<AlControl>(self)
+                .await;
+
+            if matches!(status, Ok(status) if status.state == desired_state && !status.error) {
+                continue;
+            }
+
+            let code = Command::fprd(configured_address, RegisterAddress::AlStatusCode.into())
+                .receive::<AlStatusCode>(self)
+                .await
+                .unwrap_or(AlStatusCode::UnspecifiedError);
+
+            fmt::error!(
+                "--> Slave {:#06x} did not reach {}, status code {}",
+                configured_address,
+                desired_state,
+                code
+            );
+
+            subdevices.push(configured_address, code);
+        }
+
+        Error::StateTransitionTimeout {
+            desired: desired_state,
+            subdevices,
+        }
     }
 
-    /// Send one or more PDUs in a frame.
-    pub(crate) async fn multi_pdu<T, H>(
+    /// Send one or more cyclic process data PDUs in a frame.
+    ///
+    /// Cyclic frames are always sent straight away. If
+    /// [`ClientConfig::defer_acyclic`](crate::ClientConfig::defer_acyclic) is enabled, deferred
+    /// acyclic PDUs are sent in the same frame if there is space left, and no other deferred frames
+    /// are sent until the response is received.
+    pub(crate) async fn cyclic_multi_pdu<T, H>(
         &self,
         send: impl Fn(&mut CreatedFrame) -> Result<H, PduError>,
         take: impl Fn(ReceivedFrame<'_>, H) -> Result<T, Error>,
     ) -> Result<T, Error> {
+        let _cyclic = self.pdu_loop.cyclic();
+
         for _ in 0..self.config.retry_behaviour.loop_counts() {
             let mut frame = self.pdu_loop.alloc_frame()?;
             let frame_idx = frame.frame_index();
 
             let handles = send(&mut frame)?;
 
+            // If the cyclic frame times out, the merged frames are deferred again when this is
+            // dropped.
+            let merged = self.pdu_loop.merge_deferred(&mut frame);
+
             let frame = frame.mark_sendable().timeout(self.timeouts.pdu);
 
             self.pdu_loop.wake_sender();
@@ -504,6 +923,8 @@ impl<'sto> Client<'sto> {
                 Err(e) => return Err(e),
             };
 
+            merged.complete(&received);
+
             return take(received, handles);
         }
 
@@ -523,7 +944,12 @@ impl<'sto> Client<'sto> {
 
             let handle = frame.push_pdu(command, data, len_override, false)?;
 
-            let frame = frame.mark_sendable().timeout(self.timeouts.pdu);
+            let frame = if self.config.defer_acyclic {
+                frame.mark_deferred()
+            } else {
+                frame.mark_sendable()
+            }
+            .timeout(self.timeouts.pdu);
 
             self.pdu_loop.wake_sender();
 
@@ -603,4 +1029,75 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn reset_state() {
+        static PDU_STORAGE: crate::PduStorage<1, 64> = crate::PduStorage::new();
+
+        let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().unwrap();
+
+        let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+
+        client.set_num_slaves(3);
+        client
+            .dc_reference_configured_address
+            .store(0x1001, Ordering::Relaxed);
+        client.dc_static_sync_deviation.store(50, Ordering::Relaxed);
+        client.set_dc_time(1234);
+        client
+            .large_mailbox
+            .borrow_mut()
+            .extend_from_slice(&[1, 2, 3])
+            .unwrap();
+        client
+            .coe_cache
+            .borrow_mut()
+            .insert_device_type(0x1001, 0x1389);
+
+        client.reset_state().unwrap();
+
+        assert_eq!(client.num_slaves(), 0);
+        assert_eq!(client.dc_ref_address(), None);
+        assert_eq!(client.dc_static_sync_deviation(), None);
+        assert_eq!(client.dc_time(), None);
+        assert!(client.large_mailbox.borrow().is_empty());
+        assert_eq!(client.coe_cache.borrow().device_type(0x1001), None);
+
+        {
+            let _eoe = client.eoe_reassembly.borrow();
+
+            assert_eq!(client.reset_state(), Err(Error::Borrow));
+        }
+
+        // A large mailbox transfer is in progress
+        let _mailbox = client.large_mailbox.borrow();
+
+        assert_eq!(client.reset_state(), Err(Error::Borrow));
+    }
+
+    #[test]
+    fn dc_time_does_not_block() {
+        static PDU_STORAGE: crate::PduStorage<1, 64> = crate::PduStorage::new();
+
+        let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().unwrap();
+
+        let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+
+        client.set_dc_time(1234);
+        assert_eq!(client.dc_time(), Some(1234));
+
+        {
+            let _writer = client.dc_time.borrow_mut();
+
+            assert_eq!(client.dc_time(), None);
+        }
+
+        {
+            let _reader = client.dc_time.borrow();
+
+            client.set_dc_time(5678);
+        }
+
+        assert_eq!(client.dc_time(), Some(1234));
+    }
 }
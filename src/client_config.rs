@@ -1,29 +1,396 @@
 //! Configuration passed to [`Client`](crate::Client).
 
+use crate::{AmsAddress, BusConfiguration};
+use core::time::Duration;
+
 /// Configuration passed to [`Client`](crate::Client).
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct ClientConfig {
-    /// The number of `FRMW` packets to send during the static phase of Distributed Clocks (DC)
-    /// synchronisation.
-    ///
-    /// Defaults to 10000.
+    /// Static drift compensation performed during Distributed Clocks (DC) initialisation.
     ///
-    /// If this is set to zero, no static sync will be performed.
-    pub dc_static_sync_iterations: u32,
+    /// Defaults to 10000 back to back `FRMW` packets.
+    pub dc_static_sync: DcStaticSync,
 
     /// EtherCAT packet (PDU) network retry behaviour.
     pub retry_behaviour: RetryBehaviour,
+
+    /// Read back every value written with an SDO download and check it matches what was written.
+    ///
+    /// This doubles the mailbox traffic for every SDO write, including those made by EtherCrab
+    /// during initialisation. Objects the SubDevice changes when written to will fail
+    /// verification. Write-only objects, where the SubDevice aborts the read back, are not
+    /// verified and a warning is logged instead.
+    ///
+    /// Defaults to `false`.
+    pub verify_sdo_writes: bool,
+
+    /// The number of times an FoE packet is resent while a SubDevice reports that it is busy.
+    ///
+    /// Retries are only counted while the SubDevice reports no progress, so long running
+    /// operations that report progress will not fail. The delay between retries is set by
+    /// [`Timeouts::foe_busy`](crate::Timeouts::foe_busy).
+    ///
+    /// Defaults to 50.
+    pub foe_busy_retries: usize,
+
+    /// The number of times a SubDevice is asked to repeat a mailbox response that did not arrive
+    /// within the response timeout of its mailbox protocol, e.g.
+    /// [`Timeouts::coe_response`](crate::Timeouts::coe_response) for SDOs.
+    ///
+    /// Defaults to 1.
+    pub mailbox_repeat_retries: usize,
+
+    /// Defer acyclic traffic, e.g. SDO requests made while in OP, so it doesn't delay cyclic
+    /// process data exchanges.
+    ///
+    /// When enabled, acyclic PDUs are queued and only sent while no cyclic frame is in flight. If
+    /// a cyclic frame has space left after its process data, queued PDUs are sent in the same
+    /// frame instead.
+    ///
+    /// Defaults to `false`.
+    pub defer_acyclic: bool,
+
+    /// The AMS address AoE (ADS over EtherCAT) requests are sent from.
+    ///
+    /// ADS devices may only respond to requests from addresses in their route table.
+    ///
+    /// Defaults to `0.0.0.0.1.1:32768`.
+    pub ams_address: AmsAddress,
+
+    /// The SubDevice used as the Distributed Clocks (DC) reference clock.
+    ///
+    /// Defaults to [`DcReference::First`].
+    pub dc_reference: DcReference,
+
+    /// Read the DC reference clock's system time in the same frame as every
+    /// [`SlaveGroup::tx_rx`](crate::SlaveGroup::tx_rx) process data exchange.
+    ///
+    /// This adds an `FPRD` PDU to each cyclic frame, so every process data snapshot carries the bus
+    /// time it was sampled at. The time can be read with
+    /// [`SlaveGroup::pdi_time`](crate::SlaveGroup::pdi_time). It has no effect if there are no DC
+    /// SubDevices on the network.
+    ///
+    /// Defaults to `false`.
+    pub pdi_timestamp: bool,
+
+    /// The commands used to exchange process data with
+    /// [`SlaveGroup::tx_rx`](crate::SlaveGroup::tx_rx) and its variants.
+    ///
+    /// Defaults to [`PdiExchange::Lrw`].
+    pub pdi_exchange: PdiExchange,
+
+    /// Check the working counter of every PDU sent by
+    /// [`SlaveGroup::tx_rx`](crate::SlaveGroup::tx_rx) and its variants against the number of
+    /// SubDevices mapped to the part of the PDI it carries.
+    ///
+    /// Large PDIs are split into multiple PDUs, and inputs and outputs may be sent in separate
+    /// PDUs. With this enabled, a mismatch returns
+    /// [`Error::PdiWorkingCounter`](crate::error::Error::PdiWorkingCounter) with the logical
+    /// address of the failing PDU, instead of relying on the caller to check the summed working
+    /// counter.
+    ///
+    /// Defaults to `false`.
+    pub verify_pdi_wkc: bool,
+
+    /// When a group's outputs are put into a fail-safe state instead of sending stale output data.
+    ///
+    /// Defaults to never.
+    pub fail_safe: FailSafe,
+
+    /// The SubDevices expected on the network.
+    ///
+    /// If set, [`Client::init`](crate::Client::init) checks the discovered SubDevices against
+    /// this configuration before configuring any of them, and returns
+    /// [`Error::BusConfiguration`](crate::error::Error::BusConfiguration) if they don't match.
+    ///
+    /// Defaults to `None`.
+    pub bus_configuration: Option<BusConfiguration>,
+
+    /// Which IDs are read from each SubDevice when it is discovered, so it can be assigned to a
+    /// group by ID instead of by position.
+    ///
+    /// Defaults to reading no IDs.
+    pub device_identification: DeviceIdentification,
+
+    /// Cache the locations of categories in each SubDevice's EEPROM while it is configured.
+    ///
+    /// Finding an EEPROM category reads the header of every category before it, and several
+    /// categories are read while configuring each SubDevice. Caching their locations saves many
+    /// reads over the slow SII interface, which shortens initialisation of large networks.
+    ///
+    /// Locations are only cached for the duration of each configuration step, e.g. mailbox or PDO
+    /// configuration, so no memory is used by each [`Slave`](crate::Slave) once it is configured.
+    ///
+    /// Defaults to `false`.
+    pub cache_eeprom: bool,
 }
 
 impl Default for ClientConfig {
     fn default() -> Self {
         Self {
-            dc_static_sync_iterations: 10_000,
+            dc_static_sync: DcStaticSync::default(),
             retry_behaviour: RetryBehaviour::default(),
+            verify_sdo_writes: false,
+            foe_busy_retries: 50,
+            mailbox_repeat_retries: 1,
+            defer_acyclic: false,
+            ams_address: AmsAddress::new([0, 0, 0, 0, 1, 1], 0x8000),
+            dc_reference: DcReference::default(),
+            pdi_timestamp: false,
+            pdi_exchange: PdiExchange::default(),
+            verify_pdi_wkc: false,
+            fail_safe: FailSafe::default(),
+            bus_configuration: None,
+            device_identification: DeviceIdentification::default(),
+            cache_eeprom: false,
         }
     }
 }
 
+/// IDs read from each SubDevice when it is discovered by [`Client::init`](crate::Client::init) or
+/// [`SlaveGroup::hot_connect`](crate::SlaveGroup::hot_connect).
+///
+/// Modular machines often have several SubDevices of the same type in different slots, so the
+/// SubDevice's identity doesn't say which group it belongs to. Each slot can instead be given a
+/// station alias in EEPROM, or an explicit device ID, e.g. with rotary switches on a coupler, as
+/// described in ETG1020. The IDs read are available with [`Slave::alias`](crate::Slave::alias)
+/// and [`Slave::device_id`](crate::Slave::device_id) when assigning groups.
+///
+/// Each ID read adds PDUs to every SubDevice's discovery, so none are read by default.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethercrab::{
+///     error::Error, std::ethercat_now, Client, ClientConfig, DeviceIdentification, PduStorage,
+///     SlaveGroup, Timeouts,
+/// };
+///
+/// static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+///
+/// #[derive(Default)]
+/// struct Groups {
+///     left_arm: SlaveGroup<8, 64>,
+///     right_arm: SlaveGroup<8, 64>,
+/// }
+///
+/// let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+///
+/// let client = Client::new(
+///     pdu_loop,
+///     Timeouts::default(),
+///     ClientConfig {
+///         device_identification: DeviceIdentification {
+///             explicit_device_id: true,
+///             ..DeviceIdentification::default()
+///         },
+///         ..ClientConfig::default()
+///     },
+/// );
+///
+/// # async {
+/// // Each arm is the same module, with its coupler's ID switches set to 1 or 2. SubDevices after
+/// // a coupler don't have an ID, so belong to the same arm as the coupler before them.
+/// let mut arm = 0;
+///
+/// let groups = client
+///     .init::<16, _>(ethercat_now, |groups: &Groups, slave| {
+///         if let Some(id) = slave.device_id() {
+///             arm = id;
+///         }
+///
+///         match arm {
+///             1 => Ok(&groups.left_arm),
+///             2 => Ok(&groups.right_arm),
+///             _ => Err(Error::UnknownSlave),
+///         }
+///     })
+///     .await
+///     .expect("Init");
+/// # };
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct DeviceIdentification {
+    /// Read the station alias stored in each SubDevice's EEPROM.
+    ///
+    /// Defaults to `false`.
+    pub station_alias: bool,
+
+    /// Request each SubDevice's explicit device ID through the AL control register.
+    ///
+    /// SubDevices that don't support ID requests are given no ID. Each SubDevice's EEPROM is read
+    /// to check whether it supports them, and SubDevices that claim support but don't answer
+    /// add up to [`Timeouts::id_request`](crate::Timeouts::id_request) each to
+    /// [`Client::init`](crate::Client::init). Defaults to `false`.
+    pub explicit_device_id: bool,
+}
+
+/// Selects which SubDevice is used as the Distributed Clocks (DC) reference clock.
+///
+/// The reference clock's system time is distributed to all other SubDevices with `FRMW`, which only
+/// reaches SubDevices after the reference clock in the network. Choosing a SubDevice other than the
+/// first one with DC support means any DC SubDevices before it will not be synchronised.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum DcReference {
+    /// Use the first SubDevice in the network that supports DC (default).
+    #[default]
+    First,
+
+    /// Use the SubDevice with the given configured address.
+    ///
+    /// Configured addresses are assigned in network order, starting at `0x1000` for the first
+    /// SubDevice. [`Client::init`](crate::Client::init) returns an
+    /// [`Error::DistributedClock(DistributedClockError::InvalidReference)`](crate::error::Error::DistributedClock)
+    /// error if the SubDevice doesn't exist or doesn't support DC.
+    Address(u16),
+}
+
+/// Static drift compensation settings.
+///
+/// During [`Client::init`](crate::Client::init), the DC reference clock's system time is
+/// repeatedly distributed to all other SubDevices so their clocks can settle before process data
+/// is exchanged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DcStaticSync {
+    /// The number of packets to send.
+    ///
+    /// If this is set to zero, no static sync will be performed.
+    ///
+    /// Defaults to 10000.
+    pub iterations: u32,
+
+    /// The time to wait between each packet.
+    ///
+    /// Defaults to zero, sending packets back to back.
+    pub interval: Duration,
+
+    /// The command used to distribute the reference clock's system time.
+    ///
+    /// Defaults to [`DcStaticSyncCommand::Frmw`].
+    pub command: DcStaticSyncCommand,
+
+    /// Read the residual DC system time deviation once static sync completes.
+    ///
+    /// This sends one extra `BRD` packet. The deviation is logged and can be retrieved with
+    /// [`Client::dc_static_sync_deviation`](crate::Client::dc_static_sync_deviation).
+    ///
+    /// Defaults to `true`.
+    pub read_deviation: bool,
+}
+
+impl Default for DcStaticSync {
+    fn default() -> Self {
+        Self {
+            iterations: 10_000,
+            interval: Duration::ZERO,
+            command: DcStaticSyncCommand::default(),
+            read_deviation: true,
+        }
+    }
+}
+
+impl DcStaticSync {
+    /// Do not perform any static sync.
+    pub fn disabled() -> Self {
+        Self {
+            iterations: 0,
+            read_deviation: false,
+            ..Self::default()
+        }
+    }
+}
+
+/// The command used to distribute the DC reference clock's system time during static sync.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum DcStaticSyncCommand {
+    /// Address the reference clock by its configured address with `FRMW` (default).
+    #[default]
+    Frmw,
+
+    /// Address the reference clock by its position in the network with `ARMW`.
+    Armw,
+}
+
+/// The commands used to exchange a group's process data.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum PdiExchange {
+    /// Read inputs and write outputs with a single `LRW` (default).
+    ///
+    /// The returned working counter is incremented by 1 for every SubDevice with inputs and by 2
+    /// for every SubDevice with outputs.
+    #[default]
+    Lrw,
+
+    /// Read inputs with an `LRD` and write outputs with a separate `LWR`, sent in the same frame.
+    ///
+    /// Some ESCs don't support `LRW`, and separate commands can be easier to inspect when
+    /// diagnosing a network. The returned working counter is the sum of both commands, incremented
+    /// by 1 for every SubDevice with inputs and by 1 for every SubDevice with outputs.
+    LrdLwr {
+        /// Map outputs to the same logical addresses as inputs instead of placing them after the
+        /// inputs.
+        ///
+        /// Each group's FMMUs then only span as many logical addresses as the longer of its inputs
+        /// and outputs.
+        overlap: bool,
+    },
+}
+
+impl PdiExchange {
+    /// The exchange used to send inputs and outputs on their own, keeping the same logical
+    /// addresses.
+    pub(crate) fn separate(self) -> Self {
+        match self {
+            PdiExchange::Lrw => PdiExchange::LrdLwr { overlap: false },
+            exchange @ PdiExchange::LrdLwr { .. } => exchange,
+        }
+    }
+}
+
+/// When and how a group's outputs are put into a fail-safe state.
+///
+/// The fail-safe state is checked before every process data exchange that writes outputs, e.g.
+/// [`SlaveGroup::tx_rx`](crate::SlaveGroup::tx_rx), and ends as soon as none of its conditions are
+/// met, e.g. once the application writes its outputs again and the working counter is correct.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct FailSafe {
+    /// Enter the fail-safe state when the application has not called
+    /// [`SlaveGroup::outputs_updated`](crate::SlaveGroup::outputs_updated) for this many
+    /// exchanges, e.g. because it is stuck or overrunning its cycle.
+    ///
+    /// Defaults to `None`, which disables this check.
+    pub missed_cycles: Option<u32>,
+
+    /// Enter the fail-safe state after this many consecutive exchanges that fail or return an
+    /// unexpected working counter.
+    ///
+    /// Defaults to `None`, which disables this check.
+    pub wkc_errors: Option<u32>,
+
+    /// What is sent to the group's outputs while in the fail-safe state.
+    ///
+    /// Defaults to [`FailSafeOutputs::Clear`].
+    pub outputs: FailSafeOutputs,
+}
+
+/// What a group sends to its outputs while in the fail-safe state.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum FailSafeOutputs {
+    /// Set all of the group's outputs to zero (default).
+    ///
+    /// The zeros are written into the group's output data, so the application must write all of
+    /// its outputs again to leave the fail-safe state.
+    #[default]
+    Clear,
+
+    /// Stop writing the group's outputs, so SubDevices hold the last values they received.
+    ///
+    /// Only inputs are exchanged, like
+    /// [`SlaveGroup::tx_rx_inputs_only`](crate::SlaveGroup::tx_rx_inputs_only). Note that a
+    /// SubDevice's sync manager watchdog may still clear its outputs if it is enabled.
+    Hold,
+}
+
 /// Network communication retry policy.
 ///
 /// Retries will be performed at the rate defined by [`Timeouts::pdu`](crate::Timeouts::pdu).
@@ -69,4 +436,16 @@ mod tests {
         assert_eq!(RetryBehaviour::Count(10).loop_counts(), 10);
         assert_eq!(RetryBehaviour::Forever.loop_counts(), usize::MAX);
     }
+
+    #[test]
+    fn separate_pdi_exchange() {
+        assert_eq!(
+            PdiExchange::Lrw.separate(),
+            PdiExchange::LrdLwr { overlap: false }
+        );
+        assert_eq!(
+            PdiExchange::LrdLwr { overlap: true }.separate(),
+            PdiExchange::LrdLwr { overlap: true }
+        );
+    }
 }
@@ -0,0 +1,106 @@
+//! Diagnosis History object `0x10F3`.
+//!
+//! Defined in ETG1020 Section 13 Diagnosis Handling.
+
+/// Diagnosis History object index.
+pub(crate) const DIAGNOSIS_HISTORY: u16 = 0x10f3;
+
+/// Sub-index holding the maximum number of messages stored by the SubDevice.
+pub(crate) const MAX_MESSAGES: u8 = 0x01;
+
+/// Sub-index holding the sub-index of the newest message.
+pub(crate) const NEWEST_MESSAGE: u8 = 0x02;
+
+/// Sub-index of the first message in the history ring buffer.
+pub(crate) const FIRST_MESSAGE: u8 = 0x06;
+
+/// The severity of a diagnosis message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bits = 4)]
+#[repr(u8)]
+pub enum DiagnosisSeverity {
+    /// Informational message.
+    Info = 0x00,
+    /// Warning message.
+    Warning = 0x01,
+    /// Error message.
+    Error = 0x02,
+    /// A severity not defined by ETG1020.
+    #[wire(catch_all)]
+    Other(u8),
+}
+
+/// A single message from a SubDevice's diagnosis history.
+///
+/// Messages may also contain parameters which are used to format the message text. These are not
+/// decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 16)]
+pub struct DiagnosisMessage {
+    /// Diagnosis code. Codes in the range `0xE000_0000..=0xE800_FFFF` are defined by ETG1020,
+    /// other codes are defined by the device manufacturer.
+    #[wire(bytes = 4)]
+    pub code: u32,
+    /// Message severity.
+    #[wire(bits = 4)]
+    pub severity: DiagnosisSeverity,
+    /// If set, [`timestamp`](DiagnosisMessage::timestamp) is the SubDevice's local time instead of
+    /// Distributed Clock system time.
+    #[wire(bits = 1, post_skip = 3)]
+    pub local_timestamp: bool,
+    /// The number of parameters following the message.
+    #[wire(bytes = 1)]
+    pub parameter_count: u8,
+    /// Identifies the message text in the SubDevice's ESI file.
+    #[wire(bytes = 2)]
+    pub text_id: u16,
+    /// The time the message was created, in nanoseconds.
+    #[wire(bytes = 8)]
+    pub timestamp: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireRead;
+
+    #[test]
+    fn decode_message() {
+        let raw = [
+            // Code
+            0x00, 0xe0, 0x00, 0x00, //
+            // Flags: error, DC timestamp, 1 parameter
+            0x02, 0x01, //
+            // Text ID
+            0x34, 0x12, //
+            // Timestamp
+            0x00, 0xca, 0x9a, 0x3b, 0x00, 0x00, 0x00, 0x00, //
+            // Parameter: flags, then data
+            0x05, 0x00, 0xaa,
+        ];
+
+        assert_eq!(
+            DiagnosisMessage::unpack_from_slice(&raw),
+            Ok(DiagnosisMessage {
+                code: 0xe000,
+                severity: DiagnosisSeverity::Error,
+                local_timestamp: false,
+                parameter_count: 1,
+                text_id: 0x1234,
+                timestamp: 1_000_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn decode_unknown_severity() {
+        assert_eq!(
+            DiagnosisSeverity::unpack_from_slice(&[0x07]),
+            Ok(DiagnosisSeverity::Other(0x07))
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! CoE emergency messages.
+
+/// An emergency message sent by a SubDevice through its CoE mailbox.
+///
+/// Emergencies are sent unsolicited by a SubDevice when an internal error occurs, and are read by
+/// EtherCrab whenever the SubDevice's mailbox is checked. Use
+/// [`SlaveRef::pop_emergency`](crate::SlaveRef::pop_emergency) to retrieve them.
+///
+/// Defined in ETG1000.6 Section 5.6.4 Emergency.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 8)]
+pub struct CoeEmergency {
+    /// Emergency error code, as defined in ETG1000.6 Table 50 and CiA 301.
+    #[wire(bytes = 2)]
+    pub error_code: u16,
+    /// The value of the SubDevice's error register, object `0x1001`.
+    #[wire(bytes = 1)]
+    pub error_register: u8,
+    /// Manufacturer specific error data.
+    #[wire(bytes = 5)]
+    pub data: [u8; 5],
+}
+
+impl core::fmt::Display for CoeEmergency {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "emergency {:#06x}, error register {:#04x}, data {:02x?}",
+            self.error_code, self.error_register, self.data
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireRead;
+
+    #[test]
+    fn decode_emergency() {
+        // Generic error, error register bit 0 set, manufacturer data
+        let raw = [0x00u8, 0x10, 0x01, 0xaa, 0xbb, 0xcc, 0xdd, 0xee];
+
+        assert_eq!(
+            CoeEmergency::unpack_from_slice(&raw),
+            Ok(CoeEmergency {
+                error_code: 0x1000,
+                error_register: 0x01,
+                data: [0xaa, 0xbb, 0xcc, 0xdd, 0xee]
+            })
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use ethercrab_wire::EtherCrabWireReadSized;
 
 pub mod abort_code;
+pub mod diagnosis;
+pub mod emergency;
+pub mod sdo_info;
 pub mod services;
+pub mod sm_parameters;
 
 /// Defined in ETG1000.6 Table 29 – CoE elements
 #[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
@@ -34,6 +38,8 @@ pub enum CoeService {
 #[wire(bits = 3)]
 #[repr(u8)]
 pub enum CoeCommand {
+    /// Download segment request, also used as the upload segment response specifier.
+    DownloadSegment = 0x00,
     Download = 0x01,
     Upload = 0x02,
     Abort = 0x04,
@@ -111,18 +117,90 @@ impl From<u8> for SubIndex {
     }
 }
 
+/// A single entry in a PDO mapping object, e.g. `0x1600:01`.
+///
+/// Each entry maps an object dictionary entry into the PDO it is stored in.
+///
+/// Defined in ETG1000.6 Table 74/Table 75 Receive PDO Mapping.
+///
+/// Note that this struct order is opposite to the specification as the data is big-endian in
+/// EEPROM, but little endian on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 4)]
+pub struct PdoMapping {
+    /// Length of the mapped entry in bits.
+    #[wire(bytes = 1)]
+    pub bit_len: u8,
+    /// Sub-index of the mapped entry.
+    #[wire(bytes = 1)]
+    pub sub_index: u8,
+    /// Index of the mapped entry.
+    #[wire(bytes = 2)]
+    pub index: u16,
+}
+
+impl PdoMapping {
+    /// Create a new mapping entry for the given object index, sub-index and length in bits.
+    pub const fn new(index: u16, sub_index: u8, bit_len: u8) -> Self {
+        Self {
+            bit_len,
+            sub_index,
+            index,
+        }
+    }
+}
+
+/// An SDO write applied to a SubDevice during initialisation, similar to a TwinCAT startup command.
+///
+/// A list of startup SDOs can be given for each SubDevice with
+/// [`SlaveGroup::into_safe_op_with_startup`](crate::SlaveGroup::into_safe_op_with_startup).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StartupSdo<'a> {
+    /// Object index.
+    pub index: u16,
+    /// Object sub-index.
+    pub sub_index: u8,
+    /// Raw little-endian value to write.
+    pub data: &'a [u8],
+}
+
+impl<'a> StartupSdo<'a> {
+    /// Create a new startup SDO that writes `data` to the given object index and sub-index.
+    pub const fn new(index: u16, sub_index: u8, data: &'a [u8]) -> Self {
+        Self {
+            index,
+            sub_index,
+            data,
+        }
+    }
+}
+
 /// A trait for types that can be transferred with a single expedited SDO upload.
 pub(crate) trait SdoExpedited: EtherCrabWireReadSized {}
 
 impl SdoExpedited for u8 {}
 impl SdoExpedited for u16 {}
 impl SdoExpedited for u32 {}
+impl SdoExpedited for PdoMapping {}
 
 #[cfg(test)]
 mod tests {
     pub use super::*;
     use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWriteSized};
 
+    #[test]
+    fn pdo_mapping_round_trip() {
+        // 0x6040:00 control word, 16 bits
+        let mapping = PdoMapping::new(0x6040, 0x00, 16);
+
+        assert_eq!(mapping.pack(), [0x10, 0x00, 0x40, 0x60]);
+        assert_eq!(PdoMapping::unpack_from_slice(&mapping.pack()), Ok(mapping));
+    }
+
     #[test]
     fn sanity_coe_service() {
         assert_eq!(CoeService::SdoRequest.pack(), [0x02]);
@@ -0,0 +1,305 @@
+//! CoE SDO Information service, used to discover a SubDevice's object dictionary.
+//!
+//! Defined in ETG1000.6 Section 5.6.3 SDO Information.
+
+use super::CoeService;
+use crate::mailbox::{MailboxHeader, MailboxType, Priority};
+
+/// SDO Information service operation code.
+///
+/// Defined in ETG1000.6 Section 5.6.3.2 Table 43 – SDO Information Header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bits = 7)]
+#[repr(u8)]
+pub enum SdoInfoOpCode {
+    GetOdListRequest = 0x01,
+    GetOdListResponse = 0x02,
+    GetObjectDescriptionRequest = 0x03,
+    GetObjectDescriptionResponse = 0x04,
+    GetEntryDescriptionRequest = 0x05,
+    GetEntryDescriptionResponse = 0x06,
+    SdoInfoErrorRequest = 0x07,
+}
+
+/// Defined in ETG1000.6 Section 5.6.3.2 Table 43 – SDO Information Header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 4)]
+pub struct SdoInfoHeader {
+    #[wire(bits = 7)]
+    pub op_code: SdoInfoOpCode,
+    /// Set if more response fragments will follow this one.
+    #[wire(bits = 1, post_skip = 8)]
+    pub incomplete: bool,
+    #[wire(bytes = 2)]
+    pub fragments_left: u16,
+}
+
+/// Mailbox and SDO Information headers common to all SDO Information requests and responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 12)]
+pub struct SdoInfo {
+    #[wire(bytes = 8)]
+    pub header: MailboxHeader,
+    #[wire(bytes = 4)]
+    pub info_header: SdoInfoHeader,
+}
+
+/// The kind of object list to request from a SubDevice.
+///
+/// Defined in ETG1000.6 Section 5.6.3.3.1 Table 44 – Get OD List Request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 2)]
+#[repr(u16)]
+pub enum OdListType {
+    /// All objects in the object dictionary.
+    All = 0x01,
+    /// Objects that can be mapped into an RxPDO.
+    RxPdoMappable = 0x02,
+    /// Objects that can be mapped into a TxPDO.
+    TxPdoMappable = 0x03,
+    /// Objects that are stored for a device replacement.
+    StoredForReplacement = 0x04,
+    /// Objects that can be used as startup parameters.
+    StartupParameters = 0x05,
+}
+
+/// The kind of an object in the object dictionary.
+///
+/// Defined in ETG1000.6 Section 5.6.3.5.2 Table 47 – Get Object Description Response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 1)]
+#[repr(u8)]
+pub enum ObjectCode {
+    /// A single value.
+    Variable = 0x07,
+    /// Multiple values of the same type, each at a sub-index.
+    Array = 0x08,
+    /// Multiple values of different types, each at a sub-index.
+    Record = 0x09,
+    /// An object code not defined by the EtherCAT specification.
+    #[wire(catch_all)]
+    Other(u8),
+}
+
+/// The fixed-size part of a Get Object Description response, followed by the object name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[wire(bytes = 6)]
+pub struct ObjectDescriptionHeader {
+    #[wire(bytes = 2)]
+    pub index: u16,
+    #[wire(bytes = 2)]
+    pub data_type: u16,
+    #[wire(bytes = 1)]
+    pub max_sub_index: u8,
+    #[wire(bytes = 1)]
+    pub object_code: ObjectCode,
+}
+
+/// Access rights and mapping flags of an object dictionary entry.
+///
+/// Defined in ETG1000.6 Section 5.6.3.6.2 Table 49 – Get Entry Description Response.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 2)]
+pub struct ObjectAccess {
+    /// Readable in PRE-OP.
+    #[wire(bits = 1)]
+    pub read_pre_op: bool,
+    /// Readable in SAFE-OP.
+    #[wire(bits = 1)]
+    pub read_safe_op: bool,
+    /// Readable in OP.
+    #[wire(bits = 1)]
+    pub read_op: bool,
+    /// Writable in PRE-OP.
+    #[wire(bits = 1)]
+    pub write_pre_op: bool,
+    /// Writable in SAFE-OP.
+    #[wire(bits = 1)]
+    pub write_safe_op: bool,
+    /// Writable in OP.
+    #[wire(bits = 1)]
+    pub write_op: bool,
+    /// The entry can be mapped into an RxPDO.
+    #[wire(bits = 1)]
+    pub rx_pdo_mappable: bool,
+    /// The entry can be mapped into a TxPDO.
+    #[wire(bits = 1)]
+    pub tx_pdo_mappable: bool,
+    /// The entry is stored for a device replacement.
+    #[wire(bits = 1)]
+    pub backup: bool,
+    /// The entry can be used as a startup parameter.
+    #[wire(bits = 1, post_skip = 6)]
+    pub settings: bool,
+}
+
+/// The fixed-size part of a Get Entry Description response, followed by the entry name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[wire(bytes = 10)]
+pub struct EntryDescriptionHeader {
+    #[wire(bytes = 2)]
+    pub index: u16,
+    #[wire(bytes = 1)]
+    pub sub_index: u8,
+    /// Bit mask of optional data included in the response. EtherCrab never requests any.
+    #[wire(bytes = 1)]
+    pub value_info: u8,
+    #[wire(bytes = 2)]
+    pub data_type: u16,
+    #[wire(bytes = 2)]
+    pub bit_len: u16,
+    #[wire(bytes = 2)]
+    pub access: ObjectAccess,
+}
+
+/// A description of an object in a SubDevice's object dictionary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ObjectDescription {
+    /// Object index, e.g. `0x1018`.
+    pub index: u16,
+    /// Data type index, as defined in ETG1000.6 Section 5.6.7.
+    pub data_type: u16,
+    /// The highest sub-index of this object.
+    pub max_sub_index: u8,
+    /// The kind of object.
+    pub object_code: ObjectCode,
+    /// Human readable object name.
+    pub name: heapless::String<64>,
+}
+
+/// A description of a single sub-index of an object in a SubDevice's object dictionary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EntryDescription {
+    /// Object index.
+    pub index: u16,
+    /// Entry sub-index.
+    pub sub_index: u8,
+    /// Data type index, as defined in ETG1000.6 Section 5.6.7.
+    pub data_type: u16,
+    /// Length of the entry in bits.
+    pub bit_len: u16,
+    /// Access rights and mapping flags.
+    pub access: ObjectAccess,
+    /// Human readable entry name.
+    pub name: heapless::String<64>,
+}
+
+fn request(counter: u8, op_code: SdoInfoOpCode, payload_len: u16) -> SdoInfo {
+    SdoInfo {
+        header: MailboxHeader {
+            // CoE header + SDO Info header + payload
+            length: 0x06 + payload_len,
+            priority: Priority::Lowest,
+            mailbox_type: MailboxType::Coe,
+            counter,
+            service: CoeService::SdoInformation,
+        },
+        info_header: SdoInfoHeader {
+            op_code,
+            incomplete: false,
+            fragments_left: 0,
+        },
+    }
+}
+
+/// Get OD List request, followed by an [`OdListType`].
+pub fn get_od_list(counter: u8) -> SdoInfo {
+    request(counter, SdoInfoOpCode::GetOdListRequest, 2)
+}
+
+/// Get Object Description request, followed by the object index.
+pub fn get_object_description(counter: u8) -> SdoInfo {
+    request(counter, SdoInfoOpCode::GetObjectDescriptionRequest, 2)
+}
+
+/// Get Entry Description request, followed by the object index, sub-index and value info.
+pub fn get_entry_description(counter: u8) -> SdoInfo {
+    request(counter, SdoInfoOpCode::GetEntryDescriptionRequest, 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWrite};
+
+    #[test]
+    fn encode_od_list_request() {
+        let request = get_od_list(2);
+
+        let mut buf = [0u8; 14];
+
+        assert_eq!(
+            (request, OdListType::All).pack_to_slice(&mut buf),
+            Ok(&[8u8, 0, 0, 0, 0, 0x23, 0x00, 0x80, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00][..])
+        );
+    }
+
+    #[test]
+    fn decode_incomplete_response() {
+        let raw = [
+            0x0c, 0x00, 0x00, 0x00, 0x00, 0x33, 0x00, 0x80, 0x82, 0x00, 0x03, 0x00,
+        ];
+
+        assert_eq!(
+            SdoInfo::unpack_from_slice(&raw),
+            Ok(SdoInfo {
+                header: MailboxHeader {
+                    length: 12,
+                    priority: Priority::Lowest,
+                    mailbox_type: MailboxType::Coe,
+                    counter: 3,
+                    service: CoeService::SdoInformation,
+                },
+                info_header: SdoInfoHeader {
+                    op_code: SdoInfoOpCode::GetOdListResponse,
+                    incomplete: true,
+                    fragments_left: 3,
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn decode_entry_description() {
+        // 0x1018:01, UDINT, 32 bits, readable in all states
+        let raw = [0x18, 0x10, 0x01, 0x00, 0x07, 0x00, 0x20, 0x00, 0x07, 0x00];
+
+        assert_eq!(
+            EntryDescriptionHeader::unpack_from_slice(&raw),
+            Ok(EntryDescriptionHeader {
+                index: 0x1018,
+                sub_index: 1,
+                value_info: 0,
+                data_type: 0x0007,
+                bit_len: 32,
+                access: ObjectAccess {
+                    read_pre_op: true,
+                    read_safe_op: true,
+                    read_op: true,
+                    ..ObjectAccess::default()
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn decode_object_code_other() {
+        assert_eq!(
+            ObjectCode::unpack_from_slice(&[0x09]),
+            Ok(ObjectCode::Record)
+        );
+        assert_eq!(
+            ObjectCode::unpack_from_slice(&[0x02]),
+            Ok(ObjectCode::Other(2))
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use super::{CoeService, InitSdoHeader, SegmentSdoHeader, SubIndex};
 use crate::mailbox::{MailboxHeader, MailboxType, Priority};
 use core::fmt::Display;
+use ethercrab_wire::EtherCrabWireSized;
 
 /// An expedited (data contained within SDO as opposed to sent in subsequent packets) SDO download
 /// request.
@@ -63,8 +64,42 @@ impl Display for SdoNormal {
     }
 }
 
+/// Headers for a normal (non-expedited) SDO download request.
+///
+/// This request is also used to initiate a segmented download. The data to write to the SubDevice
+/// follows these headers in the mailbox.
+///
+/// See ETG1000.6 Section 5.6.2.2.1 Initiate SDO Download Request.
+#[derive(Debug, Copy, Clone, PartialEq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 16)]
+pub struct SdoNormalDownload {
+    #[wire(bytes = 12)]
+    pub headers: SdoNormal,
+    /// The total number of bytes to be downloaded, across all segments.
+    #[wire(bytes = 4)]
+    pub complete_size: u32,
+}
+
+impl Display for SdoNormalDownload {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "SDO download({:#06x}:{}, {} bytes",
+            self.headers.sdo_header.index, self.headers.sdo_header.sub_index, self.complete_size
+        )?;
+
+        if self.headers.sdo_header.complete_access {
+            write!(f, " complete access)")?;
+        } else {
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Headers belonging to segmented SDO transfers.
-#[derive(Debug, Copy, Clone, ethercrab_wire::EtherCrabWireReadWrite)]
+#[derive(Debug, Copy, Clone, PartialEq, ethercrab_wire::EtherCrabWireReadWrite)]
 #[wire(bytes = 9)]
 pub struct SdoSegmented {
     #[wire(bytes = 8)]
@@ -85,6 +120,9 @@ impl Display for SdoSegmented {
 pub trait CoeServiceRequest:
     ethercrab_wire::EtherCrabWireReadWrite + ethercrab_wire::EtherCrabWireWriteSized
 {
+    /// The length of the headers preceding any data in the response to this request.
+    const RESPONSE_HEADER_LEN: usize = SdoNormal::PACKED_LEN;
+
     /// Get the auto increment counter value for this request.
     fn counter(&self) -> u8;
 }
@@ -101,7 +139,16 @@ impl CoeServiceRequest for SdoNormal {
     }
 }
 
+impl CoeServiceRequest for SdoNormalDownload {
+    fn counter(&self) -> u8 {
+        self.headers.header.counter
+    }
+}
+
 impl CoeServiceRequest for SdoSegmented {
+    // Segment responses only have a single byte SDO header.
+    const RESPONSE_HEADER_LEN: usize = SdoSegmented::PACKED_LEN;
+
     fn counter(&self) -> u8 {
         self.header.counter
     }
@@ -138,6 +185,69 @@ pub fn download(
     }
 }
 
+/// Create a normal SDO download request, or the first request of a segmented download.
+///
+/// `complete_size` is the length of the entire value being written, and `chunk_len` is the number
+/// of data bytes that will follow the headers in this request.
+pub fn download_normal(
+    counter: u8,
+    index: u16,
+    access: SubIndex,
+    complete_size: u32,
+    chunk_len: u16,
+) -> SdoNormalDownload {
+    SdoNormalDownload {
+        headers: SdoNormal {
+            header: MailboxHeader {
+                length: 0x0a + chunk_len,
+                // address: 0x0000,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Coe,
+                counter,
+                service: CoeService::SdoRequest,
+            },
+            sdo_header: InitSdoHeader {
+                size_indicator: true,
+                expedited_transfer: false,
+                size: 0,
+                complete_access: access.complete_access(),
+                command: super::CoeCommand::Download,
+                index,
+                sub_index: access.sub_index(),
+            },
+        },
+        complete_size,
+    }
+}
+
+/// Create a download SDO segment request carrying `chunk_len` bytes of data.
+///
+/// Segments always carry at least 7 bytes. Shorter chunks are padded, with the number of unused
+/// bytes stored in the segment header.
+pub fn download_segment(
+    counter: u8,
+    toggle: bool,
+    is_last_segment: bool,
+    chunk_len: u16,
+) -> SdoSegmented {
+    SdoSegmented {
+        header: MailboxHeader {
+            length: 0x03 + chunk_len.max(7),
+            // address: 0x0000,
+            priority: Priority::Lowest,
+            mailbox_type: MailboxType::Coe,
+            counter,
+            service: CoeService::SdoRequest,
+        },
+        sdo_header: SegmentSdoHeader {
+            is_last_segment,
+            segment_data_size: 7u16.saturating_sub(chunk_len) as u8,
+            toggle,
+            command: super::CoeCommand::DownloadSegment,
+        },
+    }
+}
+
 pub fn upload_segmented(counter: u8, toggle: bool) -> SdoSegmented {
     SdoSegmented {
         header: MailboxHeader {
@@ -184,7 +294,9 @@ pub fn upload(counter: u8, index: u16, access: SubIndex) -> SdoNormal {
 mod tests {
     use super::*;
     use crate::error::CoeAbortCode;
-    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite};
+    use ethercrab_wire::{
+        EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite, EtherCrabWireWriteSized,
+    };
 
     #[test]
     fn decode_sdo_response_normal() {
@@ -280,6 +392,52 @@ mod tests {
         )
     }
 
+    #[test]
+    fn encode_download_normal() {
+        let request = download_normal(3, 0x1234, 2.into(), 20, 20);
+
+        pretty_assertions::assert_eq!(
+            request.pack(),
+            [30u8, 0, 0, 0, 0, 0x33, 0x00, 0x20, 0x21, 0x34, 0x12, 0x02, 20, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn encode_download_segment() {
+        let request = download_segment(4, true, false, 100);
+
+        pretty_assertions::assert_eq!(
+            request,
+            SdoSegmented {
+                header: MailboxHeader {
+                    length: 103,
+                    priority: Priority::Lowest,
+                    mailbox_type: MailboxType::Coe,
+                    counter: 4,
+                    service: CoeService::SdoRequest,
+                },
+                sdo_header: SegmentSdoHeader {
+                    is_last_segment: false,
+                    segment_data_size: 0,
+                    toggle: true,
+                    command: crate::coe::CoeCommand::DownloadSegment,
+                },
+            }
+        );
+
+        assert_eq!(request.pack()[8], 0x10);
+    }
+
+    #[test]
+    fn encode_download_segment_short() {
+        let request = download_segment(5, false, true, 3);
+
+        assert_eq!(request.header.length, 10);
+        assert_eq!(request.sdo_header.segment_data_size, 4);
+        // Last segment flag, 4 unused bytes, no toggle, download segment command
+        assert_eq!(request.pack()[8], 0b0000_1001);
+    }
+
     #[test]
     fn upload_request_normal() {
         let request = upload(210, 0x4567, 2.into());
@@ -381,4 +539,35 @@ mod tests {
 
         pretty_assertions::assert_eq!(parsed, Ok(expected));
     }
+
+    #[test]
+    fn decode_abort_frame() {
+        // SubDevice aborting an upload of 0x1a00:01, a write only object
+        let raw = [
+            0x0a, 0x00, 0x00, 0x00, 0x00, 0x23, 0x00, 0x20, 0x80, 0x00, 0x1a, 0x01, 0x01, 0x00,
+            0x01, 0x06,
+        ];
+
+        let headers = SdoNormal::unpack_from_slice(&raw).expect("Headers");
+
+        assert_eq!(headers.sdo_header.command, crate::coe::CoeCommand::Abort);
+        assert_eq!(headers.sdo_header.index, 0x1a00);
+        assert_eq!(headers.sdo_header.sub_index, 1);
+
+        let abort_code = CoeAbortCode::unpack_from_slice(&raw[SdoNormal::PACKED_LEN..]);
+
+        assert_eq!(abort_code, Ok(CoeAbortCode::WriteOnlyRead));
+    }
+
+    #[test]
+    fn decode_abort_frame_unknown_code() {
+        let raw = [
+            0x0a, 0x00, 0x00, 0x00, 0x00, 0x23, 0x00, 0x20, 0x80, 0x00, 0x60, 0x00, 0xde, 0xc0,
+            0xad, 0x0b,
+        ];
+
+        let abort_code = CoeAbortCode::unpack_from_slice(&raw[SdoNormal::PACKED_LEN..]);
+
+        assert_eq!(abort_code, Ok(CoeAbortCode::Unknown(0x0bad_c0de)));
+    }
 }
@@ -0,0 +1,97 @@
+//! Sync manager parameter objects `0x1C32` (outputs) and `0x1C33` (inputs).
+//!
+//! Defined in ETG1020 Section 21.1 Synchronization.
+
+/// Sync manager 2 (outputs) parameter object.
+pub(crate) const SM_OUTPUT_PARAMETERS: u16 = 0x1c32;
+
+/// Sync manager 3 (inputs) parameter object.
+pub(crate) const SM_INPUT_PARAMETERS: u16 = 0x1c33;
+
+/// The synchronisation mode of a sync manager.
+///
+/// The mode of a SubDevice can be changed in PRE-OP with
+/// [`SlaveRef::set_sync_mode`](crate::SlaveRef::set_sync_mode).
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 2)]
+#[repr(u16)]
+pub enum SyncMode {
+    /// Free run, not synchronised to any event.
+    #[default]
+    FreeRun = 0x00,
+    /// Synchronised to the sync manager event, i.e. when process data is received.
+    ///
+    /// In `0x1C33`, this is the sync manager 3 event and is only used by SubDevices without
+    /// outputs.
+    SyncManager = 0x01,
+    /// Synchronised to the Distributed Clocks SYNC0 event.
+    Sync0 = 0x02,
+    /// Synchronised to the Distributed Clocks SYNC1 event.
+    Sync1 = 0x03,
+    /// Inputs synchronised to the sync manager 2 event. Only used in `0x1C33`.
+    SyncManager2 = 0x22,
+    /// A sync mode not defined by ETG1020.
+    #[wire(catch_all)]
+    Other(u16),
+}
+
+/// The contents of a sync manager parameter object, `0x1C32` for outputs or `0x1C33` for inputs.
+///
+/// All times are in nanoseconds. Entries not supported by the SubDevice are set to `0`.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SyncManagerParameters {
+    /// Current synchronisation mode.
+    pub sync_mode: SyncMode,
+    /// Cycle time of the synchronisation event.
+    pub cycle_time: u32,
+    /// Time between the synchronisation event and when outputs are applied or inputs are latched.
+    pub shift_time: u32,
+    /// Bit field of supported synchronisation modes.
+    pub sync_modes_supported: u16,
+    /// The shortest cycle time supported by the SubDevice.
+    pub min_cycle_time: u32,
+    /// Time needed by the SubDevice to copy process data and calculate outputs or inputs.
+    pub calc_and_copy_time: u32,
+    /// The shortest delay time supported by the SubDevice.
+    pub min_delay_time: u32,
+    /// Time between the synchronisation event and outputs being valid at the hardware.
+    pub delay_time: u32,
+    /// SYNC0 cycle time of the SubDevice.
+    pub sync0_cycle_time: u32,
+    /// Number of missed sync manager events while in OP.
+    pub sm_event_missed: u16,
+    /// Number of times the cycle time was too short for the SubDevice to process.
+    pub cycle_time_too_small: u16,
+    /// Number of times the shift time was too short to apply outputs or latch inputs in time.
+    pub shift_time_too_short: u16,
+    /// Number of times the RxPDO toggle bit was not updated. Only available in `0x1C32`.
+    pub rx_pdo_toggle_failed: u16,
+    /// Set if the SubDevice detected a synchronisation error in the previous cycle.
+    pub sync_error: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireRead;
+
+    #[test]
+    fn decode_sync_mode() {
+        assert_eq!(
+            SyncMode::unpack_from_slice(&[0x02, 0x00]),
+            Ok(SyncMode::Sync0)
+        );
+        assert_eq!(
+            SyncMode::unpack_from_slice(&[0x22, 0x00]),
+            Ok(SyncMode::SyncManager2)
+        );
+        assert_eq!(
+            SyncMode::unpack_from_slice(&[0x34, 0x12]),
+            Ok(SyncMode::Other(0x1234))
+        );
+    }
+}
@@ -16,6 +16,7 @@ const LRD: u8 = 0x0A;
 const BWR: u8 = 0x08;
 const APWR: u8 = 0x02;
 const FPWR: u8 = 0x05;
+const ARMW: u8 = 0x0D;
 const FRMW: u8 = 0x0E;
 const LWR: u8 = 0x0B;
 const LRW: u8 = 0x0c;
@@ -110,6 +111,7 @@ impl EtherCrabWireWriteSized for Command {
             Command::Read(Reads::Aprd { address, register })
             | Command::Read(Reads::Brd { address, register })
             | Command::Read(Reads::Fprd { address, register })
+            | Command::Read(Reads::Armw { address, register })
             | Command::Read(Reads::Frmw { address, register })
             | Command::Write(Writes::Apwr { address, register })
             | Command::Write(Writes::Fpwr { address, register })
@@ -126,6 +128,18 @@ impl EtherCrabWireWriteSized for Command {
     }
 }
 
+impl Command {
+    /// The logical address of an `LRD`, `LWR` or `LRW`, or `None` for any other command.
+    pub(crate) fn logical_address(&self) -> Option<u32> {
+        match *self {
+            Command::Read(Reads::Lrd { address })
+            | Command::Write(Writes::Lwr { address })
+            | Command::Write(Writes::Lrw { address }) => Some(address),
+            _ => None,
+        }
+    }
+}
+
 impl core::fmt::Display for Command {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -142,6 +156,9 @@ impl core::fmt::Display for Command {
                     write!(f, "BRD(addr {:#06x}, reg {:#06x})", address, register)
                 }
                 Reads::Lrd { address } => write!(f, "LRD(addr {:#010x})", address),
+                Reads::Armw { address, register } => {
+                    write!(f, "ARMW(addr {:#06x}, reg {:#06x})", address, register)
+                }
                 Reads::Frmw { address, register } => {
                     write!(f, "FRMW(addr {:#06x}, reg {:#06x})", address, register)
                 }
@@ -214,6 +231,16 @@ impl Command {
         })
     }
 
+    /// Auto increment physical read, multiple write (ARMW).
+    ///
+    /// Like [`frmw`](Command::frmw), but the slave is addressed by its position in the network.
+    pub fn armw(address: u16, register: u16) -> WrappedRead {
+        WrappedRead::new(Reads::Armw {
+            address: 0u16.wrapping_sub(address),
+            register,
+        })
+    }
+
     /// Configured address read, multiple write (FRMW).
     ///
     /// This can be used to distribute a value from one slave to all others on the network, e.g.
@@ -227,6 +254,11 @@ impl Command {
         WrappedWrite::new(Writes::Lrw { address })
     }
 
+    /// Logical Read (LRD).
+    pub fn lrd(address: u32) -> WrappedRead {
+        WrappedRead::new(Reads::Lrd { address })
+    }
+
     /// Logical Write (LWR).
     pub fn lwr(address: u32) -> WrappedWrite {
         WrappedWrite::new(Writes::Lwr { address })
@@ -242,6 +274,7 @@ impl Command {
                 Reads::Fprd { .. } => FPRD,
                 Reads::Brd { .. } => BRD,
                 Reads::Lrd { .. } => LRD,
+                Reads::Armw { .. } => ARMW,
                 Reads::Frmw { .. } => FRMW,
             },
 
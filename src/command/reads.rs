@@ -35,6 +35,14 @@ pub enum Reads {
         /// Logical address.
         address: u32,
     },
+    /// ARMW.
+    Armw {
+        /// Auto increment counter.
+        address: u16,
+
+        /// Memory location to read from.
+        register: u16,
+    },
     /// FRMW.
     Frmw {
         /// Configured station address.
@@ -6,12 +6,14 @@
 
 use crate::{
     command::Command,
-    error::Error,
+    error::{DistributedClockError, Error},
     fmt,
     register::RegisterAddress,
     slave::{ports::Topology, Slave},
-    Client, SlaveRef,
+    timer_factory::sleep,
+    Client, DcReference, DcStaticSync, DcStaticSyncCommand, SlaveRef,
 };
+use core::time::Duration;
 
 /// Send a broadcast to all slaves to latch in DC receive time, then store it on the slave structs.
 async fn latch_dc_times(client: &Client<'_>, slaves: &mut [Slave]) -> Result<(), Error> {
@@ -30,11 +32,20 @@ async fn latch_dc_times(client: &Client<'_>, slaves: &mut [Slave]) -> Result<(),
     for slave in slaves.iter_mut().filter(|slave| slave.flags.dc_supported) {
         let sl = SlaveRef::new(client, slave.configured_address(), ());
 
-        let dc_receive_time = sl
-            .read(RegisterAddress::DcReceiveTime)
-            .ignore_wkc()
-            .receive::<u64>(client)
-            .await?;
+        // SubDevices without 64 bit DC support only implement the lower 32 bits of DC time
+        // registers.
+        let dc_receive_time = if slave.flags.has_64bit_dc {
+            sl.read(RegisterAddress::DcReceiveTime)
+                .ignore_wkc()
+                .receive::<u64>(client)
+                .await?
+        } else {
+            sl.read(RegisterAddress::DcReceiveTime)
+                .ignore_wkc()
+                .receive::<u32>(client)
+                .await?
+                .into()
+        };
 
         let [time_p0, time_p1, time_p2, time_p3] = sl
             .read(RegisterAddress::DcTimePort0)
@@ -84,13 +95,19 @@ async fn write_dc_parameters(
         now_nanos
     );
 
-    Command::fpwr(
+    let offset = Command::fpwr(
         slave.configured_address(),
         RegisterAddress::DcSystemTimeOffset.into(),
     )
-    .ignore_wkc()
-    .send(client, system_time_offset)
-    .await?;
+    .ignore_wkc();
+
+    // The offset wraps in the same way as the 32 bit system time, so only the lower half is
+    // written to SubDevices without 64 bit DC support.
+    if slave.flags.has_64bit_dc {
+        offset.send(client, system_time_offset).await?;
+    } else {
+        offset.send(client, system_time_offset as u32).await?;
+    }
 
     Command::fpwr(
         slave.configured_address(),
@@ -400,13 +417,13 @@ pub(crate) async fn configure_dc<'slaves>(
 
     assign_parent_relationships(slaves)?;
 
-    let first_dc_slave = slaves.iter().find(|slave| slave.flags.dc_supported);
+    let reference = find_reference(slaves, client.config.dc_reference)?;
 
-    if let Some(first_dc_slave) = first_dc_slave.as_ref() {
+    if let Some(reference) = reference.as_ref() {
         let now_nanos = now();
 
         for slave in slaves.iter().filter(|sl| sl.dc_support().any()) {
-            write_dc_parameters(client, slave, first_dc_slave.dc_receive_time, now_nanos).await?;
+            write_dc_parameters(client, slave, reference.dc_receive_time, now_nanos).await?;
         }
     } else {
         fmt::debug!("No SubDevices with DC support found");
@@ -414,36 +431,78 @@ pub(crate) async fn configure_dc<'slaves>(
 
     fmt::debug!("Distributed clock config complete");
 
-    Ok(first_dc_slave)
+    Ok(reference)
+}
+
+/// Find the SubDevice to use as the DC reference clock.
+///
+/// Returns `Ok(None)` if no SubDevice supports DC and the first DC SubDevice was requested.
+fn find_reference(slaves: &[Slave], reference: DcReference) -> Result<Option<&Slave>, Error> {
+    match reference {
+        DcReference::First => Ok(slaves.iter().find(|slave| slave.flags.dc_supported)),
+        DcReference::Address(address) => slaves
+            .iter()
+            .find(|slave| slave.configured_address() == address && slave.flags.dc_supported)
+            .map(Some)
+            .ok_or_else(|| {
+                fmt::error!(
+                    "SubDevice {:#06x} doesn't exist or doesn't support DC, so can't be the DC reference",
+                    address
+                );
+
+                Error::DistributedClock(DistributedClockError::InvalidReference(address))
+            }),
+    }
 }
 
-/// Send `iterations` FRMW frames to synchronise the network with the reference clock in the
-/// designated DC SubDevice.
+/// Perform static drift compensation, returning the residual DC system time deviation if
+/// [`DcStaticSync::read_deviation`] is set.
 pub(crate) async fn run_dc_static_sync(
     client: &Client<'_>,
     dc_reference_slave: &Slave,
-    iterations: u32,
-) -> Result<(), Error> {
+    config: DcStaticSync,
+) -> Result<Option<u32>, Error> {
     fmt::debug!(
         "Performing static drift compensation using slave {:#06x} {} as reference. This can take some time...",
         dc_reference_slave.configured_address(),
         dc_reference_slave.name
     );
 
-    // Static drift compensation - distribute reference clock through network until slave clocks
-    // settle
-    for _ in 0..iterations {
-        Command::frmw(
+    let command = match config.command {
+        DcStaticSyncCommand::Frmw => Command::frmw(
             dc_reference_slave.configured_address(),
             RegisterAddress::DcSystemTime.into(),
-        )
-        .receive_wkc::<u64>(client)
-        .await?;
+        ),
+        DcStaticSyncCommand::Armw => Command::armw(
+            dc_reference_slave.index,
+            RegisterAddress::DcSystemTime.into(),
+        ),
+    };
+
+    // Static drift compensation - distribute reference clock through network until slave clocks
+    // settle
+    for i in 0..config.iterations {
+        if i > 0 && config.interval > Duration::ZERO {
+            sleep(config.interval).await;
+        }
+
+        command.receive_wkc::<u64>(client).await?;
     }
 
-    fmt::debug!("Static drift compensation complete");
+    if !config.read_deviation {
+        fmt::debug!("Static drift compensation complete");
 
-    Ok(())
+        return Ok(None);
+    }
+
+    let deviation = client.system_time_deviation().await?;
+
+    fmt::debug!(
+        "Static drift compensation complete, residual deviation up to {} ns",
+        deviation
+    );
+
+    Ok(Some(deviation))
 }
 
 #[cfg(test)]
@@ -469,6 +528,54 @@ mod tests {
         make_ports(true, false, false, false)
     }
 
+    #[test]
+    fn select_reference() {
+        let dc = SupportFlags {
+            dc_supported: true,
+            ..SupportFlags::default()
+        };
+
+        let slaves = [
+            Slave {
+                configured_address: 0x1000,
+                ..Default::default()
+            },
+            Slave {
+                configured_address: 0x1001,
+                flags: dc.clone(),
+                ..Default::default()
+            },
+            Slave {
+                configured_address: 0x1002,
+                flags: dc,
+                ..Default::default()
+            },
+        ];
+
+        let address = |reference: Result<Option<&Slave>, Error>| {
+            reference.map(|slave| slave.map(|slave| slave.configured_address))
+        };
+
+        assert_eq!(
+            address(find_reference(&slaves, DcReference::First)),
+            Ok(Some(0x1001))
+        );
+        assert_eq!(
+            address(find_reference(&slaves, DcReference::Address(0x1002))),
+            Ok(Some(0x1002))
+        );
+        assert_eq!(
+            address(find_reference(&slaves, DcReference::Address(0x1000))),
+            Err(Error::DistributedClock(
+                DistributedClockError::InvalidReference(0x1000)
+            ))
+        );
+        assert_eq!(
+            address(find_reference(&slaves[..1], DcReference::First)),
+            Ok(None)
+        );
+    }
+
     // Test for topology including an EK1100 that creates a fork in the tree.
     #[test]
     fn parent_is_ek1100() {
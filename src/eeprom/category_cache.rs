@@ -0,0 +1,106 @@
+use super::{device_reader::SII_FIRST_CATEGORY_START, types::CategoryType};
+
+/// The maximum number of category locations cached per SubDevice. Categories after these are
+/// found by reading the EEPROM every time.
+///
+/// The cache is held on the stack while a SubDevice is configured, so only enough categories for a
+/// typical EEPROM are kept.
+const MAX_CACHED_CATEGORIES: usize = 12;
+
+/// The locations of categories in an EEPROM, found by earlier searches.
+///
+/// Finding a category reads the header of every category before it, so remembering the headers
+/// that have already been read saves many slow SII reads when several categories are read in turn.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct CategoryCache {
+    /// Category type, start WORD address and length in WORDs of every header read so far, in
+    /// EEPROM order.
+    categories: heapless::Vec<(CategoryType, u16, u16), MAX_CACHED_CATEGORIES>,
+
+    /// Whether the `End` marker has been read, so every category is cached.
+    complete: bool,
+}
+
+impl CategoryCache {
+    /// Find a category, returning its start WORD address and length in WORDs.
+    ///
+    /// Returns `Some(None)` if the category is known not to exist, or `None` if the rest of the
+    /// EEPROM must be searched, starting from [`next_header`](CategoryCache::next_header).
+    pub(crate) fn get(&self, category: CategoryType) -> Option<Option<(u16, u16)>> {
+        self.categories
+            .iter()
+            .find(|(cached, _, _)| *cached == category)
+            .map(|(_, start, len)| Some((*start, *len)))
+            .or(self.complete.then_some(None))
+    }
+
+    /// The WORD address of the first category header that hasn't been cached.
+    pub(crate) fn next_header(&self) -> u16 {
+        self.categories
+            .last()
+            .map_or(SII_FIRST_CATEGORY_START, |(_, start, len)| start + len)
+    }
+
+    /// Cache a category header read from WORD address `header`.
+    ///
+    /// Headers are only cached in EEPROM order, so a header that doesn't directly follow the last
+    /// cached category, e.g. because the cache is full, is ignored.
+    pub(crate) fn insert(&mut self, header: u16, category: CategoryType, len_words: u16) {
+        if self.complete || header != self.next_header() {
+            return;
+        }
+
+        if category == CategoryType::End {
+            self.complete = true;
+        } else {
+            // Header is 2 WORDs long, followed by the category data
+            let _ = self.categories.push((category, header + 2, len_words));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_order() {
+        let mut cache = CategoryCache::default();
+
+        assert_eq!(cache.get(CategoryType::General), None);
+        assert_eq!(cache.next_header(), 0x0040);
+
+        cache.insert(0x0040, CategoryType::Strings, 0x10);
+        // Out of order headers are ignored
+        cache.insert(0x0080, CategoryType::Fmmu, 0x02);
+        cache.insert(0x0052, CategoryType::General, 0x10);
+
+        assert_eq!(cache.get(CategoryType::Strings), Some(Some((0x0042, 0x10))));
+        assert_eq!(cache.get(CategoryType::General), Some(Some((0x0054, 0x10))));
+        assert_eq!(cache.get(CategoryType::Fmmu), None);
+        assert_eq!(cache.next_header(), 0x0064);
+
+        cache.insert(0x0064, CategoryType::End, 0xffff);
+
+        assert_eq!(cache.get(CategoryType::Fmmu), Some(None));
+        assert_eq!(cache.get(CategoryType::Strings), Some(Some((0x0042, 0x10))));
+    }
+
+    #[test]
+    fn full() {
+        let mut cache = CategoryCache::default();
+
+        for _ in 0..MAX_CACHED_CATEGORIES + 1 {
+            cache.insert(cache.next_header(), CategoryType::DeviceSpecific, 0x01);
+        }
+
+        let next = cache.next_header();
+
+        // The last category wasn't cached, so neither is the end marker after it
+        cache.insert(next + 3, CategoryType::End, 0xffff);
+
+        // Categories after a full cache must still be searched for
+        assert_eq!(cache.get(CategoryType::Fmmu), None);
+        assert_eq!(cache.next_header(), next);
+    }
+}
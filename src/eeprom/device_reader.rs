@@ -1,6 +1,6 @@
 use crate::{
     eeprom::{
-        types::{SiiControl, SiiRequest},
+        types::{SiiAddressSize, SiiControl, SiiRequest},
         EepromDataProvider,
     },
     error::{EepromError, Error},
@@ -16,11 +16,17 @@ use crate::{
 /// SII EEPROM is WORD-addressed.
 pub(crate) const SII_FIRST_CATEGORY_START: u16 = 0x0040u16;
 
+/// The number of times a WORD write is attempted if the EEPROM does not acknowledge it, e.g.
+/// because it is still busy with its previous internal write cycle.
+const WRITE_ATTEMPTS: u8 = 3;
+
 /// EEPROM data provider that communicates with a physical sub device.
 #[derive(Clone)]
 pub struct DeviceEeprom<'slave> {
     client: &'slave Client<'slave>,
     configured_address: u16,
+    /// Whether WORD addresses above `0xffff` are supported, once it has been read from the ESC.
+    extended_addressing: Option<bool>,
 }
 
 impl<'slave> DeviceEeprom<'slave> {
@@ -29,20 +35,52 @@ impl<'slave> DeviceEeprom<'slave> {
         Self {
             client,
             configured_address,
+            extended_addressing: None,
         }
     }
-}
 
-impl<'slave> EepromDataProvider for DeviceEeprom<'slave> {
-    async fn read_chunk(
-        &mut self,
-        start_word: u16,
-    ) -> Result<impl core::ops::Deref<Target = [u8]>, Error> {
-        Command::fpwr(self.configured_address, RegisterAddress::SiiControl.into())
-            .send_receive(self.client, SiiRequest::read(start_word))
-            .await?;
+    /// Check that the EEPROM can be accessed at WORD address `word`.
+    ///
+    /// WORD addresses above `0xffff` only exist in EEPROMs larger than 1 Mbit, which the ESC must
+    /// access with 2 address bytes. The ESC reports whether it supports these in the SII control
+    /// register, which is read the first time such an address is used.
+    async fn check_address(&mut self, word: u32) -> Result<(), Error> {
+        if word <= u32::from(u16::MAX) {
+            return Ok(());
+        }
+
+        let supported = match self.extended_addressing {
+            Some(supported) => supported,
+            None => {
+                let control =
+                    Command::fprd(self.configured_address, RegisterAddress::SiiControl.into())
+                        .receive::<SiiControl>(self.client)
+                        .await?;
+
+                let supported = control.address_type == SiiAddressSize::U16;
 
-        let status = async {
+                self.extended_addressing = Some(supported);
+
+                supported
+            }
+        };
+
+        if !supported {
+            fmt::error!(
+                "Slave {:#06x} EEPROM does not support WORD address {:#010x}",
+                self.configured_address,
+                word
+            );
+
+            return Err(Error::Eeprom(EepromError::SectionOverrun));
+        }
+
+        Ok(())
+    }
+
+    /// Poll the SII control register until the current EEPROM operation has finished.
+    async fn wait_while_busy(&self) -> Result<SiiControl, Error> {
+        async {
             loop {
                 let control: SiiControl =
                     Command::fprd(self.configured_address, RegisterAddress::SiiControl.into())
@@ -57,7 +95,37 @@ impl<'slave> EepromDataProvider for DeviceEeprom<'slave> {
             }
         }
         .timeout(self.client.timeouts.eeprom)
-        .await?;
+        .await
+    }
+
+    /// Reload the ESC configuration area from EEPROM into the ESC's registers.
+    pub(crate) async fn reload(&self) -> Result<(), Error> {
+        Command::fpwr(self.configured_address, RegisterAddress::SiiControl.into())
+            .send(self.client, SiiControl::reload())
+            .await?;
+
+        let status = self.wait_while_busy().await?;
+
+        if status.has_error() {
+            return Err(Error::Eeprom(EepromError::Verify));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'slave> EepromDataProvider for DeviceEeprom<'slave> {
+    async fn read_chunk(
+        &mut self,
+        start_word: u32,
+    ) -> Result<impl core::ops::Deref<Target = [u8]>, Error> {
+        self.check_address(start_word).await?;
+
+        Command::fpwr(self.configured_address, RegisterAddress::SiiControl.into())
+            .send_receive(self.client, SiiRequest::read(start_word))
+            .await?;
+
+        let status = self.wait_while_busy().await?;
 
         Command::fprd(self.configured_address, RegisterAddress::SiiData.into())
             .receive_slice(self.client, status.read_size.chunk_len())
@@ -96,4 +164,55 @@ impl<'slave> EepromDataProvider for DeviceEeprom<'slave> {
             Ok(())
         }
     }
+
+    /// Write a single WORD to the EEPROM.
+    ///
+    /// The EEPROM must be owned by the master. Writes are much slower than reads, so this may need a
+    /// longer [`eeprom`](crate::Timeouts::eeprom) timeout than the default.
+    async fn write_word(&mut self, word: u32, data: u16) -> Result<(), Error> {
+        fmt::trace!("Write addr {:#06x}: {:#06x}", word, data);
+
+        self.check_address(word).await?;
+
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            // The data register must not be written while a previous command is running
+            self.wait_while_busy().await?;
+            self.clear_errors().await?;
+
+            Command::fpwr(self.configured_address, RegisterAddress::SiiData.into())
+                .send(self.client, data)
+                .await?;
+
+            // The write enable bit is set in the same frame as the write command
+            Command::fpwr(self.configured_address, RegisterAddress::SiiControl.into())
+                .send_receive(self.client, SiiRequest::write(word))
+                .await?;
+
+            let status = self.wait_while_busy().await?;
+
+            if status.write_error {
+                fmt::error!("EEPROM write to {:#06x} was not enabled", word);
+
+                break Err(Error::Eeprom(EepromError::Write));
+            }
+
+            if !status.command_error {
+                break Ok(());
+            }
+
+            fmt::warn!(
+                "EEPROM write to {:#06x} not acknowledged on attempt {}",
+                word,
+                attempts
+            );
+
+            if attempts >= WRITE_ATTEMPTS {
+                break Err(Error::Eeprom(EepromError::Write));
+            }
+        }
+    }
 }
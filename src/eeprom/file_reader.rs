@@ -3,7 +3,10 @@
 //! Useful for debugging and unit testing. These items should not be used in production as they
 //! contain quite a few panics, unwraps and poor assumptions.
 
-use crate::{eeprom::EepromDataProvider, error::Error};
+use crate::{
+    eeprom::EepromDataProvider,
+    error::{EepromError, Error},
+};
 use std::{
     fs::File,
     io::{Read, Seek},
@@ -58,7 +61,7 @@ impl EepromFile<4> {
 impl<const CHUNK: usize> EepromDataProvider for EepromFile<CHUNK> {
     async fn read_chunk(
         &mut self,
-        start_word: u16,
+        start_word: u32,
     ) -> Result<impl core::ops::Deref<Target = [u8]>, Error> {
         let file_len = self.file.metadata().unwrap().len() as usize;
 
@@ -68,7 +71,7 @@ impl<const CHUNK: usize> EepromDataProvider for EepromFile<CHUNK> {
 
         // Make sure a partial read off the end of the file is ok, e.g. 8 byte buffer but 4 byte
         // read.
-        let buf_len = self.buf.len().min(file_len - usize::from(start_word * 2));
+        let buf_len = self.buf.len().min(file_len - start_word as usize * 2);
 
         let buf = &mut self.buf[0..buf_len];
 
@@ -88,4 +91,9 @@ impl<const CHUNK: usize> EepromDataProvider for EepromFile<CHUNK> {
     async fn clear_errors(&self) -> Result<(), Error> {
         Ok(())
     }
+
+    async fn write_word(&mut self, _word: u32, _data: u16) -> Result<(), Error> {
+        // EEPROM files are opened read only
+        Err(Error::Eeprom(EepromError::Write))
+    }
 }
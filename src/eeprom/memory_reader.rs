@@ -0,0 +1,116 @@
+use crate::{
+    eeprom::EepromDataProvider,
+    error::{EepromError, Error},
+};
+
+/// EEPROM data provider that serves an EEPROM image held in memory instead of a physical
+/// SubDevice.
+///
+/// This can be used to parse an EEPROM image, e.g. one saved from a SubDevice or read from a file,
+/// with [`SlaveEeprom`](crate::sii::SlaveEeprom) when testing or simulating without hardware.
+/// The image is read only, so writes return [`EepromError::Write`].
+///
+/// # Examples
+///
+/// ```rust
+/// use ethercrab::sii::{MemoryEeprom, SlaveEeprom};
+///
+/// # async fn run() -> Result<(), ethercrab::error::Error> {
+/// let image = std::fs::read("el2828.bin").expect("read EEPROM image");
+///
+/// let eeprom = SlaveEeprom::new(MemoryEeprom::new(&image));
+///
+/// println!("{}", eeprom.identity().await?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct MemoryEeprom<'a> {
+    image: &'a [u8],
+}
+
+impl<'a> MemoryEeprom<'a> {
+    /// The number of bytes returned by each read, the same as a SubDevice with 8 byte SII reads.
+    const CHUNK_LEN: usize = 8;
+
+    /// Create a new provider serving `image`, starting at WORD address `0x0000`.
+    pub fn new(image: &'a [u8]) -> Self {
+        Self { image }
+    }
+}
+
+impl<'a> EepromDataProvider for MemoryEeprom<'a> {
+    async fn read_chunk(
+        &mut self,
+        start_word: u32,
+    ) -> Result<impl core::ops::Deref<Target = [u8]>, Error> {
+        let start = start_word as usize * 2;
+
+        // Chunks always contain at least one WORD
+        if start + 2 > self.image.len() {
+            return Err(Error::Eeprom(EepromError::SectionOverrun));
+        }
+
+        let end = self.image.len().min(start + Self::CHUNK_LEN);
+
+        Ok(&self.image[start..end])
+    }
+
+    async fn clear_errors(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn write_word(&mut self, _word: u32, _data: u16) -> Result<(), Error> {
+        Err(Error::Eeprom(EepromError::Write))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{eeprom::file_reader::EepromFile, slave::SlaveEeprom};
+
+    #[tokio::test]
+    async fn same_as_file() {
+        let image = std::fs::read("dumps/eeprom/akd.hex").expect("read dump");
+
+        let memory = SlaveEeprom::new(MemoryEeprom::new(&image));
+        let file = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
+
+        assert_eq!(memory.identity().await, file.identity().await);
+        assert_eq!(
+            memory.device_name::<64>().await,
+            file.device_name::<64>().await
+        );
+        assert_eq!(memory.general().await, file.general().await);
+        assert_eq!(memory.sync_managers().await, file.sync_managers().await);
+        assert_eq!(
+            memory.master_read_pdos().await,
+            file.master_read_pdos().await
+        );
+        assert_eq!(
+            memory.master_write_pdos().await,
+            file.master_write_pdos().await
+        );
+    }
+
+    #[tokio::test]
+    async fn out_of_range() {
+        let image = [0x04, 0x01, 0x00, 0x00, 0x00];
+
+        let mut memory = MemoryEeprom::new(&image);
+
+        assert_eq!(
+            memory.read_chunk(0).await.map(|chunk| chunk.to_vec()),
+            Ok(image.to_vec())
+        );
+        assert_eq!(
+            memory.read_chunk(2).await.map(|chunk| chunk.to_vec()),
+            Err(Error::Eeprom(EepromError::SectionOverrun))
+        );
+        assert_eq!(
+            memory.write_word(0, 0x1234).await,
+            Err(Error::Eeprom(EepromError::Write))
+        );
+    }
+}
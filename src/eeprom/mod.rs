@@ -6,21 +6,64 @@ use crate::{
 };
 use embedded_io_async::{ErrorType, Read, ReadExactError};
 
+mod category_cache;
 pub mod device_reader;
+pub mod memory_reader;
 pub mod types;
 
+pub(crate) use self::category_cache::CategoryCache;
+
 #[cfg(feature = "std")]
 pub mod file_reader;
 
 /// A data source for EEPROM reads.
+///
+/// [`DeviceEeprom`](crate::sii::DeviceEeprom) reads a SubDevice's EEPROM, and
+/// [`MemoryEeprom`](crate::sii::MemoryEeprom) serves an EEPROM image held in memory. Other sources
+/// can implement this trait to be parsed with [`SlaveEeprom`](crate::sii::SlaveEeprom).
 pub trait EepromDataProvider: Clone {
     /// Read a chunk of either 4 or 8 bytes from the backing store.
-    #[cfg_attr(feature = "__internals", allow(async_fn_in_trait))]
-    async fn read_chunk(&mut self, start_word: u16) -> Result<impl Deref<Target = [u8]>, Error>;
+    #[allow(async_fn_in_trait)]
+    async fn read_chunk(&mut self, start_word: u32) -> Result<impl Deref<Target = [u8]>, Error>;
 
     /// Attempt to clear any errors in the EEPROM source.
-    #[cfg_attr(feature = "__internals", allow(async_fn_in_trait))]
+    #[allow(async_fn_in_trait)]
     async fn clear_errors(&self) -> Result<(), Error>;
+
+    /// Write a single WORD to the backing store.
+    #[allow(async_fn_in_trait)]
+    async fn write_word(&mut self, word: u32, data: u16) -> Result<(), Error>;
+}
+
+/// Compute the checksum of the EEPROM configuration area (WORDs `0x0000` to `0x0006`), stored in
+/// the low byte of WORD `0x0007`.
+///
+/// This is a CRC-8 with polynomial `x^8 + x^2 + x + 1` and an initial value of `0xff`, as defined
+/// in ETG2010 Table 2.
+///
+/// # Examples
+///
+/// ```rust
+/// use ethercrab::sii::config_checksum;
+///
+/// // EL2004 configuration area
+/// let config = [
+///     0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, //
+///     0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+/// ];
+///
+/// assert_eq!(config_checksum(&config), 0xd8);
+/// ```
+pub fn config_checksum(config: &[u8]) -> u8 {
+    config.iter().fold(0xff, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
 }
 
 impl embedded_io_async::Error for Error {
@@ -52,16 +95,16 @@ pub struct ChunkReader<P> {
     ///
     /// This is the last byte that was returned to the caller by the reader, and should be used as a
     /// base for skip offsets.
-    pos: u16,
+    pos: u32,
 
     /// The last byte address we're allowed to access.
-    end: u16,
+    end: u32,
 
     /// Position of last data that was actually asked for, e.g. the next byte after the current
     /// cache.
     ///
     /// This is WORD based.
-    read_pointer: u16,
+    read_pointer: u32,
 }
 
 impl<P> ChunkReader<P>
@@ -69,7 +112,7 @@ where
     P: EepromDataProvider,
 {
     /// Create a new `ChunkReader`.
-    pub fn new(reader: P, start_word: u16, len_words: u16) -> Self {
+    pub fn new(reader: P, start_word: u32, len_words: u32) -> Self {
         Self {
             reader,
             pos: start_word * 2,
@@ -80,6 +123,8 @@ where
 
     /// Skip N bytes (NOT words) ahead of the current position.
     pub fn skip_ahead_bytes(&mut self, skip: u16) -> Result<(), Error> {
+        let skip = u32::from(skip);
+
         fmt::trace!(
             "Skip EEPROM from pos {:#06x}, read pointer {:#06x}, by {} bytes to {:#06x}, end {:#06x}",
             self.pos,
@@ -129,7 +174,7 @@ where
 
         let requested_read_len = buf.len();
 
-        let max_read = usize::from(self.end - self.pos);
+        let max_read = (self.end - self.pos) as usize;
 
         let mut bytes_read = 0;
 
@@ -151,7 +196,7 @@ where
 
             // If position is odd, we must skip the first received byte as the reader operates on
             // WORD addresses.
-            let skip = usize::from(self.pos % 2);
+            let skip = usize::from(self.pos % 2 == 1);
 
             // Fix any odd addressing offsets
             let chunk = &chunk[skip..];
@@ -161,17 +206,17 @@ where
                 let (chunk, _rest) = chunk.split_at(buf.len());
 
                 bytes_read += chunk.len();
-                self.pos += chunk.len() as u16;
+                self.pos += chunk.len() as u32;
 
                 buf.copy_from_slice(chunk);
 
-                self.read_pointer += chunk.len() as u16 / 2;
+                self.read_pointer += chunk.len() as u32 / 2;
 
                 break;
             }
 
             bytes_read += chunk.len();
-            self.pos += chunk.len() as u16;
+            self.pos += chunk.len() as u32;
 
             // Buffer is not full. Write another chunk into the beginning of it.
             let (buf_start, buf_rest) = buf.split_at_mut(chunk.len());
@@ -204,6 +249,25 @@ mod tests {
     use super::*;
     use crate::eeprom::file_reader::EepromFile;
 
+    #[test]
+    fn checksum() {
+        // EL2004
+        assert_eq!(
+            config_checksum(&[
+                0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+            ]),
+            0xd8
+        );
+
+        // EL2828
+        assert_eq!(
+            config_checksum(&[
+                0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+            ]),
+            0xe2
+        );
+    }
+
     #[tokio::test]
     async fn skip_past_end() {
         let _ = env_logger::builder().is_test(true).try_init();
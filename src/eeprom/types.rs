@@ -72,6 +72,23 @@ impl SiiControl {
             ..Default::default()
         }
     }
+
+    /// Write command. The write enable bit must be set in the same frame as the command.
+    fn write() -> Self {
+        Self {
+            access: SiiAccess::ReadWrite,
+            write: true,
+            ..Default::default()
+        }
+    }
+
+    /// Reload the ESC configuration area, e.g. the configured station alias, from EEPROM.
+    pub fn reload() -> Self {
+        Self {
+            reload: true,
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default, ethercrab_wire::EtherCrabWireReadWrite)]
@@ -115,11 +132,9 @@ pub enum SiiAddressSize {
 pub struct SiiRequest {
     #[wire(bytes = 2)]
     control: SiiControl,
-    // Post skip is required to send the correct amount of bytes on the wire. This is weird because
-    // addressing is all a single WORD, but the SII read request expects a low AND high WORD, hence
-    // the extra 16 bits of padding here for the unusedhigh WORD.
-    #[wire(bytes = 2, post_skip = 16)]
-    address: u16,
+    /// WORD address. The high WORD is only non-zero for EEPROMs larger than 64 Ki WORDs.
+    #[wire(bytes = 4)]
+    address: u32,
 }
 
 impl core::fmt::Debug for SiiRequest {
@@ -132,12 +147,20 @@ impl core::fmt::Debug for SiiRequest {
 }
 
 impl SiiRequest {
-    pub fn read(address: u16) -> Self {
+    pub fn read(address: u32) -> Self {
         Self {
             control: SiiControl::read(),
             address,
         }
     }
+
+    /// Write the WORD previously stored in the SII data register to `address`.
+    pub fn write(address: u32) -> Self {
+        Self {
+            control: SiiControl::write(),
+            address,
+        }
+    }
 }
 
 /// SII register address.
@@ -262,10 +285,14 @@ impl From<PdoType> for CategoryType {
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum FmmuUsage {
+    /// The FMMU is not used.
     #[wire(alternatives = [0xff])]
     Unused = 0x00,
+    /// Maps process data outputs.
     Outputs = 0x01,
+    /// Maps process data inputs.
     Inputs = 0x02,
+    /// Maps a sync manager's status, e.g. for mailbox polling.
     SyncManagerStatus = 0x03,
 }
 
@@ -281,6 +308,40 @@ pub struct FmmuEx {
     pub sync_manager: u8,
 }
 
+/// An entry in the SII "DC" category, describing one of the SubDevice's DC sync modes.
+///
+/// Defined in ETG2010 Table 15.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 24)]
+pub struct SiiDcSync {
+    /// SYNC0 cycle time in nanoseconds, or `0` to derive it from the cycle time.
+    #[wire(bytes = 4)]
+    pub cycle_time0: u32,
+    /// SYNC0 shift time in nanoseconds.
+    #[wire(bytes = 4)]
+    pub shift_time0: u32,
+    /// SYNC1 shift time in nanoseconds.
+    #[wire(bytes = 4)]
+    pub shift_time1: u32,
+    /// SYNC1 cycle time as a multiple of the SYNC0 cycle time.
+    #[wire(bytes = 2)]
+    pub sync1_cycle_factor: i16,
+    /// Value of the DC activation registers `0x0980:0x0981`.
+    #[wire(bytes = 2)]
+    pub assign_activate: u16,
+    /// SYNC0 cycle time as a multiple of the cycle time.
+    #[wire(bytes = 2)]
+    pub sync0_cycle_factor: i16,
+    /// Index into the Strings category of the sync mode's name.
+    #[wire(bytes = 1)]
+    pub name_string_idx: u8,
+    /// Index into the Strings category of the sync mode's description.
+    #[wire(bytes = 1, post_skip_bytes = 4)]
+    pub desc_string_idx: u8,
+}
+
+/// The physical layer of each of a device's 4 ports.
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PortStatuses(pub [PortStatus; 4]);
@@ -322,71 +383,93 @@ impl EtherCrabWireRead for PortStatuses {
 /// SII "General" category.
 ///
 /// Defined in ETG1000.6 Table 21
-#[derive(Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
 #[wire(bytes = 18)]
 pub struct SiiGeneral {
+    /// Index into the Strings category of the device's group name.
     #[wire(bytes = 1)]
-    pub(crate) group_string_idx: u8,
+    pub group_string_idx: u8,
+    /// Index into the Strings category of the device's image name.
     #[wire(bytes = 1)]
-    pub(crate) image_string_idx: u8,
+    pub image_string_idx: u8,
+    /// Index into the Strings category of the device's order number.
     #[wire(bytes = 1)]
-    pub(crate) order_string_idx: u8,
+    pub order_string_idx: u8,
+    /// Index into the Strings category of the device's name.
     #[wire(bytes = 1, post_skip_bytes = 1)]
     pub name_string_idx: u8,
     // reserved: u8,
+    /// CoE features supported by the device.
     #[wire(bytes = 1)]
     pub coe_details: CoeDetails,
+    /// Whether the device supports File over EtherCAT.
     #[wire(bytes = 1)]
-    pub(crate) foe_enabled: bool,
+    pub foe_enabled: bool,
+    /// Whether the device supports Ethernet over EtherCAT.
     #[wire(bytes = 1, post_skip_bytes = 3)]
-    pub(crate) eoe_enabled: bool,
+    pub eoe_enabled: bool,
     // Following 3 fields marked as reserved
     // soe_channels: u8,
     // ds402_channels: u8,
     // sysman_class: u8,
+    /// Device flags.
     #[wire(bytes = 1)]
-    pub(crate) flags: Flags,
+    pub flags: GeneralFlags,
     /// EBus Current Consumption in mA.
     ///
     /// A negative Values means feeding in current feed in sets the available current value to the
     /// given value
     #[wire(bytes = 2)]
-    pub(crate) ebus_current: i16,
+    pub ebus_current: i16,
     // reserved: u8,
+    /// The physical layer of each of the device's ports.
     #[wire(bytes = 2)]
-    pub(crate) ports: PortStatuses,
+    pub ports: PortStatuses,
     /// defines the ESC memory address where the Identification ID is saved if Identification Method
-    /// [`IDENT_PHY_M`] is set.
+    /// [`IDENT_PHY_M`](GeneralFlags::IDENT_PHY_M) is set.
     #[wire(bytes = 2)]
-    pub(crate) physical_memory_addr: u16,
+    pub physical_memory_addr: u16,
     // reserved2: [u8; 12]
 }
 
+/// The physical layer of a port.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum PortStatus {
+    /// The port is not used.
     #[default]
     Unused = 0x00,
+    /// MII/RMII port.
     Mii = 0x01,
+    /// Reserved.
     Reserved = 0x02,
+    /// EBUS port.
     Ebus = 0x03,
+    /// MII/RMII port with fast hot connect.
     FastHotConnect = 0x04,
 }
 
 bitflags::bitflags! {
-    #[derive(Debug, PartialEq, Eq)]
-    pub struct Flags: u8 {
+    /// Flags in the SII "General" category.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct GeneralFlags: u8 {
+        /// Bit 0: SAFE-OP is supported.
         const ENABLE_SAFE_OP = 0x01;
+        /// Bit 1: LRW is not supported.
         const ENABLE_NOT_LRW = 0x02;
+        /// Bit 2: Mailbox data link layer is supported.
         const MAILBOX_DLL = 0x04;
+        /// Bit 3: The device ID is read with an ID request in the AL control register.
         const IDENT_AL_STATUS = 0x08;
+        /// Bit 4: The device ID is read from the ESC memory address in
+        /// [`SiiGeneral::physical_memory_addr`].
         const IDENT_PHY_M = 0x10;
 
     }
 }
 
-impl EtherCrabWireSized for Flags {
+impl EtherCrabWireSized for GeneralFlags {
     const PACKED_LEN: usize = 1;
 
     type Buffer = [u8; Self::PACKED_LEN];
@@ -396,7 +479,7 @@ impl EtherCrabWireSized for Flags {
     }
 }
 
-impl EtherCrabWireRead for Flags {
+impl EtherCrabWireRead for GeneralFlags {
     fn unpack_from_slice(buf: &[u8]) -> Result<Self, ethercrab_wire::WireError> {
         u8::unpack_from_slice(buf)
             .and_then(|value| Self::from_bits(value).ok_or(ethercrab_wire::WireError::InvalidValue))
@@ -404,7 +487,8 @@ impl EtherCrabWireRead for Flags {
 }
 
 bitflags::bitflags! {
-    #[derive(Debug, PartialEq, Eq)]
+    /// CoE features supported by a device.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub struct CoeDetails: u8 {
         /// Bit 0: Enable SDO
         const ENABLE_SDO = 0x01;
@@ -438,20 +522,26 @@ impl EtherCrabWireRead for CoeDetails {
     }
 }
 
+/// An entry in the SII "SyncM" category, describing one of the device's sync managers.
 #[derive(Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bytes = 8)]
 pub struct SyncManager {
+    /// Physical start address in the ESC's memory.
     #[wire(bytes = 2)]
-    pub(crate) start_addr: u16,
+    pub start_addr: u16,
+    /// Length in bytes.
     #[wire(bytes = 2)]
-    pub(crate) length: u16,
+    pub length: u16,
+    /// Initial value of the sync manager's control register.
     #[wire(bytes = 1, post_skip_bytes = 1)]
-    pub(crate) control: sync_manager_channel::Control,
+    pub control: sync_manager_channel::Control,
+    /// Enable flags.
     #[wire(bytes = 1)]
-    pub(crate) enable: SyncManagerEnable,
+    pub enable: SyncManagerEnable,
+    /// What the sync manager is used for.
     #[wire(bytes = 1)]
-    pub(crate) usage_type: SyncManagerType,
+    pub usage_type: SyncManagerType,
 }
 
 impl core::fmt::Debug for SyncManager {
@@ -467,6 +557,7 @@ impl core::fmt::Debug for SyncManager {
 }
 
 bitflags::bitflags! {
+    /// Flags describing how a sync manager is enabled.
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub struct SyncManagerEnable: u8 {
         /// Bit 0: enable.
@@ -505,6 +596,7 @@ impl defmt::Format for SyncManagerEnable {
     }
 }
 
+/// What a sync manager is used for.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
@@ -529,23 +621,29 @@ impl SdoExpedited for SyncManagerType {}
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bytes = 8)]
 pub struct Pdo {
+    /// PDO index in the object dictionary, e.g. `0x1a00`.
     #[wire(bytes = 2)]
-    pub(crate) index: u16,
+    pub index: u16,
+    /// Number of entries in this PDO.
     #[wire(bytes = 1)]
-    pub(crate) num_entries: u8,
+    pub num_entries: u8,
+    /// The sync manager this PDO is assigned to.
     #[wire(bytes = 1)]
-    pub(crate) sync_manager: u8,
+    pub sync_manager: u8,
+    /// DC sync mode, referencing an entry in the DC category.
     #[wire(bytes = 1)]
-    pub(crate) dc_sync: u8,
+    pub dc_sync: u8,
     /// Index into EEPROM Strings section for PDO name.
     #[wire(bytes = 1)]
-    pub(crate) name_string_idx: u8,
+    pub name_string_idx: u8,
+    /// PDO flags.
     #[wire(bytes = 2)]
-    pub(crate) flags: PdoFlags,
+    pub flags: PdoFlags,
 
+    /// The entries mapped into this PDO.
     // NOTE: This field is skipped during parsing from the wire and is populated later.
     #[wire(skip)]
-    pub(crate) entries: heapless::Vec<PdoEntry, 16>,
+    pub entries: heapless::Vec<PdoEntry, 16>,
 }
 
 impl core::fmt::Debug for Pdo {
@@ -573,23 +671,30 @@ impl Pdo {
     }
 }
 
-#[derive(Clone, PartialEq, ethercrab_wire::EtherCrabWireRead)]
+/// An object mapped into a [`Pdo`].
+#[derive(Copy, Clone, PartialEq, ethercrab_wire::EtherCrabWireRead)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bytes = 8)]
 pub struct PdoEntry {
+    /// Object index, or `0` for padding.
     #[wire(bytes = 2)]
-    pub(crate) index: u16,
+    pub index: u16,
+    /// Object sub-index.
     #[wire(bytes = 1)]
-    pub(crate) sub_index: u8,
+    pub sub_index: u8,
+    /// Index into EEPROM Strings section for the entry name.
     #[wire(bytes = 1)]
-    pub(crate) name_string_idx: u8,
+    pub name_string_idx: u8,
+    /// Data type of the object.
     // See page 103 of ETG2000
     #[wire(bytes = 1)]
-    pub(crate) data_type: PrimitiveDataType,
+    pub data_type: PrimitiveDataType,
+    /// Length of the object in bits.
     #[wire(bytes = 1)]
-    pub(crate) data_length_bits: u8,
+    pub data_length_bits: u8,
+    /// Reserved.
     #[wire(bytes = 2)]
-    pub(crate) flags: u16,
+    pub flags: u16,
 }
 
 impl core::fmt::Debug for PdoEntry {
@@ -714,6 +819,7 @@ impl defmt::Format for MailboxProtocols {
     }
 }
 
+/// Mailbox configuration used in the `PRE-OP`, `SAFE-OP` and `OP` states.
 #[derive(Copy, Clone, Default, PartialEq, ethercrab_wire::EtherCrabWireRead)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bytes = 10)]
@@ -736,12 +842,32 @@ pub struct DefaultMailbox {
 }
 
 impl DefaultMailbox {
+    /// Whether the device has a mailbox.
     pub fn has_mailbox(&self) -> bool {
         !self.supported_protocols.is_empty() && self.slave_receive_size > 0
             || self.slave_send_size > 0
     }
 }
 
+/// Mailbox configuration used in the `BOOT` state.
+#[derive(Debug, Copy, Clone, Default, PartialEq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 8)]
+pub struct BootstrapMailbox {
+    /// Master to slave receive mailbox address offset.
+    #[wire(bytes = 2)]
+    pub slave_receive_offset: u16,
+    /// Master to slave receive mailbox size.
+    #[wire(bytes = 2)]
+    pub slave_receive_size: u16,
+    /// Slave to master send mailbox address offset.
+    #[wire(bytes = 2)]
+    pub slave_send_offset: u16,
+    /// Slave to master send mailbox size.
+    #[wire(bytes = 2)]
+    pub slave_send_size: u16,
+}
+
 impl core::fmt::Debug for DefaultMailbox {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("MailboxConfig")
@@ -798,6 +924,21 @@ mod tests {
         assert_eq!(packed, [0x00, 0x01, 0x34, 0x12, 0x00, 0x00]);
     }
 
+    #[test]
+    fn sii_request_read_pack_extended() {
+        let packed = SiiRequest::read(0x0001_2345).pack();
+
+        assert_eq!(packed, [0x00, 0x01, 0x45, 0x23, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn sii_request_write_pack() {
+        let packed = SiiRequest::write(0x1234).pack();
+
+        // Write enable and write command in the same request
+        assert_eq!(packed, [0x01, 0x02, 0x34, 0x12, 0x00, 0x00]);
+    }
+
     #[test]
     fn sii_control_unpack() {
         let ctl = SiiControl {
@@ -840,7 +981,7 @@ mod tests {
             coe_details: CoeDetails::empty(),
             foe_enabled: false,
             eoe_enabled: false,
-            flags: Flags::empty(),
+            flags: GeneralFlags::empty(),
             ebus_current: -2000,
             ports: PortStatuses([
                 PortStatus::Ebus,
@@ -0,0 +1,183 @@
+//! Ethernet over EtherCAT (EoE).
+//!
+//! Defined in ETG1000.6 Section 5.7 EoE.
+
+use crate::mailbox::{MailboxHeaderBase, MailboxType, Priority};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite};
+
+/// Ethernet frame fragments other than the last must be a multiple of this many bytes long.
+pub const FRAGMENT_BLOCK_LEN: usize = 32;
+
+/// The longest Ethernet frame that can be sent or received, limited by the 6 bit frame size field
+/// in the first fragment header.
+pub const MAX_FRAME_LEN: usize = 0x3f * FRAGMENT_BLOCK_LEN;
+
+/// EoE frame type.
+///
+/// Defined in ETG1000.6 Section 5.7.1 EoE Header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bits = 4)]
+#[repr(u8)]
+pub enum EoeFrameType {
+    /// An Ethernet frame fragment.
+    FragmentData = 0x00,
+    TimestampResponse = 0x01,
+    SetIpRequest = 0x02,
+    SetIpResponse = 0x03,
+    SetFilterRequest = 0x04,
+    SetFilterResponse = 0x05,
+    GetIpRequest = 0x06,
+    GetIpResponse = 0x07,
+    GetFilterRequest = 0x08,
+    GetFilterResponse = 0x09,
+    #[wire(catch_all)]
+    Other(u8),
+}
+
+/// The second half of the EoE header, describing the position of a fragment in an Ethernet frame.
+///
+/// The fields of this type are not byte aligned, so it is packed by hand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FragmentInfo {
+    /// Fragment number, starting at 0 for the first fragment of a frame.
+    pub fragment_number: u8,
+    /// For the first fragment, the length of the whole frame in 32 byte blocks, rounded up. For
+    /// other fragments, the offset of this fragment in the frame in 32 byte blocks.
+    pub offset: u8,
+    /// Identifies the frame this fragment belongs to.
+    pub frame_number: u8,
+}
+
+impl EtherCrabWireSized for FragmentInfo {
+    const PACKED_LEN: usize = 2;
+
+    type Buffer = [u8; 2];
+
+    fn buffer() -> Self::Buffer {
+        [0u8; 2]
+    }
+}
+
+impl EtherCrabWireRead for FragmentInfo {
+    fn unpack_from_slice(buf: &[u8]) -> Result<Self, ethercrab_wire::WireError> {
+        let raw = u16::unpack_from_slice(buf)?;
+
+        Ok(Self {
+            fragment_number: (raw & 0x3f) as u8,
+            offset: ((raw >> 6) & 0x3f) as u8,
+            frame_number: (raw >> 12) as u8,
+        })
+    }
+}
+
+impl EtherCrabWireWrite for FragmentInfo {
+    fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
+        let raw = u16::from(self.fragment_number & 0x3f)
+            | u16::from(self.offset & 0x3f) << 6
+            | u16::from(self.frame_number & 0x0f) << 12;
+
+        raw.pack_to_slice_unchecked(buf)
+    }
+
+    fn packed_len(&self) -> usize {
+        Self::PACKED_LEN
+    }
+}
+
+/// Mailbox and EoE headers common to all EoE requests and responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 10)]
+pub struct EoeHeader {
+    #[wire(bytes = 6)]
+    pub header: MailboxHeaderBase,
+    #[wire(bits = 4)]
+    pub frame_type: EoeFrameType,
+    /// Switch port. Always `0` when sent by EtherCrab.
+    #[wire(bits = 4)]
+    pub port: u8,
+    /// Set if this is the last fragment of a frame.
+    #[wire(bits = 1)]
+    pub last_fragment: bool,
+    #[wire(bits = 1)]
+    pub time_appended: bool,
+    #[wire(bits = 1, post_skip = 5)]
+    pub time_request: bool,
+    #[wire(bytes = 2)]
+    pub fragment: FragmentInfo,
+}
+
+impl EoeHeader {
+    /// Create the header for a fragment of an Ethernet frame, followed by `data_len` bytes of frame
+    /// data.
+    pub fn fragment(
+        counter: u8,
+        fragment: FragmentInfo,
+        last_fragment: bool,
+        data_len: u16,
+    ) -> Self {
+        Self {
+            header: MailboxHeaderBase {
+                // 4 byte EoE header plus data
+                length: 0x04 + data_len,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Eoe,
+                counter,
+            },
+            frame_type: EoeFrameType::FragmentData,
+            port: 0,
+            last_fragment,
+            time_appended: false,
+            time_request: false,
+            fragment,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireWriteSized;
+
+    #[test]
+    fn fragment_info_round_trip() {
+        let info = FragmentInfo {
+            fragment_number: 2,
+            offset: 0x2a,
+            frame_number: 0x0b,
+        };
+
+        let mut buf = [0u8; 2];
+
+        let packed = info.pack_to_slice(&mut buf).unwrap();
+
+        assert_eq!(packed, 0b1011_1010_1000_0010u16.to_le_bytes());
+        assert_eq!(FragmentInfo::unpack_from_slice(packed), Ok(info));
+    }
+
+    #[test]
+    fn encode_last_fragment() {
+        let header = EoeHeader::fragment(
+            1,
+            FragmentInfo {
+                fragment_number: 1,
+                offset: 3,
+                frame_number: 5,
+            },
+            true,
+            20,
+        );
+
+        assert_eq!(
+            header.pack(),
+            [
+                // Mailbox header
+                0x18, 0x00, 0x00, 0x00, 0x00, 0x12, //
+                // Frame type, port, last fragment
+                0x00, 0x01, //
+                // Fragment info
+                0xc1, 0x50
+            ]
+        );
+    }
+}
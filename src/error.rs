@@ -1,9 +1,12 @@
 //! EtherCrab error types.
 
-use crate::{command::Command, fmt, AlStatusCode, SlaveState};
+use crate::{command::Command, fmt, AlStatusCode, BusMismatch, Idn, SlaveState};
 use core::{cell::BorrowError, num::TryFromIntError, str::Utf8Error};
 
 pub use crate::coe::abort_code::CoeAbortCode;
+pub use crate::foe::FoeErrorCode;
+pub use crate::mailbox::MailboxErrorCode;
+pub use crate::soe::{SoeErrorClass, SoeErrorCode};
 
 /// An EtherCrab error.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -36,6 +39,18 @@ pub enum Error {
     },
     /// A mailbox error was encountered.
     Mailbox(MailboxError),
+    /// An FoE (File access over EtherCAT) error was encountered.
+    Foe(FoeError),
+    /// An EoE (Ethernet over EtherCAT) error was encountered.
+    Eoe(EoeError),
+    /// An SoE (Servo drive profile over EtherCAT) error was encountered.
+    Soe(SoeError),
+    /// An AoE (ADS over EtherCAT) error was encountered.
+    Aoe(AoeError),
+    /// A VoE (vendor specific protocol over EtherCAT) error was encountered.
+    Voe(VoeError),
+    /// A mailbox gateway request could not be forwarded.
+    Gateway(GatewayError),
     /// Failed to send a frame over the network interace.
     SendFrame,
     /// Failed to receive a frame properly.
@@ -61,6 +76,31 @@ pub enum Error {
         /// Actual PDI length.
         desired_length: usize,
     },
+    /// A group's logical address range overlaps another group's.
+    PdiOverlap {
+        /// The logical start address of the group's PDI.
+        start_address: u32,
+
+        /// The length of the group's PDI.
+        length: usize,
+    },
+    /// A single process data PDU returned an unexpected working counter.
+    ///
+    /// Only returned if [`ClientConfig::verify_pdi_wkc`](crate::ClientConfig::verify_pdi_wkc) is
+    /// enabled.
+    PdiWorkingCounter {
+        /// The logical start address of the PDU.
+        start_address: u32,
+
+        /// The length of the PDU's data in bytes.
+        length: usize,
+
+        /// The expected working counter value.
+        expected: u16,
+
+        /// The actual value received.
+        received: u16,
+    },
     /// An item in a list could not be found.
     NotFound {
         /// Item kind.
@@ -75,8 +115,35 @@ pub enum Error {
     Topology,
     /// An error was read back from one or more slaves when attempting to transition to a new state.
     StateTransition,
+    /// One or more SubDevices didn't reach a requested state within the
+    /// [`Timeouts`](crate::Timeouts) for that transition, e.g.
+    /// [`pre_op_to_safe_op`](crate::Timeouts::pre_op_to_safe_op).
+    StateTransitionTimeout {
+        /// The requested state.
+        desired: SlaveState,
+
+        /// The SubDevices that didn't reach the requested state, and the AL status code each one
+        /// reported.
+        subdevices: TransitionFailures,
+    },
     /// An unknown slave device was encountered during device discovery/initialisation.
     UnknownSlave,
+    /// The SubDevices discovered by [`Client::init`](crate::Client::init) don't match
+    /// [`ClientConfig::bus_configuration`](crate::ClientConfig::bus_configuration).
+    ///
+    /// Every mismatch is logged as an error.
+    BusConfiguration {
+        /// The first mismatch, in network order.
+        mismatch: BusMismatch,
+
+        /// The total number of mismatches.
+        count: u16,
+    },
+    /// A [`DynSlaveGroup`](crate::slave_group::DynSlaveGroup) operation isn't possible in the
+    /// group's current state, e.g. exchanging process data in PRE-OP before the PDI is configured.
+    ///
+    /// Contains the group's current state.
+    GroupState(SlaveState),
     /// An invalid state was encountered.
     InvalidState {
         /// The desired state.
@@ -122,6 +189,12 @@ impl core::fmt::Display for Error {
                 string_length, max_length
             ),
             Error::Mailbox(e) => write!(f, "mailbox: {e}"),
+            Error::Foe(e) => write!(f, "FoE: {e}"),
+            Error::Eoe(e) => write!(f, "EoE: {e}"),
+            Error::Soe(e) => write!(f, "SoE: {e}"),
+            Error::Aoe(e) => write!(f, "AoE: {e}"),
+            Error::Voe(e) => write!(f, "VoE: {e}"),
+            Error::Gateway(e) => write!(f, "mailbox gateway: {e}"),
             Error::SendFrame => f.write_str("failed to send EtherCAT frame"),
             Error::ReceiveFrame => f.write_str("failed to receive an EtherCAT frame"),
             Error::PartialSend { len, sent } => {
@@ -136,13 +209,58 @@ impl core::fmt::Display for Error {
                 "Process Data Image is too long ({} bytes), max length is {}",
                 desired_length, max_length
             ),
+            Error::PdiOverlap {
+                start_address,
+                length,
+            } => write!(
+                f,
+                "Process Data Image at {:#010x} ({} bytes) overlaps another group",
+                start_address, length
+            ),
+            Error::PdiWorkingCounter {
+                start_address,
+                length,
+                expected,
+                received,
+            } => write!(
+                f,
+                "Process Data Image PDU at {:#010x} ({} bytes) working counter expected {}, got {}",
+                start_address, length, expected, received
+            ),
             Error::NotFound { item, index } => {
                 write!(f, "item kind {:?} not found (index: {:?})", item, index)
             }
             Error::Internal => f.write_str("internal error"),
             Error::Topology => f.write_str("topology"),
             Error::StateTransition => f.write_str("a slave failed to transition to a new state"),
+            Error::StateTransitionTimeout {
+                desired,
+                subdevices,
+            } => {
+                write!(
+                    f,
+                    "{} SubDevice(s) did not reach state {}",
+                    subdevices.count(),
+                    desired
+                )?;
+
+                for (i, (configured_address, code)) in subdevices.subdevices().iter().enumerate() {
+                    let sep = if i == 0 { ": " } else { ", " };
+
+                    write!(f, "{}{:#06x} ({})", sep, configured_address, code)?;
+                }
+
+                Ok(())
+            }
             Error::UnknownSlave => f.write_str("unknown slave device"),
+            Error::BusConfiguration { mismatch, count } => write!(
+                f,
+                "bus configuration has {} mismatch(es), first: {}",
+                count, mismatch
+            ),
+            Error::GroupState(state) => {
+                write!(f, "operation not possible in group state {}", state)
+            }
             Error::InvalidState {
                 expected,
                 actual,
@@ -184,6 +302,14 @@ pub enum Item {
     FmmuEx,
     /// A user-defined slave group.
     Group,
+    /// An object in a SubDevice's CoE object dictionary.
+    Object,
+    /// A SubDevice mailbox too long to be accessed in multiple PDUs.
+    Mailbox,
+    /// A DC sync mode defined in a SubDevice's EEPROM.
+    DcSyncMode,
+    /// A region of a SubDevice's process data.
+    PdiRegion,
 }
 
 /// Low-level PDU (Process Data Unit) error.
@@ -233,6 +359,49 @@ impl core::fmt::Display for PduError {
     }
 }
 
+/// The SubDevices that didn't reach a requested state, returned in
+/// [`Error::StateTransitionTimeout`].
+///
+/// The configured address and AL status code of up to the first four SubDevices are stored, so the
+/// error stays small enough to return everywhere without allocating.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TransitionFailures {
+    subdevices: [(u16, AlStatusCode); 4],
+    count: u16,
+}
+
+impl TransitionFailures {
+    pub(crate) const fn new() -> Self {
+        Self {
+            subdevices: [(0, AlStatusCode::NoError); 4],
+            count: 0,
+        }
+    }
+
+    /// Record a SubDevice that didn't reach the requested state.
+    pub(crate) fn push(&mut self, configured_address: u16, code: AlStatusCode) {
+        if let Some(slot) = self.subdevices.get_mut(usize::from(self.count)) {
+            *slot = (configured_address, code);
+        }
+
+        self.count = self.count.saturating_add(1);
+    }
+
+    /// The configured address and AL status code of the first SubDevices that didn't reach the
+    /// requested state, in network order.
+    pub fn subdevices(&self) -> &[(u16, AlStatusCode)] {
+        &self.subdevices[..self.subdevices.len().min(usize::from(self.count))]
+    }
+
+    /// The total number of SubDevices that didn't reach the requested state, which may be more
+    /// than are returned by [`subdevices`](TransitionFailures::subdevices).
+    pub fn count(&self) -> usize {
+        usize::from(self.count)
+    }
+}
+
 /// CoE mailbox error.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -240,12 +409,31 @@ impl core::fmt::Display for PduError {
 pub enum DistributedClockError {
     /// No DC System Time reference SubDevice was found.
     NoReference,
+    /// The SubDevice chosen with [`DcReference::Address`](crate::DcReference::Address) doesn't
+    /// exist or doesn't support DC.
+    InvalidReference(u16),
+    /// The DC system time difference of one or more SubDevices may be larger than the sync window
+    /// passed to [`Client::check_sync_window`](crate::Client::check_sync_window).
+    OutOfSyncWindow {
+        /// Upper bound of the system time difference of all SubDevices, in nanoseconds.
+        deviation: u32,
+    },
 }
 
 impl core::fmt::Display for DistributedClockError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::NoReference => f.write_str("No DC reference SubDevice found"),
+            Self::InvalidReference(address) => write!(
+                f,
+                "SubDevice {:#06x} can't be used as the DC reference",
+                address
+            ),
+            Self::OutOfSyncWindow { deviation } => write!(
+                f,
+                "DC system time deviation of up to {} ns is outside the sync window",
+                deviation
+            ),
         }
     }
 }
@@ -273,6 +461,11 @@ pub enum MailboxError {
     },
     /// A slave has no mailbox but requires one for a given action.
     NoMailbox,
+    /// The SubDevice sent a mailbox error reply as it could not process a request.
+    ErrorReply {
+        /// Error detail code.
+        code: MailboxErrorCode,
+    },
     /// The response to a mailbox action is invalid.
     SdoResponseInvalid {
         /// The address used in the operation.
@@ -280,6 +473,13 @@ pub enum MailboxError {
         /// The subindex used in the operation.
         sub_index: u8,
     },
+    /// The value read back after an SDO write does not match the value that was written.
+    VerifyFailed {
+        /// The address used in the operation.
+        address: u16,
+        /// The subindex used in the operation.
+        sub_index: u8,
+    },
 }
 
 impl core::fmt::Display for MailboxError {
@@ -296,11 +496,200 @@ impl core::fmt::Display for MailboxError {
                 address, sub_index
             ),
             MailboxError::NoMailbox => f.write_str("device has no mailbox"),
+            MailboxError::ErrorReply { code } => write!(f, "mailbox error reply: {}", code),
             MailboxError::SdoResponseInvalid { address, sub_index } => write!(
                 f,
                 "{:#06x}:{} invalid response from device",
                 address, sub_index
             ),
+            MailboxError::VerifyFailed { address, sub_index } => write!(
+                f,
+                "{:#06x}:{} value read back does not match value written",
+                address, sub_index
+            ),
+        }
+    }
+}
+
+/// FoE (File access over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum FoeError {
+    /// The SubDevice does not support FoE.
+    NotSupported,
+    /// The SubDevice aborted the transfer with an error code.
+    Aborted {
+        /// Error code sent by the SubDevice.
+        code: FoeErrorCode,
+    },
+    /// A data or acknowledge packet was received out of order.
+    PacketNumber {
+        /// The expected packet number.
+        expected: u32,
+        /// The packet number sent by the SubDevice.
+        received: u32,
+    },
+    /// The SubDevice sent a response that is not valid at this point in the transfer.
+    InvalidResponse,
+    /// The file or a packet is too large to fit in the given buffer.
+    TooLong,
+    /// The SubDevice was still busy after all retries.
+    Busy,
+    /// Another FoE transfer with the SubDevice is already in progress.
+    TransferInProgress,
+}
+
+impl core::fmt::Display for FoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FoeError::NotSupported => f.write_str("device does not support FoE"),
+            FoeError::Aborted { code } => write!(f, "transfer aborted: {}", code),
+            FoeError::PacketNumber { expected, received } => write!(
+                f,
+                "received packet number {}, expected {}",
+                received, expected
+            ),
+            FoeError::InvalidResponse => f.write_str("invalid response from device"),
+            FoeError::TooLong => f.write_str("file is too long to fit in buffer"),
+            FoeError::Busy => f.write_str("device is busy"),
+            FoeError::TransferInProgress => f.write_str("another transfer is in progress"),
+        }
+    }
+}
+
+/// EoE (Ethernet over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum EoeError {
+    /// The SubDevice does not support EoE.
+    NotSupported,
+    /// An Ethernet frame is too long to be sent over EoE, or to fit in the given buffer.
+    FrameTooLong,
+    /// A fragment was received out of order, so the frame could not be reassembled.
+    InvalidFragment,
+}
+
+impl core::fmt::Display for EoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EoeError::NotSupported => f.write_str("device does not support EoE"),
+            EoeError::FrameTooLong => f.write_str("frame is too long"),
+            EoeError::InvalidFragment => f.write_str("fragment received out of order"),
+        }
+    }
+}
+
+/// SoE (Servo drive profile over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SoeError {
+    /// The SubDevice does not support SoE.
+    NotSupported,
+    /// The SubDevice rejected the request with an error code.
+    Aborted {
+        /// Error code sent by the SubDevice.
+        code: SoeErrorCode,
+    },
+    /// The SubDevice sent a response for a different request.
+    InvalidResponse,
+    /// The IDN data is too large to fit in the given buffer.
+    TooLong,
+    /// A procedure command finished with an error.
+    CommandFailed {
+        /// The procedure command's IDN.
+        idn: Idn,
+    },
+}
+
+impl core::fmt::Display for SoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SoeError::NotSupported => f.write_str("device does not support SoE"),
+            SoeError::Aborted { code } => write!(f, "request aborted: {}", code),
+            SoeError::InvalidResponse => f.write_str("invalid response from device"),
+            SoeError::TooLong => f.write_str("data is too long to fit in buffer"),
+            SoeError::CommandFailed { idn } => write!(f, "procedure command {} failed", idn),
+        }
+    }
+}
+
+/// AoE (ADS over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum AoeError {
+    /// The SubDevice does not support AoE.
+    NotSupported,
+    /// The request failed with an AMS or ADS error code.
+    Ads {
+        /// Error code sent by the SubDevice or ADS device.
+        code: u32,
+    },
+    /// The SubDevice sent a response that could not be decoded.
+    InvalidResponse,
+    /// The request doesn't fit in the SubDevice's mailbox, or the response doesn't fit in the
+    /// given buffer.
+    TooLong,
+}
+
+impl core::fmt::Display for AoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AoeError::NotSupported => f.write_str("device does not support AoE"),
+            AoeError::Ads { code } => write!(f, "ADS error {:#010x}", code),
+            AoeError::InvalidResponse => f.write_str("invalid response from device"),
+            AoeError::TooLong => f.write_str("data is too long"),
+        }
+    }
+}
+
+/// VoE (vendor specific protocol over EtherCAT) error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum VoeError {
+    /// The SubDevice does not support VoE.
+    NotSupported,
+    /// The SubDevice sent a non-VoE response.
+    InvalidResponse,
+    /// The message doesn't fit in the SubDevice's mailbox, or the response doesn't fit in the given
+    /// buffer.
+    TooLong,
+}
+
+impl core::fmt::Display for VoeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            VoeError::NotSupported => f.write_str("device does not support VoE"),
+            VoeError::InvalidResponse => f.write_str("invalid response from device"),
+            VoeError::TooLong => f.write_str("data is too long"),
+        }
+    }
+}
+
+/// Mailbox gateway error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum GatewayError {
+    /// The request is too short, or its mailbox header is invalid.
+    InvalidRequest,
+    /// The request is not a mailbox request.
+    UnsupportedProtocol,
+    /// The request doesn't fit in the SubDevice's mailbox, or the response doesn't fit in the
+    /// response buffer.
+    TooLong,
+}
+
+impl core::fmt::Display for GatewayError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GatewayError::InvalidRequest => f.write_str("invalid request"),
+            GatewayError::UnsupportedProtocol => f.write_str("not a mailbox request"),
+            GatewayError::TooLong => f.write_str("message is too long"),
         }
     }
 }
@@ -320,6 +709,17 @@ pub enum EepromError {
     SectionUnderrun,
     /// An attempt to clear errors on the device failed.
     ClearErrors,
+    /// The device did not acknowledge an EEPROM write, e.g. because the EEPROM is write protected.
+    Write,
+    /// Data read back from the EEPROM after a write did not match what was written, or the device
+    /// failed to load it.
+    Verify,
+    /// An EEPROM image is the wrong size for the device, or its configuration area checksum is
+    /// invalid.
+    InvalidImage,
+    /// The checksum in the EEPROM configuration area does not match its contents, so the
+    /// configuration area is corrupt.
+    Checksum,
 }
 
 impl core::fmt::Display for EepromError {
@@ -330,6 +730,10 @@ impl core::fmt::Display for EepromError {
             EepromError::NoCategory => f.write_str("category not found"),
             EepromError::SectionUnderrun => f.write_str("section too short to fill buffer"),
             EepromError::ClearErrors => f.write_str("clear device errors failed"),
+            EepromError::Write => f.write_str("write not acknowledged"),
+            EepromError::Verify => f.write_str("read back data does not match"),
+            EepromError::InvalidImage => f.write_str("invalid EEPROM image"),
+            EepromError::Checksum => f.write_str("configuration area checksum mismatch"),
         }
     }
 }
@@ -410,6 +814,36 @@ impl From<PduError> for Error {
     }
 }
 
+impl From<FoeError> for Error {
+    fn from(e: FoeError) -> Self {
+        Self::Foe(e)
+    }
+}
+
+impl From<EoeError> for Error {
+    fn from(e: EoeError) -> Self {
+        Self::Eoe(e)
+    }
+}
+
+impl From<SoeError> for Error {
+    fn from(e: SoeError) -> Self {
+        Self::Soe(e)
+    }
+}
+
+impl From<AoeError> for Error {
+    fn from(e: AoeError) -> Self {
+        Self::Aoe(e)
+    }
+}
+
+impl From<VoeError> for Error {
+    fn from(e: VoeError) -> Self {
+        Self::Voe(e)
+    }
+}
+
 impl From<DistributedClockError> for Error {
     fn from(e: DistributedClockError) -> Self {
         Self::DistributedClock(e)
@@ -447,3 +881,36 @@ impl From<ethercrab_wire::WireError> for Error {
         Self::Wire(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_failures() {
+        let mut subdevices = TransitionFailures::new();
+
+        for configured_address in 0x1000..0x1006 {
+            subdevices.push(configured_address, AlStatusCode::SyncManagerWatchdog);
+        }
+
+        assert_eq!(subdevices.count(), 6);
+        assert_eq!(subdevices.subdevices().len(), 4);
+        assert_eq!(subdevices.subdevices()[3].0, 0x1003);
+
+        let mut one = TransitionFailures::new();
+
+        one.push(0x1001, AlStatusCode::InvalidMailboxConfiguration);
+
+        assert_eq!(
+            format!(
+                "{}",
+                Error::StateTransitionTimeout {
+                    desired: SlaveState::PreOp,
+                    subdevices: one
+                }
+            ),
+            "1 SubDevice(s) did not reach state Pre-Operational: 0x1001 (0x0015: Invalid mailbox configuration)"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! File access over EtherCAT (FoE).
+//!
+//! Defined in ETG1000.6 Section 5.8 FoE.
+
+use crate::mailbox::{MailboxHeaderBase, MailboxType, Priority};
+
+/// FoE operation code.
+///
+/// Defined in ETG1000.6 Section 5.8 FoE.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 1)]
+#[repr(u8)]
+pub enum FoeOpCode {
+    ReadRequest = 0x01,
+    WriteRequest = 0x02,
+    Data = 0x03,
+    Ack = 0x04,
+    Err = 0x05,
+    Busy = 0x06,
+}
+
+/// FoE error code sent by a SubDevice when it aborts a transfer.
+///
+/// Defined in ETG1000.6 Section 5.8 FoE, Error Request.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u32)]
+pub enum FoeErrorCode {
+    /// Not defined, see error text
+    NotDefined = 0x8000,
+    /// File not found
+    NotFound = 0x8001,
+    /// Access denied, e.g. an incorrect password
+    AccessDenied = 0x8002,
+    /// Disk full
+    DiskFull = 0x8003,
+    /// Illegal operation
+    Illegal = 0x8004,
+    /// Packet number wrong
+    PacketNumberWrong = 0x8005,
+    /// File already exists
+    AlreadyExists = 0x8006,
+    /// No user
+    NoUser = 0x8007,
+    /// Bootstrap access only. The SubDevice must be in the `BOOT` state.
+    BootstrapOnly = 0x8008,
+    /// Not in bootstrap. The file cannot be accessed in the `BOOT` state.
+    NotBootstrap = 0x8009,
+    /// No rights
+    NoRights = 0x800a,
+    /// Program error
+    ProgramError = 0x800b,
+
+    /// Unknown error code.
+    #[wire(catch_all)]
+    Unknown(u32),
+}
+
+impl core::fmt::Display for FoeErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let num = u32::from(*self);
+
+        f.write_fmt(format_args!("{:#06x}", num))?;
+        f.write_str(": ")?;
+
+        match self {
+            Self::NotDefined => f.write_str("Not defined"),
+            Self::NotFound => f.write_str("File not found"),
+            Self::AccessDenied => f.write_str("Access denied"),
+            Self::DiskFull => f.write_str("Disk full"),
+            Self::Illegal => f.write_str("Illegal operation"),
+            Self::PacketNumberWrong => f.write_str("Packet number wrong"),
+            Self::AlreadyExists => f.write_str("File already exists"),
+            Self::NoUser => f.write_str("No user"),
+            Self::BootstrapOnly => f.write_str("Bootstrap access only"),
+            Self::NotBootstrap => f.write_str("Not in bootstrap"),
+            Self::NoRights => f.write_str("No rights"),
+            Self::ProgramError => f.write_str("Program error"),
+            Self::Unknown(_) => f.write_str("Unknown code"),
+        }
+    }
+}
+
+/// Mailbox and FoE headers common to all FoE requests and responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 12)]
+pub struct FoeHeader {
+    #[wire(bytes = 6)]
+    pub header: MailboxHeaderBase,
+    #[wire(bytes = 1, post_skip_bytes = 1)]
+    pub op_code: FoeOpCode,
+    /// The password for read and write requests, the packet number for data and acknowledge
+    /// packets, or the error code for an error packet.
+    ///
+    /// For busy packets, the lower 16 bits hold the progress done so far, and the upper 16 bits the
+    /// total amount of work to do.
+    #[wire(bytes = 4)]
+    pub value: u32,
+}
+
+impl FoeHeader {
+    /// Create a new FoE header, followed by `data_len` bytes of file name, file data or error text.
+    pub fn new(counter: u8, op_code: FoeOpCode, value: u32, data_len: u16) -> Self {
+        Self {
+            header: MailboxHeaderBase {
+                // 6 byte FoE header plus data
+                length: 0x06 + data_len,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Foe,
+                counter,
+            },
+            op_code,
+            value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWrite};
+
+    #[test]
+    fn encode_read_request() {
+        let name = b"log.txt";
+
+        let mut buf = [0u8; 32];
+
+        let packed = (
+            FoeHeader::new(2, FoeOpCode::ReadRequest, 0x1234_5678, name.len() as u16),
+            name.as_slice(),
+        )
+            .pack_to_slice(&mut buf)
+            .unwrap();
+
+        assert_eq!(
+            packed,
+            &[
+                // Mailbox header
+                0x0d, 0x00, 0x00, 0x00, 0x00, 0x24, //
+                // FoE header
+                0x01, 0x00, 0x78, 0x56, 0x34, 0x12, //
+                // File name
+                b'l', b'o', b'g', b'.', b't', b'x', b't'
+            ]
+        );
+    }
+
+    #[test]
+    fn error_codes() {
+        assert_eq!(FoeErrorCode::from(0x8008), FoeErrorCode::BootstrapOnly);
+
+        let unknown = FoeErrorCode::from(0x1234);
+
+        assert_eq!(unknown, FoeErrorCode::Unknown(0x1234));
+        assert_eq!(unknown.to_string(), "0x1234: Unknown code");
+    }
+
+    #[test]
+    fn decode_data() {
+        let raw = [
+            0x0a, 0x00, 0x00, 0x00, 0x00, 0x34, 0x03, 0x00, 0x07, 0x00, 0x00, 0x00, 0xaa, 0xbb,
+            0xcc, 0xdd,
+        ];
+
+        assert_eq!(
+            FoeHeader::unpack_from_slice(&raw),
+            Ok(FoeHeader {
+                header: MailboxHeaderBase {
+                    length: 10,
+                    priority: Priority::Lowest,
+                    mailbox_type: MailboxType::Foe,
+                    counter: 3
+                },
+                op_code: FoeOpCode::Data,
+                value: 7
+            })
+        );
+    }
+}
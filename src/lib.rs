@@ -167,7 +167,9 @@ pub(crate) mod fmt;
 
 mod al_control;
 mod al_status_code;
+mod aoe;
 mod base_data_types;
+mod bus_configuration;
 mod client;
 mod client_config;
 mod coe;
@@ -176,19 +178,24 @@ mod dc;
 mod dl_status;
 pub mod ds402;
 mod eeprom;
+mod eoe;
 pub mod error;
 mod fmmu;
+mod foe;
 mod generate;
 mod mailbox;
 mod pdi;
 mod pdu_loop;
 mod register;
+pub mod sii;
 mod slave;
 pub mod slave_group;
 mod slave_state;
+mod soe;
 mod sync_manager_channel;
 mod timer_factory;
 mod vendors;
+mod voe;
 
 #[cfg(feature = "__internals")]
 pub mod internals;
@@ -199,20 +206,43 @@ pub mod std;
 use smoltcp::wire::{EthernetAddress, EthernetProtocol};
 
 pub use al_status_code::AlStatusCode;
+pub use aoe::{AmsAddress, AmsNetId};
+pub use bus_configuration::{BusConfiguration, BusMismatch, ExpectedSubDevice};
 pub use client::Client;
-pub use client_config::{ClientConfig, RetryBehaviour};
-pub use coe::SubIndex;
+pub use client_config::{
+    ClientConfig, DcReference, DcStaticSync, DcStaticSyncCommand, DeviceIdentification, FailSafe,
+    FailSafeOutputs, PdiExchange, RetryBehaviour,
+};
+pub use coe::{
+    diagnosis::{DiagnosisMessage, DiagnosisSeverity},
+    emergency::CoeEmergency,
+    sdo_info::{EntryDescription, ObjectAccess, ObjectCode, ObjectDescription, OdListType},
+    sm_parameters::{SyncManagerParameters, SyncMode},
+    PdoMapping, StartupSdo, SubIndex,
+};
 pub use command::{Command, Reads, WrappedRead, WrappedWrite, Writes};
+pub use ethercrab_derive::EtherCatPdi;
+pub use ethercrab_wire;
 pub use ethercrab_wire::{
     EtherCrabWireRead, EtherCrabWireReadSized, EtherCrabWireReadWrite, EtherCrabWireSized,
     EtherCrabWireWrite, EtherCrabWireWriteSized,
 };
 pub use pdu_loop::{PduLoop, PduRx, PduStorage, PduTx, SendableFrame};
 pub use register::{DcSupport, RegisterAddress};
-pub use slave::{DcSync, Slave, SlaveIdentity, SlavePdi, SlaveRef};
-pub use slave_group::{GroupId, GroupSlaveIterator, SlaveGroup, SlaveGroupHandle};
+pub use slave::{
+    DcSync, DcSyncMode, DcSyncTiming, EoeDevice, EoeSwitch, EtherCatPdi, FoeReader, FoeWriter,
+    Latch, LatchEdge, LatchMode, LatchStatus, ObjectDictionary, Oversampled, OversampledChannel,
+    PdiAlignment, PdiEntry, PdiEntryMut, PdiEntryRef, PdoDirection, RecoveryEvent, SampleOrder,
+    Slave, SlaveIdentity, SlavePdi, SlaveRef, SyncManagerMapping, SyncManagerMappings,
+};
+pub use slave_group::{
+    DynSlaveGroup, GroupId, GroupSlaveIterator, HookError, HookStage, SlaveGroup, SlaveGroupHandle,
+    TransitionHooks,
+};
 pub use slave_state::SlaveState;
+pub use soe::{Idn, IdnList, SoeElements};
 pub use timer_factory::Timeouts;
+pub use voe::VoeHeader;
 
 const LEN_MASK: u16 = 0b0000_0111_1111_1111;
 const ETHERCAT_ETHERTYPE_RAW: u16 = 0x88a4;
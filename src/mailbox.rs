@@ -67,6 +67,93 @@ pub struct MailboxHeader {
     pub service: CoeService,
 }
 
+/// Mailbox header without any protocol-specific fields, used by protocols other than CoE.
+///
+/// Defined in ETG1000.6 5.4 Table 29 – Mailbox.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 6)]
+pub struct MailboxHeaderBase {
+    /// Mailbox data payload length.
+    #[wire(bytes = 2, post_skip_bytes = 2)]
+    pub length: u16,
+    #[wire(pre_skip = 6, bits = 2)]
+    pub priority: Priority,
+    #[wire(bits = 4)]
+    pub mailbox_type: MailboxType,
+    /// Mailbox counter from 1 to 7 inclusive.
+    #[wire(bits = 3, post_skip = 1)]
+    pub counter: u8,
+}
+
+/// Detail code of a mailbox error reply, sent by a SubDevice when it cannot process a mailbox
+/// request.
+///
+/// Defined in ETG1000.6 5.5 Table 30 – Error Reply Service Data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u16)]
+pub enum MailboxErrorCode {
+    /// Syntax of the 6 octet mailbox header is wrong
+    Syntax = 0x0001,
+    /// The mailbox protocol is not supported
+    UnsupportedProtocol = 0x0002,
+    /// Channel field contains a wrong value
+    InvalidChannel = 0x0003,
+    /// The service in the mailbox protocol is not supported
+    ServiceNotSupported = 0x0004,
+    /// The mailbox protocol header is wrong, excluding the 6 octet mailbox header
+    InvalidHeader = 0x0005,
+    /// Length of received mailbox data is too short
+    SizeTooShort = 0x0006,
+    /// Mailbox protocol cannot be processed because of limited resources
+    NoMoreMemory = 0x0007,
+    /// The length of the data is inconsistent
+    InvalidSize = 0x0008,
+
+    /// Unknown error code.
+    #[wire(catch_all)]
+    Unknown(u16),
+}
+
+impl core::fmt::Display for MailboxErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let num = u16::from(*self);
+
+        f.write_fmt(format_args!("{:#06x}", num))?;
+        f.write_str(": ")?;
+
+        match self {
+            Self::Syntax => f.write_str("Syntax of mailbox header is wrong"),
+            Self::UnsupportedProtocol => f.write_str("Mailbox protocol not supported"),
+            Self::InvalidChannel => f.write_str("Channel field contains wrong value"),
+            Self::ServiceNotSupported => f.write_str("Service not supported"),
+            Self::InvalidHeader => f.write_str("Mailbox protocol header is wrong"),
+            Self::SizeTooShort => f.write_str("Mailbox data too short"),
+            Self::NoMoreMemory => f.write_str("No more memory"),
+            Self::InvalidSize => f.write_str("Data length is inconsistent"),
+            Self::Unknown(_) => f.write_str("Unknown code"),
+        }
+    }
+}
+
+/// A mailbox error reply, sent with the [`MailboxType::Err`] mailbox type.
+///
+/// Defined in ETG1000.6 5.5 Table 30 – Error Reply Service Data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 10)]
+pub struct MailboxErrorReply {
+    #[wire(bytes = 6)]
+    pub header: MailboxHeaderBase,
+    /// Always `0x01` (mailbox command).
+    #[wire(bytes = 2)]
+    pub service_type: u16,
+    #[wire(bytes = 2)]
+    pub detail: MailboxErrorCode,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,6 +256,21 @@ mod tests {
         assert_eq!(packed, expected);
     }
 
+    #[test]
+    fn encode_header_base() {
+        let expected = [0x0c, 0x00, 0x00, 0x00, 0x00, 0x54];
+
+        let packed = MailboxHeaderBase {
+            length: 12,
+            priority: Priority::Lowest,
+            counter: 5,
+            mailbox_type: MailboxType::Foe,
+        }
+        .pack();
+
+        assert_eq!(packed, expected);
+    }
+
     #[test]
     fn decode_header() {
         // From Wireshark capture "soem-slaveinfo-akd.pcapng", packet #296
@@ -187,4 +289,14 @@ mod tests {
 
         assert_eq!(parsed, expected);
     }
+
+    #[test]
+    fn decode_error_reply() {
+        let raw = [0x04, 0x00, 0x00, 0x00, 0x00, 0x30, 0x01, 0x00, 0x02, 0x00];
+
+        let parsed = MailboxErrorReply::unpack_from_slice(&raw).unwrap();
+
+        assert_eq!(parsed.header.mailbox_type, MailboxType::Err);
+        assert_eq!(parsed.detail, MailboxErrorCode::UnsupportedProtocol);
+    }
 }
@@ -52,6 +52,27 @@ impl PdiOffset {
     //     self.start_bit + bits % 8
     // }
 
+    /// Round this offset up to the next multiple of `bytes`.
+    pub fn align(self, bytes: u32) -> Self {
+        Self {
+            start_address: (self.start_address + bytes - 1) / bytes * bytes,
+        }
+    }
+
+    /// Find the first offset at or after this one where `len` bytes don't overlap any of the
+    /// `reserved` logical address ranges.
+    pub fn skip_reserved(self, len: u32, reserved: &[Range<u32>]) -> Self {
+        let mut start_address = self.start_address;
+
+        while let Some(range) = reserved.iter().find(|range| {
+            range.start < start_address.saturating_add(len) && start_address < range.end
+        }) {
+            start_address = range.end;
+        }
+
+        Self { start_address }
+    }
+
     /// Compute an index range between this offset (inclusive) and another (exclusive).
     pub fn up_to(self, other: Self) -> Range<usize> {
         self.start_address as usize..other.start_address as usize
@@ -124,6 +145,21 @@ mod tests {
         assert_eq!(input, PdiOffset { start_address: 2 }, "second increment");
     }
 
+    #[test]
+    fn skip_reserved() {
+        let reserved = [0x100..0x200, 0x300..0x380, 0x380..0x400];
+
+        let offset = |start_address| PdiOffset { start_address };
+
+        assert_eq!(offset(0).skip_reserved(0x100, &reserved), offset(0));
+        assert_eq!(offset(0x50).skip_reserved(0x100, &reserved), offset(0x200));
+        assert_eq!(offset(0x180).skip_reserved(0x10, &reserved), offset(0x200));
+        // Too long to fit in the gap, so skips past consecutive reserved ranges
+        assert_eq!(offset(0x200).skip_reserved(0x180, &reserved), offset(0x400));
+        assert_eq!(offset(0).skip_reserved(0x101, &reserved), offset(0x400));
+        assert_eq!(offset(0x200).skip_reserved(0x100, &reserved), offset(0x200));
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn fuzz_pdi_segment() {
@@ -0,0 +1,160 @@
+//! Scheduling of deferred acyclic frames around cyclic process data frames.
+
+use crate::{
+    fmt,
+    pdu_loop::{
+        frame_element::{
+            created_frame::CreatedFrame, received_frame::ReceivedFrame,
+            receiving_frame::ReceivingFrame, FrameBox, FrameState,
+        },
+        storage::PduStorageRef,
+    },
+};
+use core::sync::atomic::Ordering;
+
+/// The maximum number of deferred frames whose PDUs can be sent in a single cyclic frame.
+const MAX_MERGED: usize = 8;
+
+/// A cyclic exchange in flight. Deferred frames are not sent on their own until this is dropped.
+pub(crate) struct CyclicGuard<'sto> {
+    storage: PduStorageRef<'sto>,
+}
+
+impl<'sto> CyclicGuard<'sto> {
+    pub(in crate::pdu_loop) fn new(storage: PduStorageRef<'sto>) -> Self {
+        storage.cyclic.fetch_add(1, Ordering::AcqRel);
+
+        Self { storage }
+    }
+}
+
+impl Drop for CyclicGuard<'_> {
+    fn drop(&mut self) {
+        // The network is now idle, so let the TX task send any deferred frames.
+        if self.storage.cyclic.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.storage.tx_waker.wake();
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Merged {
+    /// Index of the deferred frame the PDUs were copied from.
+    frame_index: u8,
+    /// Offset of the PDUs in the cyclic frame's PDU buffer.
+    offset: usize,
+    /// Length of all PDUs copied from the deferred frame.
+    len: usize,
+}
+
+/// Deferred frames whose PDUs were copied into a cyclic frame.
+///
+/// If dropped before [`complete`](MergedFrames::complete) is called, e.g. because the cyclic frame
+/// timed out, the frames are deferred again so they can be sent when the network is idle.
+pub(crate) struct MergedFrames<'sto> {
+    storage: PduStorageRef<'sto>,
+    frames: heapless::Vec<Merged, MAX_MERGED>,
+}
+
+impl<'sto> MergedFrames<'sto> {
+    /// Copy the PDUs of as many deferred frames as will fit into the free space at the end of
+    /// `frame`.
+    pub(in crate::pdu_loop) fn merge(
+        storage: PduStorageRef<'sto>,
+        frame: &mut CreatedFrame<'sto>,
+    ) -> Self {
+        let mut frames = heapless::Vec::new();
+
+        for idx in 0..storage.num_frames {
+            if frames.is_full() {
+                break;
+            }
+
+            let deferred = storage.frame_box(idx);
+
+            if deferred
+                .swap_state(FrameState::Deferred, FrameState::Merged)
+                .is_err()
+            {
+                continue;
+            }
+
+            let len = deferred.pdu_payload_len();
+
+            match frame.append_pdus(&deferred.pdu_buf()[0..len]) {
+                Ok(offset) => {
+                    fmt::trace!(
+                        "Sending deferred frame index {} in cyclic frame index {}",
+                        deferred.frame_index(),
+                        frame.frame_index()
+                    );
+
+                    // Space is checked at the start of the loop.
+                    let _ = frames.push(Merged {
+                        frame_index: deferred.frame_index(),
+                        offset,
+                        len,
+                    });
+                }
+                // Not enough space left. A smaller frame may still fit.
+                Err(_) => {
+                    let _ = deferred.swap_state(FrameState::Merged, FrameState::Deferred);
+                }
+            }
+        }
+
+        Self { storage, frames }
+    }
+
+    /// Copy the responses to the merged PDUs from the received cyclic frame back into their
+    /// original frames, and wake the futures waiting for them.
+    pub(crate) fn complete(mut self, received: &ReceivedFrame<'_>) {
+        for merged in self.frames.iter() {
+            let frame = self.storage.frame_box(usize::from(merged.frame_index));
+
+            // The frame's future may have been dropped, e.g. because it timed out.
+            let Some(mut frame) = ReceivingFrame::claim_merged(frame) else {
+                continue;
+            };
+
+            frame.buf_mut()[0..merged.len]
+                .copy_from_slice(&received.pdu_buf()[merged.offset..][..merged.len]);
+
+            if let Err(e) = frame.mark_received() {
+                fmt::debug!(
+                    "Failed to complete deferred frame index {}: {:?}",
+                    merged.frame_index,
+                    e
+                );
+            }
+        }
+
+        self.frames.clear();
+    }
+}
+
+impl Drop for MergedFrames<'_> {
+    fn drop(&mut self) {
+        for merged in self.frames.iter() {
+            let _ = self
+                .storage
+                .frame_box(usize::from(merged.frame_index))
+                .swap_state(FrameState::Merged, FrameState::Deferred);
+        }
+
+        if !self.frames.is_empty() {
+            self.storage.tx_waker.wake();
+        }
+    }
+}
+
+impl<'sto> PduStorageRef<'sto> {
+    fn frame_box(&self, idx: usize) -> FrameBox<'sto> {
+        FrameBox::new(
+            self.frame_at_index(idx),
+            self.pdu_markers,
+            self.pdu_idx,
+            self.frame_data_len,
+        )
+    }
+}
@@ -13,7 +13,9 @@ use crate::{
     Command,
 };
 use core::{marker::PhantomData, ptr::NonNull, sync::atomic::AtomicU8};
-use ethercrab_wire::{EtherCrabWireSized, EtherCrabWireWrite, EtherCrabWireWriteSized};
+use ethercrab_wire::{
+    EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite, EtherCrabWireWriteSized,
+};
 
 /// A frame in a freshly allocated state.
 ///
@@ -46,17 +48,54 @@ impl<'sto> CreatedFrame<'sto> {
     ///
     /// This method returns a future that should be fulfilled when a response to the sent frame is
     /// received.
-    pub fn mark_sendable(mut self) -> ReceiveFrameFut<'sto> {
+    pub fn mark_sendable(self) -> ReceiveFrameFut<'sto> {
+        self.mark(FrameState::Sendable)
+    }
+
+    /// Like [`mark_sendable`](CreatedFrame::mark_sendable), but the frame is not sent while a
+    /// cyclic frame is in flight. Its PDUs may instead be sent in the next cyclic frame if there is
+    /// space.
+    pub fn mark_deferred(self) -> ReceiveFrameFut<'sto> {
+        self.mark(FrameState::Deferred)
+    }
+
+    fn mark(mut self, state: FrameState) -> ReceiveFrameFut<'sto> {
         EthercatFrameHeader::pdu(self.inner.pdu_payload_len() as u16)
             .pack_to_slice_unchecked(self.inner.ecat_frame_header_mut());
 
-        self.inner.set_state(FrameState::Sendable);
+        self.inner.set_state(state);
 
         ReceiveFrameFut {
             frame: Some(self.inner),
         }
     }
 
+    /// Append already encoded PDUs from another frame to the end of this frame, returning their
+    /// offset in this frame's PDU buffer.
+    ///
+    /// The PDUs keep their original indices, so their markers keep pointing to the frame they were
+    /// copied from.
+    pub(in crate::pdu_loop) fn append_pdus(&mut self, pdus: &[u8]) -> Result<usize, PduError> {
+        let consumed = self.inner.pdu_payload_len();
+
+        let buf = self
+            .inner
+            .pdu_buf_mut()
+            .get_mut(consumed..(consumed + pdus.len()))
+            .ok_or(PduError::TooLong)?;
+
+        buf.copy_from_slice(pdus);
+
+        // The previously last PDU is now followed by the appended ones.
+        if let Some(last) = last_pdu_offset(&self.inner.pdu_buf()[0..consumed]) {
+            set_more_follows(&mut self.inner.pdu_buf_mut()[last..]);
+        }
+
+        self.inner.extend_payload(pdus.len());
+
+        Ok(consumed)
+    }
+
     pub fn push_pdu<RX>(
         &mut self,
         command: Command,
@@ -137,6 +176,33 @@ impl<'sto> CreatedFrame<'sto> {
 // a 'static bound.
 unsafe impl<'sto> Send for CreatedFrame<'sto> {}
 
+/// Find the offset of the last PDU in a buffer of encoded PDUs.
+fn last_pdu_offset(pdus: &[u8]) -> Option<usize> {
+    let mut offset = 0;
+    let mut last = None;
+
+    while let Some(header) = pdus.get(offset..) {
+        let Ok(header) = PduHeader::unpack_from_slice(header) else {
+            break;
+        };
+
+        last = Some(offset);
+
+        // PDU header + data + working counter
+        offset += PduHeader::PACKED_LEN + usize::from(header.flags.len()) + 2;
+    }
+
+    last
+}
+
+/// Set the "more follows" flag of the encoded PDU at the start of `pdu`.
+fn set_more_follows(pdu: &mut [u8]) {
+    // Flags are the last 2 bytes of the header before the IRQ field.
+    const FLAGS_MSB: usize = PduHeader::PACKED_LEN - 3;
+
+    pdu[FLAGS_MSB] |= 0x80;
+}
+
 #[derive(Debug)]
 pub struct PduResponseHandle<T> {
     _ty: PhantomData<T>,
@@ -227,6 +227,12 @@ impl<'sto> FrameBox<'sto> {
         unsafe { FrameElement::<0>::dec_refcount(self.frame) }
     }
 
+    /// Extend the PDU payload without counting any new PDUs, used for PDUs copied from another
+    /// frame.
+    pub fn extend_payload(&mut self, len: usize) {
+        unsafe { *addr_of_mut!((*self.frame.as_ptr()).pdu_payload_len) += len };
+    }
+
     pub fn add_pdu(&mut self, alloc_size: usize) {
         unsafe { *addr_of_mut!((*self.frame.as_ptr()).pdu_payload_len) += alloc_size };
 
@@ -14,7 +14,7 @@ use core::{
     ptr::{addr_of, addr_of_mut, NonNull},
     sync::atomic::{AtomicU16, Ordering},
 };
-use frame_box::FrameBox;
+pub(in crate::pdu_loop) use frame_box::FrameBox;
 use smoltcp::wire::EthernetFrame;
 
 /// Frame state.
@@ -44,6 +44,11 @@ pub enum FrameState {
     RxDone = 6,
     /// The frame TX/RX is complete, but the frame memory is still held by calling code.
     RxProcessing = 7,
+    /// The frame is ready to send, but is held back while a cyclic frame is in flight. It is
+    /// either sent once the network is idle, or its PDUs are copied into the next cyclic frame.
+    Deferred = 8,
+    /// The frame's PDUs have been copied into a cyclic frame which is in flight.
+    Merged = 9,
 }
 
 #[derive(Debug)]
@@ -251,6 +256,12 @@ impl<const N: usize> FrameElement<N> {
         Self::swap_state(this, FrameState::Sendable, FrameState::Sending).ok()
     }
 
+    unsafe fn claim_deferred_sending(
+        this: NonNull<FrameElement<N>>,
+    ) -> Option<NonNull<FrameElement<N>>> {
+        Self::swap_state(this, FrameState::Deferred, FrameState::Sending).ok()
+    }
+
     unsafe fn claim_receiving(this: NonNull<FrameElement<N>>) -> Option<NonNull<FrameElement<N>>> {
         Self::swap_state(this, FrameState::Sent, FrameState::RxBusy)
             .map_err(|actual_state| {
@@ -30,6 +30,11 @@ impl<'sto> ReceivedFrame<'sto> {
         }
     }
 
+    /// The received PDU data, including headers and working counters.
+    pub(in crate::pdu_loop) fn pdu_buf(&self) -> &[u8] {
+        self.inner.pdu_buf()
+    }
+
     pub fn take<'frame, T>(
         &'sto self,
         // SAFETY: Consumes handle so it cannot be retrieved again. If this invariant is relaxed, we
@@ -29,6 +29,15 @@ impl<'sto> ReceivingFrame<'sto> {
         })
     }
 
+    /// Claim a frame whose PDUs were sent in a cyclic frame, so the responses can be copied back
+    /// into it.
+    pub(in crate::pdu_loop) fn claim_merged(frame: FrameBox<'sto>) -> Option<Self> {
+        frame
+            .swap_state(FrameState::Merged, FrameState::RxBusy)
+            .ok()
+            .map(|()| Self { inner: frame })
+    }
+
     /// Mark the frame as fully received.
     ///
     /// This method may only be called once the frame response (header and data) has been validated
@@ -168,7 +177,12 @@ impl<'sto> Future for ReceiveFrameFut<'sto> {
         fmt::trace!("frame index {} not ready yet ({:?})", frame_idx, was);
 
         match was {
-            FrameState::Sendable | FrameState::Sending | FrameState::Sent | FrameState::RxBusy => {
+            FrameState::Sendable
+            | FrameState::Deferred
+            | FrameState::Merged
+            | FrameState::Sending
+            | FrameState::Sent
+            | FrameState::RxBusy => {
                 self.frame = Some(rxin);
 
                 Poll::Pending
@@ -68,6 +68,21 @@ impl<'sto> SendableFrame<'sto> {
         })
     }
 
+    /// Like [`claim_sending`](SendableFrame::claim_sending), but for a frame that was deferred
+    /// while a cyclic frame was in flight.
+    pub(crate) fn claim_deferred_sending(
+        frame: NonNull<FrameElement<0>>,
+        pdu_markers: NonNull<PduMarker>,
+        pdu_idx: &'sto AtomicU8,
+        frame_data_len: usize,
+    ) -> Option<Self> {
+        let frame = unsafe { FrameElement::claim_deferred_sending(frame)? };
+
+        Some(Self {
+            inner: FrameBox::new(frame, pdu_markers, pdu_idx, frame_data_len),
+        })
+    }
+
     /// The frame has been sent by the network driver.
     fn mark_sent(&self) {
         fmt::trace!("Frame index {} is sent", self.inner.frame_index());
@@ -1,3 +1,4 @@
+mod cyclic;
 mod frame_element;
 mod frame_header;
 mod pdu_flags;
@@ -8,10 +9,12 @@ mod pdu_tx;
 pub mod storage;
 
 use crate::{command::Command, error::Error, pdu_loop::storage::PduStorageRef};
+use ethercrab_wire::EtherCrabWireSized;
 pub use pdu_rx::PduRx;
 pub use pdu_tx::PduTx;
 pub use storage::PduStorage;
 
+pub(crate) use self::cyclic::{CyclicGuard, MergedFrames};
 pub(crate) use self::frame_element::created_frame::CreatedFrame;
 pub(crate) use self::frame_element::received_frame::ReceivedFrame;
 pub(crate) use frame_element::received_frame::ReceivedPdu;
@@ -31,6 +34,10 @@ const PDU_UNUSED_SENTINEL: u16 = u16::MAX;
 
 const PDU_SLOTS: usize = 256;
 
+/// The number of bytes used by each PDU in a frame on top of its data: the PDU header and working
+/// counter.
+pub(crate) const PDU_OVERHEAD: usize = pdu_header::PduHeader::PACKED_LEN + 2;
+
 /// The core EtherCrab network communications driver.
 ///
 /// TODO: Update the following docs. The current text is out of date.
@@ -76,6 +83,11 @@ impl<'sto> PduLoop<'sto> {
         self.storage.frame_data_len
     }
 
+    /// The most data a single PDU sent on its own in a frame can hold.
+    pub(crate) fn max_pdu_data(&self) -> usize {
+        self.storage.frame_data_len - storage::MIN_DATA
+    }
+
     /// Tell the packet sender there are PDUs ready to send.
     pub(crate) fn wake_sender(&self) {
         self.storage.tx_waker.wake();
@@ -108,6 +120,17 @@ impl<'sto> PduLoop<'sto> {
     pub(crate) fn alloc_frame(&self) -> Result<CreatedFrame<'sto>, Error> {
         self.storage.alloc_frame()
     }
+
+    /// Mark the start of a cyclic exchange. Deferred frames will not be sent on their own until
+    /// the returned guard is dropped.
+    pub(crate) fn cyclic(&self) -> CyclicGuard<'sto> {
+        CyclicGuard::new(self.storage.clone())
+    }
+
+    /// Copy the PDUs of deferred frames into the space left at the end of a cyclic frame.
+    pub(crate) fn merge_deferred(&self, frame: &mut CreatedFrame<'sto>) -> MergedFrames<'sto> {
+        MergedFrames::merge(self.storage.clone(), frame)
+    }
 }
 
 #[cfg(test)]
@@ -286,6 +309,134 @@ mod tests {
         cassette::block_on(poller);
     }
 
+    #[test]
+    fn deferred_frame_waits_for_cyclic() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        static STORAGE: PduStorage<2, 128> = PduStorage::<2, 128>::new();
+        let (mut tx, _rx, pdu_loop) = STORAGE.try_split().unwrap();
+
+        let cyclic = pdu_loop.cyclic();
+
+        let mut frame = pdu_loop.storage.alloc_frame().unwrap();
+
+        frame
+            .push_pdu::<()>(Command::fprd(0x1000, 0x0130).into(), (), Some(2), false)
+            .expect("Push");
+
+        let _frame_fut = frame.mark_deferred();
+
+        assert!(
+            tx.next_sendable_frame().is_none(),
+            "deferred frame should not be sent during a cyclic exchange"
+        );
+
+        drop(cyclic);
+
+        assert!(tx.next_sendable_frame().is_some());
+    }
+
+    #[test]
+    fn deferred_frame_merged_into_cyclic() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        const FRAME_OVERHEAD: usize = 28;
+
+        let storage = PduStorage::<2, 128>::new();
+
+        let (mut tx, mut rx, pdu_loop) = storage.try_split().unwrap();
+
+        let cyclic_data = [0xaau8, 0xbb, 0xcc, 0xdd];
+        let deferred_data = [0x11u8, 0x22];
+
+        let poller = poll_fn(|ctx| {
+            let mut deferred = pdu_loop.storage.alloc_frame().expect("Deferred alloc");
+
+            let deferred_handle = deferred
+                .push_pdu::<()>(
+                    Command::fpwr(0x1000, 0x0130).into(),
+                    &deferred_data,
+                    None,
+                    false,
+                )
+                .expect("Push deferred");
+
+            let mut deferred_fut = pin!(deferred.mark_deferred());
+
+            assert!(matches!(deferred_fut.as_mut().poll(ctx), Poll::Pending));
+
+            let _cyclic = pdu_loop.cyclic();
+
+            let mut frame = pdu_loop.storage.alloc_frame().expect("Cyclic alloc");
+
+            let handle = frame
+                .push_pdu::<()>(Command::lrw(0).into(), &cyclic_data, None, false)
+                .expect("Push cyclic");
+
+            let merged = pdu_loop.merge_deferred(&mut frame);
+
+            let mut frame_fut = pin!(frame.mark_sendable());
+
+            assert!(matches!(frame_fut.as_mut().poll(ctx), Poll::Pending));
+
+            let mut written_packet =
+                vec![0; FRAME_OVERHEAD + cyclic_data.len() + 12 + deferred_data.len()];
+
+            let frame = tx.next_sendable_frame().expect("need a frame");
+
+            frame
+                .send_blocking(|bytes| {
+                    written_packet.copy_from_slice(bytes);
+
+                    Ok(bytes.len())
+                })
+                .expect("send");
+
+            // Both PDUs are in the cyclic frame, with the first one marked as being followed by
+            // another.
+            assert_eq!(written_packet[16], 0x0c, "first PDU command");
+            assert_eq!(written_packet[23] & 0x80, 0x80, "more follows");
+            assert_eq!(written_packet[32], 0x05, "second PDU command");
+            assert_eq!(&written_packet[42..44], &deferred_data);
+
+            // Nothing else to send
+            assert!(tx.next_sendable_frame().is_none());
+
+            let written_packet = {
+                let mut frame = EthernetFrame::new_checked(written_packet).unwrap();
+                frame.set_src_addr(EthernetAddress([0x12, 0x10, 0x10, 0x10, 0x10, 0x10]));
+                frame.into_inner()
+            };
+
+            assert_eq!(rx.receive_frame(&written_packet), Ok(()));
+
+            let Poll::Ready(Ok(received)) = frame_fut.poll(ctx) else {
+                panic!("cyclic frame not received");
+            };
+
+            // Deferred frame isn't complete until the response is copied back
+            assert!(matches!(deferred_fut.as_mut().poll(ctx), Poll::Pending));
+
+            merged.complete(&received);
+
+            assert_eq!(received.take(handle).expect("Handle").deref(), &cyclic_data);
+
+            match deferred_fut.poll(ctx) {
+                Poll::Ready(Ok(frame)) => {
+                    let response = frame.take(deferred_handle).expect("Deferred handle");
+
+                    assert_eq!(response.deref(), &deferred_data);
+                }
+                Poll::Ready(other) => panic!("Expected Ready(Ok()), got {:?}", other),
+                Poll::Pending => panic!("deferred frame future still pending"),
+            }
+
+            Poll::Ready(())
+        });
+
+        cassette::block_on(poller);
+    }
+
     #[test]
     fn write_multiple_frame() {
         static STORAGE: PduStorage<1, 128> = PduStorage::<1, 128>::new();
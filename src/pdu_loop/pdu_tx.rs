@@ -1,5 +1,5 @@
 use super::{frame_element::sendable_frame::SendableFrame, storage::PduStorageRef};
-use core::task::Waker;
+use core::{sync::atomic::Ordering, task::Waker};
 
 /// EtherCAT frame transmit adapter.
 pub struct PduTx<'sto> {
@@ -17,6 +17,8 @@ impl<'sto> PduTx<'sto> {
     }
 
     /// Get the next sendable frame, if any are available.
+    ///
+    /// Deferred acyclic frames are only returned when no cyclic frames are in flight.
     // NOTE: Mutable so it can only be used in one task.
     pub fn next_sendable_frame(&mut self) -> Option<SendableFrame<'sto>> {
         for idx in 0..self.storage.num_frames {
@@ -34,7 +36,18 @@ impl<'sto> PduTx<'sto> {
             return Some(sending);
         }
 
-        None
+        if self.storage.cyclic.load(Ordering::Acquire) > 0 {
+            return None;
+        }
+
+        (0..self.storage.num_frames).find_map(|idx| {
+            SendableFrame::claim_deferred_sending(
+                self.storage.frame_at_index(idx),
+                self.storage.pdu_markers,
+                self.storage.pdu_idx,
+                self.storage.frame_data_len,
+            )
+        })
     }
 
     /// Set or replace the PDU loop waker.
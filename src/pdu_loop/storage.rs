@@ -20,13 +20,13 @@ use core::{
     marker::PhantomData,
     mem::MaybeUninit,
     ptr::NonNull,
-    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+    sync::atomic::{AtomicBool, AtomicU16, AtomicU8, Ordering},
 };
 use ethercrab_wire::EtherCrabWireSized;
 use smoltcp::wire::EthernetFrame;
 
 /// Smallest frame size with a data payload of 0 length
-const MIN_DATA: usize = EthernetFrame::<&[u8]>::buffer_len(
+pub(in crate::pdu_loop) const MIN_DATA: usize = EthernetFrame::<&[u8]>::buffer_len(
     EthercatFrameHeader::header_len()
                     + super::pdu_header::PduHeader::PACKED_LEN
                     // PDU payload
@@ -46,6 +46,8 @@ pub struct PduStorage<const N: usize, const DATA: usize> {
     frame_idx: AtomicU8,
     pdu_idx: AtomicU8,
     is_split: AtomicBool,
+    /// The number of cyclic exchanges in flight.
+    cyclic: AtomicU16,
     /// A waker used to wake up the TX task when a new frame is ready to be sent.
     pub(in crate::pdu_loop) tx_waker: AtomicWaker,
 }
@@ -125,6 +127,7 @@ impl<const N: usize, const DATA: usize> PduStorage<N, DATA> {
             pdu_idx: AtomicU8::new(0),
             pdu_markers: pdu_states,
             is_split: AtomicBool::new(false),
+            cyclic: AtomicU16::new(0),
             tx_waker: AtomicWaker::new(),
         }
     }
@@ -169,6 +172,7 @@ impl<const N: usize, const DATA: usize> PduStorage<N, DATA> {
             pdu_idx: &self.pdu_idx,
             pdu_markers: unsafe { NonNull::new_unchecked(self.pdu_markers.get().cast()) },
             tx_waker: &self.tx_waker,
+            cyclic: &self.cyclic,
             _lifetime: PhantomData,
         }
     }
@@ -185,6 +189,8 @@ pub(crate) struct PduStorageRef<'sto> {
     pub pdu_idx: &'sto AtomicU8,
     pub pdu_markers: NonNull<PduMarker>,
     pub tx_waker: &'sto AtomicWaker,
+    /// The number of cyclic exchanges in flight. Deferred frames are only sent when this is zero.
+    pub cyclic: &'sto AtomicU16,
     _lifetime: PhantomData<&'sto ()>,
 }
 
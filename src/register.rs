@@ -193,6 +193,11 @@ pub enum RegisterAddress {
     /// AKA ETG1000.4 Table 61 DC user P1.
     DcSyncActive = 0x0981,
 
+    /// Pulse length of the SYNC signals, `u16`.
+    ///
+    /// In units of 10ns. This register is read only and is loaded from EEPROM word `0x0002`.
+    DcSyncPulseLength = 0x0982,
+
     /// ETG1000.6 Table 27 - Distributed Clock sync parameter, `u32`.
     ///
     /// AKA ETG1000.4 Table 61 DC user P4.
@@ -207,6 +212,40 @@ pub enum RegisterAddress {
 
     /// See [`RegisterAddress::DcSync0CycleTime`].
     DcSync1CycleTime = 0x09A4,
+
+    /// Latch0 edge modes, `u8`.
+    ///
+    /// Bit 0 selects single event (`1`) or continuous (`0`) mode for the positive edge, bit 1 for
+    /// the negative edge.
+    DcLatch0Control = 0x09A8,
+
+    /// See [`RegisterAddress::DcLatch0Control`].
+    DcLatch1Control = 0x09A9,
+
+    /// Latch0 status, `u8`.
+    ///
+    /// Bit 0 is set when a positive edge event is stored, bit 1 when a negative edge event is
+    /// stored, and bit 2 holds the current state of the latch input.
+    DcLatch0Status = 0x09AE,
+
+    /// See [`RegisterAddress::DcLatch0Status`].
+    DcLatch1Status = 0x09AF,
+
+    /// DC system time of the last Latch0 positive edge, `u64`.
+    ///
+    /// Reading this register acknowledges a single event mode positive edge event.
+    DcLatch0TimePositive = 0x09B0,
+
+    /// DC system time of the last Latch0 negative edge, `u64`.
+    ///
+    /// Reading this register acknowledges a single event mode negative edge event.
+    DcLatch0TimeNegative = 0x09B8,
+
+    /// See [`RegisterAddress::DcLatch0TimePositive`].
+    DcLatch1TimePositive = 0x09C0,
+
+    /// See [`RegisterAddress::DcLatch0TimeNegative`].
+    DcLatch1TimeNegative = 0x09C8,
 }
 
 impl From<RegisterAddress> for u16 {
@@ -268,6 +307,14 @@ impl RegisterAddress {
     pub fn sync_manager_status(index: u8) -> u16 {
         u16::from(Self::sync_manager(index)) + 5
     }
+
+    /// Sync manager activate register by SM index.
+    ///
+    /// The activate register is the 6th byte after the start of the SM, followed by the PDI
+    /// control register.
+    pub fn sync_manager_activate(index: u8) -> u16 {
+        u16::from(Self::sync_manager(index)) + 6
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
@@ -0,0 +1,490 @@
+//! Read and write ETG.2000 EtherCAT Slave Information (ESI) XML files.
+//!
+//! An ESI file describes a device to configuration tools. Some vendors no longer provide ESI files
+//! for older devices, but most of the information in them is also stored in the device's EEPROM.
+//! [`to_xml`] recovers a minimal ESI file from a [`SiiImage`], containing the device's identity,
+//! names, FMMUs, sync managers, PDOs, mailbox protocols, DC modes and EEPROM configuration.
+//!
+//! Information not stored in the EEPROM, e.g. the object dictionary, is not included.
+//!
+//! The reverse is also possible. [`EsiFile::parse`] reads the same information, along with CoE init
+//! commands, from a vendor's ESI file. This can be used to configure SubDevices with incomplete
+//! EEPROMs with
+//! [`SlaveGroup::into_safe_op_with_esi`](crate::SlaveGroup::into_safe_op_with_esi).
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ethercrab::{error::Error, sii::{esi, SiiImage}, Client, SlaveGroup};
+//!
+//! # async fn run(client: &Client<'_>, group: &mut SlaveGroup<8, 64>) -> Result<(), Error> {
+//! let slave = group.slave(client, 0)?;
+//!
+//! let image = SiiImage::read(&slave.eeprom()).await?;
+//!
+//! std::fs::write("device.xml", esi::to_xml(&image)).expect("write ESI file");
+//! # Ok(())
+//! # }
+//! ```
+
+mod parse;
+mod xml;
+
+pub use self::parse::{EsiDevice, EsiError, EsiFile, EsiInitCommand};
+
+use super::{
+    FmmuUsage, Pdo, PdoFlags, PortStatus, PrimitiveDataType, SiiImage, SyncManagerEnable,
+    SyncManagerType,
+};
+use crate::eeprom::types::{CoeDetails, GeneralFlags, MailboxProtocols};
+use core::fmt::{self, Write};
+use ethercrab_wire::EtherCrabWireWriteSized;
+
+/// Locale ID for names, as ESI files require one. EEPROM strings have no language, so they are
+/// given English (US).
+const LCID: u16 = 1033;
+
+/// Generate an ESI XML document describing the device in `image`.
+pub fn to_xml(image: &SiiImage) -> String {
+    let mut xml = String::new();
+
+    // Writing to a `String` can't fail
+    let _ = write_xml(&mut xml, image);
+
+    xml
+}
+
+fn write_xml(w: &mut String, image: &SiiImage) -> fmt::Result {
+    let identity = image.identity;
+    let general = image.general;
+    let string = |index| image.string(index).unwrap_or_default();
+
+    let name = general
+        .map(|general| string(general.name_string_idx))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| string(1));
+    let group = general.map_or("", |general| string(general.group_string_idx));
+    let order = general.map_or("", |general| string(general.order_string_idx));
+
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<EtherCATInfo xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="EtherCATInfo.xsd" Version="1.6">"#
+    )?;
+    writeln!(w, "  <Vendor>")?;
+    writeln!(w, "    <Id>#x{:08x}</Id>", identity.vendor_id)?;
+    writeln!(w, "  </Vendor>")?;
+    writeln!(w, "  <Descriptions>")?;
+    writeln!(w, "    <Groups>")?;
+    writeln!(w, "      <Group>")?;
+    writeln!(w, "        <Type>{}</Type>", Escape(group))?;
+    writeln!(
+        w,
+        r#"        <Name LcId="{}">{}</Name>"#,
+        LCID,
+        Escape(group)
+    )?;
+    writeln!(w, "      </Group>")?;
+    writeln!(w, "    </Groups>")?;
+    writeln!(w, "    <Devices>")?;
+
+    write!(w, "      <Device")?;
+
+    if let Some(general) = general {
+        write!(w, r#" Physics="{}""#, physics(&general.ports.0))?;
+    }
+
+    writeln!(w, ">")?;
+    writeln!(
+        w,
+        r##"        <Type ProductCode="#x{:08x}" RevisionNo="#x{:08x}">{}</Type>"##,
+        identity.product_id,
+        identity.revision,
+        Escape(if order.is_empty() { name } else { order })
+    )?;
+    writeln!(
+        w,
+        r#"        <Name LcId="{}">{}</Name>"#,
+        LCID,
+        Escape(name)
+    )?;
+    writeln!(w, "        <GroupType>{}</GroupType>", Escape(group))?;
+
+    for fmmu in image.fmmus.iter() {
+        let usage = match fmmu {
+            FmmuUsage::Outputs => "Outputs",
+            FmmuUsage::Inputs => "Inputs",
+            FmmuUsage::SyncManagerStatus => "MBoxState",
+            FmmuUsage::Unused => continue,
+        };
+
+        writeln!(w, "        <Fmmu>{}</Fmmu>", usage)?;
+    }
+
+    for sm in image.sync_managers.iter() {
+        write!(
+            w,
+            r##"        <Sm DefaultSize="{}" StartAddress="#x{:04x}" ControlByte="#x{:02x}" Enable="{}""##,
+            sm.length,
+            sm.start_addr,
+            sm.control.pack()[0],
+            u8::from(sm.enable.contains(SyncManagerEnable::ENABLE)),
+        )?;
+
+        match sm.usage_type {
+            SyncManagerType::MailboxWrite => writeln!(w, ">MBoxOut</Sm>")?,
+            SyncManagerType::MailboxRead => writeln!(w, ">MBoxIn</Sm>")?,
+            SyncManagerType::ProcessDataWrite => writeln!(w, ">Outputs</Sm>")?,
+            SyncManagerType::ProcessDataRead => writeln!(w, ">Inputs</Sm>")?,
+            SyncManagerType::Unknown => writeln!(w, " />")?,
+        }
+    }
+
+    for pdo in image.rx_pdos.iter() {
+        write_pdo(w, image, "RxPdo", pdo)?;
+    }
+
+    for pdo in image.tx_pdos.iter() {
+        write_pdo(w, image, "TxPdo", pdo)?;
+    }
+
+    let protocols = image.mailbox.supported_protocols;
+
+    if image.mailbox.has_mailbox() {
+        let dll = general.is_some_and(|general| general.flags.contains(GeneralFlags::MAILBOX_DLL));
+
+        writeln!(w, r#"        <Mailbox DataLinkLayer="{}">"#, dll)?;
+
+        if protocols.contains(MailboxProtocols::AOE) {
+            writeln!(w, "          <AoE />")?;
+        }
+
+        if protocols.contains(MailboxProtocols::EOE) {
+            writeln!(w, "          <EoE />")?;
+        }
+
+        if protocols.contains(MailboxProtocols::COE) {
+            let coe = general.map_or(CoeDetails::empty(), |general| general.coe_details);
+
+            writeln!(
+                w,
+                r#"          <CoE SdoInfo="{}" PdoAssign="{}" PdoConfig="{}" PdoUpload="{}" CompleteAccess="{}" />"#,
+                coe.contains(CoeDetails::ENABLE_SDO_INFO),
+                coe.contains(CoeDetails::ENABLE_PDO_ASSIGN),
+                coe.contains(CoeDetails::ENABLE_PDO_CONFIG),
+                coe.contains(CoeDetails::ENABLE_STARTUP_UPLOAD),
+                coe.contains(CoeDetails::ENABLE_COMPLETE_ACCESS),
+            )?;
+        }
+
+        if protocols.contains(MailboxProtocols::FOE) {
+            writeln!(w, "          <FoE />")?;
+        }
+
+        if protocols.contains(MailboxProtocols::SOE) {
+            writeln!(w, "          <SoE />")?;
+        }
+
+        if protocols.contains(MailboxProtocols::VOE) {
+            writeln!(w, "          <VoE />")?;
+        }
+
+        writeln!(w, "        </Mailbox>")?;
+    }
+
+    if !image.dc_sync_modes.is_empty() {
+        writeln!(w, "        <Dc>")?;
+
+        for mode in image.dc_sync_modes.iter() {
+            writeln!(w, "          <OpMode>")?;
+            writeln!(
+                w,
+                "            <Name>{}</Name>",
+                Escape(string(mode.name_string_idx))
+            )?;
+
+            let desc = string(mode.desc_string_idx);
+
+            if !desc.is_empty() {
+                writeln!(w, "            <Desc>{}</Desc>", Escape(desc))?;
+            }
+
+            writeln!(
+                w,
+                "            <AssignActivate>#x{:04x}</AssignActivate>",
+                mode.assign_activate
+            )?;
+            writeln!(
+                w,
+                r#"            <CycleTimeSync0 Factor="{}">{}</CycleTimeSync0>"#,
+                mode.sync0_cycle_factor, mode.cycle_time0
+            )?;
+            writeln!(
+                w,
+                "            <ShiftTimeSync0>{}</ShiftTimeSync0>",
+                // Shifts are signed
+                mode.shift_time0 as i32
+            )?;
+            writeln!(
+                w,
+                r#"            <CycleTimeSync1 Factor="{}">0</CycleTimeSync1>"#,
+                mode.sync1_cycle_factor
+            )?;
+            writeln!(
+                w,
+                "            <ShiftTimeSync1>{}</ShiftTimeSync1>",
+                mode.shift_time1 as i32
+            )?;
+            writeln!(w, "          </OpMode>")?;
+        }
+
+        writeln!(w, "        </Dc>")?;
+    }
+
+    let bootstrap = image.bootstrap_mailbox;
+
+    writeln!(w, "        <Eeprom>")?;
+    writeln!(w, "          <ByteSize>{}</ByteSize>", image.size)?;
+    // Configuration area without the checksum, which is calculated by the configuration tool
+    writeln!(
+        w,
+        "          <ConfigData>{}</ConfigData>",
+        Hex(&image.config_area[0..14])
+    )?;
+
+    if bootstrap.slave_receive_size > 0 || bootstrap.slave_send_size > 0 {
+        let mut raw = [0u8; 8];
+
+        for (chunk, value) in raw.chunks_exact_mut(2).zip([
+            bootstrap.slave_receive_offset,
+            bootstrap.slave_receive_size,
+            bootstrap.slave_send_offset,
+            bootstrap.slave_send_size,
+        ]) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+
+        writeln!(w, "          <BootStrap>{}</BootStrap>", Hex(&raw))?;
+    }
+
+    writeln!(w, "        </Eeprom>")?;
+    writeln!(w, "      </Device>")?;
+    writeln!(w, "    </Devices>")?;
+    writeln!(w, "  </Descriptions>")?;
+    writeln!(w, "</EtherCATInfo>")
+}
+
+fn write_pdo(w: &mut String, image: &SiiImage, element: &str, pdo: &Pdo) -> fmt::Result {
+    let string = |index| image.string(index).unwrap_or_default();
+
+    write!(w, "        <{}", element)?;
+
+    if pdo.flags.contains(PdoFlags::PDO_FIXED_CONTENT) {
+        write!(w, r#" Fixed="true""#)?;
+    }
+
+    if pdo.flags.contains(PdoFlags::PDO_MANDATORY) {
+        write!(w, r#" Mandatory="true""#)?;
+    }
+
+    // Sync manager 0xff means the PDO is not assigned by default
+    if pdo.sync_manager != 0xff {
+        write!(w, r#" Sm="{}""#, pdo.sync_manager)?;
+    }
+
+    writeln!(w, ">")?;
+    writeln!(w, "          <Index>#x{:04x}</Index>", pdo.index)?;
+    writeln!(
+        w,
+        "          <Name>{}</Name>",
+        Escape(string(pdo.name_string_idx))
+    )?;
+
+    for entry in pdo.entries.iter() {
+        writeln!(w, "          <Entry>")?;
+        writeln!(w, "            <Index>#x{:04x}</Index>", entry.index)?;
+
+        // An index of zero is padding, which only has a length
+        if entry.index != 0 {
+            writeln!(w, "            <SubIndex>{}</SubIndex>", entry.sub_index)?;
+        }
+
+        writeln!(w, "            <BitLen>{}</BitLen>", entry.data_length_bits)?;
+
+        if entry.index != 0 {
+            writeln!(
+                w,
+                "            <Name>{}</Name>",
+                Escape(string(entry.name_string_idx))
+            )?;
+
+            if let Some(data_type) = data_type(entry.data_type) {
+                writeln!(w, "            <DataType>{}</DataType>", data_type)?;
+            }
+        }
+
+        writeln!(w, "          </Entry>")?;
+    }
+
+    writeln!(w, "        </{}>", element)
+}
+
+/// ESI `Physics` attribute, with one character per port.
+fn physics(ports: &[PortStatus; 4]) -> String {
+    let physics = ports
+        .iter()
+        .map(|port| match port {
+            PortStatus::Mii => 'Y',
+            PortStatus::Ebus => 'K',
+            PortStatus::FastHotConnect => 'H',
+            PortStatus::Unused | PortStatus::Reserved => ' ',
+        })
+        .collect::<String>();
+
+    physics.trim_end().to_string()
+}
+
+/// ESI name of a data type, defined in ETG.2000 Table 15.
+fn data_type(data_type: PrimitiveDataType) -> Option<&'static str> {
+    let name = match data_type {
+        PrimitiveDataType::Unknown => return None,
+        PrimitiveDataType::Bool => "BOOL",
+        PrimitiveDataType::Byte => "BYTE",
+        PrimitiveDataType::Word => "WORD",
+        PrimitiveDataType::DWord => "DWORD",
+        PrimitiveDataType::Bit1 => "BIT1",
+        PrimitiveDataType::Bit2 => "BIT2",
+        PrimitiveDataType::Bit3 => "BIT3",
+        PrimitiveDataType::Bit4 => "BIT4",
+        PrimitiveDataType::Bit5 => "BIT5",
+        PrimitiveDataType::Bit6 => "BIT6",
+        PrimitiveDataType::Bit7 => "BIT7",
+        PrimitiveDataType::Bit8 => "BIT8",
+        PrimitiveDataType::Bit9 => "BIT9",
+        PrimitiveDataType::Bit10 => "BIT10",
+        PrimitiveDataType::Bit11 => "BIT11",
+        PrimitiveDataType::Bit12 => "BIT12",
+        PrimitiveDataType::Bit13 => "BIT13",
+        PrimitiveDataType::Bit14 => "BIT14",
+        PrimitiveDataType::Bit15 => "BIT15",
+        PrimitiveDataType::Bit16 => "BIT16",
+        PrimitiveDataType::BitArr8 => "BITARR8",
+        PrimitiveDataType::BitArr16 => "BITARR16",
+        PrimitiveDataType::BitArr32 => "BITARR32",
+        PrimitiveDataType::I8 => "SINT",
+        PrimitiveDataType::I16 => "INT",
+        PrimitiveDataType::I24 => "INT24",
+        PrimitiveDataType::I32 => "DINT",
+        PrimitiveDataType::I40 => "INT40",
+        PrimitiveDataType::I48 => "INT48",
+        PrimitiveDataType::I56 => "INT56",
+        PrimitiveDataType::I64 => "LINT",
+        PrimitiveDataType::U8 => "USINT",
+        PrimitiveDataType::U16 => "UINT",
+        PrimitiveDataType::U24 => "UINT24",
+        PrimitiveDataType::U32 => "UDINT",
+        PrimitiveDataType::U40 => "UINT40",
+        PrimitiveDataType::U48 => "UINT48",
+        PrimitiveDataType::U56 => "UINT56",
+        PrimitiveDataType::U64 => "ULINT",
+        PrimitiveDataType::F32 => "REAL",
+        PrimitiveDataType::F64 => "LREAL",
+    };
+
+    Some(name)
+}
+
+/// Escape text for use in XML content or attributes.
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Format bytes as an ESI `HexBinary` value.
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02X}", byte))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{eeprom::file_reader::EepromFile, sii::SlaveEeprom};
+
+    async fn image(path: &str) -> SiiImage {
+        SiiImage::read(&SlaveEeprom::new(EepromFile::new(path)))
+            .await
+            .expect("read image")
+    }
+
+    #[tokio::test]
+    async fn el2828() {
+        let xml = to_xml(&image("dumps/eeprom/el2828.hex").await);
+
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(xml.contains("<Id>#x00000002</Id>"), "{}", xml);
+        assert!(
+            xml.contains(
+                r##"<Type ProductCode="#x0b0c3052" RevisionNo="#x00110000">EL2828</Type>"##
+            ),
+            "{}",
+            xml
+        );
+        assert!(xml.contains("<Fmmu>Outputs</Fmmu>"), "{}", xml);
+        assert!(xml.contains(">Outputs</Sm>"), "{}", xml);
+        assert!(
+            xml.contains("<RxPdo Fixed=\"true\" Mandatory=\"true\" Sm=\"0\">"),
+            "{}",
+            xml
+        );
+        assert!(xml.contains("<DataType>BOOL</DataType>"), "{}", xml);
+        assert!(
+            xml.contains("<ConfigData>040100000000FF00000000000000</ConfigData>"),
+            "{}",
+            xml
+        );
+        assert!(!xml.contains("<Mailbox"), "{}", xml);
+        assert!(xml.ends_with("</EtherCATInfo>\n"));
+    }
+
+    #[tokio::test]
+    async fn akd() {
+        let xml = to_xml(&image("dumps/eeprom/akd.hex").await);
+
+        assert!(
+            xml.contains(r#"<Name LcId="1033">AKD EtherCAT Drive (CoE)</Name>"#),
+            "{}",
+            xml
+        );
+        assert!(xml.contains("<GroupType>Drive</GroupType>"), "{}", xml);
+        assert!(xml.contains("<CoE "), "{}", xml);
+        assert!(xml.contains(">MBoxOut</Sm>"), "{}", xml);
+        assert!(xml.contains("<Name>DcSync</Name>"), "{}", xml);
+        assert!(xml.contains("<TxPdo"), "{}", xml);
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(
+            Escape(r#"<a & "b">"#).to_string(),
+            "&lt;a &amp; &quot;b&quot;&gt;"
+        );
+    }
+}
@@ -0,0 +1,1123 @@
+use super::xml::Element;
+use crate::{
+    eeprom::{config_checksum, types::CoeDetails},
+    sii::{
+        BootstrapMailbox, DefaultMailbox, FmmuUsage, GeneralFlags, MailboxProtocols, Pdo, PdoEntry,
+        PdoFlags, PortStatus, PortStatuses, PrimitiveDataType, SiiDcSync, SiiGeneral, SiiImage,
+        SyncManager, SyncManagerControl, SyncManagerEnable, SyncManagerType,
+    },
+    slave::configuration::SiiOverride,
+    DcSyncMode, SlaveIdentity, SlaveState,
+};
+use ethercrab_wire::EtherCrabWireRead;
+use std::borrow::Cow;
+
+/// An error encountered while parsing an ESI file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EsiError {
+    /// The file is not well formed XML, is not valid in its declared encoding, or nests elements
+    /// too deeply. Contains the byte offset the error was found at.
+    Syntax(usize),
+    /// A required element or attribute is missing.
+    Missing(&'static str),
+    /// An element or attribute has an invalid value.
+    InvalidValue(&'static str),
+    /// A device has more items than can be stored, e.g. more than 16 entries in a single PDO, or
+    /// more than 255 distinct strings.
+    Capacity(&'static str),
+}
+
+impl core::fmt::Display for EsiError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EsiError::Syntax(offset) => write!(f, "invalid XML at byte {}", offset),
+            EsiError::Missing(item) => write!(f, "missing {}", item),
+            EsiError::InvalidValue(item) => write!(f, "invalid value for {}", item),
+            EsiError::Capacity(item) => write!(f, "too many {}", item),
+        }
+    }
+}
+
+impl std::error::Error for EsiError {}
+
+/// The character encoding of an ESI file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    /// ISO-8859-1, used by many vendors' ESI files.
+    Latin1,
+}
+
+impl Encoding {
+    /// UTF-8 encoded byte order mark.
+    const BOM: &'static [u8] = b"\xef\xbb\xbf";
+
+    /// Find the encoding of a document from its byte order mark or XML declaration, defaulting to
+    /// UTF-8.
+    fn detect(xml: &[u8]) -> Result<Self, EsiError> {
+        // A byte order mark takes precedence over the declaration
+        if xml.starts_with(Self::BOM) {
+            return Ok(Self::Utf8);
+        }
+
+        let Some(declaration) = xml.strip_prefix(b"<?xml") else {
+            return Ok(Self::Utf8);
+        };
+
+        let end = declaration
+            .windows(2)
+            .position(|window| window == b"?>")
+            .unwrap_or(declaration.len());
+
+        // The declaration is ASCII in every supported encoding
+        let declaration = String::from_utf8_lossy(&declaration[..end]);
+
+        let Some((_, rest)) = declaration.split_once("encoding") else {
+            return Ok(Self::Utf8);
+        };
+
+        let name = rest
+            .trim_start()
+            .strip_prefix('=')
+            .map(str::trim_start)
+            .and_then(|value| {
+                let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+
+                value[1..].split(quote).next()
+            })
+            .ok_or(EsiError::InvalidValue("?xml/@encoding"))?;
+
+        match name.to_ascii_uppercase().as_str() {
+            "UTF-8" => Ok(Self::Utf8),
+            "ISO-8859-1" | "LATIN1" => Ok(Self::Latin1),
+            _ => Err(EsiError::InvalidValue("?xml/@encoding")),
+        }
+    }
+
+    fn decode(self, xml: &[u8]) -> Result<Cow<'_, str>, EsiError> {
+        match self {
+            Self::Utf8 => core::str::from_utf8(xml)
+                .map(Cow::Borrowed)
+                .map_err(|e| EsiError::Syntax(e.valid_up_to())),
+            Self::Latin1 => Ok(Cow::Owned(xml.iter().copied().map(char::from).collect())),
+        }
+    }
+
+    /// Convert an offset into the decoded document back to a byte offset into the original.
+    fn byte_offset(self, decoded: &str, offset: usize) -> usize {
+        match self {
+            Self::Utf8 => offset,
+            Self::Latin1 => decoded
+                .get(..offset)
+                .map_or(offset, |decoded| decoded.chars().count()),
+        }
+    }
+}
+
+/// A parsed ESI file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EsiFile {
+    /// Vendor ID of every device in the file.
+    pub vendor_id: u32,
+
+    /// Every device described in the file.
+    pub devices: Vec<EsiDevice>,
+}
+
+impl EsiFile {
+    /// Parse an ESI XML document.
+    ///
+    /// The document is decoded as UTF-8, or as ISO-8859-1 if its XML declaration says so. A leading
+    /// byte order mark is ignored.
+    pub fn parse(xml: &[u8]) -> Result<Self, EsiError> {
+        let encoding = Encoding::detect(xml)?;
+        let decoded = encoding.decode(xml)?;
+
+        let root = Element::parse(&decoded)
+            .map_err(|offset| EsiError::Syntax(encoding.byte_offset(&decoded, offset)))?;
+
+        if root.name != "EtherCATInfo" {
+            return Err(EsiError::Missing("EtherCATInfo"));
+        }
+
+        let vendor_id = root
+            .child("Vendor")
+            .and_then(|vendor| vendor.child("Id"))
+            .ok_or(EsiError::Missing("Vendor/Id"))
+            .and_then(|id| number(id.trimmed_text(), "Vendor/Id"))?;
+
+        let descriptions = root
+            .child("Descriptions")
+            .ok_or(EsiError::Missing("Descriptions"))?;
+
+        // Group type and display name
+        let groups = descriptions
+            .child("Groups")
+            .into_iter()
+            .flat_map(|groups| groups.children_named("Group"))
+            .filter_map(|group| {
+                let ty = group.child("Type")?.trimmed_text();
+
+                Some((ty, name(group).unwrap_or(ty)))
+            })
+            .collect::<Vec<_>>();
+
+        let devices = descriptions
+            .child("Devices")
+            .into_iter()
+            .flat_map(|devices| devices.children_named("Device"))
+            .map(|device| EsiDevice::parse(vendor_id, &groups, device))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { vendor_id, devices })
+    }
+
+    /// Find the device with the same vendor ID, product ID and revision as `identity`.
+    ///
+    /// The serial number is ignored.
+    pub fn device(&self, identity: &SlaveIdentity) -> Option<&EsiDevice> {
+        if identity.vendor_id != self.vendor_id {
+            return None;
+        }
+
+        self.devices.iter().find(|device| {
+            let id = device.image.identity;
+
+            id.product_id == identity.product_id && id.revision == identity.revision
+        })
+    }
+}
+
+/// A single `Device` in an ESI file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EsiDevice {
+    /// The device's sync managers, FMMUs, PDOs, mailbox and DC configuration, in the same form as
+    /// they would be read from its EEPROM.
+    ///
+    /// The serial number is always zero.
+    pub image: SiiImage,
+
+    /// CoE init commands, from the `Mailbox/CoE/InitCmd` elements.
+    pub init_commands: Vec<EsiInitCommand>,
+}
+
+/// An SDO written during a state transition, from a `Mailbox/CoE/InitCmd` element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EsiInitCommand {
+    /// The transitions this command is written in, as `(from, to)` states.
+    pub transitions: Vec<(SlaveState, SlaveState)>,
+
+    /// Object index.
+    pub index: u16,
+
+    /// Object sub-index.
+    pub sub_index: u8,
+
+    /// Whether the object is written with complete access, starting from sub-index 1.
+    pub complete_access: bool,
+
+    /// Raw little-endian value to write.
+    pub data: Vec<u8>,
+
+    /// A description of the command.
+    pub comment: String,
+}
+
+impl EsiInitCommand {
+    /// Whether this command is written when going from state `from` to state `to`.
+    pub fn runs_on(&self, from: SlaveState, to: SlaveState) -> bool {
+        self.transitions.contains(&(from, to))
+    }
+}
+
+impl EsiDevice {
+    /// Parse a `Device` element.
+    fn parse(vendor_id: u32, groups: &[(&str, &str)], device: &Element) -> Result<Self, EsiError> {
+        let mut strings = Strings::default();
+
+        let ty = device.child("Type").ok_or(EsiError::Missing("Type"))?;
+
+        let identity = SlaveIdentity {
+            vendor_id,
+            product_id: optional_number(ty.attribute("ProductCode"), "Type/@ProductCode")?
+                .unwrap_or(0),
+            revision: optional_number(ty.attribute("RevisionNo"), "Type/@RevisionNo")?.unwrap_or(0),
+            serial: 0,
+        };
+
+        let order_string_idx = strings.index(ty.trimmed_text())?;
+        let name_string_idx = strings.index(name(device).unwrap_or_default())?;
+
+        let group_string_idx = match device.child("GroupType") {
+            Some(group) => {
+                let ty = group.trimmed_text();
+
+                let name = groups
+                    .iter()
+                    .find(|(group, _)| *group == ty)
+                    .map_or(ty, |(_, name)| name);
+
+                strings.index(name)?
+            }
+            None => 0,
+        };
+
+        let fmmus = device
+            .children_named("Fmmu")
+            .map(|fmmu| match fmmu.trimmed_text() {
+                "Outputs" => FmmuUsage::Outputs,
+                "Inputs" => FmmuUsage::Inputs,
+                "MBoxState" => FmmuUsage::SyncManagerStatus,
+                _ => FmmuUsage::Unused,
+            })
+            .collect();
+
+        let sync_managers = device
+            .children_named("Sm")
+            .map(sync_manager)
+            .collect::<Result<Vec<SyncManager>, _>>()?;
+
+        let rx_pdos = device
+            .children_named("RxPdo")
+            .map(|pdo| self::pdo(pdo, &mut strings))
+            .collect::<Result<_, _>>()?;
+
+        let tx_pdos = device
+            .children_named("TxPdo")
+            .map(|pdo| self::pdo(pdo, &mut strings))
+            .collect::<Result<_, _>>()?;
+
+        let dc_sync_modes = device
+            .child("Dc")
+            .into_iter()
+            .flat_map(|dc| dc.children_named("OpMode"))
+            .map(|mode| dc_sync_mode(mode, &mut strings))
+            .collect::<Result<_, _>>()?;
+
+        let mut mailbox = DefaultMailbox::default();
+
+        for sm in sync_managers.iter() {
+            match sm.usage_type {
+                SyncManagerType::MailboxWrite => {
+                    mailbox.slave_receive_offset = sm.start_addr;
+                    mailbox.slave_receive_size = sm.length;
+                }
+                SyncManagerType::MailboxRead => {
+                    mailbox.slave_send_offset = sm.start_addr;
+                    mailbox.slave_send_size = sm.length;
+                }
+                _ => (),
+            }
+        }
+
+        let mut flags = GeneralFlags::empty();
+        let mut coe_details = CoeDetails::empty();
+        let mut init_commands = Vec::new();
+
+        if let Some(mbox) = device.child("Mailbox") {
+            if optional_bool(mbox.attribute("DataLinkLayer"), "Mailbox/@DataLinkLayer")? {
+                flags |= GeneralFlags::MAILBOX_DLL;
+            }
+
+            for (name, protocol) in [
+                ("AoE", MailboxProtocols::AOE),
+                ("EoE", MailboxProtocols::EOE),
+                ("CoE", MailboxProtocols::COE),
+                ("FoE", MailboxProtocols::FOE),
+                ("SoE", MailboxProtocols::SOE),
+                ("VoE", MailboxProtocols::VOE),
+            ] {
+                if mbox.child(name).is_some() {
+                    mailbox.supported_protocols |= protocol;
+                }
+            }
+
+            if let Some(coe) = mbox.child("CoE") {
+                coe_details |= CoeDetails::ENABLE_SDO;
+
+                for (attribute, flag) in [
+                    ("SdoInfo", CoeDetails::ENABLE_SDO_INFO),
+                    ("PdoAssign", CoeDetails::ENABLE_PDO_ASSIGN),
+                    ("PdoConfig", CoeDetails::ENABLE_PDO_CONFIG),
+                    ("PdoUpload", CoeDetails::ENABLE_STARTUP_UPLOAD),
+                    ("CompleteAccess", CoeDetails::ENABLE_COMPLETE_ACCESS),
+                ] {
+                    if optional_bool(coe.attribute(attribute), "Mailbox/CoE")? {
+                        coe_details |= flag;
+                    }
+                }
+
+                init_commands = coe
+                    .children_named("InitCmd")
+                    .map(init_command)
+                    .collect::<Result<_, _>>()?;
+            }
+        }
+
+        let ports = device
+            .attribute("Physics")
+            .unwrap_or_default()
+            .chars()
+            .chain(core::iter::repeat(' '))
+            .take(4)
+            .map(|port| match port {
+                'Y' => PortStatus::Mii,
+                'K' => PortStatus::Ebus,
+                'H' => PortStatus::FastHotConnect,
+                _ => PortStatus::Unused,
+            })
+            .collect::<Vec<_>>();
+
+        let ebus_current = device
+            .child("Electrical")
+            .and_then(|electrical| electrical.child("EBusCurrent"))
+            .map(|current| number(current.trimmed_text(), "Electrical/EBusCurrent"))
+            .transpose()?
+            .unwrap_or(0);
+
+        let eeprom = device.child("Eeprom");
+
+        let mut config_area = [0u8; 16];
+
+        if let Some(config) = eeprom.and_then(|eeprom| eeprom.child("ConfigData")) {
+            let config = hex_binary(config.trimmed_text(), "Eeprom/ConfigData")?;
+
+            let len = config.len().min(14);
+
+            config_area[0..len].copy_from_slice(&config[0..len]);
+        }
+
+        config_area[14] = config_checksum(&config_area[0..14]);
+
+        let size = eeprom
+            .and_then(|eeprom| eeprom.child("ByteSize"))
+            .map(|size| number(size.trimmed_text(), "Eeprom/ByteSize"))
+            .transpose()?
+            .unwrap_or(0);
+
+        let bootstrap_mailbox = match eeprom.and_then(|eeprom| eeprom.child("BootStrap")) {
+            Some(bootstrap) => {
+                let raw = hex_binary(bootstrap.trimmed_text(), "Eeprom/BootStrap")?;
+
+                BootstrapMailbox::unpack_from_slice(&raw)
+                    .map_err(|_| EsiError::InvalidValue("Eeprom/BootStrap"))?
+            }
+            None => BootstrapMailbox::default(),
+        };
+
+        let general = SiiGeneral {
+            group_string_idx,
+            image_string_idx: 0,
+            order_string_idx,
+            name_string_idx,
+            coe_details,
+            foe_enabled: mailbox.supported_protocols.contains(MailboxProtocols::FOE),
+            eoe_enabled: mailbox.supported_protocols.contains(MailboxProtocols::EOE),
+            flags,
+            ebus_current,
+            ports: PortStatuses([ports[0], ports[1], ports[2], ports[3]]),
+            physical_memory_addr: 0,
+        };
+
+        Ok(Self {
+            image: SiiImage {
+                config_area,
+                size,
+                identity,
+                mailbox,
+                bootstrap_mailbox,
+                strings: strings.strings,
+                general: Some(general),
+                fmmus,
+                sync_managers,
+                tx_pdos,
+                rx_pdos,
+                dc_sync_modes,
+            },
+            init_commands,
+        })
+    }
+
+    /// Sync managers, FMMUs and PDOs to configure a SubDevice with instead of its EEPROM.
+    pub(crate) fn sii_override(&self) -> SiiOverride<'_> {
+        SiiOverride {
+            sync_managers: &self.image.sync_managers,
+            fmmus: &self.image.fmmus,
+            tx_pdos: &self.image.tx_pdos,
+            rx_pdos: &self.image.rx_pdos,
+        }
+    }
+
+    /// The device's name.
+    pub fn name(&self) -> &str {
+        self.image
+            .general
+            .and_then(|general| self.image.string(general.name_string_idx))
+            .unwrap_or_default()
+    }
+
+    /// The DC sync modes from the `Dc/OpMode` elements, which can be applied to a SubDevice with
+    /// [`SlaveRef::set_dc_sync_mode`](crate::SlaveRef::set_dc_sync_mode).
+    pub fn dc_sync_modes(&self) -> Vec<DcSyncMode> {
+        self.image
+            .dc_sync_modes
+            .iter()
+            .map(|mode| {
+                let mut name = heapless::String::new();
+
+                for c in self
+                    .image
+                    .string(mode.name_string_idx)
+                    .unwrap_or_default()
+                    .chars()
+                {
+                    if name.push(c).is_err() {
+                        break;
+                    }
+                }
+
+                DcSyncMode::new(*mode, name)
+            })
+            .collect()
+    }
+}
+
+/// String table built up while parsing a device.
+#[derive(Default)]
+struct Strings {
+    strings: Vec<String>,
+}
+
+impl Strings {
+    /// Get the 1-based index of a string, adding it to the table if it isn't already present.
+    ///
+    /// Empty strings have an index of zero.
+    fn index(&mut self, s: &str) -> Result<u8, EsiError> {
+        if s.is_empty() {
+            return Ok(0);
+        }
+
+        let index = match self.strings.iter().position(|string| string == s) {
+            Some(index) => index,
+            None => {
+                self.strings.push(s.to_string());
+
+                self.strings.len() - 1
+            }
+        };
+
+        u8::try_from(index + 1).map_err(|_| EsiError::Capacity("strings"))
+    }
+}
+
+/// The English name of an element, or its first name if it doesn't have one in English.
+fn name<'a>(element: &'a Element) -> Option<&'a str> {
+    element
+        .children_named("Name")
+        .find(|name| name.attribute("LcId") == Some("1033"))
+        .or_else(|| element.child("Name"))
+        .map(Element::trimmed_text)
+}
+
+fn sync_manager(sm: &Element) -> Result<SyncManager, EsiError> {
+    let control =
+        optional_number::<u8>(sm.attribute("ControlByte"), "Sm/@ControlByte")?.unwrap_or(0);
+
+    Ok(SyncManager {
+        start_addr: optional_number(sm.attribute("StartAddress"), "Sm/@StartAddress")?.unwrap_or(0),
+        length: optional_number(sm.attribute("DefaultSize"), "Sm/@DefaultSize")?.unwrap_or(0),
+        control: SyncManagerControl::unpack_from_slice(&[control])
+            .map_err(|_| EsiError::InvalidValue("Sm/@ControlByte"))?,
+        enable: SyncManagerEnable::from_bits_truncate(
+            optional_number(sm.attribute("Enable"), "Sm/@Enable")?.unwrap_or(0),
+        ),
+        usage_type: match sm.trimmed_text() {
+            "MBoxOut" => SyncManagerType::MailboxWrite,
+            "MBoxIn" => SyncManagerType::MailboxRead,
+            "Outputs" => SyncManagerType::ProcessDataWrite,
+            "Inputs" => SyncManagerType::ProcessDataRead,
+            _ => SyncManagerType::Unknown,
+        },
+    })
+}
+
+fn pdo(pdo: &Element, strings: &mut Strings) -> Result<Pdo, EsiError> {
+    let mut flags = PdoFlags::empty();
+
+    if optional_bool(pdo.attribute("Fixed"), "Pdo/@Fixed")? {
+        flags |= PdoFlags::PDO_FIXED_CONTENT;
+    }
+
+    if optional_bool(pdo.attribute("Mandatory"), "Pdo/@Mandatory")? {
+        flags |= PdoFlags::PDO_MANDATORY;
+    }
+
+    let mut entries = heapless::Vec::new();
+
+    for entry in pdo.children_named("Entry") {
+        let index = required_number(entry, "Index", "Pdo/Entry/Index")?;
+
+        let entry = PdoEntry {
+            index,
+            sub_index: entry
+                .child("SubIndex")
+                .map(|sub_index| number(sub_index.trimmed_text(), "Pdo/Entry/SubIndex"))
+                .transpose()?
+                .unwrap_or(0),
+            name_string_idx: strings.index(
+                entry
+                    .child("Name")
+                    .map(Element::trimmed_text)
+                    .unwrap_or_default(),
+            )?,
+            data_type: entry
+                .child("DataType")
+                .map_or(PrimitiveDataType::Unknown, |ty| {
+                    data_type(ty.trimmed_text())
+                }),
+            data_length_bits: required_number(entry, "BitLen", "Pdo/Entry/BitLen")?,
+            flags: 0,
+        };
+
+        entries
+            .push(entry)
+            .map_err(|_| EsiError::Capacity("PDO entries"))?;
+    }
+
+    Ok(Pdo {
+        index: required_number(pdo, "Index", "Pdo/Index")?,
+        num_entries: entries.len() as u8,
+        // Sync manager 0xff means the PDO is not assigned by default
+        sync_manager: optional_number(pdo.attribute("Sm"), "Pdo/@Sm")?.unwrap_or(0xff),
+        dc_sync: 0,
+        name_string_idx: strings.index(name(pdo).unwrap_or_default())?,
+        flags,
+        entries,
+    })
+}
+
+fn dc_sync_mode(mode: &Element, strings: &mut Strings) -> Result<SiiDcSync, EsiError> {
+    let cycle_time0 = mode.child("CycleTimeSync0");
+    let cycle_time1 = mode.child("CycleTimeSync1");
+
+    let factor = |element: Option<&Element>, item| {
+        optional_number::<i16>(
+            element.and_then(|element| element.attribute("Factor")),
+            item,
+        )
+        .map(Option::unwrap_or_default)
+    };
+
+    // Shift times are signed in ESI files, but are stored as their two's complement in the EEPROM
+    let shift = |name, item| {
+        mode.child(name)
+            .map(|shift| number::<i32>(shift.trimmed_text(), item))
+            .transpose()
+            .map(|shift| shift.unwrap_or(0) as u32)
+    };
+
+    Ok(SiiDcSync {
+        cycle_time0: cycle_time0
+            .map(|cycle| number(cycle.trimmed_text(), "Dc/OpMode/CycleTimeSync0"))
+            .transpose()?
+            .unwrap_or(0),
+        shift_time0: shift("ShiftTimeSync0", "Dc/OpMode/ShiftTimeSync0")?,
+        shift_time1: shift("ShiftTimeSync1", "Dc/OpMode/ShiftTimeSync1")?,
+        sync1_cycle_factor: factor(cycle_time1, "Dc/OpMode/CycleTimeSync1/@Factor")?,
+        assign_activate: required_number(mode, "AssignActivate", "Dc/OpMode/AssignActivate")?,
+        sync0_cycle_factor: factor(cycle_time0, "Dc/OpMode/CycleTimeSync0/@Factor")?,
+        name_string_idx: strings.index(
+            mode.child("Name")
+                .map(Element::trimmed_text)
+                .unwrap_or_default(),
+        )?,
+        desc_string_idx: strings.index(
+            mode.child("Desc")
+                .map(Element::trimmed_text)
+                .unwrap_or_default(),
+        )?,
+    })
+}
+
+fn init_command(cmd: &Element) -> Result<EsiInitCommand, EsiError> {
+    let transitions = cmd
+        .children_named("Transition")
+        .map(|transition| {
+            let state = |c| match c {
+                b'I' => Ok(SlaveState::Init),
+                b'P' => Ok(SlaveState::PreOp),
+                b'B' => Ok(SlaveState::Bootstrap),
+                b'S' => Ok(SlaveState::SafeOp),
+                b'O' => Ok(SlaveState::Op),
+                _ => Err(EsiError::InvalidValue("Mailbox/CoE/InitCmd/Transition")),
+            };
+
+            match transition.trimmed_text().as_bytes() {
+                [from, to] => Ok((state(*from)?, state(*to)?)),
+                _ => Err(EsiError::InvalidValue("Mailbox/CoE/InitCmd/Transition")),
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(EsiInitCommand {
+        transitions,
+        index: required_number(cmd, "Index", "Mailbox/CoE/InitCmd/Index")?,
+        sub_index: cmd
+            .child("SubIndex")
+            .map(|sub_index| number(sub_index.trimmed_text(), "Mailbox/CoE/InitCmd/SubIndex"))
+            .transpose()?
+            .unwrap_or(0),
+        complete_access: optional_bool(
+            cmd.attribute("CompleteAccess"),
+            "Mailbox/CoE/InitCmd/@CompleteAccess",
+        )?,
+        data: cmd
+            .child("Data")
+            .map(|data| hex_binary(data.trimmed_text(), "Mailbox/CoE/InitCmd/Data"))
+            .transpose()?
+            .unwrap_or_default(),
+        comment: cmd
+            .child("Comment")
+            .map(|comment| comment.trimmed_text().to_string())
+            .unwrap_or_default(),
+    })
+}
+
+/// ESI data type name, defined in ETG.2000 Table 15.
+fn data_type(name: &str) -> PrimitiveDataType {
+    match name {
+        "BOOL" => PrimitiveDataType::Bool,
+        "BYTE" => PrimitiveDataType::Byte,
+        "WORD" => PrimitiveDataType::Word,
+        "DWORD" => PrimitiveDataType::DWord,
+        "BIT1" => PrimitiveDataType::Bit1,
+        "BIT2" => PrimitiveDataType::Bit2,
+        "BIT3" => PrimitiveDataType::Bit3,
+        "BIT4" => PrimitiveDataType::Bit4,
+        "BIT5" => PrimitiveDataType::Bit5,
+        "BIT6" => PrimitiveDataType::Bit6,
+        "BIT7" => PrimitiveDataType::Bit7,
+        "BIT8" => PrimitiveDataType::Bit8,
+        "BIT9" => PrimitiveDataType::Bit9,
+        "BIT10" => PrimitiveDataType::Bit10,
+        "BIT11" => PrimitiveDataType::Bit11,
+        "BIT12" => PrimitiveDataType::Bit12,
+        "BIT13" => PrimitiveDataType::Bit13,
+        "BIT14" => PrimitiveDataType::Bit14,
+        "BIT15" => PrimitiveDataType::Bit15,
+        "BIT16" => PrimitiveDataType::Bit16,
+        "BITARR8" => PrimitiveDataType::BitArr8,
+        "BITARR16" => PrimitiveDataType::BitArr16,
+        "BITARR32" => PrimitiveDataType::BitArr32,
+        "SINT" => PrimitiveDataType::I8,
+        "INT" => PrimitiveDataType::I16,
+        "INT24" => PrimitiveDataType::I24,
+        "DINT" => PrimitiveDataType::I32,
+        "INT40" => PrimitiveDataType::I40,
+        "INT48" => PrimitiveDataType::I48,
+        "INT56" => PrimitiveDataType::I56,
+        "LINT" => PrimitiveDataType::I64,
+        "USINT" => PrimitiveDataType::U8,
+        "UINT" => PrimitiveDataType::U16,
+        "UINT24" => PrimitiveDataType::U24,
+        "UDINT" => PrimitiveDataType::U32,
+        "UINT40" => PrimitiveDataType::U40,
+        "UINT48" => PrimitiveDataType::U48,
+        "UINT56" => PrimitiveDataType::U56,
+        "ULINT" => PrimitiveDataType::U64,
+        "REAL" => PrimitiveDataType::F32,
+        "LREAL" => PrimitiveDataType::F64,
+        _ => PrimitiveDataType::Unknown,
+    }
+}
+
+/// Parse an ESI `HexDecValue`, either decimal or hexadecimal with a `#x` prefix.
+fn number<T>(value: &str, item: &'static str) -> Result<T, EsiError>
+where
+    T: TryFrom<i64>,
+{
+    let value = value.trim();
+
+    let number = match value.strip_prefix("#x") {
+        Some(hex) => u64::from_str_radix(hex, 16)
+            .ok()
+            .and_then(|value| i64::try_from(value).ok()),
+        None => value.parse::<i64>().ok(),
+    };
+
+    number
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or(EsiError::InvalidValue(item))
+}
+
+fn optional_number<T>(value: Option<&str>, item: &'static str) -> Result<Option<T>, EsiError>
+where
+    T: TryFrom<i64>,
+{
+    value.map(|value| number(value, item)).transpose()
+}
+
+fn required_number<T>(element: &Element, child: &str, item: &'static str) -> Result<T, EsiError>
+where
+    T: TryFrom<i64>,
+{
+    element
+        .child(child)
+        .ok_or(EsiError::Missing(item))
+        .and_then(|value| number(value.trimmed_text(), item))
+}
+
+/// Parse an optional `xs:boolean`, which is `false` if not present.
+fn optional_bool(value: Option<&str>, item: &'static str) -> Result<bool, EsiError> {
+    match value.map(str::trim) {
+        None | Some("0" | "false") => Ok(false),
+        Some("1" | "true") => Ok(true),
+        Some(_) => Err(EsiError::InvalidValue(item)),
+    }
+}
+
+/// Parse an ESI `HexBinary` value.
+fn hex_binary(value: &str, item: &'static str) -> Result<Vec<u8>, EsiError> {
+    let value = value.as_bytes();
+
+    if value.len() % 2 != 0 {
+        return Err(EsiError::InvalidValue(item));
+    }
+
+    value
+        .chunks_exact(2)
+        .map(|byte| {
+            core::str::from_utf8(byte)
+                .ok()
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or(EsiError::InvalidValue(item))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{eeprom::file_reader::EepromFile, sii::esi::to_xml, sii::SlaveEeprom};
+
+    const ESI: &str = r##"<?xml version="1.0" encoding="ISO-8859-1"?>
+<EtherCATInfo Version="1.6">
+  <Vendor>
+    <Id>2</Id>
+    <Name>Beckhoff Automation GmbH &amp; Co. KG</Name>
+  </Vendor>
+  <Descriptions>
+    <Groups>
+      <Group>
+        <Type>AnaIn</Type>
+        <Name LcId="1031">Analogeingänge</Name>
+        <Name LcId="1033">Analog Input Terminals</Name>
+      </Group>
+    </Groups>
+    <Devices>
+      <Device Physics="YY">
+        <Type ProductCode="#x0c1e3052" RevisionNo="#x00140000">EL3102</Type>
+        <Name LcId="1033"><![CDATA[EL3102 2Ch. Ana. Input +/-10V, Diff.]]></Name>
+        <GroupType>AnaIn</GroupType>
+        <Fmmu>Outputs</Fmmu>
+        <Fmmu>Inputs</Fmmu>
+        <Fmmu>MBoxState</Fmmu>
+        <Sm MinSize="34" MaxSize="128" DefaultSize="128" StartAddress="#x1000" ControlByte="#x26" Enable="1">MBoxOut</Sm>
+        <Sm MinSize="34" MaxSize="128" DefaultSize="128" StartAddress="#x1080" ControlByte="#x22" Enable="1">MBoxIn</Sm>
+        <Sm StartAddress="#x1100" ControlByte="#x24" Enable="0">Outputs</Sm>
+        <Sm DefaultSize="6" StartAddress="#x1180" ControlByte="#x20" Enable="1">Inputs</Sm>
+        <TxPdo Fixed="1" Mandatory="1" Sm="3">
+          <Index>#x1a00</Index>
+          <Name>AI Inputs Channel 1</Name>
+          <Entry>
+            <Index>#x3101</Index>
+            <SubIndex>1</SubIndex>
+            <BitLen>8</BitLen>
+            <Name>Status</Name>
+            <DataType>USINT</DataType>
+          </Entry>
+          <Entry>
+            <Index>#x3101</Index>
+            <SubIndex>2</SubIndex>
+            <BitLen>16</BitLen>
+            <Name>Value</Name>
+            <DataType>INT</DataType>
+          </Entry>
+        </TxPdo>
+        <TxPdo Fixed="1">
+          <Index>#x1a01</Index>
+          <Name>AI Inputs Channel 2</Name>
+          <Entry>
+            <Index>#x0</Index>
+            <BitLen>24</BitLen>
+          </Entry>
+        </TxPdo>
+        <Mailbox DataLinkLayer="true">
+          <CoE SdoInfo="true" PdoAssign="false" PdoConfig="false">
+            <InitCmd>
+              <Transition>PS</Transition>
+              <Transition>SO</Transition>
+              <Index>#x8000</Index>
+              <SubIndex>6</SubIndex>
+              <Data>0100</Data>
+              <Comment>Enable filter</Comment>
+            </InitCmd>
+          </CoE>
+        </Mailbox>
+        <Dc>
+          <OpMode>
+            <Name>DcSync</Name>
+            <Desc>DC-Synchron</Desc>
+            <AssignActivate>#x300</AssignActivate>
+            <CycleTimeSync0 Factor="1">0</CycleTimeSync0>
+            <ShiftTimeSync0>-1000</ShiftTimeSync0>
+          </OpMode>
+        </Dc>
+        <Eeprom>
+          <ByteSize>2048</ByteSize>
+          <ConfigData>080E00CCCCCC</ConfigData>
+          <BootStrap>0010800080108000</BootStrap>
+        </Eeprom>
+      </Device>
+    </Devices>
+  </Descriptions>
+</EtherCATInfo>
+"##;
+
+    /// Encode `s` as ISO-8859-1, the encoding declared by [`ESI`].
+    fn latin1(s: &str) -> Vec<u8> {
+        s.chars()
+            .map(|c| u8::try_from(c).expect("not a Latin-1 character"))
+            .collect()
+    }
+
+    #[test]
+    fn parse_device() {
+        let esi = EsiFile::parse(&latin1(ESI)).expect("parse");
+
+        assert_eq!(esi.vendor_id, 2);
+        assert_eq!(esi.devices.len(), 1);
+
+        let identity = SlaveIdentity {
+            vendor_id: 2,
+            product_id: 0x0c1e3052,
+            revision: 0x00140000,
+            serial: 1234,
+        };
+
+        let device = esi.device(&identity).expect("device");
+        let image = &device.image;
+        let general = image.general.expect("general");
+
+        assert_eq!(device.name(), "EL3102 2Ch. Ana. Input +/-10V, Diff.");
+        assert_eq!(image.string(general.order_string_idx), Some("EL3102"));
+        assert_eq!(
+            image.string(general.group_string_idx),
+            Some("Analog Input Terminals")
+        );
+        assert_eq!(general.ports.0[1], PortStatus::Mii);
+        assert_eq!(general.ports.0[2], PortStatus::Unused);
+        assert!(general.flags.contains(GeneralFlags::MAILBOX_DLL));
+        assert_eq!(
+            general.coe_details,
+            CoeDetails::ENABLE_SDO | CoeDetails::ENABLE_SDO_INFO
+        );
+
+        assert_eq!(
+            image.fmmus,
+            [
+                FmmuUsage::Outputs,
+                FmmuUsage::Inputs,
+                FmmuUsage::SyncManagerStatus
+            ]
+        );
+        assert_eq!(image.sync_managers.len(), 4);
+        assert_eq!(image.sync_managers[3].start_addr, 0x1180);
+        assert_eq!(image.sync_managers[3].length, 6);
+        assert_eq!(
+            image.sync_managers[3].usage_type,
+            SyncManagerType::ProcessDataRead
+        );
+        assert!(!image.sync_managers[2]
+            .enable
+            .contains(SyncManagerEnable::ENABLE));
+
+        assert_eq!(image.mailbox.slave_receive_offset, 0x1000);
+        assert_eq!(image.mailbox.slave_send_size, 128);
+        assert_eq!(image.mailbox.supported_protocols, MailboxProtocols::COE);
+
+        assert!(image.rx_pdos.is_empty());
+        assert_eq!(image.tx_pdos.len(), 2);
+        assert_eq!(image.tx_pdos[0].sync_manager, 3);
+        assert_eq!(image.tx_pdos[0].bit_len(), 24);
+        assert_eq!(
+            image.tx_pdos[0].entries[1].data_type,
+            PrimitiveDataType::I16
+        );
+        assert_eq!(
+            image.string(image.tx_pdos[0].entries[1].name_string_idx),
+            Some("Value")
+        );
+        assert_eq!(image.tx_pdos[1].sync_manager, 0xff);
+        assert_eq!(image.tx_pdos[1].entries[0].index, 0);
+
+        assert_eq!(
+            device.init_commands,
+            [EsiInitCommand {
+                transitions: vec![
+                    (SlaveState::PreOp, SlaveState::SafeOp),
+                    (SlaveState::SafeOp, SlaveState::Op)
+                ],
+                index: 0x8000,
+                sub_index: 6,
+                complete_access: false,
+                data: vec![0x01, 0x00],
+                comment: "Enable filter".to_string(),
+            }]
+        );
+        assert!(device.init_commands[0].runs_on(SlaveState::PreOp, SlaveState::SafeOp));
+        assert!(!device.init_commands[0].runs_on(SlaveState::Init, SlaveState::PreOp));
+
+        let modes = device.dc_sync_modes();
+
+        assert_eq!(modes.len(), 1);
+        assert_eq!(modes[0].name, "DcSync");
+        assert_eq!(modes[0].assign_activate, 0x0300);
+        assert_eq!(image.dc_sync_modes[0].shift_time0, -1000i32 as u32);
+
+        assert_eq!(image.size, 2048);
+        assert_eq!(
+            &image.config_area[0..6],
+            &[0x08, 0x0e, 0x00, 0xcc, 0xcc, 0xcc]
+        );
+        assert_eq!(
+            image.config_area[14],
+            config_checksum(&image.config_area[0..14])
+        );
+        assert_eq!(image.bootstrap_mailbox.slave_receive_offset, 0x1000);
+        assert_eq!(image.bootstrap_mailbox.slave_send_size, 0x0080);
+    }
+
+    #[test]
+    fn no_matching_device() {
+        let esi = EsiFile::parse(&latin1(ESI)).expect("parse");
+
+        let identity = SlaveIdentity {
+            vendor_id: 2,
+            product_id: 0x0c1e3052,
+            revision: 0x00150000,
+            serial: 0,
+        };
+
+        assert_eq!(esi.device(&identity), None);
+        assert_eq!(
+            esi.device(&SlaveIdentity {
+                vendor_id: 3,
+                ..identity
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_files() {
+        assert_eq!(EsiFile::parse(b"<EtherCATInfo>"), Err(EsiError::Syntax(14)));
+        assert_eq!(
+            EsiFile::parse(b"<Other />"),
+            Err(EsiError::Missing("EtherCATInfo"))
+        );
+        assert_eq!(
+            EsiFile::parse(b"<EtherCATInfo><Vendor><Id>x</Id></Vendor></EtherCATInfo>"),
+            Err(EsiError::InvalidValue("Vendor/Id"))
+        );
+        assert_eq!(
+            EsiFile::parse(b"<?xml version=\"1.0\" encoding=\"UTF-16\"?><EtherCATInfo />"),
+            Err(EsiError::InvalidValue("?xml/@encoding"))
+        );
+        assert_eq!(
+            EsiFile::parse(&("<a>".repeat(100) + &"</a>".repeat(100)).into_bytes()),
+            Err(EsiError::Syntax(192))
+        );
+    }
+
+    #[test]
+    fn encodings() {
+        let expected = EsiFile::parse(&latin1(ESI)).expect("parse Latin-1");
+
+        let utf8 = ESI.replace(r#"encoding="ISO-8859-1""#, r#"encoding="UTF-8""#);
+
+        assert_eq!(EsiFile::parse(utf8.as_bytes()), Ok(expected.clone()));
+
+        let bom = [Encoding::BOM, ESI.as_bytes()].concat();
+
+        assert_eq!(EsiFile::parse(&bom), Ok(expected));
+
+        // Latin-1 bytes are not valid UTF-8
+        let offset = ESI.find('ä').expect("umlaut");
+
+        assert_eq!(
+            EsiFile::parse(&latin1(&utf8)),
+            Err(EsiError::Syntax(offset - 5))
+        );
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        let image = SiiImage::read(&SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex")))
+            .await
+            .expect("read image");
+
+        let esi = EsiFile::parse(to_xml(&image).as_bytes()).expect("parse");
+
+        let device = esi.device(&image.identity).expect("device");
+
+        assert_eq!(device.image.sync_managers, image.sync_managers);
+        // Unused FMMUs aren't written to the ESI file
+        assert_eq!(
+            device.image.fmmus,
+            image
+                .fmmus
+                .iter()
+                .copied()
+                .filter(|fmmu| *fmmu != FmmuUsage::Unused)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(device.image.mailbox, image.mailbox);
+        assert_eq!(device.image.config_area[0..14], image.config_area[0..14]);
+        assert_eq!(device.image.dc_sync_modes.len(), image.dc_sync_modes.len());
+        assert_eq!(device.image.tx_pdos.len(), image.tx_pdos.len());
+        assert_eq!(device.image.rx_pdos.len(), image.rx_pdos.len());
+
+        for (parsed, pdo) in device.image.tx_pdos.iter().zip(image.tx_pdos.iter()) {
+            assert_eq!(parsed.index, pdo.index);
+            assert_eq!(parsed.bit_len(), pdo.bit_len());
+        }
+    }
+
+    #[test]
+    fn negative_shift_round_trip() {
+        let esi = EsiFile::parse(&latin1(ESI)).expect("parse");
+
+        let identity = SlaveIdentity {
+            vendor_id: 2,
+            product_id: 0x0c1e3052,
+            revision: 0x00140000,
+            serial: 1234,
+        };
+
+        let image = &esi.device(&identity).expect("device").image;
+
+        assert_eq!(image.dc_sync_modes[0].shift_time0, -1000i32 as u32);
+
+        let xml = to_xml(image);
+
+        assert!(xml.contains("<ShiftTimeSync0>-1000</ShiftTimeSync0>"));
+
+        let esi = EsiFile::parse(xml.as_bytes()).expect("parse round trip");
+
+        assert_eq!(
+            esi.device(&identity).expect("device").image.dc_sync_modes,
+            image.dc_sync_modes
+        );
+    }
+}
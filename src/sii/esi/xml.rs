@@ -0,0 +1,345 @@
+//! A minimal, non-validating XML reader, sufficient for ESI files.
+//!
+//! Processing instructions, comments, `DOCTYPE` declarations and namespaces are ignored. Character
+//! and predefined entity references are decoded, and CDATA sections are treated as text.
+
+use std::borrow::Cow;
+
+/// The maximum depth of nested elements.
+///
+/// Elements are parsed recursively, so this bounds the stack used by malformed or malicious
+/// documents. ESI files nest no more than around 10 levels deep.
+const MAX_DEPTH: usize = 64;
+
+/// An XML element, its attributes, text content and child elements.
+#[derive(Debug, Default)]
+pub(crate) struct Element<'a> {
+    pub name: &'a str,
+    pub attributes: Vec<(&'a str, Cow<'a, str>)>,
+    pub children: Vec<Element<'a>>,
+    /// All text directly inside this element, concatenated.
+    pub text: String,
+}
+
+impl<'a> Element<'a> {
+    /// Parse a document, returning its root element.
+    ///
+    /// A leading byte order mark is skipped. Documents with elements nested more than
+    /// [`MAX_DEPTH`] levels deep are rejected.
+    ///
+    /// On error, returns the byte offset into `xml` where parsing failed.
+    pub fn parse(xml: &'a str) -> Result<Self, usize> {
+        let pos = if xml.starts_with('\u{feff}') {
+            '\u{feff}'.len_utf8()
+        } else {
+            0
+        };
+
+        let mut parser = Parser { xml, pos };
+
+        parser.skip_misc()?;
+
+        let root = parser.element(0)?;
+
+        parser.skip_misc()?;
+
+        if parser.pos != xml.len() {
+            return Err(parser.pos);
+        }
+
+        Ok(root)
+    }
+
+    /// Find the value of an attribute.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| *attribute == name)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    /// Find the first child element with the given name.
+    pub fn child(&self, name: &str) -> Option<&Element<'a>> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Iterate over every child element with the given name.
+    pub fn children_named<'e>(
+        &'e self,
+        name: &'e str,
+    ) -> impl Iterator<Item = &'e Element<'a>> + 'e {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Text content with leading and trailing whitespace removed.
+    pub fn trimmed_text(&self) -> &str {
+        self.text.trim()
+    }
+}
+
+struct Parser<'a> {
+    xml: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.xml[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, s: &str) -> Result<(), usize> {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+
+            Ok(())
+        } else {
+            Err(self.pos)
+        }
+    }
+
+    /// Skip to just after the next occurrence of `end`.
+    fn skip_past(&mut self, end: &str) -> Result<(), usize> {
+        let len = self.rest().find(end).ok_or(self.pos)?;
+
+        self.pos += len + end.len();
+
+        Ok(())
+    }
+
+    /// Skip whitespace, the XML declaration, processing instructions, comments and `DOCTYPE`
+    /// declarations.
+    fn skip_misc(&mut self) -> Result<(), usize> {
+        loop {
+            self.skip_whitespace();
+
+            let rest = self.rest();
+
+            if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                self.skip_past(">")?;
+            } else {
+                break Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, usize> {
+        let rest = self.rest();
+
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+
+        if len == 0 {
+            return Err(self.pos);
+        }
+
+        self.pos += len;
+
+        Ok(&rest[..len])
+    }
+
+    /// Parse an element nested `depth` levels below the root.
+    fn element(&mut self, depth: usize) -> Result<Element<'a>, usize> {
+        if depth >= MAX_DEPTH {
+            return Err(self.pos);
+        }
+
+        self.expect("<")?;
+
+        let mut element = Element {
+            name: self.name()?,
+            ..Element::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+
+                return Ok(element);
+            }
+
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+
+                break;
+            }
+
+            let name = self.name()?;
+
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+
+            let quote = match self.rest().chars().next() {
+                Some(quote @ ('"' | '\'')) => quote,
+                _ => return Err(self.pos),
+            };
+
+            self.pos += 1;
+
+            let len = self.rest().find(quote).ok_or(self.pos)?;
+            let value = unescape(&self.rest()[..len]).ok_or(self.pos)?;
+
+            self.pos += len + 1;
+
+            element.attributes.push((name, value));
+        }
+
+        loop {
+            let rest = self.rest();
+
+            if rest.starts_with("</") {
+                self.pos += 2;
+
+                if self.name()? != element.name {
+                    return Err(self.pos);
+                }
+
+                self.skip_whitespace();
+                self.expect(">")?;
+
+                break Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                let start = self.pos + "<![CDATA[".len();
+
+                self.skip_past("]]>")?;
+
+                element
+                    .text
+                    .push_str(&self.xml[start..self.pos - "]]>".len()]);
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                let child = self.element(depth + 1)?;
+
+                element.children.push(child);
+            } else {
+                let len = rest.find('<').ok_or(self.xml.len())?;
+                let text = unescape(&rest[..len]).ok_or(self.pos)?;
+
+                element.text.push_str(&text);
+
+                self.pos += len;
+            }
+        }
+    }
+}
+
+/// Decode entity and character references.
+fn unescape(s: &str) -> Option<Cow<'_, str>> {
+    if !s.contains('&') {
+        return Some(Cow::Borrowed(s));
+    }
+
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+
+        rest = &rest[start + 1..];
+
+        let end = rest.find(';')?;
+
+        let c = match &rest[..end] {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            reference => {
+                let code = reference.strip_prefix('#')?;
+
+                let code = match code.strip_prefix('x') {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+
+                char::from_u32(code)?
+            }
+        };
+
+        out.push(c);
+
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+
+    Some(Cow::Owned(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_document() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- Comment -->
+            <Root Version='1.6' Name="a &amp; b">
+                <Child Index="1">First</Child>
+                <Child Index="2" />
+                <Text>x &lt; y <![CDATA[<z>]]> &#x41;&#66;</Text>
+            </Root>
+        "#;
+
+        let root = Element::parse(xml).expect("parse");
+
+        assert_eq!(root.name, "Root");
+        assert_eq!(root.attribute("Version"), Some("1.6"));
+        assert_eq!(root.attribute("Name"), Some("a & b"));
+        assert_eq!(root.attribute("Missing"), None);
+        assert_eq!(root.children_named("Child").count(), 2);
+        assert_eq!(
+            root.child("Child").map(Element::trimmed_text),
+            Some("First")
+        );
+        assert_eq!(
+            root.child("Text").map(Element::trimmed_text),
+            Some("x < y <z> AB")
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Element::parse("<a><b></a>").map(|_| ()), Err(9));
+        assert!(Element::parse("<a>").is_err());
+        assert!(Element::parse("<a b=c />").is_err());
+        assert!(Element::parse("<a>&bad;</a>").is_err());
+        assert!(Element::parse("<a /><b />").is_err());
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let root = Element::parse("\u{feff}<?xml version=\"1.0\"?><a>b</a>").expect("parse");
+
+        assert_eq!(root.name, "a");
+        assert_eq!(root.trimmed_text(), "b");
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let nested = |depth: usize| "<a>".repeat(depth) + &"</a>".repeat(depth);
+
+        assert!(Element::parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(
+            Element::parse(&nested(MAX_DEPTH + 1)).map(|_| ()),
+            Err(MAX_DEPTH * 3)
+        );
+    }
+}
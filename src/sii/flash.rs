@@ -0,0 +1,225 @@
+use crate::{
+    eeprom::{
+        config_checksum, device_reader::DeviceEeprom, types::SiiOwner, ChunkReader,
+        EepromDataProvider,
+    },
+    error::{EepromError, Error},
+    fmt, Slave, SlaveRef,
+};
+use core::ops::Deref;
+use embedded_io_async::Read;
+
+/// Write an entire EEPROM image, e.g. a file saved from another SubDevice of the same type, to a
+/// SubDevice's EEPROM.
+///
+/// The image is checked before anything is written. It must be an even number of bytes long, at
+/// least as long as the configuration area in WORDs `0x0000` to `0x0007`, no larger than the
+/// SubDevice's EEPROM, and have a valid configuration area checksum.
+///
+/// The image is then written one WORD at a time, skipping WORDs that already hold the right value.
+/// Each written WORD is read back to check it was stored correctly. Once the whole image is
+/// written, the SubDevice reloads its configuration area from the EEPROM.
+///
+/// `progress` is called after each WORD with the number of bytes written or checked so far and the
+/// total length of the image.
+///
+/// EEPROM writes are slow on some devices, so the [`eeprom`](crate::Timeouts::eeprom) timeout may
+/// need to be increased.
+///
+/// # Errors
+///
+/// Returns [`EepromError::InvalidImage`] if the image fails any of the checks above,
+/// [`EepromError::Write`] if the SubDevice rejects a write, e.g. because its EEPROM is write
+/// protected, and [`EepromError::Verify`] if a WORD read back from the EEPROM does not match the
+/// image.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethercrab::{error::Error, sii, Client, SlaveGroup};
+///
+/// # async fn run(client: &Client<'_>, group: &mut SlaveGroup<8, 64>) -> Result<(), Error> {
+/// let image = std::fs::read("el2828.bin").expect("read EEPROM image");
+///
+/// let slave = group.slave(client, 0)?;
+///
+/// sii::flash_eeprom(&slave, &image, |written, total| {
+///     println!("{} / {} bytes", written, total);
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn flash_eeprom<S>(
+    subdevice: &SlaveRef<'_, S>,
+    image: &[u8],
+    mut progress: impl FnMut(usize, usize),
+) -> Result<(), Error>
+where
+    S: Deref<Target = Slave>,
+{
+    let configured_address = subdevice.configured_address();
+
+    let size = subdevice.eeprom().size().await?;
+
+    if image.len() % 2 != 0 || image.len() < 16 || image.len() > size {
+        fmt::error!(
+            "Slave {:#06x} EEPROM image of {} bytes is invalid for a {} byte EEPROM",
+            configured_address,
+            image.len(),
+            size
+        );
+
+        return Err(Error::Eeprom(EepromError::InvalidImage));
+    }
+
+    let checksum = config_checksum(&image[0..14]);
+
+    if image[14] != checksum {
+        fmt::error!(
+            "Slave {:#06x} EEPROM image has configuration area checksum {:#04x}, expected {:#04x}",
+            configured_address,
+            image[14],
+            checksum
+        );
+
+        return Err(Error::Eeprom(EepromError::InvalidImage));
+    }
+
+    subdevice.set_eeprom_mode(SiiOwner::Master).await?;
+
+    let mut eeprom = DeviceEeprom::new(subdevice.client, configured_address);
+
+    for (word, chunk) in (0u32..).zip(image.chunks_exact(2)) {
+        let value = u16::from_le_bytes([chunk[0], chunk[1]]);
+
+        if read_word(&eeprom, word).await? != value {
+            eeprom.write_word(word, value).await?;
+
+            let actual = read_word(&eeprom, word).await?;
+
+            if actual != value {
+                fmt::error!(
+                    "Slave {:#06x} EEPROM WORD {:#06x} is {:#06x} after writing {:#06x}",
+                    configured_address,
+                    word,
+                    actual,
+                    value
+                );
+
+                return Err(Error::Eeprom(EepromError::Verify));
+            }
+        }
+
+        progress((word as usize + 1) * 2, image.len());
+    }
+
+    eeprom.reload().await
+}
+
+async fn read_word(eeprom: &DeviceEeprom<'_>, word: u32) -> Result<u16, Error> {
+    let mut buf = [0u8; 2];
+
+    ChunkReader::new(eeprom.clone(), word, 1)
+        .read_exact(&mut buf)
+        .await?;
+
+    Ok(u16::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eeprom::types::MailboxProtocols, slave::mock::MockSubDevice, ClientConfig, Timeouts,
+    };
+
+    /// A 256 byte image with a valid configuration area.
+    fn image() -> Vec<u8> {
+        // EL2004 configuration area
+        let mut image = vec![
+            0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, //
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xd8, 0x00, //
+        ];
+
+        image.extend((16..256).map(|i| i as u8));
+
+        // EEPROM size in kibibits, minus one
+        image[0x3e * 2] = 1;
+        image[0x3e * 2 + 1] = 0;
+
+        image
+    }
+
+    #[tokio::test]
+    async fn flash() {
+        let (client, mock) = MockSubDevice::start(
+            Timeouts::default(),
+            ClientConfig::default(),
+            |_mailbox_type, _request| Vec::new(),
+        );
+
+        let mut existing = image();
+
+        existing[16..].fill(0xff);
+
+        mock.set_eeprom(existing);
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let mut reports = Vec::new();
+
+        assert_eq!(
+            flash_eeprom(&slave, &image(), |written, total| reports
+                .push((written, total)))
+            .await,
+            Ok(())
+        );
+
+        assert_eq!(mock.eeprom(), image());
+        assert_eq!(reports.len(), 128);
+        assert_eq!(reports.first(), Some(&(2, 256)));
+        assert_eq!(reports.last(), Some(&(256, 256)));
+    }
+
+    #[tokio::test]
+    async fn invalid_image() {
+        let (client, mock) = MockSubDevice::start(
+            Timeouts::default(),
+            ClientConfig::default(),
+            |_mailbox_type, _request| Vec::new(),
+        );
+
+        mock.set_eeprom(image());
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let mut bad_checksum = image();
+
+        bad_checksum[14] ^= 0xff;
+
+        let mut too_long = image();
+
+        too_long.extend([0u8; 2]);
+
+        for bad in [&image()[0..15], &image()[0..14], &too_long, &bad_checksum] {
+            assert_eq!(
+                flash_eeprom(&slave, bad, |_, _| ()).await,
+                Err(Error::Eeprom(EepromError::InvalidImage))
+            );
+        }
+
+        mock.write_protect_eeprom();
+
+        let mut changed = image();
+
+        changed[100] = 0;
+
+        assert_eq!(
+            flash_eeprom(&slave, &changed, |_, _| ()).await,
+            Err(Error::Eeprom(EepromError::Write))
+        );
+    }
+}
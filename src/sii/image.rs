@@ -0,0 +1,131 @@
+use super::{
+    BootstrapMailbox, DefaultMailbox, FmmuUsage, Pdo, SiiDcSync, SiiGeneral, SlaveEeprom,
+    SyncManager,
+};
+use crate::{
+    eeprom::EepromDataProvider,
+    error::{EepromError, Error},
+    SlaveIdentity,
+};
+
+/// The entire contents of a SubDevice's SII EEPROM, read into memory.
+///
+/// This is useful for tools that inspect or convert EEPROM contents, e.g. into an ESI file with
+/// [`esi::to_xml`](crate::sii::esi::to_xml).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiiImage {
+    /// The raw configuration area in WORDs `0x0000` to `0x0007`, including the checksum.
+    pub config_area: [u8; 16],
+
+    /// EEPROM size in bytes.
+    pub size: usize,
+
+    /// Vendor ID, product code, revision and serial number.
+    pub identity: SlaveIdentity,
+
+    /// Standard mailbox configuration.
+    pub mailbox: DefaultMailbox,
+
+    /// Mailbox configuration used in BOOT.
+    pub bootstrap_mailbox: BootstrapMailbox,
+
+    /// Every string in the "Strings" category, in order.
+    ///
+    /// Use [`string`](SiiImage::string) to look up a string by its 1-based index.
+    pub strings: Vec<String>,
+
+    /// The "General" category, if present.
+    pub general: Option<SiiGeneral>,
+
+    /// FMMU usage from the "FMMU" category.
+    pub fmmus: Vec<FmmuUsage>,
+
+    /// Sync managers from the "SyncM" category.
+    pub sync_managers: Vec<SyncManager>,
+
+    /// PDOs from the "TxPDO" category (device inputs).
+    pub tx_pdos: Vec<Pdo>,
+
+    /// PDOs from the "RxPDO" category (device outputs).
+    pub rx_pdos: Vec<Pdo>,
+
+    /// DC sync modes from the "DC" category.
+    pub dc_sync_modes: Vec<SiiDcSync>,
+}
+
+impl SiiImage {
+    /// Read and parse every category in an EEPROM.
+    pub async fn read<P>(eeprom: &SlaveEeprom<P>) -> Result<Self, Error>
+    where
+        P: EepromDataProvider,
+    {
+        let mut strings = Vec::new();
+
+        // String indices are 1-based, and the index is a `u8`
+        for index in 1..=u8::MAX {
+            match eeprom.find_string::<{ u8::MAX as usize }>(index).await? {
+                Some(s) => strings.push(s.as_str().to_string()),
+                None => break,
+            }
+        }
+
+        let general = match eeprom.general().await {
+            Ok(general) => Some(general),
+            Err(Error::Eeprom(EepromError::NoCategory)) => None,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            config_area: eeprom.config_area().await?,
+            size: eeprom.size().await?,
+            identity: eeprom.identity().await?,
+            mailbox: eeprom.mailbox_config().await?,
+            bootstrap_mailbox: eeprom.bootstrap_mailbox_config().await?,
+            strings,
+            general,
+            fmmus: eeprom.fmmus().await?.into_iter().collect(),
+            sync_managers: eeprom.sync_managers().await?.into_iter().collect(),
+            tx_pdos: eeprom.master_read_pdos().await?.into_iter().collect(),
+            rx_pdos: eeprom.master_write_pdos().await?.into_iter().collect(),
+            dc_sync_modes: eeprom.dc_sync_modes().await?.into_iter().collect(),
+        })
+    }
+
+    /// Get a string by its index in the "Strings" category.
+    ///
+    /// Indices start at 1, as they do in the EEPROM. An index of 0 denotes an empty string and
+    /// always returns `None`.
+    pub fn string(&self, index: u8) -> Option<&str> {
+        usize::from(index)
+            .checked_sub(1)
+            .and_then(|index| self.strings.get(index))
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eeprom::file_reader::EepromFile;
+
+    #[tokio::test]
+    async fn read_akd() {
+        let image = SiiImage::read(&SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex")))
+            .await
+            .expect("read image");
+
+        assert_eq!(image.size, 2048);
+        assert_eq!(image.identity.vendor_id, 0x0000006a);
+        assert_eq!(image.string(0), None);
+        assert_eq!(image.string(1), Some("AKD"));
+        assert_eq!(
+            image
+                .general
+                .and_then(|general| image.string(general.name_string_idx)),
+            Some("AKD EtherCAT Drive (CoE)")
+        );
+        assert_eq!(image.sync_managers.len(), 4);
+        assert!(!image.tx_pdos.is_empty());
+        assert!(!image.rx_pdos.is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+//! Structured contents of a SubDevice's SII (Slave Information Interface) EEPROM.
+//!
+//! The EEPROM holds a fixed configuration area followed by a list of categories, as defined in
+//! ETG2010. [`SlaveEeprom`] finds and parses these categories into the types in this module, and
+//! is obtained for a SubDevice with [`SlaveRef::eeprom`](crate::SlaveRef::eeprom). EEPROM images
+//! can also be parsed without hardware using [`MemoryEeprom`], or any other
+//! [`EepromDataProvider`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use ethercrab::{error::Error, Client, SlaveGroup};
+//!
+//! # async fn run(client: &Client<'_>, group: &mut SlaveGroup<8, 64>) -> Result<(), Error> {
+//! for slave in group.iter(client) {
+//!     let eeprom = slave.eeprom();
+//!
+//!     let order = eeprom.order_number::<64>().await?;
+//!
+//!     println!("{} ({:?})", slave.name(), order);
+//!
+//!     for pdo in eeprom.master_read_pdos().await? {
+//!         println!("--> Input PDO {:#06x}, {} bits", pdo.index, pdo.bit_len());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "std")]
+pub mod esi;
+mod flash;
+#[cfg(feature = "std")]
+mod image;
+
+pub use self::flash::flash_eeprom;
+pub use crate::base_data_types::PrimitiveDataType;
+pub use crate::eeprom::device_reader::DeviceEeprom;
+pub use crate::eeprom::memory_reader::MemoryEeprom;
+pub use crate::eeprom::types::{
+    BootstrapMailbox, CoeDetails, DefaultMailbox, FmmuEx, FmmuUsage, GeneralFlags,
+    MailboxProtocols, Pdo, PdoEntry, PdoFlags, PortStatus, PortStatuses, SiiDcSync, SiiGeneral,
+    SyncManager, SyncManagerEnable, SyncManagerType,
+};
+pub use crate::eeprom::{config_checksum, EepromDataProvider};
+pub use crate::slave::SlaveEeprom;
+pub use crate::sync_manager_channel::{
+    Control as SyncManagerControl, Direction as SyncManagerDirection,
+    OperationMode as SyncManagerMode,
+};
+
+#[cfg(feature = "std")]
+pub use self::image::SiiImage;
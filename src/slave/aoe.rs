@@ -0,0 +1,253 @@
+use super::{large_mailbox::MailboxData, Slave, SlaveRef};
+use crate::{
+    aoe::{AdsCommand, AmsAddress, AoeHeader, STATE_FLAGS_RESPONSE},
+    eeprom::types::MailboxProtocols,
+    error::{AoeError, Error, MailboxError},
+    fmt,
+    mailbox::MailboxType,
+    timer_factory::IntoTimeout,
+};
+use core::{ops::Deref, sync::atomic::Ordering};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite};
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read data from an ADS device behind the SubDevice using AoE (ADS over EtherCAT), returning
+    /// the number of bytes read into `buf`.
+    ///
+    /// Requests are sent from [`ClientConfig::ams_address`](crate::ClientConfig::ams_address).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, AmsAddress, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// let plc = AmsAddress::new([5, 12, 34, 56, 1, 1], 851);
+    ///
+    /// let mut buf = [0u8; 4];
+    ///
+    /// // Read 4 bytes from the PLC's memory area at offset 0
+    /// let len = slave.aoe_read(plc, 0x4020, 0, &mut buf).await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn aoe_read(
+        &self,
+        target: AmsAddress,
+        index_group: u32,
+        index_offset: u32,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let read_len = u32::try_from(buf.len())?;
+
+        let (header, response) = self
+            .aoe_request(
+                target,
+                AdsCommand::Read,
+                (index_group, index_offset, read_len),
+            )
+            .await?;
+
+        let data = ads_result_data(&header, &response)?;
+
+        buf.get_mut(0..data.len())
+            .ok_or(Error::Aoe(AoeError::TooLong))?
+            .copy_from_slice(data);
+
+        Ok(data.len())
+    }
+
+    /// Write data to an ADS device behind the SubDevice using AoE (ADS over EtherCAT).
+    ///
+    /// Requests are sent from [`ClientConfig::ams_address`](crate::ClientConfig::ams_address).
+    pub async fn aoe_write(
+        &self,
+        target: AmsAddress,
+        index_group: u32,
+        index_offset: u32,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let write_len = u32::try_from(data.len())?;
+
+        let (header, response) = self
+            .aoe_request(
+                target,
+                AdsCommand::Write,
+                (index_group, index_offset, write_len, data),
+            )
+            .await?;
+
+        ads_result(&header, &response)?;
+
+        Ok(())
+    }
+
+    /// Write data to and read data back from an ADS device behind the SubDevice in a single
+    /// request using AoE (ADS over EtherCAT), returning the number of bytes read into `buf`.
+    ///
+    /// This is commonly used to look up PLC variable handles by name.
+    ///
+    /// Requests are sent from [`ClientConfig::ams_address`](crate::ClientConfig::ams_address).
+    pub async fn aoe_read_write(
+        &self,
+        target: AmsAddress,
+        index_group: u32,
+        index_offset: u32,
+        data: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let read_len = u32::try_from(buf.len())?;
+        let write_len = u32::try_from(data.len())?;
+
+        let (header, response) = self
+            .aoe_request(
+                target,
+                AdsCommand::ReadWrite,
+                (index_group, index_offset, read_len, write_len, data),
+            )
+            .await?;
+
+        let data = ads_result_data(&header, &response)?;
+
+        buf.get_mut(0..data.len())
+            .ok_or(Error::Aoe(AoeError::TooLong))?
+            .copy_from_slice(data);
+
+        Ok(data.len())
+    }
+
+    /// Send an ADS request and wait for the response with the same invoke ID.
+    ///
+    /// Responses to other requests, e.g. ones that previously timed out, are discarded. The whole
+    /// request must complete within
+    /// [`Timeouts::mailbox_response`](crate::Timeouts::mailbox_response).
+    async fn aoe_request(
+        &self,
+        target: AmsAddress,
+        command: AdsCommand,
+        request: impl EtherCrabWireWrite,
+    ) -> Result<(AoeHeader, MailboxData<'_>), Error> {
+        let config = &self.state.config.mailbox;
+
+        if !config.supported_protocols.contains(MailboxProtocols::AOE) {
+            return Err(Error::Aoe(AoeError::NotSupported));
+        }
+
+        let write = config
+            .write
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        if AoeHeader::PACKED_LEN + request.packed_len() > usize::from(write.len) {
+            return Err(Error::Aoe(AoeError::TooLong));
+        }
+
+        let _lock = self.lock_mailbox().await?;
+
+        let (read_mailbox, write_mailbox) = self.mailboxes().await?;
+
+        let invoke_id = self.state.aoe_invoke_id.fetch_add(1, Ordering::Relaxed);
+
+        let header = AoeHeader::request(
+            self.mailbox_counter(),
+            target,
+            self.client.config.ams_address,
+            command,
+            invoke_id,
+            u16::try_from(request.packed_len())?,
+        );
+
+        self.write_mailbox(&write_mailbox, (header, request))
+            .await?;
+
+        // Responses to other requests don't extend the deadline for this one.
+        async {
+            loop {
+                let response = self
+                    .coe_response(&read_mailbox, self.client.timeouts.mailbox_response)
+                    .await?;
+
+                let header = AoeHeader::unpack_from_slice(&response)?;
+
+                if header.header.mailbox_type != MailboxType::Aoe {
+                    fmt::error!(
+                        "Invalid AoE response. Type: {:?}",
+                        header.header.mailbox_type
+                    );
+
+                    return Err(Error::Aoe(AoeError::InvalidResponse));
+                }
+
+                if header.invoke_id != invoke_id || header.state_flags & STATE_FLAGS_RESPONSE == 0 {
+                    fmt::debug!(
+                        "Slave {:#06x} discarding AoE message with invoke ID {:#010x}, expected {:#010x}",
+                        self.configured_address,
+                        header.invoke_id,
+                        invoke_id
+                    );
+
+                    continue;
+                }
+
+                if header.error_code != 0 {
+                    fmt::error!(
+                        "Slave {:#06x} AoE {:?} to {}:{} failed with AMS error {:#010x}",
+                        self.configured_address,
+                        command,
+                        target.net_id,
+                        target.port,
+                        header.error_code
+                    );
+
+                    return Err(Error::Aoe(AoeError::Ads {
+                        code: header.error_code,
+                    }));
+                }
+
+                break Ok((header, response));
+            }
+        }
+        .timeout(self.client.timeouts.mailbox_response)
+        .await
+    }
+}
+
+/// Check the ADS result code at the start of a response, returning the rest of the response data.
+fn ads_result<'buf>(header: &AoeHeader, response: &'buf [u8]) -> Result<&'buf [u8], Error> {
+    let len =
+        usize::try_from(header.length)?.min(response.len().saturating_sub(AoeHeader::PACKED_LEN));
+
+    let data = &response[AoeHeader::PACKED_LEN..][..len];
+
+    let result = u32::unpack_from_slice(data)?;
+
+    if result != 0 {
+        fmt::error!(
+            "ADS {:?} failed with error {:#010x}",
+            header.command,
+            result
+        );
+
+        return Err(Error::Aoe(AoeError::Ads { code: result }));
+    }
+
+    Ok(&data[4..])
+}
+
+/// Check the ADS result code of a read response, returning the data read.
+fn ads_result_data<'buf>(header: &AoeHeader, response: &'buf [u8]) -> Result<&'buf [u8], Error> {
+    let data = ads_result(header, response)?;
+
+    let len = usize::try_from(u32::unpack_from_slice(data)?)?;
+
+    data.get(4..(4 + len))
+        .ok_or(Error::Aoe(AoeError::InvalidResponse))
+}
@@ -0,0 +1,386 @@
+use super::{Slave, SlaveIdentity, SlaveRef};
+use crate::{
+    coe::sdo_info::{EntryDescription, ObjectDescription, OdListType},
+    error::Error,
+    fmt,
+};
+use core::ops::Deref;
+
+/// The maximum number of SubDevices whose identity and device type are cached. The oldest
+/// SubDevice's values are evicted when the cache is full.
+const MAX_CACHED_SLAVES: usize = 32;
+
+/// The maximum number of object and entry descriptions cached for all SubDevices together. The
+/// oldest description is evicted when the cache is full.
+///
+/// One cache is stored in the [`Client`](crate::Client) and shared by all SubDevices instead of
+/// one per [`Slave`], so it doesn't add to the size of every [`Slave`] and the futures that hold
+/// them.
+const MAX_CACHED_DESCRIPTIONS: usize = 16;
+
+/// The longest object list cached. Longer lists are read from the SubDevice every time.
+const MAX_CACHED_OD_LIST: usize = 64;
+
+#[derive(Debug, Clone)]
+enum Description {
+    Object(ObjectDescription),
+    Entry(EntryDescription),
+}
+
+/// Values cached for a single SubDevice.
+#[derive(Debug, Copy, Clone)]
+struct SlaveValues {
+    configured_address: u16,
+    identity: Option<SlaveIdentity>,
+    device_type: Option<u32>,
+}
+
+/// Values read from SubDevices' object dictionaries that are unlikely to change between reads,
+/// keyed by configured station address.
+#[derive(Debug, Clone)]
+pub(crate) struct CoeCache {
+    values: heapless::Deque<SlaveValues, MAX_CACHED_SLAVES>,
+    /// Object and entry descriptions, with the configured address of the SubDevice they were read
+    /// from.
+    descriptions: heapless::Deque<(u16, Description), MAX_CACHED_DESCRIPTIONS>,
+    /// The most recently read object list, with the configured address of the SubDevice it was
+    /// read from.
+    od_list: Option<(u16, OdListType, heapless::Vec<u16, MAX_CACHED_OD_LIST>)>,
+}
+
+impl Default for CoeCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CoeCache {
+    pub(crate) const fn new() -> Self {
+        Self {
+            values: heapless::Deque::new(),
+            descriptions: heapless::Deque::new(),
+            od_list: None,
+        }
+    }
+
+    fn values(&self, configured_address: u16) -> Option<&SlaveValues> {
+        self.values
+            .iter()
+            .find(|values| values.configured_address == configured_address)
+    }
+
+    fn update_values(&mut self, configured_address: u16, f: impl FnOnce(&mut SlaveValues)) {
+        if let Some(values) = self
+            .values
+            .iter_mut()
+            .find(|values| values.configured_address == configured_address)
+        {
+            f(values);
+
+            return;
+        }
+
+        let mut values = SlaveValues {
+            configured_address,
+            identity: None,
+            device_type: None,
+        };
+
+        f(&mut values);
+
+        if self.values.is_full() {
+            self.values.pop_front();
+        }
+
+        let _ = self.values.push_back(values);
+    }
+
+    pub(crate) fn identity(&self, configured_address: u16) -> Option<SlaveIdentity> {
+        self.values(configured_address)
+            .and_then(|values| values.identity)
+    }
+
+    pub(crate) fn insert_identity(&mut self, configured_address: u16, identity: SlaveIdentity) {
+        self.update_values(configured_address, |values| {
+            values.identity = Some(identity)
+        });
+    }
+
+    pub(crate) fn device_type(&self, configured_address: u16) -> Option<u32> {
+        self.values(configured_address)
+            .and_then(|values| values.device_type)
+    }
+
+    pub(crate) fn insert_device_type(&mut self, configured_address: u16, device_type: u32) {
+        self.update_values(configured_address, |values| {
+            values.device_type = Some(device_type)
+        });
+    }
+
+    pub(crate) fn od_list(&self, configured_address: u16, list_type: OdListType) -> Option<&[u16]> {
+        self.od_list
+            .as_ref()
+            .filter(|(address, ty, _)| *address == configured_address && *ty == list_type)
+            .map(|(_, _, indices)| indices.as_slice())
+    }
+
+    pub(crate) fn insert_od_list(
+        &mut self,
+        configured_address: u16,
+        list_type: OdListType,
+        indices: &[u16],
+    ) {
+        self.od_list = heapless::Vec::from_slice(indices)
+            .ok()
+            .map(|indices| (configured_address, list_type, indices));
+    }
+
+    pub(crate) fn object(&self, configured_address: u16, index: u16) -> Option<ObjectDescription> {
+        self.descriptions.iter().find_map(|(address, d)| match d {
+            Description::Object(o) if *address == configured_address && o.index == index => {
+                Some(o.clone())
+            }
+            _ => None,
+        })
+    }
+
+    pub(crate) fn insert_object(&mut self, configured_address: u16, object: ObjectDescription) {
+        self.insert(configured_address, Description::Object(object))
+    }
+
+    pub(crate) fn entry(
+        &self,
+        configured_address: u16,
+        index: u16,
+        sub_index: u8,
+    ) -> Option<EntryDescription> {
+        self.descriptions.iter().find_map(|(address, d)| match d {
+            Description::Entry(e)
+                if *address == configured_address
+                    && e.index == index
+                    && e.sub_index == sub_index =>
+            {
+                Some(e.clone())
+            }
+            _ => None,
+        })
+    }
+
+    pub(crate) fn insert_entry(&mut self, configured_address: u16, entry: EntryDescription) {
+        self.insert(configured_address, Description::Entry(entry))
+    }
+
+    fn insert(&mut self, configured_address: u16, description: Description) {
+        if self.descriptions.is_full() {
+            self.descriptions.pop_front();
+        }
+
+        let _ = self
+            .descriptions
+            .push_back((configured_address, description));
+    }
+
+    /// Remove every value cached for a single SubDevice.
+    pub(crate) fn invalidate(&mut self, configured_address: u16) {
+        let values = core::mem::take(&mut self.values);
+
+        for values in values
+            .into_iter()
+            .filter(|values| values.configured_address != configured_address)
+        {
+            let _ = self.values.push_back(values);
+        }
+
+        let descriptions = core::mem::take(&mut self.descriptions);
+
+        for description in descriptions
+            .into_iter()
+            .filter(|(address, _)| *address != configured_address)
+        {
+            let _ = self.descriptions.push_back(description);
+        }
+
+        if self
+            .od_list
+            .as_ref()
+            .is_some_and(|(address, _, _)| *address == configured_address)
+        {
+            self.od_list = None;
+        }
+    }
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read the identity object `0x1018` from the SubDevice's object dictionary.
+    ///
+    /// The result is cached after the first successful read. Sub-indices not supported by the
+    /// SubDevice are returned as `0`.
+    ///
+    /// The identity read from EEPROM during initialisation is available without any mailbox
+    /// traffic from [`Slave::identity`].
+    pub async fn coe_identity(&self) -> Result<SlaveIdentity, Error> {
+        if let Some(identity) = self.cached(|cache| cache.identity(self.configured_address)) {
+            return Ok(identity);
+        }
+
+        let count = self.sdo_read::<u8>(0x1018, 0).await?;
+
+        let mut values = [0u32; 4];
+
+        for (sub_index, value) in (1..=count.min(4)).zip(values.iter_mut()) {
+            *value = self.sdo_read(0x1018, sub_index).await?;
+        }
+
+        let [vendor_id, product_id, revision, serial] = values;
+
+        let identity = SlaveIdentity {
+            vendor_id,
+            product_id,
+            revision,
+            serial,
+        };
+
+        fmt::debug!(
+            "Slave {:#06x} CoE identity {}",
+            self.configured_address,
+            identity
+        );
+
+        self.update_cache(|cache| cache.insert_identity(self.configured_address, identity));
+
+        Ok(identity)
+    }
+
+    /// Read the device type object `0x1000` from the SubDevice's object dictionary.
+    ///
+    /// The lower 16 bits contain the device profile number, e.g. `402` for a DS402 servo drive.
+    /// The result is cached after the first successful read.
+    pub async fn device_type(&self) -> Result<u32, Error> {
+        if let Some(device_type) = self.cached(|cache| cache.device_type(self.configured_address)) {
+            return Ok(device_type);
+        }
+
+        let device_type = self.sdo_read::<u32>(0x1000, 0).await?;
+
+        self.update_cache(|cache| cache.insert_device_type(self.configured_address, device_type));
+
+        Ok(device_type)
+    }
+
+    /// Clear all cached CoE values for this SubDevice, including object and entry descriptions.
+    ///
+    /// This should be called if the SubDevice's object dictionary may have changed, e.g. after a
+    /// firmware update or if the device was replaced.
+    pub fn invalidate_coe_cache(&self) {
+        match self.client.coe_cache.try_borrow_mut() {
+            Ok(mut cache) => cache.invalidate(self.configured_address),
+            Err(_) => fmt::warn!(
+                "Slave {:#06x} CoE cache is in use and was not cleared",
+                self.configured_address
+            ),
+        }
+    }
+
+    /// Get a value from the CoE cache.
+    ///
+    /// Returns `None` if the cache is in use elsewhere, so the value is read from the SubDevice
+    /// instead.
+    pub(super) fn cached<T>(&self, f: impl FnOnce(&CoeCache) -> Option<T>) -> Option<T> {
+        self.client
+            .coe_cache
+            .try_borrow()
+            .ok()
+            .and_then(|cache| f(&cache))
+    }
+
+    /// Store a value in the CoE cache. Nothing is stored if the cache is in use elsewhere.
+    pub(super) fn update_cache(&self, f: impl FnOnce(&mut CoeCache)) {
+        match self.client.coe_cache.try_borrow_mut() {
+            Ok(mut cache) => f(&mut cache),
+            Err(_) => fmt::debug!(
+                "Slave {:#06x} CoE cache is in use, not caching value",
+                self.configured_address
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coe::sdo_info::ObjectCode;
+
+    fn object(index: u16) -> ObjectDescription {
+        ObjectDescription {
+            index,
+            data_type: 0x0007,
+            max_sub_index: 0,
+            object_code: ObjectCode::Variable,
+            name: heapless::String::new(),
+        }
+    }
+
+    #[test]
+    fn evict_oldest_object() {
+        let mut cache = CoeCache::default();
+
+        for index in 0..(MAX_CACHED_DESCRIPTIONS as u16 + 1) {
+            cache.insert_object(0x1001, object(0x2000 + index));
+        }
+
+        assert_eq!(cache.object(0x1001, 0x2000), None);
+        assert_eq!(cache.object(0x1001, 0x2001), Some(object(0x2001)));
+        assert_eq!(
+            cache.object(0x1001, 0x2000 + MAX_CACHED_DESCRIPTIONS as u16),
+            Some(object(0x2000 + MAX_CACHED_DESCRIPTIONS as u16))
+        );
+        assert_eq!(cache.object(0x1002, 0x2001), None, "other SubDevice");
+    }
+
+    #[test]
+    fn od_list() {
+        let mut cache = CoeCache::default();
+
+        cache.insert_od_list(0x1001, OdListType::All, &[0x1000, 0x1018]);
+
+        assert_eq!(
+            cache.od_list(0x1001, OdListType::All),
+            Some([0x1000, 0x1018].as_slice())
+        );
+        assert_eq!(cache.od_list(0x1001, OdListType::RxPdoMappable), None);
+        assert_eq!(cache.od_list(0x1002, OdListType::All), None);
+
+        // Too long to cache, so the previous list is removed too
+        cache.insert_od_list(0x1001, OdListType::All, &[0x2000; MAX_CACHED_OD_LIST + 1]);
+
+        assert_eq!(cache.od_list(0x1001, OdListType::All), None);
+    }
+
+    #[test]
+    fn per_slave_values() {
+        let mut cache = CoeCache::default();
+
+        for address in 0..(MAX_CACHED_SLAVES as u16 + 1) {
+            cache.insert_device_type(0x1000 + address, u32::from(address));
+        }
+
+        // Oldest SubDevice is evicted
+        assert_eq!(cache.device_type(0x1000), None);
+        assert_eq!(cache.device_type(0x1001), Some(1));
+
+        cache.insert_object(0x1001, object(0x2000));
+        cache.insert_object(0x1002, object(0x2000));
+        cache.insert_od_list(0x1001, OdListType::All, &[0x1000]);
+
+        cache.invalidate(0x1001);
+
+        assert_eq!(cache.device_type(0x1001), None);
+        assert_eq!(cache.object(0x1001, 0x2000), None);
+        assert_eq!(cache.od_list(0x1001, OdListType::All), None);
+        assert_eq!(cache.device_type(0x1002), Some(2));
+        assert_eq!(cache.object(0x1002, 0x2000), Some(object(0x2000)));
+    }
+}
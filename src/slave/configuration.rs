@@ -1,9 +1,15 @@
-use super::{Slave, SlaveRef};
+use super::{
+    pdo_entries::{record_entry_locations, EntryLocations, RawEntries},
+    Slave, SlaveEeprom, SlaveRef,
+};
 use crate::{
-    coe::{SdoExpedited, SubIndex},
-    eeprom::types::{
-        CoeDetails, FmmuUsage, MailboxProtocols, Pdo, SiiOwner, SyncManager, SyncManagerEnable,
-        SyncManagerType,
+    coe::{PdoMapping, SubIndex},
+    eeprom::{
+        device_reader::DeviceEeprom,
+        types::{
+            CoeDetails, FmmuUsage, MailboxProtocols, Pdo, SiiOwner, SyncManager, SyncManagerEnable,
+            SyncManagerType,
+        },
     },
     error::{Error, Item},
     fmmu::Fmmu,
@@ -14,7 +20,7 @@ use crate::{
     slave_state::SlaveState,
     sync_manager_channel::{Enable, Status, SyncManagerChannel, SM_BASE_ADDRESS, SM_TYPE_ADDRESS},
 };
-use core::ops::DerefMut;
+use core::ops::{Deref, DerefMut, Range};
 
 /// Configuation from EEPROM methods.
 impl<'a, S> SlaveRef<'a, S>
@@ -30,10 +36,12 @@ where
         // to master mode here, now that the transition is complete.
         self.set_eeprom_mode(SiiOwner::Master).await?;
 
-        let sync_managers = self.eeprom().sync_managers().await?;
+        let eeprom = self.configuration_eeprom();
+
+        let sync_managers = eeprom.sync_managers().await?;
 
         // Mailboxes must be configured in INIT state
-        self.configure_mailbox_sms(&sync_managers).await?;
+        self.configure_mailbox_sms(&eeprom, &sync_managers).await?;
 
         // Some slaves must be in PDI EEPROM mode to transition from INIT to PRE-OP. This is
         // mentioned in ETG2010 p. 146 under "Eeprom/@AssignToPd"
@@ -44,71 +52,111 @@ where
             self.configured_address
         );
 
-        self.request_slave_state(SlaveState::PreOp).await?;
+        self.request_slave_state(SlaveState::Init, SlaveState::PreOp)
+            .await?;
 
         if self.state.config.mailbox.has_coe {
-            // TODO: Abstract this no-complete-access check into a method call so we can reuse it.
-            // CA is currently only used here inside EtherCrab, but may need to be used in other
-            // places eventually.
-            let sms = if self.state.config.mailbox.complete_access {
-                // Up to 16 sync managers as per ETG1000.4 Table 59
-                self.sdo_read::<heapless::Vec<SyncManagerType, 16>>(
-                    SM_TYPE_ADDRESS,
-                    SubIndex::Complete,
-                )
-                .await?
-            } else {
-                let num_indices = self
-                    .sdo_read_expedited::<u8>(SM_TYPE_ADDRESS, SubIndex::Index(0))
-                    .await?;
+            match self.read_coe_sync_manager_types().await {
+                Ok(sms) => {
+                    fmt::debug!(
+                        "Slave {:#06x} found sync managers {:?}",
+                        self.configured_address,
+                        sms
+                    );
 
-                let mut sms = heapless::Vec::new();
+                    self.state.config.mailbox.coe_sync_manager_types = sms;
+                }
+                // Some devices advertise CoE but don't implement the sync manager communication
+                // type object. PDOs will be configured from the EEPROM instead.
+                Err(Error::Mailbox(e)) => {
+                    fmt::warn!(
+                        "Slave {:#06x} failed to read sync manager types over CoE: {}",
+                        self.configured_address,
+                        e
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
 
-                for index in 1..=num_indices {
-                    let sm = self
-                        .sdo_read_expedited::<SyncManagerType>(
-                            SM_TYPE_ADDRESS,
-                            SubIndex::Index(index),
-                        )
-                        .await?;
+        self.set_eeprom_mode(SiiOwner::Master).await?;
 
-                    fmt::trace!("Sync manager {:?} at sub-index {}", sm, index);
+        Ok(())
+    }
 
-                    sms.push(sm).map_err(|_| {
-                        fmt::error!("More than 16 sync manager types deteced");
+    /// Read sync manager communication types from object `0x1C00`.
+    async fn read_coe_sync_manager_types(
+        &self,
+    ) -> Result<heapless::Vec<SyncManagerType, 16>, Error> {
+        // TODO: Abstract this no-complete-access check into a method call so we can reuse it.
+        // CA is currently only used here inside EtherCrab, but may need to be used in other
+        // places eventually.
+        if self.state.config.mailbox.complete_access {
+            // Up to 16 sync managers as per ETG1000.4 Table 59
+            self.sdo_read::<heapless::Vec<SyncManagerType, 16>>(SM_TYPE_ADDRESS, SubIndex::Complete)
+                .await
+        } else {
+            let num_indices = self
+                .sdo_read_expedited::<u8>(SM_TYPE_ADDRESS, SubIndex::Index(0))
+                .await?;
 
-                        Error::Capacity(Item::SyncManager)
-                    })?;
-                }
+            let mut sms = heapless::Vec::new();
 
-                sms
-            };
+            for index in 1..=num_indices {
+                let sm = self
+                    .sdo_read_expedited::<SyncManagerType>(SM_TYPE_ADDRESS, SubIndex::Index(index))
+                    .await?;
 
-            fmt::debug!(
-                "Slave {:#06x} found sync managers {:?}",
-                self.configured_address,
-                sms
-            );
+                fmt::trace!("Sync manager {:?} at sub-index {}", sm, index);
 
-            self.state.config.mailbox.coe_sync_manager_types = sms;
-        }
+                sms.push(sm).map_err(|_| {
+                    fmt::error!("More than 16 sync manager types deteced");
 
-        self.set_eeprom_mode(SiiOwner::Master).await?;
+                    Error::Capacity(Item::SyncManager)
+                })?;
+            }
 
-        Ok(())
+            Ok(sms)
+        }
     }
 
     /// Second state configuration (PRE-OP -> SAFE-OP).
     ///
     /// PDOs must be configured in the PRE-OP state.
-    pub(crate) async fn configure_fmmus(
+    ///
+    /// The logical address `group_start_address` is stored at `pdi_offset` bytes into the group's
+    /// PDI.
+    ///
+    /// The sync manager lengths and FMMUs computed from CoE or the EEPROM are passed to
+    /// `configure_pdi`, which may change them before they are written to the SubDevice.
+    ///
+    /// If `sii` is given, its sync managers, FMMUs and PDOs are used instead of the EEPROM's.
+    ///
+    /// The location of each mapped PDO entry is recorded in the group's `entries`, if given.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn configure_fmmus<F>(
         &mut self,
         mut global_offset: PdiOffset,
         group_start_address: u32,
+        pdi_offset: usize,
         direction: PdoDirection,
-    ) -> Result<PdiOffset, Error> {
-        let sync_managers = self.eeprom().sync_managers().await?;
-        let fmmu_usage = self.eeprom().fmmus().await?;
+        configure_pdi: &F,
+        sii: Option<SiiOverride<'_>>,
+        entries: Option<&mut EntryLocations>,
+    ) -> Result<PdiOffset, Error>
+    where
+        F: Fn(&SlaveRef<'_, &Slave>, PdoDirection, &mut SyncManagerMappings),
+    {
+        let eeprom = self.configuration_eeprom();
+
+        let (sync_managers, fmmu_usage) = match sii {
+            Some(sii) => (
+                heapless::Vec::from_slice(sii.sync_managers)
+                    .map_err(|_| Error::Capacity(Item::SyncManager))?,
+                heapless::Vec::from_slice(sii.fmmus).map_err(|_| Error::Capacity(Item::Fmmu))?,
+            ),
+            None => (eeprom.sync_managers().await?, eeprom.fmmus().await?),
+        };
 
         let state = self.state().await?;
 
@@ -135,26 +183,91 @@ where
             has_coe
         );
 
-        let range = if has_coe {
-            self.configure_pdos_coe(&sync_managers, &fmmu_usage, direction, &mut global_offset)
-                .await?
+        // Entries are only recorded so they can be accessed by object index, so failing to store
+        // them all is not an error.
+        let mut raw = RawEntries::new();
+        let mut raw_complete = true;
+
+        let mut on_mapping = |sync_manager, mapping| {
+            raw_complete &= raw.push((sync_manager, mapping)).is_ok();
+
+            Ok(())
+        };
+
+        let coe_layout = if has_coe {
+            self.coe_pdo_layout(direction, &mut on_mapping).await?
+        } else {
+            None
+        };
+
+        let mut mappings = if let Some(layout) = coe_layout {
+            self.coe_mappings(&layout, &fmmu_usage, direction)?
         } else {
-            self.configure_pdos_eeprom(&sync_managers, &fmmu_usage, direction, &mut global_offset)
-                .await?
+            // Entries may have been read before CoE failed
+            raw.clear();
+            raw_complete = true;
+
+            self.eeprom_mappings(
+                &eeprom,
+                &sync_managers,
+                &fmmu_usage,
+                direction,
+                sii,
+                |sync_manager, mapping| {
+                    raw_complete &= raw.push((sync_manager, mapping)).is_ok();
+                },
+            )
+            .await?
         };
 
+        configure_pdi(
+            &SlaveRef::new(self.client, self.configured_address, &*self.state),
+            direction,
+            &mut mappings,
+        );
+
+        let alignment = self.state.config.layout.alignment;
+
+        let recorded = entries.map(|entries| {
+            raw_complete
+                && record_entry_locations(
+                    entries,
+                    self.configured_address,
+                    direction,
+                    &raw,
+                    &mappings,
+                    alignment,
+                )
+        });
+
+        if recorded == Some(false) {
+            fmt::debug!(
+                "Slave {:#06x} {:?} PDO entries can't be accessed by index",
+                self.configured_address,
+                direction
+            );
+        }
+
+        let range = self
+            .configure_pdos(&mappings, &sync_managers, direction, &mut global_offset)
+            .await?;
+
+        let logical = range.bytes.start as u32..range.bytes.end as u32;
+
         match direction {
             PdoDirection::MasterRead => {
+                self.state.config.layout.inputs = logical;
                 self.state.config.io.input = PdiSegment {
-                    bytes: (range.bytes.start - group_start_address as usize)
-                        ..(range.bytes.end - group_start_address as usize),
+                    bytes: (range.bytes.start - group_start_address as usize + pdi_offset)
+                        ..(range.bytes.end - group_start_address as usize + pdi_offset),
                     ..range
                 };
             }
             PdoDirection::MasterWrite => {
+                self.state.config.layout.outputs = logical;
                 self.state.config.io.output = PdiSegment {
-                    bytes: (range.bytes.start - group_start_address as usize)
-                        ..(range.bytes.end - group_start_address as usize),
+                    bytes: (range.bytes.start - group_start_address as usize + pdi_offset)
+                        ..(range.bytes.end - group_start_address as usize + pdi_offset),
                     ..range
                 };
             }
@@ -206,11 +319,15 @@ where
     }
 
     /// Configure SM0 and SM1 for mailbox communication.
-    async fn configure_mailbox_sms(&mut self, sync_managers: &[SyncManager]) -> Result<(), Error> {
+    async fn configure_mailbox_sms(
+        &mut self,
+        eeprom: &SlaveEeprom<DeviceEeprom<'_>>,
+        sync_managers: &[SyncManager],
+    ) -> Result<(), Error> {
         // Read default mailbox configuration from slave information area
-        let mailbox_config = self.eeprom().mailbox_config().await?;
+        let mailbox_config = eeprom.mailbox_config().await?;
 
-        let general = self.eeprom().general().await?;
+        let general = eeprom.general().await?;
 
         fmt::trace!(
             "Slave {:#06x} Mailbox configuration: {:#?}",
@@ -284,145 +401,92 @@ where
         Ok(())
     }
 
-    /// Configure PDOs from CoE registers.
-    async fn configure_pdos_coe(
+    /// Find the FMMU for each sync manager in a layout previously read from CoE registers.
+    ///
+    /// Sync managers with no mapped PDOs are not given an FMMU.
+    fn coe_mappings(
         &self,
-        sync_managers: &[SyncManager],
+        layout: &CoeLayout,
         fmmu_usage: &[FmmuUsage],
         direction: PdoDirection,
-        global_offset: &mut PdiOffset,
-    ) -> Result<PdiSegment, Error> {
+    ) -> Result<SyncManagerMappings, Error> {
         if !self.state.config.mailbox.has_coe {
             fmt::warn!("Invariant: attempting to configure PDOs from COE with no SOE support");
         }
 
-        let (desired_sm_type, desired_fmmu_type) = direction.filter_terms();
-
-        // NOTE: Commented out because this causes a timeout on various slave devices, possibly due
-        // to querying 0x1c00 after we enter PRE-OP but I'm unsure. See
-        // <https://github.com/ethercrab-rs/ethercrab/issues/49>. Complete access also causes the
-        // same issue.
-        // // ETG1000.6 Table 67 – CoE Communication Area
-        // let num_sms = self
-        //     .sdo_read::<u8>(SM_TYPE_ADDRESS, SubIndex::Index(0))
-        //     .await?;
-
-        let start_offset = *global_offset;
-        let mut total_bit_len = 0;
-
-        for (sync_manager_index, sm_type) in self
-            .state
-            .config
-            .mailbox
-            .coe_sync_manager_types
-            .iter()
-            .enumerate()
-        {
-            let sync_manager_index = sync_manager_index as u8;
+        let (_desired_sm_type, desired_fmmu_type) = direction.filter_terms();
 
-            let sm_address = SM_BASE_ADDRESS + u16::from(sync_manager_index);
+        let mut mappings = SyncManagerMappings::new();
 
-            let sync_manager =
-                sync_managers
-                    .get(usize::from(sync_manager_index))
+        for &(sync_manager, bit_len) in layout.iter() {
+            let fmmu = if bit_len > 0 {
+                let fmmu_index = fmmu_usage
+                    .iter()
+                    .position(|usage| *usage == desired_fmmu_type)
                     .ok_or(Error::NotFound {
-                        item: Item::SyncManager,
-                        index: Some(usize::from(sync_manager_index)),
+                        item: Item::Fmmu,
+                        index: None,
                     })?;
 
-            if *sm_type != desired_sm_type {
-                continue;
-            }
-
-            // Total number of PDO assignments for this sync manager
-            let num_sm_assignments = self
-                .sdo_read_expedited::<u8>(sm_address, SubIndex::Index(0))
-                .await?;
-
-            fmt::trace!(
-                "SDO sync manager {}  {:#06x} {:?}, sub indices: {}",
-                sync_manager_index,
-                sm_address,
-                sm_type,
-                num_sm_assignments
-            );
-
-            let mut sm_bit_len = 0u16;
+                Some(fmmu_index as u8)
+            } else {
+                None
+            };
 
-            for i in 1..=num_sm_assignments {
-                let pdo = self
-                    .sdo_read_expedited::<u16>(sm_address, SubIndex::Index(i))
-                    .await?;
-                let num_mappings = self
-                    .sdo_read_expedited::<u8>(pdo, SubIndex::Index(0))
-                    .await?;
+            mappings
+                .push(SyncManagerMapping {
+                    sync_manager,
+                    bit_len,
+                    fmmu,
+                })
+                .map_err(|_| Error::Capacity(Item::SyncManager))?;
+        }
 
-                fmt::trace!(
-                    "--> {:#04x} data: {:#06x} ({} mappings):",
-                    i,
-                    pdo,
-                    num_mappings
-                );
+        Ok(mappings)
+    }
 
-                for i in 1..=num_mappings {
-                    /// Defined in ETG1000.6 Table 74/Table 75 Receive PDO Mapping.
-                    ///
-                    /// Note that this struct order is opposite to the specification as the data is
-                    /// big-endian in EEPROM, but little endian on the wire.
-                    #[derive(ethercrab_wire::EtherCrabWireRead)]
-                    #[wire(bytes = 4)]
-                    struct Mapping {
-                        #[wire(bytes = 1)]
-                        mapping_bit_len: u8,
-                        #[wire(bytes = 1)]
-                        sub_index: u8,
-                        #[wire(bytes = 2)]
-                        index: u16,
-                    }
-
-                    impl SdoExpedited for Mapping {}
-
-                    let Mapping {
-                        index,
-                        sub_index,
-                        mapping_bit_len,
-                    } = self
-                        .sdo_read_expedited::<Mapping>(pdo, SubIndex::Index(i))
-                        .await?;
+    /// Write sync manager and FMMU configuration for every mapped sync manager.
+    async fn configure_pdos(
+        &self,
+        mappings: &SyncManagerMappings,
+        sync_managers: &[SyncManager],
+        direction: PdoDirection,
+        global_offset: &mut PdiOffset,
+    ) -> Result<PdiSegment, Error> {
+        let (desired_sm_type, _desired_fmmu_type) = direction.filter_terms();
 
-                    fmt::trace!(
-                        "----> index {:#06x}, sub index {}, bit length {}",
-                        index,
-                        sub_index,
-                        mapping_bit_len,
-                    );
+        let alignment = self.state.config.layout.alignment.bytes();
 
-                    sm_bit_len += u16::from(mapping_bit_len);
-                }
-            }
+        // The first FMMU's aligned address, so any padding before it isn't part of this SubDevice's
+        // PDI.
+        let mut start_offset = None;
+        let mut total_bit_len = 0;
 
-            fmt::trace!(
-                "----= total SM bit length {} ({} bytes)",
-                sm_bit_len,
-                (sm_bit_len + 7) / 8
-            );
+        for mapping in mappings.iter() {
+            let sync_manager =
+                sync_managers
+                    .get(usize::from(mapping.sync_manager))
+                    .ok_or(Error::NotFound {
+                        item: Item::SyncManager,
+                        index: Some(usize::from(mapping.sync_manager)),
+                    })?;
 
             let sm_config = self
-                .write_sm_config(sync_manager_index, sync_manager, (sm_bit_len + 7) / 8)
+                .write_sm_config(
+                    mapping.sync_manager,
+                    sync_manager,
+                    (mapping.bit_len + 7) / 8,
+                )
                 .await?;
 
-            if sm_bit_len > 0 {
-                let fmmu_index = fmmu_usage
-                    .iter()
-                    .position(|usage| *usage == desired_fmmu_type)
-                    .ok_or(Error::NotFound {
-                        item: Item::Fmmu,
-                        index: None,
-                    })?;
+            if let Some(fmmu_index) = mapping.fmmu {
+                *global_offset = global_offset.align(alignment);
+
+                start_offset.get_or_insert(*global_offset);
 
                 self.write_fmmu_config(
-                    sm_bit_len,
-                    fmmu_index,
+                    mapping.bit_len,
+                    usize::from(fmmu_index),
                     global_offset,
                     desired_sm_type,
                     &sm_config,
@@ -430,12 +494,12 @@ where
                 .await?;
             }
 
-            total_bit_len += sm_bit_len;
+            total_bit_len += mapping.bit_len;
         }
 
         Ok(PdiSegment {
             bit_len: total_bit_len.into(),
-            bytes: start_offset.up_to(*global_offset),
+            bytes: start_offset.unwrap_or(*global_offset).up_to(*global_offset),
         })
     }
 
@@ -491,39 +555,52 @@ where
         Ok(())
     }
 
-    /// Configure PDOs from EEPROM
-    async fn configure_pdos_eeprom(
+    /// Find the length and FMMU of each sync manager from the PDOs in the EEPROM, or in `sii` if
+    /// given.
+    ///
+    /// Every mapped entry is passed to `on_mapping` with the index of its sync manager.
+    async fn eeprom_mappings(
         &self,
+        eeprom: &SlaveEeprom<DeviceEeprom<'_>>,
         sync_managers: &[SyncManager],
         fmmu_usage: &[FmmuUsage],
         direction: PdoDirection,
-        offset: &mut PdiOffset,
-    ) -> Result<PdiSegment, Error> {
-        let pdos: heapless::Vec<Pdo, 16> = match direction {
-            PdoDirection::MasterRead => {
-                let read_pdos = self.eeprom().master_read_pdos().await?;
-
-                fmt::trace!("Slave inputs PDOs {:#?}", read_pdos);
+        sii: Option<SiiOverride<'_>>,
+        mut on_mapping: impl FnMut(u8, PdoMapping),
+    ) -> Result<SyncManagerMappings, Error> {
+        let pdos: heapless::Vec<Pdo, 16> =
+            match (sii, direction) {
+                (Some(sii), PdoDirection::MasterRead) => heapless::Vec::from_slice(sii.tx_pdos)
+                    .map_err(|_| Error::Capacity(Item::Pdo))?,
+                (Some(sii), PdoDirection::MasterWrite) => heapless::Vec::from_slice(sii.rx_pdos)
+                    .map_err(|_| Error::Capacity(Item::Pdo))?,
+                (None, PdoDirection::MasterRead) => {
+                    let read_pdos = eeprom.master_read_pdos().await?;
+
+                    fmt::trace!("Slave inputs PDOs {:#?}", read_pdos);
+
+                    read_pdos
+                }
+                (None, PdoDirection::MasterWrite) => {
+                    let write_pdos = eeprom.master_write_pdos().await?;
 
-                read_pdos
-            }
-            PdoDirection::MasterWrite => {
-                let write_pdos = self.eeprom().master_write_pdos().await?;
+                    fmt::trace!("Slave outputs PDOs {:#?}", write_pdos);
 
-                fmt::trace!("Slave outputs PDOs {:#?}", write_pdos);
+                    write_pdos
+                }
+            };
 
-                write_pdos
-            }
+        // ESI files have no equivalent of the FMMU_EX category
+        let fmmu_sm_mappings = match sii {
+            Some(_) => heapless::Vec::new(),
+            None => eeprom.fmmu_mappings().await?,
         };
 
-        let fmmu_sm_mappings = self.eeprom().fmmu_mappings().await?;
-
-        let start_offset = *offset;
-        let mut total_bit_len = 0;
+        let mut mappings = SyncManagerMappings::new();
 
         let (sm_type, fmmu_type) = direction.filter_terms();
 
-        for (sync_manager_index, sync_manager) in sync_managers
+        for (sync_manager_index, _sync_manager) in sync_managers
             .iter()
             .enumerate()
             .filter(|(_idx, sm)| sm.usage_type == sm_type)
@@ -536,7 +613,16 @@ where
                 .map(Pdo::bit_len)
                 .sum();
 
-            total_bit_len += bit_len;
+            for entry in pdos
+                .iter()
+                .filter(|pdo| pdo.sync_manager == sync_manager_index)
+                .flat_map(|pdo| pdo.entries.iter())
+            {
+                on_mapping(
+                    sync_manager_index,
+                    PdoMapping::new(entry.index, entry.sub_index, entry.data_length_bits),
+                );
+            }
 
             // Look for FMMU index using FMMU_EX section in EEPROM. If it's empty, default
             // to looking through FMMU usage list and picking out the appropriate kind
@@ -562,35 +648,263 @@ where
                     index: None,
                 })?;
 
-            let sm_config = self
-                .write_sm_config(sync_manager_index, sync_manager, (bit_len + 7) / 8)
+            mappings
+                .push(SyncManagerMapping {
+                    sync_manager: sync_manager_index,
+                    bit_len,
+                    fmmu: Some(fmmu_index),
+                })
+                .map_err(|_| Error::Capacity(Item::SyncManager))?;
+        }
+
+        Ok(mappings)
+    }
+}
+
+/// PDO configuration read over CoE, shared with PDO entry discovery.
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read the PDO assignment and mapping objects over CoE to find the bit length of each sync
+    /// manager in the given direction.
+    ///
+    /// Every mapped entry is passed to `on_mapping` with the index of its sync manager.
+    ///
+    /// Returns `None` if the SubDevice's PDO configuration cannot be read over CoE, in which case
+    /// the EEPROM should be used instead.
+    pub(in crate::slave) async fn coe_pdo_layout(
+        &self,
+        direction: PdoDirection,
+        on_mapping: impl FnMut(u8, PdoMapping) -> Result<(), Error>,
+    ) -> Result<Option<CoeLayout>, Error> {
+        if self.state.config.mailbox.coe_sync_manager_types.is_empty() {
+            fmt::warn!(
+                "Slave {:#06x} supports CoE but has no sync manager types, falling back to EEPROM PDO config",
+                self.configured_address
+            );
+
+            return Ok(None);
+        }
+
+        match self.read_coe_pdo_layout(direction, on_mapping).await {
+            Ok(layout) => Ok(Some(layout)),
+            // The SubDevice doesn't expose its PDO config over CoE
+            Err(Error::Mailbox(e)) => {
+                fmt::warn!(
+                    "Slave {:#06x} failed to read PDO config over CoE ({}), falling back to EEPROM",
+                    self.configured_address,
+                    e
+                );
+
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn read_coe_pdo_layout(
+        &self,
+        direction: PdoDirection,
+        mut on_mapping: impl FnMut(u8, PdoMapping) -> Result<(), Error>,
+    ) -> Result<CoeLayout, Error> {
+        // NOTE: Commented out because this causes a timeout on various slave devices, possibly due
+        // to querying 0x1c00 after we enter PRE-OP but I'm unsure. See
+        // <https://github.com/ethercrab-rs/ethercrab/issues/49>. Complete access also causes the
+        // same issue.
+        // // ETG1000.6 Table 67 – CoE Communication Area
+        // let num_sms = self
+        //     .sdo_read::<u8>(SM_TYPE_ADDRESS, SubIndex::Index(0))
+        //     .await?;
+
+        let (desired_sm_type, _desired_fmmu_type) = direction.filter_terms();
+
+        let mut layout = CoeLayout::new();
+
+        for (sync_manager_index, sm_type) in self
+            .state
+            .config
+            .mailbox
+            .coe_sync_manager_types
+            .iter()
+            .enumerate()
+        {
+            let sync_manager_index = sync_manager_index as u8;
+
+            let sm_address = SM_BASE_ADDRESS + u16::from(sync_manager_index);
+
+            if *sm_type != desired_sm_type {
+                continue;
+            }
+
+            // Total number of PDO assignments for this sync manager
+            let num_sm_assignments = self
+                .sdo_read_expedited::<u8>(sm_address, SubIndex::Index(0))
                 .await?;
 
-            self.write_fmmu_config(
-                bit_len,
-                usize::from(fmmu_index),
-                offset,
+            fmt::trace!(
+                "SDO sync manager {}  {:#06x} {:?}, sub indices: {}",
+                sync_manager_index,
+                sm_address,
                 sm_type,
-                &sm_config,
-            )
-            .await?;
+                num_sm_assignments
+            );
+
+            let mut sm_bit_len = 0u16;
+
+            for i in 1..=num_sm_assignments {
+                let pdo = self
+                    .sdo_read_expedited::<u16>(sm_address, SubIndex::Index(i))
+                    .await?;
+                let num_mappings = self
+                    .sdo_read_expedited::<u8>(pdo, SubIndex::Index(0))
+                    .await?;
+
+                fmt::trace!(
+                    "--> {:#04x} data: {:#06x} ({} mappings):",
+                    i,
+                    pdo,
+                    num_mappings
+                );
+
+                for i in 1..=num_mappings {
+                    let mapping = self
+                        .sdo_read_expedited::<PdoMapping>(pdo, SubIndex::Index(i))
+                        .await?;
+
+                    let PdoMapping {
+                        index,
+                        sub_index,
+                        bit_len: mapping_bit_len,
+                    } = mapping;
+
+                    fmt::trace!(
+                        "----> index {:#06x}, sub index {}, bit length {}",
+                        index,
+                        sub_index,
+                        mapping_bit_len,
+                    );
+
+                    sm_bit_len += u16::from(mapping_bit_len);
+
+                    on_mapping(sync_manager_index, mapping)?;
+                }
+            }
+
+            fmt::trace!(
+                "----= total SM bit length {} ({} bytes)",
+                sm_bit_len,
+                (sm_bit_len + 7) / 8
+            );
+
+            layout
+                .push((sync_manager_index, sm_bit_len))
+                .map_err(|_| Error::Capacity(Item::SyncManager))?;
         }
 
-        Ok(PdiSegment {
-            bit_len: total_bit_len.into(),
-            bytes: start_offset.up_to(*offset),
-        })
+        Ok(layout)
+    }
+}
+
+/// The alignment of the logical start address of each FMMU mapped for a SubDevice, set with
+/// [`SlaveRef::set_pdi_alignment`].
+///
+/// Some ESCs require FMMUs to start on a word boundary. Padding is inserted before the SubDevice's
+/// data, and between its sync managers, but is not part of its inputs or outputs.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PdiAlignment {
+    /// Start on any byte (default).
+    #[default]
+    Byte,
+    /// Start on a 2 byte boundary.
+    Word,
+    /// Start on a 4 byte boundary.
+    DoubleWord,
+}
+
+impl PdiAlignment {
+    /// The alignment in bytes.
+    pub fn bytes(self) -> u32 {
+        match self {
+            PdiAlignment::Byte => 1,
+            PdiAlignment::Word => 2,
+            PdiAlignment::DoubleWord => 4,
+        }
     }
 }
 
-#[derive(Copy, Clone)]
+/// Where a SubDevice's process data is mapped in logical address space, returned by
+/// [`SlaveRef::pdi_layout`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PdiLayout {
+    /// The alignment of each FMMU's logical start address.
+    pub alignment: PdiAlignment,
+
+    /// The logical addresses the SubDevice's inputs are mapped to.
+    pub inputs: Range<u32>,
+
+    /// The logical addresses the SubDevice's outputs are mapped to.
+    ///
+    /// These may be the same as the inputs' addresses if
+    /// [`PdiExchange::LrdLwr`](crate::PdiExchange::LrdLwr) is used with `overlap` enabled.
+    pub outputs: Range<u32>,
+}
+
+/// Sync manager index and total mapped bit length, as read from CoE.
+type CoeLayout = heapless::Vec<(u8, u16), 16>;
+
+/// The process data mapping of a single sync manager.
+///
+/// Computed from the SubDevice's CoE PDO configuration, or the PDOs in its EEPROM if it doesn't
+/// support CoE. This can be changed with
+/// [`SlaveGroup::into_pre_op_pdi_with_config`](crate::SlaveGroup::into_pre_op_pdi_with_config)
+/// before it is written to the SubDevice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SyncManagerMapping {
+    /// Sync manager index.
+    pub sync_manager: u8,
+
+    /// The length of the process data in bits. The sync manager length is rounded up to the next
+    /// whole byte.
+    pub bit_len: u16,
+
+    /// The FMMU that maps this sync manager into the PDI, or `None` to configure the sync manager
+    /// without mapping it.
+    ///
+    /// If multiple sync managers use the same FMMU, the FMMU is extended to cover all of them.
+    pub fmmu: Option<u8>,
+}
+
+/// The process data mapping of all sync managers of a SubDevice in one direction.
+pub type SyncManagerMappings = heapless::Vec<SyncManagerMapping, 16>;
+
+/// Sync managers, FMMUs and PDOs to use instead of those in a SubDevice's EEPROM, e.g. from its
+/// ESI file.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SiiOverride<'a> {
+    pub sync_managers: &'a [SyncManager],
+    pub fmmus: &'a [FmmuUsage],
+    /// Master read PDOs.
+    pub tx_pdos: &'a [Pdo],
+    /// Master write PDOs.
+    pub rx_pdos: &'a [Pdo],
+}
+
+/// The direction of process data configured by
+/// [`SlaveGroup::into_pre_op_pdi_with_config`](crate::SlaveGroup::into_pre_op_pdi_with_config).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum PdoDirection {
+    /// SubDevice inputs, read by the MainDevice.
     MasterRead,
+    /// SubDevice outputs, written by the MainDevice.
     MasterWrite,
 }
 
 impl PdoDirection {
-    fn filter_terms(self) -> (SyncManagerType, FmmuUsage) {
+    pub(in crate::slave) fn filter_terms(self) -> (SyncManagerType, FmmuUsage) {
         match self {
             PdoDirection::MasterRead => (SyncManagerType::ProcessDataRead, FmmuUsage::Inputs),
             PdoDirection::MasterWrite => (SyncManagerType::ProcessDataWrite, FmmuUsage::Outputs),
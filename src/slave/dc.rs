@@ -1,6 +1,8 @@
 //! Distributed Clock configuration for a single SubDevice.
 
-use core::{fmt, time::Duration};
+use super::{Slave, SlaveRef};
+use crate::{eeprom::types::SiiDcSync, error::Error, register::RegisterAddress};
+use core::{fmt, ops::Deref, time::Duration};
 
 /// DC sync configuration for a SubDevice.
 #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
@@ -34,3 +36,445 @@ impl fmt::Display for DcSync {
         }
     }
 }
+
+/// SYNC0 timing for a single SubDevice.
+///
+/// Fields left at their default value use the group's
+/// [`DcConfiguration`](crate::slave_group::DcConfiguration).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DcSyncTiming {
+    /// SYNC0 cycle time, or `None` to use
+    /// [`DcConfiguration::sync0_period`](crate::slave_group::DcConfiguration::sync0_period).
+    pub sync0_period: Option<Duration>,
+
+    /// Delay SYNC0 pulses by this amount relative to the start of each cycle.
+    pub sync0_shift: Duration,
+
+    /// DC system time in nanoseconds of the first SYNC0 pulse, before
+    /// [`sync0_shift`](DcSyncTiming::sync0_shift) is applied.
+    ///
+    /// If this is `None`, the start time shared by all SubDevices in the group is used. See
+    /// [`SlaveGroup::dc_start_time`](crate::SlaveGroup::dc_start_time).
+    pub start_time: Option<u64>,
+}
+
+/// A DC sync mode defined in a SubDevice's EEPROM.
+///
+/// These correspond to the `Dc/OpMode` elements of the SubDevice's ESI file. Read them with
+/// [`SlaveRef::dc_sync_modes`], and apply one with [`SlaveRef::set_dc_sync_mode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DcSyncMode {
+    /// Mode name, e.g. `DcSync`.
+    pub name: heapless::String<64>,
+
+    /// Value of the DC activation registers `0x0980:0x0981`, e.g. `0x0300` for SYNC0 or `0x0700`
+    /// for SYNC0 and SYNC1.
+    pub assign_activate: u16,
+
+    /// Fixed SYNC0 cycle time. If this is `None`, the SYNC0 cycle time is derived from the
+    /// application cycle time and [`sync0_cycle_factor`](DcSyncMode::sync0_cycle_factor).
+    pub sync0_period: Option<Duration>,
+
+    /// SYNC0 cycle time as a multiple of the application cycle time, or a divisor of it if
+    /// negative.
+    pub sync0_cycle_factor: i16,
+
+    /// SYNC0 shift time in nanoseconds. Negative shifts move SYNC0 before the start of the cycle.
+    pub sync0_shift: i32,
+
+    /// Time from SYNC0 to SYNC1, in whole SYNC0 cycles.
+    pub sync1_cycle_factor: i16,
+
+    /// SYNC1 shift time in nanoseconds, added to the SYNC1 cycle factor. May be negative.
+    pub sync1_shift: i32,
+}
+
+impl DcSyncMode {
+    pub(crate) fn new(sii: SiiDcSync, name: heapless::String<64>) -> Self {
+        Self {
+            name,
+            assign_activate: sii.assign_activate,
+            sync0_period: (sii.cycle_time0 > 0)
+                .then(|| Duration::from_nanos(u64::from(sii.cycle_time0))),
+            sync0_cycle_factor: sii.sync0_cycle_factor,
+            // Shifts are stored in the EEPROM as two's complement
+            sync0_shift: sii.shift_time0 as i32,
+            sync1_cycle_factor: sii.sync1_cycle_factor,
+            sync1_shift: sii.shift_time1 as i32,
+        }
+    }
+
+    /// Compute the SYNC0 cycle time for the given application cycle time.
+    pub fn sync0_period(&self, cycle_time: Duration) -> Duration {
+        if let Some(period) = self.sync0_period {
+            return period;
+        }
+
+        let factor = u32::from(self.sync0_cycle_factor.unsigned_abs()).max(1);
+
+        if self.sync0_cycle_factor < 0 {
+            cycle_time / factor
+        } else {
+            cycle_time * factor
+        }
+    }
+
+    /// The [`DcSync`] mode enabled by this sync mode's `AssignActivate` value.
+    pub fn dc_sync(&self, cycle_time: Duration) -> DcSync {
+        const CYCLIC_OP: u16 = 0x0100;
+        const SYNC0: u16 = 0x0200;
+        const SYNC1: u16 = 0x0400;
+
+        if self.assign_activate & (CYCLIC_OP | SYNC0) != CYCLIC_OP | SYNC0 {
+            return DcSync::Disabled;
+        }
+
+        if self.assign_activate & SYNC1 == 0 {
+            return DcSync::Sync0;
+        }
+
+        let sync1_cycles = u32::from(self.sync1_cycle_factor.max(0) as u16);
+
+        let period = self.sync0_period(cycle_time) * sync1_cycles;
+        let shift = Duration::from_nanos(u64::from(self.sync1_shift.unsigned_abs()));
+
+        DcSync::Sync01 {
+            sync1_period: if self.sync1_shift < 0 {
+                period.saturating_sub(shift)
+            } else {
+                period + shift
+            },
+        }
+    }
+
+    /// The SYNC0 timing for the given application cycle time.
+    ///
+    /// SYNC0 pulses are periodic, so a negative shift is applied as a positive shift of the rest
+    /// of the SYNC0 period.
+    pub fn timing(&self, cycle_time: Duration) -> DcSyncTiming {
+        let period = self.sync0_period(cycle_time);
+
+        let shift = i128::from(self.sync0_shift).rem_euclid(period.as_nanos().max(1) as i128);
+
+        DcSyncTiming {
+            sync0_period: Some(period),
+            sync0_shift: Duration::from_nanos(shift as u64),
+            start_time: None,
+        }
+    }
+}
+
+/// One of the two DC latch inputs of a SubDevice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Latch {
+    /// `LATCH0` input.
+    Latch0,
+    /// `LATCH1` input.
+    Latch1,
+}
+
+/// An edge of a DC latch input signal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LatchEdge {
+    /// Rising edge.
+    Positive,
+    /// Falling edge.
+    Negative,
+}
+
+/// How a latch input edge is captured.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LatchMode {
+    /// Every edge overwrites the stored time (default).
+    #[default]
+    Continuous,
+    /// Only the first edge is stored. Further edges are ignored until the stored time is read.
+    SingleEvent,
+}
+
+/// The status of a DC latch input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bytes = 1)]
+pub struct LatchStatus {
+    /// A single event mode positive edge has been stored and not yet read.
+    #[wire(bits = 1)]
+    pub positive_event: bool,
+    /// A single event mode negative edge has been stored and not yet read.
+    #[wire(bits = 1)]
+    pub negative_event: bool,
+    /// The current state of the latch input pin.
+    #[wire(bits = 1, post_skip = 5)]
+    pub pin: bool,
+}
+
+impl Latch {
+    fn control(self) -> RegisterAddress {
+        match self {
+            Latch::Latch0 => RegisterAddress::DcLatch0Control,
+            Latch::Latch1 => RegisterAddress::DcLatch1Control,
+        }
+    }
+
+    fn status(self) -> RegisterAddress {
+        match self {
+            Latch::Latch0 => RegisterAddress::DcLatch0Status,
+            Latch::Latch1 => RegisterAddress::DcLatch1Status,
+        }
+    }
+
+    fn time(self, edge: LatchEdge) -> RegisterAddress {
+        match (self, edge) {
+            (Latch::Latch0, LatchEdge::Positive) => RegisterAddress::DcLatch0TimePositive,
+            (Latch::Latch0, LatchEdge::Negative) => RegisterAddress::DcLatch0TimeNegative,
+            (Latch::Latch1, LatchEdge::Positive) => RegisterAddress::DcLatch1TimePositive,
+            (Latch::Latch1, LatchEdge::Negative) => RegisterAddress::DcLatch1TimeNegative,
+        }
+    }
+}
+
+/// Decode the sign-magnitude value of the DC system time difference register (`0x092C`).
+///
+/// Bit 31 is set when the SubDevice's local copy of the system time is smaller than the received
+/// system time. Bits 30:0 hold the magnitude of the difference in nanoseconds.
+fn decode_system_time_difference(raw: u32) -> i32 {
+    let magnitude = (raw & 0x7fff_ffff) as i32;
+
+    if raw & 0x8000_0000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read the difference between this SubDevice's local copy of the system time and the system
+    /// time last distributed by the DC reference SubDevice, in nanoseconds.
+    ///
+    /// A negative value means the local copy is behind the reference. The value should stay close
+    /// to zero while the reference time is distributed every cycle, e.g. with
+    /// [`SlaveGroup::tx_rx_sync_system_time`](crate::SlaveGroup::tx_rx_sync_system_time) or
+    /// [`Client::sync_system_time`](crate::Client::sync_system_time).
+    pub async fn dc_system_time_difference(&self) -> Result<i32, Error> {
+        self.read(RegisterAddress::DcSystemTimeDifference)
+            .receive::<u32>(self.client)
+            .await
+            .map(decode_system_time_difference)
+    }
+
+    /// Read the DC sync modes defined in this SubDevice's EEPROM.
+    ///
+    /// Returns an empty list if the SubDevice doesn't define any.
+    pub async fn dc_sync_modes(&self) -> Result<heapless::Vec<DcSyncMode, 8>, Error> {
+        let eeprom = self.eeprom();
+
+        let mut modes = heapless::Vec::new();
+
+        for sii in eeprom.dc_sync_modes().await? {
+            let name = eeprom
+                .find_string(sii.name_string_idx)
+                .await?
+                .unwrap_or_default();
+
+            crate::fmt::debug!(
+                "Slave {:#06x} DC sync mode {} AssignActivate {:#06x}",
+                self.configured_address,
+                name,
+                sii.assign_activate
+            );
+
+            // Both lists have the same capacity
+            let _ = modes.push(DcSyncMode::new(sii, name));
+        }
+
+        Ok(modes)
+    }
+
+    /// Set how the positive and negative edges of a DC latch input are captured.
+    ///
+    /// Latch inputs store the DC system time of edges on a SubDevice's `LATCH0` or `LATCH1` pin, so
+    /// external events like encoder index pulses or camera triggers can be timestamped in
+    /// EtherCAT bus time.
+    pub async fn configure_latch(
+        &self,
+        latch: Latch,
+        positive: LatchMode,
+        negative: LatchMode,
+    ) -> Result<(), Error> {
+        let control = u8::from(positive == LatchMode::SingleEvent)
+            | u8::from(negative == LatchMode::SingleEvent) << 1;
+
+        self.write(latch.control()).send(self.client, control).await
+    }
+
+    /// Read the status of a DC latch input.
+    pub async fn latch_status(&self, latch: Latch) -> Result<LatchStatus, Error> {
+        self.read(latch.status()).receive(self.client).await
+    }
+
+    /// Read the DC system time in nanoseconds of the last captured edge of a DC latch input.
+    ///
+    /// In [`LatchMode::SingleEvent`] mode, this acknowledges the event so the next edge can be
+    /// captured.
+    pub async fn latch_time(&self, latch: Latch, edge: LatchEdge) -> Result<u64, Error> {
+        self.read(latch.time(edge)).receive(self.client).await
+    }
+
+    /// Read the time of a [`LatchMode::SingleEvent`] mode latch event, if one has been captured
+    /// since the last call.
+    ///
+    /// Returns `Ok(None)` if no edge has been captured.
+    pub async fn latch_event(&self, latch: Latch, edge: LatchEdge) -> Result<Option<u64>, Error> {
+        let status = self.latch_status(latch).await?;
+
+        let captured = match edge {
+            LatchEdge::Positive => status.positive_event,
+            LatchEdge::Negative => status.negative_event,
+        };
+
+        if !captured {
+            return Ok(None);
+        }
+
+        self.latch_time(latch, edge).await.map(Some)
+    }
+
+    /// Read the length of the SYNC0 and SYNC1 pulses.
+    ///
+    /// The pulse length is loaded from the SubDevice's EEPROM on power up and can't be changed by
+    /// the MainDevice. A length of zero means the SYNC signals are acknowledged by reading the
+    /// SYNC status registers instead of being pulses.
+    pub async fn dc_sync_pulse_length(&self) -> Result<Duration, Error> {
+        self.read(RegisterAddress::DcSyncPulseLength)
+            .receive::<u16>(self.client)
+            .await
+            .map(|tens_of_ns| Duration::from_nanos(u64::from(tens_of_ns) * 10))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireRead;
+
+    #[test]
+    fn sync_mode_from_eeprom() {
+        let cycle_time = Duration::from_millis(1);
+
+        let sii = SiiDcSync {
+            cycle_time0: 0,
+            shift_time0: 10_000,
+            shift_time1: 0,
+            sync1_cycle_factor: 0,
+            assign_activate: 0x0300,
+            sync0_cycle_factor: 1,
+            name_string_idx: 6,
+            desc_string_idx: 0,
+        };
+
+        let mode = DcSyncMode::new(sii, heapless::String::try_from("DcSync").unwrap());
+
+        assert_eq!(mode.dc_sync(cycle_time), DcSync::Sync0);
+        assert_eq!(
+            mode.timing(cycle_time),
+            DcSyncTiming {
+                sync0_period: Some(cycle_time),
+                sync0_shift: Duration::from_micros(10),
+                start_time: None,
+            }
+        );
+
+        let mode = DcSyncMode::new(
+            SiiDcSync {
+                assign_activate: 0x0700,
+                sync0_cycle_factor: -4,
+                sync1_cycle_factor: 2,
+                shift_time1: 5_000,
+                ..sii
+            },
+            heapless::String::new(),
+        );
+
+        assert_eq!(mode.sync0_period(cycle_time), Duration::from_micros(250));
+        assert_eq!(
+            mode.dc_sync(cycle_time),
+            DcSync::Sync01 {
+                sync1_period: Duration::from_micros(505)
+            }
+        );
+
+        let mode = DcSyncMode::new(
+            SiiDcSync {
+                cycle_time0: 2_000_000,
+                assign_activate: 0x0000,
+                ..sii
+            },
+            heapless::String::new(),
+        );
+
+        assert_eq!(mode.sync0_period(cycle_time), Duration::from_millis(2));
+        assert_eq!(mode.dc_sync(cycle_time), DcSync::Disabled);
+    }
+
+    #[test]
+    fn negative_shift_from_eeprom() {
+        let cycle_time = Duration::from_millis(1);
+
+        let mode = DcSyncMode::new(
+            SiiDcSync {
+                cycle_time0: 0,
+                shift_time0: -1_000i32 as u32,
+                shift_time1: -5_000i32 as u32,
+                sync1_cycle_factor: 2,
+                assign_activate: 0x0700,
+                sync0_cycle_factor: 1,
+                name_string_idx: 0,
+                desc_string_idx: 0,
+            },
+            heapless::String::new(),
+        );
+
+        assert_eq!(mode.sync0_shift, -1_000);
+        assert_eq!(mode.sync1_shift, -5_000);
+        assert_eq!(
+            mode.timing(cycle_time),
+            DcSyncTiming {
+                sync0_period: Some(cycle_time),
+                sync0_shift: Duration::from_micros(999),
+                start_time: None,
+            }
+        );
+        assert_eq!(
+            mode.dc_sync(cycle_time),
+            DcSync::Sync01 {
+                sync1_period: Duration::from_micros(1995)
+            }
+        );
+    }
+
+    #[test]
+    fn decode_latch_status() {
+        assert_eq!(
+            LatchStatus::unpack_from_slice(&[0b0000_0101]),
+            Ok(LatchStatus {
+                positive_event: true,
+                negative_event: false,
+                pin: true,
+            })
+        );
+    }
+
+    #[test]
+    fn system_time_difference_sign() {
+        assert_eq!(decode_system_time_difference(0), 0);
+        assert_eq!(decode_system_time_difference(0x0000_0064), 100);
+        assert_eq!(decode_system_time_difference(0x8000_0064), -100);
+        assert_eq!(decode_system_time_difference(0x7fff_ffff), i32::MAX);
+    }
+}
@@ -0,0 +1,112 @@
+use super::{Slave, SlaveRef};
+use crate::{
+    error::{Error, MailboxError},
+    fmt,
+};
+use core::ops::Deref;
+
+/// Manufacturer device name object.
+const DEVICE_NAME: u16 = 0x1008;
+
+/// Manufacturer hardware version object.
+const HARDWARE_VERSION: u16 = 0x1009;
+
+/// Manufacturer software version object.
+const SOFTWARE_VERSION: u16 = 0x100a;
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read the manufacturer device name from CoE object `0x1008`, up to `N` bytes long.
+    ///
+    /// If the SubDevice does not support CoE or the object does not exist, the name read from the
+    /// SubDevice's EEPROM is returned instead. This is the same as [`name`](SlaveRef::name).
+    ///
+    /// # Errors
+    ///
+    /// If the name is longer than `N` bytes, a
+    /// [`MailboxError::TooLong`](crate::error::MailboxError::TooLong) is returned for a name read
+    /// over CoE, or an [`Error::StringTooLong`] for the name from EEPROM.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// log::info!(
+    ///     "{} firmware {}",
+    ///     slave.device_name::<128>().await?,
+    ///     slave.firmware_version::<128>().await?.unwrap_or_default()
+    /// );
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn device_name<const N: usize>(&self) -> Result<heapless::String<N>, Error> {
+        if let Some(name) = self.coe_string(DEVICE_NAME).await? {
+            return Ok(name);
+        }
+
+        heapless::String::try_from(self.state.name.as_str()).map_err(|_| Error::StringTooLong {
+            max_length: N,
+            string_length: self.state.name.len(),
+        })
+    }
+
+    /// Read the manufacturer hardware version from CoE object `0x1009`, up to `N` bytes long.
+    ///
+    /// The EEPROM does not store a hardware version, so `None` is returned if the SubDevice does
+    /// not support CoE or the object does not exist. The product revision from
+    /// [`identity`](SlaveRef::identity) may be used instead in this case.
+    ///
+    /// A [`MailboxError::TooLong`](crate::error::MailboxError::TooLong) is returned if the value is
+    /// longer than `N` bytes.
+    pub async fn hardware_version<const N: usize>(
+        &self,
+    ) -> Result<Option<heapless::String<N>>, Error> {
+        self.coe_string(HARDWARE_VERSION).await
+    }
+
+    /// Read the manufacturer software version from CoE object `0x100A`, up to `N` bytes long.
+    ///
+    /// Like [`hardware_version`](SlaveRef::hardware_version), `None` is returned if the SubDevice
+    /// does not support CoE or the object does not exist.
+    pub async fn firmware_version<const N: usize>(
+        &self,
+    ) -> Result<Option<heapless::String<N>>, Error> {
+        self.coe_string(SOFTWARE_VERSION).await
+    }
+
+    /// Read an optional string object, returning `None` if the SubDevice doesn't support CoE or
+    /// doesn't have the object.
+    async fn coe_string<const N: usize>(
+        &self,
+        index: u16,
+    ) -> Result<Option<heapless::String<N>>, Error> {
+        if !self.state.config.mailbox.has_coe {
+            return Ok(None);
+        }
+
+        match self.sdo_read(index, 0).await {
+            Ok(s) => Ok(Some(s)),
+            Err(Error::Mailbox(MailboxError::Aborted { code, .. })) => {
+                fmt::debug!(
+                    "Slave {:#06x} string object {:#06x} not available: {}",
+                    self.configured_address,
+                    index,
+                    code
+                );
+
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use super::{Slave, SlaveRef};
+use crate::{
+    coe::diagnosis::{
+        DiagnosisMessage, DIAGNOSIS_HISTORY, FIRST_MESSAGE, MAX_MESSAGES, NEWEST_MESSAGE,
+    },
+    error::{Error, MailboxError},
+    fmt,
+};
+use core::ops::Deref;
+use ethercrab_wire::EtherCrabWireRead;
+
+/// The longest diagnosis message, including parameters, that can be read.
+const MAX_MESSAGE_LEN: usize = 128;
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read up to `N` messages from the SubDevice's diagnosis history object `0x10F3`, newest
+    /// first.
+    ///
+    /// An empty list is returned if the SubDevice has not stored any messages. If the SubDevice
+    /// does not support the diagnosis history object, an
+    /// [`MailboxError::Aborted`](crate::error::MailboxError::Aborted) error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let mut group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// for slave in group.iter(&client) {
+    ///     for message in slave.diagnosis_history::<16>().await? {
+    ///         log::info!(
+    ///             "{}: {:?} {:#010x} text ID {:#06x} at {} ns",
+    ///             slave.name(),
+    ///             message.severity,
+    ///             message.code,
+    ///             message.text_id,
+    ///             message.timestamp
+    ///         );
+    ///     }
+    /// }
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn diagnosis_history<const N: usize>(
+        &self,
+    ) -> Result<heapless::Vec<DiagnosisMessage, N>, Error> {
+        let mut messages = heapless::Vec::new();
+
+        let max_messages = self.sdo_read::<u8>(DIAGNOSIS_HISTORY, MAX_MESSAGES).await?;
+        let newest = self
+            .sdo_read::<u8>(DIAGNOSIS_HISTORY, NEWEST_MESSAGE)
+            .await?;
+
+        for sub_index in history_ring(max_messages, newest).take(N) {
+            let raw = match self
+                .sdo_read::<heapless::Vec<u8, MAX_MESSAGE_LEN>>(DIAGNOSIS_HISTORY, sub_index)
+                .await
+            {
+                Ok(raw) => raw,
+                // Unused message slots may be rejected by the SubDevice
+                Err(Error::Mailbox(MailboxError::Aborted { .. })) => break,
+                Err(e) => return Err(e),
+            };
+
+            // An empty slot means we've read all stored messages.
+            let Ok(message) = DiagnosisMessage::unpack_from_slice(&raw) else {
+                break;
+            };
+
+            // Capacity is checked by `take(N)`
+            let _ = messages.push(message);
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} read {} diagnosis messages",
+            self.configured_address,
+            messages.len()
+        );
+
+        Ok(messages)
+    }
+}
+
+/// The sub-indices of the messages in the diagnosis history, newest first.
+///
+/// Messages are stored in a ring buffer of `max_messages` sub-indices starting at
+/// [`FIRST_MESSAGE`], so we walk backwards from the newest one, wrapping around to the end of the
+/// buffer. Nothing is returned if `newest` isn't in the buffer, meaning no messages have been
+/// stored yet.
+fn history_ring(max_messages: u8, newest: u8) -> impl Iterator<Item = u8> {
+    // Sub-indices are a `u8`, so a SubDevice can't store more messages than this.
+    let count = max_messages.min(u8::MAX - FIRST_MESSAGE + 1);
+
+    let newest = newest
+        .checked_sub(FIRST_MESSAGE)
+        .filter(|newest| *newest < count);
+
+    let count = if newest.is_some() { count } else { 0 };
+    let newest = newest.unwrap_or(0);
+
+    (0..count).map(move |i| {
+        let offset = (u16::from(newest) + u16::from(count) - u16::from(i)) % u16::from(count);
+
+        // Offset is less than `count`, so always fits in the sub-index.
+        FIRST_MESSAGE + offset as u8
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_newest_first() {
+        let ring = history_ring(4, FIRST_MESSAGE + 3).collect::<Vec<_>>();
+
+        assert_eq!(ring, [0x09, 0x08, 0x07, 0x06]);
+    }
+
+    #[test]
+    fn ring_wraps() {
+        let ring = history_ring(4, FIRST_MESSAGE + 1).collect::<Vec<_>>();
+
+        assert_eq!(ring, [0x07, 0x06, 0x09, 0x08]);
+    }
+
+    #[test]
+    fn ring_empty() {
+        assert_eq!(history_ring(0, FIRST_MESSAGE).count(), 0);
+
+        // Newest index of 0 means no messages have been stored
+        assert_eq!(history_ring(4, 0).count(), 0);
+
+        // Out of range
+        assert_eq!(history_ring(4, FIRST_MESSAGE + 4).count(), 0);
+    }
+
+    #[test]
+    fn ring_max_len() {
+        let ring = history_ring(u8::MAX, u8::MAX).collect::<Vec<_>>();
+
+        assert_eq!(ring.len(), usize::from(u8::MAX - FIRST_MESSAGE + 1));
+        assert_eq!(ring.first(), Some(&u8::MAX));
+        assert_eq!(ring.last(), Some(&FIRST_MESSAGE));
+
+        // Wrapping from the start of the buffer back to the last sub-index
+        let ring = history_ring(u8::MAX, FIRST_MESSAGE).collect::<Vec<_>>();
+
+        assert_eq!(&ring[0..2], &[FIRST_MESSAGE, u8::MAX]);
+    }
+}
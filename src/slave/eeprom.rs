@@ -1,22 +1,33 @@
 use crate::{
     eeprom::types::{
-        CategoryType, DefaultMailbox, PdoEntry, SiiGeneral, RX_PDO_RANGE, TX_PDO_RANGE,
+        BootstrapMailbox, CategoryType, DefaultMailbox, PdoEntry, SiiGeneral, RX_PDO_RANGE,
+        TX_PDO_RANGE,
     },
     eeprom::{
+        config_checksum,
         device_reader::SII_FIRST_CATEGORY_START,
-        types::{FmmuEx, FmmuUsage, Pdo, PdoType, SyncManager},
-        ChunkReader, EepromDataProvider,
+        types::{FmmuEx, FmmuUsage, Pdo, PdoType, SiiDcSync, SyncManager},
+        CategoryCache, ChunkReader, EepromDataProvider,
     },
     error::{EepromError, Error, Item},
     fmt,
     slave::SlaveIdentity,
 };
+use atomic_refcell::AtomicRefCell;
 use core::{marker::PhantomData, ops::RangeInclusive, str::FromStr};
 use embedded_io_async::{Read, ReadExactError};
 use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireReadSized, EtherCrabWireSized};
 
+/// A SubDevice's SII EEPROM, parsed into the structured categories defined in ETG2010.
+///
+/// Each method reads the EEPROM when called, and reads over the SII interface are slow, so
+/// results should be kept instead of being read again.
+///
+/// See the [`sii`](crate::sii) module for an example.
 pub struct SlaveEeprom<P> {
     provider: P,
+    /// Locations of categories found by earlier searches, if caching is enabled.
+    cache: Option<AtomicRefCell<CategoryCache>>,
 }
 
 /// EEPROM methods.
@@ -24,31 +35,77 @@ impl<P> SlaveEeprom<P>
 where
     P: EepromDataProvider,
 {
-    pub(crate) fn new(provider: P) -> Self {
-        Self { provider }
+    /// Parse the EEPROM served by `provider`.
+    ///
+    /// Use [`SlaveRef::eeprom`](crate::SlaveRef::eeprom) to read a SubDevice's EEPROM, or a
+    /// [`MemoryEeprom`](crate::sii::MemoryEeprom) to parse an EEPROM image without hardware.
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            cache: None,
+        }
+    }
+
+    /// Parse the EEPROM served by `provider`, remembering the location of every category found so
+    /// later searches don't read the same category headers again.
+    pub(crate) fn with_category_cache(provider: P) -> Self {
+        Self {
+            provider,
+            cache: Some(AtomicRefCell::new(CategoryCache::default())),
+        }
     }
 
     /// Start a reader at the given address in words, returning at most `len` bytes.
     fn start_at(&self, word_addr: u16, len_bytes: u16) -> ChunkReader<P> {
-        ChunkReader::new(self.provider.clone(), word_addr, len_bytes / 2)
+        ChunkReader::new(
+            self.provider.clone(),
+            u32::from(word_addr),
+            u32::from(len_bytes / 2),
+        )
     }
 
     /// Search for a given category and return a reader over the bytes contained within the category
     /// if it is found.
+    ///
+    /// If category locations are cached, cached locations are used and the search continues from
+    /// the last cached category.
     async fn category(&self, category: CategoryType) -> Result<Option<ChunkReader<P>>, Error> {
         let mut reader = self.provider.clone();
 
-        let mut word_addr = SII_FIRST_CATEGORY_START;
+        // If the cache is in use elsewhere, the EEPROM is searched from the start instead
+        let mut cache = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.try_borrow_mut().ok());
 
-        loop {
-            let chunk = reader.read_chunk(word_addr).await?;
+        let mut word_addr = match cache.as_ref() {
+            Some(cache) => match cache.get(category) {
+                Some(Some((start_word, len_words))) => {
+                    return Ok(Some(ChunkReader::new(
+                        self.provider.clone(),
+                        u32::from(start_word),
+                        u32::from(len_words),
+                    )));
+                }
+                Some(None) => return Ok(None),
+                None => cache.next_header(),
+            },
+            None => SII_FIRST_CATEGORY_START,
+        };
 
-            word_addr += 2;
+        loop {
+            let chunk = reader.read_chunk(u32::from(word_addr)).await?;
 
             let category_type =
                 CategoryType::from(u16::from_le_bytes(fmt::unwrap!(chunk[0..2].try_into())));
             let len_words = u16::from_le_bytes(fmt::unwrap!(chunk[2..4].try_into()));
 
+            if let Some(cache) = cache.as_mut() {
+                cache.insert(word_addr, category_type, len_words);
+            }
+
+            word_addr += 2;
+
             fmt::trace!(
                 "Found category {:?} at {:#06x} bytes, length {:#04x} ({}) words",
                 category_type,
@@ -61,8 +118,8 @@ where
                 cat if cat == category => {
                     break Ok(Some(ChunkReader::new(
                         self.provider.clone(),
-                        word_addr,
-                        len_words,
+                        u32::from(word_addr),
+                        u32::from(len_words),
                     )));
                 }
                 CategoryType::End => break Ok(None),
@@ -78,9 +135,7 @@ where
     ///
     /// Note that the string index is hard coded to `1` instead of reading the string index from the
     /// EEPROM `General` section.
-    pub(crate) async fn device_name<const N: usize>(
-        &self,
-    ) -> Result<Option<heapless::String<N>>, Error> {
+    pub async fn device_name<const N: usize>(&self) -> Result<Option<heapless::String<N>>, Error> {
         // Uncomment to read longer, but correct, name string from EEPROM
         // let general = self.general().await?;
         // let name_idx = general.name_string_idx;
@@ -95,7 +150,50 @@ where
         self.find_string(name_idx).await
     }
 
-    pub(crate) async fn mailbox_config(&self) -> Result<DefaultMailbox, Error> {
+    /// Get the device group, e.g. `"DigOut"`, from the string indexed by
+    /// [`SiiGeneral::group_string_idx`].
+    ///
+    /// Returns `Ok(None)` if the device has no group string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::NoCategory`] if the `General` category is not present.
+    pub async fn group_name<const N: usize>(&self) -> Result<Option<heapless::String<N>>, Error> {
+        let general = self.general().await?;
+
+        self.find_string(general.group_string_idx).await
+    }
+
+    /// Get the device order number, e.g. `"EL2004"`, from the string indexed by
+    /// [`SiiGeneral::order_string_idx`].
+    ///
+    /// Returns `Ok(None)` if the device has no order number string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::NoCategory`] if the `General` category is not present.
+    pub async fn order_number<const N: usize>(&self) -> Result<Option<heapless::String<N>>, Error> {
+        let general = self.general().await?;
+
+        self.find_string(general.order_string_idx).await
+    }
+
+    /// Get the name of the device's image, used by configuration tools to display an icon, from the
+    /// string indexed by [`SiiGeneral::image_string_idx`].
+    ///
+    /// Returns `Ok(None)` if the device has no image name string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::NoCategory`] if the `General` category is not present.
+    pub async fn image_name<const N: usize>(&self) -> Result<Option<heapless::String<N>>, Error> {
+        let general = self.general().await?;
+
+        self.find_string(general.image_string_idx).await
+    }
+
+    /// Get the standard mailbox configuration from the configuration area.
+    pub async fn mailbox_config(&self) -> Result<DefaultMailbox, Error> {
         // Start reading standard mailbox config. Raw start address defined in ETG2010 Table 2.
         // Mailbox config is 10 bytes long.
         let mut reader = self.start_at(0x0018, DefaultMailbox::PACKED_LEN as u16);
@@ -109,7 +207,27 @@ where
         Ok(DefaultMailbox::unpack_from_slice(&buf)?)
     }
 
-    pub(crate) async fn general(&self) -> Result<SiiGeneral, Error> {
+    /// Get the mailbox configuration used in BOOT from the configuration area.
+    pub async fn bootstrap_mailbox_config(&self) -> Result<BootstrapMailbox, Error> {
+        // Bootstrap mailbox config comes just before the standard mailbox config, as defined in
+        // ETG2010 Table 2.
+        let mut reader = self.start_at(0x0014, BootstrapMailbox::PACKED_LEN as u16);
+
+        fmt::trace!("Get bootstrap mailbox config");
+
+        let mut buf = BootstrapMailbox::buffer();
+
+        reader.read_exact(&mut buf).await?;
+
+        Ok(BootstrapMailbox::unpack_from_slice(&buf)?)
+    }
+
+    /// Get the "General" category.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::NoCategory`] if the category is not present.
+    pub async fn general(&self) -> Result<SiiGeneral, Error> {
         let mut reader = self
             .category(CategoryType::General)
             .await?
@@ -122,7 +240,8 @@ where
         Ok(SiiGeneral::unpack_from_slice(&buf)?)
     }
 
-    pub(crate) async fn identity(&self) -> Result<SlaveIdentity, Error> {
+    /// Get the vendor ID, product code, revision and serial number from the configuration area.
+    pub async fn identity(&self) -> Result<SlaveIdentity, Error> {
         let mut reader = self.start_at(0x0008, SlaveIdentity::PACKED_LEN as u16);
 
         fmt::trace!("Get identity");
@@ -134,7 +253,53 @@ where
         Ok(SlaveIdentity::unpack_from_slice(&buf)?)
     }
 
-    pub(crate) async fn sync_managers(&self) -> Result<heapless::Vec<SyncManager, 8>, Error> {
+    /// Read the raw configuration area in WORDs `0x0000` to `0x0007`, including the checksum in
+    /// the last WORD.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::Checksum`] if the checksum does not match the rest of the
+    /// configuration area.
+    pub async fn config_area(&self) -> Result<[u8; 16], Error> {
+        let mut reader = self.start_at(0x0000, 16);
+
+        fmt::trace!("Get configuration area");
+
+        let mut buf = [0u8; 16];
+
+        reader.read_exact(&mut buf).await?;
+
+        let checksum = config_checksum(&buf[0..14]);
+
+        if buf[14] != checksum {
+            fmt::error!(
+                "EEPROM configuration area checksum is {:#04x}, expected {:#04x}",
+                buf[14],
+                checksum
+            );
+
+            return Err(Error::Eeprom(EepromError::Checksum));
+        }
+
+        Ok(buf)
+    }
+
+    /// Get the size of the EEPROM in bytes.
+    pub async fn size(&self) -> Result<usize, Error> {
+        // ETG2010 Table 2: kilobits minus 1
+        let mut reader = self.start_at(0x003e, 2);
+
+        fmt::trace!("Get EEPROM size");
+
+        let mut buf = [0u8; 2];
+
+        reader.read_exact(&mut buf).await?;
+
+        Ok((usize::from(u16::from_le_bytes(buf)) + 1) * 1024 / 8)
+    }
+
+    /// Get the sync managers in the "SyncM" category, in order of sync manager index.
+    pub async fn sync_managers(&self) -> Result<heapless::Vec<SyncManager, 8>, Error> {
         let mut sync_managers = heapless::Vec::<_, 8>::new();
 
         fmt::trace!("Get sync managers");
@@ -152,7 +317,10 @@ where
         Ok(sync_managers)
     }
 
-    pub(crate) async fn fmmus(&self) -> Result<heapless::Vec<FmmuUsage, 16>, Error> {
+    /// Get the usage of each FMMU from the "FMMU" category, in order of FMMU index.
+    ///
+    /// Returns an empty list if the category is not present.
+    pub async fn fmmus(&self) -> Result<heapless::Vec<FmmuUsage, 16>, Error> {
         let category = self.category(CategoryType::Fmmu).await?;
 
         fmt::trace!("Get FMMUs");
@@ -185,7 +353,10 @@ where
         Ok(fmmus)
     }
 
-    pub(crate) async fn fmmu_mappings(&self) -> Result<heapless::Vec<FmmuEx, 16>, Error> {
+    /// Get the sync manager mapped by each FMMU from the "FMMU_EX" category.
+    ///
+    /// Returns an empty list if the category is not present.
+    pub async fn fmmu_mappings(&self) -> Result<heapless::Vec<FmmuEx, 16>, Error> {
         let mut mappings = heapless::Vec::<_, 16>::new();
 
         fmt::trace!("Get FMMU mappings");
@@ -203,6 +374,29 @@ where
         Ok(mappings)
     }
 
+    /// Get the DC sync modes from the "DC" category.
+    ///
+    /// Returns an empty list if the category is not present.
+    pub async fn dc_sync_modes(&self) -> Result<heapless::Vec<SiiDcSync, 8>, Error> {
+        let mut modes = heapless::Vec::<_, 8>::new();
+
+        fmt::trace!("Get DC sync modes");
+
+        let mut cat = self
+            .items::<SiiDcSync>(CategoryType::DistributedClock)
+            .await?;
+
+        while let Some(mode) = cat.next().await? {
+            modes
+                .push(mode)
+                .map_err(|_| Error::Capacity(Item::DcSyncMode))?;
+        }
+
+        fmt::debug!("DC sync modes: {:#?}", modes);
+
+        Ok(modes)
+    }
+
     async fn pdos(
         &self,
         direction: PdoType,
@@ -259,19 +453,25 @@ where
     }
 
     /// Transmit PDOs (from device's perspective) - inputs
-    pub(crate) async fn master_read_pdos(&self) -> Result<heapless::Vec<Pdo, 16>, Error> {
+    ///
+    /// Read from the "TxPDO" category, returning an empty list if it is not present.
+    pub async fn master_read_pdos(&self) -> Result<heapless::Vec<Pdo, 16>, Error> {
         self.pdos(PdoType::Tx, TX_PDO_RANGE).await
     }
 
     /// Receive PDOs (from device's perspective) - outputs
-    pub(crate) async fn master_write_pdos(&self) -> Result<heapless::Vec<Pdo, 16>, Error> {
+    ///
+    /// Read from the "RxPDO" category, returning an empty list if it is not present.
+    pub async fn master_write_pdos(&self) -> Result<heapless::Vec<Pdo, 16>, Error> {
         self.pdos(PdoType::Rx, RX_PDO_RANGE).await
     }
 
     /// Find a string in the device EEPROM.
     ///
-    /// An index of 0 denotes an empty string and will always return `Ok(None)`.
-    pub(crate) async fn find_string<const N: usize>(
+    /// String indices are found in other categories, e.g. [`SiiGeneral::order_string_idx`]. An
+    /// index of 0 denotes an empty string and will always return `Ok(None)`, as will an index past
+    /// the end of the "Strings" category.
+    pub async fn find_string<const N: usize>(
         &self,
         search_index: u8,
     ) -> Result<Option<heapless::String<N>>, Error> {
@@ -290,7 +490,7 @@ where
 
             fmt::trace!("--> Slave has {} strings", num_strings);
 
-            if search_index > num_strings {
+            if search_index >= num_strings {
                 return Ok(None);
             }
 
@@ -405,12 +605,13 @@ mod tests {
         eeprom::{
             file_reader::EepromFile,
             types::{
-                CoeDetails, Flags, MailboxProtocols, PdoFlags, PortStatus, PortStatuses,
+                CoeDetails, GeneralFlags, MailboxProtocols, PdoFlags, PortStatus, PortStatuses,
                 SyncManagerEnable, SyncManagerType,
             },
         },
         sync_manager_channel::{Control, Direction, OperationMode},
     };
+    use core::sync::atomic::{AtomicUsize, Ordering};
 
     #[tokio::test]
     async fn read_device_name() {
@@ -558,6 +759,7 @@ mod tests {
 
         // Any strings after the valid index range shouldn't error, but should return nothing.
         assert_eq!(e.find_string::<64>(num_strings + 1).await, Ok(None));
+        assert_eq!(e.find_string::<64>(u8::MAX).await, Ok(None));
 
         assert_eq!(
             strings,
@@ -700,6 +902,29 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_bootstrap_mailbox_config() {
+        let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
+
+        assert_eq!(
+            e.bootstrap_mailbox_config().await,
+            Ok(BootstrapMailbox {
+                slave_receive_offset: 0x1800,
+                slave_receive_size: 0x0400,
+                slave_send_offset: 0x1c00,
+                slave_send_size: 0x0400,
+            })
+        );
+
+        // No bootstrap mailbox
+        let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/ek1100.hex"));
+
+        assert_eq!(
+            e.bootstrap_mailbox_config().await,
+            Ok(BootstrapMailbox::default())
+        );
+    }
+
     #[tokio::test]
     async fn default_mailbox_config_matches_sms() {
         let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
@@ -743,6 +968,42 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_dc_sync_modes() {
+        let dc_sync = SiiDcSync {
+            cycle_time0: 0,
+            shift_time0: 0,
+            shift_time1: 0,
+            sync1_cycle_factor: 0,
+            assign_activate: 0x0300,
+            sync0_cycle_factor: 1,
+            name_string_idx: 6,
+            desc_string_idx: 0,
+        };
+
+        assert_eq!(
+            SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"))
+                .dc_sync_modes()
+                .await,
+            Ok(heapless::Vec::from_slice(&[
+                dc_sync,
+                SiiDcSync {
+                    assign_activate: 0x0000,
+                    name_string_idx: 7,
+                    ..dc_sync
+                }
+            ])
+            .unwrap())
+        );
+
+        assert_eq!(
+            SlaveEeprom::new(EepromFile::new("dumps/eeprom/el2828.hex"))
+                .dc_sync_modes()
+                .await,
+            Ok(heapless::Vec::new())
+        );
+    }
+
     #[tokio::test]
     async fn no_fmmus() {
         let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/ek1100.hex"));
@@ -750,6 +1011,20 @@ mod tests {
         assert_eq!(e.fmmus().await, Ok(heapless::Vec::new()));
     }
 
+    #[tokio::test]
+    async fn config_area() {
+        let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/el2828.hex"));
+
+        assert_eq!(
+            e.config_area().await,
+            Ok([
+                0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, //
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xe2, 0x00, //
+            ])
+        );
+        assert_eq!(e.size().await, Ok(2048));
+    }
+
     #[tokio::test]
     async fn identity() {
         let e = SlaveEeprom::new(EepromFile::new("dumps/eeprom/akd.hex"));
@@ -781,7 +1056,7 @@ mod tests {
                     | CoeDetails::ENABLE_PDO_CONFIG,
                 foe_enabled: true,
                 eoe_enabled: true,
-                flags: Flags::ENABLE_SAFE_OP | Flags::MAILBOX_DLL,
+                flags: GeneralFlags::ENABLE_SAFE_OP | GeneralFlags::MAILBOX_DLL,
                 ebus_current: 0,
                 ports: PortStatuses([
                     PortStatus::Ebus,
@@ -808,7 +1083,7 @@ mod tests {
                 coe_details: CoeDetails::empty(),
                 foe_enabled: false,
                 eoe_enabled: false,
-                flags: Flags::empty(),
+                flags: GeneralFlags::empty(),
                 ebus_current: -2000,
                 ports: PortStatuses([
                     PortStatus::Ebus,
@@ -839,6 +1114,10 @@ mod tests {
             name,
             Ok(Some("AKD EtherCAT Drive (CoE)".try_into().unwrap()))
         );
+
+        assert_eq!(e.group_name::<128>().await, group);
+        assert_eq!(e.image_name::<128>().await, image);
+        assert_eq!(e.order_number::<128>().await, order);
     }
 
     #[tokio::test]
@@ -852,6 +1131,7 @@ mod tests {
         let image = e.find_string::<128>(general.image_string_idx).await;
 
         assert_eq!(image, Ok(None));
+        assert_eq!(e.image_name::<128>().await, Ok(None));
     }
 
     #[tokio::test]
@@ -863,4 +1143,71 @@ mod tests {
         // None of the EEPROM dumps I have contain any FMMU_EX records :(
         assert_eq!(fmmu_ex, heapless::Vec::<FmmuEx, 16>::new());
     }
+
+    /// An EEPROM file that counts chunk reads.
+    #[derive(Clone)]
+    struct CountingFile<'a> {
+        file: EepromFile<8>,
+        reads: &'a AtomicUsize,
+    }
+
+    impl EepromDataProvider for CountingFile<'_> {
+        async fn read_chunk(
+            &mut self,
+            start_word: u32,
+        ) -> Result<impl core::ops::Deref<Target = [u8]>, Error> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+
+            self.file.read_chunk(start_word).await
+        }
+
+        async fn clear_errors(&self) -> Result<(), Error> {
+            self.file.clear_errors().await
+        }
+
+        async fn write_word(&mut self, word: u32, data: u16) -> Result<(), Error> {
+            self.file.write_word(word, data).await
+        }
+    }
+
+    #[tokio::test]
+    async fn category_cache() {
+        let cached_reads = AtomicUsize::new(0);
+        let uncached_reads = AtomicUsize::new(0);
+
+        let cached = SlaveEeprom::with_category_cache(CountingFile {
+            file: EepromFile::new("dumps/eeprom/akd.hex"),
+            reads: &cached_reads,
+        });
+        let uncached = SlaveEeprom::new(CountingFile {
+            file: EepromFile::new("dumps/eeprom/akd.hex"),
+            reads: &uncached_reads,
+        });
+
+        // Same reads as SubDevice configuration
+        for _ in 0..2 {
+            assert_eq!(cached.sync_managers().await, uncached.sync_managers().await);
+            assert_eq!(cached.general().await, uncached.general().await);
+            assert_eq!(cached.fmmus().await, uncached.fmmus().await);
+            assert_eq!(
+                cached.master_read_pdos().await,
+                uncached.master_read_pdos().await
+            );
+            assert_eq!(
+                cached.master_write_pdos().await,
+                uncached.master_write_pdos().await
+            );
+            assert_eq!(cached.fmmu_mappings().await, uncached.fmmu_mappings().await);
+        }
+
+        let cached_reads = cached_reads.load(Ordering::Relaxed);
+        let uncached_reads = uncached_reads.load(Ordering::Relaxed);
+
+        assert!(
+            cached_reads < uncached_reads,
+            "{} cached reads, {} uncached",
+            cached_reads,
+            uncached_reads
+        );
+    }
 }
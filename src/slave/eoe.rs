@@ -0,0 +1,1136 @@
+use super::{Slave, SlaveRef};
+use crate::{
+    eeprom::types::MailboxProtocols,
+    eoe::{EoeFrameType, EoeHeader, FragmentInfo, FRAGMENT_BLOCK_LEN, MAX_FRAME_LEN},
+    error::{EoeError, Error, MailboxError},
+    fmt,
+    mailbox::MailboxType,
+};
+use core::{ops::Deref, sync::atomic::Ordering, time::Duration};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
+use smoltcp::{
+    phy::{self, DeviceCapabilities, Medium},
+    time::Instant,
+    wire::{EthernetAddress, EthernetFrame},
+};
+
+/// The longest Ethernet II frame, excluding the FCS, buffered by [`EoeDevice`].
+const ETHERNET_FRAME_LEN: usize = 1514;
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Send an Ethernet frame to the SubDevice using EoE (Ethernet over EtherCAT).
+    ///
+    /// The frame is split into as many fragments as required to fit in the SubDevice's mailbox.
+    /// `frame` must be a complete Ethernet II frame starting with the destination MAC address, and
+    /// at most 2016 bytes long.
+    pub async fn eoe_send(&self, frame: &[u8]) -> Result<(), Error> {
+        self.eoe_fragment_len()?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        self.send_eoe_frame(frame).await
+    }
+
+    /// Send an Ethernet frame. The mailbox must already be locked.
+    async fn send_eoe_frame(&self, frame: &[u8]) -> Result<(), Error> {
+        let fragment_len = self.eoe_fragment_len()?;
+
+        if frame.len() > MAX_FRAME_LEN {
+            return Err(Error::Eoe(EoeError::FrameTooLong));
+        }
+
+        let frame_number = self.state.eoe_frame_number.fetch_add(1, Ordering::Relaxed) & 0x0f;
+
+        // Every fragment but the last is a whole number of blocks long.
+        let mut fragments = frame.chunks(fragment_len).zip(0u8..).peekable();
+
+        while let Some((data, fragment_number)) = fragments.next() {
+            let last_fragment = fragments.peek().is_none();
+
+            // The first fragment holds the length of the whole frame instead of an offset.
+            let offset = if fragment_number == 0 {
+                frame.len().div_ceil(FRAGMENT_BLOCK_LEN)
+            } else {
+                usize::from(fragment_number) * fragment_len / FRAGMENT_BLOCK_LEN
+            };
+
+            let header = EoeHeader::fragment(
+                self.mailbox_counter(),
+                FragmentInfo {
+                    fragment_number,
+                    offset: offset as u8,
+                    frame_number,
+                },
+                last_fragment,
+                u16::try_from(data.len())?,
+            );
+
+            let (_read_mailbox, write_mailbox) = self.mailboxes().await?;
+
+            self.write_mailbox(&write_mailbox, (header, data)).await?;
+        }
+
+        fmt::trace!(
+            "Slave {:#06x} EoE sent {} byte frame {}",
+            self.configured_address,
+            frame.len(),
+            frame_number
+        );
+
+        Ok(())
+    }
+
+    /// Receive an Ethernet frame sent by the SubDevice using EoE (Ethernet over EtherCAT).
+    ///
+    /// If the SubDevice has sent a frame, it is reassembled into `buf` and its length is returned.
+    /// If no frame is waiting in the SubDevice's mailbox, `None` is returned immediately. A
+    /// 1514 byte buffer is large enough to hold any standard Ethernet II frame.
+    ///
+    /// EoE fragments read from the mailbox by other operations, e.g. while clearing the mailbox
+    /// before an SDO request, are kept and returned by the next call to this method. One frame of
+    /// up to 1514 bytes is buffered for all SubDevices, so fragments from other SubDevices are
+    /// discarded until a frame being received has been completed and collected. Any CoE
+    /// emergencies received while waiting for a frame are queued as with other mailbox operations.
+    /// Other non-EoE mailbox data is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// let mut frame = [0u8; 1514];
+    ///
+    /// loop {
+    ///     if let Some(len) = slave.eoe_receive(&mut frame).await? {
+    ///         log::info!("Received {} byte frame", len);
+    ///
+    ///         // Echo the frame back
+    ///         slave.eoe_send(&frame[0..len]).await?;
+    ///     }
+    /// }
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn eoe_receive(&self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        self.eoe_fragment_len()?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        self.receive_eoe_frame(buf, Some(self.client.timeouts.eoe_response))
+            .await
+    }
+
+    /// Receive an Ethernet frame. The mailbox must already be locked.
+    ///
+    /// Once the first fragment of a frame has been received, the remaining fragments are waited for
+    /// for up to `fragment_timeout` each. If `fragment_timeout` is `None`, only fragments that are
+    /// already in the mailbox are read, and an incomplete frame is kept to be completed by a later
+    /// call.
+    async fn receive_eoe_frame(
+        &self,
+        buf: &mut [u8],
+        fragment_timeout: Option<Duration>,
+    ) -> Result<Option<usize>, Error> {
+        let read_mailbox = self
+            .state
+            .config
+            .mailbox
+            .read
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        loop {
+            if let Some(len) = self.take_eoe_frame(buf)? {
+                fmt::trace!(
+                    "Slave {:#06x} EoE received {} byte frame",
+                    self.configured_address,
+                    len
+                );
+
+                break Ok(Some(len));
+            }
+
+            let in_progress = {
+                let reassembly = self
+                    .client
+                    .eoe_reassembly
+                    .try_borrow()
+                    .map_err(|_| Error::Borrow)?;
+
+                reassembly.in_progress() && reassembly.owner() == Some(self.configured_address)
+            };
+
+            let response = match fragment_timeout {
+                Some(timeout) if in_progress => {
+                    match self.mailbox_response(&read_mailbox, timeout).await {
+                        Ok(response) => response,
+                        Err(e) => {
+                            // Don't hold up frames from other SubDevices waiting for one that may
+                            // never be completed.
+                            self.discard_eoe_frame();
+
+                            break Err(e);
+                        }
+                    }
+                }
+                _ => match self.read_mailbox_if_full(&read_mailbox).await? {
+                    Some(response) => response,
+                    None => break Ok(None),
+                },
+            };
+
+            match self.reassemble_eoe(&response) {
+                Some(result) => result?,
+                None => {
+                    self.unsolicited_response(&response);
+
+                    // Nothing was received yet, so don't wait for a frame that may never arrive.
+                    if !in_progress {
+                        break Ok(None);
+                    }
+                }
+            }
+        }
+    }
+
+    /// If the given mailbox data holds an EoE fragment, add it to the frame being received from
+    /// this SubDevice.
+    ///
+    /// Returns `true` if the data was an EoE fragment. Fragments that can't be reassembled are
+    /// logged and discarded.
+    pub(super) fn queue_eoe_fragment(&self, mailbox: &[u8]) -> bool {
+        match self.reassemble_eoe(mailbox) {
+            None => false,
+            Some(Ok(())) => true,
+            Some(Err(e)) => {
+                fmt::warn!(
+                    "Slave {:#06x} discarding EoE fragment: {}",
+                    self.configured_address,
+                    e
+                );
+
+                true
+            }
+        }
+    }
+
+    /// Add an EoE fragment to the frame being received, returning `None` if the mailbox data is
+    /// not an EoE fragment.
+    fn reassemble_eoe(&self, mailbox: &[u8]) -> Option<Result<(), Error>> {
+        let header = EoeHeader::unpack_from_slice(mailbox).ok()?;
+
+        if header.header.mailbox_type != MailboxType::Eoe
+            || header.frame_type != EoeFrameType::FragmentData
+        {
+            return None;
+        }
+
+        // Mailbox length includes the 4 byte EoE header.
+        let data_len = usize::from(header.header.length)
+            .saturating_sub(4)
+            .min(mailbox.len().saturating_sub(EoeHeader::PACKED_LEN));
+
+        let data = &mailbox[EoeHeader::PACKED_LEN..][..data_len];
+
+        let Ok(mut reassembly) = self.client.eoe_reassembly.try_borrow_mut() else {
+            return Some(Err(Error::Borrow));
+        };
+
+        // Only one frame is buffered for all SubDevices, and it must be collected before another
+        // can be received.
+        if let Some(owner) = reassembly
+            .owner()
+            .filter(|owner| *owner != self.configured_address)
+        {
+            fmt::warn!(
+                "Slave {:#06x} EoE frame is being received, discarding fragment {} of frame {} from slave {:#06x}",
+                owner,
+                header.fragment.fragment_number,
+                header.fragment.frame_number,
+                self.configured_address
+            );
+
+            return Some(Ok(()));
+        }
+
+        if reassembly.complete_frame().is_some() {
+            fmt::warn!(
+                "Slave {:#06x} EoE frame has not been received yet, discarding fragment {} of frame {}",
+                self.configured_address,
+                header.fragment.fragment_number,
+                header.fragment.frame_number
+            );
+
+            return Some(Ok(()));
+        }
+
+        Some(
+            reassembly
+                .push(self.configured_address, &header, data)
+                .map_err(|e| {
+                    fmt::error!(
+                "Slave {:#06x} sent EoE fragment {} of frame {} which could not be reassembled: {}",
+                self.configured_address,
+                header.fragment.fragment_number,
+                header.fragment.frame_number,
+                e
+            );
+
+                    Error::Eoe(e)
+                }),
+        )
+    }
+
+    /// Copy a completely received frame into `buf`, returning its length.
+    fn take_eoe_frame(&self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        let mut reassembly = self
+            .client
+            .eoe_reassembly
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow)?;
+
+        if reassembly.owner() != Some(self.configured_address) {
+            return Ok(None);
+        }
+
+        let Some(frame) = reassembly.complete_frame() else {
+            return Ok(None);
+        };
+
+        let len = frame.len();
+
+        let result = buf
+            .get_mut(0..len)
+            .ok_or(Error::Eoe(EoeError::FrameTooLong))
+            .map(|buf| buf.copy_from_slice(frame));
+
+        // The frame is discarded even if it doesn't fit so later frames can still be received.
+        *reassembly = EoeReassembly::default();
+
+        result.map(|_| Some(len))
+    }
+
+    /// Discard an incomplete frame being received from this SubDevice.
+    fn discard_eoe_frame(&self) {
+        let Ok(mut reassembly) = self.client.eoe_reassembly.try_borrow_mut() else {
+            return;
+        };
+
+        if reassembly.in_progress() && reassembly.owner() == Some(self.configured_address) {
+            fmt::warn!(
+                "Slave {:#06x} EoE frame {} was not completed, discarding it",
+                self.configured_address,
+                reassembly.frame_number
+            );
+
+            *reassembly = EoeReassembly::default();
+        }
+    }
+
+    /// Check that the SubDevice supports EoE, returning the maximum length of frame data in every
+    /// fragment but the last.
+    fn eoe_fragment_len(&self) -> Result<usize, Error> {
+        let config = &self.state.config.mailbox;
+
+        if !config.supported_protocols.contains(MailboxProtocols::EOE) {
+            return Err(Error::Eoe(EoeError::NotSupported));
+        }
+
+        let write = config
+            .write
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        let fragment_len = usize::from(write.len).saturating_sub(EoeHeader::PACKED_LEN)
+            / FRAGMENT_BLOCK_LEN
+            * FRAGMENT_BLOCK_LEN;
+
+        if fragment_len == 0 {
+            return Err(Error::Mailbox(MailboxError::NoMailbox));
+        }
+
+        Ok(fragment_len)
+    }
+}
+
+/// An Ethernet frame being reassembled from EoE fragments received from a SubDevice.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct EoeReassembly {
+    /// The configured address of the SubDevice the frame is received from.
+    configured_address: u16,
+    frame: heapless::Vec<u8, ETHERNET_FRAME_LEN>,
+    /// The number of the next fragment, or `0` if no frame is being received.
+    next_fragment: u8,
+    frame_number: u8,
+    /// Set once the last fragment has been received, until the frame is collected.
+    complete: bool,
+}
+
+impl EoeReassembly {
+    pub(crate) const fn new() -> Self {
+        Self {
+            configured_address: 0,
+            frame: heapless::Vec::new(),
+            next_fragment: 0,
+            frame_number: 0,
+            complete: false,
+        }
+    }
+
+    /// The configured address of the SubDevice a frame is being received from, or which has sent
+    /// a frame that has not been collected yet.
+    fn owner(&self) -> Option<u16> {
+        (self.next_fragment > 0 || self.complete).then_some(self.configured_address)
+    }
+
+    /// Returns `true` if some but not all fragments of a frame have been received.
+    fn in_progress(&self) -> bool {
+        self.next_fragment > 0 && !self.complete
+    }
+
+    fn complete_frame(&self) -> Option<&[u8]> {
+        self.complete.then_some(self.frame.as_slice())
+    }
+
+    /// Add a fragment received from the SubDevice with the given configured address to the frame.
+    ///
+    /// The first fragment of a frame always starts a new frame, discarding any incomplete one. If
+    /// the fragment is out of order, the frame being received is discarded.
+    fn push(
+        &mut self,
+        configured_address: u16,
+        header: &EoeHeader,
+        data: &[u8],
+    ) -> Result<(), EoeError> {
+        let fragment = header.fragment;
+
+        if fragment.fragment_number == 0 {
+            if self.in_progress() {
+                fmt::warn!(
+                    "EoE frame {} was not completed, discarding it",
+                    self.frame_number
+                );
+            }
+
+            *self = Self {
+                configured_address,
+                frame_number: fragment.frame_number,
+                ..Self::default()
+            };
+        } else {
+            let offset = usize::from(fragment.offset) * FRAGMENT_BLOCK_LEN;
+
+            if fragment.fragment_number != self.next_fragment
+                || fragment.frame_number != self.frame_number
+                || offset != self.frame.len()
+            {
+                *self = Self::default();
+
+                return Err(EoeError::InvalidFragment);
+            }
+        }
+
+        if self.frame.extend_from_slice(data).is_err() {
+            *self = Self::default();
+
+            return Err(EoeError::FrameTooLong);
+        }
+
+        if header.last_fragment {
+            self.complete = true;
+        } else {
+            self.next_fragment = fragment.fragment_number.wrapping_add(1);
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`smoltcp`] network device that sends and receives Ethernet frames through a SubDevice's EoE
+/// (Ethernet over EtherCAT) mailbox.
+///
+/// smoltcp devices are synchronous, so the device buffers one frame in each direction. Call
+/// [`EoeDevice::poll`] alongside the smoltcp interface's own `poll` to move frames between these
+/// buffers and the SubDevice. No OS network stack is required, so this can be used in `no_std`
+/// environments.
+///
+/// To run EoE traffic from the same task as the cyclic process data exchange, call
+/// [`EoeDevice::poll_cyclic`] once per cycle instead. This limits mailbox polling to the rate set
+/// with [`EoeDevice::with_poll_interval`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, EoeDevice, PduStorage, Timeouts };
+/// use smoltcp::{
+///     iface::{Config, Interface, SocketSet},
+///     time::Instant,
+///     wire::EthernetAddress,
+/// };
+/// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+/// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+/// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+/// # async {
+/// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+///
+/// let slave = group.slave(&client, 0)?;
+///
+/// let mut device = EoeDevice::new();
+///
+/// let config = Config::new(EthernetAddress([0x02, 0, 0, 0, 0, 0x01]).into());
+/// let mut iface = Interface::new(config, &mut device, Instant::from_millis(0));
+/// let mut sockets = SocketSet::new(Vec::new());
+///
+/// loop {
+///     let now = Instant::from_millis(0);
+///
+///     iface.poll(now, &mut device, &mut sockets);
+///
+///     device.poll(&slave).await?;
+/// }
+/// # Ok::<(), Error>(())
+/// # };
+/// ```
+pub struct EoeDevice {
+    rx: [u8; ETHERNET_FRAME_LEN],
+    rx_len: Option<usize>,
+    tx: [u8; ETHERNET_FRAME_LEN],
+    tx_len: Option<usize>,
+    /// Poll the mailbox every this many calls to `poll_cyclic`.
+    poll_interval: u32,
+    /// Calls to `poll_cyclic` since the mailbox was last polled.
+    cycles: u32,
+}
+
+impl Default for EoeDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EoeDevice {
+    /// Create a new device with empty send and receive buffers.
+    pub const fn new() -> Self {
+        Self {
+            rx: [0u8; ETHERNET_FRAME_LEN],
+            rx_len: None,
+            tx: [0u8; ETHERNET_FRAME_LEN],
+            tx_len: None,
+            poll_interval: 1,
+            cycles: 0,
+        }
+    }
+
+    /// Set how often [`EoeDevice::poll_cyclic`] polls the SubDevice's mailbox, in process data
+    /// cycles.
+    ///
+    /// The default is `1`, polling every cycle. A value of `0` is treated as `1`.
+    pub const fn with_poll_interval(self, cycles: u32) -> Self {
+        Self {
+            poll_interval: if cycles == 0 { 1 } else { cycles },
+            ..self
+        }
+    }
+
+    /// Receive a frame from the SubDevice if the receive buffer is empty, then send a frame queued
+    /// by smoltcp to the SubDevice.
+    ///
+    /// The mailbox is read before sending so a frame the SubDevice has already sent isn't held up
+    /// behind the outgoing one.
+    ///
+    /// Returns `true` if a frame was sent or received.
+    pub async fn poll<S>(&mut self, slave: &SlaveRef<'_, S>) -> Result<bool, Error>
+    where
+        S: Deref<Target = Slave>,
+    {
+        slave.eoe_fragment_len()?;
+
+        let _lock = slave.lock_mailbox().await?;
+
+        self.exchange(slave, Some(slave.client.timeouts.eoe_response))
+            .await
+    }
+
+    /// Call [`EoeDevice::poll`] at the rate set by [`EoeDevice::with_poll_interval`], without
+    /// waiting for the mailbox.
+    ///
+    /// This method should be called once per process data cycle, e.g. after
+    /// [`SlaveGroup::tx_rx`](crate::SlaveGroup::tx_rx). On cycles where the mailbox isn't due to be
+    /// polled, it returns `Ok(false)` immediately.
+    ///
+    /// If another mailbox transfer such as an SDO read is in progress when a poll is due, the poll is
+    /// skipped and the mailbox is next polled after another full interval, so the cyclic task is
+    /// never held up waiting for the mailbox. Only fragments already waiting in the mailbox are
+    /// read, so a frame sent in several fragments is reassembled over as many polls as it takes.
+    /// Frames from other SubDevices can't be received until it is complete, as one frame is
+    /// buffered for all SubDevices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, EoeDevice, PduStorage, Timeouts };
+    /// # use std::time::Duration;
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let group = group.into_op(&client).await?;
+    ///
+    /// // Poll the EoE mailbox every 10th cycle
+    /// let mut device = EoeDevice::new().with_poll_interval(10);
+    ///
+    /// loop {
+    ///     group.tx_rx(&client).await?;
+    ///
+    ///     let slave = group.slave(&client, 0)?;
+    ///
+    ///     device.poll_cyclic(&slave).await?;
+    ///
+    ///     // Poll smoltcp interface here
+    ///
+    ///     smol::Timer::after(Duration::from_millis(1)).await;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn poll_cyclic<S>(&mut self, slave: &SlaveRef<'_, S>) -> Result<bool, Error>
+    where
+        S: Deref<Target = Slave>,
+    {
+        if !self.poll_due() {
+            return Ok(false);
+        }
+
+        slave.eoe_fragment_len()?;
+
+        let Some(_lock) = slave.try_lock_mailbox() else {
+            fmt::trace!("Mailbox busy, skipping EoE poll");
+
+            return Ok(false);
+        };
+
+        self.exchange(slave, None).await
+    }
+
+    /// Count a process data cycle, returning `true` if the mailbox should be polled this cycle.
+    fn poll_due(&mut self) -> bool {
+        self.cycles = self.cycles.saturating_add(1);
+
+        if self.cycles < self.poll_interval {
+            return false;
+        }
+
+        self.cycles = 0;
+
+        true
+    }
+
+    /// Receive then send a frame. The mailbox must already be locked.
+    async fn exchange<S>(
+        &mut self,
+        slave: &SlaveRef<'_, S>,
+        fragment_timeout: Option<Duration>,
+    ) -> Result<bool, Error>
+    where
+        S: Deref<Target = Slave>,
+    {
+        let mut activity = false;
+
+        if self.rx_len.is_none() {
+            self.rx_len = slave
+                .receive_eoe_frame(&mut self.rx, fragment_timeout)
+                .await?;
+
+            activity = self.rx_len.is_some();
+        }
+
+        if let Some(len) = self.tx_len {
+            slave.send_eoe_frame(&self.tx[0..len]).await?;
+
+            self.tx_len = None;
+
+            activity = true;
+        }
+
+        Ok(activity)
+    }
+}
+
+impl phy::Device for EoeDevice {
+    type RxToken<'a> = EoeRxToken<'a>;
+    type TxToken<'a> = EoeTxToken<'a>;
+
+    fn receive(&mut self, _timestamp: Instant) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        // A reply can't be queued until the previous frame has been sent.
+        if self.tx_len.is_some() {
+            return None;
+        }
+
+        let len = self.rx_len.take()?;
+
+        Some((
+            EoeRxToken {
+                buf: &mut self.rx[0..len],
+            },
+            EoeTxToken {
+                buf: &mut self.tx,
+                len: &mut self.tx_len,
+            },
+        ))
+    }
+
+    fn transmit(&mut self, _timestamp: Instant) -> Option<Self::TxToken<'_>> {
+        if self.tx_len.is_some() {
+            return None;
+        }
+
+        Some(EoeTxToken {
+            buf: &mut self.tx,
+            len: &mut self.tx_len,
+        })
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let mut caps = DeviceCapabilities::default();
+
+        caps.medium = Medium::Ethernet;
+        caps.max_transmission_unit = ETHERNET_FRAME_LEN;
+        caps.max_burst_size = Some(1);
+
+        caps
+    }
+}
+
+/// A frame received by an [`EoeDevice`].
+pub struct EoeRxToken<'a> {
+    buf: &'a mut [u8],
+}
+
+impl phy::RxToken for EoeRxToken<'_> {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        f(self.buf)
+    }
+}
+
+/// Space for a frame to be sent by an [`EoeDevice`].
+pub struct EoeTxToken<'a> {
+    buf: &'a mut [u8; ETHERNET_FRAME_LEN],
+    len: &'a mut Option<usize>,
+}
+
+impl phy::TxToken for EoeTxToken<'_> {
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let len = len.min(ETHERNET_FRAME_LEN);
+
+        let result = f(&mut self.buf[0..len]);
+
+        *self.len = Some(len);
+
+        result
+    }
+}
+
+/// A virtual Ethernet switch that forwards EoE (Ethernet over EtherCAT) frames between
+/// SubDevices.
+///
+/// Each SubDevice passed to [`EoeSwitch::poll`] is treated as a switch port, identified by its
+/// configured address. The switch learns which port each source MAC address was seen on, and
+/// forwards frames to the port their destination MAC address was learnt on. Broadcast, multicast
+/// and unknown destination frames are sent to every other port.
+///
+/// Up to `N` MAC addresses are remembered. When the table is full, the oldest entry is forgotten.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, EoeSwitch, PduStorage, Timeouts };
+/// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+/// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+/// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+/// # async {
+/// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+///
+/// let ports = [group.slave(&client, 1)?, group.slave(&client, 4)?];
+///
+/// let mut switch = EoeSwitch::<16>::new();
+///
+/// loop {
+///     switch.poll(&ports).await?;
+/// }
+/// # Ok::<(), Error>(())
+/// # };
+/// ```
+pub struct EoeSwitch<const N: usize> {
+    /// Learnt MAC addresses and the configured address of the port they were seen on, oldest
+    /// first.
+    table: heapless::Vec<(EthernetAddress, u16), N>,
+    frame: [u8; ETHERNET_FRAME_LEN],
+}
+
+impl<const N: usize> Default for EoeSwitch<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> EoeSwitch<N> {
+    /// Create a new switch with an empty MAC address table.
+    pub const fn new() -> Self {
+        Self {
+            table: heapless::Vec::new(),
+            frame: [0u8; ETHERNET_FRAME_LEN],
+        }
+    }
+
+    /// Receive at most one frame from each port and forward it to its destination port(s).
+    ///
+    /// Returns the number of frames received.
+    pub async fn poll<S>(&mut self, ports: &[SlaveRef<'_, S>]) -> Result<usize, Error>
+    where
+        S: Deref<Target = Slave>,
+    {
+        let mut received = 0;
+
+        for port in ports {
+            let Some(len) = port.eoe_receive(&mut self.frame).await? else {
+                continue;
+            };
+
+            received += 1;
+
+            let Ok(frame) = EthernetFrame::new_checked(&self.frame[0..len]) else {
+                fmt::warn!(
+                    "Slave {:#06x} sent {} byte EoE frame which is too short, discarding",
+                    port.configured_address,
+                    len
+                );
+
+                continue;
+            };
+
+            let (src, dst) = (frame.src_addr(), frame.dst_addr());
+
+            self.learn(src, port.configured_address);
+
+            match self.lookup(dst) {
+                // Destination is on the same port the frame came from
+                Some(address) if address == port.configured_address => (),
+                Some(address) => {
+                    if let Some(dst_port) = ports.iter().find(|p| p.configured_address == address) {
+                        dst_port.eoe_send(&self.frame[0..len]).await?;
+                    }
+                }
+                // Broadcast, multicast, and unknown destinations are flooded
+                None => {
+                    for dst_port in ports
+                        .iter()
+                        .filter(|p| p.configured_address != port.configured_address)
+                    {
+                        dst_port.eoe_send(&self.frame[0..len]).await?;
+                    }
+                }
+            }
+        }
+
+        Ok(received)
+    }
+
+    /// Record that `mac` was seen on the port with the given configured address.
+    fn learn(&mut self, mac: EthernetAddress, port: u16) {
+        if !mac.is_unicast() {
+            return;
+        }
+
+        if let Some(position) = self.table.iter().position(|(m, _)| *m == mac) {
+            self.table.remove(position);
+        } else if self.table.is_full() && !self.table.is_empty() {
+            self.table.remove(0);
+        }
+
+        // Space was made above, so this can't fail.
+        let _ = self.table.push((mac, port));
+    }
+
+    /// Find the configured address of the port `mac` was last seen on.
+    fn lookup(&self, mac: EthernetAddress) -> Option<u16> {
+        if !mac.is_unicast() {
+            return None;
+        }
+
+        self.table
+            .iter()
+            .find(|(m, _)| *m == mac)
+            .map(|(_, port)| *port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        slave::mock::{MockSubDevice, SdoServer},
+        ClientConfig, Timeouts,
+    };
+    use ethercrab_wire::EtherCrabWireWriteSized;
+    use smoltcp::phy::{Device, RxToken, TxToken};
+
+    #[test]
+    fn device_buffers_one_frame_each_way() {
+        let mut device = EoeDevice::new();
+
+        assert!(device.receive(Instant::ZERO).is_none());
+
+        device
+            .transmit(Instant::ZERO)
+            .expect("empty tx buffer")
+            .consume(4, |buf| buf.copy_from_slice(&[1, 2, 3, 4]));
+
+        assert_eq!(device.tx_len, Some(4));
+        assert!(device.transmit(Instant::ZERO).is_none());
+
+        // Simulate `poll` sending and receiving a frame
+        device.tx_len = None;
+        device.rx[0..2].copy_from_slice(&[5, 6]);
+        device.rx_len = Some(2);
+
+        let (rx, _tx) = device.receive(Instant::ZERO).expect("frame received");
+
+        rx.consume(|buf| assert_eq!(buf, &[5, 6]));
+
+        assert!(device.receive(Instant::ZERO).is_none());
+    }
+
+    #[test]
+    fn switch_learns_and_forgets() {
+        let mut switch = EoeSwitch::<2>::new();
+
+        let a = EthernetAddress([0x02, 0, 0, 0, 0, 0x0a]);
+        let b = EthernetAddress([0x02, 0, 0, 0, 0, 0x0b]);
+        let c = EthernetAddress([0x02, 0, 0, 0, 0, 0x0c]);
+
+        switch.learn(a, 0x1000);
+        switch.learn(b, 0x1001);
+        switch.learn(EthernetAddress::BROADCAST, 0x1001);
+
+        assert_eq!(switch.lookup(a), Some(0x1000));
+        assert_eq!(switch.lookup(b), Some(0x1001));
+        assert_eq!(switch.lookup(EthernetAddress::BROADCAST), None);
+
+        // Station moved to another port
+        switch.learn(a, 0x1002);
+
+        assert_eq!(switch.lookup(a), Some(0x1002));
+
+        // Table is full so the oldest entry, `b`, is dropped
+        switch.learn(c, 0x1000);
+
+        assert_eq!(switch.lookup(b), None);
+        assert_eq!(switch.lookup(a), Some(0x1002));
+        assert_eq!(switch.lookup(c), Some(0x1000));
+    }
+
+    fn fragment(fragment_number: u8, offset: u8, frame_number: u8, last: bool) -> EoeHeader {
+        EoeHeader::fragment(
+            1,
+            FragmentInfo {
+                fragment_number,
+                offset,
+                frame_number,
+            },
+            last,
+            0,
+        )
+    }
+
+    #[test]
+    fn reassemble_fragments() {
+        let mut reassembly = EoeReassembly::default();
+
+        assert_eq!(
+            reassembly.push(0x1001, &fragment(0, 2, 3, false), &[1u8; 32]),
+            Ok(())
+        );
+        assert!(reassembly.in_progress());
+        assert_eq!(reassembly.complete_frame(), None);
+
+        assert_eq!(
+            reassembly.push(0x1001, &fragment(1, 1, 3, true), &[2u8; 10]),
+            Ok(())
+        );
+        assert!(!reassembly.in_progress());
+
+        let frame = reassembly.complete_frame().expect("complete frame");
+
+        assert_eq!(frame.len(), 42);
+        assert_eq!(&frame[30..34], &[1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn reassemble_out_of_order() {
+        let mut reassembly = EoeReassembly::default();
+
+        // No first fragment
+        assert_eq!(
+            reassembly.push(0x1001, &fragment(1, 1, 3, true), &[2u8; 10]),
+            Err(EoeError::InvalidFragment)
+        );
+
+        assert_eq!(
+            reassembly.push(0x1001, &fragment(0, 3, 3, false), &[1u8; 32]),
+            Ok(())
+        );
+
+        // Wrong frame number
+        assert_eq!(
+            reassembly.push(0x1001, &fragment(1, 1, 4, false), &[1u8; 32]),
+            Err(EoeError::InvalidFragment)
+        );
+        assert!(!reassembly.in_progress());
+
+        // A new first fragment replaces the incomplete frame
+        assert_eq!(
+            reassembly.push(0x1001, &fragment(0, 3, 5, false), &[1u8; 32]),
+            Ok(())
+        );
+        assert_eq!(
+            reassembly.push(0x1001, &fragment(0, 1, 6, true), &[3u8; 4]),
+            Ok(())
+        );
+        assert_eq!(reassembly.complete_frame(), Some([3u8; 4].as_slice()));
+    }
+
+    #[test]
+    fn reassembly_owner() {
+        let mut reassembly = EoeReassembly::new();
+
+        assert_eq!(reassembly.owner(), None);
+
+        reassembly
+            .push(0x1001, &fragment(0, 2, 3, false), &[1u8; 32])
+            .expect("first fragment");
+
+        assert_eq!(reassembly.owner(), Some(0x1001));
+
+        // A single fragment frame is owned until it is collected.
+        reassembly
+            .push(0x1001, &fragment(0, 1, 4, true), &[1u8; 4])
+            .expect("single fragment");
+
+        assert!(!reassembly.in_progress());
+        assert_eq!(reassembly.owner(), Some(0x1001));
+    }
+
+    /// EoE mailbox data holding a fragment of a frame.
+    fn eoe_fragment(fragment: FragmentInfo, last: bool, data: &[u8]) -> Vec<u8> {
+        let header = EoeHeader::fragment(0, fragment, last, data.len() as u16).pack();
+
+        // Skip the mailbox header
+        let mut packet = header[6..].to_vec();
+
+        packet.extend_from_slice(data);
+
+        packet
+    }
+
+    /// Send the fragments in `range` of a frame split into 96 byte fragments.
+    fn send_frame(mock: &MockSubDevice, frame: &[u8], range: core::ops::Range<u8>) {
+        for fragment_number in range {
+            let start = usize::from(fragment_number) * 96;
+            let data = &frame[start..frame.len().min(start + 96)];
+
+            let offset = if fragment_number == 0 {
+                frame.len().div_ceil(FRAGMENT_BLOCK_LEN)
+            } else {
+                start / FRAGMENT_BLOCK_LEN
+            };
+
+            mock.send(
+                MailboxType::Eoe,
+                &eoe_fragment(
+                    FragmentInfo {
+                        fragment_number,
+                        offset: offset as u8,
+                        frame_number: 7,
+                    },
+                    start + data.len() == frame.len(),
+                    data,
+                ),
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn receive_fragmented_frame() {
+        let (client, mock) =
+            MockSubDevice::start(Timeouts::default(), ClientConfig::default(), |_, _| {
+                Vec::new()
+            });
+
+        let slave = MockSubDevice::slave(MailboxProtocols::EOE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let mut buf = [0u8; ETHERNET_FRAME_LEN];
+
+        assert_eq!(slave.eoe_receive(&mut buf).await, Ok(None), "no frame");
+
+        let frame = (0..200u8).collect::<Vec<_>>();
+
+        send_frame(&mock, &frame, 0..3);
+
+        assert_eq!(slave.eoe_receive(&mut buf).await, Ok(Some(200)));
+        assert_eq!(&buf[0..200], frame.as_slice());
+    }
+
+    #[tokio::test]
+    async fn keep_fragments_read_by_sdo() {
+        let mut server = SdoServer::new([((0x1000, 0), 0x0192u32.to_le_bytes().to_vec())]);
+
+        let (client, mock) = MockSubDevice::start(
+            Timeouts::default(),
+            ClientConfig::default(),
+            move |_, request| vec![(MailboxType::Coe, server.handle(request))],
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::COE | MailboxProtocols::EOE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let frame = (0..200u8).collect::<Vec<_>>();
+
+        // The first fragment is waiting in the mailbox when the SDO request is sent.
+        send_frame(&mock, &frame, 0..1);
+
+        assert_eq!(slave.sdo_read::<u32>(0x1000, 0).await, Ok(0x0192));
+
+        send_frame(&mock, &frame, 1..3);
+
+        let mut buf = [0u8; ETHERNET_FRAME_LEN];
+
+        assert_eq!(slave.eoe_receive(&mut buf).await, Ok(Some(200)));
+        assert_eq!(&buf[0..200], frame.as_slice());
+    }
+
+    #[test]
+    fn cyclic_poll_interval() {
+        let mut device = EoeDevice::new().with_poll_interval(3);
+
+        let due = (0..6).map(|_| device.poll_due()).collect::<Vec<_>>();
+
+        assert_eq!(due, [false, false, true, false, false, true]);
+    }
+}
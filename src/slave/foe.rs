@@ -0,0 +1,1050 @@
+use super::{
+    large_mailbox::MailboxData,
+    types::{BootMailbox, Mailbox},
+    ProtocolSession, Slave, SlaveRef,
+};
+use crate::{
+    eeprom::types::MailboxProtocols,
+    error::{Error, FoeError, MailboxError},
+    fmt,
+    foe::{FoeErrorCode, FoeHeader, FoeOpCode},
+    mailbox::MailboxType,
+    register::RegisterAddress,
+    slave_state::SlaveState,
+    sync_manager_channel::{Status, SyncManagerChannel},
+};
+use core::ops::Deref;
+use embedded_io_async::{ErrorType, Read, Write};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read a file from the SubDevice using FoE (File access over EtherCAT), returning the number
+    /// of bytes read into `buf`.
+    ///
+    /// A `password` of `0` is used by most SubDevices if no password is required.
+    ///
+    /// # Errors
+    ///
+    /// An [`FoeError::TooLong`] is returned if the file doesn't fit in `buf`. The transfer is not
+    /// completed in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let mut group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// let mut buf = [0u8; 4096];
+    ///
+    /// let len = slave.foe_read("log.txt", 0, &mut buf).await?;
+    ///
+    /// log::info!("{}", core::str::from_utf8(&buf[0..len]).unwrap_or("<invalid UTF8>"));
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn foe_read(
+        &self,
+        name: &str,
+        password: u32,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let mut reader = self.foe_reader(name, password).await?;
+
+        let mut len = 0;
+
+        loop {
+            let Some(rest) = buf.get_mut(len..).filter(|rest| !rest.is_empty()) else {
+                // The buffer is full, so the file must end here for it to fit.
+                if reader.read(&mut [0u8]).await? > 0 {
+                    return Err(Error::Foe(FoeError::TooLong));
+                }
+
+                break;
+            };
+
+            match reader.read(rest).await? {
+                0 => break,
+                n => len += n,
+            }
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} FoE read {} bytes from {}",
+            self.configured_address,
+            len,
+            name
+        );
+
+        Ok(len)
+    }
+
+    /// Start reading a file from the SubDevice using FoE (File access over EtherCAT), returning a
+    /// reader that implements [`embedded_io_async::Read`].
+    ///
+    /// This allows large files to be read without buffering the entire file in memory. The
+    /// SubDevice's mailbox is only locked while each packet is received, so other mailbox
+    /// protocols can be used while the reader exists, but no other FoE transfer can be started
+    /// until it is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// use embedded_io_async::Read;
+    ///
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// let mut reader = slave.foe_reader("log.txt", 0).await?;
+    ///
+    /// let mut buf = [0u8; 128];
+    ///
+    /// loop {
+    ///     let len = reader.read(&mut buf).await?;
+    ///
+    ///     if len == 0 {
+    ///         break;
+    ///     }
+    ///
+    ///     // Do something with `buf[0..len]`
+    /// }
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn foe_reader(
+        &self,
+        name: &str,
+        password: u32,
+    ) -> Result<FoeReader<'_, 'a, S>, Error> {
+        let (read_len, _write_len) = self.foe_data_capacity(name)?;
+
+        let session = self.foe_session()?;
+
+        let mut reader = FoeReader {
+            slave: self,
+            packet_len: read_len,
+            packet_number: 1,
+            packet: None,
+            pos: 0,
+            done: false,
+            _session: session,
+        };
+
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self
+            .send_foe(FoeOpCode::ReadRequest, password, name.as_bytes())
+            .await?;
+
+        reader.receive_packet(&read_mailbox).await?;
+
+        Ok(reader)
+    }
+
+    /// Start writing a file to the SubDevice using FoE (File access over EtherCAT), returning a
+    /// writer that implements [`embedded_io_async::Write`].
+    ///
+    /// `N` is the size of the packet buffer in the writer which must be at least as large as the
+    /// SubDevice's write mailbox, minus 12 bytes of headers. If it is too small, an
+    /// [`FoeError::TooLong`] is returned.
+    ///
+    /// [`FoeWriter::finish`] must be called once all data is written to complete the transfer. As
+    /// with [`foe_reader`](SlaveRef::foe_reader), no other FoE transfer can be started until the
+    /// writer is finished or dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// use embedded_io_async::Write;
+    ///
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// let mut writer = slave.foe_writer::<1024>("config.bin", 0).await?;
+    ///
+    /// for chunk in [b"first chunk".as_slice(), b"second chunk"] {
+    ///     writer.write_all(chunk).await?;
+    /// }
+    ///
+    /// writer.finish().await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn foe_writer<const N: usize>(
+        &self,
+        name: &str,
+        password: u32,
+    ) -> Result<FoeWriter<'_, 'a, S, N>, Error> {
+        let (_read_len, write_len) = self.foe_data_capacity(name)?;
+
+        if write_len > N {
+            fmt::error!(
+                "FoE writer buffer of {} bytes cannot hold {} byte packets",
+                N,
+                write_len
+            );
+
+            return Err(Error::Foe(FoeError::TooLong));
+        }
+
+        let session = self.foe_session()?;
+
+        self.send_foe_acked(FoeOpCode::WriteRequest, password, name.as_bytes(), 0)
+            .await?;
+
+        Ok(FoeWriter {
+            slave: self,
+            packet_len: write_len,
+            packet_number: 1,
+            acknowledged: 0,
+            pending: heapless::Vec::new(),
+            _session: session,
+        })
+    }
+
+    /// Write a file to the SubDevice using FoE (File access over EtherCAT).
+    ///
+    /// A `password` of `0` is used by most SubDevices if no password is required.
+    ///
+    /// Many SubDevices only accept firmware files in the `BOOT` state. Use
+    /// [`update_firmware`](SlaveRef::update_firmware) to transition to `BOOT` and write the file in
+    /// one go, or [`enter_bootstrap`](SlaveRef::enter_bootstrap) to enter `BOOT` first.
+    pub async fn foe_write(&self, name: &str, password: u32, data: &[u8]) -> Result<(), Error> {
+        self.foe_write_with_progress(name, password, data, |_, _| ())
+            .await
+    }
+
+    /// Update the SubDevice's firmware by writing `data` to the file `name` in the `BOOT` state.
+    ///
+    /// The SubDevice is first transitioned to `INIT`, then to `BOOT`, after which the firmware file
+    /// is written with [`foe_write`](SlaveRef::foe_write). `progress` is called with the number of
+    /// bytes written so far and the total length of `data` every time the SubDevice acknowledges a
+    /// packet.
+    ///
+    /// If the SubDevice's EEPROM defines a bootstrap mailbox, the mailbox sync managers are
+    /// reconfigured to use it before entering `BOOT`, and restored afterwards. See
+    /// [`enter_bootstrap`](SlaveRef::enter_bootstrap) for details.
+    ///
+    /// The SubDevice is returned to `INIT` once the write completes or fails and must be
+    /// initialised again, e.g. by power cycling it and calling
+    /// [`Client::init`](crate::Client::init), before it can be used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let firmware = std::fs::read("./firmware.efw").expect("Read firmware file");
+    ///
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// slave
+    ///     .update_firmware("firmware.efw", 0, &firmware, |done, total| {
+    ///         log::info!("Written {} of {} bytes", done, total);
+    ///     })
+    ///     .await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn update_firmware(
+        &self,
+        name: &str,
+        password: u32,
+        data: &[u8],
+        progress: impl FnMut(usize, usize),
+    ) -> Result<(), Error> {
+        // Check FoE support before changing any state
+        self.foe_data_capacity(name)?;
+
+        fmt::info!(
+            "Slave {:#06x} updating firmware with {} ({} bytes)",
+            self.configured_address,
+            name,
+            data.len()
+        );
+
+        self.enter_bootstrap().await?;
+
+        let result = self
+            .foe_write_with_progress(name, password, data, progress)
+            .await;
+
+        // Leave BOOT even if the write failed, but report the write error first.
+        let leave = self.leave_bootstrap().await;
+
+        result.and(leave)
+    }
+
+    /// Transition the SubDevice to the `BOOT` state through `INIT`.
+    ///
+    /// If the SubDevice's EEPROM defines a bootstrap mailbox, the mailbox sync managers are
+    /// reconfigured to use it, and all mailbox operations use it until
+    /// [`leave_bootstrap`](SlaveRef::leave_bootstrap) is called. This is required for SubDevices
+    /// whose bootloader uses a different mailbox size or location to their application firmware.
+    ///
+    /// [`update_firmware`](SlaveRef::update_firmware) should be preferred to write a firmware file
+    /// in one go. This method is useful to stream large firmware files with
+    /// [`foe_writer`](SlaveRef::foe_writer) instead.
+    pub async fn enter_bootstrap(&self) -> Result<(), Error> {
+        // BOOT only supports FoE, so check for it before changing any state
+        if !self
+            .state
+            .config
+            .mailbox
+            .supported_protocols
+            .contains(MailboxProtocols::FOE)
+        {
+            return Err(Error::Foe(FoeError::NotSupported));
+        }
+
+        // BOOT can only be entered from INIT, which is also the only state the mailbox sync
+        // managers can be changed in.
+        self.request_slave_state(SlaveState::None, SlaveState::Init)
+            .await?;
+
+        self.configure_bootstrap_mailbox().await?;
+
+        if let Err(e) = self
+            .request_slave_state(SlaveState::Init, SlaveState::Bootstrap)
+            .await
+        {
+            // Don't leave the SubDevice with a mailbox it won't use outside BOOT
+            self.restore_mailbox().await?;
+
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Transition the SubDevice from the `BOOT` state back to `INIT`, restoring the standard
+    /// mailbox configuration if the bootstrap mailbox was used.
+    ///
+    /// The SubDevice must be initialised again, e.g. by power cycling it and calling
+    /// [`Client::init`](crate::Client::init), before it can be used.
+    pub async fn leave_bootstrap(&self) -> Result<(), Error> {
+        self.request_slave_state(SlaveState::Bootstrap, SlaveState::Init)
+            .await?;
+
+        self.restore_mailbox().await
+    }
+
+    /// Configure the mailbox sync managers with the bootstrap mailbox from the SubDevice's EEPROM.
+    /// The SubDevice must be in `INIT`.
+    ///
+    /// Nothing is changed if the SubDevice doesn't define a bootstrap mailbox, in which case the
+    /// standard mailbox is used in `BOOT` as well, or if the bootstrap mailbox is already
+    /// configured.
+    async fn configure_bootstrap_mailbox(&self) -> Result<(), Error> {
+        if self
+            .state
+            .bootstrap_mailbox
+            .try_borrow()
+            .map_err(|_| Error::Borrow)?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let bootstrap = self.eeprom().bootstrap_mailbox_config().await?;
+
+        let config = &self.state.config.mailbox;
+
+        let (Some(read), Some(write)) = (config.read, config.write) else {
+            return Err(Error::Mailbox(MailboxError::NoMailbox));
+        };
+
+        if bootstrap.slave_receive_size == 0 || bootstrap.slave_send_size == 0 {
+            fmt::debug!(
+                "Slave {:#06x} has no bootstrap mailbox, using standard mailbox",
+                self.configured_address
+            );
+
+            return Ok(());
+        }
+
+        let bootstrap_write = Mailbox {
+            address: bootstrap.slave_receive_offset,
+            len: bootstrap.slave_receive_size,
+            sync_manager: write.sync_manager,
+        };
+        let bootstrap_read = Mailbox {
+            address: bootstrap.slave_send_offset,
+            len: bootstrap.slave_send_size,
+            sync_manager: read.sync_manager,
+        };
+
+        let mut previous = [
+            (write.sync_manager, SyncManagerChannel::default()),
+            (read.sync_manager, SyncManagerChannel::default()),
+        ];
+
+        for ((sync_manager, previous), mailbox) in
+            previous.iter_mut().zip([bootstrap_write, bootstrap_read])
+        {
+            let register = RegisterAddress::sync_manager(*sync_manager);
+
+            *previous = self
+                .read(register)
+                .receive::<SyncManagerChannel>(self.client)
+                .await?;
+
+            let sm_config = SyncManagerChannel {
+                physical_start_address: mailbox.address,
+                length_bytes: mailbox.len,
+                status: Status::default(),
+                ..*previous
+            };
+
+            self.write(register).send(self.client, sm_config).await?;
+
+            fmt::debug!(
+                "Slave {:#06x} bootstrap SM{}: {}",
+                self.configured_address,
+                sync_manager,
+                sm_config
+            );
+        }
+
+        *self
+            .state
+            .bootstrap_mailbox
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow)? = Some(BootMailbox {
+            read: bootstrap_read,
+            write: bootstrap_write,
+            previous,
+        });
+
+        Ok(())
+    }
+
+    /// Restore the mailbox sync managers to the configuration saved by
+    /// [`configure_bootstrap_mailbox`](SlaveRef::configure_bootstrap_mailbox), if any. The
+    /// SubDevice must be in `INIT`.
+    async fn restore_mailbox(&self) -> Result<(), Error> {
+        let Some(bootstrap) = self
+            .state
+            .bootstrap_mailbox
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow)?
+            .take()
+        else {
+            return Ok(());
+        };
+
+        for (sync_manager, sm_config) in bootstrap.previous {
+            self.write(RegisterAddress::sync_manager(sync_manager))
+                .send(
+                    self.client,
+                    SyncManagerChannel {
+                        status: Status::default(),
+                        ..sm_config
+                    },
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Like [`foe_write`](SlaveRef::foe_write), but call `progress` with the number of bytes
+    /// written so far and the total length after each acknowledged packet.
+    async fn foe_write_with_progress(
+        &self,
+        name: &str,
+        password: u32,
+        data: &[u8],
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<(), Error> {
+        let (_read_len, write_len) = self.foe_data_capacity(name)?;
+
+        let _session = self.foe_session()?;
+
+        self.send_foe_acked(FoeOpCode::WriteRequest, password, name.as_bytes(), 0)
+            .await?;
+
+        // The last packet must be shorter than the mailbox can hold, so an empty packet is sent if
+        // the file length is an exact multiple of the packet length.
+        let packets = data.len() / write_len + 1;
+
+        let mut written = 0;
+
+        for (i, packet_number) in (0..packets).zip(1u32..) {
+            let chunk = data.get((i * write_len)..).unwrap_or(&[]);
+            let chunk = &chunk[0..chunk.len().min(write_len)];
+
+            self.send_foe_acked(FoeOpCode::Data, packet_number, chunk, packet_number)
+                .await?;
+
+            written += chunk.len();
+
+            progress(written, data.len());
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} FoE wrote {} bytes to {}",
+            self.configured_address,
+            data.len(),
+            name
+        );
+
+        Ok(())
+    }
+
+    /// Check that the SubDevice supports FoE and that `name` fits in a request, returning the
+    /// maximum number of data bytes in a single packet for the read and write mailboxes
+    /// respectively.
+    fn foe_data_capacity(&self, name: &str) -> Result<(usize, usize), Error> {
+        let config = &self.state.config.mailbox;
+
+        if !config.supported_protocols.contains(MailboxProtocols::FOE) {
+            return Err(Error::Foe(FoeError::NotSupported));
+        }
+
+        let (Some(read), Some(write)) = self.configured_mailboxes() else {
+            return Err(Error::Mailbox(MailboxError::NoMailbox));
+        };
+
+        let read_len = usize::from(read.len).saturating_sub(FoeHeader::PACKED_LEN);
+        let write_len = usize::from(write.len).saturating_sub(FoeHeader::PACKED_LEN);
+
+        if name.len() > write_len {
+            return Err(Error::StringTooLong {
+                max_length: write_len,
+                string_length: name.len(),
+            });
+        }
+
+        // There must be at least one byte of space to be able to make progress.
+        if read_len == 0 || write_len == 0 {
+            return Err(Error::Mailbox(MailboxError::NoMailbox));
+        }
+
+        Ok((read_len, write_len))
+    }
+
+    /// Start an FoE transfer, failing if one is already in progress with this SubDevice.
+    fn foe_session(&self) -> Result<ProtocolSession<'_>, Error> {
+        ProtocolSession::start(&self.state.foe_session).ok_or_else(|| {
+            fmt::error!(
+                "Slave {:#06x} already has an FoE transfer in progress",
+                self.configured_address
+            );
+
+            Error::Foe(FoeError::TransferInProgress)
+        })
+    }
+
+    /// Send an FoE packet, returning the mailbox to read the response from.
+    async fn send_foe(
+        &self,
+        op_code: FoeOpCode,
+        value: u32,
+        data: &[u8],
+    ) -> Result<Mailbox, Error> {
+        let (read_mailbox, write_mailbox) = self.mailboxes().await?;
+
+        let header = FoeHeader::new(
+            self.mailbox_counter(),
+            op_code,
+            value,
+            u16::try_from(data.len())?,
+        );
+
+        self.write_mailbox(&write_mailbox, (header, data)).await?;
+
+        Ok(read_mailbox)
+    }
+
+    /// Send an FoE packet and wait for it to be acknowledged, resending it if the SubDevice is
+    /// busy.
+    ///
+    /// The packet is resent after [`Timeouts::foe_busy`](crate::Timeouts::foe_busy) up to
+    /// [`ClientConfig::foe_busy_retries`](crate::ClientConfig::foe_busy_retries) times. The retry
+    /// count is reset whenever the SubDevice reports more progress than in its previous busy
+    /// response.
+    async fn send_foe_acked(
+        &self,
+        op_code: FoeOpCode,
+        value: u32,
+        data: &[u8],
+        packet_number: u32,
+    ) -> Result<(), Error> {
+        let mut retries = 0;
+        let mut last_done = None;
+
+        loop {
+            let lock = self.lock_mailbox().await?;
+
+            let read_mailbox = self.send_foe(op_code, value, data).await?;
+
+            let (header, _data) = self.foe_response(&read_mailbox).await?;
+
+            // Don't hold the mailbox while waiting to retry.
+            drop(lock);
+
+            match header.op_code {
+                FoeOpCode::Ack if header.value == packet_number => break Ok(()),
+                FoeOpCode::Ack => {
+                    break Err(Error::Foe(FoeError::PacketNumber {
+                        expected: packet_number,
+                        received: header.value,
+                    }))
+                }
+                FoeOpCode::Busy => {
+                    let done = header.value & 0xffff;
+
+                    fmt::debug!(
+                        "Slave {:#06x} FoE busy ({}/{})",
+                        self.configured_address,
+                        done,
+                        header.value >> 16
+                    );
+
+                    // SubDevices still making progress, e.g. through a long flash erase, should
+                    // not run out of retries.
+                    if last_done.is_some_and(|last| done > last) {
+                        retries = 0;
+                    } else {
+                        retries += 1;
+                    }
+
+                    last_done = Some(done);
+
+                    if retries > self.client.config.foe_busy_retries {
+                        fmt::error!(
+                            "Slave {:#06x} FoE still busy after {} retries",
+                            self.configured_address,
+                            self.client.config.foe_busy_retries
+                        );
+
+                        break Err(Error::Foe(FoeError::Busy));
+                    }
+
+                    self.client.timeouts.foe_busy_tick().await;
+                }
+                other => {
+                    fmt::error!(
+                        "Slave {:#06x} sent FoE {:?}, expected acknowledge",
+                        self.configured_address,
+                        other
+                    );
+
+                    break Err(Error::Foe(FoeError::InvalidResponse));
+                }
+            }
+        }
+    }
+
+    /// Read a single FoE response. The returned data has the mailbox and FoE headers removed.
+    ///
+    /// FoE error responses are returned as an [`FoeError::Aborted`].
+    async fn foe_response(
+        &self,
+        read_mailbox: &Mailbox,
+    ) -> Result<(FoeHeader, FoeData<'_>), Error> {
+        let response = self
+            .coe_response(read_mailbox, self.client.timeouts.foe_response)
+            .await?;
+
+        let header = FoeHeader::unpack_from_slice(&response)?;
+
+        // Mailbox length includes the 6 byte FoE header.
+        let data_len = usize::from(header.header.length)
+            .saturating_sub(6)
+            .min(response.len().saturating_sub(FoeHeader::PACKED_LEN));
+
+        let data = FoeData {
+            response,
+            len: data_len,
+        };
+
+        if header.header.mailbox_type != MailboxType::Foe {
+            fmt::error!(
+                "Invalid FoE response. Type: {:?}",
+                header.header.mailbox_type
+            );
+
+            return Err(Error::Foe(FoeError::InvalidResponse));
+        }
+
+        if header.op_code == FoeOpCode::Err {
+            let code = FoeErrorCode::from(header.value);
+
+            fmt::error!(
+                "FoE error for slave {:#06x}: {} {}",
+                self.configured_address,
+                code,
+                core::str::from_utf8(&data).unwrap_or("")
+            );
+
+            return Err(Error::Foe(FoeError::Aborted { code }));
+        }
+
+        Ok((header, data))
+    }
+}
+
+/// A streaming FoE file read, created by [`SlaveRef::foe_reader`].
+///
+/// Each packet is acknowledged when the next one is needed, so the SubDevice doesn't send data
+/// while the mailbox is free for other requests.
+pub struct FoeReader<'slave, 'client, S> {
+    slave: &'slave SlaveRef<'client, S>,
+    /// The maximum length of a single packet. A shorter packet ends the file.
+    packet_len: usize,
+    /// The packet number expected next.
+    packet_number: u32,
+    packet: Option<FoeData<'slave>>,
+    /// Read position in the current packet.
+    pos: usize,
+    /// Set once the last packet of the file has been received.
+    done: bool,
+    _session: ProtocolSession<'slave>,
+}
+
+impl<'slave, 'client, S> FoeReader<'slave, 'client, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Acknowledge the current packet and receive the next one.
+    async fn next_packet(&mut self) -> Result<(), Error> {
+        // Release the previous packet's frame before receiving the next one
+        self.packet = None;
+
+        let _lock = self.slave.lock_mailbox().await?;
+
+        let read_mailbox = self
+            .slave
+            .send_foe(FoeOpCode::Ack, self.packet_number.wrapping_sub(1), &[])
+            .await?;
+
+        self.receive_packet(&read_mailbox).await
+    }
+
+    /// Receive a data packet. The mailbox must be locked by the caller.
+    ///
+    /// The last packet of the file is acknowledged straight away, as the SubDevice doesn't respond
+    /// to it.
+    async fn receive_packet(&mut self, read_mailbox: &Mailbox) -> Result<(), Error> {
+        let (header, data) = self.slave.foe_response(read_mailbox).await?;
+
+        if header.op_code != FoeOpCode::Data {
+            fmt::error!(
+                "Slave {:#06x} sent FoE {:?}, expected data",
+                self.slave.configured_address,
+                header.op_code
+            );
+
+            return Err(Error::Foe(FoeError::InvalidResponse));
+        }
+
+        if header.value != self.packet_number {
+            return Err(Error::Foe(FoeError::PacketNumber {
+                expected: self.packet_number,
+                received: header.value,
+            }));
+        }
+
+        self.done = data.len() < self.packet_len;
+
+        if self.done {
+            self.slave
+                .send_foe(FoeOpCode::Ack, self.packet_number, &[])
+                .await?;
+        }
+        self.packet = Some(data);
+        self.pos = 0;
+        self.packet_number = self.packet_number.wrapping_add(1);
+
+        Ok(())
+    }
+}
+
+impl<'slave, 'client, S> ErrorType for FoeReader<'slave, 'client, S> {
+    type Error = Error;
+}
+
+impl<'slave, 'client, S> Read for FoeReader<'slave, 'client, S>
+where
+    S: Deref<Target = Slave>,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        loop {
+            let remaining = self
+                .packet
+                .as_ref()
+                .map(|packet| &packet[self.pos..])
+                .unwrap_or(&[]);
+
+            if !remaining.is_empty() || self.done {
+                let len = remaining.len().min(buf.len());
+
+                buf[0..len].copy_from_slice(&remaining[0..len]);
+
+                self.pos += len;
+
+                break Ok(len);
+            }
+
+            self.next_packet().await?;
+        }
+    }
+}
+
+/// A streaming FoE file write, created by [`SlaveRef::foe_writer`].
+///
+/// Data is sent in packets as large as the SubDevice's mailbox allows, buffering any data that
+/// doesn't fill a whole packet.
+///
+/// # Resuming a failed write
+///
+/// If [`write`](Write::write) returns an error, e.g. because a packet or its acknowledgement was
+/// lost, none of the data passed to that call is consumed and the packet that was being sent is
+/// not counted as acknowledged. The transfer can be resumed by calling `write` again with the same
+/// data, which resends the unacknowledged packet with the same packet number.
+/// [`acknowledged`](FoeWriter::acknowledged) returns the number of bytes the SubDevice has
+/// confirmed so far.
+pub struct FoeWriter<'slave, 'client, S, const N: usize> {
+    slave: &'slave SlaveRef<'client, S>,
+    /// The length of every packet except the last.
+    packet_len: usize,
+    /// The number of the next packet to send.
+    packet_number: u32,
+    /// The number of bytes acknowledged by the SubDevice.
+    acknowledged: usize,
+    pending: heapless::Vec<u8, N>,
+    _session: ProtocolSession<'slave>,
+}
+
+impl<'slave, 'client, S, const N: usize> FoeWriter<'slave, 'client, S, N>
+where
+    S: Deref<Target = Slave>,
+{
+    /// The number of bytes the SubDevice has acknowledged so far.
+    ///
+    /// This does not include data buffered in the writer that has not yet been sent.
+    pub fn acknowledged(&self) -> usize {
+        self.acknowledged
+    }
+
+    /// Send any buffered data as the last packet of the file, completing the transfer.
+    ///
+    /// If this method is not called, the SubDevice will not consider the file complete.
+    pub async fn finish(self) -> Result<(), Error> {
+        self.send_packet(&self.pending).await
+    }
+
+    /// Send the current data packet and wait for the SubDevice to acknowledge it.
+    async fn send_packet(&self, data: &[u8]) -> Result<(), Error> {
+        self.slave
+            .send_foe_acked(
+                FoeOpCode::Data,
+                self.packet_number,
+                data,
+                self.packet_number,
+            )
+            .await
+    }
+
+    fn packet_acknowledged(&mut self, len: usize) {
+        self.acknowledged += len;
+        self.packet_number = self.packet_number.wrapping_add(1);
+    }
+}
+
+impl<'slave, 'client, S, const N: usize> ErrorType for FoeWriter<'slave, 'client, S, N> {
+    type Error = Error;
+}
+
+impl<'slave, 'client, S, const N: usize> Write for FoeWriter<'slave, 'client, S, N>
+where
+    S: Deref<Target = Slave>,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        // Send whole packets straight from the caller's buffer if nothing is buffered.
+        if self.pending.is_empty() && buf.len() >= self.packet_len {
+            self.send_packet(&buf[0..self.packet_len]).await?;
+
+            self.packet_acknowledged(self.packet_len);
+
+            return Ok(self.packet_len);
+        }
+
+        let buffered = self.pending.len();
+
+        let len = (self.packet_len - buffered).min(buf.len());
+
+        // `foe_writer` checks that `N` can hold an entire packet.
+        self.pending
+            .extend_from_slice(&buf[0..len])
+            .map_err(|_| Error::Foe(FoeError::TooLong))?;
+
+        if self.pending.len() == self.packet_len {
+            if let Err(e) = self.send_packet(&self.pending).await {
+                // Un-consume this call's data so writing it again resends the same packet.
+                self.pending.truncate(buffered);
+
+                return Err(e);
+            }
+
+            self.pending.clear();
+            self.packet_acknowledged(self.packet_len);
+        }
+
+        Ok(len)
+    }
+
+    /// Does nothing, as sending a partially filled packet would end the file. Use
+    /// [`finish`](FoeWriter::finish) to send any remaining data.
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// The payload of an FoE response, excluding headers.
+struct FoeData<'a> {
+    response: MailboxData<'a>,
+    len: usize,
+}
+
+impl<'a> Deref for FoeData<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.response[FoeHeader::PACKED_LEN..][..self.len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{slave::mock::MockSubDevice, ClientConfig, Timeouts};
+    use core::time::Duration;
+
+    /// FoE mailbox data with the given op code, value and data.
+    fn foe(op_code: FoeOpCode, value: u32, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![op_code as u8, 0];
+
+        packet.extend_from_slice(&value.to_le_bytes());
+        packet.extend_from_slice(data);
+
+        packet
+    }
+
+    fn timeouts() -> Timeouts {
+        Timeouts {
+            foe_busy: Duration::from_millis(1),
+            ..Timeouts::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn write_retries_while_busy() {
+        let mut busy = 0;
+
+        let (client, mock) = MockSubDevice::start(
+            timeouts(),
+            ClientConfig {
+                foe_busy_retries: 2,
+                ..ClientConfig::default()
+            },
+            move |mailbox_type, request| {
+                assert_eq!(mailbox_type, MailboxType::Foe);
+
+                let value = u32::from_le_bytes([request[2], request[3], request[4], request[5]]);
+
+                let response = match request[0] {
+                    // A flash erase reporting progress takes more attempts than `foe_busy_retries`
+                    // allows without progress.
+                    0x02 if busy < 5 => {
+                        busy += 1;
+
+                        foe(FoeOpCode::Busy, busy | 5 << 16, &[])
+                    }
+                    0x02 => foe(FoeOpCode::Ack, 0, &[]),
+                    // The first data packet is busy twice without any progress.
+                    0x03 if value == 1 && busy < 7 => {
+                        busy += 1;
+
+                        foe(FoeOpCode::Busy, 5 | 5 << 16, &[])
+                    }
+                    0x03 => foe(FoeOpCode::Ack, value, &[]),
+                    other => panic!("unexpected FoE op code {other}"),
+                };
+
+                vec![(MailboxType::Foe, response)]
+            },
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::FOE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let data = (0..=255u8).cycle().take(300).collect::<Vec<_>>();
+
+        assert_eq!(slave.foe_write("firmware", 0, &data).await, Ok(()));
+
+        let requests = mock.requests();
+
+        // Write request sent 6 times, the first data packet 3 times, then 2 more data packets
+        assert_eq!(requests.len(), 6 + 3 + 2);
+
+        let written = requests
+            .iter()
+            .filter(|(_, request)| request[0] == 0x03)
+            .skip(2)
+            .flat_map(|(_, request)| request[6..].iter().copied())
+            .collect::<Vec<_>>();
+
+        assert_eq!(written, data);
+    }
+
+    #[tokio::test]
+    async fn write_busy_retries_exhausted() {
+        let (client, mock) = MockSubDevice::start(
+            timeouts(),
+            ClientConfig {
+                foe_busy_retries: 3,
+                ..ClientConfig::default()
+            },
+            |_mailbox_type, _request| vec![(MailboxType::Foe, foe(FoeOpCode::Busy, 0, &[]))],
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::FOE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        assert_eq!(
+            slave.foe_write("firmware", 0, &[1, 2, 3]).await,
+            Err(Error::Foe(FoeError::Busy))
+        );
+
+        // The first attempt, then every retry
+        assert_eq!(mock.requests().len(), 4);
+    }
+}
@@ -0,0 +1,255 @@
+use super::{types::Mailbox, Slave, SlaveRef};
+use crate::{
+    error::{Error, Item},
+    fmt,
+    pdu_loop::ReceivedPdu,
+    timer_factory::IntoTimeout,
+};
+use atomic_refcell::AtomicRefMut;
+use core::ops::Deref;
+use ethercrab_wire::EtherCrabWireWrite;
+
+/// The longest mailbox that can be used when it doesn't fit in a single PDU.
+///
+/// One buffer of this length is stored in the [`Client`](crate::Client) and shared by all
+/// SubDevices, as it is only needed when `MAX_PDU_DATA` is smaller than a SubDevice's mailbox.
+pub(crate) const MAX_LARGE_MAILBOX: usize = 2048;
+
+/// A buffer holding the whole contents of a mailbox that is accessed with multiple PDUs.
+pub(crate) type LargeMailbox = heapless::Vec<u8, MAX_LARGE_MAILBOX>;
+
+/// The contents of a SubDevice's OUT mailbox.
+pub(crate) enum MailboxData<'a> {
+    /// The mailbox was read with a single PDU.
+    Pdu(ReceivedPdu<'a, ()>),
+    /// The mailbox was read in chunks into the client's shared buffer.
+    Large {
+        buf: AtomicRefMut<'a, LargeMailbox>,
+        /// The number of bytes removed from the front of the data.
+        start: usize,
+    },
+}
+
+impl<'a> MailboxData<'a> {
+    /// Remove `ct` bytes from the front of the data.
+    pub(crate) fn trim_front(&mut self, ct: usize) {
+        match self {
+            MailboxData::Pdu(pdu) => pdu.trim_front(ct),
+            MailboxData::Large { buf, start } => *start = (*start + ct).min(buf.len()),
+        }
+    }
+}
+
+impl<'a> Deref for MailboxData<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            MailboxData::Pdu(pdu) => pdu,
+            MailboxData::Large { buf, start } => &buf[*start..],
+        }
+    }
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read the whole contents of a mailbox.
+    ///
+    /// Mailboxes longer than a single PDU can hold are read in consecutive chunks. The SubDevice
+    /// only releases the mailbox once its last byte is read.
+    pub(super) async fn read_mailbox(
+        &self,
+        mailbox: &Mailbox,
+        ignore_wkc: bool,
+    ) -> Result<MailboxData<'_>, Error> {
+        let chunk_len = self.client.pdu_loop.max_pdu_data();
+
+        if usize::from(mailbox.len) <= chunk_len {
+            let command = self.read(mailbox.address);
+
+            let command = if ignore_wkc {
+                command.ignore_wkc()
+            } else {
+                command
+            };
+
+            return command
+                .receive_slice(self.client, mailbox.len)
+                .await
+                .map(MailboxData::Pdu);
+        }
+
+        let mut buf = self.large_mailbox(mailbox).await?;
+
+        for start in (0..buf.len()).step_by(chunk_len) {
+            let end = (start + chunk_len).min(buf.len());
+
+            let command = self.read(mailbox.address + start as u16);
+
+            let command = if ignore_wkc {
+                command.ignore_wkc()
+            } else {
+                command
+            };
+
+            let chunk = command
+                .receive_slice(self.client, (end - start) as u16)
+                .await?;
+
+            buf[start..end].copy_from_slice(&chunk);
+        }
+
+        Ok(MailboxData::Large { buf, start: 0 })
+    }
+
+    /// Write `value` into a mailbox, padding it with zeroes to the mailbox's full length.
+    ///
+    /// Mailboxes longer than a single PDU can hold are written in consecutive chunks. The
+    /// SubDevice only processes the request once the last byte of the mailbox is written.
+    pub(super) async fn write_mailbox(
+        &self,
+        mailbox: &Mailbox,
+        value: impl EtherCrabWireWrite,
+    ) -> Result<(), Error> {
+        let chunk_len = self.client.pdu_loop.max_pdu_data();
+        let len = usize::from(mailbox.len);
+
+        if len <= chunk_len {
+            return self
+                .write(mailbox.address)
+                .with_len(mailbox.len)
+                .send(self.client, value)
+                .await;
+        }
+
+        // Short values, e.g. acknowledgements, only need the first chunk followed by zeroes, so
+        // the shared buffer isn't used. This lets a response still held in the buffer be
+        // acknowledged.
+        if value.packed_len() <= chunk_len {
+            self.write(mailbox.address)
+                .with_len(chunk_len as u16)
+                .send(self.client, value)
+                .await?;
+
+            for start in (chunk_len..len).step_by(chunk_len) {
+                let end = (start + chunk_len).min(len);
+
+                self.write(mailbox.address + start as u16)
+                    .with_len((end - start) as u16)
+                    .send(self.client, ())
+                    .await?;
+            }
+
+            return Ok(());
+        }
+
+        let mut buf = self.large_mailbox(mailbox).await?;
+
+        value.pack_to_slice(&mut buf[..])?;
+
+        for start in (0..len).step_by(chunk_len) {
+            let end = (start + chunk_len).min(len);
+
+            self.write(mailbox.address + start as u16)
+                .send(self.client, &buf[start..end])
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Wait for the client's large mailbox buffer to be free, then fill it with `mailbox.len`
+    /// zeroes.
+    async fn large_mailbox(
+        &self,
+        mailbox: &Mailbox,
+    ) -> Result<AtomicRefMut<'_, LargeMailbox>, Error> {
+        fmt::trace!(
+            "Slave {:#06x} mailbox of {} bytes is longer than a PDU, accessing it in chunks",
+            self.configured_address,
+            mailbox.len
+        );
+
+        let mut buf = async {
+            loop {
+                if let Ok(buf) = self.client.large_mailbox.try_borrow_mut() {
+                    break Ok(buf);
+                }
+
+                self.client.timeouts.loop_tick().await;
+            }
+        }
+        .timeout(self.client.timeouts.mailbox_echo)
+        .await
+        .map_err(|e| {
+            fmt::error!(
+                "Slave {:#06x} timed out waiting for large mailbox buffer",
+                self.configured_address
+            );
+
+            e
+        })?;
+
+        buf.clear();
+
+        buf.resize_default(usize::from(mailbox.len)).map_err(|_| {
+            fmt::error!(
+                "Slave {:#06x} mailbox of {} bytes is longer than the maximum of {} bytes",
+                self.configured_address,
+                mailbox.len,
+                MAX_LARGE_MAILBOX
+            );
+
+            Error::Capacity(Item::Mailbox)
+        })?;
+
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eeprom::types::MailboxProtocols,
+        slave::mock::{coe_subdevice_with_pdu_data, MockSubDevice, SdoServer},
+        ClientConfig, PduStorage, Timeouts,
+    };
+
+    #[tokio::test]
+    async fn mailbox_longer_than_pdu() {
+        // The 128 byte mailbox needs 3 PDUs of 48 bytes to read or write.
+        let (client, mock, server) = coe_subdevice_with_pdu_data::<{ PduStorage::element_size(48) }>(
+            Timeouts::default(),
+            ClientConfig::default(),
+            SdoServer::new([((0x1018, 1), 0x0000_0002u32.to_le_bytes().to_vec())]),
+        );
+
+        assert_eq!(client.pdu_loop.max_pdu_data(), 48);
+
+        let slave = MockSubDevice::slave(MailboxProtocols::COE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        assert_eq!(slave.sdo_read::<u32>(0x1018, 1).await, Ok(0x0000_0002));
+
+        // Segments fill the whole mailbox, so must be packed into the shared buffer to be written.
+        let data = (0..=255u8).cycle().take(300).collect::<Vec<_>>();
+
+        assert_eq!(slave.sdo_write_slice(0x2000, 1, &data).await, Ok(()));
+
+        assert_eq!(
+            server.lock().unwrap().objects.get(&(0x2000, 1)),
+            Some(&data),
+            "downloaded data"
+        );
+
+        assert_eq!(mock.requests().len(), 4);
+
+        let mut buf = [0u8; 300];
+
+        assert_eq!(slave.sdo_read_into(0x2000, 1, &mut buf).await, Ok(300));
+        assert_eq!(&buf[..], &data[..]);
+    }
+}
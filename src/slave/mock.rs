@@ -0,0 +1,730 @@
+//! An emulated SubDevice mailbox, used to test mailbox protocols without hardware.
+//!
+//! Frames sent by a [`Client`] are answered by a fake network task in place of a real EtherCAT
+//! network. Only configured address reads and writes (`FPRD` and `FPWR`) of the SubDevice's
+//! mailbox sync manager registers and mailbox buffers are emulated. Requests written into the IN
+//! mailbox are passed to a handler, whose replies are written into the OUT mailbox one at a time
+//! as the MainDevice reads them. The OUT mailbox supports the sync manager repeat request, and
+//! reads of it can be lost or answered twice to test error recovery.
+//!
+//! The SII registers are also emulated over an in-memory EEPROM image, with commands completing
+//! immediately. AL control and status registers are emulated only far enough to answer explicit
+//! device ID requests.
+
+use super::{
+    types::{Mailbox, MailboxConfig, SlaveConfig},
+    Slave,
+};
+use crate::{
+    eeprom::types::MailboxProtocols, mailbox::MailboxType, Client, ClientConfig, PduStorage,
+    Timeouts,
+};
+use core::sync::atomic::AtomicU8;
+use smoltcp::wire::{EthernetAddress, EthernetFrame};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+/// Configured address of the emulated SubDevice.
+pub(crate) const ADDRESS: u16 = 0x1001;
+
+/// IN (MainDevice to SubDevice) mailbox, using SM0.
+const WRITE_MAILBOX: Mailbox = Mailbox {
+    address: 0x1000,
+    len: 128,
+    sync_manager: 0,
+};
+
+/// OUT (SubDevice to MainDevice) mailbox, using SM1.
+const READ_MAILBOX: Mailbox = Mailbox {
+    address: 0x1080,
+    len: 128,
+    sync_manager: 1,
+};
+
+const FPRD: u8 = 0x04;
+const FPWR: u8 = 0x05;
+
+// AL control register ID request bit
+const AL_ID_REQUEST: u8 = 1 << 5;
+
+// SII control register command, status and error bits
+const SII_WRITE_ENABLE: u16 = 1 << 0;
+const SII_TWO_ADDRESS_BYTES: u16 = 1 << 7;
+const SII_READ: u16 = 1 << 8;
+const SII_WRITE: u16 = 1 << 9;
+const SII_RELOAD: u16 = 1 << 10;
+const SII_COMMAND_ERROR: u16 = 1 << 13;
+const SII_WRITE_ERROR: u16 = 1 << 14;
+
+/// Replies to a request written into the IN mailbox, as mailbox type and data following the
+/// mailbox header.
+type Handler = Box<dyn FnMut(MailboxType, &[u8]) -> Vec<(MailboxType, Vec<u8>)> + Send>;
+
+struct Inner {
+    handler: Handler,
+    /// Messages waiting for the OUT mailbox to be read.
+    queued: VecDeque<Vec<u8>>,
+    /// The contents of the OUT mailbox, if it is full.
+    out: Option<Vec<u8>>,
+    /// Data written into the IN mailbox so far, which is handled once its last byte is written.
+    incoming: Vec<u8>,
+    /// The message last written into the OUT mailbox, which is written again on a repeat request.
+    last: Option<Vec<u8>>,
+    repeat: bool,
+    repeat_ack: bool,
+    /// Reads of the OUT mailbox whose response frames are dropped.
+    lose_reads: usize,
+    /// Reads of the OUT mailbox that are followed by the same message again.
+    duplicate_reads: usize,
+    /// Requests received, as mailbox type and data following the mailbox header.
+    requests: Vec<(MailboxType, Vec<u8>)>,
+    /// Set if a request was written while a reply to an earlier one was still waiting to be read.
+    overlapped: bool,
+    repeats: usize,
+    /// EEPROM image.
+    eeprom: Vec<u8>,
+    /// Set if EEPROM writes are not acknowledged.
+    write_protected: bool,
+    /// SII control register error bits.
+    sii_errors: u16,
+    /// SII data register.
+    sii_data: [u8; 4],
+    /// Configured station alias register, loaded from the EEPROM on reload.
+    alias: u16,
+    /// Set if EEPROM control is offered to the PDI.
+    eeprom_offered: bool,
+    /// Set if the PDI has taken control of the EEPROM.
+    pdi_eeprom: bool,
+    /// Low byte of the AL control register, last written by the MainDevice.
+    al_control: u8,
+    /// Explicit device ID returned in answer to an ID request, if ID requests are supported.
+    device_id: Option<u16>,
+}
+
+impl Inner {
+    /// Move the next queued message into the OUT mailbox if it is empty.
+    fn fill(&mut self) {
+        if self.out.is_none() {
+            if let Some(message) = self.queued.pop_front() {
+                self.last = Some(message.clone());
+                self.out = Some(message);
+            }
+        }
+    }
+
+    fn request(&mut self, data: &[u8]) {
+        let length = usize::from(u16::from_le_bytes([data[0], data[1]]));
+        let mailbox_type = mailbox_type(data[5] & 0x0f);
+        let counter = data[5] >> 4 & 0x07;
+        let body = data[6..][..length].to_vec();
+
+        if self.out.is_some() || !self.queued.is_empty() {
+            self.overlapped = true;
+        }
+
+        let replies = (self.handler)(mailbox_type, &body);
+
+        self.requests.push((mailbox_type, body));
+
+        // The first reply echoes the counter of the request. Any further replies continue the
+        // sequence, so they aren't discarded as repeats.
+        for (i, (mailbox_type, body)) in replies.into_iter().enumerate() {
+            let counter = (counter + i as u8 + 6) % 7 + 1;
+
+            self.queued.push_back(message(mailbox_type, counter, &body));
+        }
+
+        self.fill();
+    }
+
+    /// Answer a single PDU, returning its working counter and whether the frame holding it is
+    /// lost.
+    fn pdu(&mut self, command: u8, address: u16, register: u16, data: &mut [u8]) -> (u16, bool) {
+        if address != ADDRESS || !matches!(command, FPRD | FPWR) {
+            return (0, false);
+        }
+
+        let read_sm = 0x0800 + 8 * u16::from(READ_MAILBOX.sync_manager);
+        let write_sm = 0x0800 + 8 * u16::from(WRITE_MAILBOX.sync_manager);
+
+        match (command, register) {
+            // Sync manager status. The IN mailbox is always empty, as requests are handled as soon
+            // as they're written.
+            (FPRD, r) if r == read_sm + 5 => data[0] = u8::from(self.out.is_some()) << 3,
+            (FPRD, r) if r == write_sm + 5 => data[0] = 0,
+            // Sync manager activate and PDI control registers
+            (FPRD, r) if r == read_sm + 6 => {
+                data[0] = 0x01 | u8::from(self.repeat) << 1;
+
+                if let Some(pdi_control) = data.get_mut(1) {
+                    *pdi_control = u8::from(self.repeat_ack) << 1;
+                }
+            }
+            (FPWR, r) if r == read_sm + 6 => {
+                let repeat = data[0] & 0x02 != 0;
+
+                if repeat != self.repeat {
+                    self.repeat = repeat;
+                    self.repeats += 1;
+                    self.out = self.last.clone();
+                    self.repeat_ack = repeat;
+                }
+            }
+            (FPRD, r) if contains(&READ_MAILBOX, r) => {
+                let offset = usize::from(r - READ_MAILBOX.address);
+
+                data.fill(0);
+
+                let Some(message) = &self.out else {
+                    return (1, false);
+                };
+
+                if let Some(message) = message.get(offset..) {
+                    let len = message.len().min(data.len());
+
+                    data[0..len].copy_from_slice(&message[0..len]);
+                }
+
+                // The mailbox is only emptied once its last byte is read
+                if offset + data.len() < usize::from(READ_MAILBOX.len) {
+                    return (1, false);
+                }
+
+                let message = self.out.take().expect("full mailbox");
+
+                if self.duplicate_reads > 0 {
+                    self.duplicate_reads -= 1;
+                    self.out = Some(message);
+                } else {
+                    self.fill();
+                }
+
+                if self.lose_reads > 0 {
+                    self.lose_reads -= 1;
+
+                    return (1, true);
+                }
+            }
+            (FPRD, 0x0012) => data.copy_from_slice(&self.alias.to_le_bytes()[..data.len()]),
+            (FPWR, 0x0120) => self.al_control = data[0],
+            (FPRD, 0x0130) => {
+                let id_request = if self.device_id.is_some() {
+                    self.al_control & AL_ID_REQUEST
+                } else {
+                    0
+                };
+
+                data[0] = self.al_control & 0x0f | id_request;
+            }
+            (FPRD, 0x0134) => {
+                let code = self
+                    .device_id
+                    .filter(|_| self.al_control & AL_ID_REQUEST != 0)
+                    .unwrap_or(0);
+
+                data.copy_from_slice(&code.to_le_bytes()[..data.len()]);
+            }
+            (FPRD, 0x0500) => {
+                data[0] = u8::from(self.eeprom_offered);
+
+                if let Some(pdi_access) = data.get_mut(1) {
+                    *pdi_access = u8::from(self.pdi_eeprom);
+                }
+            }
+            (FPWR, 0x0500) => {
+                self.eeprom_offered = data[0] & 0x01 != 0;
+
+                // Forcing access back to EtherCAT cancels PDI access
+                if data[0] & 0x02 != 0 || !self.eeprom_offered {
+                    self.pdi_eeprom = false;
+                }
+            }
+            (FPRD, 0x0502) => {
+                // EEPROMs larger than 16 kbit are accessed with 2 address bytes
+                let address_bytes = if self.eeprom.len() > 2048 {
+                    SII_TWO_ADDRESS_BYTES
+                } else {
+                    0
+                };
+
+                data[0..2].copy_from_slice(&(self.sii_errors | address_bytes).to_le_bytes())
+            }
+            (FPWR, 0x0502) => self.sii_command(data),
+            (FPRD, 0x0508) => data.copy_from_slice(&self.sii_data[..data.len()]),
+            (FPWR, 0x0508) => self.sii_data[..data.len()].copy_from_slice(data),
+            (FPWR, r) if contains(&WRITE_MAILBOX, r) => {
+                let offset = usize::from(r - WRITE_MAILBOX.address);
+
+                self.incoming.resize(usize::from(WRITE_MAILBOX.len), 0);
+                self.incoming[offset..][..data.len()].copy_from_slice(data);
+
+                // The request is only handled once the last byte of the mailbox is written
+                if offset + data.len() >= usize::from(WRITE_MAILBOX.len) {
+                    let request = core::mem::take(&mut self.incoming);
+
+                    self.request(&request);
+                }
+            }
+            _ => (),
+        }
+
+        (1, false)
+    }
+
+    /// Handle a write to the SII control register, optionally followed by the SII address.
+    fn sii_command(&mut self, data: &[u8]) {
+        let control = u16::from_le_bytes([data[0], data[1]]);
+        let word = data.get(2..6).map_or(0, |address| {
+            u32::from_le_bytes([address[0], address[1], address[2], address[3]]) as usize
+        });
+
+        if control & (SII_READ | SII_WRITE | SII_RELOAD) == 0 {
+            // Error reset
+            self.sii_errors = 0;
+        } else if control & SII_READ != 0 {
+            self.sii_data = [0; 4];
+
+            if let Some(chunk) = self.eeprom.get(word * 2..) {
+                let len = chunk.len().min(4);
+
+                self.sii_data[..len].copy_from_slice(&chunk[..len]);
+            }
+        } else if control & SII_WRITE != 0 {
+            if control & SII_WRITE_ENABLE == 0 {
+                self.sii_errors |= SII_WRITE_ERROR;
+            } else if self.write_protected || self.eeprom.len() < word * 2 + 2 {
+                self.sii_errors |= SII_COMMAND_ERROR;
+            } else {
+                self.eeprom[word * 2..][..2].copy_from_slice(&self.sii_data[0..2]);
+            }
+        } else if let Some(alias) = self.eeprom.get(8..10) {
+            self.alias = u16::from_le_bytes([alias[0], alias[1]]);
+        }
+    }
+}
+
+/// An emulated SubDevice with a mailbox, answering frames sent by a [`Client`].
+#[derive(Clone)]
+pub(crate) struct MockSubDevice {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl MockSubDevice {
+    /// Create a client connected to a SubDevice whose mailbox requests are answered by `handler`.
+    ///
+    /// The fake network task is spawned onto the current tokio runtime.
+    pub(crate) fn start(
+        timeouts: Timeouts,
+        config: ClientConfig,
+        handler: impl FnMut(MailboxType, &[u8]) -> Vec<(MailboxType, Vec<u8>)> + Send + 'static,
+    ) -> (Client<'static>, Self) {
+        Self::start_with_pdu_data::<{ PduStorage::element_size(1100) }>(timeouts, config, handler)
+    }
+
+    /// Like [`start`](MockSubDevice::start), but with `DATA` bytes of PDU storage per frame, e.g.
+    /// to make the mailbox longer than a single PDU.
+    pub(crate) fn start_with_pdu_data<const DATA: usize>(
+        timeouts: Timeouts,
+        config: ClientConfig,
+        handler: impl FnMut(MailboxType, &[u8]) -> Vec<(MailboxType, Vec<u8>)> + Send + 'static,
+    ) -> (Client<'static>, Self) {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let storage: &'static PduStorage<16, DATA> = Box::leak(Box::new(PduStorage::new()));
+
+        let (mut tx, mut rx, pdu_loop) = storage.try_split().expect("split");
+
+        let mock = Self {
+            inner: Arc::new(Mutex::new(Inner {
+                handler: Box::new(handler),
+                queued: VecDeque::new(),
+                out: None,
+                incoming: Vec::new(),
+                last: None,
+                repeat: false,
+                repeat_ack: false,
+                lose_reads: 0,
+                duplicate_reads: 0,
+                requests: Vec::new(),
+                overlapped: false,
+                repeats: 0,
+                eeprom: Vec::new(),
+                write_protected: false,
+                sii_errors: 0,
+                sii_data: [0; 4],
+                alias: 0,
+                eeprom_offered: false,
+                pdi_eeprom: false,
+                al_control: 0,
+                device_id: None,
+            })),
+        };
+
+        let inner = mock.inner.clone();
+
+        tokio::spawn(async move {
+            loop {
+                while let Some(frame) = tx.next_sendable_frame() {
+                    let mut response = None;
+
+                    frame
+                        .send_blocking(|bytes| {
+                            response = inner.lock().unwrap().frame(bytes);
+
+                            Ok(bytes.len())
+                        })
+                        .expect("send");
+
+                    if let Some(response) = response {
+                        rx.receive_frame(&response).expect("receive");
+                    }
+                }
+
+                tokio::task::yield_now().await;
+            }
+        });
+
+        (Client::new(pdu_loop, timeouts, config), mock)
+    }
+
+    /// Create a SubDevice with a mailbox supporting the given protocols.
+    pub(crate) fn slave(protocols: MailboxProtocols) -> Slave {
+        Slave {
+            configured_address: ADDRESS,
+            config: SlaveConfig {
+                mailbox: MailboxConfig {
+                    read: Some(READ_MAILBOX),
+                    write: Some(WRITE_MAILBOX),
+                    supported_protocols: protocols,
+                    has_coe: protocols.contains(MailboxProtocols::COE),
+                    ..MailboxConfig::default()
+                },
+                ..SlaveConfig::default()
+            },
+            mailbox_counter: AtomicU8::new(1),
+            ..Slave::default()
+        }
+    }
+
+    /// Queue a message sent by the SubDevice without a request, e.g. an emergency.
+    ///
+    /// Unsolicited messages have a counter of `0`, so are never treated as repeats.
+    pub(crate) fn send(&self, mailbox_type: MailboxType, body: &[u8]) {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.queued.push_back(message(mailbox_type, 0, body));
+        inner.fill();
+    }
+
+    /// Leave the next `count` messages in the OUT mailbox after they're read, as if the SubDevice
+    /// sent each of them twice.
+    pub(crate) fn duplicate_reads(&self, count: usize) {
+        self.inner.lock().unwrap().duplicate_reads = count;
+    }
+
+    /// Lose the frames holding the next `count` reads of the OUT mailbox. The message is still
+    /// removed from the mailbox, so must be repeated to be read again.
+    pub(crate) fn lose_reads(&self, count: usize) {
+        self.inner.lock().unwrap().lose_reads = count;
+    }
+
+    /// The number of times the repeat bit of the OUT mailbox's sync manager has been toggled.
+    pub(crate) fn repeats(&self) -> usize {
+        self.inner.lock().unwrap().repeats
+    }
+
+    /// Requests received so far, as mailbox type and data following the mailbox header.
+    pub(crate) fn requests(&self) -> Vec<(MailboxType, Vec<u8>)> {
+        self.inner.lock().unwrap().requests.clone()
+    }
+
+    /// Set the contents of the SubDevice's EEPROM.
+    pub(crate) fn set_eeprom(&self, image: Vec<u8>) {
+        self.inner.lock().unwrap().eeprom = image;
+    }
+
+    /// Get the contents of the SubDevice's EEPROM.
+    pub(crate) fn eeprom(&self) -> Vec<u8> {
+        self.inner.lock().unwrap().eeprom.clone()
+    }
+
+    /// Don't acknowledge any EEPROM writes.
+    pub(crate) fn write_protect_eeprom(&self) {
+        self.inner.lock().unwrap().write_protected = true;
+    }
+
+    /// Take control of the EEPROM from the SubDevice's local application, if it is offered to the
+    /// PDI. Returns `true` if control was taken.
+    pub(crate) fn pdi_take_eeprom(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.pdi_eeprom = inner.eeprom_offered;
+
+        inner.pdi_eeprom
+    }
+
+    /// Answer explicit device ID requests with `id`.
+    pub(crate) fn set_device_id(&self, id: u16) {
+        self.inner.lock().unwrap().device_id = Some(id);
+    }
+
+    /// Returns `true` if a request was ever written while a reply to an earlier request was still
+    /// waiting to be read.
+    pub(crate) fn overlapped(&self) -> bool {
+        self.inner.lock().unwrap().overlapped
+    }
+}
+
+impl Inner {
+    /// Answer every PDU in an Ethernet frame, returning the response frame or `None` if it is
+    /// lost.
+    fn frame(&mut self, bytes: &[u8]) -> Option<Vec<u8>> {
+        let mut frame = EthernetFrame::new_checked(bytes.to_vec()).expect("Ethernet frame");
+
+        frame.set_src_addr(EthernetAddress([0x12, 0x10, 0x10, 0x10, 0x10, 0x10]));
+
+        let payload = frame.payload_mut();
+
+        let mut lost = false;
+
+        // Skip the 2 byte EtherCAT header
+        let mut pos = 2;
+
+        loop {
+            let command = payload[pos];
+            let address = u16::from_le_bytes([payload[pos + 2], payload[pos + 3]]);
+            let register = u16::from_le_bytes([payload[pos + 4], payload[pos + 5]]);
+            let flags = u16::from_le_bytes([payload[pos + 6], payload[pos + 7]]);
+            let len = usize::from(flags & 0x07ff);
+
+            let data = &mut payload[(pos + 10)..(pos + 10 + len)];
+
+            let (wkc, lost_pdu) = self.pdu(command, address, register, data);
+
+            lost |= lost_pdu;
+
+            payload[(pos + 10 + len)..(pos + 12 + len)].copy_from_slice(&wkc.to_le_bytes());
+
+            pos += 12 + len;
+
+            // More PDUs follow
+            if flags & 0x8000 == 0 {
+                break;
+            }
+        }
+
+        (!lost).then(|| frame.into_inner())
+    }
+}
+
+/// Returns `true` if `register` is within the buffer of `mailbox`.
+fn contains(mailbox: &Mailbox, register: u16) -> bool {
+    (mailbox.address..(mailbox.address + mailbox.len)).contains(&register)
+}
+
+/// Build a mailbox message with the given header fields.
+fn message(mailbox_type: MailboxType, counter: u8, body: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(6 + body.len());
+
+    message.extend_from_slice(&(body.len() as u16).to_le_bytes());
+    // Address, channel and priority
+    message.extend_from_slice(&[0, 0, 0]);
+    message.push((counter & 0x07) << 4 | mailbox_type as u8);
+    message.extend_from_slice(body);
+
+    message
+}
+
+fn mailbox_type(raw: u8) -> MailboxType {
+    match raw {
+        0x01 => MailboxType::Aoe,
+        0x02 => MailboxType::Eoe,
+        0x03 => MailboxType::Coe,
+        0x04 => MailboxType::Foe,
+        0x05 => MailboxType::Soe,
+        0x0f => MailboxType::VendorSpecific,
+        _ => MailboxType::Err,
+    }
+}
+
+/// A CoE SDO server holding an object dictionary, answering expedited, normal and segmented
+/// uploads and downloads.
+#[derive(Default)]
+pub(crate) struct SdoServer {
+    pub(crate) objects: HashMap<(u16, u8), Vec<u8>>,
+    /// An upload or download in progress, as object address, data and the next segment's offset.
+    segmented: Option<((u16, u8), Vec<u8>, usize)>,
+    /// The number of bytes expected by a segmented download.
+    download_len: usize,
+}
+
+impl SdoServer {
+    /// The longest segment sent in a segmented upload.
+    const SEGMENT_LEN: usize = 16;
+
+    pub(crate) fn new(objects: impl IntoIterator<Item = ((u16, u8), Vec<u8>)>) -> Self {
+        Self {
+            objects: objects.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Answer a CoE request, returning the CoE header and SDO response that follows the mailbox
+    /// header.
+    pub(crate) fn handle(&mut self, request: &[u8]) -> Vec<u8> {
+        // CoE header: SDO response service
+        let mut response = vec![0x00, 0x30];
+
+        let command = request[2];
+        let key = (u16::from_le_bytes([request[3], request[4]]), request[5]);
+
+        match command >> 5 {
+            // Initiate download
+            1 => {
+                let data = if command & 0x02 != 0 {
+                    // Expedited
+                    let len = 4 - usize::from(command >> 2 & 0x03);
+
+                    request[6..][..len].to_vec()
+                } else {
+                    let complete_size =
+                        u32::from_le_bytes([request[6], request[7], request[8], request[9]]);
+
+                    let data = request[10..].to_vec();
+
+                    if data.len() < complete_size as usize {
+                        self.download_len = complete_size as usize;
+                        self.segmented = Some((key, data, 0));
+
+                        response.extend_from_slice(&[0x60, request[3], request[4], request[5]]);
+                        response.extend_from_slice(&[0; 4]);
+
+                        return response;
+                    }
+
+                    data
+                };
+
+                self.objects.insert(key, data);
+
+                response.extend_from_slice(&[0x60, request[3], request[4], request[5]]);
+                response.extend_from_slice(&[0; 4]);
+            }
+            // Download segment
+            0 => {
+                let (key, mut data, _) = self.segmented.take().expect("download in progress");
+
+                let toggle = command & 0x10;
+                let unused = usize::from(command >> 1 & 0x07);
+                let segment = &request[3..];
+
+                // Segments shorter than 7 bytes are padded.
+                let len = if segment.len() <= 7 {
+                    7 - unused
+                } else {
+                    segment.len()
+                };
+
+                data.extend_from_slice(&segment[..len]);
+
+                if command & 0x01 != 0 {
+                    assert_eq!(data.len(), self.download_len, "segmented download length");
+
+                    self.objects.insert(key, data);
+                } else {
+                    self.segmented = Some((key, data, 0));
+                }
+
+                response.extend_from_slice(&[0x20 | toggle]);
+                response.extend_from_slice(&[0; 7]);
+            }
+            // Initiate upload
+            2 => {
+                let Some(data) = self.objects.get(&key).cloned() else {
+                    // Object does not exist
+                    response.extend_from_slice(&[0x80, request[3], request[4], request[5]]);
+                    response.extend_from_slice(&0x0602_0000u32.to_le_bytes());
+
+                    return response;
+                };
+
+                if data.len() <= 4 {
+                    let size = (4 - data.len() as u8) << 2;
+
+                    response.extend_from_slice(&[0x43 | size, request[3], request[4], request[5]]);
+                    response.extend_from_slice(&data);
+                    response.resize(10, 0);
+                } else {
+                    response.extend_from_slice(&[0x41, request[3], request[4], request[5]]);
+                    response.extend_from_slice(&(data.len() as u32).to_le_bytes());
+
+                    if data.len() <= Self::SEGMENT_LEN {
+                        response.extend_from_slice(&data);
+                    } else {
+                        self.segmented = Some((key, data, 0));
+                    }
+                }
+            }
+            // Upload segment
+            3 => {
+                let (key, data, offset) = self.segmented.take().expect("upload in progress");
+
+                let toggle = command & 0x10;
+                let len = (data.len() - offset).min(Self::SEGMENT_LEN);
+                let last = offset + len == data.len();
+
+                let unused = 7u8.saturating_sub(len as u8);
+
+                response.push(toggle | unused << 1 | u8::from(last));
+                response.extend_from_slice(&data[offset..][..len]);
+                response.resize(response.len().max(10), 0);
+
+                if !last {
+                    self.segmented = Some((key, data, offset + len));
+                }
+            }
+            other => panic!("unsupported SDO command {other}"),
+        }
+
+        response
+    }
+}
+
+/// Start an emulated SubDevice serving `server`'s object dictionary over CoE.
+///
+/// The server is returned so tests can inspect or change the object dictionary.
+pub(crate) fn coe_subdevice(
+    timeouts: Timeouts,
+    config: ClientConfig,
+    server: SdoServer,
+) -> (Client<'static>, MockSubDevice, Arc<Mutex<SdoServer>>) {
+    coe_subdevice_with_pdu_data::<{ PduStorage::element_size(1100) }>(timeouts, config, server)
+}
+
+/// Like [`coe_subdevice`], but with `DATA` bytes of PDU storage per frame.
+pub(crate) fn coe_subdevice_with_pdu_data<const DATA: usize>(
+    timeouts: Timeouts,
+    config: ClientConfig,
+    server: SdoServer,
+) -> (Client<'static>, MockSubDevice, Arc<Mutex<SdoServer>>) {
+    let server = Arc::new(Mutex::new(server));
+
+    let handler_server = server.clone();
+
+    let (client, mock) = MockSubDevice::start_with_pdu_data::<DATA>(
+        timeouts,
+        config,
+        move |mailbox_type, request| {
+            assert_eq!(mailbox_type, MailboxType::Coe);
+
+            vec![(
+                MailboxType::Coe,
+                handler_server.lock().unwrap().handle(request),
+            )]
+        },
+    );
+
+    (client, mock, server)
+}
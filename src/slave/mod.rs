@@ -1,47 +1,92 @@
+mod aoe;
+mod coe_cache;
 pub(crate) mod configuration;
 mod dc;
+mod device_info;
+mod diagnosis;
 mod eeprom;
+mod eoe;
+mod foe;
+mod large_mailbox;
+#[cfg(test)]
+pub(crate) mod mock;
+mod oversampling;
 pub mod pdi;
+mod pdo_entries;
+mod pdo_mapping;
 pub mod ports;
+mod recovery;
+mod sdo_info;
+mod sm_parameters;
+mod soe;
 mod types;
+mod voe;
 
 use crate::{
     al_control::AlControl,
     al_status_code::AlStatusCode,
     client::Client,
     coe::{
-        self, abort_code::CoeAbortCode, services::CoeServiceRequest, CoeCommand, SdoExpedited,
-        SubIndex,
+        self,
+        abort_code::CoeAbortCode,
+        emergency::CoeEmergency,
+        services::{CoeServiceRequest, SdoNormalDownload, SdoSegmented},
+        CoeCommand, CoeService, SdoExpedited, SubIndex,
     },
     command::Command,
     dl_status::DlStatus,
-    eeprom::{device_reader::DeviceEeprom, types::SiiOwner},
-    error::{Error, MailboxError, PduError},
+    eeprom::{
+        config_checksum,
+        device_reader::DeviceEeprom,
+        types::{GeneralFlags, SiiCoding, SiiOwner},
+        ChunkReader, EepromDataProvider,
+    },
+    error::{EepromError, Error, MailboxError, PduError},
     fmt,
-    mailbox::{MailboxHeader, MailboxType},
-    pdu_loop::ReceivedPdu,
+    mailbox::{MailboxErrorReply, MailboxHeader, MailboxHeaderBase, MailboxType},
     register::{DcSupport, RegisterAddress, SupportFlags},
     slave::{ports::Ports, types::SlaveConfig},
     slave_state::SlaveState,
     timer_factory::IntoTimeout,
     WrappedRead, WrappedWrite,
 };
+use atomic_refcell::AtomicRefCell;
 use core::{
     any::type_name,
     fmt::{Debug, Write},
     ops::{Deref, DerefMut},
-    sync::atomic::{AtomicU8, Ordering},
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
+    time::Duration,
 };
+use embedded_io_async::Read;
 use ethercrab_wire::{
     EtherCrabWireRead, EtherCrabWireReadSized, EtherCrabWireReadWrite, EtherCrabWireSized,
     EtherCrabWireWrite,
 };
 
-pub use self::pdi::SlavePdi;
+pub(crate) use self::coe_cache::CoeCache;
+pub use self::configuration::{
+    PdiAlignment, PdiLayout, PdoDirection, SyncManagerMapping, SyncManagerMappings,
+};
+pub use self::eeprom::SlaveEeprom;
+pub(crate) use self::eoe::EoeReassembly;
+pub use self::eoe::{EoeDevice, EoeSwitch};
+pub use self::foe::{FoeReader, FoeWriter};
+pub(crate) use self::large_mailbox::LargeMailbox;
+pub use self::oversampling::{Oversampled, OversampledChannel, SampleOrder};
+pub use self::pdi::{EtherCatPdi, SlavePdi};
+pub(crate) use self::pdo_entries::EntryLocations;
+pub use self::pdo_entries::{PdiEntry, PdiEntryMut, PdiEntryRef};
+pub(crate) use self::recovery::GroupPdi;
+pub use self::recovery::RecoveryEvent;
+pub use self::sdo_info::ObjectDictionary;
 pub use self::types::IoRanges;
 pub use self::types::SlaveIdentity;
-use self::{eeprom::SlaveEeprom, types::Mailbox};
-pub use dc::DcSync;
+use self::{
+    large_mailbox::MailboxData,
+    types::{BootMailbox, Mailbox},
+};
+pub use dc::{DcSync, DcSyncMode, DcSyncTiming, Latch, LatchEdge, LatchMode, LatchStatus};
 
 /// Slave device metadata. See [`SlaveRef`] for richer behaviour.
 #[derive(Debug)]
@@ -56,6 +101,12 @@ pub struct Slave {
 
     pub(crate) identity: SlaveIdentity,
 
+    /// The station alias, if read during init.
+    pub(crate) alias: Option<u16>,
+
+    /// The explicit device ID, if read during init and supported by the SubDevice.
+    pub(crate) device_id: Option<u16>,
+
     // NOTE: Default length in SOEM is 40 bytes
     pub(crate) name: heapless::String<64>,
 
@@ -74,6 +125,10 @@ pub struct Slave {
     /// For the first slave in the network, this will always be `None`.
     pub(crate) parent_index: Option<u16>,
 
+    /// Set when the SubDevice was left behind by a group state transition with
+    /// [`SlaveGroup::with_partial_op`](crate::SlaveGroup::with_partial_op).
+    pub(crate) missing: bool,
+
     /// Propagation delay in nanoseconds.
     ///
     /// `u32::MAX` gives a maximum propagation delay of ~4.2 seconds for the last slave in the
@@ -83,10 +138,44 @@ pub struct Slave {
     /// The 1-7 cyclic counter used when working with mailbox requests.
     pub(crate) mailbox_counter: AtomicU8,
 
+    /// The counter of the last mailbox message received from the SubDevice, or `0` if none has
+    /// been received yet.
+    pub(crate) mailbox_read_counter: AtomicU8,
+
     /// DC config.
     pub(crate) dc_sync: DcSync,
+
+    /// SYNC0 timing overrides for this SubDevice.
+    pub(crate) dc_sync_timing: DcSyncTiming,
+
+    /// CoE emergency messages received from the SubDevice but not yet retrieved by the user.
+    pub(crate) emergencies: AtomicRefCell<heapless::Deque<CoeEmergency, MAX_EMERGENCIES>>,
+
+    /// Set while a mailbox request to this SubDevice is waiting for its response.
+    pub(crate) mailbox_busy: AtomicBool,
+
+    /// Set while an FoE transfer with this SubDevice is in progress.
+    pub(crate) foe_session: AtomicBool,
+
+    /// The mailboxes used instead of the ones in `config` while the SubDevice's mailbox sync
+    /// managers are configured for the `BOOT` state.
+    pub(crate) bootstrap_mailbox: AtomicRefCell<Option<BootMailbox>>,
+
+    /// The 4 bit number of the next Ethernet frame sent over EoE.
+    pub(crate) eoe_frame_number: AtomicU8,
+
+    /// The invoke ID of the next ADS request sent over AoE.
+    pub(crate) aoe_invoke_id: AtomicU32,
 }
 
+/// The maximum number of CoE emergencies queued per SubDevice. The oldest message is discarded when
+/// the queue is full.
+const MAX_EMERGENCIES: usize = 8;
+
+/// The longest value written with [`SlaveRef::sdo_write`] that can be checked when
+/// [`ClientConfig::verify_sdo_writes`](crate::ClientConfig::verify_sdo_writes) is enabled.
+const MAX_VERIFY_LEN: usize = 256;
+
 // Only required for tests, also doesn't make much sense - consumers of EtherCrab should be
 // comparing e.g. `slave.identity()`, names, configured address or something other than the whole
 // struct.
@@ -96,6 +185,9 @@ impl PartialEq for Slave {
         self.configured_address == other.configured_address
             && self.config == other.config
             && self.identity == other.identity
+            && self.alias == other.alias
+            && self.missing == other.missing
+            && self.device_id == other.device_id
             && self.name == other.name
             && self.flags == other.flags
             && self.ports == other.ports
@@ -104,7 +196,9 @@ impl PartialEq for Slave {
             && self.parent_index == other.parent_index
             && self.propagation_delay == other.propagation_delay
             && self.dc_sync == other.dc_sync
-        // NOTE: No mailbox_counter
+            && self.dc_sync_timing == other.dc_sync_timing
+        // NOTE: No mailbox_counter, mailbox_read_counter, mailbox_busy, foe_session,
+        // bootstrap_mailbox, emergencies, eoe_frame_number or aoe_invoke_id
     }
 }
 
@@ -117,6 +211,9 @@ impl Clone for Slave {
             configured_address: self.configured_address,
             config: self.config.clone(),
             identity: self.identity,
+            alias: self.alias,
+            missing: self.missing,
+            device_id: self.device_id,
             name: self.name.clone(),
             flags: self.flags.clone(),
             ports: self.ports,
@@ -125,7 +222,15 @@ impl Clone for Slave {
             parent_index: self.parent_index,
             propagation_delay: self.propagation_delay,
             dc_sync: self.dc_sync,
+            dc_sync_timing: self.dc_sync_timing,
             mailbox_counter: AtomicU8::new(self.mailbox_counter.load(Ordering::Acquire)),
+            mailbox_read_counter: AtomicU8::new(self.mailbox_read_counter.load(Ordering::Acquire)),
+            emergencies: AtomicRefCell::new(self.emergencies.borrow().clone()),
+            mailbox_busy: AtomicBool::new(self.mailbox_busy.load(Ordering::Acquire)),
+            foe_session: AtomicBool::new(self.foe_session.load(Ordering::Acquire)),
+            bootstrap_mailbox: AtomicRefCell::new(*self.bootstrap_mailbox.borrow()),
+            eoe_frame_number: AtomicU8::new(self.eoe_frame_number.load(Ordering::Acquire)),
+            aoe_invoke_id: AtomicU32::new(self.aoe_invoke_id.load(Ordering::Acquire)),
         }
     }
 }
@@ -148,13 +253,29 @@ impl Slave {
             SlaveState::Init
         );
 
-        slave_ref.wait_for_state(SlaveState::Init).await?;
+        slave_ref
+            .wait_for_state(SlaveState::None, SlaveState::Init)
+            .await?;
 
         // Make sure master has access to slave EEPROM
         slave_ref.set_eeprom_mode(SiiOwner::Master).await?;
 
         let identity = slave_ref.eeprom().identity().await?;
 
+        let identification = client.config.device_identification;
+
+        let alias = if identification.station_alias {
+            Some(slave_ref.station_alias().await?)
+        } else {
+            None
+        };
+
+        let device_id = if identification.explicit_device_id {
+            slave_ref.explicit_device_id().await?
+        } else {
+            None
+        };
+
         let name = slave_ref.eeprom().device_name().await?.unwrap_or_else(|| {
             let mut s = heapless::String::new();
 
@@ -205,12 +326,23 @@ impl Slave {
             propagation_delay: 0,
             dc_receive_time: 0,
             identity,
+            alias,
+            device_id,
+            missing: false,
             name,
             flags,
             ports,
             dc_sync: DcSync::Disabled,
+            dc_sync_timing: DcSyncTiming::default(),
             // 0 is a reserved value, so we initialise the cycle at 1. The cycle repeats 1 - 7.
             mailbox_counter: AtomicU8::new(1),
+            mailbox_read_counter: AtomicU8::new(0),
+            emergencies: AtomicRefCell::new(heapless::Deque::new()),
+            mailbox_busy: AtomicBool::new(false),
+            foe_session: AtomicBool::new(false),
+            bootstrap_mailbox: AtomicRefCell::new(None),
+            eoe_frame_number: AtomicU8::new(0),
+            aoe_invoke_id: AtomicU32::new(0),
         })
     }
 
@@ -229,6 +361,36 @@ impl Slave {
         self.configured_address
     }
 
+    /// Get the station alias stored in the SubDevice's EEPROM.
+    ///
+    /// This is only read during [`Client::init`](crate::client::Client::init) if
+    /// [`DeviceIdentification::station_alias`](crate::DeviceIdentification::station_alias) is
+    /// enabled, and is `None` otherwise. Use
+    /// [`SlaveRef::station_alias`] to read it at any time.
+    pub fn alias(&self) -> Option<u16> {
+        self.alias
+    }
+
+    /// Get the SubDevice's explicit device ID, e.g. set with rotary switches.
+    ///
+    /// This is only read during [`Client::init`](crate::client::Client::init) if
+    /// [`DeviceIdentification::explicit_device_id`](crate::DeviceIdentification::explicit_device_id)
+    /// is enabled, and is `None` otherwise or if the SubDevice doesn't support ID requests. Use
+    /// [`SlaveRef::explicit_device_id`] to read it at any time.
+    pub fn device_id(&self) -> Option<u16> {
+        self.device_id
+    }
+
+    /// Check whether the SubDevice is taking part in its group's state transitions.
+    ///
+    /// This returns `false` if the SubDevice was missing or faulted during a state transition of a
+    /// group with [`SlaveGroup::with_partial_op`](crate::SlaveGroup::with_partial_op), and so was
+    /// left behind in a lower state. It returns `true` again once
+    /// [`SlaveGroup::recover`](crate::SlaveGroup::recover) brings the SubDevice back to OP.
+    pub fn is_present(&self) -> bool {
+        !self.missing
+    }
+
     /// Get the network propagation delay of this device in nanoseconds.
     ///
     /// Note that before [`Client::init`](crate::client::Client::init) is called, this method will
@@ -237,6 +399,26 @@ impl Slave {
         self.propagation_delay
     }
 
+    /// Get the DC receive time latched at port 0 to 3 of this device during
+    /// [`Client::init`](crate::client::Client::init), in nanoseconds, indexed by port number.
+    ///
+    /// These are the raw local time values of registers `0x0900` to `0x090C`, which can be used to
+    /// do custom propagation delay modelling or diagnose cabling asymmetry. Only the differences
+    /// between ports of the same device are meaningful. Closed ports and devices without DC
+    /// support return `0`.
+    pub fn port_receive_times(&self) -> [u32; 4] {
+        self.ports.receive_times()
+    }
+
+    /// Get the DC system time at which the receive times were latched at port 0 of this device, in
+    /// nanoseconds.
+    ///
+    /// This is the value of register `0x0918` read during
+    /// [`Client::init`](crate::client::Client::init).
+    pub fn dc_receive_time(&self) -> u64 {
+        self.dc_receive_time
+    }
+
     /// Distributed Clock (DC) support.
     pub fn dc_support(&self) -> DcSupport {
         self.flags.dc_support()
@@ -304,6 +486,55 @@ where
     pub fn set_dc_sync(&mut self, dc_sync: DcSync) {
         self.state.dc_sync = dc_sync;
     }
+
+    /// Set SYNC0 cycle time, shift and start time for this SubDevice, overriding the values in the
+    /// group's [`DcConfiguration`](crate::slave_group::DcConfiguration).
+    ///
+    /// Like [`set_dc_sync`](SlaveRef::set_dc_sync), this is only applied by
+    /// [`SlaveGroup::configure_dc_sync`](crate::SlaveGroup::configure_dc_sync).
+    pub fn set_dc_sync_timing(&mut self, timing: DcSyncTiming) {
+        self.state.dc_sync_timing = timing;
+    }
+
+    /// Set both the DC sync mode and timing for this SubDevice from a sync mode read from its
+    /// EEPROM with [`dc_sync_modes`](SlaveRef::dc_sync_modes).
+    ///
+    /// `cycle_time` is the application cycle time the mode's cycle factors are relative to.
+    pub fn set_dc_sync_mode(&mut self, mode: &DcSyncMode, cycle_time: Duration) {
+        self.set_dc_sync(mode.dc_sync(cycle_time));
+        self.set_dc_sync_timing(mode.timing(cycle_time));
+    }
+
+    /// Configure an oversampling SubDevice, e.g. an EL3702, to take `factor` samples every
+    /// process data cycle.
+    ///
+    /// SYNC0 is set to `factor` times the cycle rate to trigger each sample, and SYNC1 fires once
+    /// every `factor` SYNC0 pulses to latch the samples of a whole cycle. The SYNC0 shift and start
+    /// time set with [`set_dc_sync_timing`](SlaveRef::set_dc_sync_timing) are kept.
+    ///
+    /// Like [`set_dc_sync`](SlaveRef::set_dc_sync), this is only applied by
+    /// [`SlaveGroup::configure_dc_sync`](crate::SlaveGroup::configure_dc_sync). The samples are
+    /// read with an [`OversampledChannel`].
+    pub fn set_oversampling(&mut self, factor: u32, cycle_time: Duration) {
+        let factor = factor.max(1);
+
+        let sync0_period = cycle_time / factor;
+
+        self.set_dc_sync(DcSync::Sync01 {
+            sync1_period: sync0_period * factor,
+        });
+
+        self.state.dc_sync_timing.sync0_period = Some(sync0_period);
+    }
+
+    /// Set the alignment of the logical start address of each of this SubDevice's FMMUs.
+    ///
+    /// This must be set in PRE-OP, before the group's PDI is configured by e.g.
+    /// [`SlaveGroup::into_safe_op`](crate::SlaveGroup::into_safe_op). The resulting layout can be
+    /// read with [`pdi_layout`](SlaveRef::pdi_layout).
+    pub fn set_pdi_alignment(&mut self, alignment: PdiAlignment) {
+        self.state.config.layout.alignment = alignment;
+    }
 }
 
 impl<'a, S> SlaveRef<'a, S>
@@ -320,6 +551,13 @@ where
         self.state.identity
     }
 
+    /// Check whether the SubDevice is taking part in its group's state transitions.
+    ///
+    /// See [`Slave::is_present`] for more information.
+    pub fn is_present(&self) -> bool {
+        self.state.is_present()
+    }
+
     /// Get the network propagation delay of this device in nanoseconds.
     ///
     /// Note that before [`Client::init`](crate::client::Client::init) is called, this method will
@@ -328,6 +566,20 @@ where
         self.state.propagation_delay
     }
 
+    /// Get the DC receive time latched at port 0 to 3 of this device, indexed by port number.
+    ///
+    /// See [`Slave::port_receive_times`] for more information.
+    pub fn port_receive_times(&self) -> [u32; 4] {
+        self.state.port_receive_times()
+    }
+
+    /// Get the DC system time at which the port receive times were latched.
+    ///
+    /// See [`Slave::dc_receive_time`] for more information.
+    pub fn dc_receive_time(&self) -> u64 {
+        self.state.dc_receive_time
+    }
+
     /// Distributed Clock (DC) support.
     pub fn dc_support(&self) -> DcSupport {
         self.state.flags.dc_support()
@@ -337,6 +589,38 @@ where
         self.state.dc_sync
     }
 
+    /// Get the SubDevice's SII EEPROM for configuration.
+    ///
+    /// Category locations are cached if
+    /// [`ClientConfig::cache_eeprom`](crate::ClientConfig::cache_eeprom) is enabled. The returned
+    /// EEPROM should only live for one configuration step so the cache isn't kept in every
+    /// [`Slave`].
+    pub(crate) fn configuration_eeprom(&self) -> SlaveEeprom<DeviceEeprom<'a>> {
+        let eeprom = DeviceEeprom::new(self.client, self.configured_address);
+
+        if self.client.config.cache_eeprom {
+            SlaveEeprom::with_category_cache(eeprom)
+        } else {
+            SlaveEeprom::new(eeprom)
+        }
+    }
+
+    /// Where this SubDevice's process data is mapped in logical address space, once its group's
+    /// PDI has been configured.
+    pub fn pdi_layout(&self) -> PdiLayout {
+        self.state.config.layout.clone()
+    }
+
+    pub(crate) fn dc_sync_timing(&self) -> DcSyncTiming {
+        self.state.dc_sync_timing
+    }
+
+    /// Whether the SubDevice implements 64 bit DC time registers, instead of only the lower 32
+    /// bits.
+    pub(crate) fn has_64bit_dc(&self) -> bool {
+        self.state.flags.has_64bit_dc
+    }
+
     /// Return the current cyclic mailbox counter value, from 0-7.
     ///
     /// Calling this method internally increments the counter, so subequent calls will produce a new
@@ -355,23 +639,83 @@ where
         ))
     }
 
-    /// Get CoE read/write mailboxes.
-    async fn coe_mailboxes(&self) -> Result<(Mailbox, Mailbox), Error> {
-        let write_mailbox = self
+    /// Wait for exclusive access to this SubDevice's mailbox.
+    ///
+    /// Each SubDevice has its own lock, so transfers to different SubDevices can run concurrently.
+    /// The lock must be held from sending a request until its response, including all segments or
+    /// fragments, has been read, as the SubDevice can only process one mailbox request at a time.
+    ///
+    /// If the mailbox is still in use after
+    /// [`Timeouts::mailbox_response`](crate::Timeouts::mailbox_response), an [`Error::Timeout`]
+    /// is returned.
+    async fn lock_mailbox(&self) -> Result<MailboxLock<'_>, Error> {
+        async {
+            loop {
+                if let Some(lock) = self.try_lock_mailbox() {
+                    break Ok(lock);
+                }
+
+                self.client.timeouts.loop_tick().await;
+
+                // Let the task holding the lock make progress, even on a single threaded executor.
+                futures_lite::future::yield_now().await;
+            }
+        }
+        .timeout(self.client.timeouts.mailbox_response)
+        .await
+        .map_err(|e| {
+            fmt::error!(
+                "Slave {:#06x} mailbox is still in use: {}",
+                self.configured_address,
+                e
+            );
+
+            e
+        })
+    }
+
+    /// Lock the SubDevice's mailbox if it isn't already in use, without waiting.
+    fn try_lock_mailbox(&self) -> Option<MailboxLock<'_>> {
+        self.state
+            .mailbox_busy
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| MailboxLock {
+                busy: &self.state.mailbox_busy,
+            })
+    }
+
+    /// The read and write mailboxes currently configured in the SubDevice, which differ from the
+    /// ones set up during initialisation while it is being updated in the `BOOT` state.
+    fn configured_mailboxes(&self) -> (Option<Mailbox>, Option<Mailbox>) {
+        let bootstrap = self
             .state
-            .config
-            .mailbox
-            .write
+            .bootstrap_mailbox
+            .try_borrow()
+            .ok()
+            .and_then(|mailboxes| *mailboxes);
+
+        match bootstrap {
+            Some(BootMailbox { read, write, .. }) => (Some(read), Some(write)),
+            None => (
+                self.state.config.mailbox.read,
+                self.state.config.mailbox.write,
+            ),
+        }
+    }
+
+    /// Get the read/write mailboxes, clearing any stale data from the read mailbox and waiting for
+    /// the write mailbox to be ready.
+    async fn mailboxes(&self) -> Result<(Mailbox, Mailbox), Error> {
+        let (read_mailbox, write_mailbox) = self.configured_mailboxes();
+
+        let write_mailbox = write_mailbox
             .ok_or(Error::Mailbox(MailboxError::NoMailbox))
             .map_err(|e| {
                 fmt::error!("No write (slave IN) mailbox found but one is required");
                 e
             })?;
-        let read_mailbox = self
-            .state
-            .config
-            .mailbox
-            .read
+        let read_mailbox = read_mailbox
             .ok_or(Error::Mailbox(MailboxError::NoMailbox))
             .map_err(|e| {
                 fmt::error!("No read (slave OUT) mailbox found but one is required");
@@ -397,10 +741,11 @@ where
                     self.configured_address()
                 );
 
-                self.read(read_mailbox.address)
-                    .ignore_wkc()
-                    .receive_slice(self.client, read_mailbox.len)
-                    .await?;
+                let stale = self.read_mailbox(&read_mailbox, true).await?;
+
+                if !self.is_repeated_response(&stale) {
+                    self.unsolicited_response(&stale);
+                }
             }
         }
 
@@ -416,7 +761,8 @@ where
                     break Ok(());
                 }
 
-                self.client.timeouts.loop_tick().await;
+                // No extra delay between polls: every status read is already a full network
+                // round trip, so the mailbox is checked as often as the network allows.
             }
         }
         .timeout(self.client.timeouts.mailbox_echo)
@@ -434,165 +780,768 @@ where
         Ok((read_mailbox, write_mailbox))
     }
 
-    /// Wait for a mailbox response
-    async fn coe_response(&self, read_mailbox: &Mailbox) -> Result<ReceivedPdu<'_, ()>, Error> {
-        let mailbox_read_sm = RegisterAddress::sync_manager_status(read_mailbox.sync_manager);
+    /// Record the counter of a mailbox message read from the SubDevice.
+    ///
+    /// Returns `true` if the message has the same counter as the previous one, meaning the
+    /// SubDevice sent it again and it must be discarded. A gap in the counter sequence means a
+    /// message was lost, which is logged.
+    ///
+    /// A gap isn't recovered with a repeat request: the SubDevice can only repeat the message it
+    /// wrote last, which is the one just read. A read that is lost before the response arrives is
+    /// instead recovered by [`mailbox_response`](SlaveRef::mailbox_response), which requests a
+    /// repeat when no response arrives in time.
+    fn is_repeated_response(&self, mailbox: &[u8]) -> bool {
+        let Ok(header) = MailboxHeaderBase::unpack_from_slice(mailbox) else {
+            return false;
+        };
 
-        // Wait for slave OUT mailbox to be ready
-        async {
-            loop {
-                let sm_status = self
-                    .read(mailbox_read_sm)
-                    .receive::<crate::sync_manager_channel::Status>(self.client)
-                    .await?;
+        // SubDevices that don't implement the counter always send 0.
+        if header.counter == 0 {
+            return false;
+        }
 
-                if sm_status.mailbox_full {
-                    break Ok(());
-                }
+        let previous = self
+            .state
+            .mailbox_read_counter
+            .swap(header.counter, Ordering::AcqRel);
 
-                self.client.timeouts.loop_tick().await;
-            }
-        }
-        .timeout(self.client.timeouts.mailbox_echo)
-        .await
-        .map_err(|e| {
-            fmt::error!(
-                "Response mailbox IN error for slave {:#06x}: {}",
+        if previous == header.counter {
+            fmt::debug!(
+                "Slave {:#06x} discarding repeated mailbox response with counter {}",
                 self.configured_address,
-                e
+                header.counter
             );
 
-            e
-        })?;
+            return true;
+        }
 
-        // Read acknowledgement from slave OUT mailbox
-        let response = self
-            .read(read_mailbox.address)
-            .receive_slice(self.client, read_mailbox.len)
-            .await?;
+        let expected = if previous >= 7 { 1 } else { previous + 1 };
 
-        // TODO: Retries. Refer to SOEM's `ecx_mbxreceive` for inspiration
+        if previous != 0 && header.counter != expected {
+            fmt::warn!(
+                "Slave {:#06x} mailbox counter {} does not follow {}, a message was lost",
+                self.configured_address,
+                header.counter,
+                previous
+            );
+        }
 
-        Ok(response)
+        false
     }
 
-    /// Send a mailbox request, wait for response mailbox to be ready, read response from mailbox
-    /// and return as a slice.
-    async fn send_coe_service<R>(&'a self, request: R) -> Result<(R, ReceivedPdu<'_, ()>), Error>
-    where
-        R: CoeServiceRequest + Debug,
-    {
-        let (read_mailbox, write_mailbox) = self.coe_mailboxes().await?;
-
-        let counter = request.counter();
+    /// If the given mailbox data holds a CoE emergency message, add it to this SubDevice's queue of
+    /// emergencies.
+    ///
+    /// Returns `true` if the data was an emergency message.
+    fn queue_emergency(&self, mailbox: &[u8]) -> bool {
+        let Ok(header) = MailboxHeader::unpack_from_slice(mailbox) else {
+            return false;
+        };
 
-        // Send data to slave IN mailbox
-        self.write(write_mailbox.address)
-            .with_len(write_mailbox.len)
-            .send(self.client, &request.pack().as_ref())
-            .await?;
+        if header.mailbox_type != MailboxType::Coe || header.service != CoeService::Emergency {
+            return false;
+        }
 
-        let mut response = self.coe_response(&read_mailbox).await?;
+        let Ok(emergency) = CoeEmergency::unpack_from_slice(&mailbox[MailboxHeader::PACKED_LEN..])
+        else {
+            return false;
+        };
 
-        /// A super generalised version of the various header shapes for responses, extracting only
-        /// what we need in this method.
-        #[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
-        #[wire(bytes = 12)]
-        struct HeadersRaw {
-            #[wire(bytes = 8)]
-            header: MailboxHeader,
+        fmt::warn!("Slave {:#06x} sent {}", self.configured_address, emergency);
 
-            #[wire(pre_skip = 5, bits = 3)]
-            command: CoeCommand,
+        let Ok(mut queue) = self.state.emergencies.try_borrow_mut() else {
+            fmt::error!(
+                "Slave {:#06x} emergency queue is in use, discarding emergency",
+                self.configured_address
+            );
 
-            // 9 bytes up to here
+            return true;
+        };
 
-            // SAFETY: These fields will be garbage (but not invalid) if the response is NOT an
-            // abort transfer request. Use with caution!
-            #[wire(bytes = 2)]
-            address: u16,
-            #[wire(bytes = 1)]
-            sub_index: u8,
+        if queue.is_full() {
+            let _discarded = queue.pop_front();
         }
 
-        let headers = HeadersRaw::unpack_from_slice(&response)?;
+        // Space is guaranteed by the pop above.
+        let _ = queue.push_back(emergency);
 
-        if headers.command == CoeCommand::Abort {
-            let code = CoeAbortCode::Incompatible;
+        true
+    }
 
-            fmt::error!(
-                "Mailbox error for slave {:#06x} (supports complete access: {}): {}",
-                self.configured_address,
-                self.state.config.mailbox.complete_access,
-                code
-            );
+    /// Retrieve the oldest CoE emergency message received from this SubDevice, if there are any.
+    ///
+    /// Emergencies are collected whenever EtherCrab reads the SubDevice's mailbox, e.g. during an
+    /// SDO transfer. Use [`poll_emergencies`](SlaveRef::poll_emergencies) to check for new
+    /// emergencies when no other mailbox communication is happening. Up to 8 messages are stored,
+    /// after which the oldest message is discarded.
+    pub fn pop_emergency(&self) -> Option<CoeEmergency> {
+        self.state
+            .emergencies
+            .try_borrow_mut()
+            .ok()
+            .and_then(|mut queue| queue.pop_front())
+    }
 
-            Err(Error::Mailbox(MailboxError::Aborted {
-                code,
-                address: headers.address,
-                sub_index: headers.sub_index,
-            }))
+    /// Check the SubDevice's mailbox for any pending CoE emergency messages.
+    ///
+    /// Emergencies are added to the queue read by [`pop_emergency`](SlaveRef::pop_emergency).
+    /// Other pending mailbox data is handled in the same way as data received while clearing the
+    /// mailbox before a request.
+    pub async fn poll_emergencies(&self) -> Result<(), Error> {
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self
+            .state
+            .config
+            .mailbox
+            .read
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        if let Some(response) = self.read_mailbox_if_full(&read_mailbox).await? {
+            self.unsolicited_response(&response);
         }
-        // Validate that the mailbox response is to the request we just sent
-        else if headers.header.mailbox_type != MailboxType::Coe
-            || headers.header.counter != counter
-        {
-            fmt::error!(
-                "Invalid SDO response. Type: {:?} (expected {:?}), counter {} (expected {})",
-                headers.header.mailbox_type,
-                MailboxType::Coe,
-                headers.header.counter,
-                counter
-            );
 
-            Err(Error::Mailbox(MailboxError::SdoResponseInvalid {
-                address: headers.address,
-                sub_index: headers.sub_index,
-            }))
-        } else {
-            let headers = R::unpack_from_slice(&response)?;
+        Ok(())
+    }
 
-            response.trim_front(HeadersRaw::PACKED_LEN);
+    /// Handle mailbox data the SubDevice sent without a matching request, e.g. data left in the
+    /// mailbox by a previous transfer.
+    ///
+    /// CoE emergencies are queued, and EoE fragments are added to the Ethernet frame being
+    /// received so they can be collected by [`eoe_receive`](SlaveRef::eoe_receive). Mailbox error
+    /// replies are logged, and anything else is discarded with a warning.
+    fn unsolicited_response(&self, mailbox: &[u8]) {
+        if self.queue_emergency(mailbox) || self.queue_eoe_fragment(mailbox) {
+            return;
+        }
 
-            Ok((headers, response))
+        match MailboxErrorReply::unpack_from_slice(mailbox) {
+            Ok(reply) if reply.header.mailbox_type == MailboxType::Err => {
+                fmt::warn!(
+                    "Slave {:#06x} sent unsolicited mailbox error reply: {}",
+                    self.configured_address,
+                    reply.detail
+                );
+            }
+            Ok(reply) => {
+                fmt::warn!(
+                    "Slave {:#06x} discarding unsolicited {:?} mailbox data",
+                    self.configured_address,
+                    reply.header.mailbox_type
+                );
+            }
+            Err(_) => (),
         }
     }
 
-    /// Write a value to the given SDO index (address) and sub-index.
+    /// Read the SubDevice's OUT mailbox if it is full.
     ///
-    /// Note that this method currently only supports expedited SDO downloads (4 bytes maximum).
-    pub async fn sdo_write<T>(
+    /// Returns `None` if the mailbox is empty, or if it holds a repeat of the previous message.
+    async fn read_mailbox_if_full(
         &self,
-        index: u16,
-        sub_index: impl Into<SubIndex>,
-        value: T,
-    ) -> Result<(), Error>
-    where
-        T: EtherCrabWireWrite,
-    {
-        let sub_index = sub_index.into();
+        read_mailbox: &Mailbox,
+    ) -> Result<Option<MailboxData<'_>>, Error> {
+        let sm_status = self
+            .read(RegisterAddress::sync_manager_status(
+                read_mailbox.sync_manager,
+            ))
+            .receive::<crate::sync_manager_channel::Status>(self.client)
+            .await?;
 
-        let counter = self.mailbox_counter();
+        if !sm_status.mailbox_full {
+            return Ok(None);
+        }
 
-        if value.packed_len() > 4 {
-            fmt::error!("Only 4 byte SDO writes or smaller are supported currently.");
+        let response = self.read_mailbox(read_mailbox, false).await?;
 
-            // TODO: Normal SDO download. Only expedited requests for now
-            return Err(Error::Internal);
+        if self.is_repeated_response(&response) {
+            return Ok(None);
         }
 
-        let mut buf = [0u8; 4];
+        Ok(Some(response))
+    }
 
-        value.pack_to_slice(&mut buf)?;
+    /// Wait for a mailbox response.
+    ///
+    /// Any CoE emergency messages or EoE fragments received while waiting are queued, and the wait
+    /// continues. A mailbox error reply is returned as [`MailboxError::ErrorReply`].
+    async fn coe_response(
+        &self,
+        read_mailbox: &Mailbox,
+        timeout: Duration,
+    ) -> Result<MailboxData<'_>, Error> {
+        loop {
+            let response = self.mailbox_response(read_mailbox, timeout).await?;
+
+            if self.queue_emergency(&response) || self.queue_eoe_fragment(&response) {
+                continue;
+            }
 
-        let request =
-            coe::services::download(counter, index, sub_index, buf, value.packed_len() as u8);
+            if let Ok(reply) = MailboxErrorReply::unpack_from_slice(&response) {
+                if reply.header.mailbox_type == MailboxType::Err {
+                    fmt::error!(
+                        "Slave {:#06x} mailbox error reply: {}",
+                        self.configured_address,
+                        reply.detail
+                    );
+
+                    break Err(Error::Mailbox(MailboxError::ErrorReply {
+                        code: reply.detail,
+                    }));
+                }
+            }
 
-        fmt::trace!("CoE download");
+            break Ok(response);
+        }
+    }
+
+    /// Send a complete mailbox message, including its header, and copy the whole response into
+    /// `buf`, returning its length.
+    ///
+    /// The request's counter is replaced with this SubDevice's next mailbox counter, and its
+    /// address is replaced with `0`, the MainDevice's address, so messages from other masters can
+    /// be forwarded as-is. The response is copied unchanged, so its address field is the one set by
+    /// the SubDevice. CoE emergencies and EoE fragments are queued and not returned as a
+    /// response, but mailbox error replies are.
+    #[cfg(feature = "std")]
+    pub(crate) async fn mailbox_passthrough(
+        &self,
+        request: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        use crate::error::GatewayError;
+
+        let header = MailboxHeaderBase::unpack_from_slice(request)?;
+
+        let data = request
+            .get(MailboxHeaderBase::PACKED_LEN..)
+            .and_then(|data| data.get(0..usize::from(header.length)))
+            .ok_or(Error::Gateway(GatewayError::InvalidRequest))?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        let (read_mailbox, write_mailbox) = self.mailboxes().await?;
+
+        if MailboxHeaderBase::PACKED_LEN + data.len() > usize::from(write_mailbox.len) {
+            return Err(Error::Gateway(GatewayError::TooLong));
+        }
+
+        // The address field isn't part of `MailboxHeaderBase`, so it is always sent as `0`.
+        let header = MailboxHeaderBase {
+            counter: self.mailbox_counter(),
+            ..header
+        };
+
+        self.write_mailbox(&write_mailbox, (header, data)).await?;
+
+        // Error replies are forwarded as-is, so use the raw response.
+        let response = loop {
+            let response = self
+                .mailbox_response(&read_mailbox, self.client.timeouts.mailbox_response)
+                .await?;
+
+            if !self.queue_emergency(&response) && !self.queue_eoe_fragment(&response) {
+                break response;
+            }
+        };
+
+        let response_header = MailboxHeaderBase::unpack_from_slice(&response)?;
+
+        let len = (MailboxHeaderBase::PACKED_LEN + usize::from(response_header.length))
+            .min(response.len());
+
+        buf.get_mut(0..len)
+            .ok_or(Error::Gateway(GatewayError::TooLong))?
+            .copy_from_slice(&response[0..len]);
+
+        Ok(len)
+    }
+
+    /// Wait for the SubDevice's OUT mailbox to be filled, then read it.
+    ///
+    /// The mailbox full bit of the OUT mailbox sync manager's status register is polled to detect
+    /// when a response is ready, so the mailbox is read as soon as the SubDevice has written to it.
+    ///
+    /// If no response arrives within `timeout`, e.g. because the frame holding it was lost, the
+    /// SubDevice is asked to repeat it up to
+    /// [`ClientConfig::mailbox_repeat_retries`](crate::ClientConfig::mailbox_repeat_retries) times.
+    async fn mailbox_response(
+        &self,
+        read_mailbox: &Mailbox,
+        timeout: Duration,
+    ) -> Result<MailboxData<'_>, Error> {
+        let mut repeats = 0;
+
+        loop {
+            match self.wait_mailbox_response(read_mailbox, timeout).await {
+                Err(Error::Timeout) if repeats < self.client.config.mailbox_repeat_retries => {
+                    repeats += 1;
+
+                    fmt::warn!(
+                        "Slave {:#06x} mailbox response timed out, requesting repeat ({}/{})",
+                        self.configured_address,
+                        repeats,
+                        self.client.config.mailbox_repeat_retries
+                    );
+
+                    self.repeat_mailbox(read_mailbox).await?;
+                }
+                result => break result,
+            }
+        }
+    }
+
+    /// Ask the SubDevice to write its last mailbox response into the OUT mailbox again.
+    ///
+    /// The repeat bit in the mailbox sync manager's activate register is toggled, then the repeat
+    /// acknowledge bit is polled until it matches, which the SubDevice does once the response has
+    /// been written again.
+    async fn repeat_mailbox(&self, read_mailbox: &Mailbox) -> Result<(), Error> {
+        let activate = RegisterAddress::sync_manager_activate(read_mailbox.sync_manager);
+
+        let enable = self
+            .read(activate)
+            .receive::<crate::sync_manager_channel::Enable>(self.client)
+            .await?;
+
+        let repeat = !enable.repeat;
+
+        self.write(activate)
+            .send(
+                self.client,
+                crate::sync_manager_channel::Enable { repeat, ..enable },
+            )
+            .await?;
+
+        async {
+            loop {
+                let enable = self
+                    .read(activate)
+                    .receive::<crate::sync_manager_channel::Enable>(self.client)
+                    .await?;
+
+                if enable.repeat_ack == repeat {
+                    break Ok(());
+                }
+
+                self.client.timeouts.loop_tick().await;
+            }
+        }
+        .timeout(self.client.timeouts.mailbox_echo)
+        .await
+        .map_err(|e| {
+            fmt::error!(
+                "Slave {:#06x} did not acknowledge mailbox repeat request: {}",
+                self.configured_address,
+                e
+            );
+
+            e
+        })
+    }
+
+    /// Wait for a single mailbox response, timing out after `timeout`.
+    async fn wait_mailbox_response(
+        &self,
+        read_mailbox: &Mailbox,
+        timeout: Duration,
+    ) -> Result<MailboxData<'_>, Error> {
+        // Wait for slave OUT mailbox to be ready, then read the response from it. Repeated
+        // responses are discarded and the wait continues. The status is polled again straight
+        // away, as in `mailboxes`.
+        let response = async {
+            loop {
+                if let Some(response) = self.read_mailbox_if_full(read_mailbox).await? {
+                    break Ok(response);
+                }
+            }
+        }
+        .timeout(timeout)
+        .await
+        .map_err(|e| {
+            fmt::error!(
+                "Response mailbox IN error for slave {:#06x}: {}",
+                self.configured_address,
+                e
+            );
+
+            e
+        })?;
+
+        Ok(response)
+    }
+
+    /// Send a mailbox request, wait for response mailbox to be ready, read response from mailbox
+    /// and return as a slice.
+    async fn send_coe_service<R>(&'a self, request: R) -> Result<(R, MailboxData<'_>), Error>
+    where
+        R: CoeServiceRequest + Debug,
+    {
+        self.send_coe_service_with_data(request, ()).await
+    }
+
+    /// Like [`send_coe_service`](SlaveRef::send_coe_service), but with additional data sent
+    /// immediately after the request headers.
+    async fn send_coe_service_with_data<R>(
+        &'a self,
+        request: R,
+        data: impl EtherCrabWireWrite,
+    ) -> Result<(R, MailboxData<'_>), Error>
+    where
+        R: CoeServiceRequest + Debug,
+    {
+        let (read_mailbox, write_mailbox) = self.mailboxes().await?;
+
+        let counter = request.counter();
+
+        // Send data to slave IN mailbox
+        self.write_mailbox(&write_mailbox, (request.pack().as_ref(), data))
+            .await?;
+
+        let mut response = self
+            .coe_response(&read_mailbox, self.client.timeouts.coe_response)
+            .await?;
+
+        /// A super generalised version of the various header shapes for responses, extracting only
+        /// what we need in this method.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+        #[wire(bytes = 12)]
+        struct HeadersRaw {
+            #[wire(bytes = 8)]
+            header: MailboxHeader,
+
+            #[wire(pre_skip = 5, bits = 3)]
+            command: CoeCommand,
+
+            // 9 bytes up to here
+
+            // SAFETY: These fields will be garbage (but not invalid) if the response is NOT an
+            // abort transfer request. Use with caution!
+            #[wire(bytes = 2)]
+            address: u16,
+            #[wire(bytes = 1)]
+            sub_index: u8,
+        }
+
+        let headers = HeadersRaw::unpack_from_slice(&response)?;
+
+        if headers.command == CoeCommand::Abort {
+            // The abort code immediately follows the SDO header
+            let code = CoeAbortCode::unpack_from_slice(&response[HeadersRaw::PACKED_LEN..])?;
+
+            fmt::error!(
+                "Mailbox error for slave {:#06x} (supports complete access: {}): {}",
+                self.configured_address,
+                self.state.config.mailbox.complete_access,
+                code
+            );
+
+            Err(Error::Mailbox(MailboxError::Aborted {
+                code,
+                address: headers.address,
+                sub_index: headers.sub_index,
+            }))
+        }
+        // Validate that the mailbox response is to the request we just sent
+        else if headers.header.mailbox_type != MailboxType::Coe
+            || headers.header.counter != counter
+        {
+            fmt::error!(
+                "Invalid SDO response. Type: {:?} (expected {:?}), counter {} (expected {})",
+                headers.header.mailbox_type,
+                MailboxType::Coe,
+                headers.header.counter,
+                counter
+            );
+
+            Err(Error::Mailbox(MailboxError::SdoResponseInvalid {
+                address: headers.address,
+                sub_index: headers.sub_index,
+            }))
+        } else {
+            let headers = R::unpack_from_slice(&response)?;
+
+            response.trim_front(R::RESPONSE_HEADER_LEN);
+
+            Ok((headers, response))
+        }
+    }
+
+    /// Write a value to the given SDO index (address) and sub-index.
+    ///
+    /// Values of 4 bytes or less are sent with an expedited SDO download. Larger values are sent
+    /// with a normal SDO download, and must fit in a single mailbox. To write values longer than
+    /// the SubDevice mailbox, use [`sdo_write_slice`](SlaveRef::sdo_write_slice) which supports
+    /// segmented transfers.
+    ///
+    /// If [`ClientConfig::verify_sdo_writes`](crate::ClientConfig::verify_sdo_writes) is enabled,
+    /// the value is read back after writing and a
+    /// [`MailboxError::VerifyFailed`](crate::error::MailboxError::VerifyFailed) error is returned if
+    /// it does not match. Values longer than 256 bytes are not verified; use
+    /// [`sdo_write_slice`](SlaveRef::sdo_write_slice) to verify values of any length.
+    pub async fn sdo_write<T>(
+        &self,
+        index: u16,
+        sub_index: impl Into<SubIndex>,
+        value: T,
+    ) -> Result<(), Error>
+    where
+        T: EtherCrabWireWrite,
+    {
+        let sub_index = sub_index.into();
+
+        let _lock = self.lock_mailbox().await?;
+
+        if !self.client.config.verify_sdo_writes {
+            return self.sdo_download(index, sub_index, value).await;
+        }
+
+        if value.packed_len() > MAX_VERIFY_LEN {
+            fmt::warn!(
+                "Slave {:#06x} SDO {:#06x}:{:?} is too long to verify",
+                self.configured_address,
+                index,
+                sub_index
+            );
+
+            return self.sdo_download(index, sub_index, value).await;
+        }
+
+        let mut expected = [0u8; MAX_VERIFY_LEN];
+
+        let expected = value.pack_to_slice(&mut expected)?;
+
+        self.sdo_download(index, sub_index, expected).await?;
+
+        self.sdo_verify(index, sub_index, expected).await
+    }
+
+    /// Read back a value that was just written to an SDO and check it matches `expected`. The
+    /// mailbox must already be locked.
+    ///
+    /// The value is compared as each segment is received, so values of any length can be checked.
+    /// If the SubDevice aborts the read, e.g. because the object is write only, the value can't be
+    /// verified and this is logged instead of returning an error.
+    async fn sdo_verify(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        expected: &[u8],
+    ) -> Result<(), Error> {
+        let mut mismatch = None;
+
+        let result = self
+            .sdo_upload_chunks(index, sub_index, usize::MAX, |offset, chunk| {
+                if mismatch.is_none() && expected.get(offset..(offset + chunk.len())) != Some(chunk)
+                {
+                    mismatch = Some(offset);
+                }
+
+                Ok(())
+            })
+            .await;
+
+        let len = match result {
+            Ok(len) => len,
+            Err(Error::Mailbox(MailboxError::Aborted { code, .. })) => {
+                fmt::warn!(
+                    "Slave {:#06x} SDO {:#06x}:{:?} cannot be read back to verify: {}",
+                    self.configured_address,
+                    index,
+                    sub_index,
+                    code
+                );
+
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        if mismatch.is_some() || len != expected.len() {
+            fmt::error!(
+                "Slave {:#06x} SDO {:#06x}:{:?} verification failed: wrote {} bytes, read back {} bytes differing from offset {}",
+                self.configured_address,
+                index,
+                sub_index,
+                expected.len(),
+                len,
+                mismatch.unwrap_or(expected.len().min(len))
+            );
+
+            return Err(Error::Mailbox(MailboxError::VerifyFailed {
+                address: index,
+                sub_index: sub_index.sub_index(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Expedited or normal SDO download. The mailbox must already be locked.
+    async fn sdo_download<T>(&self, index: u16, sub_index: SubIndex, value: T) -> Result<(), Error>
+    where
+        T: EtherCrabWireWrite,
+    {
+        if value.packed_len() > 4 {
+            return self.sdo_write_normal(index, sub_index, value).await;
+        }
+
+        let counter = self.mailbox_counter();
+
+        let mut buf = [0u8; 4];
+
+        value.pack_to_slice(&mut buf)?;
+
+        let request =
+            coe::services::download(counter, index, sub_index, buf, value.packed_len() as u8);
+
+        fmt::trace!("CoE download");
+
+        let (_response, _data) = self.send_coe_service(request).await?;
+
+        // TODO: Validate reply?
+
+        Ok(())
+    }
+
+    /// Write a slice of raw bytes to the given SDO index (address) and sub-index.
+    ///
+    /// The transfer type is chosen based on the length of `data`. Short values use an expedited
+    /// download, values that fit in the SubDevice's mailbox use a normal download, and anything
+    /// longer is split into a segmented download.
+    ///
+    /// Like [`sdo_write`](SlaveRef::sdo_write), the data is read back and compared if
+    /// [`ClientConfig::verify_sdo_writes`](crate::ClientConfig::verify_sdo_writes) is enabled.
+    pub async fn sdo_write_slice(
+        &self,
+        index: u16,
+        sub_index: impl Into<SubIndex>,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let sub_index = sub_index.into();
+
+        let _lock = self.lock_mailbox().await?;
+
+        if data.len() <= self.sdo_normal_download_capacity()? {
+            self.sdo_download(index, sub_index, data).await?;
+        } else {
+            self.sdo_write_segmented(index, sub_index, data).await?;
+        }
+
+        if self.client.config.verify_sdo_writes {
+            self.sdo_verify(index, sub_index, data).await?;
+        }
+
+        Ok(())
+    }
+
+    /// The maximum number of data bytes that fit into a single normal SDO download request.
+    fn sdo_normal_download_capacity(&self) -> Result<usize, Error> {
+        let write_mailbox = self
+            .state
+            .config
+            .mailbox
+            .write
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        Ok(usize::from(write_mailbox.len).saturating_sub(SdoNormalDownload::PACKED_LEN))
+    }
+
+    async fn sdo_write_normal(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        value: impl EtherCrabWireWrite,
+    ) -> Result<(), Error> {
+        let len = value.packed_len();
+
+        if len > self.sdo_normal_download_capacity()? {
+            fmt::error!(
+                "SDO {:#06x}:{} value of {} bytes is too long for slave {:#06x} mailbox",
+                index,
+                sub_index.sub_index(),
+                len,
+                self.configured_address
+            );
+
+            return Err(Error::Mailbox(MailboxError::TooLong {
+                address: index,
+                sub_index: sub_index.sub_index(),
+            }));
+        }
+
+        let request = coe::services::download_normal(
+            self.mailbox_counter(),
+            index,
+            sub_index,
+            len as u32,
+            len as u16,
+        );
+
+        fmt::trace!("CoE download normal {:#06x} {:?}", index, sub_index);
+
+        self.send_coe_service_with_data(request, value).await?;
+
+        Ok(())
+    }
+
+    async fn sdo_write_segmented(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let capacity = self.sdo_normal_download_capacity()?;
+
+        // Segment requests have a smaller header than the initiate request, so can carry a little
+        // more data.
+        let segment_capacity = capacity + SdoNormalDownload::PACKED_LEN - SdoSegmented::PACKED_LEN;
+
+        let (first, mut rest) = data.split_at(capacity);
+
+        let request = coe::services::download_normal(
+            self.mailbox_counter(),
+            index,
+            sub_index,
+            data.len() as u32,
+            first.len() as u16,
+        );
+
+        fmt::trace!("CoE download segmented {:#06x} {:?}", index, sub_index);
+
+        self.send_coe_service_with_data(request, first).await?;
+
+        let mut toggle = false;
+
+        while !rest.is_empty() {
+            let (chunk, remaining) = rest.split_at(rest.len().min(segment_capacity));
 
-        let (_response, _data) = self.send_coe_service(request).await?;
+            let request = coe::services::download_segment(
+                self.mailbox_counter(),
+                toggle,
+                remaining.is_empty(),
+                chunk.len() as u16,
+            );
 
-        // TODO: Validate reply?
+            fmt::trace!("CoE download segment, {} bytes remaining", remaining.len());
+
+            let (headers, _data) = self.send_coe_service_with_data(request, chunk).await?;
+
+            if headers.sdo_header.toggle != toggle {
+                fmt::error!(
+                    "SDO download segment toggle mismatch for slave {:#06x}",
+                    self.configured_address
+                );
+
+                return Err(Error::Mailbox(MailboxError::SdoResponseInvalid {
+                    address: index,
+                    sub_index: sub_index.sub_index(),
+                }));
+            }
+
+            rest = remaining;
+            toggle = !toggle;
+        }
 
         Ok(())
     }
@@ -613,6 +1562,8 @@ where
 
         let sub_index = sub_index.into();
 
+        let _lock = self.lock_mailbox().await?;
+
         let request = coe::services::upload(self.mailbox_counter(), index, sub_index);
 
         fmt::trace!("CoE upload {:#06x} {:?}", index, sub_index);
@@ -632,12 +1583,67 @@ where
     }
 
     /// Read a value from an SDO (Service Data Object) from the given index (address) and sub-index.
+    ///
+    /// The response is decoded into `T`, with any little-endian conversion handled by EtherCrab.
+    /// Supported types include:
+    ///
+    /// - Integers (`u8`, `i16`, `u32`, etc) and floats.
+    /// - `bool`.
+    /// - Strings, e.g. `heapless::String<64>`, with any trailing null bytes removed.
+    /// - Fixed size arrays like `[u16; 4]`, or a `heapless::Vec<u8, N>` for variable length data.
+    /// - Any type that implements [`EtherCrabWireReadSized`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let mut group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// for slave in group.iter(&client) {
+    ///     // Identity object vendor ID
+    ///     let vendor_id = slave.sdo_read::<u32>(0x1018, 1).await?;
+    ///
+    ///     // Manufacturer device name
+    ///     let name = slave.sdo_read::<heapless::String<64>>(0x1008, 0).await?;
+    ///
+    ///     log::info!("{}: vendor {:#010x}", name, vendor_id);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the SubDevice aborts the transfer, an
+    /// [`MailboxError::Aborted`](crate::error::MailboxError::Aborted) is returned containing the
+    /// [`CoeAbortCode`](crate::error::CoeAbortCode) sent by the SubDevice:
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::{CoeAbortCode, Error, MailboxError}, SlaveRef };
+    /// # async fn read(slave: SlaveRef<'_, ethercrab::SlavePdi<'_>>) -> Result<(), Error> {
+    /// match slave.sdo_read::<u16>(0x6060, 0).await {
+    ///     Ok(mode) => log::info!("Mode of operation: {}", mode),
+    ///     Err(Error::Mailbox(MailboxError::Aborted {
+    ///         code: CoeAbortCode::NotFound,
+    ///         ..
+    ///     })) => log::warn!("Device has no modes of operation object"),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn sdo_read<T>(&self, index: u16, sub_index: impl Into<SubIndex>) -> Result<T, Error>
     where
         T: EtherCrabWireReadSized,
     {
         let sub_index = sub_index.into();
 
+        let _lock = self.lock_mailbox().await?;
+
         let mut storage = T::buffer();
         let buf = storage.as_mut();
 
@@ -659,129 +1665,706 @@ where
         else {
             let data_length = headers.header.length.saturating_sub(0x0a);
 
-            let complete_size = u32::unpack_from_slice(data)?;
-            let data = &data[u32::PACKED_LEN..];
+            let complete_size = u32::unpack_from_slice(data)?;
+            let data = &data[u32::PACKED_LEN..];
+
+            // The value is longer than the type it's being decoded into.
+            if complete_size > T::PACKED_LEN as u32 {
+                return Err(Error::Mailbox(MailboxError::TooLong {
+                    address: headers.sdo_header.index,
+                    sub_index: headers.sdo_header.sub_index,
+                }));
+            }
+
+            // If it's a normal upload, the response payload is returned in the initial mailbox read
+            if complete_size <= u32::from(data_length) {
+                data.get(0..usize::from(data_length)).ok_or(Error::Mailbox(
+                    MailboxError::SdoResponseInvalid {
+                        address: index,
+                        sub_index: sub_index.sub_index(),
+                    },
+                ))?
+            }
+            // If it's a segmented upload, we must make subsequent requests to load all segment data
+            // from the read mailbox.
+            else {
+                // The intermediate buffer isn't long enough to reassemble all segments into.
+                if complete_size > buf.len() as u32 {
+                    return Err(Error::Mailbox(MailboxError::TooLong {
+                        address: headers.sdo_header.index,
+                        sub_index: headers.sdo_header.sub_index,
+                    }));
+                }
+
+                // Release the initiate response before requesting segments
+                drop(response);
+
+                let total_len = self
+                    .sdo_upload_segments(index, sub_index, complete_size, |offset, chunk| {
+                        buf.get_mut(offset..(offset + chunk.len()))
+                            .ok_or(Error::Mailbox(MailboxError::TooLong {
+                                address: index,
+                                sub_index: sub_index.sub_index(),
+                            }))?
+                            .copy_from_slice(chunk);
+
+                        Ok(())
+                    })
+                    .await?;
+
+                &buf[0..total_len]
+            }
+        };
+
+        T::unpack_from_slice(response_payload).map_err(|_| {
+            fmt::error!(
+                "SDO expedited data decode T: {} (len {}) data {:?} (len {})",
+                type_name::<T>(),
+                T::PACKED_LEN,
+                response_payload,
+                response_payload.len()
+            );
+
+            Error::Pdu(PduError::Decode)
+        })
+    }
+
+    /// Read the raw value of an SDO into `buf`, returning the number of bytes written.
+    ///
+    /// Unlike [`sdo_read`](SlaveRef::sdo_read), no intermediate buffer is used, so objects of any
+    /// length can be read as long as `buf` is large enough to hold them. Segmented uploads are
+    /// reassembled directly into `buf`.
+    ///
+    /// # Errors
+    ///
+    /// If the value is longer than `buf`, a
+    /// [`MailboxError::TooLong`](crate::error::MailboxError::TooLong) error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, SlaveRef, Slave };
+    /// # async fn read(slave: SlaveRef<'_, &Slave>) -> Result<(), Error> {
+    /// let mut buf = [0u8; 512];
+    ///
+    /// let len = slave.sdo_read_into(0x2000, 0, &mut buf).await?;
+    ///
+    /// log::info!("Read {} bytes: {:?}", len, &buf[0..len]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sdo_read_into(
+        &self,
+        index: u16,
+        sub_index: impl Into<SubIndex>,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let _lock = self.lock_mailbox().await?;
+
+        self.sdo_upload_into(index, sub_index.into(), buf).await
+    }
+
+    /// Upload an SDO into `buf`. The mailbox must already be locked.
+    async fn sdo_upload_into(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let too_long = Error::Mailbox(MailboxError::TooLong {
+            address: index,
+            sub_index: sub_index.sub_index(),
+        });
+
+        let max_len = buf.len();
+
+        self.sdo_upload_chunks(index, sub_index, max_len, |offset, chunk| {
+            buf.get_mut(offset..(offset + chunk.len()))
+                .ok_or(too_long)?
+                .copy_from_slice(chunk);
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Upload an SDO, passing each chunk of data to `sink` along with its offset from the start of
+    /// the value, and returning the total length. The mailbox must already be locked.
+    ///
+    /// If the SubDevice reports a value longer than `max_len`, the transfer is not started.
+    async fn sdo_upload_chunks(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        max_len: usize,
+        mut sink: impl FnMut(usize, &[u8]) -> Result<(), Error>,
+    ) -> Result<usize, Error> {
+        let too_long = Error::Mailbox(MailboxError::TooLong {
+            address: index,
+            sub_index: sub_index.sub_index(),
+        });
+
+        let request = coe::services::upload(self.mailbox_counter(), index, sub_index);
+
+        fmt::trace!("CoE upload {:#06x} {:?} into buffer", index, sub_index);
+
+        let (headers, response) = self.send_coe_service(request).await?;
+        let data: &[u8] = &response;
+
+        let payload = if headers.sdo_header.expedited_transfer {
+            let data_len = 4usize.saturating_sub(usize::from(headers.sdo_header.size));
+
+            &data[0..data_len]
+        } else {
+            let data_length = headers.header.length.saturating_sub(0x0a);
+
+            let complete_size = u32::unpack_from_slice(data)?;
+            let data = &data[u32::PACKED_LEN..];
+
+            if complete_size as usize > max_len {
+                return Err(too_long);
+            }
+
+            // Segmented upload
+            if complete_size > u32::from(data_length) {
+                // Release the initiate response before requesting segments
+                drop(response);
+
+                return self
+                    .sdo_upload_segments(index, sub_index, complete_size, sink)
+                    .await;
+            }
+
+            data.get(0..usize::from(data_length)).ok_or(Error::Mailbox(
+                MailboxError::SdoResponseInvalid {
+                    address: index,
+                    sub_index: sub_index.sub_index(),
+                },
+            ))?
+        };
+
+        sink(0, payload)?;
+
+        Ok(payload.len())
+    }
+
+    /// Read all segments of a segmented SDO upload, passing each one to `sink` along with its
+    /// offset, and returning the total length.
+    ///
+    /// The initiating upload request must already have been acknowledged by the SubDevice. The
+    /// toggle bit of each response is checked against the request to detect lost or repeated
+    /// segments, and the total length read is checked against the `complete_size` given in the
+    /// initiate response.
+    async fn sdo_upload_segments(
+        &self,
+        index: u16,
+        sub_index: SubIndex,
+        complete_size: u32,
+        mut sink: impl FnMut(usize, &[u8]) -> Result<(), Error>,
+    ) -> Result<usize, Error> {
+        let invalid = Error::Mailbox(MailboxError::SdoResponseInvalid {
+            address: index,
+            sub_index: sub_index.sub_index(),
+        });
+
+        let mut toggle = false;
+        let mut total_len = 0usize;
+
+        loop {
+            let request = coe::services::upload_segmented(self.mailbox_counter(), toggle);
+
+            fmt::trace!("CoE upload segmented, toggle {:?}", toggle);
+
+            let (headers, data) = self.send_coe_service(request).await?;
+
+            if headers.sdo_header.toggle != toggle {
+                fmt::error!(
+                    "Segmented upload toggle mismatch for {:#06x}:{:?}",
+                    index,
+                    sub_index
+                );
+
+                return Err(invalid);
+            }
+
+            // The spec defines the data length as n-3, so we'll just go with that magic
+            // number...
+            let mut chunk_len = usize::from(headers.header.length.saturating_sub(3));
+
+            // Special case as per spec: Minimum response size is 7 bytes. For smaller
+            // responses, we must remove the number of unused bytes at the end of the
+            // response. Extremely weird.
+            if chunk_len == 7 {
+                chunk_len -= usize::from(headers.sdo_header.segment_data_size);
+            }
+
+            let data = data.get(0..chunk_len).ok_or(invalid)?;
+
+            sink(total_len, data)?;
+
+            total_len += chunk_len;
+
+            if headers.sdo_header.is_last_segment {
+                break;
+            }
+
+            toggle = !toggle;
+        }
+
+        if total_len as u32 != complete_size {
+            fmt::error!(
+                "Segmented upload for {:#06x}:{:?} returned {} bytes, expected {}",
+                index,
+                sub_index,
+                total_len,
+                complete_size
+            );
+
+            return Err(invalid);
+        }
+
+        Ok(total_len)
+    }
+}
+
+/// Exclusive access to a SubDevice's mailbox, released when dropped.
+struct MailboxLock<'a> {
+    busy: &'a AtomicBool,
+}
+
+impl Drop for MailboxLock<'_> {
+    fn drop(&mut self) {
+        self.busy.store(false, Ordering::Release);
+    }
+}
+
+/// A transfer that spans more than one mailbox exchange, e.g. an FoE file read, ended when
+/// dropped.
+///
+/// Only one session per protocol can be in progress with a SubDevice, but the mailbox itself is
+/// only locked for each exchange, so other protocols can be used while a session is open.
+struct ProtocolSession<'a> {
+    active: &'a AtomicBool,
+}
+
+impl<'a> ProtocolSession<'a> {
+    /// Start a session, returning `None` if one is already in progress.
+    fn start(active: &'a AtomicBool) -> Option<Self> {
+        active
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| Self { active })
+    }
+}
+
+impl Drop for ProtocolSession<'_> {
+    fn drop(&mut self) {
+        self.active.store(false, Ordering::Release);
+    }
+}
+
+// General impl with no bounds
+impl<'a, S> SlaveRef<'a, S> {
+    pub(crate) fn new(client: &'a Client<'a>, configured_address: u16, state: S) -> Self {
+        Self {
+            client,
+            configured_address,
+            state,
+        }
+    }
+
+    /// Get the configured station address of the slave device.
+    pub fn configured_address(&self) -> u16 {
+        self.configured_address
+    }
+
+    /// Get the EtherCAT state machine state of the sub device.
+    ///
+    /// Returns [`Error::SubDevice`] with the sub device's AL status code if its error flag is set.
+    pub async fn state(&self) -> Result<SlaveState, Error> {
+        match self
+            .read(RegisterAddress::AlStatus)
+            .receive::<AlControl>(self.client)
+            .await
+            .and_then(|ctl| {
+                if ctl.error {
+                    Err(Error::SubDevice(AlStatusCode::Unknown(0)))
+                } else {
+                    Ok(ctl.state)
+                }
+            }) {
+            Ok(state) => Ok(state),
+            Err(e) => match e {
+                Error::SubDevice(AlStatusCode::Unknown(0)) => {
+                    let code = self
+                        .read(RegisterAddress::AlStatusCode)
+                        .receive::<AlStatusCode>(self.client)
+                        .await
+                        .unwrap_or(AlStatusCode::Unknown(0));
+
+                    Err(Error::SubDevice(code))
+                }
+                e => Err(e),
+            },
+        }
+    }
+
+    /// Get the EtherCAT state machine state of the sub device.
+    pub async fn status(&self) -> Result<(SlaveState, AlStatusCode), Error> {
+        let code = self
+            .read(RegisterAddress::AlStatusCode)
+            .receive::<AlStatusCode>(self.client);
+
+        futures_lite::future::try_zip(self.state(), code).await
+    }
+
+    /// Request the SubDevice go to the given state and wait for it to get there, independently of
+    /// the rest of its group.
+    ///
+    /// This can be used to take one SubDevice to PRE-OP, e.g. to write SDOs that may only be
+    /// written in PRE-OP, while the rest of the group stays in OP. When going to a higher state,
+    /// the SubDevice is taken through each state in between, e.g. PRE-OP, SAFE-OP then OP.
+    ///
+    /// A SubDevice out of OP doesn't exchange process data, so the group's working counter will be
+    /// lower than expected until it returns to OP. Many SubDevices only enter OP once they receive
+    /// valid process data, so keep calling [`tx_rx`](crate::SlaveGroup::tx_rx) from another task
+    /// while waiting for OP.
+    ///
+    /// Sync managers and FMMUs are kept in PRE-OP and SAFE-OP, but a SubDevice taken to INIT must
+    /// be configured again before it can return to OP, e.g. with
+    /// [`SlaveGroup::recover`](crate::SlaveGroup::recover). If `recover` is polled from a
+    /// supervision task, it will return the SubDevice to OP unless the SubDevice stays borrowed.
+    pub async fn request_state(&self, state: SlaveState) -> Result<(), Error> {
+        // SubDevices can only go up one state at a time
+        const STEPS: [SlaveState; 4] = [
+            SlaveState::Init,
+            SlaveState::PreOp,
+            SlaveState::SafeOp,
+            SlaveState::Op,
+        ];
+
+        let mut current = self.state().await?;
+
+        let position = |state| STEPS.iter().position(|step| *step == state);
+
+        if let (Some(from), Some(to)) = (position(current), position(state)) {
+            for step in STEPS.get(from + 1..to).unwrap_or(&[]) {
+                self.request_slave_state(current, *step).await?;
+
+                current = *step;
+            }
+        }
+
+        self.request_slave_state(current, state).await
+    }
+
+    /// Read the SubDevice's configured station alias, which is loaded from its EEPROM at power up.
+    ///
+    /// A value of zero means no alias is set. Use [`set_station_alias`](SlaveRef::set_station_alias)
+    /// to change it.
+    pub async fn station_alias(&self) -> Result<u16, Error> {
+        self.read(RegisterAddress::ConfiguredStationAlias)
+            .receive::<u16>(self.client)
+            .await
+    }
+
+    /// Write a new configured station alias into the SubDevice's EEPROM and activate it.
+    ///
+    /// The alias is stored in the EEPROM's configuration area along with an updated checksum, so
+    /// it is kept through power cycles and identifies the SubDevice regardless of its position in
+    /// the network. The SubDevice then reloads its configuration area from EEPROM so the new alias
+    /// is loaded into the configured station alias register (`0x0012`) straight away, and the
+    /// register is read back to check the alias was written.
+    ///
+    /// [`Slave::alias`] is not updated by this method.
+    ///
+    /// EEPROM writes are slow on some devices, so the [`eeprom`](crate::Timeouts::eeprom) timeout
+    /// may need to be increased.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::Write`] if the SubDevice rejects the write, e.g. because its
+    /// EEPROM is write protected, or [`EepromError::Verify`] if the alias read back from the
+    /// SubDevice does not match.
+    pub async fn set_station_alias(&self, alias: u16) -> Result<(), Error> {
+        // WORDs 0x0000 - 0x0007
+        let mut config = [0u8; 16];
+
+        self.eeprom_read(0, &mut config).await?;
+
+        config[8..10].copy_from_slice(&alias.to_le_bytes());
+
+        // The high byte of the checksum WORD is reserved, so is kept as-is
+        config[14] = config_checksum(&config[0..14]);
+
+        self.eeprom_write(SiiCoding::ConfiguredStationAlias as u32, &config[8..10])
+            .await?;
+        self.eeprom_write(SiiCoding::Checksum as u32, &config[14..16])
+            .await?;
+
+        DeviceEeprom::new(self.client, self.configured_address)
+            .reload()
+            .await?;
+
+        let actual = self.station_alias().await?;
+
+        if actual != alias {
+            fmt::error!(
+                "Slave {:#06x} station alias is {:#06x} after writing {:#06x}",
+                self.configured_address,
+                actual,
+                alias
+            );
+
+            return Err(Error::Eeprom(EepromError::Verify));
+        }
+
+        Ok(())
+    }
+
+    /// Write the EEPROM configuration area in WORDs `0x0000` to `0x0006`, along with its checksum
+    /// in WORD `0x0007`.
+    ///
+    /// The checksum is computed with [`config_checksum`](crate::sii::config_checksum), and the
+    /// reserved high byte of the checksum WORD is kept as-is. This can be used to repair a
+    /// SubDevice whose configuration area is corrupt. The SubDevice then reloads its configuration
+    /// area from EEPROM, and the configuration area is read back to check it was written.
+    ///
+    /// EEPROM writes are slow on some devices, so the [`eeprom`](crate::Timeouts::eeprom) timeout
+    /// may need to be increased.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::Write`] if the SubDevice rejects the write, e.g. because its
+    /// EEPROM is write protected, or [`EepromError::Verify`] if the configuration area read back
+    /// from the EEPROM does not match.
+    pub async fn write_config_area(&self, config: &[u8; 14]) -> Result<(), Error> {
+        let mut data = [0u8; 15];
+
+        data[0..14].copy_from_slice(config);
+        data[14] = config_checksum(config);
+
+        // Odd length keeps the reserved high byte of the checksum WORD
+        self.eeprom_write(0, &data).await?;
 
-            // The provided buffer isn't long enough to contain all mailbox data.
-            if complete_size > buf.len() as u32 {
-                return Err(Error::Mailbox(MailboxError::TooLong {
-                    address: headers.sdo_header.index,
-                    sub_index: headers.sdo_header.sub_index,
-                }));
-            }
+        let eeprom = DeviceEeprom::new(self.client, self.configured_address);
 
-            // If it's a normal upload, the response payload is returned in the initial mailbox read
-            if complete_size <= u32::from(data_length) {
-                &data[0..usize::from(data_length)]
-            }
-            // If it's a segmented upload, we must make subsequent requests to load all segment data
-            // from the read mailbox.
-            else {
-                let mut toggle = false;
-                let mut total_len = 0usize;
+        eeprom.reload().await?;
+
+        let actual = SlaveEeprom::new(eeprom).config_area().await?;
+
+        if actual[0..15] != data {
+            fmt::error!(
+                "Slave {:#06x} EEPROM configuration area does not match after writing",
+                self.configured_address
+            );
+
+            return Err(Error::Eeprom(EepromError::Verify));
+        }
 
-                loop {
-                    let request = coe::services::upload_segmented(self.mailbox_counter(), toggle);
+        Ok(())
+    }
 
-                    fmt::trace!("CoE upload segmented");
+    /// Read raw bytes from the SubDevice's EEPROM, starting at WORD address `start_word`, until
+    /// `buf` is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::SectionOverrun`] if the read would go past the end of the 32 bit
+    /// EEPROM address space, or uses WORD addresses above `0xffff` on a SubDevice that doesn't
+    /// support EEPROMs larger than 1 Mbit.
+    pub async fn eeprom_read(&self, start_word: u32, buf: &mut [u8]) -> Result<(), Error> {
+        let len_words = eeprom_len_words(start_word, buf.len())?;
+
+        self.set_eeprom_mode(SiiOwner::Master).await?;
+
+        ChunkReader::new(
+            DeviceEeprom::new(self.client, self.configured_address),
+            start_word,
+            len_words,
+        )
+        .read_exact(buf)
+        .await?;
 
-                    let (headers, data) = self.send_coe_service(request).await?;
+        Ok(())
+    }
 
-                    // The spec defines the data length as n-3, so we'll just go with that magic
-                    // number...
-                    let mut chunk_len = usize::from(headers.header.length - 3);
+    /// Write raw bytes to the SubDevice's EEPROM, starting at WORD address `start_word`.
+    ///
+    /// The EEPROM is written one WORD at a time, waiting for each write to complete. If `data` has
+    /// an odd length, the high byte of the last WORD is read from the EEPROM and kept as-is.
+    ///
+    /// Writing invalid data, particularly to the configuration area in WORDs `0x0000` to `0x0007`,
+    /// may stop the SubDevice from working until its EEPROM is written again. EEPROM writes are
+    /// slow on some devices, so the [`eeprom`](crate::Timeouts::eeprom) timeout may need to be
+    /// increased.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EepromError::Write`] if the SubDevice doesn't acknowledge a write after a few
+    /// attempts, or the EEPROM is write protected, and [`EepromError::SectionOverrun`] if the
+    /// write would go past the end of the 32 bit EEPROM address space, or uses WORD addresses
+    /// above `0xffff` on a SubDevice that doesn't support EEPROMs larger than 1 Mbit.
+    pub async fn eeprom_write(&self, start_word: u32, data: &[u8]) -> Result<(), Error> {
+        eeprom_len_words(start_word, data.len())?;
 
-                    // Special case as per spec: Minimum response size is 7 bytes. For smaller
-                    // responses, we must remove the number of unused bytes at the end of the
-                    // response. Extremely weird.
-                    if chunk_len == 7 {
-                        chunk_len -= usize::from(headers.sdo_header.segment_data_size);
-                    }
+        self.set_eeprom_mode(SiiOwner::Master).await?;
 
-                    let data = &data[0..chunk_len];
+        let mut eeprom = DeviceEeprom::new(self.client, self.configured_address);
 
-                    buf[total_len..][..chunk_len].copy_from_slice(data);
-                    total_len += chunk_len;
+        for (word, chunk) in (start_word..).zip(data.chunks(2)) {
+            let high = match chunk.get(1) {
+                Some(high) => *high,
+                None => {
+                    let mut existing = [0u8; 2];
 
-                    if headers.sdo_header.is_last_segment {
-                        break;
-                    }
+                    ChunkReader::new(eeprom.clone(), word, 1)
+                        .read_exact(&mut existing)
+                        .await?;
 
-                    toggle = !toggle;
+                    existing[1]
                 }
+            };
 
-                &buf[0..total_len]
-            }
-        };
+            eeprom
+                .write_word(word, u16::from_le_bytes([chunk[0], high]))
+                .await?;
+        }
 
-        T::unpack_from_slice(response_payload).map_err(|_| {
-            fmt::error!(
-                "SDO expedited data decode T: {} (len {}) data {:?} (len {})",
-                type_name::<T>(),
-                T::PACKED_LEN,
-                response_payload,
-                response_payload.len()
-            );
+        Ok(())
+    }
 
-            Error::Pdu(PduError::Decode)
-        })
+    /// Offer control of the SubDevice's EEPROM to its local application over the PDI (Process
+    /// Data Interface).
+    ///
+    /// Some SubDevices must have EEPROM access before they will complete a firmware update or run
+    /// local configuration. Use [`pdi_has_eeprom`](SlaveRef::pdi_has_eeprom) to check whether the
+    /// local application has taken control, and [`reclaim_eeprom`](SlaveRef::reclaim_eeprom) to
+    /// take control back.
+    ///
+    /// Methods that access the EEPROM, e.g. [`eeprom_read`](SlaveRef::eeprom_read) and
+    /// [`eeprom_write`](SlaveRef::eeprom_write), take control back first. EtherCrab also takes
+    /// control back while configuring a SubDevice.
+    pub async fn release_eeprom_to_pdi(&self) -> Result<(), Error> {
+        self.set_eeprom_mode(SiiOwner::Pdi).await
     }
-}
 
-// General impl with no bounds
-impl<'a, S> SlaveRef<'a, S> {
-    pub(crate) fn new(client: &'a Client<'a>, configured_address: u16, state: S) -> Self {
-        Self {
-            client,
-            configured_address,
-            state,
-        }
+    /// Take control of the SubDevice's EEPROM back from its local application.
+    ///
+    /// Any EEPROM access by the local application that is still in progress is cancelled.
+    pub async fn reclaim_eeprom(&self) -> Result<(), Error> {
+        self.set_eeprom_mode(SiiOwner::Master).await
     }
 
-    /// Get the configured station address of the slave device.
-    pub fn configured_address(&self) -> u16 {
-        self.configured_address
+    /// Check whether the SubDevice's local application has taken control of its EEPROM after it
+    /// was offered with [`release_eeprom_to_pdi`](SlaveRef::release_eeprom_to_pdi).
+    pub async fn pdi_has_eeprom(&self) -> Result<bool, Error> {
+        // The high byte is the PDI access state register `0x0501`
+        let config = self
+            .read(RegisterAddress::SiiConfig)
+            .receive::<u16>(self.client)
+            .await?;
+
+        Ok(config & 0x0100 != 0)
     }
 
-    /// Get the sub device status.
-    pub(crate) async fn state(&self) -> Result<SlaveState, Error> {
-        match self
+    /// Read the SubDevice's explicit device ID, e.g. set with rotary switches, by sending an ID
+    /// request in the AL control register as described in ETG1020.
+    ///
+    /// The request is only sent if the SubDevice declares support for it with
+    /// [`GeneralFlags::IDENT_AL_STATUS`](crate::sii::GeneralFlags::IDENT_AL_STATUS) in its EEPROM,
+    /// so this costs a few EEPROM reads for SubDevices without an explicit device ID.
+    ///
+    /// Returns `None` if the SubDevice doesn't support ID requests, doesn't answer the request
+    /// within the [`id_request`](crate::Timeouts::id_request) timeout, or rejects it.
+    pub async fn explicit_device_id(&self) -> Result<Option<u16>, Error> {
+        // Most SubDevices ignore ID requests, and would otherwise each wait for the whole timeout
+        let supported = match self.eeprom().general().await {
+            Ok(general) => general.flags.contains(GeneralFlags::IDENT_AL_STATUS),
+            Err(Error::Eeprom(EepromError::NoCategory)) => false,
+            Err(e) => return Err(e),
+        };
+
+        if !supported {
+            fmt::debug!(
+                "Slave {:#06x} does not support explicit device ID requests",
+                self.configured_address
+            );
+
+            return Ok(None);
+        }
+
+        let state = self
             .read(RegisterAddress::AlStatus)
             .receive::<AlControl>(self.client)
-            .await
-            .and_then(|ctl| {
-                if ctl.error {
-                    Err(Error::SubDevice(AlStatusCode::Unknown(0)))
-                } else {
-                    Ok(ctl.state)
-                }
-            }) {
-            Ok(state) => Ok(state),
-            Err(e) => match e {
-                Error::SubDevice(AlStatusCode::Unknown(0)) => {
-                    let code = self
-                        .read(RegisterAddress::AlStatusCode)
-                        .receive::<AlStatusCode>(self.client)
-                        .await
-                        .unwrap_or(AlStatusCode::Unknown(0));
+            .await?
+            .state;
+
+        self.write(RegisterAddress::AlControl)
+            .send(
+                self.client,
+                AlControl {
+                    id_request: true,
+                    ..AlControl::new(state)
+                },
+            )
+            .await?;
 
-                    Err(Error::SubDevice(code))
+        let status = async {
+            loop {
+                let status = self
+                    .read(RegisterAddress::AlStatus)
+                    .ignore_wkc()
+                    .receive::<AlControl>(self.client)
+                    .await?;
+
+                if status.id_request || status.error {
+                    break Ok(status);
                 }
-                e => Err(e),
-            },
+
+                self.client.timeouts.loop_tick().await;
+            }
         }
-    }
+        .timeout(self.client.timeouts.id_request)
+        .await;
+
+        let (id, error) = match status {
+            Ok(AlControl {
+                id_request: true,
+                error: false,
+                ..
+            }) => {
+                // The ID is returned in place of the AL status code
+                let id = self
+                    .read(RegisterAddress::AlStatusCode)
+                    .receive::<u16>(self.client)
+                    .await?;
 
-    /// Get the EtherCAT state machine state of the sub device.
-    pub async fn status(&self) -> Result<(SlaveState, AlStatusCode), Error> {
-        let code = self
-            .read(RegisterAddress::AlStatusCode)
-            .receive::<AlStatusCode>(self.client);
+                (Some(id), false)
+            }
+            Ok(status) => (None, status.error),
+            Err(Error::Timeout) => (None, false),
+            Err(e) => return Err(e),
+        };
 
-        futures_lite::future::try_zip(self.state(), code).await
+        // Clear the request, acknowledging any error caused by it
+        self.write(RegisterAddress::AlControl)
+            .send(
+                self.client,
+                AlControl {
+                    error,
+                    ..AlControl::new(state)
+                },
+            )
+            .await?;
+
+        Ok(id)
     }
 
-    fn eeprom(&self) -> SlaveEeprom<DeviceEeprom> {
+    /// Get the SubDevice's SII EEPROM, to read its categories as structured data.
+    ///
+    /// See the [`sii`](crate::sii) module for details.
+    pub fn eeprom(&self) -> SlaveEeprom<DeviceEeprom<'_>> {
         SlaveEeprom::new(DeviceEeprom::new(self.client, self.configured_address))
     }
 
@@ -809,8 +2392,16 @@ impl<'a, S> SlaveRef<'a, S> {
             .await
     }
 
-    pub(crate) async fn wait_for_state(&self, desired_state: SlaveState) -> Result<(), Error> {
-        async {
+    /// Wait for the SubDevice to go from `current_state` to `desired_state`.
+    ///
+    /// `current_state` is only used to choose the timeout, and may be [`SlaveState::None`] if it
+    /// isn't known.
+    pub(crate) async fn wait_for_state(
+        &self,
+        current_state: SlaveState,
+        desired_state: SlaveState,
+    ) -> Result<(), Error> {
+        let result = async {
             loop {
                 let status = self
                     .read(RegisterAddress::AlStatus)
@@ -825,8 +2416,20 @@ impl<'a, S> SlaveRef<'a, S> {
                 self.client.timeouts.loop_tick().await;
             }
         }
-        .timeout(self.client.timeouts.state_transition)
-        .await
+        .timeout(
+            self.client
+                .timeouts
+                .transition(current_state, desired_state),
+        )
+        .await;
+
+        match result {
+            Err(Error::Timeout) => Err(self
+                .client
+                .transition_timeout(core::iter::once(self.configured_address), desired_state)
+                .await),
+            result => result,
+        }
     }
 
     pub(crate) fn write(&self, register: impl Into<u16>) -> WrappedWrite {
@@ -872,10 +2475,14 @@ impl<'a, S> SlaveRef<'a, S> {
         Ok(())
     }
 
-    pub(crate) async fn request_slave_state(&self, desired_state: SlaveState) -> Result<(), Error> {
+    pub(crate) async fn request_slave_state(
+        &self,
+        current_state: SlaveState,
+        desired_state: SlaveState,
+    ) -> Result<(), Error> {
         self.request_slave_state_nowait(desired_state).await?;
 
-        self.wait_for_state(desired_state).await
+        self.wait_for_state(current_state, desired_state).await
     }
 
     pub(crate) async fn set_eeprom_mode(&self, mode: SiiOwner) -> Result<(), Error> {
@@ -892,3 +2499,389 @@ impl<'a, S> SlaveRef<'a, S> {
         Ok(())
     }
 }
+
+/// Get the number of WORDs covering `len_bytes`, checking that they fit in the EEPROM address
+/// space after `start_word`.
+fn eeprom_len_words(start_word: u32, len_bytes: usize) -> Result<u32, Error> {
+    // Byte addresses must fit in a `u32`
+    u32::try_from(len_bytes.div_ceil(2))
+        .ok()
+        .filter(|len| {
+            start_word
+                .checked_add(*len)
+                .is_some_and(|end| end < 0x8000_0000)
+        })
+        .ok_or(Error::Eeprom(EepromError::SectionOverrun))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mock::*, *};
+    use crate::{eeprom::types::MailboxProtocols, mailbox::MailboxType, ClientConfig, Timeouts};
+
+    #[tokio::test]
+    async fn segmented_sdo_download() {
+        let (client, mock, server) = coe_subdevice(
+            Timeouts::default(),
+            ClientConfig::default(),
+            SdoServer::default(),
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::COE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        // Longer than the 128 byte mailbox, so needs multiple segments.
+        let data = (0..=255u8).cycle().take(300).collect::<Vec<_>>();
+
+        assert_eq!(slave.sdo_write_slice(0x2000, 1, &data).await, Ok(()));
+
+        assert_eq!(
+            server.lock().unwrap().objects.get(&(0x2000, 1)),
+            Some(&data),
+            "downloaded data"
+        );
+
+        // Initiate request, then one full segment and a shorter last segment
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn concurrent_sdo_reads_take_turns() {
+        let (client, mock, _server) = coe_subdevice(
+            Timeouts::default(),
+            ClientConfig::default(),
+            SdoServer::new([
+                ((0x1018, 1), 0x0000_0002u32.to_le_bytes().to_vec()),
+                ((0x1018, 2), 0x1234_5678u32.to_le_bytes().to_vec()),
+            ]),
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::COE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let (vendor, product) = futures_lite::future::zip(
+            slave.sdo_read::<u32>(0x1018, 1),
+            slave.sdo_read::<u32>(0x1018, 2),
+        )
+        .await;
+
+        assert_eq!(vendor, Ok(0x0000_0002));
+        assert_eq!(product, Ok(0x1234_5678));
+        assert_eq!(mock.requests().len(), 2);
+        assert!(
+            !mock.overlapped(),
+            "request sent before the previous response was read"
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_sdo_writes() {
+        let mut server = SdoServer::default();
+
+        let (client, mock) = MockSubDevice::start(
+            Timeouts::default(),
+            ClientConfig {
+                verify_sdo_writes: true,
+                ..ClientConfig::default()
+            },
+            move |_mailbox_type, request| {
+                let response = server.handle(request);
+
+                // This object ignores writes
+                server.objects.insert((0x2000, 2), vec![0xaa; 2]);
+
+                vec![(MailboxType::Coe, response)]
+            },
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::COE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        assert_eq!(slave.sdo_write(0x2000, 1, 0x1234u16).await, Ok(()));
+
+        // Read back with a segmented upload
+        let data = (0..=255u8).cycle().take(300).collect::<Vec<_>>();
+
+        assert_eq!(slave.sdo_write_slice(0x2000, 3, &data).await, Ok(()));
+
+        assert_eq!(
+            slave.sdo_write(0x2000, 2, 0x1234u16).await,
+            Err(Error::Mailbox(MailboxError::VerifyFailed {
+                address: 0x2000,
+                sub_index: 2
+            }))
+        );
+
+        // Each write is followed by an upload
+        assert_eq!(
+            mock.requests()
+                .iter()
+                .filter(|(_, request)| request[2] >> 5 == 2)
+                .count(),
+            3
+        );
+    }
+
+    /// Short timeouts so lost mailbox reads are noticed quickly.
+    fn repeat_timeouts() -> Timeouts {
+        Timeouts {
+            pdu: Duration::from_millis(10),
+            mailbox_response: Duration::from_millis(50),
+            ..Timeouts::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn repeat_lost_response() {
+        let (client, mock, _server) = coe_subdevice(
+            repeat_timeouts(),
+            ClientConfig {
+                mailbox_repeat_retries: 2,
+                ..ClientConfig::default()
+            },
+            SdoServer::new([((0x1018, 1), 0x0000_0002u32.to_le_bytes().to_vec())]),
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::COE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        mock.lose_reads(2);
+
+        assert_eq!(slave.sdo_read::<u32>(0x1018, 1).await, Ok(0x0000_0002));
+
+        // The repeat bit is toggled once per lost response, and the request is only sent once.
+        assert_eq!(mock.repeats(), 2);
+        assert_eq!(mock.requests().len(), 1);
+
+        // The repeat bit is left toggled, so the next repeat request toggles it back.
+        mock.lose_reads(1);
+
+        assert_eq!(slave.sdo_read::<u32>(0x1018, 1).await, Ok(0x0000_0002));
+        assert_eq!(mock.repeats(), 3);
+    }
+
+    #[tokio::test]
+    async fn repeat_retries_exhausted() {
+        let (client, mock, _server) = coe_subdevice(
+            repeat_timeouts(),
+            ClientConfig {
+                mailbox_repeat_retries: 2,
+                ..ClientConfig::default()
+            },
+            SdoServer::new([((0x1018, 1), 0x0000_0002u32.to_le_bytes().to_vec())]),
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::COE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        mock.lose_reads(3);
+
+        assert_eq!(slave.sdo_read::<u32>(0x1018, 1).await, Err(Error::Timeout));
+        assert_eq!(mock.repeats(), 2);
+    }
+
+    fn eeprom_subdevice(image: Vec<u8>) -> (Client<'static>, MockSubDevice) {
+        let (client, mock) = MockSubDevice::start(
+            Timeouts::default(),
+            ClientConfig::default(),
+            |_mailbox_type, _request| Vec::new(),
+        );
+
+        mock.set_eeprom(image);
+
+        (client, mock)
+    }
+
+    #[tokio::test]
+    async fn eeprom_write() {
+        let (client, mock) = eeprom_subdevice(vec![0xff; 32]);
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        // Odd length keeps the high byte of the last WORD
+        assert_eq!(slave.eeprom_write(2, &[1, 2, 3, 4, 5]).await, Ok(()));
+
+        let mut buf = [0u8; 8];
+
+        assert_eq!(slave.eeprom_read(1, &mut buf).await, Ok(()));
+        assert_eq!(buf, [0xff, 0xff, 1, 2, 3, 4, 5, 0xff]);
+        assert_eq!(mock.eeprom()[4..9], [1, 2, 3, 4, 5]);
+
+        assert_eq!(
+            slave.eeprom_write(0x7fff_ffff, &[1, 2]).await,
+            Err(Error::Eeprom(EepromError::SectionOverrun))
+        );
+
+        mock.write_protect_eeprom();
+
+        assert_eq!(
+            slave.eeprom_write(0, &[1, 2]).await,
+            Err(Error::Eeprom(EepromError::Write))
+        );
+        assert_eq!(mock.eeprom()[0..2], [0xff, 0xff]);
+    }
+
+    #[tokio::test]
+    async fn eeprom_extended_address() {
+        // 1 Mbit plus 2 WORDs
+        let mut image = vec![0xff; 0x2_0004];
+
+        image[0x2_0000..].copy_from_slice(&[1, 2, 3, 4]);
+
+        let (client, mock) = eeprom_subdevice(image);
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let mut buf = [0u8; 4];
+
+        assert_eq!(slave.eeprom_read(0x1_0000, &mut buf).await, Ok(()));
+        assert_eq!(buf, [1, 2, 3, 4]);
+
+        assert_eq!(slave.eeprom_write(0x1_0001, &[5, 6]).await, Ok(()));
+        assert_eq!(mock.eeprom()[0x2_0000..], [1, 2, 5, 6]);
+
+        // 16 kbit EEPROM only supports 1 address byte
+        mock.set_eeprom(vec![0xff; 2048]);
+
+        assert_eq!(
+            slave.eeprom_read(0x1_0000, &mut buf).await,
+            Err(Error::Eeprom(EepromError::SectionOverrun))
+        );
+    }
+
+    #[tokio::test]
+    async fn release_eeprom_to_pdi() {
+        let (client, mock) = eeprom_subdevice(vec![0xff; 32]);
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        // Not offered yet
+        assert!(!mock.pdi_take_eeprom());
+        assert_eq!(slave.pdi_has_eeprom().await, Ok(false));
+
+        assert_eq!(slave.release_eeprom_to_pdi().await, Ok(()));
+        assert!(mock.pdi_take_eeprom());
+        assert_eq!(slave.pdi_has_eeprom().await, Ok(true));
+
+        assert_eq!(slave.reclaim_eeprom().await, Ok(()));
+        assert_eq!(slave.pdi_has_eeprom().await, Ok(false));
+
+        // EEPROM access takes control back
+        assert_eq!(slave.release_eeprom_to_pdi().await, Ok(()));
+        assert!(mock.pdi_take_eeprom());
+        assert_eq!(slave.eeprom_read(0, &mut [0u8; 2]).await, Ok(()));
+        assert_eq!(slave.pdi_has_eeprom().await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn write_config_area() {
+        // EL2004 configuration area with a corrupt checksum
+        let mut image = vec![
+            0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, //
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x12, 0x00, //
+        ];
+
+        image.resize(64, 0xff);
+
+        let (client, mock) = eeprom_subdevice(image.clone());
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        assert_eq!(
+            slave.eeprom().config_area().await,
+            Err(Error::Eeprom(EepromError::Checksum))
+        );
+
+        let config: [u8; 14] = image[0..14].try_into().unwrap();
+
+        assert_eq!(slave.write_config_area(&config).await, Ok(()));
+
+        image[14] = 0xd8;
+
+        assert_eq!(mock.eeprom(), image);
+        assert_eq!(slave.eeprom().config_area().await.map(|c| c[14]), Ok(0xd8));
+    }
+
+    /// An EEPROM with only a General category holding `flags`.
+    fn general_eeprom(flags: GeneralFlags) -> Vec<u8> {
+        let mut image = vec![0u8; 0x80];
+
+        // General category, 16 WORDs long
+        image.extend([0x1e, 0x00, 0x10, 0x00]);
+        image.extend([0u8; 32]);
+        image[0x80 + 4 + 11] = flags.bits();
+
+        // End marker
+        image.extend([0xff, 0xff]);
+
+        image
+    }
+
+    #[tokio::test]
+    async fn explicit_device_id() {
+        let (client, mock) = eeprom_subdevice(general_eeprom(GeneralFlags::empty()));
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        mock.set_device_id(0x1234);
+
+        // Not requested if the EEPROM doesn't declare support
+        assert_eq!(slave.explicit_device_id().await, Ok(None));
+
+        mock.set_eeprom(general_eeprom(GeneralFlags::IDENT_AL_STATUS));
+
+        assert_eq!(slave.explicit_device_id().await, Ok(Some(0x1234)));
+    }
+
+    #[tokio::test]
+    async fn explicit_device_id_timeout() {
+        let (client, mock) = MockSubDevice::start(
+            Timeouts {
+                id_request: Duration::from_millis(10),
+                ..Timeouts::default()
+            },
+            ClientConfig::default(),
+            |_mailbox_type, _request| Vec::new(),
+        );
+
+        mock.set_eeprom(general_eeprom(GeneralFlags::IDENT_AL_STATUS));
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        // Declares support but never answers
+        assert_eq!(slave.explicit_device_id().await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn set_station_alias() {
+        // EL2004 configuration area
+        let mut image = vec![
+            0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x00, //
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xd8, 0x00, //
+        ];
+
+        image.resize(64, 0xff);
+
+        let (client, mock) = eeprom_subdevice(image);
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        assert_eq!(slave.station_alias().await, Ok(0));
+        assert_eq!(slave.set_station_alias(0x1234).await, Ok(()));
+        assert_eq!(slave.station_alias().await, Ok(0x1234));
+
+        let image = mock.eeprom();
+
+        assert_eq!(image[8..10], [0x34, 0x12]);
+        assert_eq!(image[14], config_checksum(&image[0..14]));
+        assert_ne!(image[14], 0xd8);
+        assert_eq!(image[15], 0x00);
+    }
+}
@@ -0,0 +1,266 @@
+use super::pdi::{read_at, write_at};
+use crate::error::Error;
+use core::marker::PhantomData;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite, WireError};
+
+/// The order an oversampling SubDevice stores the samples of a single cycle in its process data.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SampleOrder {
+    /// The first sample in the PDI was taken first (default).
+    #[default]
+    OldestFirst,
+    /// The first sample in the PDI was taken last.
+    NewestFirst,
+}
+
+/// The samples read from one cycle of an [`OversampledChannel`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Oversampled<T, const N: usize> {
+    /// The SubDevice's cycle counter, if the channel has one.
+    pub cycle_counter: Option<u16>,
+
+    /// The number of cycles whose samples were not read, counted from the cycle counter.
+    ///
+    /// This is always zero for the first read, or if the channel has no cycle counter.
+    pub missed_cycles: u16,
+
+    /// Whether the cycle counter hasn't changed since the last read, meaning these samples were
+    /// already returned.
+    pub repeated: bool,
+
+    /// The samples in the order they were taken, oldest first.
+    pub samples: [T; N],
+}
+
+/// One channel of an oversampling SubDevice, e.g. an EL3702 analog input or EL4732 analog output,
+/// that transfers `N` samples of type `T` every process data cycle.
+///
+/// The samples are stored one after the other in the SubDevice's inputs or outputs, starting at a
+/// byte offset that depends on its PDO assignment. Many oversampling SubDevices also map a 16 bit
+/// cycle counter that is used to detect missed cycles.
+///
+/// The SubDevice must be configured to take `N` samples per cycle, both by assigning `N` sample
+/// PDOs and by setting SYNC0 to `N` times the cycle rate with
+/// [`SlaveRef::set_oversampling`](crate::SlaveRef::set_oversampling).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{error::Error, slave_group::Op, Client, OversampledChannel, SlaveGroup};
+/// # async fn cycle(client: &Client<'_>, group: SlaveGroup<16, 64, Op>) -> Result<(), Error> {
+/// // An EL3702 with 10 samples per cycle. The cycle counter is the first 2 bytes of its inputs
+/// // and is followed by the samples of the first channel.
+/// let mut channel = OversampledChannel::<i16, 10>::new(2).with_cycle_counter(0);
+///
+/// loop {
+///     group.tx_rx(client).await?;
+///
+///     let el3702 = group.slave(client, 1)?;
+///
+///     let samples = channel.read(el3702.inputs_raw())?;
+///
+///     if samples.missed_cycles > 0 {
+///         log::warn!("Missed {} cycles of samples", samples.missed_cycles);
+///     }
+///
+///     for sample in samples.samples {
+///         // ...
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OversampledChannel<T, const N: usize> {
+    samples_offset: usize,
+    counter_offset: Option<usize>,
+    order: SampleOrder,
+    /// The cycle counter from the last read, or the last counter value written.
+    counter: Option<u16>,
+    _sample: PhantomData<T>,
+}
+
+impl<T, const N: usize> OversampledChannel<T, N> {
+    /// Create a channel whose first sample is `samples_offset` bytes into the SubDevice's inputs
+    /// or outputs.
+    pub const fn new(samples_offset: usize) -> Self {
+        Self {
+            samples_offset,
+            counter_offset: None,
+            order: SampleOrder::OldestFirst,
+            counter: None,
+            _sample: PhantomData,
+        }
+    }
+
+    /// Use the 16 bit cycle counter `offset` bytes into the SubDevice's inputs or outputs.
+    pub const fn with_cycle_counter(self, offset: usize) -> Self {
+        Self {
+            counter_offset: Some(offset),
+            ..self
+        }
+    }
+
+    /// Set the order the SubDevice stores samples in.
+    pub const fn with_order(self, order: SampleOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    /// The position in the PDI of the sample taken at index `index` of the cycle.
+    fn sample_position(&self, index: usize) -> usize {
+        match self.order {
+            SampleOrder::OldestFirst => index,
+            SampleOrder::NewestFirst => N - 1 - index,
+        }
+    }
+}
+
+impl<T, const N: usize> OversampledChannel<T, N>
+where
+    T: EtherCrabWireRead + EtherCrabWireSized + Copy + Default,
+{
+    /// Read this cycle's samples from an input channel, e.g. from
+    /// [`SlaveRef::inputs_raw`](crate::SlaveRef::inputs_raw).
+    ///
+    /// Samples are returned oldest first, whatever order the SubDevice stores them in. Returns an
+    /// error if the samples or cycle counter lie past the end of `inputs`.
+    pub fn read(&mut self, inputs: &[u8]) -> Result<Oversampled<T, N>, Error> {
+        let cycle_counter = self
+            .counter_offset
+            .map(|offset| read_at::<u16>(inputs, offset))
+            .transpose()?;
+
+        let mut samples = [T::default(); N];
+
+        for (index, sample) in samples.iter_mut().enumerate() {
+            *sample = read_at(
+                inputs,
+                self.samples_offset + self.sample_position(index) * T::PACKED_LEN,
+            )?;
+        }
+
+        let (missed_cycles, repeated) = match (self.counter, cycle_counter) {
+            (Some(previous), Some(current)) if previous == current => (0, true),
+            (Some(previous), Some(current)) => {
+                (current.wrapping_sub(previous).wrapping_sub(1), false)
+            }
+            _ => (0, false),
+        };
+
+        self.counter = cycle_counter;
+
+        Ok(Oversampled {
+            cycle_counter,
+            missed_cycles,
+            repeated,
+            samples,
+        })
+    }
+}
+
+impl<T, const N: usize> OversampledChannel<T, N>
+where
+    T: EtherCrabWireWrite + EtherCrabWireSized + Copy,
+{
+    /// Write this cycle's samples, oldest first, to an output channel, e.g. to
+    /// [`SlaveRef::outputs_raw_mut`](crate::SlaveRef::outputs_raw_mut).
+    ///
+    /// If the channel has a cycle counter, it is incremented each time samples are written.
+    /// Nothing is written if the samples or cycle counter would lie past the end of `outputs`.
+    pub fn write(&mut self, outputs: &mut [u8], samples: &[T; N]) -> Result<(), Error> {
+        let samples_end = self.samples_offset + N * T::PACKED_LEN;
+
+        let end = self
+            .counter_offset
+            .map_or(samples_end, |offset| samples_end.max(offset + 2));
+
+        if end > outputs.len() {
+            return Err(WireError::WriteBufferTooShort {
+                expected: end,
+                got: outputs.len(),
+            }
+            .into());
+        }
+
+        for (index, sample) in samples.iter().enumerate() {
+            write_at(
+                outputs,
+                self.samples_offset + self.sample_position(index) * T::PACKED_LEN,
+                *sample,
+            )?;
+        }
+
+        if let Some(offset) = self.counter_offset {
+            let counter = self.counter.map_or(0, |counter| counter.wrapping_add(1));
+
+            write_at(outputs, offset, counter)?;
+
+            self.counter = Some(counter);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_newest_first() {
+        let mut channel = OversampledChannel::<i16, 3>::new(2)
+            .with_cycle_counter(0)
+            .with_order(SampleOrder::NewestFirst);
+
+        let inputs = [0xfe, 0xff, 0x03, 0x00, 0x02, 0x00, 0x01, 0x00];
+
+        assert_eq!(
+            channel.read(&inputs),
+            Ok(Oversampled {
+                cycle_counter: Some(0xfffe),
+                missed_cycles: 0,
+                repeated: false,
+                samples: [1, 2, 3]
+            })
+        );
+
+        let same = channel.read(&inputs).unwrap();
+
+        assert!(same.repeated);
+        assert_eq!(same.missed_cycles, 0);
+
+        // Counter wraps from 0xfffe to 0x0001, skipping two cycles
+        let next = channel
+            .read(&[0x01, 0x00, 0x06, 0x00, 0x05, 0x00, 0x04, 0x00])
+            .unwrap();
+
+        assert_eq!(next.missed_cycles, 2);
+        assert_eq!(next.samples, [4, 5, 6]);
+
+        assert!(channel.read(&inputs[0..7]).is_err());
+    }
+
+    #[test]
+    fn write_with_counter() {
+        let mut channel = OversampledChannel::<u16, 2>::new(0).with_cycle_counter(4);
+
+        let mut outputs = [0u8; 6];
+
+        channel.write(&mut outputs, &[0x1234, 0x5678]).unwrap();
+        assert_eq!(outputs, [0x34, 0x12, 0x78, 0x56, 0x00, 0x00]);
+
+        channel.write(&mut outputs, &[0x0001, 0x0002]).unwrap();
+        assert_eq!(outputs, [0x01, 0x00, 0x02, 0x00, 0x01, 0x00]);
+
+        let mut short = [0u8; 5];
+
+        assert_eq!(
+            channel.write(&mut short, &[0xffff, 0xffff]),
+            Err(Error::Wire(WireError::WriteBufferTooShort {
+                expected: 6,
+                got: 5
+            }))
+        );
+        assert_eq!(short, [0u8; 5], "nothing is written");
+    }
+}
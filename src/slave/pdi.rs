@@ -1,6 +1,59 @@
-use super::{Slave, SlaveRef};
+use super::{pdo_entries::EntryLocation, Slave, SlaveRef};
+use crate::error::Error;
 use atomic_refcell::AtomicRefMut;
 use core::ops::Deref;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite, WireError};
+
+/// A typed view of a slave's inputs and outputs in the Process Data Image (PDI).
+///
+/// This trait should be derived with [`EtherCatPdi`](macro@crate::EtherCatPdi), which checks at
+/// compile time that all fields fit in the declared input and output lengths. Use
+/// [`SlaveRef::read_inputs`] and [`SlaveRef::write_outputs`] to exchange it with a slave's PDI.
+///
+/// # Examples
+///
+/// ```rust
+/// use ethercrab::EtherCatPdi;
+///
+/// #[derive(Debug, Default, EtherCatPdi)]
+/// #[pdi(inputs_bytes = 2, outputs_bytes = 1)]
+/// struct Io {
+///     #[pdi(input)]
+///     #[wire(bytes = 2)]
+///     position: i16,
+///
+///     #[pdi(output)]
+///     #[wire(bits = 1, post_skip = 7)]
+///     enable: bool,
+/// }
+///
+/// let mut io = Io::default();
+///
+/// io.unpack_inputs(&[0x34, 0x12]).expect("Unpack");
+///
+/// assert_eq!(io.position, 0x1234);
+///
+/// io.enable = true;
+///
+/// let mut outputs = [0u8; Io::OUTPUTS_LEN];
+///
+/// io.pack_outputs(&mut outputs).expect("Pack");
+///
+/// assert_eq!(outputs, [0b0000_0001]);
+/// ```
+pub trait EtherCatPdi {
+    /// The length of the slave's inputs in bytes.
+    const INPUTS_LEN: usize;
+
+    /// The length of the slave's outputs in bytes.
+    const OUTPUTS_LEN: usize;
+
+    /// Update all input fields from the slave's raw input data.
+    fn unpack_inputs(&mut self, inputs: &[u8]) -> Result<(), WireError>;
+
+    /// Pack all output fields into the slave's raw output data.
+    fn pack_outputs(&self, outputs: &mut [u8]) -> Result<(), WireError>;
+}
 
 /// Process Data Image (PDI) segments for a given slave device.
 ///
@@ -12,6 +65,9 @@ pub struct SlavePdi<'group> {
     inputs: &'group [u8],
 
     outputs: &'group mut [u8],
+
+    /// The locations of the PDO entries mapped by every SubDevice in the group.
+    entries: &'group [EntryLocation],
 }
 
 impl<'group> Deref for SlavePdi<'group> {
@@ -27,13 +83,19 @@ impl<'group> SlavePdi<'group> {
         slave: AtomicRefMut<'group, Slave>,
         inputs: &'group [u8],
         outputs: &'group mut [u8],
+        entries: &'group [EntryLocation],
     ) -> Self {
         Self {
             slave,
             inputs,
             outputs,
+            entries,
         }
     }
+
+    pub(crate) fn entries(&self) -> &[EntryLocation] {
+        self.entries
+    }
 }
 
 /// Methods used when a slave device is part of a group and part of the PDI has been mapped to it.
@@ -121,4 +183,123 @@ impl<'a, 'group> SlaveRef<'a, SlavePdi<'group>> {
     pub fn outputs_raw_mut(&mut self) -> &mut [u8] {
         self.state.outputs
     }
+
+    /// Decode a value from this slave's inputs, starting `offset` bytes in.
+    ///
+    /// Values are decoded from the little-endian wire format used by EtherCAT. Returns an error if
+    /// the value would read past the end of the inputs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{error::Error, slave_group::Op, Client, SlaveGroup};
+    /// # fn io(client: &Client<'_>, group: SlaveGroup<16, 64, Op>) -> Result<(), Error> {
+    /// let mut slave = group.slave(client, 0)?;
+    ///
+    /// let position = slave.read_at::<i32>(2)?;
+    ///
+    /// slave.write_at::<u16>(0, 0x000f)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_at<T>(&self, offset: usize) -> Result<T, Error>
+    where
+        T: EtherCrabWireRead + EtherCrabWireSized,
+    {
+        read_at(self.state.inputs, offset)
+    }
+
+    /// Encode a value into this slave's outputs, starting `offset` bytes in.
+    ///
+    /// Values are encoded in the little-endian wire format used by EtherCAT. Returns an error and
+    /// leaves the outputs unchanged if the value would write past the end of the outputs.
+    pub fn write_at<T>(&mut self, offset: usize, value: T) -> Result<(), Error>
+    where
+        T: EtherCrabWireWrite,
+    {
+        write_at(self.state.outputs, offset, value)
+    }
+
+    /// Update the input fields of a typed process data image from this slave's inputs.
+    ///
+    /// Returns an error if the slave has fewer input bytes than
+    /// [`EtherCatPdi::INPUTS_LEN`].
+    pub fn read_inputs<T: EtherCatPdi>(&self, image: &mut T) -> Result<(), Error> {
+        image.unpack_inputs(self.state.inputs).map_err(Error::from)
+    }
+
+    /// Write the output fields of a typed process data image into this slave's outputs.
+    ///
+    /// Returns an error if the slave has fewer output bytes than
+    /// [`EtherCatPdi::OUTPUTS_LEN`].
+    pub fn write_outputs<T: EtherCatPdi>(&mut self, image: &T) -> Result<(), Error> {
+        image.pack_outputs(self.state.outputs).map_err(Error::from)
+    }
+}
+
+pub(super) fn read_at<T>(data: &[u8], offset: usize) -> Result<T, Error>
+where
+    T: EtherCrabWireRead + EtherCrabWireSized,
+{
+    let end = offset.saturating_add(T::PACKED_LEN);
+
+    let buf = data.get(offset..end).ok_or(WireError::ReadBufferTooShort {
+        expected: end,
+        got: data.len(),
+    })?;
+
+    T::unpack_from_slice(buf).map_err(Error::from)
+}
+
+pub(super) fn write_at<T>(data: &mut [u8], offset: usize, value: T) -> Result<(), Error>
+where
+    T: EtherCrabWireWrite,
+{
+    let end = offset.saturating_add(value.packed_len());
+    let len = data.len();
+
+    let buf = data
+        .get_mut(offset..end)
+        .ok_or(WireError::WriteBufferTooShort {
+            expected: end,
+            got: len,
+        })?;
+
+    value.pack_to_slice_unchecked(buf);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_offsets() {
+        let inputs = [0x00, 0x78, 0x56, 0x34, 0x12];
+
+        assert_eq!(read_at::<u32>(&inputs, 1), Ok(0x1234_5678));
+        assert_eq!(read_at::<i16>(&inputs, 3), Ok(0x1234));
+        assert_eq!(
+            read_at::<u32>(&inputs, 2),
+            Err(Error::Wire(WireError::ReadBufferTooShort {
+                expected: 6,
+                got: 5
+            }))
+        );
+
+        let mut outputs = [0u8; 3];
+
+        write_at(&mut outputs, 1, -2i16).unwrap();
+        assert_eq!(outputs, [0x00, 0xfe, 0xff]);
+
+        assert_eq!(
+            write_at(&mut outputs, 2, 0xaaaau16),
+            Err(Error::Wire(WireError::WriteBufferTooShort {
+                expected: 4,
+                got: 3
+            }))
+        );
+        assert_eq!(outputs, [0x00, 0xfe, 0xff], "outputs are unchanged");
+    }
 }
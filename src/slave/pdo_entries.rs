@@ -0,0 +1,709 @@
+use super::{
+    configuration::{PdiAlignment, PdoDirection, SyncManagerMappings},
+    pdi::SlavePdi,
+    Slave, SlaveRef,
+};
+use crate::{
+    coe::PdoMapping,
+    eeprom::types::Pdo,
+    error::{Error, Item},
+    fmt,
+};
+use core::ops::Deref;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireWrite, WireError};
+
+/// The most PDO entries a SubDevice can map in one direction.
+const MAX_PDO_ENTRIES: usize = 64;
+
+/// The most PDO entry locations recorded for all SubDevices in a group.
+///
+/// Locations are stored once per group instead of in every [`Slave`] to keep SubDevices small.
+pub(crate) const MAX_GROUP_PDO_ENTRIES: usize = 128;
+
+/// PDO entries in one direction with the index of the sync manager they are assigned to, in PDI
+/// order.
+pub(in crate::slave) type RawEntries = heapless::Vec<(u8, PdoMapping), MAX_PDO_ENTRIES>;
+
+/// The position of a mapped PDO entry in a SubDevice's process data, recorded when its PDI is
+/// configured.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct EntryLocation {
+    configured_address: u16,
+    direction: PdoDirection,
+    index: u16,
+    sub_index: u8,
+    bit_len: u8,
+    byte_offset: u16,
+    bit_offset: u8,
+}
+
+/// The locations of the PDO entries mapped in both directions by every SubDevice in a group.
+pub(crate) type EntryLocations = heapless::Vec<EntryLocation, MAX_GROUP_PDO_ENTRIES>;
+
+/// A PDO entry mapped into a SubDevice's process data, returned by [`SlaveRef::pdi_entries`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdiEntry {
+    /// Whether the entry is one of the SubDevice's inputs or outputs.
+    pub direction: PdoDirection,
+
+    /// Object index.
+    pub index: u16,
+
+    /// Object sub-index.
+    pub sub_index: u8,
+
+    /// Length of the entry in bits.
+    pub bit_len: u8,
+
+    /// Offset in bytes from the start of the SubDevice's inputs or outputs, as returned by e.g.
+    /// [`SlaveRef::io_raw`].
+    pub byte_offset: usize,
+
+    /// The bit in the first byte at which the entry starts, from 0 to 7.
+    pub bit_offset: u8,
+
+    /// The name of the entry from the SubDevice's EEPROM, or an empty string if it has none.
+    pub name: heapless::String<64>,
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Get every PDO entry mapped into this SubDevice's inputs and outputs, with its position in
+    /// the SubDevice's process data.
+    ///
+    /// This can be used to find values by name or object index instead of hard coding their
+    /// offsets. Padding entries with an object index of zero are not included.
+    ///
+    /// Entries are read from the SubDevice's CoE PDO assignment if it supports CoE, otherwise from
+    /// its EEPROM, the same way the PDI is configured. Names are always read from the EEPROM. This
+    /// method should be called once the group's PDI has been configured, e.g. in SAFE-OP.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, SlaveRef, Slave };
+    /// # async fn find(slave: SlaveRef<'_, &Slave>) -> Result<(), Error> {
+    /// let entries = slave.pdi_entries::<32>().await?;
+    ///
+    /// if let Some(position) = entries
+    ///     .iter()
+    ///     .find(|entry| entry.name == "Position actual value")
+    /// {
+    ///     log::info!(
+    ///         "Position is {} bits at byte {}",
+    ///         position.bit_len,
+    ///         position.byte_offset
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn pdi_entries<const N: usize>(&self) -> Result<heapless::Vec<PdiEntry, N>, Error> {
+        let mut entries = heapless::Vec::new();
+
+        for direction in [PdoDirection::MasterRead, PdoDirection::MasterWrite] {
+            let eeprom_pdos = match direction {
+                PdoDirection::MasterRead => self.eeprom().master_read_pdos().await?,
+                PdoDirection::MasterWrite => self.eeprom().master_write_pdos().await?,
+            };
+
+            let raw = self.raw_pdo_entries(direction, &eeprom_pdos).await?;
+
+            let alignment = self.state.config.layout.alignment;
+
+            for (mapping, byte_offset, bit_offset) in entry_offsets(&raw, alignment) {
+                if mapping.index == 0 {
+                    continue;
+                }
+
+                let name_string_idx = eeprom_pdos
+                    .iter()
+                    .flat_map(|pdo| pdo.entries.iter())
+                    .find(|entry| {
+                        entry.index == mapping.index && entry.sub_index == mapping.sub_index
+                    })
+                    .map_or(0, |entry| entry.name_string_idx);
+
+                let name = self
+                    .eeprom()
+                    .find_string(name_string_idx)
+                    .await?
+                    .unwrap_or_default();
+
+                entries
+                    .push(PdiEntry {
+                        direction,
+                        index: mapping.index,
+                        sub_index: mapping.sub_index,
+                        bit_len: mapping.bit_len,
+                        byte_offset,
+                        bit_offset,
+                        name,
+                    })
+                    .map_err(|_| Error::Capacity(Item::PdoEntry))?;
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Read the PDO entries mapped in one direction from CoE, falling back to the EEPROM.
+    async fn raw_pdo_entries(
+        &self,
+        direction: PdoDirection,
+        eeprom_pdos: &[Pdo],
+    ) -> Result<RawEntries, Error> {
+        let mut raw = RawEntries::new();
+
+        if self.state.config.mailbox.has_coe {
+            let layout = self
+                .coe_pdo_layout(direction, |sync_manager, mapping| {
+                    raw.push((sync_manager, mapping))
+                        .map_err(|_| Error::Capacity(Item::PdoEntry))
+                })
+                .await?;
+
+            if layout.is_some() {
+                return Ok(raw);
+            }
+
+            // Entries may have been read before CoE failed
+            raw.clear();
+        }
+
+        let (sm_type, _fmmu_type) = direction.filter_terms();
+
+        let sync_managers = self.eeprom().sync_managers().await?;
+
+        for (sync_manager_index, _sync_manager) in sync_managers
+            .iter()
+            .enumerate()
+            .filter(|(_idx, sm)| sm.usage_type == sm_type)
+        {
+            let sync_manager_index = sync_manager_index as u8;
+
+            for entry in eeprom_pdos
+                .iter()
+                .filter(|pdo| pdo.sync_manager == sync_manager_index)
+                .flat_map(|pdo| pdo.entries.iter())
+            {
+                raw.push((
+                    sync_manager_index,
+                    PdoMapping::new(entry.index, entry.sub_index, entry.data_length_bits),
+                ))
+                .map_err(|_| Error::Capacity(Item::PdoEntry))?;
+            }
+        }
+
+        fmt::trace!(
+            "Slave {:#06x} {:?} PDO entries {:?}",
+            self.configured_address,
+            direction,
+            raw
+        );
+
+        Ok(raw)
+    }
+}
+
+/// Methods used to access PDO entries by object index once the SubDevice's PDI is configured.
+impl<'a, 'group> SlaveRef<'a, SlavePdi<'group>> {
+    /// Get a PDO entry mapped into this SubDevice's inputs or outputs by its object index and
+    /// sub-index.
+    ///
+    /// Entry positions are recorded when the group's PDI is configured, so no mailbox traffic is
+    /// needed. Inputs are searched before outputs.
+    ///
+    /// Returns [`Error::NotFound`] if the entry is not mapped. Entries are not available if the
+    /// sync manager lengths were changed by the hook passed to
+    /// [`SlaveGroup::into_pre_op_pdi_with_config`](crate::SlaveGroup::into_pre_op_pdi_with_config)
+    /// or if more than 128 entries are mapped by all SubDevices in the group.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{error::Error, slave_group::Op, Client, SlaveGroup};
+    /// # fn entries(client: &Client<'_>, group: SlaveGroup<16, 64, Op>) -> Result<(), Error> {
+    /// let mut slave = group.slave(client, 0)?;
+    ///
+    /// let input: bool = slave.entry(0x6000, 1)?.read()?;
+    ///
+    /// slave.entry_mut(0x7010, 1)?.write(input)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn entry(&self, index: u16, sub_index: u8) -> Result<PdiEntryRef<'_>, Error> {
+        let location = self.entry_location(None, index, sub_index)?;
+
+        let data = match location.direction {
+            PdoDirection::MasterRead => self.inputs_raw(),
+            PdoDirection::MasterWrite => self.outputs_raw(),
+        };
+
+        Ok(PdiEntryRef {
+            data: location.data_range(data)?,
+            bit_offset: location.bit_offset,
+            bit_len: location.bit_len,
+        })
+    }
+
+    /// Get a PDO entry mapped into this SubDevice's outputs by its object index and sub-index, so
+    /// it can be written.
+    ///
+    /// See [`entry`](SlaveRef::entry) for details.
+    pub fn entry_mut(&mut self, index: u16, sub_index: u8) -> Result<PdiEntryMut<'_>, Error> {
+        let location = self.entry_location(Some(PdoDirection::MasterWrite), index, sub_index)?;
+
+        let outputs = self.outputs_raw_mut();
+
+        let range = location.byte_range();
+
+        let len = outputs.len();
+
+        let data =
+            outputs
+                .get_mut(range.clone())
+                .ok_or(Error::Wire(WireError::WriteBufferTooShort {
+                    expected: range.end,
+                    got: len,
+                }))?;
+
+        Ok(PdiEntryMut {
+            data,
+            bit_offset: location.bit_offset,
+            bit_len: location.bit_len,
+        })
+    }
+
+    fn entry_location(
+        &self,
+        direction: Option<PdoDirection>,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<EntryLocation, Error> {
+        self.state
+            .entries()
+            .iter()
+            .find(|entry| {
+                entry.configured_address == self.configured_address
+                    && entry.index == index
+                    && entry.sub_index == sub_index
+                    && direction.map_or(true, |direction| entry.direction == direction)
+            })
+            .copied()
+            .ok_or(Error::NotFound {
+                item: Item::PdoEntry,
+                index: Some(usize::from(index)),
+            })
+    }
+}
+
+/// A PDO entry in a SubDevice's process data, returned by [`SlaveRef::entry`].
+#[derive(Debug)]
+pub struct PdiEntryRef<'a> {
+    /// The bytes holding the entry.
+    data: &'a [u8],
+    bit_offset: u8,
+    bit_len: u8,
+}
+
+impl<'a> PdiEntryRef<'a> {
+    /// Decode the entry's value.
+    ///
+    /// Entries that don't start on a byte boundary are shifted down first, so e.g. a single bit
+    /// entry can be read as a `bool`.
+    pub fn read<T: EtherCrabWireRead>(&self) -> Result<T, Error> {
+        read_bits(self.data, self.bit_offset, self.bit_len)
+    }
+
+    /// The bytes holding the entry in the SubDevice's process data.
+    ///
+    /// The first byte may hold other entries before [`bit_offset`](PdiEntryRef::bit_offset).
+    pub fn raw(&self) -> &[u8] {
+        self.data
+    }
+
+    /// The bit in the first byte at which the entry starts, from 0 to 7.
+    pub fn bit_offset(&self) -> u8 {
+        self.bit_offset
+    }
+
+    /// Length of the entry in bits.
+    pub fn bit_len(&self) -> u8 {
+        self.bit_len
+    }
+}
+
+/// A PDO entry in a SubDevice's outputs, returned by [`SlaveRef::entry_mut`].
+#[derive(Debug)]
+pub struct PdiEntryMut<'a> {
+    /// The bytes holding the entry.
+    data: &'a mut [u8],
+    bit_offset: u8,
+    bit_len: u8,
+}
+
+impl<'a> PdiEntryMut<'a> {
+    /// Decode the entry's current value.
+    pub fn read<T: EtherCrabWireRead>(&self) -> Result<T, Error> {
+        read_bits(self.data, self.bit_offset, self.bit_len)
+    }
+
+    /// Encode a value into the entry, leaving any other entries sharing its bytes unchanged.
+    ///
+    /// Returns an error if the value is longer than the entry. Shorter values are padded with
+    /// zeros.
+    pub fn write<T: EtherCrabWireWrite>(&mut self, value: T) -> Result<(), Error> {
+        let mut buf = [0u8; ENTRY_BUF_LEN];
+
+        let entry_len = (usize::from(self.bit_len) + 7) / 8;
+
+        let packed = value.pack_to_slice(&mut buf)?;
+
+        if packed.len() > entry_len {
+            return Err(Error::Wire(WireError::WriteBufferTooShort {
+                expected: packed.len(),
+                got: entry_len,
+            }));
+        }
+
+        copy_bits(
+            &buf,
+            0,
+            self.data,
+            usize::from(self.bit_offset),
+            usize::from(self.bit_len),
+        );
+
+        Ok(())
+    }
+
+    /// The bit in the first byte at which the entry starts, from 0 to 7.
+    pub fn bit_offset(&self) -> u8 {
+        self.bit_offset
+    }
+
+    /// Length of the entry in bits.
+    pub fn bit_len(&self) -> u8 {
+        self.bit_len
+    }
+}
+
+/// Large enough to hold the longest possible entry of 255 bits.
+const ENTRY_BUF_LEN: usize = 32;
+
+fn read_bits<T: EtherCrabWireRead>(data: &[u8], bit_offset: u8, bit_len: u8) -> Result<T, Error> {
+    if bit_offset == 0 && bit_len % 8 == 0 {
+        return T::unpack_from_slice(data).map_err(Error::from);
+    }
+
+    let mut buf = [0u8; ENTRY_BUF_LEN];
+
+    copy_bits(
+        data,
+        usize::from(bit_offset),
+        &mut buf,
+        0,
+        usize::from(bit_len),
+    );
+
+    T::unpack_from_slice(&buf[0..(usize::from(bit_len) + 7) / 8]).map_err(Error::from)
+}
+
+/// Copy `bit_len` bits from bit `src_bit` of `src` to bit `dst_bit` of `dst`, least significant
+/// bit first.
+fn copy_bits(src: &[u8], src_bit: usize, dst: &mut [u8], dst_bit: usize, bit_len: usize) {
+    for i in 0..bit_len {
+        let bit = (src[(src_bit + i) / 8] >> ((src_bit + i) % 8)) & 1;
+
+        let shift = (dst_bit + i) % 8;
+        let byte = &mut dst[(dst_bit + i) / 8];
+
+        *byte = (*byte & !(1 << shift)) | (bit << shift);
+    }
+}
+
+impl EntryLocation {
+    /// The configured station address of the SubDevice that maps the entry.
+    pub(crate) fn configured_address(&self) -> u16 {
+        self.configured_address
+    }
+
+    /// The bytes holding the entry, relative to the start of the SubDevice's inputs or outputs.
+    fn byte_range(&self) -> core::ops::Range<usize> {
+        let start = usize::from(self.byte_offset);
+
+        start..(start + (usize::from(self.bit_offset) + usize::from(self.bit_len) + 7) / 8)
+    }
+
+    fn data_range<'data>(&self, data: &'data [u8]) -> Result<&'data [u8], Error> {
+        let range = self.byte_range();
+
+        data.get(range.clone())
+            .ok_or(Error::Wire(WireError::ReadBufferTooShort {
+                expected: range.end,
+                got: data.len(),
+            }))
+    }
+}
+
+/// Record where each PDO entry mapped in one direction is in the SubDevice's process data, so it
+/// can be found with [`SlaveRef::entry`].
+///
+/// Returns `false` and records no entries for this direction if the entries don't add up to the
+/// configured sync manager lengths, or there are too many of them.
+pub(in crate::slave) fn record_entry_locations(
+    locations: &mut EntryLocations,
+    configured_address: u16,
+    direction: PdoDirection,
+    raw: &[(u8, PdoMapping)],
+    mappings: &SyncManagerMappings,
+    alignment: PdiAlignment,
+) -> bool {
+    let other = |location: &EntryLocation| {
+        (location.configured_address, location.direction) != (configured_address, direction)
+    };
+
+    locations.retain(other);
+
+    let entries_match = mappings.iter().all(|mapping| {
+        let entries_len = raw
+            .iter()
+            .filter(|(sync_manager, _)| *sync_manager == mapping.sync_manager)
+            .map(|(_, entry)| u16::from(entry.bit_len))
+            .sum::<u16>();
+
+        entries_len == mapping.bit_len && (entries_len == 0 || mapping.fmmu.is_some())
+    }) && raw.iter().all(|(sync_manager, _)| {
+        mappings
+            .iter()
+            .any(|mapping| mapping.sync_manager == *sync_manager)
+    });
+
+    if !entries_match {
+        return false;
+    }
+
+    for (mapping, byte_offset, bit_offset) in entry_offsets(raw, alignment) {
+        if mapping.index == 0 {
+            continue;
+        }
+
+        let location = EntryLocation {
+            configured_address,
+            direction,
+            index: mapping.index,
+            sub_index: mapping.sub_index,
+            bit_len: mapping.bit_len,
+            byte_offset: byte_offset as u16,
+            bit_offset,
+        };
+
+        if locations.push(location).is_err() {
+            locations.retain(other);
+
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Compute the byte and bit offset of each entry.
+///
+/// Entries are packed one after the other, but each sync manager starts on a new byte, or the next
+/// multiple of `alignment`, as its FMMU is byte aligned.
+fn entry_offsets(
+    raw: &[(u8, PdoMapping)],
+    alignment: PdiAlignment,
+) -> impl Iterator<Item = (PdoMapping, usize, u8)> + '_ {
+    let align_bits = alignment.bytes() as usize * 8;
+
+    let mut position = 0usize;
+    let mut current_sm = None;
+
+    raw.iter().map(move |&(sync_manager, mapping)| {
+        if current_sm.replace(sync_manager) != Some(sync_manager) {
+            position = (position + align_bits - 1) / align_bits * align_bits;
+        }
+
+        let offset = position;
+
+        position += usize::from(mapping.bit_len);
+
+        (mapping, offset / 8, (offset % 8) as u8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_entries() {
+        let data = [0b1011_0100, 0x34, 0x12];
+
+        assert_eq!(read_bits::<bool>(&data, 2, 1), Ok(true));
+        assert_eq!(read_bits::<bool>(&data, 3, 1), Ok(false));
+        assert_eq!(read_bits::<u8>(&data, 4, 4), Ok(0b1011));
+        assert_eq!(read_bits::<u16>(&data[1..], 0, 16), Ok(0x1234));
+
+        let mut outputs = [0xffu8, 0x00];
+
+        let mut entry = PdiEntryMut {
+            data: &mut outputs,
+            bit_offset: 6,
+            bit_len: 4,
+        };
+
+        entry.write(0b0110u8).unwrap();
+        assert_eq!(entry.read::<u8>(), Ok(0b0110));
+        assert_eq!(
+            entry.write(0x1234u16),
+            Err(Error::Wire(WireError::WriteBufferTooShort {
+                expected: 2,
+                got: 1
+            }))
+        );
+
+        assert_eq!(outputs, [0b1011_1111, 0b0000_0001]);
+    }
+
+    #[test]
+    fn record_locations() {
+        use crate::slave::configuration::SyncManagerMapping;
+
+        let raw = [
+            (2, PdoMapping::new(0x7000, 1, 1)),
+            (2, PdoMapping::new(0x0000, 0, 7)),
+            (3, PdoMapping::new(0x7010, 1, 16)),
+        ];
+
+        let mut mappings = SyncManagerMappings::new();
+
+        for (sync_manager, bit_len) in [(2, 8), (3, 16)] {
+            mappings
+                .push(SyncManagerMapping {
+                    sync_manager,
+                    bit_len,
+                    fmmu: Some(sync_manager - 2),
+                })
+                .unwrap();
+        }
+
+        let mut locations = EntryLocations::new();
+
+        // Another SubDevice in the same group
+        assert!(record_entry_locations(
+            &mut locations,
+            0x1000,
+            PdoDirection::MasterWrite,
+            &raw,
+            &mappings,
+            PdiAlignment::Byte
+        ));
+
+        assert!(record_entry_locations(
+            &mut locations,
+            0x1001,
+            PdoDirection::MasterWrite,
+            &raw,
+            &mappings,
+            PdiAlignment::Byte
+        ));
+
+        let recorded = |locations: &EntryLocations| {
+            locations
+                .iter()
+                .map(|location| {
+                    (
+                        location.configured_address,
+                        location.index,
+                        location.byte_offset,
+                        location.bit_offset,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            recorded(&locations),
+            vec![
+                (0x1000, 0x7000, 0, 0),
+                (0x1000, 0x7010, 1, 0),
+                (0x1001, 0x7000, 0, 0),
+                (0x1001, 0x7010, 1, 0)
+            ]
+        );
+
+        // Sync manager length changed by a configuration hook
+        mappings[1].bit_len = 32;
+
+        assert!(!record_entry_locations(
+            &mut locations,
+            0x1001,
+            PdoDirection::MasterWrite,
+            &raw,
+            &mappings,
+            PdiAlignment::Byte
+        ));
+        assert_eq!(
+            recorded(&locations),
+            vec![(0x1000, 0x7000, 0, 0), (0x1000, 0x7010, 1, 0)]
+        );
+    }
+
+    #[test]
+    fn offsets_align_sync_managers() {
+        let raw = [
+            (2, PdoMapping::new(0x6040, 0, 16)),
+            (2, PdoMapping::new(0x7000, 1, 1)),
+            (2, PdoMapping::new(0x7000, 2, 1)),
+            // Padding
+            (2, PdoMapping::new(0x0000, 0, 3)),
+            (2, PdoMapping::new(0x7000, 3, 4)),
+            (2, PdoMapping::new(0x7000, 4, 1)),
+            // Next sync manager starts on a new byte
+            (3, PdoMapping::new(0x607a, 0, 32)),
+        ];
+
+        let offsets = |alignment| {
+            entry_offsets(&raw, alignment)
+                .map(|(mapping, byte, bit)| (mapping.index, mapping.sub_index, byte, bit))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            offsets(PdiAlignment::Byte),
+            vec![
+                (0x6040, 0, 0, 0),
+                (0x7000, 1, 2, 0),
+                (0x7000, 2, 2, 1),
+                (0x0000, 0, 2, 2),
+                (0x7000, 3, 2, 5),
+                (0x7000, 4, 3, 1),
+                (0x607a, 0, 4, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn offsets_align_words() {
+        let raw = [
+            (2, PdoMapping::new(0x7000, 1, 8)),
+            (3, PdoMapping::new(0x7010, 1, 16)),
+        ];
+
+        let second = |alignment| {
+            entry_offsets(&raw, alignment)
+                .nth(1)
+                .map(|(_mapping, byte, bit)| (byte, bit))
+        };
+
+        assert_eq!(second(PdiAlignment::Byte), Some((1, 0)));
+        assert_eq!(second(PdiAlignment::Word), Some((2, 0)));
+        assert_eq!(second(PdiAlignment::DoubleWord), Some((4, 0)));
+    }
+}
@@ -0,0 +1,87 @@
+use super::{Slave, SlaveRef};
+use crate::{coe::PdoMapping, error::Error, fmt, sync_manager_channel::SM_BASE_ADDRESS};
+use core::ops::Deref;
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Assign a list of PDOs to a sync manager by writing its PDO assignment object, e.g. `0x1C12`
+    /// for sync manager 2 (RxPDOs) or `0x1C13` for sync manager 3 (TxPDOs).
+    ///
+    /// This must be done while the SubDevice is in PRE-OP. The new assignment is used to compute
+    /// the group's PDI layout when it transitions into SAFE-OP.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, PdoMapping, SlaveRef, Slave };
+    /// # async fn remap(slave: SlaveRef<'_, &Slave>) -> Result<(), Error> {
+    /// // Map control word and target position into RxPDO 0x1600
+    /// slave
+    ///     .set_pdo_mapping(
+    ///         0x1600,
+    ///         &[
+    ///             PdoMapping::new(0x6040, 0x00, 16),
+    ///             PdoMapping::new(0x607a, 0x00, 32),
+    ///         ],
+    ///     )
+    ///     .await?;
+    ///
+    /// // Assign it to sync manager 2
+    /// slave.set_pdo_assignment(2, &[0x1600]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_pdo_assignment(&self, sync_manager: u8, pdos: &[u16]) -> Result<(), Error> {
+        let index = SM_BASE_ADDRESS + u16::from(sync_manager);
+
+        fmt::debug!(
+            "Slave {:#06x} assign PDOs {:?} to {:#06x}",
+            self.configured_address,
+            pdos,
+            index
+        );
+
+        self.write_object_list(index, pdos.iter()).await
+    }
+
+    /// Set the entries mapped into a PDO, e.g. `0x1600` for the first RxPDO or `0x1A00` for the
+    /// first TxPDO.
+    ///
+    /// This must be done while the SubDevice is in PRE-OP. The PDO must also be assigned to a sync
+    /// manager with [`set_pdo_assignment`](SlaveRef::set_pdo_assignment) for it to be included in
+    /// the PDI.
+    pub async fn set_pdo_mapping(&self, pdo: u16, entries: &[PdoMapping]) -> Result<(), Error> {
+        fmt::debug!(
+            "Slave {:#06x} set {} mappings for PDO {:#06x}",
+            self.configured_address,
+            entries.len(),
+            pdo
+        );
+
+        self.write_object_list(pdo, entries.iter()).await
+    }
+
+    /// Write a list of values to sub-indices `1..` of an object, using the procedure defined in
+    /// ETG1000.6 Section 5.6.7.4.7: sub-index 0 is set to zero, the list items are written, then
+    /// sub-index 0 is set to the number of items.
+    async fn write_object_list<T>(
+        &self,
+        index: u16,
+        items: impl ExactSizeIterator<Item = T>,
+    ) -> Result<(), Error>
+    where
+        T: ethercrab_wire::EtherCrabWireWrite,
+    {
+        let count = u8::try_from(items.len()).map_err(|_| Error::IntegerTypeConversion)?;
+
+        self.sdo_write(index, 0, 0u8).await?;
+
+        for (sub_index, item) in (1..=count).zip(items) {
+            self.sdo_write(index, sub_index, item).await?;
+        }
+
+        self.sdo_write(index, 0, count).await
+    }
+}
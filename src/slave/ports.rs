@@ -109,6 +109,17 @@ impl Ports {
         self.0[3].dc_receive_time = time_p2;
     }
 
+    /// Get port DC receive times, indexed by port number.
+    pub(crate) fn receive_times(&self) -> [u32; 4] {
+        let mut times = [0u32; 4];
+
+        for port in self.0.iter() {
+            times[usize::from(port.number)] = port.dc_receive_time;
+        }
+
+        times
+    }
+
     /// TEST ONLY: Set downstream ports.
     #[cfg(test)]
     pub(crate) fn set_downstreams(
@@ -311,6 +322,15 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn receive_times_port_order() {
+        let mut ports = make_ports(true, true, true, false);
+
+        ports.set_receive_times(100, 400, 200, 300);
+
+        assert_eq!(ports.receive_times(), [100, 200, 300, 400]);
+    }
+
     #[test]
     fn propagation_time() {
         // Passthrough slave
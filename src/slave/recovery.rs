@@ -0,0 +1,203 @@
+use super::{configuration::PdoDirection, Slave, SlaveRef};
+use crate::{
+    al_control::AlControl, error::Error, fmt, pdi::PdiOffset, AlStatusCode, RegisterAddress,
+    SlaveState,
+};
+use core::ops::DerefMut;
+
+/// A SubDevice that was found out of OP by [`SlaveGroup::recover`](crate::SlaveGroup::recover),
+/// and whether it was returned to OP.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RecoveryEvent {
+    /// The index of the SubDevice in its group.
+    pub index: usize,
+
+    /// The SubDevice's configured station address.
+    pub configured_address: u16,
+
+    /// The state the SubDevice was found in.
+    ///
+    /// This is [`SlaveState::None`] if the SubDevice didn't respond, e.g. because it was
+    /// disconnected or power cycled and lost its configured station address.
+    pub state: SlaveState,
+
+    /// The AL status code the SubDevice reported, e.g.
+    /// [`AlStatusCode::SyncManagerWatchdog`] if it fell back to SAFE-OP because it stopped
+    /// receiving process data.
+    pub status_code: AlStatusCode,
+
+    /// The result of returning the SubDevice to OP.
+    pub result: Result<(), Error>,
+}
+
+/// Where a group's process data is mapped, so a SubDevice's FMMUs can be configured again at the
+/// same logical addresses.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GroupPdi {
+    pub inputs_start: PdiOffset,
+    pub outputs_start: PdiOffset,
+    pub read_pdi_len: usize,
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: DerefMut<Target = Slave>,
+{
+    /// Check the SubDevice's AL status and, if it is no longer in OP, try to return it to OP.
+    ///
+    /// Returns `None` if the SubDevice is in OP with no error.
+    pub(crate) async fn recover(&mut self, index: usize, pdi: GroupPdi) -> Option<RecoveryEvent> {
+        let configured_address = self.configured_address;
+
+        let event = |state, status_code, result| RecoveryEvent {
+            index,
+            configured_address,
+            state,
+            status_code,
+            result,
+        };
+
+        let status = match self
+            .read(RegisterAddress::AlStatus)
+            .receive::<AlControl>(self.client)
+            .await
+        {
+            Ok(status) => status,
+            Err(e) => return Some(event(SlaveState::None, AlStatusCode::NoError, Err(e))),
+        };
+
+        if status.state == SlaveState::Op && !status.error {
+            self.state.missing = false;
+
+            return None;
+        }
+
+        let status_code = if status.error {
+            match self
+                .read(RegisterAddress::AlStatusCode)
+                .receive::<AlStatusCode>(self.client)
+                .await
+            {
+                Ok(code) => code,
+                Err(e) => return Some(event(status.state, AlStatusCode::Unknown(0), Err(e))),
+            }
+        } else {
+            AlStatusCode::NoError
+        };
+
+        fmt::warn!(
+            "Slave {:#06x} dropped out of OP to {} ({}), recovering",
+            self.configured_address,
+            status.state,
+            status_code
+        );
+
+        let result = self.return_to_op(status, pdi).await;
+
+        match result {
+            Ok(()) => {
+                fmt::info!("Slave {:#06x} recovered", self.configured_address);
+
+                self.state.missing = false;
+            }
+            Err(e) => fmt::error!(
+                "Slave {:#06x} could not be recovered: {}",
+                self.configured_address,
+                e
+            ),
+        }
+
+        Some(event(status.state, status_code, result))
+    }
+
+    async fn return_to_op(&mut self, status: AlControl, pdi: GroupPdi) -> Result<(), Error> {
+        if status.error {
+            // The SubDevice ignores state requests until the error is acknowledged
+            self.write(RegisterAddress::AlControl)
+                .send(
+                    self.client,
+                    AlControl {
+                        error: true,
+                        ..AlControl::new(status.state)
+                    },
+                )
+                .await?;
+        }
+
+        match status.state {
+            SlaveState::Op => return Ok(()),
+            // Sync managers and FMMUs are kept in SAFE-OP
+            SlaveState::SafeOp => {
+                return self
+                    .request_slave_state(SlaveState::SafeOp, SlaveState::Op)
+                    .await
+            }
+            SlaveState::Init => (),
+            state => self.request_slave_state(state, SlaveState::Init).await?,
+        }
+
+        let previous_io = self.state.config.io.clone();
+        let previous_layout = self.state.config.layout.clone();
+
+        let configured = self.reconfigure(pdi).await;
+
+        if configured.is_err() {
+            self.state.config.io = previous_io;
+            self.state.config.layout = previous_layout;
+        }
+
+        configured?;
+
+        self.request_slave_state(SlaveState::PreOp, SlaveState::SafeOp)
+            .await?;
+        self.request_slave_state(SlaveState::SafeOp, SlaveState::Op)
+            .await
+    }
+
+    /// Configure the SubDevice from INIT to PRE-OP again, mapping its process data to the same
+    /// place in the group's PDI.
+    async fn reconfigure(&mut self, pdi: GroupPdi) -> Result<(), Error> {
+        let previous = self.state.config.io.clone();
+
+        self.configure_mailboxes().await?;
+
+        self.configure_fmmus(
+            pdi.inputs_start
+                .increment(previous.input.bytes.start as u16),
+            pdi.inputs_start.start_address,
+            0,
+            PdoDirection::MasterRead,
+            &|_slave, _direction, _mappings| {},
+            None,
+            None,
+        )
+        .await?;
+
+        self.configure_fmmus(
+            pdi.outputs_start
+                .increment(previous.output.bytes.start.saturating_sub(pdi.read_pdi_len) as u16),
+            pdi.outputs_start.start_address,
+            pdi.read_pdi_len,
+            PdoDirection::MasterWrite,
+            &|_slave, _direction, _mappings| {},
+            None,
+            None,
+        )
+        .await?;
+
+        let io = &self.state.config.io;
+
+        // The PDO mapping may have changed, e.g. if the SubDevice was reset to its defaults
+        for (previous, current) in [(&previous.input, &io.input), (&previous.output, &io.output)] {
+            if current.len() > previous.len() {
+                return Err(Error::PdiTooLong {
+                    max_length: previous.len(),
+                    desired_length: current.len(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
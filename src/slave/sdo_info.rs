@@ -0,0 +1,384 @@
+use super::{large_mailbox::MailboxData, types::Mailbox, Slave, SlaveRef};
+use crate::{
+    coe::{
+        abort_code::CoeAbortCode,
+        sdo_info::{
+            self, EntryDescription, EntryDescriptionHeader, ObjectDescription,
+            ObjectDescriptionHeader, OdListType, SdoInfo, SdoInfoOpCode,
+        },
+        CoeService,
+    },
+    error::{Error, Item, MailboxError},
+    fmt,
+    mailbox::MailboxType,
+};
+use core::ops::Deref;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite};
+
+/// An iterator over the objects in a SubDevice's object dictionary, created by
+/// [`SlaveRef::object_dictionary`].
+///
+/// The list of object indices is read from the SubDevice up front. Each call to
+/// [`next`](ObjectDictionary::next) then requests the description of a single object.
+pub struct ObjectDictionary<'slave, 'client, S, const N: usize> {
+    slave: &'slave SlaveRef<'client, S>,
+    indices: heapless::Vec<u16, N>,
+    position: usize,
+}
+
+impl<'slave, 'client, S, const N: usize> ObjectDictionary<'slave, 'client, S, N>
+where
+    S: Deref<Target = Slave>,
+{
+    /// The indices of all objects in the object dictionary.
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
+    }
+
+    /// Get the description of the next object in the object dictionary, or `None` if all objects
+    /// have been described.
+    pub async fn next(&mut self) -> Option<Result<ObjectDescription, Error>> {
+        let index = *self.indices.get(self.position)?;
+
+        self.position += 1;
+
+        Some(self.slave.object_description(index).await)
+    }
+}
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read the object dictionary of this SubDevice using the CoE SDO Information service.
+    ///
+    /// `N` is the maximum number of objects that can be stored. If the SubDevice has more objects
+    /// than this, an [`Error::Capacity`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let mut group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// for slave in group.iter(&client) {
+    ///     let mut od = slave.object_dictionary::<256>().await?;
+    ///
+    ///     while let Some(object) = od.next().await {
+    ///         let object = object?;
+    ///
+    ///         log::info!("{:#06x}: {}", object.index, object.name);
+    ///     }
+    /// }
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn object_dictionary<const N: usize>(
+        &self,
+    ) -> Result<ObjectDictionary<'_, 'a, S, N>, Error> {
+        let indices = self.od_list(OdListType::All).await?;
+
+        Ok(ObjectDictionary {
+            slave: self,
+            indices,
+            position: 0,
+        })
+    }
+
+    /// Get a list of object indices from the SubDevice's object dictionary.
+    ///
+    /// The most recently read list is cached. Use
+    /// [`invalidate_coe_cache`](SlaveRef::invalidate_coe_cache) to clear the cache.
+    pub async fn od_list<const N: usize>(
+        &self,
+        list_type: OdListType,
+    ) -> Result<heapless::Vec<u16, N>, Error> {
+        let cached = self.cached(|cache| {
+            cache
+                .od_list(self.configured_address, list_type)
+                .map(|indices| heapless::Vec::from_slice(indices).map_err(|_| ()))
+        });
+
+        match cached {
+            Some(Ok(indices)) => return Ok(indices),
+            Some(Err(())) => return Err(Error::Capacity(Item::Object)),
+            None => (),
+        }
+
+        let mut indices = heapless::Vec::new();
+
+        let _lock = self.lock_mailbox().await?;
+
+        let request = sdo_info::get_od_list(self.mailbox_counter());
+
+        let read_mailbox = self.send_sdo_info(request, list_type).await?;
+
+        let mut response = self
+            .sdo_info_response(&read_mailbox, SdoInfoOpCode::GetOdListResponse, 0, 0)
+            .await?;
+
+        // The first fragment echoes the list type before the list of indices.
+        let mut skip = OdListType::PACKED_LEN;
+
+        loop {
+            let (headers, data) = response;
+
+            let data = data.get(skip..).unwrap_or(&[]);
+
+            for index in data.chunks_exact(u16::PACKED_LEN) {
+                indices
+                    .push(u16::unpack_from_slice(index)?)
+                    .map_err(|_| Error::Capacity(Item::Object))?;
+            }
+
+            if !headers.info_header.incomplete {
+                break;
+            }
+
+            skip = 0;
+
+            response = self
+                .sdo_info_response(&read_mailbox, SdoInfoOpCode::GetOdListResponse, 0, 0)
+                .await?;
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} has {} objects of type {:?}",
+            self.configured_address,
+            indices.len(),
+            list_type
+        );
+
+        self.update_cache(|cache| {
+            cache.insert_od_list(self.configured_address, list_type, &indices)
+        });
+
+        Ok(indices)
+    }
+
+    /// Get a description of a single object in the SubDevice's object dictionary.
+    ///
+    /// Recently read descriptions are cached. Use
+    /// [`invalidate_coe_cache`](SlaveRef::invalidate_coe_cache) to clear the cache.
+    pub async fn object_description(&self, index: u16) -> Result<ObjectDescription, Error> {
+        if let Some(object) = self.cached(|cache| cache.object(self.configured_address, index)) {
+            return Ok(object);
+        }
+
+        let _lock = self.lock_mailbox().await?;
+
+        let request = sdo_info::get_object_description(self.mailbox_counter());
+
+        let read_mailbox = self.send_sdo_info(request, index).await?;
+
+        let (_headers, data) = self
+            .sdo_info_response(
+                &read_mailbox,
+                SdoInfoOpCode::GetObjectDescriptionResponse,
+                index,
+                0,
+            )
+            .await?;
+
+        let header = ObjectDescriptionHeader::unpack_from_slice(&data)?;
+
+        let name = name_from_bytes(&data[ObjectDescriptionHeader::PACKED_LEN..], index, 0)?;
+
+        let object = ObjectDescription {
+            index: header.index,
+            data_type: header.data_type,
+            max_sub_index: header.max_sub_index,
+            object_code: header.object_code,
+            name,
+        };
+
+        self.update_cache(|cache| cache.insert_object(self.configured_address, object.clone()));
+
+        Ok(object)
+    }
+
+    /// Get a description of a single sub-index of an object in the SubDevice's object dictionary.
+    ///
+    /// Like [`object_description`](SlaveRef::object_description), recently read descriptions are
+    /// cached.
+    pub async fn entry_description(
+        &self,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<EntryDescription, Error> {
+        if let Some(entry) =
+            self.cached(|cache| cache.entry(self.configured_address, index, sub_index))
+        {
+            return Ok(entry);
+        }
+
+        let _lock = self.lock_mailbox().await?;
+
+        let request = sdo_info::get_entry_description(self.mailbox_counter());
+
+        // No optional value info (default, min, max values, etc) is requested.
+        let value_info = 0u8;
+
+        let read_mailbox = self
+            .send_sdo_info(request, (index, sub_index, value_info))
+            .await?;
+
+        let (_headers, data) = self
+            .sdo_info_response(
+                &read_mailbox,
+                SdoInfoOpCode::GetEntryDescriptionResponse,
+                index,
+                sub_index,
+            )
+            .await?;
+
+        let header = EntryDescriptionHeader::unpack_from_slice(&data)?;
+
+        let name = name_from_bytes(
+            &data[EntryDescriptionHeader::PACKED_LEN..],
+            index,
+            sub_index,
+        )?;
+
+        let entry = EntryDescription {
+            index: header.index,
+            sub_index: header.sub_index,
+            data_type: header.data_type,
+            bit_len: header.bit_len,
+            access: header.access,
+            name,
+        };
+
+        self.update_cache(|cache| cache.insert_entry(self.configured_address, entry.clone()));
+
+        Ok(entry)
+    }
+
+    /// Send an SDO Information request, returning the mailbox to read the response from.
+    async fn send_sdo_info(
+        &self,
+        request: SdoInfo,
+        payload: impl EtherCrabWireWrite,
+    ) -> Result<Mailbox, Error> {
+        let (read_mailbox, write_mailbox) = self.mailboxes().await?;
+
+        self.write_mailbox(&write_mailbox, (request, payload))
+            .await?;
+
+        Ok(read_mailbox)
+    }
+
+    /// Read a single SDO Information response fragment. The returned data has the mailbox and SDO
+    /// Information headers removed.
+    async fn sdo_info_response(
+        &self,
+        read_mailbox: &Mailbox,
+        expected: SdoInfoOpCode,
+        index: u16,
+        sub_index: u8,
+    ) -> Result<(SdoInfo, SdoInfoData<'_>), Error> {
+        let response = self
+            .coe_response(read_mailbox, self.client.timeouts.coe_response)
+            .await?;
+
+        let headers = SdoInfo::unpack_from_slice(&response)?;
+
+        // Mailbox length includes the 2 byte CoE header and 4 byte SDO Information header.
+        let data_len = usize::from(headers.header.length)
+            .saturating_sub(6)
+            .min(response.len() - SdoInfo::PACKED_LEN);
+
+        let data = SdoInfoData {
+            response,
+            len: data_len,
+        };
+
+        if headers.header.mailbox_type != MailboxType::Coe
+            || headers.header.service != CoeService::SdoInformation
+        {
+            fmt::error!(
+                "Invalid SDO Information response. Type: {:?}, service {:?}",
+                headers.header.mailbox_type,
+                headers.header.service,
+            );
+
+            return Err(Error::Mailbox(MailboxError::SdoResponseInvalid {
+                address: index,
+                sub_index,
+            }));
+        }
+
+        if headers.info_header.op_code == SdoInfoOpCode::SdoInfoErrorRequest {
+            let code = CoeAbortCode::unpack_from_slice(&data)?;
+
+            fmt::error!(
+                "SDO Information error for slave {:#06x}: {}",
+                self.configured_address,
+                code
+            );
+
+            return Err(Error::Mailbox(MailboxError::Aborted {
+                code,
+                address: index,
+                sub_index,
+            }));
+        }
+
+        if headers.info_header.op_code != expected {
+            fmt::error!(
+                "Invalid SDO Information response op code {:?}, expected {:?}",
+                headers.info_header.op_code,
+                expected
+            );
+
+            return Err(Error::Mailbox(MailboxError::SdoResponseInvalid {
+                address: index,
+                sub_index,
+            }));
+        }
+
+        Ok((headers, data))
+    }
+}
+
+/// The payload of an SDO Information response, excluding headers.
+struct SdoInfoData<'a> {
+    response: MailboxData<'a>,
+    len: usize,
+}
+
+impl<'a> Deref for SdoInfoData<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.response[SdoInfo::PACKED_LEN..][..self.len]
+    }
+}
+
+/// Decode an object or entry name, truncating it if it is too long to store.
+fn name_from_bytes(bytes: &[u8], index: u16, sub_index: u8) -> Result<heapless::String<64>, Error> {
+    let s = core::str::from_utf8(bytes)
+        .map_err(|_| {
+            fmt::error!("Invalid UTF8 in name of {:#06x}:{}", index, sub_index);
+
+            Error::Mailbox(MailboxError::SdoResponseInvalid {
+                address: index,
+                sub_index,
+            })
+        })?
+        .trim_end_matches('\0');
+
+    let mut name = heapless::String::new();
+
+    for c in s.chars() {
+        if name.push(c).is_err() {
+            break;
+        }
+    }
+
+    Ok(name)
+}
@@ -0,0 +1,196 @@
+use super::{Slave, SlaveRef};
+use crate::{
+    coe::sm_parameters::{
+        SyncManagerParameters, SyncMode, SM_INPUT_PARAMETERS, SM_OUTPUT_PARAMETERS,
+    },
+    error::{Error, MailboxError},
+    fmt,
+};
+use core::ops::Deref;
+use ethercrab_wire::EtherCrabWireReadSized;
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read the output sync manager parameter object `0x1C32`.
+    ///
+    /// This can be used to check that the configured cycle and shift times are long enough for the
+    /// SubDevice, e.g. by comparing [`calc_and_copy_time`](SyncManagerParameters::calc_and_copy_time)
+    /// to the cycle time, or checking the error counters while the group is in OP.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, SlaveRef, Slave };
+    /// # async fn check(slave: SlaveRef<'_, &Slave>) -> Result<(), Error> {
+    /// let outputs = slave.sm_output_parameters().await?;
+    ///
+    /// if outputs.cycle_time_too_small > 0 || outputs.sm_event_missed > 0 {
+    ///     log::warn!(
+    ///         "{} cannot keep up with a cycle time of {} ns (minimum {} ns)",
+    ///         slave.name(),
+    ///         outputs.cycle_time,
+    ///         outputs.min_cycle_time
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sm_output_parameters(&self) -> Result<SyncManagerParameters, Error> {
+        self.sm_parameters(SM_OUTPUT_PARAMETERS).await
+    }
+
+    /// Read the input sync manager parameter object `0x1C33`.
+    ///
+    /// See [`sm_output_parameters`](SlaveRef::sm_output_parameters) for more information.
+    pub async fn sm_input_parameters(&self) -> Result<SyncManagerParameters, Error> {
+        self.sm_parameters(SM_INPUT_PARAMETERS).await
+    }
+
+    /// Set the synchronisation mode of this SubDevice to free run, sync manager synchronous or DC
+    /// synchronous by writing the sync manager parameter objects `0x1C32:01` and `0x1C33:01`.
+    ///
+    /// Some SubDevices default to free run and won't enter OP with DC enabled until they are
+    /// switched to [`SyncMode::Sync0`]. This method must be called in PRE-OP. DC modes also require
+    /// the SYNC signals to be configured with [`set_dc_sync`](SlaveRef::set_dc_sync) and
+    /// [`SlaveGroup::configure_dc_sync`](crate::SlaveGroup::configure_dc_sync).
+    ///
+    /// The input mode is derived from `mode`: [`SyncMode::SyncManager`] synchronises inputs to the
+    /// sync manager 2 event if the SubDevice has outputs. Objects the SubDevice doesn't have are
+    /// skipped.
+    pub async fn set_sync_mode(&self, mode: SyncMode) -> Result<(), Error> {
+        let has_outputs = self.has_sm_parameters(SM_OUTPUT_PARAMETERS).await?;
+
+        if has_outputs {
+            self.sdo_write(SM_OUTPUT_PARAMETERS, 0x01, mode).await?;
+        }
+
+        if self.has_sm_parameters(SM_INPUT_PARAMETERS).await? {
+            let input_mode = match mode {
+                SyncMode::SyncManager if has_outputs => SyncMode::SyncManager2,
+                mode => mode,
+            };
+
+            self.sdo_write(SM_INPUT_PARAMETERS, 0x01, input_mode)
+                .await?;
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} sync mode set to {:?}",
+            self.configured_address,
+            mode
+        );
+
+        Ok(())
+    }
+
+    /// Check whether the SubDevice has the given sync manager parameter object.
+    async fn has_sm_parameters(&self, index: u16) -> Result<bool, Error> {
+        match self.sdo_read::<u8>(index, 0).await {
+            Ok(_) => Ok(true),
+            Err(Error::Mailbox(MailboxError::Aborted { .. })) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn sm_parameters(&self, index: u16) -> Result<SyncManagerParameters, Error> {
+        let max_sub_index = self.sdo_read::<u8>(index, 0).await?;
+
+        let params = SyncManagerParameters {
+            sync_mode: self.sm_parameter(index, max_sub_index, 0x01).await?,
+            cycle_time: self.sm_parameter(index, max_sub_index, 0x02).await?,
+            shift_time: self.sm_parameter(index, max_sub_index, 0x03).await?,
+            sync_modes_supported: self.sm_parameter(index, max_sub_index, 0x04).await?,
+            min_cycle_time: self.sm_parameter(index, max_sub_index, 0x05).await?,
+            calc_and_copy_time: self.sm_parameter(index, max_sub_index, 0x06).await?,
+            min_delay_time: self.sm_parameter(index, max_sub_index, 0x07).await?,
+            delay_time: self.sm_parameter(index, max_sub_index, 0x09).await?,
+            sync0_cycle_time: self.sm_parameter(index, max_sub_index, 0x0a).await?,
+            sm_event_missed: self.sm_parameter(index, max_sub_index, 0x0b).await?,
+            cycle_time_too_small: self.sm_parameter(index, max_sub_index, 0x0c).await?,
+            shift_time_too_short: self.sm_parameter(index, max_sub_index, 0x0d).await?,
+            rx_pdo_toggle_failed: self.sm_parameter(index, max_sub_index, 0x0e).await?,
+            sync_error: self.sm_parameter(index, max_sub_index, 0x20).await?,
+        };
+
+        fmt::debug!(
+            "Slave {:#06x} SM parameters {:#06x}: {:?}",
+            self.configured_address,
+            index,
+            params
+        );
+
+        Ok(params)
+    }
+
+    /// Read a single entry of a sync manager parameter object, returning the default value if the
+    /// SubDevice does not support it.
+    async fn sm_parameter<T>(
+        &self,
+        index: u16,
+        max_sub_index: u8,
+        sub_index: u8,
+    ) -> Result<T, Error>
+    where
+        T: EtherCrabWireReadSized + Default,
+    {
+        if sub_index > max_sub_index {
+            return Ok(T::default());
+        }
+
+        match self.sdo_read(index, sub_index).await {
+            Ok(value) => Ok(value),
+            Err(Error::Mailbox(MailboxError::Aborted { .. })) => Ok(T::default()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eeprom::types::MailboxProtocols,
+        slave::mock::{coe_subdevice, MockSubDevice, SdoServer},
+        ClientConfig, Timeouts,
+    };
+
+    #[tokio::test]
+    async fn set_sync_mode() {
+        let (client, _mock, server) = coe_subdevice(
+            Timeouts::default(),
+            ClientConfig::default(),
+            SdoServer::new([
+                ((0x1c32, 0), vec![0x20]),
+                ((0x1c32, 1), vec![0x00, 0x00]),
+                ((0x1c33, 0), vec![0x20]),
+                ((0x1c33, 1), vec![0x00, 0x00]),
+            ]),
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::COE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let mode = |index| server.lock().unwrap().objects.get(&(index, 1)).cloned();
+
+        assert_eq!(slave.set_sync_mode(SyncMode::SyncManager).await, Ok(()));
+        assert_eq!(mode(0x1c32), Some(vec![0x01, 0x00]));
+        assert_eq!(mode(0x1c33), Some(vec![0x22, 0x00]));
+
+        assert_eq!(slave.set_sync_mode(SyncMode::Sync0).await, Ok(()));
+        assert_eq!(mode(0x1c32), Some(vec![0x02, 0x00]));
+        assert_eq!(mode(0x1c33), Some(vec![0x02, 0x00]));
+
+        // SubDevice without outputs
+        server
+            .lock()
+            .unwrap()
+            .objects
+            .retain(|(index, _), _| *index != 0x1c32);
+
+        assert_eq!(slave.set_sync_mode(SyncMode::SyncManager).await, Ok(()));
+        assert_eq!(mode(0x1c32), None);
+        assert_eq!(mode(0x1c33), Some(vec![0x01, 0x00]));
+    }
+}
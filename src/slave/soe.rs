@@ -0,0 +1,702 @@
+use super::{large_mailbox::MailboxData, types::Mailbox, Slave, SlaveRef};
+use crate::timer_factory::IntoTimeout;
+use crate::{
+    eeprom::types::MailboxProtocols,
+    error::{Error, MailboxError, SoeError},
+    fmt,
+    mailbox::MailboxType,
+    soe::{CommandStatus, Idn, IdnList, SoeElements, SoeErrorCode, SoeHeader, SoeOpCode},
+};
+use core::ops::Deref;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Read elements of an IDN from a drive using SoE (Servo drive profile over EtherCAT),
+    /// returning the number of bytes read into `buf`.
+    ///
+    /// `drive_no` selects the drive for SubDevices that control more than one, and is `0`
+    /// otherwise. Responses split over multiple mailbox fragments are reassembled into `buf`.
+    ///
+    /// # Errors
+    ///
+    /// An [`SoeError::TooLong`] is returned if the response doesn't fit in `buf`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, Idn, PduStorage, SoeElements, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// let mut buf = [0u8; 4];
+    ///
+    /// // S-0-0044: Velocity data scaling type
+    /// slave
+    ///     .idn_read(0, Idn::s(0, 44), SoeElements::VALUE, &mut buf)
+    ///     .await?;
+    ///
+    /// let scaling = u16::from_le_bytes([buf[0], buf[1]]);
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn idn_read(
+        &self,
+        drive_no: u8,
+        idn: impl Into<Idn>,
+        elements: SoeElements,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let idn = idn.into();
+
+        self.soe_write_capacity()?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self
+            .send_soe(
+                SoeOpCode::ReadRequest,
+                drive_no,
+                elements,
+                idn.into(),
+                false,
+                &[],
+            )
+            .await?;
+
+        let mut len = 0;
+
+        loop {
+            let (header, response) = self
+                .soe_response(&read_mailbox, SoeOpCode::ReadResponse, drive_no)
+                .await?;
+
+            // The IDN field holds the number of fragments left until the last fragment.
+            if !header.incomplete && header.idn != u16::from(idn) {
+                fmt::error!(
+                    "Slave {:#06x} sent SoE response for {}, expected {}",
+                    self.configured_address,
+                    Idn::from(header.idn),
+                    idn
+                );
+
+                return Err(Error::Soe(SoeError::InvalidResponse));
+            }
+
+            let data = soe_data(&header, &response);
+
+            buf.get_mut(len..(len + data.len()))
+                .ok_or(Error::Soe(SoeError::TooLong))?
+                .copy_from_slice(data);
+
+            len += data.len();
+
+            if !header.incomplete {
+                break;
+            }
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} SoE read {} bytes from {}",
+            self.configured_address,
+            len,
+            idn
+        );
+
+        Ok(len)
+    }
+
+    /// Write elements of an IDN to a drive using SoE (Servo drive profile over EtherCAT).
+    ///
+    /// `drive_no` selects the drive for SubDevices that control more than one, and is `0`
+    /// otherwise. Data that doesn't fit in the SubDevice's mailbox is split into multiple
+    /// fragments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, Idn, PduStorage, SoeElements, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// // S-0-0091: Bipolar velocity limit value
+    /// slave
+    ///     .idn_write(0, Idn::s(0, 91), SoeElements::VALUE, &100_000u32.to_le_bytes())
+    ///     .await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn idn_write(
+        &self,
+        drive_no: u8,
+        idn: impl Into<Idn>,
+        elements: SoeElements,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let idn = idn.into();
+
+        let write_len = self.soe_write_capacity()?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        // At least one fragment is sent, even if there is no data.
+        let fragments = data.len().div_ceil(write_len).max(1);
+
+        let mut i = 0;
+
+        let read_mailbox = loop {
+            let chunk = data.get((i * write_len)..).unwrap_or(&[]);
+            let chunk = &chunk[0..chunk.len().min(write_len)];
+
+            let fragments_left = fragments - i - 1;
+
+            // Every fragment but the last holds the number of fragments still to come instead of
+            // the IDN.
+            let incomplete = fragments_left > 0;
+
+            let idn_or_left = if incomplete {
+                u16::try_from(fragments_left)?
+            } else {
+                idn.into()
+            };
+
+            let read_mailbox = self
+                .send_soe(
+                    SoeOpCode::WriteRequest,
+                    drive_no,
+                    elements,
+                    idn_or_left,
+                    incomplete,
+                    chunk,
+                )
+                .await?;
+
+            if !incomplete {
+                break read_mailbox;
+            }
+
+            i += 1;
+        };
+
+        // Only the last fragment is responded to
+        let (header, _response) = self
+            .soe_response(&read_mailbox, SoeOpCode::WriteResponse, drive_no)
+            .await?;
+
+        if header.idn != u16::from(idn) {
+            fmt::error!(
+                "Slave {:#06x} sent SoE response for {}, expected {}",
+                self.configured_address,
+                Idn::from(header.idn),
+                idn
+            );
+
+            return Err(Error::Soe(SoeError::InvalidResponse));
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} SoE wrote {} bytes to {}",
+            self.configured_address,
+            data.len(),
+            idn
+        );
+
+        Ok(())
+    }
+
+    /// Read a list of IDNs from a drive using SoE (Servo drive profile over EtherCAT), e.g.
+    /// [`Idn::ALL_OPERATION_DATA`] (`S-0-0017`) to find every parameter the drive supports.
+    ///
+    /// `buf` must be large enough to hold the whole list, plus 4 bytes for its length header. Each
+    /// entry takes 2 bytes.
+    ///
+    /// # Examples
+    ///
+    /// Print the value of every parameter in a drive, e.g. to make a backup.
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, Idn, PduStorage, SoeElements, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// let mut list = [0u8; 4096];
+    /// let mut value = [0u8; 1024];
+    ///
+    /// for idn in slave.idn_list(0, Idn::ALL_OPERATION_DATA, &mut list).await? {
+    ///     let len = slave.idn_read(0, idn, SoeElements::VALUE, &mut value).await?;
+    ///
+    ///     log::info!("{}: {:02x?}", idn, &value[0..len]);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn idn_list<'buf>(
+        &self,
+        drive_no: u8,
+        idn: impl Into<Idn>,
+        buf: &'buf mut [u8],
+    ) -> Result<IdnList<'buf>, Error> {
+        let idn = idn.into();
+
+        let len = self
+            .idn_read(drive_no, idn, SoeElements::VALUE, buf)
+            .await?;
+
+        let list = IdnList::new(&buf[0..len])?;
+
+        fmt::debug!(
+            "Slave {:#06x} drive {} list {} has {} entries",
+            self.configured_address,
+            drive_no,
+            idn,
+            list.len()
+        );
+
+        Ok(list)
+    }
+
+    /// Execute a procedure command on a drive using SoE (Servo drive profile over EtherCAT), e.g.
+    /// `S-0-0099` to reset class 1 diagnostics.
+    ///
+    /// The command is set and enabled, then its data state is polled until the drive reports that
+    /// it has finished executing. The command is cancelled afterwards, whether or not it
+    /// succeeded, so it can be run again.
+    ///
+    /// # Errors
+    ///
+    /// An [`SoeError::CommandFailed`] is returned if the drive reports that the command could not
+    /// be executed. [`Error::Timeout`] is returned if the command hasn't finished after
+    /// [`Timeouts::soe_procedure`](crate::Timeouts::soe_procedure).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, Idn, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// // S-0-0099: Reset class 1 diagnostics
+    /// slave.idn_command(0, Idn::s(0, 99)).await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn idn_command(&self, drive_no: u8, idn: impl Into<Idn>) -> Result<(), Error> {
+        let idn = idn.into();
+
+        let start = CommandStatus::SET | CommandStatus::EXECUTE;
+
+        self.idn_write(
+            drive_no,
+            idn,
+            SoeElements::VALUE,
+            &start.bits().to_le_bytes(),
+        )
+        .await?;
+
+        let status = async {
+            loop {
+                let mut buf = [0u8; 2];
+
+                self.idn_read(drive_no, idn, SoeElements::DATA_STATE, &mut buf)
+                    .await?;
+
+                let status = CommandStatus::from_bits_truncate(u16::from_le_bytes(buf));
+
+                if !status.contains(CommandStatus::IN_PROGRESS) {
+                    break Ok(status);
+                }
+
+                self.client.timeouts.loop_tick().await;
+            }
+        }
+        .timeout(self.client.timeouts.soe_procedure)
+        .await;
+
+        // Cancel the command even if it failed, but report the original error first.
+        let cancel = self
+            .idn_write(drive_no, idn, SoeElements::VALUE, &0u16.to_le_bytes())
+            .await;
+
+        let status = status?;
+
+        if status.contains(CommandStatus::ERROR) {
+            fmt::error!(
+                "Slave {:#06x} drive {} procedure command {} failed",
+                self.configured_address,
+                drive_no,
+                idn
+            );
+
+            return Err(Error::Soe(SoeError::CommandFailed { idn }));
+        }
+
+        fmt::debug!(
+            "Slave {:#06x} drive {} executed procedure command {}",
+            self.configured_address,
+            drive_no,
+            idn
+        );
+
+        cancel
+    }
+
+    /// Check that the SubDevice supports SoE, returning the maximum number of data bytes in a
+    /// single request fragment.
+    fn soe_write_capacity(&self) -> Result<usize, Error> {
+        let config = &self.state.config.mailbox;
+
+        if !config.supported_protocols.contains(MailboxProtocols::SOE) {
+            return Err(Error::Soe(SoeError::NotSupported));
+        }
+
+        let (Some(_read), Some(write)) = (config.read, config.write) else {
+            return Err(Error::Mailbox(MailboxError::NoMailbox));
+        };
+
+        let write_len = usize::from(write.len).saturating_sub(SoeHeader::PACKED_LEN);
+
+        if write_len == 0 {
+            return Err(Error::Mailbox(MailboxError::NoMailbox));
+        }
+
+        Ok(write_len)
+    }
+
+    /// Send a single SoE request fragment, returning the mailbox to read the response from.
+    async fn send_soe(
+        &self,
+        op_code: SoeOpCode,
+        drive_no: u8,
+        elements: SoeElements,
+        idn: u16,
+        incomplete: bool,
+        data: &[u8],
+    ) -> Result<Mailbox, Error> {
+        let (read_mailbox, write_mailbox) = self.mailboxes().await?;
+
+        let header = SoeHeader::new(
+            self.mailbox_counter(),
+            op_code,
+            drive_no,
+            elements,
+            idn,
+            incomplete,
+            u16::try_from(data.len())?,
+        );
+
+        self.write_mailbox(&write_mailbox, (header, data)).await?;
+
+        Ok(read_mailbox)
+    }
+
+    /// Read a single SoE response fragment, checking that it is the expected kind of response for
+    /// the given drive.
+    ///
+    /// SoE error responses are returned as an [`SoeError::Aborted`].
+    async fn soe_response(
+        &self,
+        read_mailbox: &Mailbox,
+        expected: SoeOpCode,
+        drive_no: u8,
+    ) -> Result<(SoeHeader, MailboxData<'_>), Error> {
+        let (header, response) = loop {
+            let response = self
+                .coe_response(read_mailbox, self.client.timeouts.soe_response)
+                .await?;
+
+            let header = SoeHeader::unpack_from_slice(&response)?;
+
+            // Drives may send a notification whenever a procedure command's status changes. The
+            // status is polled explicitly instead, so these can be skipped.
+            if header.header.mailbox_type == MailboxType::Soe
+                && header.op_code == SoeOpCode::Notification
+            {
+                fmt::debug!(
+                    "Slave {:#06x} drive {} sent SoE notification for {}",
+                    self.configured_address,
+                    header.drive_no,
+                    Idn::from(header.idn)
+                );
+
+                continue;
+            }
+
+            break (header, response);
+        };
+
+        if header.header.mailbox_type != MailboxType::Soe {
+            fmt::error!(
+                "Invalid SoE response. Type: {:?}",
+                header.header.mailbox_type
+            );
+
+            return Err(Error::Soe(SoeError::InvalidResponse));
+        }
+
+        if header.error {
+            let code = SoeErrorCode::unpack_from_slice(soe_data(&header, &response))
+                .unwrap_or(SoeErrorCode::General);
+
+            fmt::error!(
+                "SoE error for slave {:#06x} drive {}: {}",
+                self.configured_address,
+                header.drive_no,
+                code
+            );
+
+            return Err(Error::Soe(SoeError::Aborted { code }));
+        }
+
+        if header.op_code != expected || header.drive_no != drive_no {
+            fmt::error!(
+                "Slave {:#06x} sent SoE {:?} for drive {}, expected {:?} for drive {}",
+                self.configured_address,
+                header.op_code,
+                header.drive_no,
+                expected,
+                drive_no
+            );
+
+            return Err(Error::Soe(SoeError::InvalidResponse));
+        }
+
+        Ok((header, response))
+    }
+}
+
+/// Get the data following the SoE header in a response.
+fn soe_data<'buf>(header: &SoeHeader, response: &'buf [u8]) -> &'buf [u8] {
+    // Mailbox length includes the 4 byte SoE header.
+    let data_len = usize::from(header.header.length)
+        .saturating_sub(4)
+        .min(response.len().saturating_sub(SoeHeader::PACKED_LEN));
+
+    &response[SoeHeader::PACKED_LEN..][..data_len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{slave::mock::MockSubDevice, ClientConfig, Timeouts};
+
+    /// SoE mailbox data for drive 0 with the given header fields and data.
+    fn soe(op_code: SoeOpCode, incomplete: bool, error: bool, idn: u16, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![
+            u8::from(op_code) | u8::from(incomplete) << 3 | u8::from(error) << 4,
+            SoeElements::VALUE.bits(),
+        ];
+
+        packet.extend_from_slice(&idn.to_le_bytes());
+        packet.extend_from_slice(data);
+
+        packet
+    }
+
+    fn idn(request: &[u8]) -> u16 {
+        u16::from_le_bytes([request[2], request[3]])
+    }
+
+    #[tokio::test]
+    async fn read_fragmented_response() {
+        let value = (0..=255u8).cycle().take(250).collect::<Vec<_>>();
+
+        let (client, mock) = MockSubDevice::start(Timeouts::default(), ClientConfig::default(), {
+            let value = value.clone();
+
+            move |mailbox_type, request| {
+                assert_eq!(mailbox_type, MailboxType::Soe);
+                assert_eq!(request[0] & 0x07, u8::from(SoeOpCode::ReadRequest));
+
+                let (first, rest) = value.split_at(100);
+                let (second, last) = rest.split_at(100);
+
+                vec![
+                    // Fragments but the last hold the number of fragments left instead of the IDN
+                    (
+                        MailboxType::Soe,
+                        soe(SoeOpCode::ReadResponse, true, false, 2, first),
+                    ),
+                    // Notifications are skipped
+                    (
+                        MailboxType::Soe,
+                        soe(SoeOpCode::Notification, false, false, idn(request), &[]),
+                    ),
+                    (
+                        MailboxType::Soe,
+                        soe(SoeOpCode::ReadResponse, true, false, 1, second),
+                    ),
+                    (
+                        MailboxType::Soe,
+                        soe(SoeOpCode::ReadResponse, false, false, idn(request), last),
+                    ),
+                ]
+            }
+        });
+
+        let slave = MockSubDevice::slave(MailboxProtocols::SOE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let mut buf = [0u8; 256];
+
+        let len = slave
+            .idn_read(0, Idn::s(0, 17), SoeElements::VALUE, &mut buf)
+            .await;
+
+        assert_eq!(len, Ok(value.len()));
+        assert_eq!(&buf[0..value.len()], value.as_slice());
+        assert_eq!(mock.requests().len(), 1);
+
+        // Buffer too short for the whole response
+        assert_eq!(
+            slave
+                .idn_read(0, Idn::s(0, 17), SoeElements::VALUE, &mut buf[0..150])
+                .await,
+            Err(Error::Soe(SoeError::TooLong))
+        );
+    }
+
+    #[tokio::test]
+    async fn discard_repeated_fragment() {
+        let value = (0..=255u8).cycle().take(200).collect::<Vec<_>>();
+
+        let (client, mock) = MockSubDevice::start(Timeouts::default(), ClientConfig::default(), {
+            let value = value.clone();
+
+            move |_mailbox_type, request| {
+                let (first, last) = value.split_at(100);
+
+                vec![
+                    (
+                        MailboxType::Soe,
+                        soe(SoeOpCode::ReadResponse, true, false, 1, first),
+                    ),
+                    (
+                        MailboxType::Soe,
+                        soe(SoeOpCode::ReadResponse, false, false, idn(request), last),
+                    ),
+                ]
+            }
+        });
+
+        let slave = MockSubDevice::slave(MailboxProtocols::SOE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        // The first fragment is read twice with the same counter, so the second copy is discarded.
+        mock.duplicate_reads(1);
+
+        let mut buf = [0u8; 256];
+
+        let len = slave
+            .idn_read(0, Idn::s(0, 17), SoeElements::VALUE, &mut buf)
+            .await;
+
+        assert_eq!(len, Ok(value.len()));
+        assert_eq!(&buf[0..value.len()], value.as_slice());
+    }
+
+    #[tokio::test]
+    async fn write_fragmented_request() {
+        let (client, mock) = MockSubDevice::start(
+            Timeouts::default(),
+            ClientConfig::default(),
+            |mailbox_type, request| {
+                assert_eq!(mailbox_type, MailboxType::Soe);
+                assert_eq!(request[0] & 0x07, u8::from(SoeOpCode::WriteRequest));
+
+                // Only the last fragment is responded to
+                if request[0] & 0x08 != 0 {
+                    return Vec::new();
+                }
+
+                vec![(
+                    MailboxType::Soe,
+                    soe(SoeOpCode::WriteResponse, false, false, idn(request), &[]),
+                )]
+            },
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::SOE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let data = (0..=255u8).cycle().take(300).collect::<Vec<_>>();
+
+        assert_eq!(
+            slave
+                .idn_write(0, Idn::p(0, 1), SoeElements::VALUE, &data)
+                .await,
+            Ok(())
+        );
+
+        let requests = mock.requests();
+
+        // 118 bytes of data fit in each fragment of the 128 byte mailbox
+        assert_eq!(
+            requests
+                .iter()
+                .map(|(_, request)| idn(request))
+                .collect::<Vec<_>>(),
+            [2, 1, u16::from(Idn::p(0, 1))]
+        );
+
+        let written = requests
+            .iter()
+            .flat_map(|(_, request)| request[4..].iter().copied())
+            .collect::<Vec<_>>();
+
+        assert_eq!(written, data);
+    }
+
+    #[tokio::test]
+    async fn error_response() {
+        let (client, _mock) = MockSubDevice::start(
+            Timeouts::default(),
+            ClientConfig::default(),
+            |_mailbox_type, request| {
+                vec![(
+                    MailboxType::Soe,
+                    soe(
+                        SoeOpCode::WriteResponse,
+                        false,
+                        true,
+                        idn(request),
+                        &0x700au16.to_le_bytes(),
+                    ),
+                )]
+            },
+        );
+
+        let slave = MockSubDevice::slave(MailboxProtocols::SOE);
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        assert_eq!(
+            slave
+                .idn_write(0, Idn::s(0, 91), SoeElements::VALUE, &[0; 4])
+                .await,
+            Err(Error::Soe(SoeError::Aborted {
+                code: SoeErrorCode::DataCyclic
+            }))
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
     eeprom::types::{MailboxProtocols, SyncManagerType},
     pdi::PdiSegment,
+    slave::configuration::PdiLayout,
+    sync_manager_channel::SyncManagerChannel,
 };
 use core::fmt::{self, Debug};
 
@@ -47,6 +49,7 @@ impl Debug for SlaveIdentity {
 pub struct SlaveConfig {
     pub io: IoRanges,
     pub mailbox: MailboxConfig,
+    pub layout: PdiLayout,
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -67,6 +70,16 @@ pub struct Mailbox {
     pub(in crate::slave) sync_manager: u8,
 }
 
+/// The mailboxes used while a SubDevice is in the `BOOT` state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BootMailbox {
+    pub(in crate::slave) read: Mailbox,
+    pub(in crate::slave) write: Mailbox,
+    /// Sync manager index and configuration of the standard mailboxes, restored when leaving
+    /// `BOOT`.
+    pub(in crate::slave) previous: [(u8, SyncManagerChannel); 2],
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct IoRanges {
     pub input: PdiSegment,
@@ -0,0 +1,167 @@
+use super::{types::Mailbox, Slave, SlaveRef};
+use crate::{
+    eeprom::types::MailboxProtocols,
+    error::{Error, MailboxError, VoeError},
+    fmt,
+    mailbox::MailboxType,
+    voe::{VoeHeader, VoeMailboxHeader},
+};
+use core::ops::Deref;
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized};
+
+impl<'a, S> SlaveRef<'a, S>
+where
+    S: Deref<Target = Slave>,
+{
+    /// Send a vendor specific (VoE) mailbox message to the SubDevice.
+    ///
+    /// The mailbox and VoE headers are added by EtherCrab, so `data` holds only the vendor's
+    /// payload. It must fit in the SubDevice's mailbox along with the 12 bytes of headers.
+    pub async fn voe_send(&self, header: VoeHeader, data: &[u8]) -> Result<(), Error> {
+        self.voe_write_capacity(data)?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        self.send_voe(header, data).await?;
+
+        Ok(())
+    }
+
+    /// Wait for a vendor specific (VoE) mailbox message from the SubDevice, returning its VoE
+    /// header and the number of payload bytes copied into `buf`.
+    ///
+    /// [`Error::Timeout`] is returned if no message is received within
+    /// [`Timeouts::mailbox_response`](crate::Timeouts::mailbox_response).
+    pub async fn voe_receive(&self, buf: &mut [u8]) -> Result<(VoeHeader, usize), Error> {
+        self.voe_write_capacity(&[])?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self
+            .state
+            .config
+            .mailbox
+            .read
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        self.voe_response(&read_mailbox, buf).await
+    }
+
+    /// Send a vendor specific (VoE) mailbox message and wait for the SubDevice's reply, returning
+    /// the reply's VoE header and the number of payload bytes copied into `buf`.
+    ///
+    /// The mailbox is locked for the whole exchange, so other mailbox traffic such as SDO reads
+    /// can't be received in place of the reply.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts, VoeHeader };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let slave = group.slave(&client, 0)?;
+    ///
+    /// let header = VoeHeader {
+    ///     vendor_id: slave.identity().vendor_id,
+    ///     vendor_type: 0x0001,
+    /// };
+    ///
+    /// let mut buf = [0u8; 128];
+    ///
+    /// let (reply, len) = slave.voe_request(header, &[0x01, 0x02], &mut buf).await?;
+    ///
+    /// log::info!("Reply type {:#06x}: {:02x?}", reply.vendor_type, &buf[0..len]);
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn voe_request(
+        &self,
+        header: VoeHeader,
+        data: &[u8],
+        buf: &mut [u8],
+    ) -> Result<(VoeHeader, usize), Error> {
+        self.voe_write_capacity(data)?;
+
+        let _lock = self.lock_mailbox().await?;
+
+        let read_mailbox = self.send_voe(header, data).await?;
+
+        self.voe_response(&read_mailbox, buf).await
+    }
+
+    /// Check that the SubDevice supports VoE and that `data` fits in its mailbox.
+    fn voe_write_capacity(&self, data: &[u8]) -> Result<(), Error> {
+        let config = &self.state.config.mailbox;
+
+        if !config.supported_protocols.contains(MailboxProtocols::VOE) {
+            return Err(Error::Voe(VoeError::NotSupported));
+        }
+
+        let write = config
+            .write
+            .ok_or(Error::Mailbox(MailboxError::NoMailbox))?;
+
+        if VoeMailboxHeader::PACKED_LEN + data.len() > usize::from(write.len) {
+            return Err(Error::Voe(VoeError::TooLong));
+        }
+
+        Ok(())
+    }
+
+    /// Send a VoE message, returning the mailbox to read the response from.
+    async fn send_voe(&self, header: VoeHeader, data: &[u8]) -> Result<Mailbox, Error> {
+        let (read_mailbox, write_mailbox) = self.mailboxes().await?;
+
+        let header =
+            VoeMailboxHeader::new(self.mailbox_counter(), header, u16::try_from(data.len())?);
+
+        self.write_mailbox(&write_mailbox, (header, data)).await?;
+
+        Ok(read_mailbox)
+    }
+
+    /// Read a VoE message, copying its payload into `buf`.
+    async fn voe_response(
+        &self,
+        read_mailbox: &Mailbox,
+        buf: &mut [u8],
+    ) -> Result<(VoeHeader, usize), Error> {
+        let response = self
+            .coe_response(read_mailbox, self.client.timeouts.mailbox_response)
+            .await?;
+
+        let header = VoeMailboxHeader::unpack_from_slice(&response)?;
+
+        if header.header.mailbox_type != MailboxType::VendorSpecific {
+            fmt::error!(
+                "Invalid VoE response. Type: {:?}",
+                header.header.mailbox_type
+            );
+
+            return Err(Error::Voe(VoeError::InvalidResponse));
+        }
+
+        // Mailbox length includes the 6 byte VoE header.
+        let len = usize::from(header.header.length)
+            .saturating_sub(6)
+            .min(response.len().saturating_sub(VoeMailboxHeader::PACKED_LEN));
+
+        buf.get_mut(0..len)
+            .ok_or(Error::Voe(VoeError::TooLong))?
+            .copy_from_slice(&response[VoeMailboxHeader::PACKED_LEN..][..len]);
+
+        fmt::trace!(
+            "Slave {:#06x} VoE received {} bytes, vendor {:#010x} type {:#06x}",
+            self.configured_address,
+            len,
+            header.voe.vendor_id,
+            header.voe.vendor_type
+        );
+
+        Ok((header.voe, len))
+    }
+}
@@ -6,11 +6,13 @@ use crate::{
     Client, SlaveGroup,
 };
 use atomic_refcell::AtomicRefCell;
+use core::ops::Range;
 
 #[derive(Debug)]
 struct GroupInnerRef<'a> {
     slaves: &'a mut [AtomicRefCell<Slave>],
     pdi_start: &'a mut PdiOffset,
+    fixed_pdi_start: Option<PdiOffset>,
 }
 
 // TODO: Prove if this is safe. All this stuff is internal to the crate and short lived so I think
@@ -40,11 +42,28 @@ impl<'a> SlaveGroupRef<'a> {
                 GroupInnerRef {
                     slaves: &mut inner.slaves,
                     pdi_start: &mut inner.pdi_start,
+                    fixed_pdi_start: inner.fixed_pdi_start,
                 }
             },
         }
     }
 
+    /// The logical address range reserved for this group's PDI if it was placed at a fixed address
+    /// with [`SlaveGroup::with_logical_address`].
+    pub(crate) fn fixed_logical_range(&self) -> Option<Range<u32>> {
+        self.inner.fixed_pdi_start.map(|start| {
+            start.start_address..start.start_address.saturating_add(self.max_pdi_len as u32)
+        })
+    }
+
+    /// The offset at which this group's PDI will start, given the end of the previous group's PDI
+    /// and the ranges reserved by groups with fixed addresses.
+    pub(crate) fn pdi_start(&self, previous: PdiOffset, reserved: &[Range<u32>]) -> PdiOffset {
+        self.inner
+            .fixed_pdi_start
+            .unwrap_or_else(|| previous.skip_reserved(self.max_pdi_len as u32, reserved))
+    }
+
     /// Initialise all slaves in the group and place them in PRE-OP.
     // Clippy: shush
     #[allow(clippy::wrong_self_convention)]
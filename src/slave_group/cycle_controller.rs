@@ -0,0 +1,161 @@
+use core::time::Duration;
+
+/// A PI controller that aligns the MainDevice process data cycle with the DC reference clock.
+///
+/// The controller is given the DC system time returned by each process data exchange, e.g.
+/// [`CycleInfo::dc_system_time`](crate::slave_group::CycleInfo::dc_system_time), and computes how
+/// long to wait before the next exchange so that frames reach the DC reference SubDevice
+/// `sync0_shift` after each SYNC0 pulse.
+///
+/// Unlike [`CycleInfo::next_cycle_wait`](crate::slave_group::CycleInfo::next_cycle_wait), which
+/// corrects the whole phase error every cycle, the error is smoothed so that host timer jitter isn't
+/// passed straight on to the next cycle. The integral term removes the constant phase error caused
+/// by the host clock drifting relative to the DC reference clock.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{error::Error, slave_group::{CycleInfo, DcCycleController, HasDc}, Client, SlaveGroup};
+/// # use std::time::{Duration, Instant};
+/// # async fn cycle(client: &Client<'_>, group: SlaveGroup<16, 64, ethercrab::slave_group::Op, HasDc>) -> Result<(), Error> {
+/// let cycle_time = Duration::from_millis(1);
+///
+/// // Values must match those passed in `DcConfiguration`
+/// let mut controller = DcCycleController::new(cycle_time, cycle_time / 2);
+///
+/// loop {
+///     let now = Instant::now();
+///
+///     let (_wkc, CycleInfo { dc_system_time, .. }) = group.tx_rx_dc(client).await?;
+///
+///     smol::Timer::at(now + controller.next_cycle_wait(dc_system_time)).await;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct DcCycleController {
+    /// Cycle time in nanoseconds.
+    cycle_time: i64,
+    /// Target offset into the cycle in nanoseconds.
+    shift: i64,
+    kp: f32,
+    ki: f32,
+    /// Accumulated phase error in nanoseconds.
+    integral: f32,
+}
+
+impl DcCycleController {
+    /// Default proportional gain.
+    pub const DEFAULT_KP: f32 = 0.1;
+
+    /// Default integral gain.
+    pub const DEFAULT_KI: f32 = 0.01;
+
+    /// Create a new controller with default gains.
+    ///
+    /// `cycle_time` and `sync0_shift` should be the same as
+    /// [`DcConfiguration::sync0_period`](crate::slave_group::DcConfiguration::sync0_period) and
+    /// [`DcConfiguration::sync0_shift`](crate::slave_group::DcConfiguration::sync0_shift).
+    pub fn new(cycle_time: Duration, sync0_shift: Duration) -> Self {
+        Self {
+            cycle_time: cycle_time.as_nanos() as i64,
+            shift: sync0_shift.as_nanos() as i64,
+            kp: Self::DEFAULT_KP,
+            ki: Self::DEFAULT_KI,
+            integral: 0.0,
+        }
+    }
+
+    /// Set the proportional and integral gains.
+    ///
+    /// Higher gains correct phase errors more quickly, but pass more host timer jitter on to the
+    /// cycle time. A `kp` of `1.0` and `ki` of `0.0` corrects the whole error every cycle, the same
+    /// as [`CycleInfo::next_cycle_wait`](crate::slave_group::CycleInfo::next_cycle_wait).
+    pub fn with_gains(self, kp: f32, ki: f32) -> Self {
+        Self { kp, ki, ..self }
+    }
+
+    /// Clear the accumulated error, e.g. after the cyclic task was paused.
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+    }
+
+    /// The difference in nanoseconds between the given DC system time's offset into the cycle and
+    /// the target offset, in the range `-cycle_time / 2..cycle_time / 2`.
+    ///
+    /// A positive error means the frame arrived late in the cycle.
+    pub fn phase_error(&self, dc_system_time: u64) -> i64 {
+        let offset = (dc_system_time % self.cycle_time as u64) as i64;
+
+        let half = self.cycle_time / 2;
+
+        (offset - self.shift + half).rem_euclid(self.cycle_time) - half
+    }
+
+    /// Compute how long to wait from the start of this cycle before starting the next one, given
+    /// the DC system time returned by this cycle's process data exchange.
+    ///
+    /// The returned duration is never longer than two cycles.
+    pub fn next_cycle_wait(&mut self, dc_system_time: u64) -> Duration {
+        let error = self.phase_error(dc_system_time) as f32;
+
+        let limit = self.cycle_time as f32;
+
+        // Limit the integral term's contribution to one cycle to stop windup, e.g. while the
+        // cyclic task is delayed.
+        let integral_limit = limit / self.ki.max(f32::EPSILON);
+
+        self.integral = (self.integral + error).clamp(-integral_limit, integral_limit);
+
+        let correction = (self.kp * error + self.ki * self.integral).clamp(-limit, limit);
+
+        let wait = (self.cycle_time as f32 - correction).clamp(0.0, 2.0 * limit);
+
+        Duration::from_nanos(wait as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phase_error_wraps() {
+        let c = DcCycleController::new(Duration::from_micros(1000), Duration::from_micros(100));
+
+        assert_eq!(c.phase_error(1_000_100_000), 0);
+        assert_eq!(c.phase_error(1_000_150_000), 50_000);
+        assert_eq!(c.phase_error(1_000_050_000), -50_000);
+        // Just before the target offset in the next cycle
+        assert_eq!(c.phase_error(1_000_900_000), -200_000);
+        assert_eq!(c.phase_error(1_000_500_000), 400_000);
+    }
+
+    #[test]
+    fn converges_with_drifting_host_clock() {
+        let cycle_time = Duration::from_micros(1000);
+        let shift = Duration::from_micros(500);
+
+        let mut c = DcCycleController::new(cycle_time, shift);
+
+        // DC time the first frame arrives at the reference
+        let mut dc_time = 1_000_000_123_456u64;
+
+        for i in 0..2000 {
+            let wait = c.next_cycle_wait(dc_time);
+
+            // The host clock runs 200ppm slow relative to the DC clock, plus some jitter.
+            let jitter = [0i64, 3_000, -2_000, 1_000][i % 4];
+
+            dc_time =
+                (dc_time as i64 + wait.as_nanos() as i64 + wait.as_nanos() as i64 / 5_000 + jitter)
+                    as u64;
+        }
+
+        assert!(
+            c.phase_error(dc_time).abs() < 5_000,
+            "phase error {} ns",
+            c.phase_error(dc_time)
+        );
+    }
+}
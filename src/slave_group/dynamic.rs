@@ -0,0 +1,228 @@
+use super::{Init, NoDc, Op, PreOp, PreOpPdi, SafeOp, SlaveGroup};
+use crate::{error::Error, slave::pdi::SlavePdi, Client, SlaveRef, SlaveState};
+
+/// A [`SlaveGroup`] whose state is tracked at runtime instead of with a typestate.
+///
+/// The typestate API makes invalid transitions a compile error, but the group's type changes with
+/// every transition, so it can't easily be kept in a collection, a struct field or behind a trait
+/// object for the life of an application. `DynSlaveGroup` holds a group in any state, and can be
+/// moved between states with [`into_state`](DynSlaveGroup::into_state). Operations that aren't
+/// possible in the current state return [`Error::GroupState`].
+///
+/// A typed group can be converted into a `DynSlaveGroup` with [`From`], and matched on to get the
+/// typed group back.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethercrab::{error::Error, slave_group::DynSlaveGroup, Client, SlaveGroup, SlaveState};
+///
+/// # async fn run(client: &Client<'_>) -> Result<(), Error> {
+/// let groups = client
+///     .init::<16, _>(ethercrab::std::ethercat_now, |groups: &[SlaveGroup<8, 64>; 2], slave| {
+///         Ok(&groups[usize::from(slave.name() != "EK1100")])
+///     })
+///     .await?
+///     .map(DynSlaveGroup::from);
+///
+/// let mut running = Vec::new();
+///
+/// for group in groups {
+///     running.push(group.into_state(client, SlaveState::Op).await?);
+/// }
+///
+/// loop {
+///     for group in running.iter() {
+///         group.tx_rx(client).await?;
+///     }
+/// # break;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub enum DynSlaveGroup<const MAX_SLAVES: usize, const MAX_PDI: usize, DC = NoDc> {
+    /// The group is in INIT.
+    Init(SlaveGroup<MAX_SLAVES, MAX_PDI, Init, DC>),
+    /// The group is in PRE-OP, and its PDI has not been configured.
+    PreOp(SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, DC>),
+    /// The group is in PRE-OP with a configured PDI.
+    PreOpPdi(SlaveGroup<MAX_SLAVES, MAX_PDI, PreOpPdi, DC>),
+    /// The group is in SAFE-OP.
+    SafeOp(SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, DC>),
+    /// The group is in OP.
+    Op(SlaveGroup<MAX_SLAVES, MAX_PDI, Op, DC>),
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> DynSlaveGroup<MAX_SLAVES, MAX_PDI, DC> {
+    /// Get the state the group was last transitioned to.
+    ///
+    /// This is not read from the SubDevices, so won't reflect e.g. a SubDevice dropping out of OP.
+    pub fn state(&self) -> SlaveState {
+        match self {
+            Self::Init(_) => SlaveState::Init,
+            Self::PreOp(_) | Self::PreOpPdi(_) => SlaveState::PreOp,
+            Self::SafeOp(_) => SlaveState::SafeOp,
+            Self::Op(_) => SlaveState::Op,
+        }
+    }
+
+    /// Get the number of slave devices in this group.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Init(group) => group.len(),
+            Self::PreOp(group) => group.len(),
+            Self::PreOpPdi(group) => group.len(),
+            Self::SafeOp(group) => group.len(),
+            Self::Op(group) => group.len(),
+        }
+    }
+
+    /// Check whether this slave group is empty or not.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Transition the group to the given state, going through each state in between, e.g. OP to
+    /// SAFE-OP to PRE-OP.
+    ///
+    /// A group in PRE-OP has its PDI configured on the way to SAFE-OP. Going back down to PRE-OP
+    /// keeps the PDI.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::GroupState`] if the group can't reach `state`, e.g. from INIT or if `state`
+    /// is BOOT. The group is consumed if any transition fails, like the typestate API.
+    pub async fn into_state(
+        mut self,
+        client: &Client<'_>,
+        state: SlaveState,
+    ) -> Result<Self, Error> {
+        const STATES: [SlaveState; 4] = [
+            SlaveState::Init,
+            SlaveState::PreOp,
+            SlaveState::SafeOp,
+            SlaveState::Op,
+        ];
+
+        let position = |state| STATES.iter().position(|s| *s == state);
+
+        let desired = position(state).ok_or(Error::GroupState(self.state()))?;
+
+        loop {
+            // Every group state is in `STATES`
+            let Some(current) = position(self.state()) else {
+                break Err(Error::GroupState(self.state()));
+            };
+
+            let up = desired > current;
+
+            self = match self {
+                group if desired == current => break Ok(group),
+                Self::Init(_) => break Err(Error::GroupState(SlaveState::Init)),
+                Self::PreOp(group) if up => group.into_safe_op(client).await?.into(),
+                Self::PreOp(group) => group.into_init(client).await?.into(),
+                Self::PreOpPdi(group) if up => group.into_safe_op(client).await?.into(),
+                Self::PreOpPdi(group) => group.into_init(client).await?.into(),
+                Self::SafeOp(group) if up => group.into_op(client).await?.into(),
+                Self::SafeOp(group) => group.into_pre_op(client).await?.into(),
+                Self::Op(group) => group.into_safe_op(client).await?.into(),
+            };
+        }
+    }
+
+    /// Drive the slave group's inputs and outputs.
+    ///
+    /// See [`SlaveGroup::tx_rx`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::GroupState`] if the group has no PDI in its current state.
+    pub async fn tx_rx<'sto>(&self, client: &'sto Client<'sto>) -> Result<u16, Error> {
+        match self {
+            Self::PreOpPdi(group) => group.tx_rx(client).await,
+            Self::SafeOp(group) => group.tx_rx(client).await,
+            Self::Op(group) => group.tx_rx(client).await,
+            _ => Err(Error::GroupState(self.state())),
+        }
+    }
+
+    /// Borrow an individual slave device with access to its process data.
+    ///
+    /// See [`SlaveGroup::slave`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::GroupState`] if the group has no PDI in its current state.
+    pub fn slave<'client, 'group>(
+        &'group self,
+        client: &'client Client<'client>,
+        index: usize,
+    ) -> Result<SlaveRef<'client, SlavePdi<'group>>, Error> {
+        match self {
+            Self::PreOpPdi(group) => group.slave(client, index),
+            Self::SafeOp(group) => group.slave(client, index),
+            Self::Op(group) => group.slave(client, index),
+            _ => Err(Error::GroupState(self.state())),
+        }
+    }
+}
+
+macro_rules! from_typestate {
+    ($($state:ident),+) => {
+        $(
+            impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC>
+                From<SlaveGroup<MAX_SLAVES, MAX_PDI, $state, DC>>
+                for DynSlaveGroup<MAX_SLAVES, MAX_PDI, DC>
+            {
+                fn from(group: SlaveGroup<MAX_SLAVES, MAX_PDI, $state, DC>) -> Self {
+                    Self::$state(group)
+                }
+            }
+        )+
+    };
+}
+
+from_typestate!(Init, PreOp, PreOpPdi, SafeOp, Op);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future::block_on;
+
+    #[test]
+    fn invalid_state() {
+        static PDU_STORAGE: crate::PduStorage<1, 64> = crate::PduStorage::new();
+
+        let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().unwrap();
+
+        let client = Client::new(pdu_loop, crate::Timeouts::default(), Default::default());
+
+        let group = DynSlaveGroup::from(SlaveGroup::<4, 8, PreOp>::default());
+
+        assert_eq!(group.state(), SlaveState::PreOp);
+        assert!(group.is_empty());
+        assert_eq!(
+            block_on(group.tx_rx(&client)),
+            Err(Error::GroupState(SlaveState::PreOp))
+        );
+        assert_eq!(
+            group.slave(&client, 0).err(),
+            Some(Error::GroupState(SlaveState::PreOp))
+        );
+
+        // Already in the requested state, so no PDUs are sent
+        let group = block_on(group.into_state(&client, SlaveState::PreOp)).unwrap();
+
+        assert_eq!(
+            block_on(group.into_state(&client, SlaveState::Bootstrap)).err(),
+            Some(Error::GroupState(SlaveState::PreOp))
+        );
+
+        let group = DynSlaveGroup::from(SlaveGroup::<4, 8, Init>::default());
+
+        assert_eq!(
+            block_on(group.into_state(&client, SlaveState::PreOp)).err(),
+            Some(Error::GroupState(SlaveState::Init))
+        );
+    }
+}
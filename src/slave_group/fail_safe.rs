@@ -0,0 +1,115 @@
+use crate::{fmt, FailSafe};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// Counters used to decide when a group's outputs are put into their fail-safe state.
+///
+/// Only the task exchanging process data updates the counters, so they are loaded and stored
+/// instead of using read-modify-write operations.
+#[derive(Debug, Default)]
+pub(crate) struct FailSafeState {
+    /// The number of exchanges since the application last updated its outputs.
+    cycles_since_update: AtomicU32,
+    /// The number of consecutive failed exchanges or unexpected working counters.
+    wkc_errors: AtomicU32,
+    /// Whether the most recent exchange was sent in the fail-safe state.
+    active: AtomicBool,
+}
+
+impl FailSafeState {
+    /// Record that the application has written this cycle's outputs.
+    pub(crate) fn outputs_updated(&self) {
+        self.cycles_since_update.store(0, Ordering::Relaxed);
+    }
+
+    /// Count an exchange that writes outputs, returning `true` if it should be sent in the
+    /// fail-safe state.
+    pub(crate) fn begin_exchange(&self, config: &FailSafe) -> bool {
+        let missed = self.cycles_since_update.load(Ordering::Relaxed);
+
+        self.cycles_since_update
+            .store(missed.saturating_add(1), Ordering::Relaxed);
+
+        let wkc_errors = self.wkc_errors.load(Ordering::Relaxed);
+
+        let active = config.missed_cycles.is_some_and(|limit| missed >= limit)
+            || config.wkc_errors.is_some_and(|limit| wkc_errors >= limit);
+
+        if self.active.swap(active, Ordering::Relaxed) != active {
+            if active {
+                fmt::warn!(
+                    "Group outputs entering fail-safe state: {} missed cycles, {} working counter errors",
+                    missed,
+                    wkc_errors
+                );
+            } else {
+                fmt::info!("Group outputs leaving fail-safe state");
+            }
+        }
+
+        active
+    }
+
+    /// Record whether an exchange that writes outputs succeeded with the expected working counter.
+    pub(crate) fn end_exchange(&self, ok: bool) {
+        let errors = if ok {
+            0
+        } else {
+            self.wkc_errors.load(Ordering::Relaxed).saturating_add(1)
+        };
+
+        self.wkc_errors.store(errors, Ordering::Relaxed);
+    }
+
+    /// Whether the most recent exchange was sent in the fail-safe state.
+    pub(crate) fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missed_cycles() {
+        let state = FailSafeState::default();
+
+        let config = FailSafe {
+            missed_cycles: Some(2),
+            ..FailSafe::default()
+        };
+
+        state.outputs_updated();
+        assert!(!state.begin_exchange(&config));
+        assert!(!state.begin_exchange(&config), "1 missed cycle");
+        assert!(state.begin_exchange(&config), "2 missed cycles");
+        assert!(state.is_active());
+
+        state.outputs_updated();
+        assert!(!state.begin_exchange(&config));
+        assert!(!state.is_active());
+    }
+
+    #[test]
+    fn wkc_errors() {
+        let state = FailSafeState::default();
+
+        let config = FailSafe {
+            wkc_errors: Some(2),
+            ..FailSafe::default()
+        };
+
+        assert!(!state.begin_exchange(&config));
+        state.end_exchange(false);
+        assert!(!state.begin_exchange(&config));
+        state.end_exchange(false);
+        assert!(state.begin_exchange(&config));
+        state.end_exchange(true);
+        assert!(!state.begin_exchange(&config));
+
+        // Disabled by default
+        state.end_exchange(false);
+        state.end_exchange(false);
+        assert!(!state.begin_exchange(&FailSafe::default()));
+    }
+}
@@ -0,0 +1,317 @@
+use crate::error::{Error, Item};
+use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
+use core::ops::Range;
+
+/// A single forced output byte.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct ForcedByte {
+    slave: usize,
+    byte_offset: usize,
+    /// Bits in the byte that are forced.
+    mask: u8,
+    /// Forced bit values. Bits not set in `mask` are always zero.
+    value: u8,
+}
+
+/// A table of output bits forced to fixed values, overriding whatever the application writes.
+///
+/// This mirrors the "force" feature of PLC tooling, e.g. to drive an actuator by hand while
+/// commissioning a machine. Bits stay forced until they are released, and can be forced and
+/// released from any task while the cyclic task keeps running. Nothing waits for the table, so
+/// any method returns [`Error::Borrow`] if another task is using it at the same moment, and can be
+/// retried.
+///
+/// The cyclic task applies the table to a group's outputs with
+/// [`SlaveGroup::apply_forces`](crate::SlaveGroup::apply_forces) every cycle, after the
+/// application has written its outputs and before calling
+/// [`tx_rx`](crate::SlaveGroup::tx_rx).
+///
+/// Offsets are relative to a SubDevice's outputs as returned by
+/// [`SlaveRef::outputs_raw`](crate::SlaveRef::outputs_raw), and `N` is the most output bytes that
+/// can have forced bits at once.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{error::Error, slave_group::{Op, OutputForces}, Client, SlaveGroup};
+/// # async fn tasks(client: &Client<'_>, group: SlaveGroup<16, 64, Op>) -> Result<(), Error> {
+/// static FORCES: OutputForces<8> = OutputForces::new();
+///
+/// // Commissioning task: turn on output 3 of the second SubDevice
+/// FORCES.force_bit(1, 0, 3, true)?;
+///
+/// // Cyclic task
+/// group.apply_forces(&FORCES)?;
+/// group.tx_rx(client).await?;
+///
+/// // Give control of the output back to the application
+/// FORCES.release_bit(1, 0, 3)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct OutputForces<const N: usize> {
+    forces: AtomicRefCell<heapless::Vec<ForcedByte, N>>,
+}
+
+impl<const N: usize> Default for OutputForces<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> OutputForces<N> {
+    /// Create a new table with no forced outputs.
+    pub const fn new() -> Self {
+        Self {
+            forces: AtomicRefCell::new(heapless::Vec::new()),
+        }
+    }
+
+    fn borrow(&self) -> Result<AtomicRef<'_, heapless::Vec<ForcedByte, N>>, Error> {
+        self.forces.try_borrow().map_err(|_| Error::Borrow)
+    }
+
+    fn borrow_mut(&self) -> Result<AtomicRefMut<'_, heapless::Vec<ForcedByte, N>>, Error> {
+        self.forces.try_borrow_mut().map_err(|_| Error::Borrow)
+    }
+
+    /// Force the bits set in `mask` of an output byte to the same bits in `value`.
+    ///
+    /// Bits of the byte that were already forced and are not in `mask` stay forced. Returns
+    /// [`Error::Capacity`] if `N` bytes already have forced bits.
+    pub fn force(
+        &self,
+        slave: usize,
+        byte_offset: usize,
+        mask: u8,
+        value: u8,
+    ) -> Result<(), Error> {
+        let mut forces = self.borrow_mut()?;
+
+        if let Some(forced) = forces
+            .iter_mut()
+            .find(|forced| forced.slave == slave && forced.byte_offset == byte_offset)
+        {
+            forced.mask |= mask;
+            forced.value = (forced.value & !mask) | (value & mask);
+
+            return Ok(());
+        }
+
+        if mask == 0 {
+            return Ok(());
+        }
+
+        forces
+            .push(ForcedByte {
+                slave,
+                byte_offset,
+                mask,
+                value: value & mask,
+            })
+            .map_err(|_| Error::Capacity(Item::PdiRegion))
+    }
+
+    /// Force a whole output byte to `value`.
+    pub fn force_byte(&self, slave: usize, byte_offset: usize, value: u8) -> Result<(), Error> {
+        self.force(slave, byte_offset, 0xff, value)
+    }
+
+    /// Force a single bit, from 0 to 7, of an output byte on or off.
+    pub fn force_bit(
+        &self,
+        slave: usize,
+        byte_offset: usize,
+        bit: u8,
+        value: bool,
+    ) -> Result<(), Error> {
+        self.force(
+            slave,
+            byte_offset,
+            1 << (bit % 8),
+            if value { 0xff } else { 0x00 },
+        )
+    }
+
+    /// Release the bits set in `mask` of an output byte, so the application's values are sent
+    /// again.
+    pub fn release(&self, slave: usize, byte_offset: usize, mask: u8) -> Result<(), Error> {
+        let mut forces = self.borrow_mut()?;
+
+        forces.retain_mut(|forced| {
+            if forced.slave == slave && forced.byte_offset == byte_offset {
+                forced.mask &= !mask;
+                forced.value &= !mask;
+            }
+
+            forced.mask != 0
+        });
+
+        Ok(())
+    }
+
+    /// Release a whole output byte.
+    pub fn release_byte(&self, slave: usize, byte_offset: usize) -> Result<(), Error> {
+        self.release(slave, byte_offset, 0xff)
+    }
+
+    /// Release a single bit, from 0 to 7, of an output byte.
+    pub fn release_bit(&self, slave: usize, byte_offset: usize, bit: u8) -> Result<(), Error> {
+        self.release(slave, byte_offset, 1 << (bit % 8))
+    }
+
+    /// Release every forced output.
+    pub fn release_all(&self) -> Result<(), Error> {
+        self.borrow_mut()?.clear();
+
+        Ok(())
+    }
+
+    /// The bits of an output byte that are currently forced, or zero if none are.
+    pub fn forced_mask(&self, slave: usize, byte_offset: usize) -> Result<u8, Error> {
+        Ok(self
+            .borrow()?
+            .iter()
+            .find(|forced| forced.slave == slave && forced.byte_offset == byte_offset)
+            .map_or(0, |forced| forced.mask))
+    }
+
+    /// Overwrite forced bits in a group's outputs.
+    ///
+    /// `output_ranges` holds each SubDevice's outputs as a range into `pdi`. All forces are
+    /// checked before any are written, so nothing is written if any force is out of range.
+    pub(crate) fn apply(
+        &self,
+        pdi: &mut [u8],
+        output_ranges: &[Range<usize>],
+    ) -> Result<(), Error> {
+        let forces = self.borrow()?;
+
+        let position = |forced: &ForcedByte| {
+            let range = output_ranges.get(forced.slave).ok_or(Error::NotFound {
+                item: Item::Slave,
+                index: Some(forced.slave),
+            })?;
+
+            let position = range.start + forced.byte_offset;
+
+            if position >= range.end {
+                return Err(Error::NotFound {
+                    item: Item::PdiRegion,
+                    index: Some(forced.slave),
+                });
+            }
+
+            Ok(position)
+        };
+
+        for forced in forces.iter() {
+            position(forced)?;
+        }
+
+        for forced in forces.iter() {
+            let byte = &mut pdi[position(forced)?];
+
+            *byte = (*byte & !forced.mask) | forced.value;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn force_and_release() {
+        let forces = OutputForces::<2>::new();
+
+        // Two SubDevices with 2 and 1 output bytes
+        let ranges = [4..6, 6..7];
+        let mut pdi = [0xaa; 8];
+
+        forces.force_bit(0, 1, 0, true).unwrap();
+        forces.force_bit(0, 1, 1, false).unwrap();
+        forces.force_byte(1, 0, 0x12).unwrap();
+
+        assert_eq!(forces.forced_mask(0, 1), Ok(0b0000_0011));
+        assert_eq!(
+            forces.force_bit(0, 0, 0, true),
+            Err(Error::Capacity(Item::PdiRegion))
+        );
+
+        forces.apply(&mut pdi, &ranges).unwrap();
+
+        assert_eq!(pdi, [0xaa, 0xaa, 0xaa, 0xaa, 0xaa, 0b1010_1001, 0x12, 0xaa]);
+
+        // Application writes are overridden every cycle
+        pdi[5] = 0xff;
+        forces.apply(&mut pdi, &ranges).unwrap();
+        assert_eq!(pdi[5], 0b1111_1101);
+
+        forces.release_bit(0, 1, 1).unwrap();
+        assert_eq!(forces.forced_mask(0, 1), Ok(0b0000_0001));
+
+        forces.release_byte(1, 0).unwrap();
+        assert_eq!(forces.forced_mask(1, 0), Ok(0));
+
+        pdi[5] = 0x00;
+        pdi[6] = 0x00;
+        forces.apply(&mut pdi, &ranges).unwrap();
+        assert_eq!(pdi[5..7], [0b0000_0001, 0x00]);
+
+        forces.release_all().unwrap();
+        assert_eq!(forces.forced_mask(0, 1), Ok(0));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let forces = OutputForces::<2>::new();
+
+        // The second SubDevice has no outputs
+        let ranges = [0..1, 1..1];
+        let mut pdi = [0u8; 2];
+
+        forces.force_byte(0, 0, 0xff).unwrap();
+        forces.force_byte(1, 0, 0xff).unwrap();
+
+        assert_eq!(
+            forces.apply(&mut pdi, &ranges),
+            Err(Error::NotFound {
+                item: Item::PdiRegion,
+                index: Some(1)
+            })
+        );
+        assert_eq!(pdi, [0u8; 2], "nothing is written");
+    }
+
+    #[test]
+    fn does_not_block() {
+        let forces = OutputForces::<2>::new();
+
+        let ranges = [0..1, 1..1];
+        let mut pdi = [0u8; 1];
+
+        forces.force_byte(0, 0, 0xff).unwrap();
+
+        {
+            // Table being changed by another task
+            let _writer = forces.forces.borrow_mut();
+
+            assert_eq!(forces.apply(&mut pdi, &ranges), Err(Error::Borrow));
+            assert_eq!(forces.forced_mask(0, 0), Err(Error::Borrow));
+        }
+
+        {
+            // Table being applied by the cyclic task
+            let _reader = forces.forces.borrow();
+
+            assert_eq!(forces.force_byte(0, 0, 0x00), Err(Error::Borrow));
+            assert_eq!(forces.release_all(), Err(Error::Borrow));
+        }
+
+        assert_eq!(forces.apply(&mut pdi, &ranges), Ok(()));
+        assert_eq!(pdi, [0xff]);
+    }
+}
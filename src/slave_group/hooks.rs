@@ -0,0 +1,271 @@
+use crate::{
+    al_control::AlControl, error::Error, fmt, RegisterAddress, Slave, SlaveRef, SlaveState,
+};
+use core::future::Future;
+
+/// Per-SubDevice hooks run around each SubDevice's state transition by
+/// [`SlaveGroup::into_safe_op_with_hooks`](crate::SlaveGroup::into_safe_op_with_hooks) and
+/// [`SlaveGroup::into_op_with_hooks`](crate::SlaveGroup::into_op_with_hooks).
+///
+/// Both hooks do nothing by default. `()` implements this trait with no hooks, so a transition can
+/// be retried without running any.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use ethercrab::{error::Error, Slave, SlaveRef, SlaveState, TransitionHooks};
+///
+/// struct DriveSetup;
+///
+/// impl TransitionHooks for DriveSetup {
+///     async fn before(&self, slave: &SlaveRef<'_, &Slave>, state: SlaveState) -> Result<(), Error> {
+///         if state == SlaveState::SafeOp && slave.name() == "AKD" {
+///             // Cyclic synchronous position mode
+///             slave.sdo_write(0x6060, 0, 0x08u8).await?;
+///         }
+///
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait TransitionHooks {
+    /// Called before the SubDevice is requested to go to `state`.
+    fn before(
+        &self,
+        slave: &SlaveRef<'_, &Slave>,
+        state: SlaveState,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let _ = (slave, state);
+
+        core::future::ready(Ok(()))
+    }
+
+    /// Called once the SubDevice has reached `state`.
+    fn after(
+        &self,
+        slave: &SlaveRef<'_, &Slave>,
+        state: SlaveState,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let _ = (slave, state);
+
+        core::future::ready(Ok(()))
+    }
+}
+
+impl TransitionHooks for () {}
+
+/// The part of a SubDevice's state transition that failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HookStage {
+    /// [`TransitionHooks::before`] returned an error.
+    Before,
+    /// The SubDevice didn't reach the requested state.
+    Transition,
+    /// [`TransitionHooks::after`] returned an error.
+    After,
+}
+
+impl core::fmt::Display for HookStage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HookStage::Before => f.write_str("before hook"),
+            HookStage::Transition => f.write_str("state transition"),
+            HookStage::After => f.write_str("after hook"),
+        }
+    }
+}
+
+/// A SubDevice that failed its state transition or one of its [`TransitionHooks`] on every
+/// attempt.
+///
+/// This can be converted into the underlying [`Error`] with `?`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HookError {
+    /// The index of the SubDevice in its group.
+    pub index: usize,
+
+    /// The SubDevice's configured station address.
+    pub configured_address: u16,
+
+    /// The part of the transition that failed on the last attempt.
+    pub stage: HookStage,
+
+    /// The number of times the transition was attempted, saturating at `255`.
+    pub attempts: u8,
+
+    /// The error returned by the last attempt.
+    pub error: Error,
+}
+
+impl core::fmt::Display for HookError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "slave {:#06x} {} failed after {} attempt(s): {}",
+            self.configured_address, self.stage, self.attempts, self.error
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HookError {}
+
+impl From<HookError> for Error {
+    fn from(value: HookError) -> Self {
+        value.error
+    }
+}
+
+/// Run the hooks and state transition of a single SubDevice, retrying up to `retries` times.
+pub(crate) async fn transition_with_hooks<H>(
+    slave: &SlaveRef<'_, &Slave>,
+    index: usize,
+    hooks: &H,
+    current_state: SlaveState,
+    desired_state: SlaveState,
+    retries: u8,
+) -> Result<(), HookError>
+where
+    H: TransitionHooks,
+{
+    // Counted in a `u16` so `retries = u8::MAX` doesn't overflow
+    let mut attempts = 0u16;
+
+    loop {
+        attempts += 1;
+
+        let (stage, error) = match attempt(slave, hooks, current_state, desired_state).await {
+            Ok(()) => break Ok(()),
+            Err(failure) => failure,
+        };
+
+        fmt::warn!(
+            "Slave {:#06x} {} to {} failed on attempt {}: {}",
+            slave.configured_address(),
+            stage,
+            desired_state,
+            attempts,
+            error
+        );
+
+        let hook_error = |error| HookError {
+            index,
+            configured_address: slave.configured_address(),
+            stage,
+            attempts: u8::try_from(attempts).unwrap_or(u8::MAX),
+            error,
+        };
+
+        if attempts > u16::from(retries) {
+            break Err(hook_error(error));
+        }
+
+        // A SubDevice ignores state requests until its error is acknowledged
+        if let Ok(status) = slave
+            .register_read::<AlControl>(RegisterAddress::AlStatus)
+            .await
+        {
+            if status.error {
+                slave
+                    .register_write(
+                        RegisterAddress::AlControl,
+                        AlControl {
+                            error: true,
+                            ..AlControl::new(status.state)
+                        },
+                    )
+                    .await
+                    .map_err(hook_error)?;
+            }
+        }
+    }
+}
+
+async fn attempt<H>(
+    slave: &SlaveRef<'_, &Slave>,
+    hooks: &H,
+    current_state: SlaveState,
+    desired_state: SlaveState,
+) -> Result<(), (HookStage, Error)>
+where
+    H: TransitionHooks,
+{
+    hooks
+        .before(slave, desired_state)
+        .await
+        .map_err(|e| (HookStage::Before, e))?;
+
+    slave
+        .request_slave_state(current_state, desired_state)
+        .await
+        .map_err(|e| (HookStage::Transition, e))?;
+
+    hooks
+        .after(slave, desired_state)
+        .await
+        .map_err(|e| (HookStage::After, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eeprom::types::MailboxProtocols, slave::mock::MockSubDevice, ClientConfig, Timeouts,
+    };
+    use core::sync::atomic::{AtomicU16, Ordering};
+
+    /// Hooks whose `before` hook always fails, counting how many times it was called.
+    #[derive(Default)]
+    struct FailingHooks {
+        calls: AtomicU16,
+    }
+
+    impl TransitionHooks for FailingHooks {
+        async fn before(
+            &self,
+            _slave: &SlaveRef<'_, &Slave>,
+            _state: SlaveState,
+        ) -> Result<(), Error> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+
+            Err(Error::Internal)
+        }
+    }
+
+    #[tokio::test]
+    async fn max_retries() {
+        let (client, _mock) =
+            MockSubDevice::start(Timeouts::default(), ClientConfig::default(), |_, _| {
+                Vec::new()
+            });
+
+        let slave = MockSubDevice::slave(MailboxProtocols::empty());
+        let slave = SlaveRef::new(&client, slave.configured_address, &slave);
+
+        let hooks = FailingHooks::default();
+
+        let result = transition_with_hooks(
+            &slave,
+            0,
+            &hooks,
+            SlaveState::PreOp,
+            SlaveState::SafeOp,
+            u8::MAX,
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            Err(HookError {
+                index: 0,
+                configured_address: slave.configured_address(),
+                stage: HookStage::Before,
+                attempts: u8::MAX,
+                error: Error::Internal,
+            })
+        );
+        assert_eq!(hooks.calls.load(Ordering::Relaxed), 256);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::{error::Error, SlaveRef};
+
+/// How the first SubDevice of a hot connect module is identified, so the module can be added to
+/// the right group with [`SlaveGroup::hot_connect`](crate::SlaveGroup::hot_connect).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HotConnectId {
+    /// The configured station alias stored in the SubDevice's EEPROM. See
+    /// [`SlaveRef::station_alias`].
+    StationAlias(u16),
+
+    /// The explicit device ID, e.g. set with rotary switches on a coupler. See
+    /// [`SlaveRef::explicit_device_id`].
+    DeviceId(u16),
+}
+
+impl HotConnectId {
+    /// Check whether the given SubDevice has this ID.
+    pub(crate) async fn matches<S>(&self, slave: &SlaveRef<'_, S>) -> Result<bool, Error> {
+        match *self {
+            HotConnectId::StationAlias(alias) => Ok(slave.station_alias().await? == alias),
+            HotConnectId::DeviceId(id) => Ok(slave.explicit_device_id().await? == Some(id)),
+        }
+    }
+}
@@ -4,28 +4,58 @@
 //! potentially at different tick rates.
 
 mod configurator;
+mod cycle_controller;
+mod dynamic;
+mod fail_safe;
+mod force;
 mod group_id;
 mod handle;
+mod hooks;
+mod hot_connect;
 mod iterator;
+mod schedule;
+mod snapshot;
+mod watch;
 
+use self::fail_safe::FailSafeState;
+#[cfg(feature = "std")]
+use crate::{
+    coe::SubIndex,
+    sii::esi::{EsiDevice, EsiFile},
+};
 use crate::{
     command::Command,
     error::{DistributedClockError, Error, Item, PduError},
     fmt,
     pdi::PdiOffset,
-    slave::{configuration::PdoDirection, pdi::SlavePdi, IoRanges, Slave, SlaveRef},
+    pdu_loop::PDU_OVERHEAD,
+    slave::{
+        configuration::{PdoDirection, SiiOverride, SyncManagerMappings},
+        pdi::SlavePdi,
+        EntryLocations, GroupPdi, IoRanges, RecoveryEvent, Slave, SlaveRef,
+    },
     timer_factory::IntoTimeout,
-    Client, DcSync, RegisterAddress, SlaveState,
+    Client, DcSync, FailSafeOutputs, PdiExchange, RegisterAddress, SlaveState, StartupSdo,
+    BASE_SLAVE_ADDR,
 };
 use atomic_refcell::{AtomicRefCell, AtomicRefMut};
 use core::{
-    cell::UnsafeCell, marker::PhantomData, slice, sync::atomic::AtomicUsize, time::Duration,
+    cell::UnsafeCell, marker::PhantomData, ops::Range, slice, sync::atomic::AtomicUsize,
+    time::Duration,
 };
 use ethercrab_wire::EtherCrabWireRead;
 
+pub use self::cycle_controller::DcCycleController;
+pub use self::dynamic::DynSlaveGroup;
+pub use self::force::OutputForces;
 pub use self::group_id::GroupId;
 pub use self::handle::SlaveGroupHandle;
+pub use self::hooks::{HookError, HookStage, TransitionHooks};
+pub use self::hot_connect::HotConnectId;
 pub use self::iterator::GroupSlaveIterator;
+pub use self::schedule::{CycleSchedule, DueGroups};
+pub use self::snapshot::{PdiSnapshot, PdiSnapshotRef};
+pub use self::watch::InputWatch;
 pub use configurator::SlaveGroupRef;
 
 static GROUP_ID: AtomicUsize = AtomicUsize::new(0);
@@ -68,6 +98,8 @@ pub struct HasDc {
     sync0_shift: u64,
     /// Configured address of the DC reference SubDevice.
     reference: u16,
+    /// DC system time of the first SYNC0 pulse.
+    start_time: u64,
 }
 
 /// Marker trait for `SlaveGroup` typestates where all SubDevices have a PDI.
@@ -88,6 +120,188 @@ impl IsPreOp for PreOpPdi {}
 struct GroupInner<const MAX_SLAVES: usize> {
     slaves: heapless::Vec<AtomicRefCell<Slave>, MAX_SLAVES>,
     pdi_start: PdiOffset,
+    /// The logical start address chosen with
+    /// [`with_logical_address`](SlaveGroup::with_logical_address), if any.
+    fixed_pdi_start: Option<PdiOffset>,
+    /// The minimum length in bytes of the group's inputs, set with
+    /// [`with_reserved_pdi`](SlaveGroup::with_reserved_pdi).
+    reserved_inputs: u16,
+    /// The minimum length in bytes of the group's outputs.
+    reserved_outputs: u16,
+    /// Offsets into the group PDI at the start of a SubDevice's inputs, where the PDI can be split
+    /// into multiple `LRW`s without splitting any SubDevice's inputs.
+    input_splits: heapless::Vec<usize, MAX_SLAVES>,
+    /// Offsets into the group PDI at the start of a SubDevice's outputs, where the PDI can be split
+    /// into multiple `LRW`s without splitting any SubDevice's outputs.
+    output_splits: heapless::Vec<usize, MAX_SLAVES>,
+    /// Each SubDevice's inputs, as a range into the group PDI.
+    input_ranges: heapless::Vec<Range<usize>, MAX_SLAVES>,
+    /// Each SubDevice's outputs, as a range into the group PDI.
+    output_ranges: heapless::Vec<Range<usize>, MAX_SLAVES>,
+    /// Where each PDO entry mapped by the group's SubDevices is in their process data.
+    entries: EntryLocations,
+    fail_safe: FailSafeState,
+    /// Leave SubDevices that fail a state transition behind instead of failing the transition, set
+    /// with [`with_partial_op`](SlaveGroup::with_partial_op).
+    partial_op: bool,
+}
+
+impl<const MAX_SLAVES: usize> GroupInner<MAX_SLAVES> {
+    /// Record each SubDevice's inputs and outputs, and where the group PDI can be split into
+    /// multiple PDUs, from the SubDevices' configured PDI segments.
+    fn update_ranges(&mut self, max_pdu_data: usize) {
+        self.input_splits.clear();
+        self.output_splits.clear();
+        self.input_ranges.clear();
+        self.output_ranges.clear();
+
+        let segments = || {
+            self.slaves
+                .iter()
+                .map(|slave| slave.borrow().io_segments().clone())
+        };
+
+        for IoRanges { input, output } in segments() {
+            // Unwrap: there is one range per SubDevice
+            fmt::unwrap!(self.input_ranges.push(input.bytes.clone()).map_err(|_| ()));
+            fmt::unwrap!(self
+                .output_ranges
+                .push(output.bytes.clone())
+                .map_err(|_| ()));
+
+            for (segment, splits, other) in [
+                (&input, &mut self.input_splits, false),
+                (&output, &mut self.output_splits, true),
+            ] {
+                if segment.is_empty() {
+                    continue;
+                }
+
+                if segment.len() > max_pdu_data {
+                    fmt::warn!(
+                        "PDI segment {} is longer than a frame and will be split, so working counters will be higher than expected",
+                        segment
+                    );
+                }
+
+                let split = segment.bytes.start;
+
+                // Bit-packed SubDevices may share a byte, so the PDI can't be split there.
+                let straddled = segments().any(|io| {
+                    let other = if other { io.output } else { io.input };
+
+                    other.bytes.start < split && split < other.bytes.end
+                });
+
+                if split > 0 && !straddled && !splits.contains(&split) {
+                    // Unwrap: there is at most one split per SubDevice
+                    fmt::unwrap!(splits.push(split));
+                }
+            }
+        }
+    }
+}
+
+/// Pad one direction of a group's PDI, from `start` to `position`, to at least `reserved` bytes.
+fn reserve(position: PdiOffset, start: PdiOffset, reserved: u16, direction: &str) -> PdiOffset {
+    let reserved_end = start.increment(reserved);
+
+    if position.start_address >= reserved_end.start_address {
+        return position;
+    }
+
+    fmt::debug!(
+        "Reserving {} {} bytes for SubDevices that are not present",
+        reserved_end.start_address - position.start_address,
+        direction
+    );
+
+    reserved_end
+}
+
+/// Find the end of the chunk of a PDI of `pdi_len` bytes starting at `start` that fits in
+/// `max_len` bytes.
+///
+/// Chunks end at the last offset in `splits` that fits, so each SubDevice's inputs and outputs are
+/// read or written by a single `LRW` and the sum of the working counters of all chunks is the same
+/// as if the whole PDI were sent at once.
+///
+/// If a single SubDevice's data is too long to fit, [`Error::PdiTooLong`] is returned as splitting
+/// it would count its working counter once for every chunk.
+fn pdi_chunk_end(
+    start: usize,
+    pdi_len: usize,
+    max_len: usize,
+    splits: impl Iterator<Item = usize> + Clone,
+) -> Result<usize, Error> {
+    let limit = start + max_len;
+
+    if pdi_len <= limit {
+        return Ok(pdi_len);
+    }
+
+    splits
+        .clone()
+        .filter(|split| (start + 1..=limit).contains(split))
+        .last()
+        .ok_or_else(|| Error::PdiTooLong {
+            max_length: max_len,
+            desired_length: splits
+                .filter(|split| *split > start)
+                .min()
+                .unwrap_or(pdi_len)
+                - start,
+        })
+}
+
+/// The logical commands used to exchange a chunk of a group's PDI, and the range of the PDI each
+/// command reads or writes.
+///
+/// With [`PdiExchange::Lrw`], the whole chunk is sent in one `LRW`. Otherwise, the part of the
+/// chunk holding inputs is read with an `LRD` and the part holding outputs is written with an
+/// `LWR`.
+fn chunk_pdus(
+    chunk: Range<usize>,
+    start_address: u32,
+    read_pdi_len: usize,
+    exchange: PdiExchange,
+) -> heapless::Vec<(Command, Range<usize>), 2> {
+    let mut pdus = heapless::Vec::new();
+
+    let overlap = match exchange {
+        PdiExchange::Lrw => {
+            let command = Command::lrw(start_address + chunk.start as u32).into();
+
+            // Unwrap: there is space for two PDUs
+            fmt::unwrap!(pdus.push((command, chunk)).map_err(|_| ()));
+
+            return pdus;
+        }
+        PdiExchange::LrdLwr { overlap } => overlap,
+    };
+
+    let inputs = chunk.start..chunk.end.min(read_pdi_len);
+    let outputs = chunk.start.max(read_pdi_len)..chunk.end;
+
+    if !inputs.is_empty() || outputs.is_empty() {
+        let command = Command::lrd(start_address + inputs.start as u32).into();
+
+        fmt::unwrap!(pdus.push((command, inputs)).map_err(|_| ()));
+    }
+
+    if !outputs.is_empty() {
+        let offset = if overlap {
+            outputs.start - read_pdi_len
+        } else {
+            outputs.start
+        };
+
+        let command = Command::lwr(start_address + offset as u32).into();
+
+        fmt::unwrap!(pdus.push((command, outputs)).map_err(|_| ()));
+    }
+
+    pdus
 }
 
 const CYCLIC_OP_ENABLE: u8 = 0b0000_0001;
@@ -98,6 +312,11 @@ const SYNC1_ACTIVATE: u8 = 0b0000_0100;
 #[derive(Default, Debug, Copy, Clone)]
 pub struct DcConfiguration {
     /// How long the SubDevices in the group should wait before starting SYNC0 pulse generation.
+    ///
+    /// All SubDevices are given the same start time, computed from the DC reference clock's
+    /// system time plus this delay. It must be long enough to configure every SubDevice in the
+    /// group, otherwise SubDevices configured after the start time won't begin pulsing on the
+    /// same edge as the others.
     pub start_delay: Duration,
 
     /// SYNC0 cycle time.
@@ -129,6 +348,78 @@ pub struct CycleInfo {
     pub cycle_start_offset: Duration,
 }
 
+/// Statistics of DC system time differences, collected with
+/// [`SlaveGroup::update_dc_statistics`].
+///
+/// All values are in nanoseconds. A negative difference means a SubDevice's clock is behind the DC
+/// reference clock.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DcStatistics {
+    min: i32,
+    max: i32,
+    sum: i64,
+    count: u64,
+}
+
+impl DcStatistics {
+    /// Create an empty set of statistics.
+    pub const fn new() -> Self {
+        Self {
+            min: 0,
+            max: 0,
+            sum: 0,
+            count: 0,
+        }
+    }
+
+    /// Add a single system time difference sample.
+    pub fn push(&mut self, difference: i32) {
+        if self.count == 0 {
+            self.min = difference;
+            self.max = difference;
+        } else {
+            self.min = self.min.min(difference);
+            self.max = self.max.max(difference);
+        }
+
+        self.sum = self.sum.saturating_add(i64::from(difference));
+        self.count += 1;
+    }
+
+    /// Clear all samples.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// The number of samples collected.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The smallest difference, or `None` if no samples have been collected.
+    pub fn min(&self) -> Option<i32> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// The largest difference, or `None` if no samples have been collected.
+    pub fn max(&self) -> Option<i32> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// The largest absolute difference, or `None` if no samples have been collected.
+    pub fn max_abs(&self) -> Option<u32> {
+        (self.count > 0).then(|| self.min.unsigned_abs().max(self.max.unsigned_abs()))
+    }
+
+    /// The mean difference, or `None` if no samples have been collected.
+    pub fn mean(&self) -> Option<i32> {
+        // `None` if there are no samples
+        self.sum
+            .checked_div(self.count as i64)
+            .map(|mean| mean as i32)
+    }
+}
+
 /// A group of one or more EtherCAT slaves.
 ///
 /// Groups are created during EtherCrab initialisation, and are the only way to access individual
@@ -142,12 +433,230 @@ pub struct SlaveGroup<const MAX_SLAVES: usize, const MAX_PDI: usize, S = PreOp,
     pdi_len: usize,
     inner: UnsafeCell<GroupInner<MAX_SLAVES>>,
     dc_conf: DC,
+    /// The DC reference clock's system time read in the same frame as the most recent process data
+    /// exchange.
+    pdi_time: AtomicRefCell<Option<u64>>,
     _state: PhantomData<S>,
 }
 
 impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, DC> {
+    /// Place this group's PDI at the given logical address instead of after the previous group's.
+    ///
+    /// By default, [`Client::init`] packs groups one after the other in logical address space, so
+    /// adding or removing a group shifts the FMMU configuration of every group after it. A group
+    /// with a fixed address occupies `MAX_PDI` bytes starting at `start_address` regardless of
+    /// other groups, and groups without a fixed address are packed around it.
+    ///
+    /// [`Client::init`] returns [`Error::PdiOverlap`] if the ranges of two groups with fixed
+    /// addresses overlap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ethercrab::SlaveGroup;
+    ///
+    /// #[derive(Default)]
+    /// struct Groups {
+    ///     slow: SlaveGroup<4, 32>,
+    ///     fast: SlaveGroup<8, 64>,
+    /// }
+    ///
+    /// let groups = Groups {
+    ///     // Always at logical address 0x0001_0000, even if the `slow` group grows.
+    ///     fast: SlaveGroup::default().with_logical_address(0x0001_0000),
+    ///     ..Groups::default()
+    /// };
+    /// ```
+    pub fn with_logical_address(mut self, start_address: u32) -> Self {
+        self.inner.get_mut().fixed_pdi_start = Some(PdiOffset { start_address });
+
+        self
+    }
+
+    /// Reserve space in this group's PDI for SubDevices that are expected but not currently
+    /// present, e.g. optional machine modules that are only sometimes attached.
+    ///
+    /// The group's inputs and outputs are each padded to at least the given number of bytes. If
+    /// the reserved lengths fit the group with all optional SubDevices attached, attaching or
+    /// removing them doesn't change the logical address of the group's outputs, or of any
+    /// SubDevice before them. Optional SubDevices should therefore be placed at the end of a group,
+    /// or in their own group.
+    ///
+    /// Reserved bytes count towards the group's `MAX_PDI`. No FMMUs are mapped to them, so they
+    /// don't change the working counter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use ethercrab::SlaveGroup;
+    ///
+    /// // Room for 24 bytes of inputs and 16 bytes of outputs, including an optional I/O module at the
+    /// // end of the line.
+    /// let group = SlaveGroup::<8, 64>::default().with_reserved_pdi(24, 16);
+    /// ```
+    pub fn with_reserved_pdi(mut self, input_bytes: u16, output_bytes: u16) -> Self {
+        let inner = self.inner.get_mut();
+
+        inner.reserved_inputs = input_bytes;
+        inner.reserved_outputs = output_bytes;
+
+        self
+    }
+
+    /// Let this group's state transitions succeed even if some SubDevices don't reach the
+    /// requested state, so a machine can run in a degraded mode.
+    ///
+    /// By default, a transition fails if any SubDevice in the group rejects the request, doesn't
+    /// respond, or doesn't reach the requested state in time. With partial OP, these SubDevices are
+    /// logged and left behind in whatever state they are in, and the transition succeeds as long
+    /// as at least one SubDevice reaches the requested state. SubDevices that were left behind
+    /// return `false` from [`Slave::is_present`] and aren't included in later transitions or in
+    /// [`all_op`](SlaveGroup::all_op). [`recover`](SlaveGroup::recover) brings them back to OP
+    /// once the fault is fixed.
+    ///
+    /// Left behind SubDevices don't exchange process data, so the working counter is lower than
+    /// expected while any are missing, which trips
+    /// [`ClientConfig::fail_safe`](crate::ClientConfig::fail_safe) and
+    /// [`ClientConfig::verify_pdi_wkc`](crate::ClientConfig::verify_pdi_wkc) if they are enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{error::Error, Client, SlaveGroup};
+    /// # async fn run(client: &Client<'_>) -> Result<(), Error> {
+    /// let group = SlaveGroup::<8, 64>::default().with_partial_op();
+    /// # let group = client.init_single_group::<8, 64>(ethercrab::std::ethercat_now).await?;
+    ///
+    /// let mut group = group.into_op(client).await?;
+    ///
+    /// for slave in group.iter(client) {
+    ///     if !slave.is_present() {
+    ///         log::warn!("{} is not in OP, running without it", slave.name());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_partial_op(mut self) -> Self {
+        self.inner.get_mut().partial_op = true;
+
+        self
+    }
+
+    /// Move the SubDevice at `index` to the end of another group.
+    ///
+    /// Groups can be reorganised after [`Client::init`], e.g. when an optional machine section is
+    /// attached or removed, by taking both groups back to PRE-OP with
+    /// [`into_pre_op`](SlaveGroup::into_pre_op). The process data of both groups is mapped again
+    /// from scratch when they next go to SAFE-OP, e.g. with [`into_op`](SlaveGroup::into_op), so
+    /// the SubDevice's inputs and outputs move to the other group's PDI.
+    ///
+    /// Returns [`Error::Capacity`] if the other group is full, in which case neither group is
+    /// changed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{error::Error, slave_group::{Op, PreOp}, Client, SlaveGroup};
+    /// # async fn reorganise(
+    /// #     client: &Client<'_>,
+    /// #     fast: SlaveGroup<16, 64, Op>,
+    /// #     slow: SlaveGroup<16, 64, Op>,
+    /// # ) -> Result<(), Error> {
+    /// let mut fast = fast.into_safe_op(client).await?.into_pre_op(client).await?;
+    /// let mut slow = slow.into_safe_op(client).await?.into_pre_op(client).await?;
+    ///
+    /// // Move the last SubDevice of the fast group to the slow group
+    /// fast.move_slave(fast.len() - 1, &mut slow)?;
+    ///
+    /// let fast = fast.into_op(client).await?;
+    /// let slow = slow.into_op(client).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn move_slave<const TO_MAX_SLAVES: usize, const TO_MAX_PDI: usize, ToDc>(
+        &mut self,
+        index: usize,
+        to: &mut SlaveGroup<TO_MAX_SLAVES, TO_MAX_PDI, PreOp, ToDc>,
+    ) -> Result<(), Error> {
+        let from = &mut self.inner.get_mut().slaves;
+        let to = &mut to.inner.get_mut().slaves;
+
+        if index >= from.len() {
+            return Err(Error::NotFound {
+                item: Item::Slave,
+                index: Some(index),
+            });
+        }
+
+        if to.is_full() {
+            return Err(Error::Capacity(Item::Slave));
+        }
+
+        fmt::unwrap!(to.push(from.remove(index)).map_err(|_| ()));
+
+        Ok(())
+    }
+
+    /// Move the SubDevices from `at` to the end of this group to the end of another group, e.g. to
+    /// give an optional machine section its own group.
+    ///
+    /// See [`move_slave`](SlaveGroup::move_slave) for how groups are reorganised. Returns
+    /// [`Error::Capacity`] if the other group can't hold all the moved SubDevices, in which case
+    /// neither group is changed.
+    pub fn split_off<const TO_MAX_SLAVES: usize, const TO_MAX_PDI: usize, ToDc>(
+        &mut self,
+        at: usize,
+        to: &mut SlaveGroup<TO_MAX_SLAVES, TO_MAX_PDI, PreOp, ToDc>,
+    ) -> Result<(), Error> {
+        let from = &mut self.inner.get_mut().slaves;
+        let to = &mut to.inner.get_mut().slaves;
+
+        if at > from.len() {
+            return Err(Error::NotFound {
+                item: Item::Slave,
+                index: Some(at),
+            });
+        }
+
+        if to.len() + (from.len() - at) > to.capacity() {
+            return Err(Error::Capacity(Item::Slave));
+        }
+
+        while from.len() > at {
+            fmt::unwrap!(to.push(from.remove(at)).map_err(|_| ()));
+        }
+
+        Ok(())
+    }
+
+    /// Move all SubDevices of another group to the end of this group, leaving the other group
+    /// empty.
+    ///
+    /// See [`move_slave`](SlaveGroup::move_slave) for how groups are reorganised. Returns
+    /// [`Error::Capacity`] if this group can't hold all the moved SubDevices, in which case
+    /// neither group is changed.
+    pub fn append<const FROM_MAX_SLAVES: usize, const FROM_MAX_PDI: usize, FromDc>(
+        &mut self,
+        from: &mut SlaveGroup<FROM_MAX_SLAVES, FROM_MAX_PDI, PreOp, FromDc>,
+    ) -> Result<(), Error> {
+        from.split_off(0, self)
+    }
+
     /// Configure read/write FMMUs and PDI for this group.
-    async fn configure_fmmus(&mut self, client: &Client<'_>) -> Result<(), Error> {
+    ///
+    /// SubDevices that `sii` returns an override for are configured from it instead of their
+    /// EEPROM.
+    async fn configure_fmmus<'sii, F, S>(
+        &mut self,
+        client: &Client<'_>,
+        configure_pdi: &F,
+        sii: &S,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&SlaveRef<'_, &Slave>, PdoDirection, &mut SyncManagerMappings),
+        S: Fn(&SlaveRef<'_, &Slave>) -> Option<SiiOverride<'sii>>,
+    {
         let inner = self.inner.get_mut();
 
         let mut pdi_position = inner.pdi_start;
@@ -160,16 +669,29 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
 
         // Configure master read PDI mappings in the first section of the PDI
         for slave in inner.slaves.iter_mut().map(AtomicRefCell::get_mut) {
+            let sii = sii(&SlaveRef::new(client, slave.configured_address(), &*slave));
+
             // We're in PRE-OP at this point
             pdi_position = SlaveRef::new(client, slave.configured_address(), slave)
                 .configure_fmmus(
                     pdi_position,
                     inner.pdi_start.start_address,
+                    0,
                     PdoDirection::MasterRead,
+                    configure_pdi,
+                    sii,
+                    Some(&mut inner.entries),
                 )
                 .await?;
         }
 
+        pdi_position = reserve(
+            pdi_position,
+            inner.pdi_start,
+            inner.reserved_inputs,
+            "input",
+        );
+
         self.read_pdi_len = (pdi_position.start_address - inner.pdi_start.start_address) as usize;
 
         fmt::debug!("Slave mailboxes configured and init hooks called");
@@ -177,24 +699,48 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
         // We configured all read PDI mappings as a contiguous block in the previous loop. Now we'll
         // configure the write mappings in a separate loop. This means we have IIIIOOOO instead of
         // IOIOIO.
+        //
+        // Outputs are always stored after inputs in the group PDI, but may be mapped to the same
+        // logical addresses as the inputs if they are written with a separate `LWR`.
+        let outputs_start = match client.config.pdi_exchange {
+            PdiExchange::LrdLwr { overlap: true } => inner.pdi_start,
+            PdiExchange::Lrw | PdiExchange::LrdLwr { overlap: false } => pdi_position,
+        };
+
+        pdi_position = outputs_start;
+
         for slave in inner.slaves.iter_mut().map(AtomicRefCell::get_mut) {
             let addr = slave.configured_address();
 
+            let sii = sii(&SlaveRef::new(client, addr, &*slave));
+
             let mut slave_config = SlaveRef::new(client, addr, slave);
 
             // Still in PRE-OP
             pdi_position = slave_config
                 .configure_fmmus(
                     pdi_position,
-                    inner.pdi_start.start_address,
+                    outputs_start.start_address,
+                    self.read_pdi_len,
                     PdoDirection::MasterWrite,
+                    configure_pdi,
+                    sii,
+                    Some(&mut inner.entries),
                 )
                 .await?;
         }
 
+        pdi_position = reserve(
+            pdi_position,
+            outputs_start,
+            inner.reserved_outputs,
+            "output",
+        );
+
         fmt::debug!("Slave FMMUs configured for group. Able to move to SAFE-OP");
 
-        self.pdi_len = (pdi_position.start_address - inner.pdi_start.start_address) as usize;
+        self.pdi_len =
+            self.read_pdi_len + (pdi_position.start_address - outputs_start.start_address) as usize;
 
         fmt::debug!(
             "Group PDI length: start {:#010x}, {} total bytes ({} input bytes)",
@@ -210,6 +756,18 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
             });
         }
 
+        let max_pdu_data = client.pdu_loop.max_pdu_data();
+
+        if self.pdi_len > max_pdu_data {
+            fmt::debug!(
+                "Group PDI of {} bytes is longer than {} byte frame data, sending in multiple frames",
+                self.pdi_len,
+                max_pdu_data
+            );
+        }
+
+        inner.update_ranges(max_pdu_data);
+
         Ok(())
     }
 
@@ -219,6 +777,9 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
     /// single reference to it at any one time. Multiple different slaves can be borrowed
     /// simultaneously, but multiple references to the same slave are not allowed.
     ///
+    /// Each SubDevice has its own mailbox state, so SDO and other mailbox transfers to different
+    /// SubDevices can be run concurrently, e.g. from separate tasks, to reduce startup time.
+    ///
     /// # Errors
     ///
     /// This method will return an error if the given index is out of range of the current group, or
@@ -255,9 +816,17 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
         self,
         client: &Client<'_>,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op, DC>, Error> {
-        let self_ = self.into_safe_op(client).await?;
+        // Transitions are awaited directly instead of through `into_safe_op` as every nested
+        // future holds its own copies of the group, which can overflow the stack in debug builds.
+        let self_ = self.into_pre_op_pdi(client).await?;
+
+        let self_ = self_
+            .transition_to::<SafeOp>(client, SlaveState::PreOp, SlaveState::SafeOp)
+            .await?;
 
-        self_.into_op(client).await
+        self_
+            .transition_to(client, SlaveState::SafeOp, SlaveState::Op)
+            .await
     }
 
     /// Configure FMMUs, but leave the group in [`PreOp`] state.
@@ -266,10 +835,57 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
     /// and other configuration should be complete at this point otherwise issues with cyclic data
     /// may occur (e.g. incorrect lengths, misplaced fields, etc).
     pub async fn into_pre_op_pdi(
-        mut self,
+        self,
         client: &Client<'_>,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, PreOpPdi, DC>, Error> {
-        self.configure_fmmus(client).await?;
+        self.into_pre_op_pdi_with_config(client, |_slave, _direction, _mappings| {})
+            .await
+    }
+
+    /// Configure FMMUs like [`into_pre_op_pdi`](SlaveGroup::into_pre_op_pdi), but allow the sync
+    /// manager lengths and FMMU mappings of each SubDevice to be changed before they are written.
+    ///
+    /// The `configure_pdi` hook is called for every SubDevice in the group, once for its inputs and
+    /// once for its outputs, with the mappings computed from the SubDevice's CoE PDO configuration
+    /// or EEPROM. Any changes it makes are written to the SubDevice and used to compute the group's
+    /// PDI layout. This can be used to work around SubDevices with incorrect EEPROM data or unusual
+    /// mappings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, PdoDirection, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let group = group
+    ///     .into_pre_op_pdi_with_config(&client, |slave, direction, mappings| {
+    ///         // This SubDevice's EEPROM is missing the status word from its inputs
+    ///         if slave.name() == "EL9999" && direction == PdoDirection::MasterRead {
+    ///             for mapping in mappings.iter_mut().filter(|m| m.sync_manager == 3) {
+    ///                 mapping.bit_len += 16;
+    ///             }
+    ///         }
+    ///     })
+    ///     .await?;
+    ///
+    /// let group = group.into_safe_op(&client).await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn into_pre_op_pdi_with_config<F>(
+        mut self,
+        client: &Client<'_>,
+        configure_pdi: F,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, PreOpPdi, DC>, Error>
+    where
+        F: Fn(&SlaveRef<'_, &Slave>, PdoDirection, &mut SyncManagerMappings),
+    {
+        self.configure_fmmus(client, &configure_pdi, &|_slave| None)
+            .await?;
 
         Ok(SlaveGroup {
             id: self.id,
@@ -278,6 +894,7 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
             pdi_len: self.pdi_len,
             inner: UnsafeCell::new(self.inner.into_inner()),
             dc_conf: self.dc_conf,
+            pdi_time: AtomicRefCell::new(None),
             _state: PhantomData,
         })
     }
@@ -291,7 +908,195 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
 
         // We're done configuring FMMUs, etc, now we can request all slaves in this group go into
         // SAFE-OP
-        self_.transition_to(client, SlaveState::SafeOp).await
+        self_
+            .transition_to(client, SlaveState::PreOp, SlaveState::SafeOp)
+            .await
+    }
+
+    /// Transition the slave group from PRE-OP to SAFE-OP, writing a list of startup SDOs to each
+    /// SubDevice first.
+    ///
+    /// The `startup` hook is called once for every SubDevice in the group and returns the SDOs to
+    /// write to it, or an empty slice if none are required. The SDOs are written in order while the
+    /// group is still in PRE-OP, before the PDI is configured, so they may also be used to change
+    /// PDO assignments and mappings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, std::ethercat_now, Client, ClientConfig, PduStorage, StartupSdo, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// const SERVO_STARTUP: &[StartupSdo] = &[
+    ///     // Cyclic synchronous position mode
+    ///     StartupSdo::new(0x6060, 0, &[0x08]),
+    ///     // Following error window
+    ///     StartupSdo::new(0x6065, 0, &10_000u32.to_le_bytes()),
+    /// ];
+    ///
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let group = group
+    ///     .into_safe_op_with_startup(&client, |slave| match slave.name() {
+    ///         "AKD" => SERVO_STARTUP,
+    ///         _ => &[],
+    ///     })
+    ///     .await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    pub async fn into_safe_op_with_startup<'cmd, F>(
+        mut self,
+        client: &Client<'_>,
+        startup: F,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, DC>, Error>
+    where
+        F: Fn(&SlaveRef<'_, &Slave>) -> &'cmd [StartupSdo<'cmd>],
+    {
+        for slave in self
+            .inner
+            .get_mut()
+            .slaves
+            .iter_mut()
+            .map(AtomicRefCell::get_mut)
+        {
+            let slave = SlaveRef::new(client, slave.configured_address(), &*slave);
+
+            for sdo in startup(&slave) {
+                fmt::debug!(
+                    "Slave {:#06x} startup SDO {:#06x}:{} = {:?}",
+                    slave.configured_address(),
+                    sdo.index,
+                    sdo.sub_index,
+                    sdo.data
+                );
+
+                slave
+                    .sdo_write_slice(sdo.index, sdo.sub_index, sdo.data)
+                    .await
+                    .map_err(|e| {
+                        fmt::error!(
+                            "Slave {:#06x} startup SDO {:#06x}:{} failed: {}",
+                            slave.configured_address(),
+                            sdo.index,
+                            sdo.sub_index,
+                            e
+                        );
+
+                        e
+                    })?;
+            }
+        }
+
+        self.into_safe_op(client).await
+    }
+
+    /// Transition the slave group from PRE-OP to SAFE-OP, configuring SubDevices described in an
+    /// ESI file from the file instead of their EEPROM.
+    ///
+    /// This can be used for SubDevices with incomplete EEPROMs. Each SubDevice is matched to a
+    /// device in `esi` by its vendor ID, product ID and revision. Matching SubDevices have the CoE
+    /// init commands for the PRE-OP to SAFE-OP transition written to them, then their process data
+    /// is configured from the sync managers, FMMUs and PDOs in the ESI file. Other SubDevices are
+    /// configured from their EEPROM as normal.
+    ///
+    /// Mailboxes are configured from the EEPROM by [`Client::init`] before an ESI file can be
+    /// given, and SubDevices recovered by [`SlaveGroup::recover`] are reconfigured from their
+    /// EEPROM. DC sync modes are not applied automatically, but can be read with
+    /// [`EsiDevice::dc_sync_modes`](crate::sii::esi::EsiDevice::dc_sync_modes) and set with
+    /// [`SlaveRef::set_dc_sync_mode`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{ error::Error, sii::esi::EsiFile, std::ethercat_now, Client, ClientConfig, PduStorage, Timeouts };
+    /// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+    /// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+    /// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+    /// # async {
+    /// let xml = std::fs::read("Beckhoff EL31xx.xml").expect("read ESI file");
+    /// let esi = EsiFile::parse(&xml).expect("invalid ESI file");
+    ///
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let group = group.into_safe_op_with_esi(&client, &esi).await?;
+    /// # Ok::<(), Error>(())
+    /// # };
+    /// ```
+    #[cfg(feature = "std")]
+    pub async fn into_safe_op_with_esi(
+        mut self,
+        client: &Client<'_>,
+        esi: &EsiFile,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, DC>, Error> {
+        for slave in self
+            .inner
+            .get_mut()
+            .slaves
+            .iter_mut()
+            .map(AtomicRefCell::get_mut)
+        {
+            let slave = SlaveRef::new(client, slave.configured_address(), &*slave);
+
+            let Some(device) = esi.device(&slave.identity()) else {
+                continue;
+            };
+
+            fmt::debug!(
+                "Slave {:#06x} configured from ESI device {}",
+                slave.configured_address(),
+                device.name()
+            );
+
+            for cmd in device
+                .init_commands
+                .iter()
+                .filter(|cmd| cmd.runs_on(SlaveState::PreOp, SlaveState::SafeOp))
+            {
+                let sub_index = if cmd.complete_access {
+                    SubIndex::Complete
+                } else {
+                    SubIndex::Index(cmd.sub_index)
+                };
+
+                slave
+                    .sdo_write_slice(cmd.index, sub_index, &cmd.data)
+                    .await
+                    .map_err(|e| {
+                        fmt::error!(
+                            "Slave {:#06x} ESI init command {:#06x}:{} failed: {}",
+                            slave.configured_address(),
+                            cmd.index,
+                            cmd.sub_index,
+                            e
+                        );
+
+                        e
+                    })?;
+            }
+        }
+
+        self.configure_fmmus(client, &|_slave, _direction, _mappings| {}, &|slave| {
+            esi.device(&slave.identity()).map(EsiDevice::sii_override)
+        })
+        .await?;
+
+        let self_ = SlaveGroup::<MAX_SLAVES, MAX_PDI, PreOpPdi, DC> {
+            id: self.id,
+            pdi: self.pdi,
+            read_pdi_len: self.read_pdi_len,
+            pdi_len: self.pdi_len,
+            inner: UnsafeCell::new(self.inner.into_inner()),
+            dc_conf: self.dc_conf,
+            pdi_time: AtomicRefCell::new(None),
+            _state: PhantomData,
+        };
+
+        self_
+            .transition_to(client, SlaveState::PreOp, SlaveState::SafeOp)
+            .await
     }
 
     /// Transition all slave devices in the group from PRE-OP to INIT.
@@ -299,7 +1104,8 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
         self,
         client: &Client<'_>,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Init, DC>, Error> {
-        self.transition_to(client, SlaveState::Init).await
+        self.transition_to(client, SlaveState::PreOp, SlaveState::Init)
+            .await
     }
 
     /// Get an iterator over all slaves in this group.
@@ -349,9 +1155,27 @@ where
             pdi_len: self.pdi_len,
             inner: UnsafeCell::new(self.inner.into_inner()),
             dc_conf: NoDc,
+            pdi_time: AtomicRefCell::new(None),
             _state: PhantomData::<PreOp>,
         };
 
+        let reference_time: u64 = Command::fprd(reference, RegisterAddress::DcSystemTime.into())
+            .receive(client)
+            .await?;
+
+        // All SubDevices share the same start time so they begin pulsing SYNC0 on the same edge.
+        // It is rounded to a whole number of cycles so the process data cycle can be aligned to
+        // it.
+        let group_start_time = (reference_time + start_delay.as_nanos() as u64)
+            / sync0_period.as_nanos() as u64
+            * sync0_period.as_nanos() as u64;
+
+        fmt::debug!(
+            "--> Reference time {} ns, computed DC sync start time: {}",
+            reference_time,
+            group_start_time
+        );
+
         // Only configure DC for those devices that want and support it
         let dc_devices = GroupSlaveIterator::new(client, &self_).filter(|slave| {
             slave.dc_support().any() && !matches!(slave.dc_sync(), DcSync::Disabled)
@@ -378,27 +1202,39 @@ where
                 .send(client, 0u8)
                 .await?;
 
-            let device_time: u64 = slave
-                .read(RegisterAddress::DcSystemTime)
-                .ignore_wkc()
-                .receive(client)
-                .await?;
+            let timing = slave.dc_sync_timing();
 
-            fmt::debug!("--> Device time {} ns", device_time);
+            let sync0_period = timing.sync0_period.unwrap_or(sync0_period).as_nanos() as u64;
 
-            let sync0_period = sync0_period.as_nanos() as u64;
+            let start_time = timing.start_time.unwrap_or(group_start_time)
+                + timing.sync0_shift.as_nanos() as u64;
 
-            let first_pulse_delay = start_delay.as_nanos() as u64;
+            fmt::debug!("--> DC sync start time: {}", start_time);
 
-            // Round first pulse time to a whole number of cycles
-            let start_time = (device_time + first_pulse_delay) / sync0_period * sync0_period;
+            // SubDevices compare the start time with the lower 32 bits of their system time, so
+            // only those bits are shared between 32 and 64 bit SubDevices.
+            if slave.has_64bit_dc() {
+                let device_time: u64 = slave
+                    .read(RegisterAddress::DcSystemTime)
+                    .ignore_wkc()
+                    .receive(client)
+                    .await?;
 
-            fmt::debug!("--> Computed DC sync start time: {}", start_time);
+                // The next time after the device time with the same lower 32 bits as the start
+                // time. This is the start time itself if the reference clock is also 64 bit.
+                let start_time =
+                    device_time + u64::from((start_time as u32).wrapping_sub(device_time as u32));
 
-            slave
-                .write(RegisterAddress::DcSyncStartTime)
-                .send(client, start_time)
-                .await?;
+                slave
+                    .write(RegisterAddress::DcSyncStartTime)
+                    .send(client, start_time)
+                    .await?;
+            } else {
+                slave
+                    .write(RegisterAddress::DcSyncStartTime)
+                    .send(client, start_time as u32)
+                    .await?;
+            }
 
             // Cycle time in nanoseconds
             slave
@@ -423,6 +1259,17 @@ where
                 .await?;
         }
 
+        let configured_time: u64 = Command::fprd(reference, RegisterAddress::DcSystemTime.into())
+            .receive(client)
+            .await?;
+
+        if configured_time >= group_start_time {
+            fmt::warn!(
+                "DC configuration finished {} ns after the SYNC0 start time. Increase the start delay so all SubDevices start at the same time",
+                configured_time - group_start_time
+            );
+        }
+
         Ok(SlaveGroup {
             id: self_.id,
             pdi: self_.pdi,
@@ -433,7 +1280,9 @@ where
                 sync0_period: sync0_period.as_nanos() as u64,
                 sync0_shift: sync0_shift.as_nanos() as u64,
                 reference,
+                start_time: group_start_time,
             },
+            pdi_time: AtomicRefCell::new(None),
             _state: PhantomData,
         })
     }
@@ -447,7 +1296,73 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC>
         self,
         client: &Client<'_>,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, DC>, Error> {
-        self.transition_to(client, SlaveState::SafeOp).await
+        self.transition_to(client, SlaveState::PreOp, SlaveState::SafeOp)
+            .await
+    }
+
+    /// Transition the slave group from PRE-OP to SAFE-OP one SubDevice at a time, running
+    /// per-SubDevice hooks around each transition.
+    ///
+    /// For each SubDevice in turn, [`TransitionHooks::before`] is called, the SubDevice is
+    /// requested to go to SAFE-OP and waited for, then [`TransitionHooks::after`] is called. If any
+    /// of these fail, any SubDevice error is acknowledged and the SubDevice is tried again, up to
+    /// `retries` more times.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`HookError`] with the SubDevice and the step that failed once it runs out of
+    /// retries. In a group with [`with_partial_op`](SlaveGroup::with_partial_op), the SubDevice is
+    /// instead left behind, and an error is only returned if every SubDevice fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{error::Error, std::ethercat_now, Client, Slave, SlaveRef, SlaveState, TransitionHooks};
+    /// # async fn run(client: &Client<'_>) -> Result<(), Error> {
+    /// struct Startup;
+    ///
+    /// impl TransitionHooks for Startup {
+    ///     async fn before(&self, slave: &SlaveRef<'_, &Slave>, _state: SlaveState) -> Result<(), Error> {
+    ///         if slave.name() == "AKD" {
+    ///             slave.sdo_write(0x6060, 0, 0x08u8).await?;
+    ///         }
+    ///
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+    ///
+    /// let group = group
+    ///     .into_pre_op_pdi(client)
+    ///     .await?
+    ///     .into_safe_op_with_hooks(client, &Startup, 2)
+    ///     .await
+    ///     .map_err(|e| {
+    ///         log::error!("{}", e);
+    ///
+    ///         e
+    ///     })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn into_safe_op_with_hooks<H>(
+        self,
+        client: &Client<'_>,
+        hooks: &H,
+        retries: u8,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, DC>, HookError>
+    where
+        H: TransitionHooks,
+    {
+        self.transition_each(
+            client,
+            SlaveState::PreOp,
+            SlaveState::SafeOp,
+            hooks,
+            retries,
+        )
+        .await
     }
 
     /// Transition all slave devices in the group from PRE-OP to SAFE-OP, then to OP.
@@ -460,7 +1375,9 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC>
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op, DC>, Error> {
         let self_ = self.into_safe_op(client).await?;
 
-        self_.transition_to(client, SlaveState::Op).await
+        self_
+            .transition_to(client, SlaveState::SafeOp, SlaveState::Op)
+            .await
     }
 
     /// Like [`into_op`](SlaveGroup::into_op), however does not wait for all SubDevices to enter OP
@@ -485,7 +1402,8 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC>
         self,
         client: &Client<'_>,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Init, DC>, Error> {
-        self.transition_to(client, SlaveState::Init).await
+        self.transition_to(client, SlaveState::PreOp, SlaveState::Init)
+            .await
     }
 }
 
@@ -497,7 +1415,25 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC>
         self,
         client: &Client<'_>,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op, DC>, Error> {
-        self.transition_to(client, SlaveState::Op).await
+        self.transition_to(client, SlaveState::SafeOp, SlaveState::Op)
+            .await
+    }
+
+    /// Transition the slave group from SAFE-OP to OP one SubDevice at a time, running per-SubDevice
+    /// hooks around each transition.
+    ///
+    /// See [`into_safe_op_with_hooks`](SlaveGroup::into_safe_op_with_hooks) for details.
+    pub async fn into_op_with_hooks<H>(
+        self,
+        client: &Client<'_>,
+        hooks: &H,
+        retries: u8,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, Op, DC>, HookError>
+    where
+        H: TransitionHooks,
+    {
+        self.transition_each(client, SlaveState::SafeOp, SlaveState::Op, hooks, retries)
+            .await
     }
 
     /// Transition all slave devices in the group from SAFE-OP to PRE-OP.
@@ -505,7 +1441,8 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC>
         self,
         client: &Client<'_>,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, PreOp, DC>, Error> {
-        self.transition_to(client, SlaveState::PreOp).await
+        self.transition_to(client, SlaveState::SafeOp, SlaveState::PreOp)
+            .await
     }
 
     /// Like [`into_op`](SlaveGroup::into_op), however does not wait for all SubDevices to enter OP
@@ -539,6 +1476,7 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC>
             pdi_len: self.pdi_len,
             inner: UnsafeCell::new(self.inner.into_inner()),
             dc_conf: self.dc_conf,
+            pdi_time: AtomicRefCell::new(None),
             _state: PhantomData,
         })
     }
@@ -550,12 +1488,298 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, DC> SlaveGroup<MAX_SLAVES, M
         self,
         client: &Client<'_>,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, SafeOp, DC>, Error> {
-        self.transition_to(client, SlaveState::SafeOp).await
+        self.transition_to(client, SlaveState::Op, SlaveState::SafeOp)
+            .await
     }
 
     /// Returns true if all SubDevices in the group are in OP state
+    ///
+    /// SubDevices left behind by a group with [`with_partial_op`](SlaveGroup::with_partial_op)
+    /// aren't checked.
     pub async fn all_op(&self, client: &Client<'_>) -> Result<bool, Error> {
-        self.is_state(client, SlaveState::Op).await
+        self.is_state(client, SlaveState::Op, false).await
+    }
+
+    /// Add a hot connect module that was attached to the end of the network while the group is
+    /// in OP, returning the number of SubDevices added.
+    ///
+    /// If more SubDevices are on the network than were found by [`Client::init`] or a previous
+    /// call to this method, the first new SubDevice is checked against `id`. If it matches, it and
+    /// all SubDevices after it are configured and added to the end of this group. If it doesn't
+    /// match, nothing is changed and `Ok(0)` is returned, so the module can be offered to another
+    /// group.
+    ///
+    /// The new SubDevices' process data is mapped into space reserved with
+    /// [`with_reserved_pdi`](SlaveGroup::with_reserved_pdi), so the logical addresses of the
+    /// rest of the group and of other groups don't change. If the reserved space is too small,
+    /// [`Error::PdiTooLong`] is returned and the new SubDevices are left in PRE-OP.
+    ///
+    /// The new SubDevices are transitioned to SAFE-OP, and OP is requested without waiting for
+    /// it, as many SubDevices only enter OP once they receive process data. Keep calling
+    /// [`tx_rx`](SlaveGroup::tx_rx) and check [`all_op`](SlaveGroup::all_op). The new SubDevices
+    /// are not included in distributed clock configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{error::Error, slave_group::{HotConnectId, Op}, Client, SlaveGroup};
+    /// # async fn cycle(
+    /// #     client: &Client<'_>,
+    /// #     mut group: SlaveGroup<16, 64, Op>,
+    /// # ) -> Result<(), Error> {
+    /// // A module whose coupler's ID switches are set to 3
+    /// if group.hot_connect(client, HotConnectId::DeviceId(3)).await? > 0 {
+    ///     log::info!("Module connected, group now has {} SubDevices", group.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn hot_connect(
+        &mut self,
+        client: &Client<'_>,
+        id: HotConnectId,
+    ) -> Result<usize, Error> {
+        let known = client.num_slaves() as u16;
+        let found = client.count_slaves().await?;
+
+        if found <= known {
+            return Ok(0);
+        }
+
+        // SubDevices are addressed the same way as during init, so the first new SubDevice is
+        // given the address after the last known one.
+        for position in known..found {
+            Command::apwr(position, RegisterAddress::ConfiguredStationAddress.into())
+                .send(client, BASE_SLAVE_ADDR.wrapping_add(position))
+                .await?;
+        }
+
+        let head = SlaveRef::new(client, BASE_SLAVE_ADDR.wrapping_add(known), ());
+
+        if !id.matches(&head).await? {
+            fmt::debug!(
+                "Hot connect SubDevice {:#06x} does not match {:?}",
+                head.configured_address(),
+                id
+            );
+
+            return Ok(0);
+        }
+
+        fmt::debug!(
+            "Adding {} hot connect SubDevice(s) matching {:?} to group",
+            found - known,
+            id
+        );
+
+        let GroupPdi {
+            outputs_start,
+            read_pdi_len,
+            ..
+        } = self.group_pdi(client);
+        let pdi_len = self.pdi_len;
+        let inner = self.inner.get_mut();
+
+        // The end of the data already used in each direction, as offsets into the group PDI
+        let mut inputs_end = inner.input_ranges.iter().map(|r| r.end).max().unwrap_or(0);
+        let mut outputs_end = inner
+            .output_ranges
+            .iter()
+            .map(|r| r.end)
+            .max()
+            .unwrap_or(0)
+            .max(read_pdi_len);
+
+        let first = inner.slaves.len();
+
+        // If any SubDevice fails to configure, none of them are added to the group
+        let added = async {
+            for position in known..found {
+                let configured_address = BASE_SLAVE_ADDR.wrapping_add(position);
+
+                let mut slave = Slave::new(client, position, configured_address).await?;
+
+                let mut slave_ref = SlaveRef::new(client, configured_address, &mut slave);
+
+                slave_ref.configure_mailboxes().await?;
+
+                let end = slave_ref
+                    .configure_fmmus(
+                        inner.pdi_start.increment(inputs_end as u16),
+                        inner.pdi_start.start_address,
+                        0,
+                        PdoDirection::MasterRead,
+                        &|_slave, _direction, _mappings| {},
+                        None,
+                        Some(&mut inner.entries),
+                    )
+                    .await?;
+
+                inputs_end = (end.start_address - inner.pdi_start.start_address) as usize;
+
+                let end = slave_ref
+                    .configure_fmmus(
+                        outputs_start.increment((outputs_end - read_pdi_len) as u16),
+                        outputs_start.start_address,
+                        read_pdi_len,
+                        PdoDirection::MasterWrite,
+                        &|_slave, _direction, _mappings| {},
+                        None,
+                        Some(&mut inner.entries),
+                    )
+                    .await?;
+
+                outputs_end =
+                    read_pdi_len + (end.start_address - outputs_start.start_address) as usize;
+
+                for (desired_length, max_length) in
+                    [(inputs_end, read_pdi_len), (outputs_end, pdi_len)]
+                {
+                    if desired_length > max_length {
+                        fmt::error!(
+                            "Hot connect SubDevice {:#06x} does not fit in the reserved PDI",
+                            configured_address
+                        );
+
+                        return Err(Error::PdiTooLong {
+                            max_length,
+                            desired_length,
+                        });
+                    }
+                }
+
+                inner
+                    .slaves
+                    .push(AtomicRefCell::new(slave))
+                    .map_err(|_| Error::Capacity(Item::Slave))?;
+            }
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = added {
+            inner.slaves.truncate(first);
+
+            let slaves = &inner.slaves;
+
+            inner.entries.retain(|entry| {
+                slaves
+                    .iter()
+                    .any(|slave| slave.borrow().configured_address() == entry.configured_address())
+            });
+
+            return Err(e);
+        }
+
+        inner.update_ranges(client.pdu_loop.max_pdu_data());
+
+        client.set_num_slaves(found);
+
+        for slave in inner.slaves[first..].iter_mut().map(AtomicRefCell::get_mut) {
+            let slave = SlaveRef::new(client, slave.configured_address(), slave);
+
+            slave
+                .request_slave_state(SlaveState::PreOp, SlaveState::SafeOp)
+                .await?;
+            slave.request_slave_state_nowait(SlaveState::Op).await?;
+        }
+
+        Ok(usize::from(found - known))
+    }
+
+    /// Find SubDevices that dropped out of OP and try to return them to OP, returning an event
+    /// for each SubDevice found.
+    ///
+    /// A SubDevice that fell back to SAFE-OP, e.g. after its sync manager watchdog expired, has
+    /// its error acknowledged and is requested to go to OP. A SubDevice found in INIT, PRE-OP or
+    /// BOOT is configured again from INIT, with its process data mapped to the same place in the
+    /// group's PDI as before. If its process data no longer fits, e.g. because its PDO assignment
+    /// was reset, it is left in PRE-OP and [`Error::PdiTooLong`] is reported.
+    ///
+    /// This method is meant to be called periodically from a supervision task that runs alongside
+    /// the task calling [`tx_rx`](SlaveGroup::tx_rx), as SubDevices only enter OP once they
+    /// receive valid process data. SubDevices that are currently borrowed, e.g. with
+    /// [`slave`](SlaveGroup::slave), are skipped, and a SubDevice can't be borrowed while it is
+    /// being recovered.
+    ///
+    /// [Startup SDOs](SlaveGroup::into_safe_op_with_startup) and
+    /// [`configure_pdi`](SlaveGroup::into_pre_op_pdi_with_config) hooks are not run again. A
+    /// SubDevice that was power cycled loses its configured station address, so it can't be
+    /// recovered and is reported as not responding.
+    ///
+    /// The PDO entry locations used by [`SlaveRef::entry`] are kept from when the group was
+    /// configured, so a SubDevice must be mapped the same way after recovery for them to be
+    /// correct.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{slave_group::Op, Client, SlaveGroup};
+    /// # use std::time::Duration;
+    /// # async fn supervise(client: &Client<'_>, group: &SlaveGroup<16, 64, Op>) {
+    /// loop {
+    ///     for event in group.recover(client).await {
+    ///         match event.result {
+    ///             Ok(()) => log::info!(
+    ///                 "SubDevice {:#06x} recovered from {} ({})",
+    ///                 event.configured_address,
+    ///                 event.state,
+    ///                 event.status_code
+    ///             ),
+    ///             Err(e) => log::error!(
+    ///                 "SubDevice {:#06x} could not be recovered: {}",
+    ///                 event.configured_address,
+    ///                 e
+    ///             ),
+    ///         }
+    ///     }
+    ///
+    ///     smol::Timer::after(Duration::from_millis(500)).await;
+    /// }
+    /// # }
+    /// ```
+    pub async fn recover(&self, client: &Client<'_>) -> heapless::Vec<RecoveryEvent, MAX_SLAVES> {
+        let pdi = self.group_pdi(client);
+
+        let mut events = heapless::Vec::new();
+
+        for (index, slave) in self.inner().slaves.iter().enumerate() {
+            let Ok(mut slave) = slave.try_borrow_mut() else {
+                fmt::debug!(
+                    "Slave at index {} is borrowed, not checking its state",
+                    index
+                );
+
+                continue;
+            };
+
+            let mut slave = SlaveRef::new(client, slave.configured_address(), &mut *slave);
+
+            if let Some(event) = slave.recover(index, pdi).await {
+                fmt::unwrap!(events.push(event).map_err(|_| ()));
+            }
+        }
+
+        events
+    }
+
+    /// Where this group's inputs and outputs are mapped.
+    fn group_pdi(&self, client: &Client<'_>) -> GroupPdi {
+        let inputs_start = self.inner().pdi_start;
+
+        let outputs_start = match client.config.pdi_exchange {
+            PdiExchange::LrdLwr { overlap: true } => inputs_start,
+            PdiExchange::Lrw | PdiExchange::LrdLwr { overlap: false } => {
+                inputs_start.increment(self.read_pdi_len as u16)
+            }
+        };
+
+        GroupPdi {
+            inputs_start,
+            outputs_start,
+            read_pdi_len: self.read_pdi_len,
+        }
     }
 }
 
@@ -579,6 +1803,7 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> Default
             pdi_len: Default::default(),
             inner: UnsafeCell::new(GroupInner::default()),
             dc_conf: NoDc,
+            pdi_time: AtomicRefCell::new(None),
             _state: PhantomData,
         }
     }
@@ -602,6 +1827,90 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, DC> SlaveGroup<MAX_SLAVES
         self.inner().slaves.is_empty()
     }
 
+    /// Borrow the SubDevice with the given configured address, without access to its process
+    /// data.
+    ///
+    /// Returns `Ok(None)` if no SubDevice in this group has the given address. If the SubDevice
+    /// can't be found but another SubDevice in the group is borrowed, it may be the borrowed one, so
+    /// [`Error::Borrow`] is returned instead.
+    #[cfg(feature = "std")]
+    pub(crate) fn slave_by_address<'client, 'group>(
+        &'group self,
+        client: &'client Client<'client>,
+        configured_address: u16,
+    ) -> Result<Option<SlaveRef<'client, AtomicRefMut<'group, Slave>>>, Error> {
+        let mut borrowed = false;
+
+        for cell in self.inner().slaves.iter() {
+            let Ok(slave) = cell.try_borrow_mut() else {
+                borrowed = true;
+
+                continue;
+            };
+
+            if slave.configured_address() == configured_address {
+                return Ok(Some(SlaveRef::new(client, configured_address, slave)));
+            }
+        }
+
+        if borrowed {
+            fmt::debug!(
+                "Slave {:#06x} not found, but a slave in the group is already borrowed",
+                configured_address
+            );
+
+            return Err(Error::Borrow);
+        }
+
+        Ok(None)
+    }
+
+    /// Get the largest DC system time difference of all SubDevices in this group that support
+    /// distributed clocks, in nanoseconds.
+    ///
+    /// This can be polled during OP to monitor how well SubDevice clocks track the DC reference
+    /// clock. The reference time must be distributed periodically for SubDevices to compensate
+    /// drift, e.g. with [`tx_rx_sync_system_time`](SlaveGroup::tx_rx_sync_system_time) or
+    /// [`Client::sync_system_time`].
+    ///
+    /// SubDevices that are currently borrowed are skipped.
+    pub async fn max_system_time_difference(&self, client: &Client<'_>) -> Result<u32, Error> {
+        let mut stats = DcStatistics::new();
+
+        self.update_dc_statistics(client, &mut stats).await?;
+
+        Ok(stats.max_abs().unwrap_or(0))
+    }
+
+    /// Read the DC system time difference of all SubDevices in this group that support
+    /// distributed clocks, and add them to `stats`.
+    ///
+    /// Call this periodically, e.g. every few hundred cycles during OP, to collect the minimum,
+    /// maximum and mean deviation of SubDevice clocks from the DC reference clock over time.
+    ///
+    /// SubDevices that are currently borrowed are skipped.
+    pub async fn update_dc_statistics(
+        &self,
+        client: &Client<'_>,
+        stats: &mut DcStatistics,
+    ) -> Result<(), Error> {
+        for slave in self.inner().slaves.iter() {
+            let Ok(slave) = slave.try_borrow() else {
+                continue;
+            };
+
+            if !slave.dc_support().any() {
+                continue;
+            }
+
+            let slave = SlaveRef::new(client, slave.configured_address(), slave);
+
+            stats.push(slave.dc_system_time_difference().await?);
+        }
+
+        Ok(())
+    }
+
     #[allow(clippy::mut_from_ref)]
     fn pdi_mut(&self) -> &mut [u8] {
         let all_buf = unsafe { &mut *self.pdi.get() };
@@ -615,25 +1924,39 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, DC> SlaveGroup<MAX_SLAVES
         &all_buf[0..self.pdi_len]
     }
 
-    /// Check if all SubDevices in the group are the given desired state.
+    /// Check if all present SubDevices in the group are the given desired state.
+    ///
+    /// If `skip_faulted` is set, SubDevices that report an error or don't respond are ignored
+    /// instead of returning the error.
     async fn is_state(
         &self,
         client: &Client<'_>,
         desired_state: SlaveState,
+        skip_faulted: bool,
     ) -> Result<bool, Error> {
-        for slave in self.inner().slaves.iter().map(|slave| slave.borrow()) {
+        for slave in self
+            .inner()
+            .slaves
+            .iter()
+            .map(|slave| slave.borrow())
+            .filter(|slave| slave.is_present())
+        {
             let s = SlaveRef::new(client, slave.configured_address(), slave);
 
             // TODO: Add a way to queue up a bunch of PDUs and send all at once
-            let slave_state = s.state().await.map_err(|e| {
-                fmt::error!(
-                    "Failed to transition SubDevice {:#06x}: {}",
-                    s.configured_address(),
-                    e
-                );
-
-                e
-            })?;
+            let slave_state = match s.state().await {
+                Ok(state) => state,
+                Err(_) if skip_faulted => continue,
+                Err(e) => {
+                    fmt::error!(
+                        "Failed to transition SubDevice {:#06x}: {}",
+                        s.configured_address(),
+                        e
+                    );
+
+                    return Err(e);
+                }
+            };
 
             if slave_state != desired_state {
                 return Ok(false);
@@ -643,31 +1966,169 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, DC> SlaveGroup<MAX_SLAVES
         Ok(true)
     }
 
-    /// Wait for all slaves in this group to transition to the given state.
+    /// Wait for all slaves in this group to transition from `current_state` to the given state.
     async fn wait_for_state(
         &self,
         client: &Client<'_>,
+        current_state: SlaveState,
         desired_state: SlaveState,
     ) -> Result<(), Error> {
-        async {
+        let partial_op = self.inner().partial_op;
+
+        let result = async {
             loop {
-                if self.is_state(client, desired_state).await? {
+                if self.is_state(client, desired_state, partial_op).await? {
                     break Ok(());
                 }
 
                 client.timeouts.loop_tick().await;
             }
         }
-        .timeout(client.timeouts.state_transition)
-        .await
+        .timeout(client.timeouts.transition(current_state, desired_state))
+        .await;
+
+        match result {
+            // SubDevices that didn't reach the desired state are left behind by `transition_to`
+            Err(Error::Timeout) if partial_op => Ok(()),
+            Err(Error::Timeout) => Err(client
+                .transition_timeout(
+                    self.inner()
+                        .slaves
+                        .iter()
+                        .map(|slave| slave.borrow())
+                        .filter(|slave| slave.is_present())
+                        .map(|slave| slave.configured_address()),
+                    desired_state,
+                )
+                .await),
+            result => result,
+        }
+    }
+
+    /// Mark every present SubDevice that isn't in `desired_state` as missing.
+    ///
+    /// Returns [`Error::StateTransition`] if no SubDevice in a non-empty group reached the desired
+    /// state.
+    async fn leave_behind(
+        &mut self,
+        client: &Client<'_>,
+        desired_state: SlaveState,
+    ) -> Result<(), Error> {
+        let slaves = &mut self.inner.get_mut().slaves;
+
+        for slave in slaves
+            .iter_mut()
+            .map(AtomicRefCell::get_mut)
+            .filter(|slave| slave.is_present())
+        {
+            let state = SlaveRef::new(client, slave.configured_address(), ())
+                .state()
+                .await;
+
+            if state != Ok(desired_state) {
+                fmt::warn!(
+                    "Slave {:#06x} did not reach {}, leaving it behind: {:?}",
+                    slave.configured_address(),
+                    desired_state,
+                    state
+                );
+
+                slave.missing = true;
+            }
+        }
+
+        if slaves.iter_mut().all(|slave| !slave.get_mut().is_present()) && !slaves.is_empty() {
+            fmt::error!("No slaves in group reached {}", desired_state);
+
+            return Err(Error::StateTransition);
+        }
+
+        Ok(())
+    }
+
+    /// Transition to a new state one SubDevice at a time, running `hooks` around each SubDevice's
+    /// transition.
+    async fn transition_each<TO, H>(
+        mut self,
+        client: &Client<'_>,
+        current_state: SlaveState,
+        desired_state: SlaveState,
+        hooks: &H,
+        retries: u8,
+    ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, TO, DC>, HookError>
+    where
+        H: TransitionHooks,
+    {
+        let inner = self.inner.get_mut();
+
+        let mut last_error = None;
+
+        for (index, slave) in inner
+            .slaves
+            .iter_mut()
+            .map(AtomicRefCell::get_mut)
+            .enumerate()
+            .filter(|(_index, slave)| slave.is_present())
+        {
+            let result = hooks::transition_with_hooks(
+                &SlaveRef::new(client, slave.configured_address(), &*slave),
+                index,
+                hooks,
+                current_state,
+                desired_state,
+                retries,
+            )
+            .await;
+
+            match result {
+                Err(e) if inner.partial_op => {
+                    fmt::warn!(
+                        "Slave {:#06x} did not reach {}, leaving it behind",
+                        e.configured_address,
+                        desired_state
+                    );
+
+                    slave.missing = true;
+
+                    last_error = Some(e);
+                }
+                result => result?,
+            }
+        }
+
+        if let Some(e) = last_error {
+            if inner
+                .slaves
+                .iter_mut()
+                .all(|slave| !slave.get_mut().is_present())
+            {
+                return Err(e);
+            }
+        }
+
+        fmt::debug!("--> Group reached state {}", desired_state);
+
+        Ok(SlaveGroup {
+            id: self.id,
+            pdi: self.pdi,
+            read_pdi_len: self.read_pdi_len,
+            pdi_len: self.pdi_len,
+            inner: UnsafeCell::new(self.inner.into_inner()),
+            dc_conf: self.dc_conf,
+            pdi_time: AtomicRefCell::new(None),
+            _state: PhantomData,
+        })
     }
 
     /// Transition to a new state.
     async fn transition_to<TO>(
         mut self,
         client: &Client<'_>,
+        current_state: SlaveState,
         desired_state: SlaveState,
     ) -> Result<SlaveGroup<MAX_SLAVES, MAX_PDI, TO, DC>, Error> {
+        let partial_op = self.inner.get_mut().partial_op;
+
         // We're done configuring FMMUs, etc, now we can request all slaves in this group go into
         // SAFE-OP
         for slave in self
@@ -676,15 +2137,35 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, DC> SlaveGroup<MAX_SLAVES
             .slaves
             .iter_mut()
             .map(AtomicRefCell::get_mut)
+            .filter(|slave| slave.is_present())
         {
-            SlaveRef::new(client, slave.configured_address(), slave)
+            let result = SlaveRef::new(client, slave.configured_address(), &mut *slave)
                 .request_slave_state_nowait(desired_state)
-                .await?;
+                .await;
+
+            match result {
+                Err(e) if partial_op => {
+                    fmt::warn!(
+                        "Slave {:#06x} failed to transition to {}, leaving it behind: {}",
+                        slave.configured_address(),
+                        desired_state,
+                        e
+                    );
+
+                    slave.missing = true;
+                }
+                result => result?,
+            }
         }
 
         fmt::debug!("Waiting for group state {}", desired_state);
 
-        self.wait_for_state(client, desired_state).await?;
+        self.wait_for_state(client, current_state, desired_state)
+            .await?;
+
+        if partial_op {
+            self.leave_behind(client, desired_state).await?;
+        }
 
         fmt::debug!("--> Group reached state {}", desired_state);
 
@@ -695,6 +2176,7 @@ impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, DC> SlaveGroup<MAX_SLAVES
             pdi_len: self.pdi_len,
             inner: UnsafeCell::new(self.inner.into_inner()),
             dc_conf: self.dc_conf,
+            pdi_time: AtomicRefCell::new(None),
             _state: PhantomData,
         })
     }
@@ -710,6 +2192,9 @@ where
     /// Each slave device in the group is wrapped in an `AtomicRefCell`, meaning it may only have a
     /// single reference to it at any one time. Multiple different slaves can be borrowed
     /// simultaneously, but multiple references to the same slave are not allowed.
+    ///
+    /// Each SubDevice has its own mailbox state, so SDO and other mailbox transfers to different
+    /// SubDevices can be run concurrently, e.g. from separate tasks, to reduce startup time.
     pub fn slave<'client, 'group>(
         &'group self,
         client: &'client Client<'client>,
@@ -774,10 +2259,49 @@ where
             // SAFETY: A given slave contained in a `SlavePdi` MUST only be borrowed once (currently
             // enforced by `AtomicRefCell`). If it is borrowed more than once, immutable APIs in
             // `SlaveRef<SlavePdi>` will be unsound.
-            SlavePdi::new(slave, inputs, outputs),
+            SlavePdi::new(slave, inputs, outputs, &self.inner().entries),
         ))
     }
 
+    /// Borrow several slave devices at once, each with mutable access to its own outputs.
+    ///
+    /// Each SubDevice's inputs and outputs are separate parts of the group PDI, so the returned
+    /// references can be moved into different tasks or threads which update their own SubDevice's
+    /// outputs concurrently, instead of funnelling every write through one task.
+    ///
+    /// This method returns [`Error::Borrow`] if the same index is given more than once or any of
+    /// the SubDevices is already borrowed, and [`Error::NotFound`] if an index is out of range. No
+    /// SubDevices are left borrowed on error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use ethercrab::{error::Error, slave_group::Op, Client, SlaveGroup};
+    /// # fn split(client: &Client<'_>, group: SlaveGroup<16, 64, Op>) -> Result<(), Error> {
+    /// let [mut outputs, mut drive] = group.slaves_disjoint(client, [1, 3])?;
+    ///
+    /// std::thread::scope(|s| {
+    ///     s.spawn(move || outputs.outputs_raw_mut().fill(0xff));
+    ///     s.spawn(move || drive.outputs_raw_mut()[0] = 0x0f);
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn slaves_disjoint<'client, 'group, const N: usize>(
+        &'group self,
+        client: &'client Client<'client>,
+        indices: [usize; N],
+    ) -> Result<[SlaveRef<'client, SlavePdi<'group>>; N], Error> {
+        let mut slaves = heapless::Vec::<_, N>::new();
+
+        for index in indices {
+            fmt::unwrap!(slaves.push(self.slave(client, index)?).map_err(|_| ()));
+        }
+
+        // Unwrap: one SubDevice is pushed for each index
+        Ok(fmt::unwrap!(slaves.into_array().map_err(|_| ())))
+    }
+
     /// Get an iterator over all slaves in this group.
     pub fn iter<'group, 'client>(
         &'group mut self,
@@ -791,18 +2315,25 @@ where
     /// A `SlaveGroup` will not process any inputs or outputs unless this method is called
     /// periodically. It will send an `LRW` to update slave outputs and read slave inputs.
     ///
+    /// If [`ClientConfig::pdi_timestamp`](crate::ClientConfig::pdi_timestamp) is enabled, an
+    /// `FPRD` of the DC reference clock's system time is sent in the same frame. The time can be
+    /// read with [`pdi_time`](SlaveGroup::pdi_time).
+    ///
+    /// If [`ClientConfig::pdi_exchange`](crate::ClientConfig::pdi_exchange) is set to
+    /// [`PdiExchange::LrdLwr`], inputs are read with an `LRD` and
+    /// outputs are written with an `LWR` instead.
+    ///
+    /// If the group's PDI is too long to fit in a single frame, it is split into multiple `LRW`s
+    /// sent in consecutive frames. The PDI is only split between SubDevices, so the returned working
+    /// counter is the sum of all `LRW`s and can be checked the same way as for a single frame.
+    ///
     /// This method returns the working counter on success.
     ///
     /// # Errors
     ///
     /// This method will return with an error if the PDU could not be sent over the network, or the
-    /// response times out.
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if the frame data length of the group is too large to fit in the
-    /// configured maximum PDU length set by the `DATA` const generic of
-    /// [`PduStorage`](crate::PduStorage).
+    /// response times out. [`Error::PdiTooLong`] is returned if a single SubDevice's process data
+    /// is too long to fit in one frame.
     pub async fn tx_rx<'sto>(&self, client: &'sto Client<'sto>) -> Result<u16, Error> {
         fmt::trace!(
             "Group TX/RX, start address {:#010x}, data len {}, of which read bytes: {}",
@@ -811,19 +2342,403 @@ where
             self.read_pdi_len
         );
 
-        assert!(
-            self.len() <= client.max_frame_data(),
-            "Chunked sends not yet supported. Buffer len {} B too long to send in {} B frame",
-            self.len(),
-            client.max_frame_data()
+        let time_command = client
+            .dc_ref_address()
+            .filter(|_| client.config.pdi_timestamp)
+            .map(|dc_ref| Command::fprd(dc_ref, RegisterAddress::DcSystemTime.into()).into());
+
+        let (wkc, time) = self
+            .tx_rx_chunks(
+                client,
+                time_command,
+                0..self.pdi().len(),
+                client.config.pdi_exchange,
+            )
+            .await?;
+
+        if let Some(time) = time {
+            self.set_pdi_time(time);
+        }
+
+        Ok(wkc)
+    }
+
+    /// Signal that the application has written this cycle's outputs.
+    ///
+    /// This must be called every cycle before [`tx_rx`](SlaveGroup::tx_rx) if
+    /// [`FailSafe::missed_cycles`](crate::FailSafe::missed_cycles) is set, otherwise the group's
+    /// outputs are put into their fail-safe state.
+    pub fn outputs_updated(&self) {
+        self.inner().fail_safe.outputs_updated();
+    }
+
+    /// Whether the most recent process data exchange was sent with the group's outputs in their
+    /// fail-safe state, as configured by [`ClientConfig::fail_safe`](crate::ClientConfig::fail_safe).
+    pub fn fail_safe_active(&self) -> bool {
+        self.inner().fail_safe.is_active()
+    }
+
+    /// Copy the group's inputs into a [`PdiSnapshot`] so they can be read by other tasks without
+    /// being torn by the next process data exchange.
+    ///
+    /// This should be called by the same task that calls [`tx_rx`](SlaveGroup::tx_rx), after each
+    /// exchange. It never waits for readers of the snapshot. Returns `false` if the snapshot could
+    /// not be published because a reader still holds the buffer it would be written to.
+    pub fn snapshot_inputs(&self, snapshot: &PdiSnapshot<MAX_SLAVES, MAX_PDI>) -> bool {
+        snapshot.publish(
+            &self.pdi()[0..self.read_pdi_len],
+            self.inner().input_ranges.iter().cloned(),
+            self.pdi_time(),
+        )
+    }
+
+    /// Overwrite the group's outputs with any bits forced in an [`OutputForces`] table.
+    ///
+    /// This should be called by the same task that calls [`tx_rx`](SlaveGroup::tx_rx), every
+    /// cycle after the application has written its outputs and before `tx_rx`, so forced bits
+    /// are sent instead of the application's values.
+    ///
+    /// Returns [`Error::NotFound`] if a forced SubDevice is not in this group, or a forced byte is
+    /// outside of its outputs, and [`Error::Borrow`] if another task is changing the table at the
+    /// same moment. No outputs are changed in either case.
+    pub fn apply_forces<const N: usize>(&self, forces: &OutputForces<N>) -> Result<(), Error> {
+        forces.apply(self.pdi_mut(), &self.inner().output_ranges)
+    }
+
+    /// Check a watched region of a SubDevice's inputs for changes since the last call, waking any
+    /// task waiting in [`InputWatch::changed`].
+    ///
+    /// This should be called by the same task that calls [`tx_rx`](SlaveGroup::tx_rx), after each
+    /// exchange. Returns `true` if any watched bit changed, so a callback can be run from the
+    /// cyclic task without waiting on the watch. If another task is reading the watch at the same
+    /// moment, the update is skipped and the change is reported by the next call instead.
+    ///
+    /// Returns [`Error::NotFound`] if the watched SubDevice is not in this group, or if the watched
+    /// region is outside of its inputs.
+    pub fn update_watch<const LEN: usize>(&self, watch: &InputWatch<LEN>) -> Result<bool, Error> {
+        let range = self
+            .inner()
+            .input_ranges
+            .get(watch.slave())
+            .cloned()
+            .ok_or(Error::NotFound {
+                item: Item::Slave,
+                index: Some(watch.slave()),
+            })?;
+
+        watch.update(&self.pdi()[range]).ok_or(Error::NotFound {
+            item: Item::PdiRegion,
+            index: Some(watch.slave()),
+        })
+    }
+
+    /// Read the slave group's inputs without writing its outputs.
+    ///
+    /// Only the inputs are sent, in one or more `LRD`s, so this is faster than
+    /// [`tx_rx`](SlaveGroup::tx_rx) for groups with large outputs, e.g. to sample inputs more
+    /// often than outputs are updated. SubDevice outputs keep their previous values.
+    ///
+    /// This method returns the working counter on success, which is incremented once by every
+    /// SubDevice with inputs. If the group has no inputs, nothing is sent and `0` is returned.
+    ///
+    /// # Errors
+    ///
+    /// This method will return with an error if the PDU could not be sent over the network, or the
+    /// response times out.
+    pub async fn tx_rx_inputs_only<'sto>(&self, client: &'sto Client<'sto>) -> Result<u16, Error> {
+        fmt::trace!(
+            "Group RX inputs, start address {:#010x}, read bytes: {}",
+            self.inner().pdi_start.start_address,
+            self.read_pdi_len
+        );
+
+        if self.read_pdi_len == 0 {
+            return Ok(0);
+        }
+
+        let (wkc, _time) = self
+            .tx_rx_chunks(
+                client,
+                None,
+                0..self.read_pdi_len,
+                client.config.pdi_exchange.separate(),
+            )
+            .await?;
+
+        Ok(wkc)
+    }
+
+    /// Write the slave group's outputs without reading its inputs.
+    ///
+    /// Only the outputs are sent, in one or more `LWR`s, so this is faster than
+    /// [`tx_rx`](SlaveGroup::tx_rx) for groups with large inputs. The group's input data is not
+    /// updated.
+    ///
+    /// This method returns the working counter on success, which is incremented once by every
+    /// SubDevice with outputs. If the group has no outputs, nothing is sent and `0` is returned.
+    ///
+    /// # Errors
+    ///
+    /// This method will return with an error if the PDU could not be sent over the network, or the
+    /// response times out.
+    pub async fn tx_rx_outputs_only<'sto>(&self, client: &'sto Client<'sto>) -> Result<u16, Error> {
+        fmt::trace!(
+            "Group TX outputs, start address {:#010x}, write bytes: {}",
+            self.inner().pdi_start.start_address,
+            self.pdi().len() - self.read_pdi_len
         );
 
-        let data = Command::lrw(self.inner().pdi_start.start_address)
-            .ignore_wkc()
-            .send_receive_slice(client, self.pdi())
+        if self.pdi().len() == self.read_pdi_len {
+            return Ok(0);
+        }
+
+        let (wkc, _time) = self
+            .tx_rx_chunks(
+                client,
+                None,
+                self.read_pdi_len..self.pdi().len(),
+                client.config.pdi_exchange.separate(),
+            )
             .await?;
 
-        self.process_pdi_response(&data)
+        Ok(wkc)
+    }
+
+    /// Send the given `range` of the group PDI, applying
+    /// [`ClientConfig::fail_safe`](crate::ClientConfig::fail_safe) if it includes outputs.
+    ///
+    /// See [`exchange_chunks`](SlaveGroup::exchange_chunks) for how the range is sent.
+    async fn tx_rx_chunks<'sto>(
+        &self,
+        client: &'sto Client<'sto>,
+        time_command: Option<Command>,
+        range: Range<usize>,
+        exchange: PdiExchange,
+    ) -> Result<(u16, Option<u64>), Error> {
+        if range.end <= self.read_pdi_len {
+            return self
+                .exchange_chunks(client, time_command, range, exchange)
+                .await;
+        }
+
+        let config = &client.config.fail_safe;
+        let fail_safe = &self.inner().fail_safe;
+
+        let (range, exchange) = if fail_safe.begin_exchange(config) {
+            match config.outputs {
+                FailSafeOutputs::Clear => {
+                    self.pdi_mut()[self.read_pdi_len..].fill(0);
+
+                    (range, exchange)
+                }
+                FailSafeOutputs::Hold => (
+                    range.start..self.read_pdi_len.max(range.start),
+                    exchange.separate(),
+                ),
+            }
+        } else {
+            (range, exchange)
+        };
+
+        if range.is_empty() && time_command.is_none() {
+            return Ok((0, None));
+        }
+
+        let res = self
+            .exchange_chunks(client, time_command, range.clone(), exchange)
+            .await;
+
+        fail_safe.end_exchange(matches!(
+            res,
+            Ok((wkc, _)) if wkc == self.expected_wkc(range, exchange)
+        ));
+
+        res
+    }
+
+    /// The working counter expected when the given `range` of the group PDI is exchanged.
+    fn expected_wkc(&self, range: Range<usize>, exchange: PdiExchange) -> u16 {
+        let inner = self.inner();
+
+        let count = |ranges: &[Range<usize>]| {
+            ranges
+                .iter()
+                .filter(|slave| {
+                    !slave.is_empty() && slave.start < range.end && range.start < slave.end
+                })
+                .count() as u16
+        };
+
+        let write_wkc = if exchange == PdiExchange::Lrw { 2 } else { 1 };
+
+        count(&inner.input_ranges) + count(&inner.output_ranges) * write_wkc
+    }
+
+    /// Send the given `range` of the group PDI in one or more `LRW`s, or `LRD`s and `LWR`s,
+    /// splitting it across multiple frames if it is too long to fit in one.
+    ///
+    /// If `time_command` is given, it is sent in the first frame and the DC system time it reads
+    /// is returned.
+    ///
+    /// The working counters of all PDUs are summed. The PDI is only split at SubDevice boundaries,
+    /// so this is the same value as if the whole PDI were sent in one frame. If a single
+    /// SubDevice's data doesn't fit in a frame, [`Error::PdiTooLong`] is returned.
+    async fn exchange_chunks<'sto>(
+        &self,
+        client: &'sto Client<'sto>,
+        time_command: Option<Command>,
+        range: Range<usize>,
+        exchange: PdiExchange,
+    ) -> Result<(u16, Option<u64>), Error> {
+        let inner = self.inner();
+        let pdi_len = range.end;
+        let max_pdu_data = client.pdu_loop.max_pdu_data();
+
+        let mut start = range.start;
+        let mut wkc = 0u16;
+        let mut time = None;
+
+        loop {
+            // The time is only read in the first frame
+            let time_command = time_command.filter(|_| start == range.start);
+
+            let max_len = if time_command.is_some() {
+                max_pdu_data.saturating_sub(PDU_OVERHEAD + 8)
+            } else {
+                max_pdu_data
+            };
+
+            let splits = || {
+                inner
+                    .input_splits
+                    .iter()
+                    .chain(inner.output_splits.iter())
+                    .copied()
+            };
+
+            let mut end = pdi_chunk_end(start, pdi_len, max_len, splits())?;
+
+            // A chunk holding both inputs and outputs needs an extra PDU header when they are sent
+            // with separate commands.
+            if exchange != PdiExchange::Lrw && start < self.read_pdi_len && self.read_pdi_len < end
+            {
+                end = pdi_chunk_end(
+                    start,
+                    pdi_len,
+                    max_len.saturating_sub(PDU_OVERHEAD),
+                    splits(),
+                )?;
+            }
+
+            let pdus = chunk_pdus(
+                start..end,
+                inner.pdi_start.start_address,
+                self.read_pdi_len,
+                exchange,
+            );
+
+            // The working counter is returned to the caller instead of being checked here.
+            let (chunk_time, chunk_wkc) = client
+                .cyclic_multi_pdu(
+                    |frame| {
+                        let dc_handle = time_command
+                            .map(|command| frame.push_pdu::<u64>(command, 0u64, None, true))
+                            .transpose()?;
+
+                        let mut pdu_handles = heapless::Vec::<_, 2>::new();
+
+                        for (i, (command, range)) in pdus.iter().enumerate() {
+                            let handle = frame.push_pdu::<()>(
+                                *command,
+                                &self.pdi()[range.clone()],
+                                None,
+                                i + 1 < pdus.len(),
+                            )?;
+
+                            // Unwrap: there is one handle per PDU
+                            fmt::unwrap!(pdu_handles.push(handle).map_err(|_| ()));
+                        }
+
+                        Ok((dc_handle, pdu_handles))
+                    },
+                    |received, (dc, pdu_handles)| {
+                        let time = dc
+                            .map(|dc| {
+                                received
+                                    .take(dc)
+                                    .and_then(|dc| u64::unpack_from_slice(&dc).map_err(Error::from))
+                            })
+                            .transpose()?;
+
+                        let mut wkc = 0u16;
+                        let mut mismatch = None;
+
+                        for (handle, (command, range)) in pdu_handles.into_iter().zip(pdus.iter()) {
+                            let pdu_wkc =
+                                self.process_pdi_response(range.clone(), &received.take(handle)?)?;
+
+                            let expected = client
+                                .config
+                                .verify_pdi_wkc
+                                .then(|| self.expected_wkc(range.clone(), exchange));
+
+                            if let Some(expected) = expected.filter(|expected| *expected != pdu_wkc)
+                            {
+                                mismatch.get_or_insert(Error::PdiWorkingCounter {
+                                    start_address: command.logical_address().unwrap_or_default(),
+                                    length: range.len(),
+                                    expected,
+                                    received: pdu_wkc,
+                                });
+                            }
+
+                            wkc = wkc.wrapping_add(pdu_wkc);
+                        }
+
+                        // All inputs are still copied into the PDI before returning an error
+                        if let Some(e) = mismatch {
+                            fmt::error!("{}", e);
+
+                            return Err(e);
+                        }
+
+                        Ok((time, wkc))
+                    },
+                )
+                .await?;
+
+            wkc = wkc.wrapping_add(chunk_wkc);
+            time = time.or(chunk_time);
+
+            if end >= pdi_len {
+                break Ok((wkc, time));
+            }
+
+            start = end;
+        }
+    }
+
+    /// The DC reference clock's system time in nanoseconds, read in the same frame as the most
+    /// recent process data exchange.
+    ///
+    /// This is set by [`tx_rx_sync_system_time`](SlaveGroup::tx_rx_sync_system_time) and
+    /// [`tx_rx_dc`](SlaveGroup::tx_rx_dc), and by [`tx_rx`](SlaveGroup::tx_rx) if
+    /// [`ClientConfig::pdi_timestamp`](crate::ClientConfig::pdi_timestamp) is enabled, so the
+    /// current input data can be related to EtherCAT bus time. `None` is returned if no time has
+    /// been read yet, if there are no DC SubDevices on the network, or if the time is being updated
+    /// by another task at the same moment.
+    pub fn pdi_time(&self) -> Option<u64> {
+        self.pdi_time.try_borrow().ok().and_then(|time| *time)
+    }
+
+    /// Store the DC system time read with the process data.
+    ///
+    /// If the time is being read by another task at the same moment, this update is skipped
+    /// instead of waiting.
+    fn set_pdi_time(&self, time: u64) {
+        if let Ok(mut pdi_time) = self.pdi_time.try_borrow_mut() {
+            *pdi_time = Some(time);
+        }
     }
 
     /// Drive the slave group's inputs and outputs and synchronise EtherCAT system time with `FRMW`.
@@ -838,23 +2753,10 @@ where
     ///
     /// This method will return with an error if the PDU could not be sent over the network, or the
     /// response times out.
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if the frame data length of the group is too large to fit in the
-    /// configured maximum PDU length set by the `DATA` const generic of
-    /// [`PduStorage`](crate::PduStorage).
     pub async fn tx_rx_sync_system_time<'sto>(
         &self,
         client: &'sto Client<'sto>,
     ) -> Result<(u16, Option<u64>), Error> {
-        assert!(
-            self.len() <= client.max_frame_data(),
-            "Chunked sends not yet supported. Buffer len {} B too long to send in {} B frame",
-            self.len(),
-            client.max_frame_data()
-        );
-
         fmt::trace!(
             "Group TX/RX with DC sync, start address {:#010x}, data len {}, of which read bytes: {}",
             self.inner().pdi_start.start_address,
@@ -863,62 +2765,39 @@ where
         );
 
         if let Some(dc_ref) = client.dc_ref_address() {
-            let (time, wkc) = client
-                .multi_pdu(
-                    |frame| {
-                        let dc_handle = frame.push_pdu::<u64>(
-                            Command::frmw(dc_ref, RegisterAddress::DcSystemTime.into()).into(),
-                            0u64,
-                            None,
-                            true,
-                        )?;
-
-                        let pdu_handle = frame.push_pdu::<()>(
-                            Command::lrw(self.inner().pdi_start.start_address).into(),
-                            self.pdi(),
-                            None,
-                            false,
-                        )?;
-
-                        Ok((dc_handle, pdu_handle))
-                    },
-                    |received, (dc, data)| {
-                        self.process_pdi_response_with_time(
-                            &received.take(dc)?,
-                            &received.take(data)?,
-                        )
-                    },
+            let (wkc, time) = self
+                .tx_rx_chunks(
+                    client,
+                    Some(Command::frmw(dc_ref, RegisterAddress::DcSystemTime.into()).into()),
+                    0..self.pdi().len(),
+                    client.config.pdi_exchange,
                 )
                 .await?;
 
+            let time = time.ok_or(Error::Internal)?;
+
+            client.set_dc_time(time);
+            self.set_pdi_time(time);
+
             Ok((wkc, Some(time)))
         } else {
             self.tx_rx(client).await.map(|wkc| (wkc, None))
         }
     }
 
-    fn process_pdi_response_with_time(
-        &self,
-        dc: &crate::pdu_loop::ReceivedPdu<'_, u64>,
-        data: &crate::pdu_loop::ReceivedPdu<'_, ()>,
-    ) -> Result<(u64, u16), Error> {
-        let time = u64::unpack_from_slice(dc)?;
-
-        Ok((time, self.process_pdi_response(data)?))
-    }
-
-    /// Take a received PDI and copy its inputs into the group's memory.
+    /// Take a received chunk of the PDI and copy its inputs into the group's memory.
     ///
     /// Returns working counter on success.
     fn process_pdi_response(
         &self,
+        chunk: Range<usize>,
         data: &crate::pdu_loop::ReceivedPdu<'_, ()>,
     ) -> Result<u16, Error> {
-        if data.len() != self.pdi().len() {
+        if data.len() != chunk.len() {
             fmt::error!(
                 "Data length {} does not match value length {}",
                 data.len(),
-                self.pdi().len()
+                chunk.len()
             );
 
             return Err(Error::Pdu(PduError::Decode));
@@ -926,12 +2805,33 @@ where
 
         let wkc = data.working_counter;
 
-        self.pdi_mut()[0..self.read_pdi_len].copy_from_slice(&data[0..self.read_pdi_len]);
+        let inputs_end = chunk.end.min(self.read_pdi_len);
+
+        if inputs_end > chunk.start {
+            self.pdi_mut()[chunk.start..inputs_end]
+                .copy_from_slice(&data[0..(inputs_end - chunk.start)]);
+        }
 
         Ok(wkc)
     }
 }
 
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> SlaveGroup<MAX_SLAVES, MAX_PDI, S, HasDc> {
+    /// Get the configured address of the DC reference SubDevice this group is synchronised to.
+    pub fn dc_ref_address(&self) -> u16 {
+        self.dc_conf.reference
+    }
+
+    /// Get the DC system time in nanoseconds of the first SYNC0 pulse of all SubDevices in this
+    /// group, before any per-SubDevice [`DcSyncTiming`](crate::DcSyncTiming) is applied.
+    ///
+    /// SubDevices in the group begin pulsing SYNC0 on the same edge, so this time can be used to
+    /// coordinate moves of multiple axes.
+    pub fn dc_start_time(&self) -> u64 {
+        self.dc_conf.start_time
+    }
+}
+
 // Methods for when the group has a PDI AND has Distributed Clocks configured
 impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S> SlaveGroup<MAX_SLAVES, MAX_PDI, S, HasDc>
 where
@@ -951,12 +2851,6 @@ where
     /// This method will return with an error if the PDU could not be sent over the network, or the
     /// response times out.
     ///
-    /// # Panics
-    ///
-    /// This method will panic if the frame data length of the group is too large to fit in the
-    /// configured maximum PDU length set by the `DATA` const generic of
-    /// [`PduStorage`](crate::PduStorage).
-    ///
     /// # Examples
     ///
     /// This example sends process data at 2.5ms offset into a 5ms cycle.
@@ -1048,13 +2942,6 @@ where
         &self,
         client: &'sto Client<'sto>,
     ) -> Result<(u16, CycleInfo), Error> {
-        assert!(
-            self.len() <= client.max_frame_data(),
-            "Chunked sends not yet supported. Buffer len {} B too long to send in {} B frame",
-            self.len(),
-            client.max_frame_data()
-        );
-
         fmt::trace!(
             "Group TX/RX with DC sync, start address {:#010x}, data len {}, of which read bytes: {}",
             self.inner().pdi_start.start_address,
@@ -1062,32 +2949,23 @@ where
             self.read_pdi_len
         );
 
-        let (time, wkc) = client
-            .multi_pdu(
-                |frame| {
-                    let dc_handle = frame.push_pdu::<u64>(
-                        Command::frmw(self.dc_conf.reference, RegisterAddress::DcSystemTime.into())
-                            .into(),
-                        0u64,
-                        None,
-                        true,
-                    )?;
-
-                    let pdu_handle = frame.push_pdu::<()>(
-                        Command::lrw(self.inner().pdi_start.start_address).into(),
-                        self.pdi(),
-                        None,
-                        false,
-                    )?;
-
-                    Ok((dc_handle, pdu_handle))
-                },
-                |received, (dc, data)| {
-                    self.process_pdi_response_with_time(&received.take(dc)?, &received.take(data)?)
-                },
+        let (wkc, time) = self
+            .tx_rx_chunks(
+                client,
+                Some(
+                    Command::frmw(self.dc_conf.reference, RegisterAddress::DcSystemTime.into())
+                        .into(),
+                ),
+                0..self.pdi().len(),
+                client.config.pdi_exchange,
             )
             .await?;
 
+        let time = time.ok_or(Error::Internal)?;
+
+        client.set_dc_time(time);
+        self.set_pdi_time(time);
+
         // Nanoseconds from the start of the cycle. This works because the first SYNC0 pulse
         // time is rounded to a whole number of `sync0_period`-length cycles.
         let cycle_start_offset = time % self.dc_conf.sync0_period;
@@ -1105,3 +2983,240 @@ where
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn pdi_chunks() {
+        // Inputs of 3 SubDevices then outputs of 2
+        let splits = [40, 90, 100, 160];
+
+        let chunks = |max_len| {
+            let mut chunks = Vec::new();
+            let mut start = 0;
+
+            loop {
+                let end = pdi_chunk_end(start, 200, max_len, splits.iter().copied())?;
+
+                chunks.push(start..end);
+
+                if end >= 200 {
+                    break Ok(chunks);
+                }
+
+                start = end;
+            }
+        };
+
+        assert_eq!(chunks(1000), Ok(vec![0..200]));
+        assert_eq!(chunks(200), Ok(vec![0..200]));
+        assert_eq!(chunks(100), Ok(vec![0..100, 100..200]));
+        assert_eq!(chunks(95), Ok(vec![0..90, 90..160, 160..200]));
+        assert_eq!(chunks(60), Ok(vec![0..40, 40..100, 100..160, 160..200]));
+        // SubDevice data longer than a chunk is not split
+        assert_eq!(
+            chunks(45),
+            Err(Error::PdiTooLong {
+                max_length: 45,
+                desired_length: 50
+            })
+        );
+        assert_eq!(
+            chunks(30),
+            Err(Error::PdiTooLong {
+                max_length: 30,
+                desired_length: 40
+            })
+        );
+    }
+
+    #[test]
+    fn lrd_lwr_chunks() {
+        let pdus = |chunk, exchange| {
+            chunk_pdus(chunk, 0x1000, 100, exchange)
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            pdus(0..150, PdiExchange::Lrw),
+            vec![(Command::lrw(0x1000).into(), 0..150)]
+        );
+
+        assert_eq!(
+            pdus(0..150, PdiExchange::LrdLwr { overlap: false }),
+            vec![
+                (Command::lrd(0x1000).into(), 0..100),
+                (Command::lwr(0x1000 + 100).into(), 100..150)
+            ]
+        );
+
+        // Outputs are mapped to the same logical addresses as inputs
+        assert_eq!(
+            pdus(0..150, PdiExchange::LrdLwr { overlap: true }),
+            vec![
+                (Command::lrd(0x1000).into(), 0..100),
+                (Command::lwr(0x1000).into(), 100..150)
+            ]
+        );
+
+        assert_eq!(
+            pdus(40..100, PdiExchange::LrdLwr { overlap: true }),
+            vec![(Command::lrd(0x1000 + 40).into(), 40..100)]
+        );
+
+        assert_eq!(
+            pdus(120..150, PdiExchange::LrdLwr { overlap: true }),
+            vec![(Command::lwr(0x1000 + 20).into(), 120..150)]
+        );
+    }
+
+    #[test]
+    fn disjoint_borrows() {
+        static PDU_STORAGE: crate::PduStorage<1, 64> = crate::PduStorage::new();
+
+        let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().unwrap();
+
+        let client = Client::new(pdu_loop, crate::Timeouts::default(), Default::default());
+
+        let mut group = SlaveGroup::<4, 8, Op>::default();
+
+        for _ in 0..3 {
+            group
+                .inner
+                .get_mut()
+                .slaves
+                .push(AtomicRefCell::new(Slave::default()))
+                .unwrap();
+        }
+
+        let [first, third] = group.slaves_disjoint(&client, [0, 2]).unwrap();
+
+        assert_eq!(
+            group.slaves_disjoint(&client, [1, 2]).err(),
+            Some(Error::Borrow)
+        );
+
+        drop(third);
+
+        // Failed borrows are released
+        assert!(group.slaves_disjoint(&client, [1, 2]).is_ok());
+
+        assert_eq!(
+            group.slaves_disjoint(&client, [1, 1]).err(),
+            Some(Error::Borrow)
+        );
+        assert_eq!(
+            group.slaves_disjoint(&client, [1, 4]).err(),
+            Some(Error::NotFound {
+                item: Item::Slave,
+                index: Some(4)
+            })
+        );
+
+        drop(first);
+    }
+
+    #[test]
+    fn move_between_groups() {
+        let addresses = |group: &mut SlaveGroup<3, 8, PreOp>| {
+            group
+                .inner
+                .get_mut()
+                .slaves
+                .iter_mut()
+                .map(|slave| slave.get_mut().configured_address)
+                .collect::<Vec<_>>()
+        };
+
+        let mut a = SlaveGroup::<3, 8, PreOp>::default();
+        let mut b = SlaveGroup::<3, 8, PreOp>::default();
+
+        for configured_address in [0x1000, 0x1001, 0x1002] {
+            a.inner
+                .get_mut()
+                .slaves
+                .push(AtomicRefCell::new(Slave {
+                    configured_address,
+                    ..Slave::default()
+                }))
+                .unwrap();
+        }
+
+        a.move_slave(1, &mut b).unwrap();
+
+        assert_eq!(addresses(&mut a), [0x1000, 0x1002]);
+        assert_eq!(addresses(&mut b), [0x1001]);
+
+        a.split_off(0, &mut b).unwrap();
+
+        assert_eq!(addresses(&mut a), []);
+        assert_eq!(addresses(&mut b), [0x1001, 0x1000, 0x1002]);
+
+        assert_eq!(
+            b.move_slave(3, &mut a),
+            Err(Error::NotFound {
+                item: Item::Slave,
+                index: Some(3)
+            })
+        );
+
+        b.move_slave(0, &mut a).unwrap();
+
+        // Nothing is moved if the other group is too small
+        let mut small = SlaveGroup::<1, 8, PreOp>::default();
+
+        assert_eq!(small.append(&mut b), Err(Error::Capacity(Item::Slave)));
+        assert_eq!(addresses(&mut b), [0x1000, 0x1002]);
+
+        b.append(&mut a).unwrap();
+
+        assert_eq!(addresses(&mut a), []);
+        assert_eq!(addresses(&mut b), [0x1000, 0x1002, 0x1001]);
+    }
+
+    #[test]
+    fn reserved_pdi() {
+        let start = PdiOffset {
+            start_address: 0x1000,
+        };
+
+        // SubDevices use fewer bytes than reserved
+        assert_eq!(
+            reserve(start.increment(6), start, 16, "input"),
+            start.increment(16)
+        );
+        // SubDevices use more bytes than reserved
+        assert_eq!(
+            reserve(start.increment(20), start, 16, "input"),
+            start.increment(20)
+        );
+        assert_eq!(reserve(start, start, 0, "output"), start);
+    }
+
+    #[test]
+    fn dc_statistics() {
+        let mut stats = DcStatistics::new();
+
+        assert_eq!(stats.min(), None);
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.max_abs(), None);
+
+        for difference in [20, -150, 40, 10] {
+            stats.push(difference);
+        }
+
+        assert_eq!(stats.count(), 4);
+        assert_eq!(stats.min(), Some(-150));
+        assert_eq!(stats.max(), Some(40));
+        assert_eq!(stats.max_abs(), Some(150));
+        assert_eq!(stats.mean(), Some(-20));
+
+        stats.reset();
+
+        assert_eq!(stats, DcStatistics::new());
+    }
+}
@@ -0,0 +1,257 @@
+use crate::error::{Error, Item};
+use core::time::Duration;
+
+/// The most ticks checked when choosing a new group's phase offset.
+const MAX_OFFSET_SEARCH: u64 = 10_000;
+
+#[derive(Debug, Copy, Clone)]
+struct Scheduled {
+    /// Cycle time in ticks.
+    period: u64,
+    /// The tick in each cycle the group is exchanged on, from `0..period`.
+    offset: u64,
+}
+
+impl Scheduled {
+    fn is_due(&self, tick: u64) -> bool {
+        tick % self.period == self.offset
+    }
+}
+
+/// Interleave process data exchanges of groups running at different cycle times from a single
+/// task, e.g. a 1 ms servo group and a 10 ms IO group.
+///
+/// The schedule runs on a base tick, and each group's cycle time is a whole number of ticks. Each
+/// call to [`next_tick`](CycleSchedule::next_tick) returns the groups due on the next tick, which
+/// should be exchanged one after the other before waiting for the following tick. Exchanging every
+/// group from the same task means frames from different groups never compete for the network.
+///
+/// Groups due on the same tick are returned shortest cycle time first, so the fastest groups are
+/// sent at a consistent offset into each tick. Slower groups are given a phase offset when they
+/// are added so that, where possible, they are due on ticks where fewer other groups are.
+///
+/// Like [`DcCycleController`](crate::slave_group::DcCycleController), the schedule doesn't wait
+/// itself, so any timer can be used. `N` is the most groups that can be scheduled.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{error::Error, slave_group::{CycleSchedule, Op}, Client, SlaveGroup};
+/// # use std::time::{Duration, Instant};
+/// # async fn cycle(client: &Client<'_>, servos: SlaveGroup<4, 32, Op>, io: SlaveGroup<16, 64, Op>) -> Result<(), Error> {
+/// let mut schedule = CycleSchedule::<2>::new(Duration::from_millis(1));
+///
+/// let servo_cycle = schedule.add(Duration::from_millis(1))?;
+/// let io_cycle = schedule.add(Duration::from_millis(10))?;
+///
+/// let mut tick = Instant::now();
+///
+/// loop {
+///     for group in schedule.next_tick() {
+///         if group == servo_cycle {
+///             servos.tx_rx(client).await?;
+///         } else if group == io_cycle {
+///             io.tx_rx(client).await?;
+///         }
+///     }
+///
+///     tick += schedule.tick();
+///
+///     smol::Timer::at(tick).await;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CycleSchedule<const N: usize> {
+    tick: Duration,
+    /// Groups in the order they were added.
+    groups: heapless::Vec<Scheduled, N>,
+    /// Indices into `groups`, shortest cycle time first.
+    order: heapless::Vec<usize, N>,
+    /// The tick that will be returned by the next call to `next_tick`.
+    current: u64,
+}
+
+impl<const N: usize> CycleSchedule<N> {
+    /// Create an empty schedule with the given base tick.
+    ///
+    /// The tick should be the shortest cycle time of any group, or a common divisor of all the
+    /// cycle times.
+    pub fn new(tick: Duration) -> Self {
+        Self {
+            tick,
+            groups: heapless::Vec::new(),
+            order: heapless::Vec::new(),
+            current: 0,
+        }
+    }
+
+    /// The base tick of the schedule.
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+
+    /// Add a group with the given cycle time, returning the index that will be returned by
+    /// [`next_tick`](CycleSchedule::next_tick) when it is due.
+    ///
+    /// The cycle time is rounded up to a whole number of ticks, with a minimum of one tick.
+    /// Returns [`Error::Capacity`] if `N` groups have already been added.
+    pub fn add(&mut self, cycle_time: Duration) -> Result<usize, Error> {
+        let tick = self.tick.as_nanos().max(1);
+
+        let period = cycle_time.as_nanos().div_ceil(tick).max(1) as u64;
+
+        let offset = self.least_loaded_offset(period);
+
+        let index = self.groups.len();
+
+        self.groups
+            .push(Scheduled { period, offset })
+            .map_err(|_| Error::Capacity(Item::Group))?;
+
+        let position = self
+            .order
+            .iter()
+            .position(|other| self.groups[*other].period > period)
+            .unwrap_or(self.order.len());
+
+        // `order` has the same capacity as `groups`, so this can't fail.
+        self.order
+            .insert(position, index)
+            .map_err(|_| Error::Internal)?;
+
+        Ok(index)
+    }
+
+    /// The cycle time of the given group, rounded to a whole number of ticks.
+    pub fn cycle_time(&self, index: usize) -> Option<Duration> {
+        self.groups
+            .get(index)
+            .map(|group| self.tick * group.period as u32)
+    }
+
+    /// Choose the offset in `0..period` where the most other groups due on any one tick is
+    /// lowest, preferring earlier offsets.
+    fn least_loaded_offset(&self, period: u64) -> u64 {
+        let hyperperiod = self
+            .groups
+            .iter()
+            .fold(period, |acc, group| lcm(acc, group.period))
+            .min(MAX_OFFSET_SEARCH.max(period));
+
+        (0..period)
+            .min_by_key(|offset| {
+                (*offset..hyperperiod)
+                    .step_by(period as usize)
+                    .map(|tick| {
+                        self.groups
+                            .iter()
+                            .filter(|group| group.is_due(tick))
+                            .count()
+                    })
+                    .max()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Advance to the next tick, returning the indices of the groups due on it, shortest cycle
+    /// time first.
+    pub fn next_tick(&mut self) -> DueGroups<'_, N> {
+        let tick = self.current;
+
+        self.current = self.current.wrapping_add(1);
+
+        DueGroups {
+            schedule: self,
+            tick,
+            position: 0,
+        }
+    }
+
+    /// Start again from the first tick, e.g. after the cyclic task was paused.
+    pub fn reset(&mut self) {
+        self.current = 0;
+    }
+}
+
+/// The groups due on a tick of a [`CycleSchedule`], returned by
+/// [`CycleSchedule::next_tick`].
+#[derive(Debug)]
+pub struct DueGroups<'schedule, const N: usize> {
+    schedule: &'schedule CycleSchedule<N>,
+    tick: u64,
+    /// Position in `schedule.order`.
+    position: usize,
+}
+
+impl<'schedule, const N: usize> Iterator for DueGroups<'schedule, N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(index) = self.schedule.order.get(self.position).copied() {
+            self.position += 1;
+
+            if self.schedule.groups[index].is_due(self.tick) {
+                return Some(index);
+            }
+        }
+
+        None
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    (a / gcd(a, b)).saturating_mul(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleave_groups() {
+        let mut schedule = CycleSchedule::<3>::new(Duration::from_millis(1));
+
+        let io = schedule.add(Duration::from_millis(4)).unwrap();
+        let servo = schedule.add(Duration::from_micros(500)).unwrap();
+        let slow = schedule.add(Duration::from_millis(4)).unwrap();
+
+        assert_eq!(schedule.cycle_time(servo), Some(Duration::from_millis(1)));
+        assert_eq!(
+            schedule.add(Duration::from_millis(1)),
+            Err(Error::Capacity(Item::Group))
+        );
+
+        let ticks = (0..8)
+            .map(|_| schedule.next_tick().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // Fastest group first, and the two slow groups are on different ticks
+        assert_eq!(
+            ticks,
+            [
+                vec![servo, io],
+                vec![servo, slow],
+                vec![servo],
+                vec![servo],
+                vec![servo, io],
+                vec![servo, slow],
+                vec![servo],
+                vec![servo],
+            ]
+        );
+
+        schedule.reset();
+
+        assert_eq!(schedule.next_tick().collect::<Vec<_>>(), [servo, io]);
+    }
+}
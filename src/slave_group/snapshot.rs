@@ -0,0 +1,238 @@
+use atomic_refcell::{AtomicRef, AtomicRefCell};
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+};
+
+/// Sentinel for [`PdiSnapshot::latest`] before anything has been published.
+const NONE: usize = usize::MAX;
+
+struct SnapshotBuffer<const MAX_SLAVES: usize, const MAX_PDI: usize> {
+    inputs: [u8; MAX_PDI],
+    len: usize,
+    /// Each SubDevice's inputs, as a range into `inputs`.
+    slave_inputs: heapless::Vec<Range<usize>, MAX_SLAVES>,
+    cycle: u32,
+    pdi_time: Option<u64>,
+}
+
+/// A double buffered copy of a group's inputs that can be read from a different task than the one
+/// calling [`SlaveGroup::tx_rx`](crate::SlaveGroup::tx_rx).
+///
+/// Reading a group's inputs with e.g. [`SlaveRef::io_raw`](crate::SlaveRef::io_raw) while another
+/// task is exchanging process data can return a mix of data from two cycles, e.g. half of an
+/// updated `u32`. Instead, the cyclic task copies the inputs into a `PdiSnapshot` after each
+/// exchange with [`SlaveGroup::snapshot_inputs`](crate::SlaveGroup::snapshot_inputs), and other
+/// tasks read a complete copy from a single cycle with [`read`](PdiSnapshot::read).
+///
+/// The cyclic task never waits for readers. If a reader still holds the buffer the next snapshot
+/// would be written to, that cycle is not published and readers keep seeing the previous one.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{error::Error, slave_group::{Op, PdiSnapshot}, Client, SlaveGroup};
+/// # async fn tasks(client: &Client<'_>, group: SlaveGroup<16, 64, Op>) -> Result<(), Error> {
+/// static SNAPSHOT: PdiSnapshot<16, 64> = PdiSnapshot::new();
+///
+/// // Cyclic task
+/// group.tx_rx(client).await?;
+/// group.snapshot_inputs(&SNAPSHOT);
+///
+/// // Application task
+/// if let Some(snapshot) = SNAPSHOT.read() {
+///     let first = snapshot.slave_inputs(0);
+///
+///     log::info!("Cycle {}: {:?}", snapshot.cycle(), first);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct PdiSnapshot<const MAX_SLAVES: usize, const MAX_PDI: usize> {
+    buffers: [AtomicRefCell<SnapshotBuffer<MAX_SLAVES, MAX_PDI>>; 2],
+    /// Index into `buffers` of the most recently published snapshot.
+    latest: AtomicUsize,
+    /// The number of snapshots published so far.
+    cycle: AtomicU32,
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize> Default for PdiSnapshot<MAX_SLAVES, MAX_PDI> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize> PdiSnapshot<MAX_SLAVES, MAX_PDI> {
+    /// Create a new, empty snapshot.
+    ///
+    /// `MAX_SLAVES` and `MAX_PDI` must be the same as the group's.
+    pub const fn new() -> Self {
+        Self {
+            buffers: [
+                AtomicRefCell::new(SnapshotBuffer::new()),
+                AtomicRefCell::new(SnapshotBuffer::new()),
+            ],
+            latest: AtomicUsize::new(NONE),
+            cycle: AtomicU32::new(0),
+        }
+    }
+
+    /// Copy a group's inputs into whichever buffer is not the latest snapshot, then make it the
+    /// latest.
+    ///
+    /// Returns `false` if the buffer is still borrowed by a reader.
+    pub(crate) fn publish(
+        &self,
+        inputs: &[u8],
+        slave_inputs: impl Iterator<Item = Range<usize>>,
+        pdi_time: Option<u64>,
+    ) -> bool {
+        // Only one task publishes, so the latest buffer can't change while this runs.
+        let next = match self.latest.load(Ordering::Acquire) {
+            NONE => 0,
+            latest => 1 - latest,
+        };
+
+        let Ok(mut buf) = self.buffers[next].try_borrow_mut() else {
+            return false;
+        };
+
+        buf.inputs[0..inputs.len()].copy_from_slice(inputs);
+        buf.len = inputs.len();
+        buf.slave_inputs.clear();
+        buf.slave_inputs.extend(slave_inputs.take(MAX_SLAVES));
+        buf.cycle = self.cycle.load(Ordering::Relaxed);
+        buf.pdi_time = pdi_time;
+
+        self.cycle
+            .store(buf.cycle.wrapping_add(1), Ordering::Relaxed);
+
+        drop(buf);
+
+        self.latest.store(next, Ordering::Release);
+
+        true
+    }
+
+    /// Borrow the most recently published snapshot.
+    ///
+    /// Returns `None` if no snapshot has been published yet, or if the cyclic task is writing to
+    /// the latest buffer faster than it can be borrowed. While the returned reference is held, the
+    /// cyclic task publishes into the other buffer, and stops publishing new snapshots once that
+    /// one becomes the latest, so it should be dropped as soon as possible.
+    pub fn read(&self) -> Option<PdiSnapshotRef<'_, MAX_SLAVES, MAX_PDI>> {
+        // The buffer may be borrowed by the cyclic task if it has already moved on to the next
+        // cycle, in which case the other buffer has just become the latest. Try once more with it
+        // instead of waiting for the cyclic task.
+        for _ in 0..2 {
+            let latest = self.latest.load(Ordering::Acquire);
+
+            if latest == NONE {
+                return None;
+            }
+
+            if let Ok(buf) = self.buffers[latest].try_borrow() {
+                return Some(PdiSnapshotRef { buf });
+            }
+        }
+
+        None
+    }
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize> SnapshotBuffer<MAX_SLAVES, MAX_PDI> {
+    const fn new() -> Self {
+        Self {
+            inputs: [0u8; MAX_PDI],
+            len: 0,
+            slave_inputs: heapless::Vec::new(),
+            cycle: 0,
+            pdi_time: None,
+        }
+    }
+}
+
+/// A consistent copy of a group's inputs from a single cycle, returned by [`PdiSnapshot::read`].
+pub struct PdiSnapshotRef<'snapshot, const MAX_SLAVES: usize, const MAX_PDI: usize> {
+    buf: AtomicRef<'snapshot, SnapshotBuffer<MAX_SLAVES, MAX_PDI>>,
+}
+
+impl<'snapshot, const MAX_SLAVES: usize, const MAX_PDI: usize>
+    PdiSnapshotRef<'snapshot, MAX_SLAVES, MAX_PDI>
+{
+    /// The inputs of every SubDevice in the group.
+    pub fn inputs(&self) -> &[u8] {
+        &self.buf.inputs[0..self.buf.len]
+    }
+
+    /// The inputs of the SubDevice at the given index in the group, or `None` if the index is out
+    /// of range.
+    pub fn slave_inputs(&self, index: usize) -> Option<&[u8]> {
+        self.buf
+            .slave_inputs
+            .get(index)
+            .map(|range| &self.buf.inputs[range.clone()])
+    }
+
+    /// The number of snapshots published before this one.
+    ///
+    /// This can be used to tell whether a snapshot is new, or how many cycles were missed between
+    /// two reads.
+    pub fn cycle(&self) -> u32 {
+        self.buf.cycle
+    }
+
+    /// The DC reference clock's system time read in the same frame as these inputs, as returned by
+    /// [`SlaveGroup::pdi_time`](crate::SlaveGroup::pdi_time).
+    pub fn pdi_time(&self) -> Option<u64> {
+        self.buf.pdi_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reader_holds_buffer() {
+        let snapshot = PdiSnapshot::<2, 8>::new();
+
+        assert!(snapshot.read().is_none());
+
+        assert!(snapshot.publish(&[1, 2, 3], [0..1, 1..3].into_iter(), Some(100)));
+
+        let first = snapshot.read().unwrap();
+
+        assert_eq!(first.inputs(), &[1, 2, 3]);
+        assert_eq!(first.slave_inputs(1), Some(&[2u8, 3][..]));
+        assert_eq!(first.slave_inputs(2), None);
+        assert_eq!(first.pdi_time(), Some(100));
+
+        // Written into the other buffer
+        assert!(snapshot.publish(&[4, 5, 6], [0..1, 1..3].into_iter(), None));
+        // The next buffer is still held by `first`
+        assert!(!snapshot.publish(&[7, 8, 9], [0..1, 1..3].into_iter(), None));
+
+        assert_eq!(first.inputs(), &[1, 2, 3], "first snapshot is unchanged");
+        assert_eq!(snapshot.read().unwrap().inputs(), &[4, 5, 6]);
+        assert_eq!(snapshot.read().unwrap().cycle(), 1);
+
+        drop(first);
+
+        assert!(snapshot.publish(&[7, 8, 9], [0..1, 1..3].into_iter(), None));
+        assert_eq!(snapshot.read().unwrap().inputs(), &[7, 8, 9]);
+        assert_eq!(snapshot.read().unwrap().cycle(), 2);
+    }
+
+    #[test]
+    fn read_does_not_block() {
+        let snapshot = PdiSnapshot::<2, 8>::new();
+
+        assert!(snapshot.publish(&[1, 2, 3], [0..1, 1..3].into_iter(), None));
+
+        // Latest buffer being written to, e.g. by a cyclic task pre-empted mid-publish
+        let _writer = snapshot.buffers[0].borrow_mut();
+
+        assert!(snapshot.read().is_none());
+    }
+}
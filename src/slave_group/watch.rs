@@ -0,0 +1,231 @@
+use atomic_refcell::AtomicRefCell;
+use atomic_waker::AtomicWaker;
+use core::{
+    future::poll_fn,
+    sync::atomic::{AtomicBool, Ordering},
+    task::Poll,
+};
+
+/// Watch a region of a SubDevice's inputs for changes between process data cycles.
+///
+/// The cyclic task checks the region after each exchange with
+/// [`SlaveGroup::update_watch`](crate::SlaveGroup::update_watch), which returns `true` if any of
+/// the watched bits changed, so a callback can be run directly from the cyclic task. Other tasks
+/// can instead wait for a change with [`changed`](InputWatch::changed), without polling the whole
+/// input image.
+///
+/// Only bits set in the mask are compared. The first update only records the current value and
+/// is not counted as a change.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{error::Error, slave_group::{InputWatch, Op}, Client, SlaveGroup};
+/// # async fn tasks(client: &Client<'_>, group: SlaveGroup<16, 64, Op>) -> Result<(), Error> {
+/// // Bit 2 of the first byte of the second SubDevice's inputs, e.g. a digital input terminal.
+/// static START_BUTTON: InputWatch<1> = InputWatch::bit(1, 0, 2);
+///
+/// // Cyclic task
+/// group.tx_rx(client).await?;
+/// group.update_watch(&START_BUTTON)?;
+///
+/// // Application task
+/// let [state] = START_BUTTON.changed().await;
+///
+/// log::info!("Start button pressed: {}", state != 0);
+/// # Ok(())
+/// # }
+/// ```
+pub struct InputWatch<const LEN: usize> {
+    slave: usize,
+    byte_offset: usize,
+    mask: [u8; LEN],
+    /// The masked value at the last update, or `None` before the first update.
+    value: AtomicRefCell<Option<[u8; LEN]>>,
+    changed: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl InputWatch<1> {
+    /// Watch a single bit, from 0 to 7, of the given byte of a SubDevice's inputs.
+    pub const fn bit(slave: usize, byte_offset: usize, bit: u8) -> Self {
+        Self::new(slave, byte_offset, [1 << (bit % 8)])
+    }
+}
+
+impl<const LEN: usize> InputWatch<LEN> {
+    /// Watch the bits set in `mask` of the `LEN` bytes starting at `byte_offset` in the inputs of
+    /// the SubDevice at index `slave` in the group.
+    ///
+    /// Offsets are relative to the SubDevice's inputs, the same as
+    /// [`PdiEntry::byte_offset`](crate::PdiEntry::byte_offset).
+    pub const fn new(slave: usize, byte_offset: usize, mask: [u8; LEN]) -> Self {
+        Self {
+            slave,
+            byte_offset,
+            mask,
+            value: AtomicRefCell::new(None),
+            changed: AtomicBool::new(false),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    /// The index of the watched SubDevice in its group.
+    pub(crate) fn slave(&self) -> usize {
+        self.slave
+    }
+
+    /// Compare the watched region of a SubDevice's inputs with the previous value, waking any task
+    /// waiting for a change.
+    ///
+    /// Returns `None` if the region lies outside the given inputs. If the value is being read by
+    /// another task at the same moment, this update is skipped and returns `Some(false)`, and any
+    /// change is reported by the next update instead.
+    pub(crate) fn update(&self, inputs: &[u8]) -> Option<bool> {
+        let region = inputs.get(self.byte_offset..(self.byte_offset + LEN))?;
+
+        let mut current = [0u8; LEN];
+
+        for ((current, input), mask) in current.iter_mut().zip(region).zip(self.mask.iter()) {
+            *current = input & mask;
+        }
+
+        let Ok(mut value) = self.value.try_borrow_mut() else {
+            return Some(false);
+        };
+
+        let previous = value.replace(current);
+
+        drop(value);
+
+        let changed = previous.is_some_and(|previous| previous != current);
+
+        if changed {
+            self.changed.store(true, Ordering::Release);
+            self.waker.wake();
+        }
+
+        Some(changed)
+    }
+
+    /// The masked value of the watched region at the last update, or `None` if it hasn't been
+    /// updated yet or is being updated at the same moment.
+    pub fn value(&self) -> Option<[u8; LEN]> {
+        self.value.try_borrow().ok().and_then(|value| *value)
+    }
+
+    /// Wait for the watched region to change, returning its new masked value.
+    ///
+    /// If the region has changed since this method last returned, it returns immediately. Only one
+    /// task should wait on a watch at a time.
+    pub async fn changed(&self) -> [u8; LEN] {
+        poll_fn(|ctx| {
+            self.waker.register(ctx.waker());
+
+            if !self.changed.swap(false, Ordering::Acquire) {
+                return Poll::Pending;
+            }
+
+            // A change can only be recorded after the first update, so there is always a value
+            // unless the cyclic task is updating it right now. Yield to the executor and try again
+            // instead of waiting here.
+            match self.value() {
+                Some(value) => Poll::Ready(value),
+                None => {
+                    self.changed.store(true, Ordering::Release);
+                    ctx.waker().wake_by_ref();
+
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masked_changes() {
+        let watch = InputWatch::new(0, 1, [0b0000_0100, 0xff]);
+
+        assert_eq!(watch.value(), None);
+
+        // First update only records the value
+        assert_eq!(watch.update(&[0x00, 0b0000_0100, 0x12]), Some(false));
+        assert_eq!(watch.value(), Some([0b0000_0100, 0x12]));
+
+        // Unwatched bits and bytes
+        assert_eq!(watch.update(&[0xff, 0b1111_0101, 0x12]), Some(false));
+
+        assert_eq!(watch.update(&[0xff, 0b1111_0001, 0x12]), Some(true));
+        assert_eq!(watch.update(&[0xff, 0b1111_0001, 0x13]), Some(true));
+
+        // Region is past the end of the inputs
+        assert_eq!(watch.update(&[0xff, 0xff]), None);
+    }
+
+    #[test]
+    fn wake_on_change() {
+        let watch = InputWatch::bit(0, 0, 0);
+
+        watch.update(&[0]);
+
+        let waiter = watch.changed();
+
+        futures_lite::pin!(waiter);
+
+        assert_eq!(
+            futures_lite::future::block_on(futures_lite::future::poll_once(&mut waiter)),
+            None
+        );
+
+        watch.update(&[1]);
+
+        assert_eq!(
+            futures_lite::future::block_on(futures_lite::future::poll_once(&mut waiter)),
+            Some([1])
+        );
+    }
+
+    #[test]
+    fn does_not_block() {
+        let watch = InputWatch::bit(0, 0, 0);
+
+        watch.update(&[0]);
+        watch.update(&[1]);
+
+        let waiter = watch.changed();
+
+        futures_lite::pin!(waiter);
+
+        {
+            // Value being read by another task
+            let _reader = watch.value.borrow();
+
+            // Skipped instead of waiting for the reader
+            assert_eq!(watch.update(&[0]), Some(false));
+        }
+
+        {
+            // Value being updated by the cyclic task
+            let _writer = watch.value.borrow_mut();
+
+            assert_eq!(watch.value(), None);
+            assert_eq!(
+                futures_lite::future::block_on(futures_lite::future::poll_once(&mut waiter)),
+                None
+            );
+        }
+
+        assert_eq!(
+            futures_lite::future::block_on(futures_lite::future::poll_once(&mut waiter)),
+            Some([1])
+        );
+
+        // The skipped change is reported by the next update
+        assert_eq!(watch.update(&[0]), Some(true));
+    }
+}
@@ -0,0 +1,598 @@
+//! Servo drive profile over EtherCAT (SoE).
+//!
+//! Defined in ETG1000.6 Section 5.9 SoE.
+
+use crate::mailbox::{MailboxHeaderBase, MailboxType, Priority};
+use ethercrab_wire::{EtherCrabWireRead, EtherCrabWireSized, EtherCrabWireWrite};
+
+/// SoE operation code.
+///
+/// Defined in ETG1000.6 Section 5.9 SoE.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[wire(bits = 3)]
+#[repr(u8)]
+pub enum SoeOpCode {
+    ReadRequest = 0x01,
+    ReadResponse = 0x02,
+    WriteRequest = 0x03,
+    WriteResponse = 0x04,
+    Notification = 0x05,
+    Emergency = 0x06,
+    #[wire(catch_all)]
+    Other(u8),
+}
+
+/// SoE error code sent by a SubDevice when it rejects a request.
+///
+/// Defined in ETG1000.6 Section 5.9 SoE and IEC 61800-7-204.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ethercrab_wire::EtherCrabWireRead)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u16)]
+pub enum SoeErrorCode {
+    /// No error
+    NoError = 0x0000,
+    /// No IDN
+    NoIdn = 0x1001,
+    /// Invalid access to element 1
+    InvalidElement1Access = 0x1009,
+    /// No name
+    NoName = 0x2001,
+    /// Name transmission too short
+    NameTooShort = 0x2002,
+    /// Name transmission too long
+    NameTooLong = 0x2003,
+    /// Name cannot be changed (read only)
+    NameReadOnly = 0x2004,
+    /// Name is write protected at this time
+    NameWriteProtected = 0x2005,
+    /// Attribute transmission too short
+    AttributeTooShort = 0x3002,
+    /// Attribute transmission too long
+    AttributeTooLong = 0x3003,
+    /// Attribute cannot be changed (read only)
+    AttributeReadOnly = 0x3004,
+    /// Attribute is write protected at this time
+    AttributeWriteProtected = 0x3005,
+    /// No unit
+    NoUnit = 0x4001,
+    /// Unit transmission too short
+    UnitTooShort = 0x4002,
+    /// Unit transmission too long
+    UnitTooLong = 0x4003,
+    /// Unit cannot be changed (read only)
+    UnitReadOnly = 0x4004,
+    /// Unit is write protected at this time
+    UnitWriteProtected = 0x4005,
+    /// No minimum input value
+    NoMin = 0x5001,
+    /// Minimum input value transmission too short
+    MinTooShort = 0x5002,
+    /// Minimum input value transmission too long
+    MinTooLong = 0x5003,
+    /// Minimum input value cannot be changed (read only)
+    MinReadOnly = 0x5004,
+    /// Minimum input value is write protected at this time
+    MinWriteProtected = 0x5005,
+    /// No maximum input value
+    NoMax = 0x6001,
+    /// Maximum input value transmission too short
+    MaxTooShort = 0x6002,
+    /// Maximum input value transmission too long
+    MaxTooLong = 0x6003,
+    /// Maximum input value cannot be changed (read only)
+    MaxReadOnly = 0x6004,
+    /// Maximum input value is write protected at this time
+    MaxWriteProtected = 0x6005,
+    /// Operation data transmission too short
+    DataTooShort = 0x7002,
+    /// Operation data transmission too long
+    DataTooLong = 0x7003,
+    /// Operation data cannot be changed (read only)
+    DataReadOnly = 0x7004,
+    /// Operation data is write protected at this time
+    DataWriteProtected = 0x7005,
+    /// Operation data is smaller than the minimum input value
+    DataBelowMin = 0x7006,
+    /// Operation data is larger than the maximum input value
+    DataAboveMax = 0x7007,
+    /// Invalid operation data
+    DataInvalid = 0x7008,
+    /// Operation data is write protected by a password
+    DataPasswordProtected = 0x7009,
+    /// Operation data is write protected because it is configured cyclically
+    DataCyclic = 0x700a,
+    /// Invalid indirect addressing
+    InvalidIndirectAddressing = 0x700b,
+    /// Operation data is write protected due to other settings
+    DataProtectedBySettings = 0x700c,
+    /// Procedure command already active
+    CommandActive = 0x7010,
+    /// Procedure command not interruptible
+    CommandNotInterruptible = 0x7011,
+    /// Procedure command not executable at this time
+    CommandNotExecutableNow = 0x7012,
+    /// Procedure command not executable (invalid or false parameters)
+    CommandInvalidParameters = 0x7013,
+    /// No data state
+    NoDataState = 0x7014,
+    /// No default value
+    NoDefault = 0x8001,
+    /// Default value transmission too long
+    DefaultTooLong = 0x8002,
+    /// Default value cannot be changed (read only)
+    DefaultReadOnly = 0x8004,
+    /// Invalid drive number
+    InvalidDriveNumber = 0x800a,
+    /// General error
+    General = 0x800b,
+    /// No element addressed
+    NoElement = 0x800c,
+
+    /// Unknown error code.
+    #[wire(catch_all)]
+    Unknown(u16),
+}
+
+impl SoeErrorCode {
+    /// The class of this error, i.e. which part of the IDN it relates to.
+    pub fn class(&self) -> SoeErrorClass {
+        match u16::from(*self) >> 12 {
+            0x0 | 0x1 => SoeErrorClass::Idn,
+            0x2 => SoeErrorClass::Name,
+            0x3 => SoeErrorClass::Attribute,
+            0x4 => SoeErrorClass::Unit,
+            0x5 => SoeErrorClass::Min,
+            0x6 => SoeErrorClass::Max,
+            0x7 => SoeErrorClass::Value,
+            0x8 => SoeErrorClass::Default,
+            _ => SoeErrorClass::Other,
+        }
+    }
+}
+
+impl core::fmt::Display for SoeErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let num = u16::from(*self);
+
+        f.write_fmt(format_args!("{:#06x}", num))?;
+        f.write_str(": ")?;
+
+        match self {
+            Self::NoError => f.write_str("No error"),
+            Self::NoIdn => f.write_str("No IDN"),
+            Self::InvalidElement1Access => f.write_str("Invalid access to element 1"),
+            Self::NoName => f.write_str("No name"),
+            Self::NameTooShort => f.write_str("Name transmission too short"),
+            Self::NameTooLong => f.write_str("Name transmission too long"),
+            Self::NameReadOnly => f.write_str("Name cannot be changed (read only)"),
+            Self::NameWriteProtected => f.write_str("Name is write protected at this time"),
+            Self::AttributeTooShort => f.write_str("Attribute transmission too short"),
+            Self::AttributeTooLong => f.write_str("Attribute transmission too long"),
+            Self::AttributeReadOnly => f.write_str("Attribute cannot be changed (read only)"),
+            Self::AttributeWriteProtected => {
+                f.write_str("Attribute is write protected at this time")
+            }
+            Self::NoUnit => f.write_str("No unit"),
+            Self::UnitTooShort => f.write_str("Unit transmission too short"),
+            Self::UnitTooLong => f.write_str("Unit transmission too long"),
+            Self::UnitReadOnly => f.write_str("Unit cannot be changed (read only)"),
+            Self::UnitWriteProtected => f.write_str("Unit is write protected at this time"),
+            Self::NoMin => f.write_str("No minimum input value"),
+            Self::MinTooShort => f.write_str("Minimum input value transmission too short"),
+            Self::MinTooLong => f.write_str("Minimum input value transmission too long"),
+            Self::MinReadOnly => f.write_str("Minimum input value cannot be changed (read only)"),
+            Self::MinWriteProtected => {
+                f.write_str("Minimum input value is write protected at this time")
+            }
+            Self::NoMax => f.write_str("No maximum input value"),
+            Self::MaxTooShort => f.write_str("Maximum input value transmission too short"),
+            Self::MaxTooLong => f.write_str("Maximum input value transmission too long"),
+            Self::MaxReadOnly => f.write_str("Maximum input value cannot be changed (read only)"),
+            Self::MaxWriteProtected => {
+                f.write_str("Maximum input value is write protected at this time")
+            }
+            Self::DataTooShort => f.write_str("Operation data transmission too short"),
+            Self::DataTooLong => f.write_str("Operation data transmission too long"),
+            Self::DataReadOnly => f.write_str("Operation data cannot be changed (read only)"),
+            Self::DataWriteProtected => {
+                f.write_str("Operation data is write protected at this time")
+            }
+            Self::DataBelowMin => {
+                f.write_str("Operation data is smaller than the minimum input value")
+            }
+            Self::DataAboveMax => {
+                f.write_str("Operation data is larger than the maximum input value")
+            }
+            Self::DataInvalid => f.write_str("Invalid operation data"),
+            Self::DataPasswordProtected => {
+                f.write_str("Operation data is write protected by a password")
+            }
+            Self::DataCyclic => {
+                f.write_str("Operation data is write protected because it is configured cyclically")
+            }
+            Self::InvalidIndirectAddressing => f.write_str("Invalid indirect addressing"),
+            Self::DataProtectedBySettings => {
+                f.write_str("Operation data is write protected due to other settings")
+            }
+            Self::CommandActive => f.write_str("Procedure command already active"),
+            Self::CommandNotInterruptible => f.write_str("Procedure command not interruptible"),
+            Self::CommandNotExecutableNow => {
+                f.write_str("Procedure command not executable at this time")
+            }
+            Self::CommandInvalidParameters => {
+                f.write_str("Procedure command not executable (invalid or false parameters)")
+            }
+            Self::NoDataState => f.write_str("No data state"),
+            Self::NoDefault => f.write_str("No default value"),
+            Self::DefaultTooLong => f.write_str("Default value transmission too long"),
+            Self::DefaultReadOnly => f.write_str("Default value cannot be changed (read only)"),
+            Self::InvalidDriveNumber => f.write_str("Invalid drive number"),
+            Self::General => f.write_str("General error"),
+            Self::NoElement => f.write_str("No element addressed"),
+            Self::Unknown(_) => f.write_str("Unknown code"),
+        }
+    }
+}
+
+/// The class of an [`SoeErrorCode`], given by its upper 4 bits.
+///
+/// Most classes correspond to the IDN element the error relates to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SoeErrorClass {
+    /// The IDN itself, e.g. it does not exist.
+    Idn,
+    /// The name element.
+    Name,
+    /// The attribute element.
+    Attribute,
+    /// The unit element.
+    Unit,
+    /// The minimum input value element.
+    Min,
+    /// The maximum input value element.
+    Max,
+    /// The operation data element, including procedure commands.
+    Value,
+    /// The default value element, and general errors.
+    Default,
+    /// A vendor specific or unknown class.
+    Other,
+}
+
+/// An identification number (IDN) of a Sercos drive parameter, e.g. `S-0-0017`.
+///
+/// Standard parameters (`S-x-xxxx`) and product specific parameters (`P-x-xxxx`) are both divided
+/// into 8 parameter sets of up to 4096 parameters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Idn(u16);
+
+impl Idn {
+    /// `S-0-0017`, the list of all operation data IDNs supported by a drive.
+    pub const ALL_OPERATION_DATA: Self = Self::s(0, 17);
+
+    /// Create a standard IDN, `S-<set>-<number>`.
+    ///
+    /// `set` must be at most `7` and `number` at most `4095`. Higher bits are ignored.
+    pub const fn s(set: u8, number: u16) -> Self {
+        Self(((set as u16 & 0x07) << 12) | (number & 0x0fff))
+    }
+
+    /// Create a product specific IDN, `P-<set>-<number>`.
+    ///
+    /// `set` must be at most `7` and `number` at most `4095`. Higher bits are ignored.
+    pub const fn p(set: u8, number: u16) -> Self {
+        Self(0x8000 | Self::s(set, number).0)
+    }
+
+    /// Returns `true` if this is a product specific (`P-x-xxxx`) IDN.
+    pub const fn is_product_specific(&self) -> bool {
+        self.0 & 0x8000 != 0
+    }
+
+    /// The parameter set, from `0` to `7`.
+    pub const fn set(&self) -> u8 {
+        ((self.0 >> 12) & 0x07) as u8
+    }
+
+    /// The parameter number within the parameter set, from `0` to `4095`.
+    pub const fn number(&self) -> u16 {
+        self.0 & 0x0fff
+    }
+}
+
+impl From<u16> for Idn {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Idn> for u16 {
+    fn from(value: Idn) -> Self {
+        value.0
+    }
+}
+
+impl core::fmt::Display for Idn {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let kind = if self.is_product_specific() { 'P' } else { 'S' };
+
+        write!(f, "{}-{}-{:04}", kind, self.set(), self.number())
+    }
+}
+
+/// An iterator over a list of IDNs read from a drive, created by
+/// [`SlaveRef::idn_list`](crate::SlaveRef::idn_list).
+#[derive(Clone, Debug)]
+pub struct IdnList<'buf> {
+    entries: core::slice::ChunksExact<'buf, u8>,
+}
+
+impl<'buf> IdnList<'buf> {
+    /// Parse a list from an IDN's operation data, starting with its 2 byte current and maximum
+    /// lengths in bytes.
+    pub(crate) fn new(data: &'buf [u8]) -> Result<Self, ethercrab_wire::WireError> {
+        let len = usize::from(u16::unpack_from_slice(data)?);
+
+        let entries =
+            data.get(4..(4 + len))
+                .ok_or(ethercrab_wire::WireError::ReadBufferTooShort {
+                    expected: 4 + len,
+                    got: data.len(),
+                })?;
+
+        Ok(Self {
+            entries: entries.chunks_exact(2),
+        })
+    }
+}
+
+impl Iterator for IdnList<'_> {
+    type Item = Idn;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries
+            .next()
+            .map(|entry| Idn(u16::from_le_bytes([entry[0], entry[1]])))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IdnList<'_> {}
+
+bitflags::bitflags! {
+    /// The elements of an IDN to read or write.
+    ///
+    /// Defined in ETG1000.6 Section 5.9 SoE.
+    #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+    pub struct SoeElements: u8 {
+        /// Data state.
+        const DATA_STATE = 0x01;
+        /// Parameter name.
+        const NAME = 0x02;
+        /// Attributes, e.g. data length and type.
+        const ATTRIBUTE = 0x04;
+        /// Unit.
+        const UNIT = 0x08;
+        /// Minimum value.
+        const MIN = 0x10;
+        /// Maximum value.
+        const MAX = 0x20;
+        /// Operation data, i.e. the parameter's current value.
+        const VALUE = 0x40;
+        /// Default value.
+        const DEFAULT = 0x80;
+    }
+}
+
+impl EtherCrabWireSized for SoeElements {
+    const PACKED_LEN: usize = 1;
+
+    type Buffer = [u8; Self::PACKED_LEN];
+
+    fn buffer() -> Self::Buffer {
+        [0u8; Self::PACKED_LEN]
+    }
+}
+
+impl EtherCrabWireRead for SoeElements {
+    fn unpack_from_slice(buf: &[u8]) -> Result<Self, ethercrab_wire::WireError> {
+        u8::unpack_from_slice(buf).map(Self::from_bits_retain)
+    }
+}
+
+impl EtherCrabWireWrite for SoeElements {
+    fn pack_to_slice_unchecked<'buf>(&self, buf: &'buf mut [u8]) -> &'buf [u8] {
+        self.bits().pack_to_slice_unchecked(buf)
+    }
+
+    fn packed_len(&self) -> usize {
+        Self::PACKED_LEN
+    }
+}
+
+// Can't derive, so manual impl
+#[cfg(feature = "defmt")]
+impl defmt::Format for SoeElements {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u8:b}", self.bits())
+    }
+}
+
+bitflags::bitflags! {
+    /// Procedure command value and acknowledgement (data state) bits.
+    #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+    pub struct CommandStatus: u16 {
+        /// The command is set.
+        const SET = 0x0001;
+        /// Execution of the command is enabled.
+        const EXECUTE = 0x0002;
+        /// The command has not finished executing yet.
+        const IN_PROGRESS = 0x0004;
+        /// The command could not be executed.
+        const ERROR = 0x0008;
+    }
+}
+
+/// Mailbox and SoE headers common to all SoE requests and responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 10)]
+pub struct SoeHeader {
+    #[wire(bytes = 6)]
+    pub header: MailboxHeaderBase,
+    #[wire(bits = 3)]
+    pub op_code: SoeOpCode,
+    /// Set if more fragments of this request or response follow.
+    #[wire(bits = 1)]
+    pub incomplete: bool,
+    /// Set by the SubDevice if the request failed. The data holds a 2 byte error code.
+    #[wire(bits = 1)]
+    pub error: bool,
+    /// Drive number, for SubDevices that control more than one drive.
+    #[wire(bits = 3)]
+    pub drive_no: u8,
+    #[wire(bytes = 1)]
+    pub elements: SoeElements,
+    /// The IDN, or the number of fragments still to come if `incomplete` is set.
+    #[wire(bytes = 2)]
+    pub idn: u16,
+}
+
+impl SoeHeader {
+    /// Create a new SoE header, followed by `data_len` bytes of data.
+    pub fn new(
+        counter: u8,
+        op_code: SoeOpCode,
+        drive_no: u8,
+        elements: SoeElements,
+        idn: u16,
+        incomplete: bool,
+        data_len: u16,
+    ) -> Self {
+        Self {
+            header: MailboxHeaderBase {
+                // 4 byte SoE header plus data
+                length: 0x04 + data_len,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::Soe,
+                counter,
+            },
+            op_code,
+            incomplete,
+            error: false,
+            drive_no,
+            elements,
+            idn,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireWriteSized;
+
+    #[test]
+    fn idn_display() {
+        assert_eq!(Idn::s(0, 17).to_string(), "S-0-0017");
+        assert_eq!(Idn::p(1, 1234).to_string(), "P-1-1234");
+        assert_eq!(u16::from(Idn::p(1, 1234)), 0x94d2);
+        assert_eq!(Idn::from(0x94d2), Idn::p(1, 1234));
+    }
+
+    #[test]
+    fn idn_list() {
+        let data = [
+            // Current length, maximum length
+            0x06, 0x00, 0x08, 0x00, //
+            // S-0-0001, S-0-0017, P-0-0004
+            0x01, 0x00, 0x11, 0x00, 0x04, 0x80, //
+            // Unused space
+            0x00, 0x00,
+        ];
+
+        let list = IdnList::new(&data).unwrap();
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(
+            list.collect::<Vec<_>>(),
+            [Idn::s(0, 1), Idn::ALL_OPERATION_DATA, Idn::p(0, 4)]
+        );
+
+        assert!(IdnList::new(&data[0..8]).is_err());
+    }
+
+    #[test]
+    fn error_codes() {
+        let code = SoeErrorCode::from(0x7005);
+
+        assert_eq!(code, SoeErrorCode::DataWriteProtected);
+        assert_eq!(code.class(), SoeErrorClass::Value);
+
+        let unknown = SoeErrorCode::from(0xa123);
+
+        assert_eq!(unknown, SoeErrorCode::Unknown(0xa123));
+        assert_eq!(unknown.class(), SoeErrorClass::Other);
+        assert_eq!(unknown.to_string(), "0xa123: Unknown code");
+    }
+
+    #[test]
+    fn encode_write_fragment() {
+        let header = SoeHeader::new(
+            3,
+            SoeOpCode::WriteRequest,
+            1,
+            SoeElements::VALUE,
+            2,
+            true,
+            8,
+        );
+
+        assert_eq!(
+            header.pack(),
+            [
+                // Mailbox header
+                0x0c, 0x00, 0x00, 0x00, 0x00, 0x35, //
+                // Op code, incomplete, drive number
+                0x2b, //
+                // Elements
+                0x40, //
+                // Fragments left
+                0x02, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_error_response() {
+        let raw = [
+            0x06, 0x00, 0x00, 0x00, 0x00, 0x25, 0x12, 0x40, 0x11, 0x00, 0x09, 0x70,
+        ];
+
+        assert_eq!(
+            SoeHeader::unpack_from_slice(&raw),
+            Ok(SoeHeader {
+                header: MailboxHeaderBase {
+                    length: 6,
+                    priority: Priority::Lowest,
+                    mailbox_type: MailboxType::Soe,
+                    counter: 2
+                },
+                op_code: SoeOpCode::ReadResponse,
+                incomplete: false,
+                error: true,
+                drive_no: 0,
+                elements: SoeElements::VALUE,
+                idn: 17,
+            })
+        );
+    }
+}
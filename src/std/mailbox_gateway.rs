@@ -0,0 +1,313 @@
+//! EtherCAT Mailbox Gateway server, defined in ETG.8200.
+//!
+//! The gateway accepts mailbox requests over UDP and forwards them to SubDevices, allowing tools
+//! such as drive commissioning software to use CoE, FoE, SoE and other mailbox protocols through
+//! a running EtherCrab MainDevice.
+
+use crate::{
+    error::{Error, GatewayError, Item, MailboxError},
+    fmt,
+    mailbox::{MailboxErrorCode, MailboxErrorReply, MailboxHeaderBase, MailboxType, Priority},
+    Client, Slave, SlaveGroup, SlaveRef,
+};
+use async_io::Async;
+use atomic_refcell::AtomicRefMut;
+use ethercrab_wire::{EtherCrabWireSized, EtherCrabWireWriteSized};
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+};
+
+/// The UDP port the Mailbox Gateway listens on by default.
+pub const MAILBOX_GATEWAY_PORT: u16 = 0x88a4;
+
+/// EtherCAT frame header protocol type of a mailbox gateway request.
+const PROTOCOL_MAILBOX: u16 = 0x05;
+
+/// The longest UDP payload handled by the gateway.
+const MAX_DATAGRAM_LEN: usize = 1500;
+
+/// A group of SubDevices that mailbox gateway requests can be forwarded to.
+///
+/// This is implemented for [`SlaveGroup`] in any state, so groups of different types can be served
+/// by the same gateway.
+pub trait GatewayGroup {
+    /// Borrow the SubDevice with the given configured address, returning `Ok(None)` if it is not
+    /// in this group.
+    fn gateway_slave<'client, 'group>(
+        &'group self,
+        client: &'client Client<'client>,
+        configured_address: u16,
+    ) -> Result<Option<SlaveRef<'client, AtomicRefMut<'group, Slave>>>, Error>;
+}
+
+impl<const MAX_SLAVES: usize, const MAX_PDI: usize, S, DC> GatewayGroup
+    for SlaveGroup<MAX_SLAVES, MAX_PDI, S, DC>
+{
+    fn gateway_slave<'client, 'group>(
+        &'group self,
+        client: &'client Client<'client>,
+        configured_address: u16,
+    ) -> Result<Option<SlaveRef<'client, AtomicRefMut<'group, Slave>>>, Error> {
+        self.slave_by_address(client, configured_address)
+    }
+}
+
+/// Run an EtherCAT Mailbox Gateway server, forwarding mailbox requests received over UDP to the
+/// SubDevices in `groups`.
+///
+/// Each request is forwarded to the SubDevice whose configured address matches the address field
+/// in the request's mailbox header, and the SubDevice's response is sent back to the requester.
+/// The SubDevice must not be borrowed elsewhere, e.g. with
+/// [`SlaveGroup::slave`](crate::SlaveGroup::slave), while a request is being forwarded.
+///
+/// If a request can't be forwarded, e.g. because no SubDevice has the requested address or its
+/// mailbox is busy, the failure is logged and a mailbox error reply is sent back to the
+/// requester. Datagrams that aren't mailbox requests are ignored. This function only returns if
+/// the UDP socket fails.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{ error::Error, std::{ethercat_now, mailbox_gateway_task, MAILBOX_GATEWAY_PORT}, Client, ClientConfig, PduStorage, Timeouts };
+/// # use std::net::{Ipv4Addr, SocketAddr};
+/// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+/// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+/// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+/// # async {
+/// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+///
+/// let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, MAILBOX_GATEWAY_PORT));
+///
+/// mailbox_gateway_task(&client, &[&group], addr).await?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// # };
+/// ```
+pub async fn mailbox_gateway_task<'client>(
+    client: &'client Client<'client>,
+    groups: &[&dyn GatewayGroup],
+    addr: SocketAddr,
+) -> io::Result<()> {
+    let socket = Async::<UdpSocket>::bind(addr).map_err(|e| {
+        fmt::error!("Failed to bind mailbox gateway to {}: {}", addr, e);
+
+        e
+    })?;
+
+    fmt::info!("Mailbox gateway listening on {}", addr);
+
+    let mut request = [0u8; MAX_DATAGRAM_LEN];
+    let mut response = [0u8; MAX_DATAGRAM_LEN];
+
+    loop {
+        let (len, peer) = socket.recv_from(&mut request).await.map_err(|e| {
+            fmt::error!("Mailbox gateway failed to receive request: {}", e);
+
+            e
+        })?;
+
+        let response_len =
+            match forward_request(client, groups, &request[0..len], &mut response).await {
+                Ok(len) => len,
+                Err(e) => {
+                    fmt::warn!("Mailbox gateway request from {} failed: {}", peer, e);
+
+                    match error_reply(&request[0..len], e, &mut response) {
+                        Some(len) => len,
+                        None => continue,
+                    }
+                }
+            };
+
+        socket
+            .send_to(&response[0..response_len], peer)
+            .await
+            .map_err(|e| {
+                fmt::error!("Mailbox gateway failed to send response: {}", e);
+
+                e
+            })?;
+    }
+}
+
+/// Split a gateway request into the station address it is sent to and its mailbox message.
+fn parse_request(request: &[u8]) -> Result<(u16, &[u8]), Error> {
+    let frame_header = request
+        .get(0..2)
+        .map(|header| u16::from_le_bytes([header[0], header[1]]))
+        .ok_or(Error::Gateway(GatewayError::InvalidRequest))?;
+
+    if frame_header >> 12 != PROTOCOL_MAILBOX {
+        fmt::debug!(
+            "Mailbox gateway ignoring request with protocol type {:#04x}",
+            frame_header >> 12
+        );
+
+        return Err(Error::Gateway(GatewayError::UnsupportedProtocol));
+    }
+
+    let len = usize::from(frame_header & crate::LEN_MASK);
+
+    // The mailbox header holds the station address in bytes 2 and 3.
+    let mailbox = request
+        .get(2..)
+        .and_then(|mailbox| mailbox.get(0..len))
+        .filter(|mailbox| mailbox.len() >= MailboxHeaderBase::PACKED_LEN)
+        .ok_or(Error::Gateway(GatewayError::InvalidRequest))?;
+
+    Ok((u16::from_le_bytes([mailbox[2], mailbox[3]]), mailbox))
+}
+
+/// Forward a single gateway request, returning the length of the response written into `buf`.
+async fn forward_request<'client>(
+    client: &'client Client<'client>,
+    groups: &[&dyn GatewayGroup],
+    request: &[u8],
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+    let (address, mailbox_request) = parse_request(request)?;
+
+    let mut borrowed = false;
+
+    for group in groups {
+        let slave = match group.gateway_slave(client, address) {
+            Ok(Some(slave)) => slave,
+            Ok(None) => continue,
+            // The SubDevice may be in another group, so keep looking.
+            Err(Error::Borrow) => {
+                borrowed = true;
+
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let (header, mailbox) = buf.split_at_mut(2);
+
+        let response_len = slave.mailbox_passthrough(mailbox_request, mailbox).await?;
+
+        // The response is addressed from the SubDevice it was forwarded to.
+        mailbox[2..4].copy_from_slice(&address.to_le_bytes());
+
+        header.copy_from_slice(&frame_header(response_len));
+
+        return Ok(2 + response_len);
+    }
+
+    Err(if borrowed {
+        Error::Borrow
+    } else {
+        Error::NotFound {
+            item: Item::Slave,
+            index: Some(usize::from(address)),
+        }
+    })
+}
+
+/// Write a mailbox error reply to a request that failed into `buf`, returning its length.
+///
+/// Returns `None` if the request isn't a mailbox request, so can't be replied to.
+fn error_reply(request: &[u8], error: Error, buf: &mut [u8]) -> Option<usize> {
+    let (address, _) = parse_request(request).ok()?;
+
+    let detail = match error {
+        Error::Gateway(GatewayError::TooLong) => MailboxErrorCode::InvalidSize,
+        Error::Mailbox(MailboxError::NoMailbox) => MailboxErrorCode::UnsupportedProtocol,
+        Error::Gateway(_) | Error::Wire(_) | Error::NotFound { .. } => MailboxErrorCode::Syntax,
+        // The SubDevice is busy, or didn't respond in time.
+        _ => MailboxErrorCode::NoMoreMemory,
+    };
+
+    let reply = MailboxErrorReply {
+        header: MailboxHeaderBase {
+            // Service type and detail code
+            length: 4,
+            priority: Priority::Lowest,
+            mailbox_type: MailboxType::Err,
+            counter: 0,
+        },
+        service_type: 0x01,
+        detail,
+    };
+
+    let len = MailboxErrorReply::PACKED_LEN;
+
+    let (header, mailbox) = buf.get_mut(0..2 + len)?.split_at_mut(2);
+
+    mailbox.copy_from_slice(&reply.pack());
+
+    // The reply is addressed from the SubDevice the request was for.
+    mailbox[2..4].copy_from_slice(&address.to_le_bytes());
+
+    header.copy_from_slice(&frame_header(len));
+
+    Some(2 + len)
+}
+
+/// The EtherCAT frame header of a gateway response holding a `len` byte mailbox message.
+fn frame_header(len: usize) -> [u8; 2] {
+    ((len as u16 & crate::LEN_MASK) | PROTOCOL_MAILBOX << 12).to_le_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mailbox_request() {
+        let request = [
+            // Frame header: 10 byte mailbox request
+            0x0a, 0x50, //
+            // Mailbox header addressed to 0x1001
+            0x04, 0x00, 0x01, 0x10, 0x00, 0x13, //
+            // CoE
+            0x00, 0x20, 0x00, 0x00, //
+            // Trailing bytes aren't part of the request
+            0xff,
+        ];
+
+        assert_eq!(
+            parse_request(&request),
+            Ok((0x1001, &request[2..12])),
+            "valid request"
+        );
+
+        assert_eq!(
+            parse_request(&request[0..8]),
+            Err(Error::Gateway(GatewayError::InvalidRequest)),
+            "truncated request"
+        );
+
+        assert_eq!(
+            parse_request(&[0x0a, 0x10]),
+            Err(Error::Gateway(GatewayError::UnsupportedProtocol)),
+            "not a mailbox request"
+        );
+    }
+
+    #[test]
+    fn reply_with_mailbox_error() {
+        let request = [
+            0x0a, 0x50, 0x04, 0x00, 0x01, 0x10, 0x00, 0x13, 0, 0x20, 0, 0,
+        ];
+
+        let mut buf = [0u8; 32];
+
+        let len = error_reply(&request, Error::Timeout, &mut buf);
+
+        assert_eq!(len, Some(12));
+        assert_eq!(
+            buf[0..12],
+            [
+                // Frame header
+                0x0a, 0x50, //
+                // Mailbox header, ERR type
+                0x04, 0x00, 0x01, 0x10, 0x00, 0x00, //
+                // Service type, detail
+                0x01, 0x00, 0x07, 0x00
+            ]
+        );
+
+        assert_eq!(error_reply(&request[0..4], Error::Timeout, &mut buf), None);
+    }
+}
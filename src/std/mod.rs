@@ -2,11 +2,13 @@
 
 #[cfg(target_os = "linux")]
 mod io_uring;
+mod mailbox_gateway;
 #[cfg(unix)]
 mod unix;
 #[cfg(target_os = "windows")]
 mod windows;
 
+pub use self::mailbox_gateway::{mailbox_gateway_task, GatewayGroup, MAILBOX_GATEWAY_PORT};
 #[cfg(target_os = "windows")]
 pub use self::windows::{ethercat_now, tx_rx_task};
 #[cfg(unix)]
@@ -14,3 +16,9 @@ pub use unix::{ethercat_now, tx_rx_task};
 // io_uring is Linux-only
 #[cfg(target_os = "linux")]
 pub use io_uring::tx_rx_task_io_uring;
+// TAP interfaces are Linux-only
+#[cfg(target_os = "linux")]
+pub use unix::eoe_tap_task;
+// CLOCK_TAI is Linux-only
+#[cfg(target_os = "linux")]
+pub use unix::ethercat_now_tai;
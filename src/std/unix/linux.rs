@@ -131,7 +131,7 @@ impl io::Write for RawSocketDesc {
     }
 }
 
-fn ifreq_ioctl(
+pub(super) fn ifreq_ioctl(
     lower: libc::c_int,
     ifreq: &mut ifreq,
     cmd: libc::c_ulong,
@@ -4,11 +4,15 @@
 mod bpf;
 #[cfg(target_os = "linux")]
 mod linux;
+#[cfg(target_os = "linux")]
+mod tap;
 
 #[cfg(all(not(target_os = "linux"), unix))]
 use self::bpf::BpfDevice as RawSocketDesc;
 #[cfg(target_os = "linux")]
 pub(in crate::std) use self::linux::RawSocketDesc;
+#[cfg(target_os = "linux")]
+pub use self::tap::eoe_tap_task;
 
 use crate::{
     error::{Error, PduError},
@@ -158,6 +162,35 @@ pub fn ethercat_now() -> u64 {
     t.saturating_sub(946684800)
 }
 
+/// Get the current International Atomic Time (TAI) in nanoseconds from the EtherCAT epoch,
+/// 2000-01-01.
+///
+/// This uses `CLOCK_TAI`, which can be synchronised to other systems with PTP. Pass this function
+/// to [`Client::init`](crate::Client::init) and
+/// [`Client::steer_reference_clock`](crate::Client::steer_reference_clock) to align DC system time
+/// with the host clock.
+///
+/// Note that `CLOCK_TAI` is the same as `CLOCK_REALTIME` unless the kernel's TAI offset is set,
+/// e.g. by `ptp4l` or `chronyd`.
+#[cfg(target_os = "linux")]
+pub fn ethercat_now_tai() -> u64 {
+    /// 2000-01-01 in nanoseconds since the Unix epoch.
+    const ETHERCAT_EPOCH: u64 = 946_684_800 * 1_000_000_000;
+
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    unsafe {
+        libc::clock_gettime(libc::CLOCK_TAI, &mut time);
+    };
+
+    let t = (time.tv_sec * 1000 * 1000 * 1000 + time.tv_nsec) as u64;
+
+    t.saturating_sub(ETHERCAT_EPOCH)
+}
+
 // Unix only
 #[allow(trivial_numeric_casts)]
 fn ifreq_for(name: &str) -> ifreq {
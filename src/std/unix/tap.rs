@@ -0,0 +1,224 @@
+//! A Linux TAP interface used to bridge EoE (Ethernet over EtherCAT) frames to the host network
+//! stack.
+
+use crate::{
+    fmt,
+    std::unix::{ifreq, ifreq_for, linux::ifreq_ioctl},
+    Slave, SlaveRef,
+};
+use async_io::{Async, IoSafe, Timer};
+use core::{ops::Deref, time::Duration};
+use futures_lite::{AsyncReadExt, AsyncWriteExt};
+use std::{
+    io,
+    os::{
+        fd::{AsFd, BorrowedFd},
+        unix::io::{AsRawFd, RawFd},
+    },
+};
+
+// From `linux/if_tun.h`. Not exported by all supported versions of `libc`.
+const TUNSETIFF: libc::c_ulong = 0x4004_54ca;
+const IFF_TAP: libc::c_int = 0x0002;
+const IFF_NO_PI: libc::c_int = 0x1000;
+
+/// The longest Ethernet frame read from or written to the TAP interface.
+const MAX_FRAME_LEN: usize = 1514;
+
+struct TapDesc {
+    lower: i32,
+    ifreq: ifreq,
+}
+
+impl TapDesc {
+    fn new(name: &str) -> io::Result<Self> {
+        let lower = unsafe {
+            let lower = libc::open(
+                b"/dev/net/tun\0".as_ptr().cast(),
+                libc::O_RDWR | libc::O_NONBLOCK,
+            );
+            if lower == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            lower
+        };
+
+        let mut self_ = TapDesc {
+            lower,
+            ifreq: ifreq_for(name),
+        };
+
+        self_.ifreq.ifr_data = IFF_TAP | IFF_NO_PI;
+
+        ifreq_ioctl(self_.lower, &mut self_.ifreq, TUNSETIFF)?;
+
+        Ok(self_)
+    }
+
+    /// Bring the interface up, equivalent to `ip link set <name> up`.
+    fn set_up(&mut self) -> io::Result<()> {
+        let sock = unsafe {
+            let sock = libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0);
+            if sock == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            sock
+        };
+
+        let res = ifreq_ioctl(sock, &mut self.ifreq, libc::SIOCGIFFLAGS).and_then(|flags| {
+            self.ifreq.ifr_data = flags | libc::IFF_UP;
+
+            ifreq_ioctl(sock, &mut self.ifreq, libc::SIOCSIFFLAGS)
+        });
+
+        unsafe {
+            libc::close(sock);
+        }
+
+        res.map(|_| ())
+    }
+}
+
+impl AsRawFd for TapDesc {
+    fn as_raw_fd(&self) -> RawFd {
+        self.lower
+    }
+}
+
+impl AsFd for TapDesc {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.lower) }
+    }
+}
+
+// SAFETY: The file descriptor is only closed when `TapDesc` is dropped, not by the `Read` or
+// `Write` impls.
+unsafe impl IoSafe for TapDesc {}
+
+impl Drop for TapDesc {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.lower);
+        }
+    }
+}
+
+impl io::Read for TapDesc {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = unsafe { libc::read(self.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+        if len == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(len as usize)
+        }
+    }
+}
+
+impl io::Write for TapDesc {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = unsafe { libc::write(self.as_raw_fd(), buf.as_ptr().cast(), buf.len()) };
+        if len == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(len as usize)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Create a TAP interface called `interface` and forward Ethernet frames between it and a
+/// SubDevice's EoE (Ethernet over EtherCAT) mailbox until an error occurs.
+///
+/// The interface is created and brought up by this function, which requires the `CAP_NET_ADMIN`
+/// capability. Once running, the host can reach the SubDevice (or devices connected to it, e.g.
+/// behind an EL6601 switch port terminal) by assigning an address to the interface, e.g. with
+/// `ip addr add 192.168.10.1/24 dev <interface>`.
+///
+/// The SubDevice's mailbox is checked for frames every `poll_interval`, as well as after every
+/// frame sent by the host.
+///
+/// Errors from the TAP interface are returned as-is. EtherCrab errors, e.g. from the SubDevice's
+/// mailbox, are returned as an [`io::Error`] of kind [`io::ErrorKind::Other`] wrapping the
+/// [`Error`](crate::error::Error).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use ethercrab::{ error::Error, std::{ethercat_now, eoe_tap_task}, Client, ClientConfig, PduStorage, Timeouts };
+/// # use std::time::Duration;
+/// # static PDU_STORAGE: PduStorage<16, 1100> = PduStorage::new();
+/// # let (_tx, _rx, pdu_loop) = PDU_STORAGE.try_split().expect("can only split once");
+/// # let client = Client::new(pdu_loop, Timeouts::default(), ClientConfig::default());
+/// # async {
+/// let group = client.init_single_group::<8, 64>(ethercat_now).await?;
+///
+/// let slave = group.slave(&client, 0)?;
+///
+/// eoe_tap_task(&slave, "eoe0", Duration::from_millis(10)).await?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// # };
+/// ```
+pub async fn eoe_tap_task<S>(
+    slave: &SlaveRef<'_, S>,
+    interface: &str,
+    poll_interval: Duration,
+) -> io::Result<()>
+where
+    S: Deref<Target = Slave>,
+{
+    let mut tap = TapDesc::new(interface)
+        .and_then(|mut tap| tap.set_up().map(|_| tap))
+        .and_then(Async::new)
+        .map_err(|e| {
+            fmt::error!("Failed to open TAP interface {}: {}", interface, e);
+
+            e
+        })?;
+
+    fmt::info!(
+        "Forwarding EoE frames between slave {:#06x} and {}",
+        slave.configured_address(),
+        interface
+    );
+
+    let mut from_host = [0u8; MAX_FRAME_LEN];
+    let mut from_slave = [0u8; MAX_FRAME_LEN];
+
+    loop {
+        while let Some(len) = slave
+            .eoe_receive(&mut from_slave)
+            .await
+            .map_err(io::Error::other)?
+        {
+            tap.write(&from_slave[0..len]).await.map_err(|e| {
+                fmt::error!("Failed to write frame to {}: {}", interface, e);
+
+                e
+            })?;
+        }
+
+        let received =
+            futures_lite::future::or(async { Some(tap.read(&mut from_host).await) }, async {
+                Timer::after(poll_interval).await;
+
+                None
+            })
+            .await;
+
+        if let Some(received) = received {
+            let len = received.map_err(|e| {
+                fmt::error!("Failed to read frame from {}: {}", interface, e);
+
+                e
+            })?;
+
+            slave
+                .eoe_send(&from_host[0..len])
+                .await
+                .map_err(io::Error::other)?;
+        }
+    }
+}
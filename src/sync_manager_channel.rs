@@ -59,18 +59,24 @@ impl core::fmt::Display for SyncManagerChannel {
     }
 }
 
+/// Sync manager control register.
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bytes = 1)]
 pub struct Control {
+    /// Buffered (process data) or mailbox mode.
     #[wire(bits = 2)]
     pub operation_mode: OperationMode,
+    /// Whether the MainDevice reads or writes the sync manager's buffer.
     #[wire(bits = 2)]
     pub direction: Direction,
+    /// Raise an ECAT event when the buffer is accessed.
     #[wire(bits = 1)]
     pub ecat_event_enable: bool,
+    /// Raise an AL event when the buffer is accessed.
     #[wire(bits = 1)]
     pub dls_user_event_enable: bool,
+    /// Enable the sync manager watchdog.
     #[wire(bits = 1, post_skip = 1)]
     pub watchdog_enable: bool,
     // reserved1: bool
@@ -124,23 +130,29 @@ pub struct Enable {
     // reserved6: u8,
 }
 
+/// Sync manager buffer mode.
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bits = 2)]
 #[repr(u8)]
 pub enum OperationMode {
+    /// Buffered (3 buffer) mode, used for process data.
     #[default]
     Normal = 0x00,
+    /// Mailbox (single buffer) mode.
     Mailbox = 0x02,
 }
 
+/// Sync manager buffer direction.
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[wire(bits = 2)]
 #[repr(u8)]
 pub enum Direction {
+    /// The buffer is written by the SubDevice and read by the MainDevice.
     #[default]
     MasterRead = 0x00,
+    /// The buffer is written by the MainDevice and read by the SubDevice.
     MasterWrite = 0x01,
 }
 
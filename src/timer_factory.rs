@@ -1,4 +1,4 @@
-use crate::error::Error;
+use crate::{error::Error, SlaveState};
 use core::{future::Future, pin::Pin, task::Poll, time::Duration};
 
 #[cfg(not(feature = "std"))]
@@ -18,6 +18,11 @@ fn timer(duration: Duration) -> Timer {
     async_io::Timer::after(duration)
 }
 
+/// Wait for the given duration.
+pub(crate) async fn sleep(duration: Duration) {
+    timer(duration).await;
+}
+
 pub(crate) trait IntoTimeout<O> {
     fn timeout(self, timeout: Duration) -> TimeoutFuture<impl Future<Output = Result<O, Error>>>;
 }
@@ -65,11 +70,26 @@ where
 /// Timeout configuration for the EtherCrab master.
 #[derive(Copy, Clone, Debug)]
 pub struct Timeouts {
-    /// How long to wait for a slave state change, e.g. SAFE-OP to OP.
+    /// How long to wait for a slave state change that doesn't have its own timeout below, e.g.
+    /// OP to SAFE-OP or any state to INIT.
     ///
-    /// This timeout is global for all state transitions.
+    /// If this or any other state transition timeout expires,
+    /// [`Error::StateTransitionTimeout`](crate::error::Error::StateTransitionTimeout) is returned
+    /// with the AL status code of each SubDevice that didn't reach the requested state.
     pub state_transition: Duration,
 
+    /// How long to wait for SubDevices to go from INIT to PRE-OP.
+    pub init_to_pre_op: Duration,
+
+    /// How long to wait for SubDevices to go from PRE-OP to SAFE-OP.
+    ///
+    /// SubDevices may take much longer for this transition than others, e.g. while applying many
+    /// SDO init commands or starting up a drive.
+    pub pre_op_to_safe_op: Duration,
+
+    /// How long to wait for SubDevices to go from SAFE-OP to OP.
+    pub safe_op_to_op: Duration,
+
     /// How long to wait for a PDU response.
     pub pdu: Duration,
 
@@ -82,30 +102,128 @@ pub struct Timeouts {
     /// This duration specifies the wait time between polls.
     ///
     /// This defaults to a timeout of 0 to keep latency to a minimum.
+    ///
+    /// Mailbox status polling does not use this delay, and instead polls as fast as the network
+    /// allows.
     pub wait_loop_delay: Duration,
 
     /// How long to wait for a slave mailbox to become ready.
     pub mailbox_echo: Duration,
 
     /// How long to wait for a response to be read from the slave's response mailbox.
+    ///
+    /// This is used by protocols without their own timeout below, e.g. AoE and VoE.
     pub mailbox_response: Duration,
+
+    /// How long to wait for a CoE response, e.g. to an SDO read or write.
+    pub coe_response: Duration,
+
+    /// How long to wait for an FoE response.
+    ///
+    /// SubDevices may take a long time to respond while writing a file to flash, e.g. during a
+    /// firmware update.
+    pub foe_response: Duration,
+
+    /// How long to wait for an SoE response.
+    pub soe_response: Duration,
+
+    /// How long to wait for the next fragment of an Ethernet frame received over EoE.
+    pub eoe_response: Duration,
+
+    /// How long to wait before resending an FoE packet when a SubDevice reports that it is busy,
+    /// e.g. while erasing flash during a firmware update.
+    ///
+    /// The number of retries is set by
+    /// [`ClientConfig::foe_busy_retries`](crate::ClientConfig::foe_busy_retries).
+    pub foe_busy: Duration,
+
+    /// How long to wait for an SoE procedure command to finish executing.
+    pub soe_procedure: Duration,
+
+    /// How long to wait for a SubDevice to answer an explicit device ID request.
+    ///
+    /// SubDevices that support ID requests answer them almost immediately, so this is much shorter
+    /// than the state transition timeouts.
+    pub id_request: Duration,
 }
 
 impl Timeouts {
     pub(crate) async fn loop_tick(&self) {
         timer(self.wait_loop_delay).await;
     }
+
+    pub(crate) async fn foe_busy_tick(&self) {
+        timer(self.foe_busy).await;
+    }
+
+    /// Get the timeout for a state transition. `from` is [`SlaveState::None`] if the current
+    /// state isn't known.
+    pub(crate) fn transition(&self, from: SlaveState, to: SlaveState) -> Duration {
+        match (from, to) {
+            (SlaveState::Init, SlaveState::PreOp) => self.init_to_pre_op,
+            (SlaveState::PreOp, SlaveState::SafeOp) => self.pre_op_to_safe_op,
+            (SlaveState::SafeOp, SlaveState::Op) => self.safe_op_to_op,
+            _ => self.state_transition,
+        }
+    }
 }
 
 impl Default for Timeouts {
     fn default() -> Self {
         Self {
             state_transition: Duration::from_millis(5000),
+            init_to_pre_op: Duration::from_millis(5000),
+            pre_op_to_safe_op: Duration::from_millis(10_000),
+            safe_op_to_op: Duration::from_millis(5000),
             pdu: Duration::from_micros(30_000),
             eeprom: Duration::from_millis(10),
             wait_loop_delay: Duration::from_millis(0),
             mailbox_echo: Duration::from_millis(100),
             mailbox_response: Duration::from_millis(1000),
+            coe_response: Duration::from_millis(1000),
+            foe_response: Duration::from_millis(30_000),
+            soe_response: Duration::from_millis(1000),
+            eoe_response: Duration::from_millis(1000),
+            foe_busy: Duration::from_millis(100),
+            soe_procedure: Duration::from_millis(10_000),
+            id_request: Duration::from_millis(100),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_timeouts() {
+        let timeouts = Timeouts {
+            state_transition: Duration::from_millis(1),
+            init_to_pre_op: Duration::from_millis(2),
+            pre_op_to_safe_op: Duration::from_millis(3),
+            safe_op_to_op: Duration::from_millis(4),
+            ..Timeouts::default()
+        };
+
+        assert_eq!(
+            timeouts.transition(SlaveState::Init, SlaveState::PreOp),
+            Duration::from_millis(2)
+        );
+        assert_eq!(
+            timeouts.transition(SlaveState::PreOp, SlaveState::SafeOp),
+            Duration::from_millis(3)
+        );
+        assert_eq!(
+            timeouts.transition(SlaveState::SafeOp, SlaveState::Op),
+            Duration::from_millis(4)
+        );
+        assert_eq!(
+            timeouts.transition(SlaveState::Op, SlaveState::SafeOp),
+            Duration::from_millis(1)
+        );
+        assert_eq!(
+            timeouts.transition(SlaveState::None, SlaveState::PreOp),
+            Duration::from_millis(1)
+        );
+    }
+}
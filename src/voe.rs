@@ -0,0 +1,71 @@
+//! Vendor specific protocol over EtherCAT (VoE).
+
+use crate::mailbox::{MailboxHeaderBase, MailboxType, Priority};
+
+/// The header at the start of every VoE mailbox message, identifying the vendor protocol in use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[wire(bytes = 6)]
+pub struct VoeHeader {
+    /// EtherCAT vendor ID of the protocol's owner.
+    #[wire(bytes = 4)]
+    pub vendor_id: u32,
+    /// Vendor defined message type.
+    #[wire(bytes = 2)]
+    pub vendor_type: u16,
+}
+
+/// Mailbox and VoE headers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ethercrab_wire::EtherCrabWireReadWrite)]
+#[wire(bytes = 12)]
+pub struct VoeMailboxHeader {
+    #[wire(bytes = 6)]
+    pub header: MailboxHeaderBase,
+    #[wire(bytes = 6)]
+    pub voe: VoeHeader,
+}
+
+impl VoeMailboxHeader {
+    /// Create a new VoE message header, followed by `data_len` bytes of vendor data.
+    pub fn new(counter: u8, voe: VoeHeader, data_len: u16) -> Self {
+        Self {
+            header: MailboxHeaderBase {
+                // 6 byte VoE header plus data
+                length: 0x06 + data_len,
+                priority: Priority::Lowest,
+                mailbox_type: MailboxType::VendorSpecific,
+                counter,
+            },
+            voe,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethercrab_wire::EtherCrabWireWriteSized;
+
+    #[test]
+    fn encode_header() {
+        let header = VoeMailboxHeader::new(
+            4,
+            VoeHeader {
+                vendor_id: 0x0000_0002,
+                vendor_type: 0x1234,
+            },
+            2,
+        );
+
+        assert_eq!(
+            header.pack(),
+            [
+                // Mailbox header
+                0x08, 0x00, 0x00, 0x00, 0x00, 0x4f, //
+                // Vendor ID, vendor type
+                0x02, 0x00, 0x00, 0x00, 0x34, 0x12
+            ]
+        );
+    }
+}
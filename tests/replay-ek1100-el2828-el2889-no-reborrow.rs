@@ -10,7 +10,7 @@
 mod util;
 
 use ethercrab::{
-    error::Error, slave_group, Client, ClientConfig, PduStorage, SlaveGroup, Timeouts,
+    error::Error, slave_group, Client, ClientConfig, DcStaticSync, PduStorage, SlaveGroup, Timeouts,
 };
 use std::{path::PathBuf, time::Duration};
 use tokio::time::MissedTickBehavior;
@@ -36,7 +36,11 @@ async fn replay_ek1100_el2828_el2889_no_reborrow() -> Result<(), Error> {
         pdu_loop,
         Timeouts::default(),
         ClientConfig {
-            dc_static_sync_iterations: 100,
+            dc_static_sync: DcStaticSync {
+                iterations: 100,
+                read_deviation: false,
+                ..DcStaticSync::default()
+            },
             ..Default::default()
         },
     );
@@ -10,7 +10,7 @@ mod util;
 
 use env_logger::Env;
 use ethercrab::{
-    error::Error, slave_group, Client, ClientConfig, PduStorage, SlaveGroup, Timeouts,
+    error::Error, slave_group, Client, ClientConfig, DcStaticSync, PduStorage, SlaveGroup, Timeouts,
 };
 use std::{path::PathBuf, time::Duration};
 use tokio::time::MissedTickBehavior;
@@ -42,7 +42,12 @@ async fn replay_ek1100_el2828_el2889() -> Result<(), Error> {
         pdu_loop,
         Timeouts::default(),
         ClientConfig {
-            dc_static_sync_iterations: 100,
+            dc_static_sync: DcStaticSync {
+                iterations: 100,
+                read_deviation: false,
+                ..DcStaticSync::default()
+            },
+            verify_pdi_wkc: true,
             ..Default::default()
         },
     );
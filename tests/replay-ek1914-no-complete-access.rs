@@ -6,7 +6,9 @@
 
 mod util;
 
-use ethercrab::{error::Error, Client, ClientConfig, PduStorage, RetryBehaviour, Timeouts};
+use ethercrab::{
+    error::Error, Client, ClientConfig, DcStaticSync, PduStorage, RetryBehaviour, Timeouts,
+};
 use std::{path::PathBuf, time::Duration};
 
 const MAX_SLAVES: usize = 16;
@@ -28,8 +30,13 @@ async fn replay_ek1914_no_complete_access() -> Result<(), Error> {
             ..Timeouts::default()
         },
         ClientConfig {
-            dc_static_sync_iterations: 100,
+            dc_static_sync: DcStaticSync {
+                iterations: 100,
+                read_deviation: false,
+                ..DcStaticSync::default()
+            },
             retry_behaviour: RetryBehaviour::None,
+            ..Default::default()
         },
     );
 
@@ -5,7 +5,7 @@
 mod util;
 
 use env_logger::Env;
-use ethercrab::{error::Error, Client, ClientConfig, PduStorage, Timeouts};
+use ethercrab::{error::Error, Client, ClientConfig, DcStaticSync, PduStorage, Timeouts};
 use std::path::PathBuf;
 
 const MAX_SLAVES: usize = 16;
@@ -26,7 +26,11 @@ async fn replay_ek1914_segmented_upload() -> Result<(), Error> {
         pdu_loop,
         Timeouts::default(),
         ClientConfig {
-            dc_static_sync_iterations: 100,
+            dc_static_sync: DcStaticSync {
+                iterations: 100,
+                read_deviation: false,
+                ..DcStaticSync::default()
+            },
             ..Default::default()
         },
     );